@@ -1,13 +1,263 @@
 """Thin wrapper re-exporting Rust-accelerated parsers from snoopy_native."""
 
 from snoopy_native import (
+    TMUX_PANE_FORMAT,
+    TMUX_SESSION_FORMAT,
+    Anonymizer,
+    Collector,
+    DatabaseLocked,
+    DatabaseSnapshot,
+    DownloadsMonitor,
+    EncryptionKey,
+    EsloggerParser,
+    FileActivityParser,
+    FormatError,
+    FuzzyIndex,
+    MeetingDetector,
+    ParseError,
+    ParserConfig,
+    PermissionDenied,
+    PrivacyFilter,
+    ResourceLimits,
+    RewriteRules,
+    ScreenshotOptions,
+    TmuxOptions,
+    TranscriptMerge,
+    TranscriptPipeline,
+    Watcher,
+    aggregate_events,
+    audit_trail_events,
+    bench,
+    browser_downloads,
+    calendar_events,
+    check_permissions,
+    chromium_history,
+    chromium_profiles,
+    content_hash,
+    correlate,
+    current_wifi,
+    decrypt_file,
+    dedup_events,
+    diff_processes,
+    dns_cache,
+    dns_hostnames,
+    download_origin,
+    encrypt_file,
+    estimate_tokens,
+    estimate_tokens_batch,
+    export_html_timeline,
+    export_ics,
+    export_training_data,
     extract_attributed_body_text,
+    extract_message_content,
+    extract_urls,
+    find_duplicate_prompts,
+    find_verification_code,
+    firefox_history,
+    firefox_profiles,
+    frontmost_window,
+    generate_daily_report,
+    homebrew_installs,
+    idle_seconds,
+    infer_git_activity,
+    infer_title,
+    input_counts,
+    install_log_events,
+    interruption_stats,
+    iter_transcripts,
+    link_events,
+    loc_stats,
+    login_sessions,
+    mail_events,
+    media_in_use,
+    merge_timelines,
+    narrate_session,
+    notes_events,
+    parse_backupd_log,
+    parse_dns_cache,
+    parse_eslogger,
+    parse_install_log,
+    parse_iso_timestamp,
+    parse_iso_timestamps,
+    parse_last,
     parse_lsof_output,
+    parse_pmset_log,
+    parse_praudit,
+    parse_shell_history,
+    parse_ssh_log,
+    parse_tmux_panes,
+    parse_tmux_sessions,
     parse_transcript,
+    parse_transcript_bytes,
+    perceptual_hash,
+    permission_requests,
+    persistence_snapshot,
+    power_events,
+    project_rollup,
+    prompt_fingerprint,
+    quarantine_events,
+    query_unified_log,
+    read_ndjson,
+    recent_documents,
+    reminders_events,
+    replay_transcript,
+    rewrite_transcript,
+    safari_history,
+    safe_snapshot,
+    scan_git_activity,
+    screen_time_usage,
+    session_activity,
+    session_lineage,
+    sessionize,
+    set_log_level,
+    set_privacy_filter,
+    slack_messages,
+    snapshot_processes,
+    ssh_events,
+    tcc_permissions,
+    time_machine_events,
+    time_machine_status,
+    tmux_panes,
+    tmux_sessions,
+    tool_failure_stats,
+    train_zstd_dictionary,
+    transcript_keywords,
+    truncate_preview,
+    usb_devices,
+    verification_events,
+    vscode_activity,
+    whatsapp_messages,
+    xcode_builds,
+    xcode_recent_projects,
 )
 
 __all__ = [
+    "TMUX_PANE_FORMAT",
+    "TMUX_SESSION_FORMAT",
+    "Anonymizer",
+    "Collector",
+    "DatabaseLocked",
+    "DatabaseSnapshot",
+    "DownloadsMonitor",
+    "EncryptionKey",
+    "EsloggerParser",
+    "FileActivityParser",
+    "FormatError",
+    "FuzzyIndex",
+    "MeetingDetector",
+    "ParseError",
+    "ParserConfig",
+    "PermissionDenied",
+    "PrivacyFilter",
+    "ResourceLimits",
+    "RewriteRules",
+    "ScreenshotOptions",
+    "TmuxOptions",
+    "TranscriptMerge",
+    "TranscriptPipeline",
+    "Watcher",
+    "aggregate_events",
+    "audit_trail_events",
+    "bench",
+    "browser_downloads",
+    "calendar_events",
+    "check_permissions",
+    "chromium_history",
+    "chromium_profiles",
+    "content_hash",
+    "correlate",
+    "current_wifi",
+    "decrypt_file",
+    "dedup_events",
+    "diff_processes",
+    "dns_cache",
+    "dns_hostnames",
+    "download_origin",
+    "encrypt_file",
+    "estimate_tokens",
+    "estimate_tokens_batch",
+    "export_html_timeline",
+    "export_ics",
+    "export_training_data",
     "extract_attributed_body_text",
+    "extract_message_content",
+    "extract_urls",
+    "find_duplicate_prompts",
+    "find_verification_code",
+    "firefox_history",
+    "firefox_profiles",
+    "frontmost_window",
+    "generate_daily_report",
+    "homebrew_installs",
+    "idle_seconds",
+    "infer_git_activity",
+    "infer_title",
+    "input_counts",
+    "install_log_events",
+    "interruption_stats",
+    "iter_transcripts",
+    "link_events",
+    "loc_stats",
+    "login_sessions",
+    "mail_events",
+    "media_in_use",
+    "merge_timelines",
+    "narrate_session",
+    "notes_events",
+    "parse_backupd_log",
+    "parse_dns_cache",
+    "parse_eslogger",
+    "parse_install_log",
+    "parse_iso_timestamp",
+    "parse_iso_timestamps",
+    "parse_last",
     "parse_lsof_output",
+    "parse_pmset_log",
+    "parse_praudit",
+    "parse_shell_history",
+    "parse_ssh_log",
+    "parse_tmux_panes",
+    "parse_tmux_sessions",
     "parse_transcript",
+    "parse_transcript_bytes",
+    "perceptual_hash",
+    "permission_requests",
+    "persistence_snapshot",
+    "power_events",
+    "project_rollup",
+    "prompt_fingerprint",
+    "quarantine_events",
+    "query_unified_log",
+    "read_ndjson",
+    "recent_documents",
+    "reminders_events",
+    "replay_transcript",
+    "rewrite_transcript",
+    "safari_history",
+    "safe_snapshot",
+    "scan_git_activity",
+    "screen_time_usage",
+    "session_activity",
+    "session_lineage",
+    "sessionize",
+    "set_log_level",
+    "set_privacy_filter",
+    "slack_messages",
+    "snapshot_processes",
+    "ssh_events",
+    "tcc_permissions",
+    "time_machine_events",
+    "time_machine_status",
+    "tmux_panes",
+    "tmux_sessions",
+    "tool_failure_stats",
+    "train_zstd_dictionary",
+    "transcript_keywords",
+    "truncate_preview",
+    "usb_devices",
+    "verification_events",
+    "vscode_activity",
+    "whatsapp_messages",
+    "xcode_builds",
+    "xcode_recent_projects",
 ]
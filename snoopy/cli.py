@@ -9,6 +9,7 @@ import textwrap
 import time
 from pathlib import Path
 
+from snoopy._native import check_permissions
 from snoopy.config import DATA_DIR, DB_PATH, LOG_PATH, PID_PATH
 
 _PLIST_LABEL = "com.snoopy.daemon"
@@ -111,16 +112,20 @@ def _trigger_permissions() -> None:
         except (subprocess.TimeoutExpired, OSError):
             print("  [!] Location Services: could not trigger — grant manually in System Settings")
 
-    # Full Disk Access — attempt to read a protected file
-    safari_hist = Path.home() / "Library/Safari/History.db"
-    if safari_hist.exists():
-        try:
-            safari_hist.read_bytes()[:1]
-            print("  [+] Full Disk Access: already granted")
-        except PermissionError:
-            print("  [!] Full Disk Access: not yet granted")
+    # Full Disk Access — probe the protected files the parsers read
+    report = check_permissions()
+    if report["full_disk_access"]:
+        print("  [+] Full Disk Access: already granted")
+    elif report["full_disk_access"] is None:
+        print("  [!] Full Disk Access: could not probe (no protected files found)")
     else:
-        print("  [!] Full Disk Access: could not probe (no Safari history file)")
+        print("  [!] Full Disk Access: not yet granted")
+    denied = [c for c in report["checks"] if c["status"] == "denied"]
+    for check in denied:
+        print(f"      cannot read {check['path']}")
+    if denied:
+        print(textwrap.fill(denied[0]["hint"], width=78, initial_indent="      ",
+                            subsequent_indent="      "))
 
     print()
     print("  If you did not see permission prompts, open:")
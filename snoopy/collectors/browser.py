@@ -5,21 +5,19 @@ Each browser stores history in a SQLite DB with different timestamp epochs:
 - Firefox: microseconds since Unix epoch
 - Safari: seconds since 2001-01-01
 
-We copy the DB before reading because Chrome/Arc hold a write lock.
+We snapshot the DB before reading because Chrome/Arc hold a write lock.
 A per-browser watermark (last_visit_id) avoids re-importing old visits.
 """
 
 import json
 import logging
-import os
 import re
-import shutil
 import sqlite3
-import tempfile
 import time
 from pathlib import Path
 
 import snoopy.config as config
+from snoopy._native import DatabaseSnapshot, safe_snapshot
 from snoopy.buffer import Event
 from snoopy.collectors.base import BaseCollector
 
@@ -61,12 +59,12 @@ class BrowserCollector(BaseCollector):
         watermark_key = f"{self.name}_{browser}"
         last_id = self.db.get_watermark(watermark_key)
 
-        tmp = self._copy_db(db_path)
-        if tmp is None:
+        snapshot = self._snapshot_db(db_path)
+        if snapshot is None:
             return
 
         try:
-            conn = sqlite3.connect(tmp)
+            conn = sqlite3.connect(snapshot.path)
 
             # First run: skip historical data, just set watermark to current max
             if last_id is None:
@@ -109,7 +107,7 @@ class BrowserCollector(BaseCollector):
                 self.db.set_watermark(watermark_key, str(max_id), time.time())
                 log.info("[%s] collected %d visits from %s", self.name, len(events), browser)
         finally:
-            Path(tmp).unlink(missing_ok=True)
+            snapshot.close()
 
     def _collect_chromium_searches(self, browser: str, db_path: Path) -> None:
         """Collect search terms from Chrome/Arc keyword_search_terms table."""
@@ -119,12 +117,12 @@ class BrowserCollector(BaseCollector):
         watermark_key = f"{self.name}_{browser}_search"
         last_url_id = self.db.get_watermark(watermark_key)
 
-        tmp = self._copy_db(db_path)
-        if tmp is None:
+        snapshot = self._snapshot_db(db_path)
+        if snapshot is None:
             return
 
         try:
-            conn = sqlite3.connect(tmp)
+            conn = sqlite3.connect(snapshot.path)
 
             # Table may not exist in fresh profiles
             has_table = conn.execute(
@@ -175,7 +173,7 @@ class BrowserCollector(BaseCollector):
                 self.db.set_watermark(watermark_key, str(max_id), time.time())
                 log.info("[%s] collected %d search terms from %s", self.name, len(events), browser)
         finally:
-            Path(tmp).unlink(missing_ok=True)
+            snapshot.close()
 
     def _collect_chromium_downloads(self, browser: str, db_path: Path) -> None:
         """Collect file downloads from Chrome/Arc downloads table."""
@@ -185,12 +183,12 @@ class BrowserCollector(BaseCollector):
         watermark_key = f"{self.name}_{browser}_downloads"
         last_id = self.db.get_watermark(watermark_key)
 
-        tmp = self._copy_db(db_path)
-        if tmp is None:
+        snapshot = self._snapshot_db(db_path)
+        if snapshot is None:
             return
 
         try:
-            conn = sqlite3.connect(tmp)
+            conn = sqlite3.connect(snapshot.path)
 
             has_table = conn.execute(
                 "SELECT 1 FROM sqlite_master WHERE type='table' AND name='downloads'"
@@ -238,7 +236,7 @@ class BrowserCollector(BaseCollector):
                 self.db.set_watermark(watermark_key, str(max_id), time.time())
                 log.info("[%s] collected %d downloads from %s", self.name, len(events), browser)
         finally:
-            Path(tmp).unlink(missing_ok=True)
+            snapshot.close()
 
     def _collect_bookmarks(self, browser: str, bookmarks_path: Path) -> None:
         """Collect new bookmarks from Chrome/Arc Bookmarks JSON."""
@@ -308,12 +306,12 @@ class BrowserCollector(BaseCollector):
         watermark_key = f"{self.name}_safari"
         last_ts_str = self.db.get_watermark(watermark_key)
 
-        tmp = self._copy_db(config.SAFARI_HISTORY)
-        if tmp is None:
+        snapshot = self._snapshot_db(config.SAFARI_HISTORY)
+        if snapshot is None:
             return
 
         try:
-            conn = sqlite3.connect(tmp)
+            conn = sqlite3.connect(snapshot.path)
 
             # First run: skip historical data
             if last_ts_str is None:
@@ -353,7 +351,7 @@ class BrowserCollector(BaseCollector):
                 self.db.set_watermark(watermark_key, str(max_ts), time.time())
                 log.info("[%s] collected %d visits from safari", self.name, len(events))
         finally:
-            Path(tmp).unlink(missing_ok=True)
+            snapshot.close()
 
     def _collect_firefox(self) -> None:
         if not config.FIREFOX_PROFILES.exists():
@@ -369,12 +367,12 @@ class BrowserCollector(BaseCollector):
         watermark_key = f"{self.name}_firefox"
         last_id = self.db.get_watermark(watermark_key)
 
-        tmp = self._copy_db(places_db)
-        if tmp is None:
+        snapshot = self._snapshot_db(places_db)
+        if snapshot is None:
             return
 
         try:
-            conn = sqlite3.connect(tmp)
+            conn = sqlite3.connect(snapshot.path)
 
             # First run: skip historical data
             if last_id is None:
@@ -413,21 +411,18 @@ class BrowserCollector(BaseCollector):
                 self.db.set_watermark(watermark_key, str(max_id), time.time())
                 log.info("[%s] collected %d visits from firefox", self.name, len(events))
         finally:
-            Path(tmp).unlink(missing_ok=True)
+            snapshot.close()
 
-    def _copy_db(self, src: Path) -> str | None:
-        """Copy a locked SQLite DB to a temp file for safe reading."""
+    def _snapshot_db(self, src: Path) -> DatabaseSnapshot | None:
+        """Snapshot a locked SQLite DB, WAL included, for safe reading."""
         try:
-            fd, tmp = tempfile.mkstemp(suffix=".db")
-            os.close(fd)
-            shutil.copy2(str(src), tmp)
-            return tmp
+            return safe_snapshot(str(src))
         except PermissionError:
             key = str(src)
             if key not in self._permission_warned:
                 log.warning("%s needs Full Disk Access — skipping until granted", src)
                 self._permission_warned.add(key)
             return None
-        except (OSError, shutil.Error):
-            log.exception("failed to copy db %s", src)
+        except OSError:
+            log.exception("failed to snapshot db %s", src)
             return None
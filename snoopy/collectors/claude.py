@@ -4,6 +4,8 @@ Transcript JSONL format (from Claude Code):
   - type=user:      user messages
   - type=assistant:  assistant responses with content blocks (text, tool_use, thinking)
   - type=progress:   tool results, agent progress, hook progress
+  - type=system:     compaction boundaries, hook results, API/system notices
+  - type=summary:    session summaries written when a session is resumed
   - type=file-history-snapshot: file backup snapshots
   - type=queue-operation: internal queue ops
 
@@ -8,15 +8,14 @@ Subsequent runs: incremental via ROWID watermark.
 """
 
 import logging
-import os
-import shutil
 import sqlite3
-import tempfile
 import time
 from pathlib import Path
 from urllib.parse import unquote
 
 import snoopy.config as config
+from snoopy._native import safe_snapshot
+from snoopy._native import truncate_preview
 from snoopy.buffer import Event
 from snoopy.collectors.base import BaseCollector
 
@@ -56,22 +55,6 @@ def _find_envelope_index() -> Path | None:
     return None
 
 
-def _copy_mail_db(src: Path) -> str | None:
-    """Copy Envelope Index + WAL/SHM to a temp file. Returns temp path or None."""
-    fd, tmp = tempfile.mkstemp(suffix=".db")
-    os.close(fd)
-    try:
-        shutil.copy2(str(src), tmp)
-        for suffix in ("-wal", "-shm"):
-            wal = str(src) + suffix
-            if os.path.exists(wal):
-                shutil.copy2(wal, tmp + suffix)
-        return tmp
-    except (OSError, shutil.Error):
-        Path(tmp).unlink(missing_ok=True)
-        return None
-
-
 def _mailbox_name_from_url(url: str | None) -> str:
     """Extract human-readable mailbox name from the mailbox url column.
 
@@ -106,15 +89,19 @@ class MailCollector(BaseCollector):
             log.debug("Envelope Index not found — Mail may not be set up")
             return
 
-        tmp = _copy_mail_db(idx_path)
-        if tmp is None:
+        try:
+            snapshot = safe_snapshot(str(idx_path))
+        except PermissionError:
             if not self._permission_warned:
                 log.warning("Mail Envelope Index needs Full Disk Access — skipping until granted")
                 self._permission_warned = True
             return
+        except OSError:
+            log.exception("failed to snapshot Mail Envelope Index")
+            return
 
         try:
-            conn = sqlite3.connect(tmp)
+            conn = sqlite3.connect(snapshot.path)
 
             # Build mailbox ROWID -> name map
             mailbox_map = {}
@@ -133,7 +120,7 @@ class MailCollector(BaseCollector):
         except sqlite3.OperationalError:
             log.warning("Mail DB query failed (schema may differ on this macOS version)")
         finally:
-            Path(tmp).unlink(missing_ok=True)
+            snapshot.close()
 
     def _first_run(self, conn: sqlite3.Connection, mailbox_map: dict[int, str]) -> None:
         """Seed with last MAIL_SEED_DAYS of emails, then set watermark to MAX(ROWID)."""
@@ -174,7 +161,7 @@ class MailCollector(BaseCollector):
             ts = date_received if date_received else time.time()
             mailbox_name = mailbox_map.get(mailbox_id, "")
             is_from_me = _is_sent(mailbox_name)
-            content_preview = (subject or "")[:_CONTENT_PREVIEW_LEN]
+            content_preview = truncate_preview(subject or "", _CONTENT_PREVIEW_LEN)
 
             events.append(Event(
                 table="mail_events",
@@ -198,7 +185,7 @@ class MailCollector(BaseCollector):
             ts = date_received if date_received else time.time()
             mailbox_name = mailbox_map.get(mailbox_id, "")
             is_from_me = _is_sent(mailbox_name)
-            content_preview = (subject or "")[:_CONTENT_PREVIEW_LEN]
+            content_preview = truncate_preview(subject or "", _CONTENT_PREVIEW_LEN)
 
             events.append(Event(
                 table="mail_events",
@@ -10,15 +10,14 @@ rather than the plain text column.
 """
 
 import logging
-import os
-import shutil
 import sqlite3
-import tempfile
 import time
 from pathlib import Path
 
 import snoopy.config as config
 from snoopy._native import extract_attributed_body_text as _extract_text_from_attributed_body
+from snoopy._native import safe_snapshot
+from snoopy._native import truncate_preview
 from snoopy.buffer import Event
 from snoopy.collectors.base import BaseCollector
 
@@ -104,25 +103,20 @@ class MessagesCollector(BaseCollector):
             return
 
         try:
-            fd, tmp = tempfile.mkstemp(suffix=".db")
-            os.close(fd)
-            shutil.copy2(str(_MESSAGES_DB), tmp)
-            # Also copy WAL/SHM so we see recent uncheckpointed writes
-            for suffix in ("-wal", "-shm"):
-                src = str(_MESSAGES_DB) + suffix
-                if os.path.exists(src):
-                    shutil.copy2(src, tmp + suffix)
+            # The snapshot includes uncheckpointed WAL writes and waits out
+            # Messages' write locks rather than failing with "database is locked".
+            snapshot = safe_snapshot(str(_MESSAGES_DB))
         except PermissionError:
             if not self._permission_warned:
                 log.warning("Messages chat.db needs Full Disk Access — skipping until granted")
                 self._permission_warned = True
             return
-        except (OSError, shutil.Error):
-            log.exception("failed to copy Messages chat.db")
+        except OSError:
+            log.exception("failed to snapshot Messages chat.db")
             return
 
         try:
-            conn = sqlite3.connect(tmp)
+            conn = sqlite3.connect(snapshot.path)
 
             # First run: skip historical messages
             if self._last_id is None:
@@ -159,9 +153,10 @@ class MessagesCollector(BaseCollector):
                 # Convert Apple nanosecond timestamp to Unix epoch
                 ts = date / 1_000_000_000 + _APPLE_EPOCH_OFFSET if date else time.time()
 
-                content = (text or "")[:_CONTENT_PREVIEW_LEN]
+                content = truncate_preview(text or "", _CONTENT_PREVIEW_LEN)
                 if not content:
-                    content = _extract_text_from_attributed_body(attr_body)[:_CONTENT_PREVIEW_LEN]
+                    content = truncate_preview(_extract_text_from_attributed_body(attr_body),
+                                               _CONTENT_PREVIEW_LEN)
                 if not content and has_attach:
                     content = "[attachment]"
 
@@ -186,4 +181,4 @@ class MessagesCollector(BaseCollector):
         except sqlite3.OperationalError:
             log.warning("Messages DB query failed (schema may differ on this macOS version)")
         finally:
-            Path(tmp).unlink(missing_ok=True)
+            snapshot.close()
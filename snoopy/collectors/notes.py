@@ -13,14 +13,12 @@ macOS Notes date format: seconds since 2001-01-01 (Core Data / Apple epoch).
 
 import gzip
 import logging
-import os
-import shutil
 import sqlite3
-import tempfile
 import time
 from pathlib import Path
 
 import snoopy.config as config
+from snoopy._native import safe_snapshot
 from snoopy.buffer import Event
 from snoopy.collectors.base import BaseCollector
 
@@ -205,13 +203,7 @@ class NotesCollector(BaseCollector):
             return
 
         try:
-            fd, tmp = tempfile.mkstemp(suffix=".db")
-            os.close(fd)
-            shutil.copy2(str(_NOTES_DB), tmp)
-            for suffix in ("-wal", "-shm"):
-                src = str(_NOTES_DB) + suffix
-                if os.path.exists(src):
-                    shutil.copy2(src, tmp + suffix)
+            snapshot = safe_snapshot(str(_NOTES_DB))
         except PermissionError:
             if not self._permission_warned:
                 log.warning(
@@ -219,12 +211,12 @@ class NotesCollector(BaseCollector):
                 )
                 self._permission_warned = True
             return
-        except (OSError, shutil.Error):
-            log.exception("failed to copy NoteStore.sqlite")
+        except OSError:
+            log.exception("failed to snapshot NoteStore.sqlite")
             return
 
         try:
-            conn = sqlite3.connect(tmp)
+            conn = sqlite3.connect(snapshot.path)
 
             if self._last_mod is None:
                 # First run: seed with notes from the last N days
@@ -269,6 +261,4 @@ class NotesCollector(BaseCollector):
         except sqlite3.OperationalError:
             log.warning("Notes DB query failed (schema may differ on this macOS version)")
         finally:
-            Path(tmp).unlink(missing_ok=True)
-            for suffix in ("-wal", "-shm"):
-                Path(tmp + suffix).unlink(missing_ok=True)
+            snapshot.close()
@@ -6,15 +6,13 @@ have changed, so we handle errors gracefully.
 """
 
 import logging
-import os
 import plistlib
-import shutil
 import sqlite3
-import tempfile
 import time
 from pathlib import Path
 
 import snoopy.config as config
+from snoopy._native import safe_snapshot
 from snoopy.buffer import Event
 from snoopy.collectors.base import BaseCollector
 
@@ -50,20 +48,18 @@ class NotificationCollector(BaseCollector):
             return
 
         try:
-            fd, tmp = tempfile.mkstemp(suffix=".db")
-            os.close(fd)
-            shutil.copy2(str(db_path), tmp)
+            snapshot = safe_snapshot(str(db_path))
         except PermissionError:
             if not self._permission_warned:
                 log.warning("notification DB needs Full Disk Access — skipping until granted")
                 self._permission_warned = True
             return
-        except (OSError, shutil.Error):
-            log.exception("failed to copy notification db")
+        except OSError:
+            log.exception("failed to snapshot notification db")
             return
 
         try:
-            conn = sqlite3.connect(tmp)
+            conn = sqlite3.connect(snapshot.path)
 
             # Build app_id → bundle identifier map if the app table exists
             app_map = {}
@@ -128,4 +124,4 @@ class NotificationCollector(BaseCollector):
                 "notification DB query failed (schema may have changed on this macOS version)"
             )
         finally:
-            Path(tmp).unlink(missing_ok=True)
+            snapshot.close()
@@ -0,0 +1,163 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use rayon::prelude::*;
+
+use crate::extract_content;
+use crate::rollup::{decode_project_dir, find_jsonl_files};
+use crate::timeutil::parse_iso_ts;
+
+#[derive(Default)]
+struct Activity {
+    project_path: String,
+    start: Option<f64>,
+    end: Option<f64>,
+    active: f64,
+    idle: f64,
+    user_wait: f64,
+    agent_work: f64,
+    idle_gaps: u64,
+    prompts: u64,
+}
+
+impl Activity {
+    /// Account for the time since the previous entry, up to one at ts.
+    fn step(&mut self, ts: f64, from_user: bool, idle_threshold: f64) {
+        if let Some(prev) = self.end {
+            let gap = (ts - prev).max(0.0);
+            if gap > idle_threshold {
+                self.idle += gap;
+                self.idle_gaps += 1;
+            } else {
+                self.active += gap;
+                if from_user {
+                    self.user_wait += gap;
+                } else {
+                    self.agent_work += gap;
+                }
+            }
+        }
+        self.start.get_or_insert(ts);
+        self.end = Some(self.end.map_or(ts, |end| end.max(ts)));
+    }
+}
+
+/// Whether a transcript entry is something the user typed (a prompt or an
+/// interruption), rather than a tool result or a message Claude Code generated.
+fn is_prompt(entry: &serde_json::Value) -> bool {
+    let flag = |name: &str| entry.get(name).and_then(|v| v.as_bool()).unwrap_or(false);
+    if flag("isMeta") || flag("isCompactSummary") {
+        return false;
+    }
+    let content = extract_content(&entry["message"]);
+    let content = content.trim();
+    !content.is_empty()
+        && !content.starts_with("<task-notification")
+        && !content.starts_with("This session is being continued")
+}
+
+fn scan_file(path: &Path, idle_threshold: f64) -> Activity {
+    let mut activity = Activity {
+        project_path: path
+            .parent()
+            .and_then(|p| p.file_name())
+            .and_then(|n| n.to_str())
+            .map(decode_project_dir)
+            .unwrap_or_default(),
+        ..Activity::default()
+    };
+    let file = match File::open(path) {
+        Ok(f) => f,
+        Err(_) => return activity,
+    };
+    let mut cwd_seen = false;
+    for line in BufReader::new(file).lines() {
+        let line = match line {
+            Ok(l) => l,
+            Err(_) => break,
+        };
+        let entry: serde_json::Value = match serde_json::from_str(line.trim()) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        if !cwd_seen {
+            if let Some(cwd) = entry.get("cwd").and_then(|v| v.as_str()) {
+                activity.project_path = cwd.to_string();
+                cwd_seen = true;
+            }
+        }
+        let Some(ts) = entry.get("timestamp").and_then(|v| v.as_str()).and_then(parse_iso_ts)
+        else {
+            continue;
+        };
+        let from_user =
+            entry.get("type").and_then(|v| v.as_str()) == Some("user") && is_prompt(&entry);
+        if from_user {
+            activity.prompts += 1;
+        }
+        activity.step(ts, from_user, idle_threshold);
+    }
+    activity
+}
+
+/// Wall-clock versus active time for each session.
+///
+/// path is a transcript file or a directory searched recursively; subagent
+/// transcripts (agent-*.jsonl) are left out, as their time falls within the
+/// session that started them. Consecutive entries up to idle_threshold seconds
+/// apart count as active time, longer gaps as idle. Active time is split by who the
+/// session was waiting on: user_wait_seconds ends in something the user typed (they
+/// were reading or writing a prompt), agent_work_seconds in the agent's own output
+/// or tool results. Returns {session_id: {project_path, start, end, wall_seconds,
+/// active_seconds, idle_seconds, user_wait_seconds, agent_work_seconds, idle_gaps,
+/// prompts, active_ratio}}; sessions without timestamps are omitted.
+#[pyfunction]
+#[pyo3(signature = (path, idle_threshold=300.0))]
+pub fn session_activity<'py>(
+    py: Python<'py>,
+    path: &str,
+    idle_threshold: f64,
+) -> PyResult<Bound<'py, PyDict>> {
+    if idle_threshold.is_nan() || idle_threshold < 0.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("idle_threshold must be non-negative"));
+    }
+    let root = PathBuf::from(path);
+    let sessions = py.detach(|| {
+        let files = if root.is_dir() { find_jsonl_files(&root) } else { vec![root.clone()] };
+        files
+            .par_iter()
+            .filter(|f| {
+                !f.file_stem().and_then(|s| s.to_str()).is_some_and(|s| s.starts_with("agent-"))
+            })
+            .map(|f| {
+                let id = f.file_stem().and_then(|s| s.to_str()).unwrap_or("").to_string();
+                (id, scan_file(f, idle_threshold))
+            })
+            .collect::<Vec<_>>()
+    });
+
+    let out = PyDict::new(py);
+    for (id, a) in sessions {
+        let (Some(start), Some(end)) = (a.start, a.end) else {
+            continue;
+        };
+        let wall = end - start;
+        let dict = PyDict::new(py);
+        dict.set_item("project_path", a.project_path)?;
+        dict.set_item("start", start)?;
+        dict.set_item("end", end)?;
+        dict.set_item("wall_seconds", wall)?;
+        dict.set_item("active_seconds", a.active)?;
+        dict.set_item("idle_seconds", a.idle)?;
+        dict.set_item("user_wait_seconds", a.user_wait)?;
+        dict.set_item("agent_work_seconds", a.agent_work)?;
+        dict.set_item("idle_gaps", a.idle_gaps)?;
+        dict.set_item("prompts", a.prompts)?;
+        dict.set_item("active_ratio", if wall > 0.0 { a.active / wall } else { 0.0 })?;
+        out.set_item(id, dict)?;
+    }
+    Ok(out)
+}
@@ -0,0 +1,98 @@
+use std::collections::BTreeMap;
+
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+
+use crate::sessions::event_timestamp;
+
+/// Monday 1970-01-05, the first week boundary after the epoch.
+pub(crate) const FIRST_MONDAY: i64 = 4 * 86400;
+
+#[derive(Default)]
+struct Bucket {
+    count: u64,
+    tokens: u64,
+    cost_usd: f64,
+}
+
+fn bucket_seconds(bucket: &str) -> Option<i64> {
+    match bucket {
+        "minute" => Some(60),
+        "hour" => Some(3600),
+        "day" => Some(86400),
+        "week" => Some(7 * 86400),
+        _ => None,
+    }
+}
+
+/// Floor ts to the start of its bucket, shifted by utc_offset so days start at local midnight.
+/// Buckets count from FIRST_MONDAY, a whole number of days after the epoch, so weeks
+/// start on Monday and shorter buckets fall where they would from the epoch.
+fn bucket_start(ts: f64, width: i64, utc_offset: i64) -> i64 {
+    let local = ts.floor() as i64 + utc_offset;
+    (local - FIRST_MONDAY).div_euclid(width) * width + FIRST_MONDAY - utc_offset
+}
+
+/// Count events per time bucket and message_type.
+///
+/// bucket is one of "minute", "hour", "day" or "week" (starting Monday); utc_offset
+/// (seconds) aligns buckets to local time. Events are dicts with a timestamp and optional
+/// message_type, tokens and cost_usd keys. Returns a list of dicts sorted by bucket_start,
+/// message_type.
+#[pyfunction]
+#[pyo3(signature = (events, bucket="hour", utc_offset=0))]
+pub fn aggregate_events<'py>(
+    py: Python<'py>,
+    events: &Bound<'py, PyAny>,
+    bucket: &str,
+    utc_offset: i64,
+) -> PyResult<Bound<'py, PyList>> {
+    let width = bucket_seconds(bucket).ok_or_else(|| {
+        pyo3::exceptions::PyValueError::new_err(format!("unknown bucket: {bucket}"))
+    })?;
+
+    let mut buckets: BTreeMap<(i64, String), Bucket> = BTreeMap::new();
+    for item in events.try_iter()? {
+        let item = item?;
+        let ts = event_timestamp(&item)?;
+        if !ts.is_finite() {
+            continue;
+        }
+        let (message_type, tokens, cost_usd) = match item.cast::<PyDict>() {
+            Ok(dict) => (
+                get_or_default::<String>(dict, "message_type")?,
+                get_or_default::<u64>(dict, "tokens")?,
+                get_or_default::<f64>(dict, "cost_usd")?,
+            ),
+            Err(_) => (String::new(), 0, 0.0),
+        };
+        let b = buckets
+            .entry((bucket_start(ts, width, utc_offset), message_type))
+            .or_default();
+        b.count += 1;
+        b.tokens += tokens;
+        b.cost_usd += cost_usd;
+    }
+
+    let py_list = PyList::empty(py);
+    for ((start, message_type), b) in buckets {
+        let dict = PyDict::new(py);
+        dict.set_item("bucket_start", start as f64)?;
+        dict.set_item("message_type", message_type)?;
+        dict.set_item("count", b.count)?;
+        dict.set_item("tokens", b.tokens)?;
+        dict.set_item("cost_usd", b.cost_usd)?;
+        py_list.append(dict)?;
+    }
+    Ok(py_list)
+}
+
+fn get_or_default<'py, T>(dict: &Bound<'py, PyDict>, key: &str) -> PyResult<T>
+where
+    T: for<'a> FromPyObject<'a, 'py> + Default,
+{
+    match dict.get_item(key)? {
+        Some(v) if !v.is_none() => v.extract().map_err(Into::into),
+        _ => Ok(T::default()),
+    }
+}
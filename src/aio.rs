@@ -0,0 +1,127 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use pyo3::exceptions::PyStopAsyncIteration;
+use pyo3::prelude::*;
+use pyo3::types::PyCFunction;
+
+use crate::shutdown;
+
+/// Longest a waiting thread blocks before checking whether its future was cancelled.
+const TICK: Duration = Duration::from_millis(100);
+
+/// What a blocking source had when asked for its next item.
+pub(crate) enum Next<T> {
+    Item(T),
+    /// Nothing yet; ask again.
+    Empty,
+    /// Nothing more is coming (stopped and drained).
+    Closed,
+}
+
+/// Set a future's result or exception, unless it was cancelled meanwhile.
+fn settle(future: &Bound<'_, PyAny>, result: PyResult<Py<PyAny>>) -> PyResult<()> {
+    if future.call_method0("done")?.is_truthy()? {
+        return Ok(());
+    }
+    match result {
+        Ok(value) => future.call_method1("set_result", (value,))?,
+        Err(e) => future.call_method1("set_exception", (e.into_value(future.py()),))?,
+    };
+    Ok(())
+}
+
+/// An asyncio future for the next item from a blocking source.
+///
+/// next is called with the GIL released, first without waiting and then from a
+/// worker thread with waits of up to TICK, until it yields an item, reports the
+/// source closed, fails, the timeout passes or the future is cancelled. The
+/// result is handed back through the running loop's call_soon_threadsafe, so
+/// awaiting never blocks the loop. A closed source resolves to None, or raises
+/// StopAsyncIteration when anext is set; a timeout resolves to None. Raises
+/// ValueError for a NaN timeout.
+pub(crate) fn next_future<'py, T, N, C>(
+    py: Python<'py>,
+    timeout: Option<f64>,
+    anext: bool,
+    mut next: N,
+    convert: C,
+) -> PyResult<Bound<'py, PyAny>>
+where
+    T: Send + 'static,
+    N: FnMut(Duration) -> PyResult<Next<T>> + Send + 'static,
+    C: FnOnce(Python<'_>, T) -> PyResult<Py<PyAny>> + Send + 'static,
+{
+    let deadline = shutdown::deadline(timeout)?;
+    let event_loop = py.import("asyncio")?.call_method0("get_running_loop")?;
+    let future = event_loop.call_method0("create_future")?;
+    let closed = move |py: Python<'_>| {
+        if anext {
+            Err(PyStopAsyncIteration::new_err(()))
+        } else {
+            Ok(py.None())
+        }
+    };
+    match py.detach(|| next(Duration::ZERO))? {
+        Next::Item(item) => {
+            settle(&future, convert(py, item))?;
+            return Ok(future);
+        }
+        Next::Closed => {
+            settle(&future, closed(py))?;
+            return Ok(future);
+        }
+        Next::Empty => {}
+    }
+
+    // Done covers cancellation too; the worker checks it between waits.
+    let done = Arc::new(AtomicBool::new(false));
+    let flag = Arc::clone(&done);
+    let on_done = PyCFunction::new_closure(py, None, None, move |_, _| {
+        flag.store(true, Ordering::Relaxed);
+    })?;
+    future.call_method1("add_done_callback", (on_done,))?;
+
+    let (event_loop, target) = (event_loop.unbind(), future.clone().unbind());
+    std::thread::Builder::new()
+        .name("snoopy-async".to_string())
+        .spawn(move || {
+            let outcome = loop {
+                if done.load(Ordering::Relaxed) {
+                    return;
+                }
+                let wait = match deadline {
+                    Some(d) => d.saturating_duration_since(Instant::now()).min(TICK),
+                    None => TICK,
+                };
+                match next(wait) {
+                    Ok(Next::Empty) if deadline.is_none_or(|d| Instant::now() < d) => {}
+                    other => break other,
+                }
+            };
+            Python::attach(|py| {
+                let result = match outcome {
+                    Ok(Next::Item(item)) => convert(py, item),
+                    Ok(Next::Closed) => closed(py),
+                    // Timed out.
+                    Ok(Next::Empty) => Ok(py.None()),
+                    Err(e) => Err(e),
+                };
+                let result = Mutex::new(Some(result));
+                let setter =
+                    PyCFunction::new_closure(py, None, None, move |args, _| {
+                        match result.lock().unwrap_or_else(|e| e.into_inner()).take() {
+                            Some(result) => settle(target.bind(args.py()), result),
+                            None => Ok(()),
+                        }
+                    });
+                // A closed loop has nobody left to tell.
+                if let Ok(setter) = setter {
+                    let _ = event_loop.call_method1(py, "call_soon_threadsafe", (setter,));
+                }
+            });
+        })
+        .map_err(pyo3::exceptions::PyRuntimeError::new_err)?;
+    Ok(future)
+}
@@ -0,0 +1,225 @@
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::OsRng;
+use hmac::{Hmac, Mac};
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict, PyList, PyString};
+use serde_json::Value;
+use sha2::Sha256;
+
+use crate::collector::Record;
+use crate::privacy::host_of;
+
+/// What an identifying field holds, which decides how it is pseudonymized.
+#[derive(Clone, Copy)]
+enum Kind {
+    Session,
+    /// Hashed a component at a time, so paths under a project stay under its pseudonym.
+    Path,
+    Contact,
+    Host,
+}
+
+/// Event fields replaced with pseudonyms, across all sources. List fields have each
+/// string item replaced.
+const FIELDS: [(&str, Kind); 26] = [
+    ("session_id", Kind::Session),
+    ("session", Kind::Session),
+    ("project_path", Kind::Path),
+    ("cwd", Kind::Path),
+    ("repo", Kind::Path),
+    ("directory", Kind::Path),
+    ("path", Kind::Path),
+    ("previous_path", Kind::Path),
+    ("log_path", Kind::Path),
+    ("workspace_file", Kind::Path),
+    ("file_name", Kind::Path),
+    ("files", Kind::Path),
+    ("contact", Kind::Contact),
+    ("chat_name", Kind::Contact),
+    ("sender", Kind::Contact),
+    ("sender_name", Kind::Contact),
+    ("sender_address", Kind::Contact),
+    ("recipient", Kind::Contact),
+    ("recipients", Kind::Contact),
+    ("attendees", Kind::Contact),
+    ("email", Kind::Contact),
+    ("host", Kind::Host),
+    ("hostname", Kind::Host),
+    ("domain", Kind::Host),
+    ("remote_address", Kind::Host),
+    ("url", Kind::Host),
+];
+
+/// Longest file extension kept on a pseudonymized path.
+const MAX_EXTENSION: usize = 5;
+
+/// Replaces identifying event fields with keyed-hash pseudonyms for sharing.
+///
+/// Session ids, project and file paths, contacts (names, handles, addresses) and
+/// hostnames become stable pseudonyms such as "session-1f3a9c0e2b7d" and
+/// "contact-84c2d6e1f0a9", derived with HMAC-SHA256 under key, so the same value
+/// always maps to the same pseudonym and events can still be grouped and joined.
+/// Paths are hashed a component at a time, keeping their shape and file extension
+/// ("/Users/me/app/main.rs" becomes something like "/5e0a91c2/d21c0b7f/8a3e6f10/
+/// 0c9d2e4b.rs"), and URLs keep only their scheme and pseudonymized host. Contacts
+/// and hosts are compared without case. Free text (content_preview, titles) is left
+/// alone; redact it with a PrivacyFilter.
+///
+/// key is bytes or a string; without one a random key is drawn, so pseudonyms are
+/// stable for this Anonymizer only. Pass the same key to keep them stable across
+/// exports, and keep it secret: anyone holding it can test guesses. Anonymizers
+/// pickle with their key.
+#[pyclass(frozen, from_py_object, module = "snoopy_native")]
+#[derive(Clone)]
+pub struct Anonymizer {
+    key: Vec<u8>,
+}
+
+impl Anonymizer {
+    fn digest(&self, value: &str, hex_len: usize) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC takes any key");
+        mac.update(value.as_bytes());
+        let bytes = mac.finalize().into_bytes();
+        bytes.iter().map(|b| format!("{b:02x}")).take(hex_len / 2).collect()
+    }
+
+    pub(crate) fn path(&self, path: &str) -> String {
+        let parts: Vec<&str> = path.split('/').collect();
+        let last = parts.len() - 1;
+        let hashed: Vec<String> = parts
+            .iter()
+            .enumerate()
+            .map(|(i, part)| match *part {
+                "" | "." | ".." | "~" => part.to_string(),
+                _ => {
+                    let ext = part.rsplit_once('.').filter(|(stem, ext)| {
+                        !stem.is_empty()
+                            && ext.len() <= MAX_EXTENSION
+                            && ext.chars().all(|c| c.is_ascii_alphanumeric())
+                    });
+                    match ext {
+                        Some((_, ext)) if i == last => format!("{}.{ext}", self.digest(part, 8)),
+                        _ => self.digest(part, 8),
+                    }
+                }
+            })
+            .collect();
+        hashed.join("/")
+    }
+
+    fn pseudonym_of(&self, kind: Kind, value: &str) -> String {
+        match kind {
+            Kind::Session => format!("session-{}", self.digest(value, 12)),
+            Kind::Path => self.path(value),
+            Kind::Contact => format!("contact-{}", self.digest(&value.trim().to_lowercase(), 12)),
+            Kind::Host if value.contains("://") => {
+                let scheme = value.split_once("://").map_or("", |(s, _)| s);
+                format!("{scheme}://host-{}", self.digest(&host_of(value), 12))
+            }
+            Kind::Host => format!("host-{}", self.digest(&value.trim().to_lowercase(), 12)),
+        }
+    }
+
+    /// Pseudonymize a collector record in place.
+    pub(crate) fn apply_record(&self, rec: &mut Record) {
+        for (field, kind) in FIELDS {
+            match rec.get_mut(field) {
+                Some(Value::String(s)) if !s.is_empty() => *s = self.pseudonym_of(kind, s),
+                Some(Value::Array(items)) => {
+                    for item in items {
+                        if let Value::String(s) = item {
+                            *s = self.pseudonym_of(kind, s);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn apply_dict<'py>(&self, event: &Bound<'py, PyDict>) -> PyResult<Bound<'py, PyDict>> {
+        let py = event.py();
+        let out = event.copy()?;
+        for (field, kind) in FIELDS {
+            let Some(value) = event.get_item(field)? else {
+                continue;
+            };
+            if let Ok(s) = value.cast::<PyString>() {
+                let s = s.to_str()?;
+                if !s.is_empty() {
+                    out.set_item(field, self.pseudonym_of(kind, s))?;
+                }
+            } else if let Ok(items) = value.cast::<PyList>() {
+                let replaced = PyList::empty(py);
+                for item in items.iter() {
+                    match item.cast::<PyString>() {
+                        Ok(s) => replaced.append(self.pseudonym_of(kind, s.to_str()?))?,
+                        Err(_) => replaced.append(item)?,
+                    }
+                }
+                out.set_item(field, replaced)?;
+            }
+        }
+        Ok(out)
+    }
+}
+
+#[pymethods]
+impl Anonymizer {
+    #[new]
+    #[pyo3(signature = (key=None))]
+    fn new(key: Option<&Bound<'_, PyAny>>) -> PyResult<Self> {
+        let key = match key {
+            Some(k) if k.is_instance_of::<PyString>() => k.extract::<String>()?.into_bytes(),
+            Some(k) if !k.is_none() => k.extract::<Vec<u8>>()?,
+            _ => {
+                let mut key = vec![0; 32];
+                OsRng.fill_bytes(&mut key);
+                key
+            }
+        };
+        if key.is_empty() {
+            return Err(pyo3::exceptions::PyValueError::new_err("key must not be empty"));
+        }
+        Ok(Anonymizer { key })
+    }
+
+    fn __getnewargs__<'py>(&self, py: Python<'py>) -> (Bound<'py, PyBytes>,) {
+        (PyBytes::new(py, &self.key),)
+    }
+
+    /// The pseudonym value would get as a kind field: "session", "path", "contact"
+    /// or "host". Lets you find your own project or contact in a shared dataset.
+    fn pseudonym(&self, value: &str, kind: &str) -> PyResult<String> {
+        let kind = match kind {
+            "session" => Kind::Session,
+            "path" => Kind::Path,
+            "contact" => Kind::Contact,
+            "host" => Kind::Host,
+            _ => {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "kind must be 'session', 'path', 'contact' or 'host', not {kind:?}"
+                )))
+            }
+        };
+        Ok(self.pseudonym_of(kind, value))
+    }
+
+    /// A copy of the event with its identifying fields pseudonymized.
+    fn apply<'py>(&self, event: &Bound<'py, PyDict>) -> PyResult<Bound<'py, PyDict>> {
+        self.apply_dict(event)
+    }
+
+    /// Pseudonymize a list of events, returning the copies.
+    fn anonymize<'py>(
+        &self,
+        py: Python<'py>,
+        events: &Bound<'py, PyAny>,
+    ) -> PyResult<Bound<'py, PyList>> {
+        let out = PyList::empty(py);
+        for item in events.try_iter()? {
+            out.append(self.apply_dict(item?.cast::<PyDict>()?)?)?;
+        }
+        Ok(out)
+    }
+}
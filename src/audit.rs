@@ -0,0 +1,341 @@
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::OnceLock;
+
+use chrono::{Local, NaiveDateTime, TimeZone};
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+use regex::Regex;
+use serde_json::{json, Value};
+
+use crate::collector::{record, records_to_py, Record};
+
+const CURRENT_TRAIL: &str = "/var/audit/current";
+
+/// Token names praudit prints in its text formats, used to split `praudit -l`'s
+/// one-line records back into tokens.
+const TOKENS: [&str; 16] = [
+    "header",
+    "trailer",
+    "subject",
+    "subject_ex",
+    "process",
+    "process_ex",
+    "return",
+    "path",
+    "attribute",
+    "text",
+    "exec arg",
+    "exec env",
+    "argument",
+    "identity",
+    "socket-inet",
+    "socket-unix",
+];
+
+/// BSM login-type events and the kind each becomes.
+const LOGINS: [(&str, &str); 8] = [
+    ("OpenSSH login", "ssh"),
+    ("login - local", "console"),
+    ("login - remote", "remote"),
+    ("loginwindow login", "session"),
+    ("logout - local", "logout"),
+    ("loginwindow logout", "logout"),
+    ("user authentication", "auth"),
+    ("sudo(1)", "sudo"),
+];
+
+/// The parts of an audit record the mapping needs, from either praudit format.
+#[derive(Default)]
+struct AuditRecord {
+    event: String,
+    time: String,
+    msec: String,
+    args: Vec<String>,
+    paths: Vec<String>,
+    texts: Vec<String>,
+    audit_user: Option<String>,
+    uid: Option<String>,
+    pid: Option<i64>,
+    address: Option<String>,
+    errval: Option<String>,
+}
+
+impl AuditRecord {
+    /// Fill in from a subject token: audit user, effective user, pid and the
+    /// terminal's "port address".
+    fn subject(&mut self, audit_user: &str, uid: &str, pid: &str, terminal: &str) {
+        self.audit_user = Some(audit_user.to_string());
+        self.uid = Some(uid.to_string());
+        self.pid = pid.parse().ok();
+        self.address = terminal
+            .split_whitespace()
+            .last()
+            .filter(|a| !matches!(*a, "0.0.0.0" | "::" | "0"))
+            .map(str::to_string);
+    }
+}
+
+/// praudit writes "Fri Mar  1 10:00:00 2024" in local time plus " + 123 msec".
+fn record_time(time: &str, msec: &str) -> Option<f64> {
+    let time = time.split_whitespace().collect::<Vec<_>>().join(" ");
+    let at = NaiveDateTime::parse_from_str(&time, "%a %b %d %H:%M:%S %Y").ok()?;
+    let at = Local.from_local_datetime(&at).earliest()?;
+    let msec: f64 =
+        msec.trim().trim_start_matches('+').trim_end_matches("msec").trim().parse().unwrap_or(0.0);
+    Some(at.timestamp() as f64 + msec / 1000.0)
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn xml_record_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?s)<record\s([^>]*)>(.*?)</record>").unwrap())
+}
+
+fn xml_attr_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r#"([\w-]+)="([^"]*)""#).unwrap())
+}
+
+/// Leaf elements: <path>, <text> and <arg> (inside exec_args) carry text;
+/// <subject>, <subject_ex> and <return> carry attributes.
+fn xml_element_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?s)<(path|text|arg)>(.*?)</\w+>|<(subject|subject_ex|return)\s([^>]*?)/?>")
+            .unwrap()
+    })
+}
+
+/// Records from `praudit -x` output.
+fn xml_records(text: &str) -> Vec<AuditRecord> {
+    let attrs = |text: &str| -> Vec<(String, String)> {
+        xml_attr_re().captures_iter(text).map(|c| (c[1].to_string(), unescape(&c[2]))).collect()
+    };
+    let get = |attrs: &[(String, String)], key: &str| {
+        attrs.iter().find(|(k, _)| k == key).map(|(_, v)| v.clone()).unwrap_or_default()
+    };
+    xml_record_re()
+        .captures_iter(text)
+        .map(|caps| {
+            let header = attrs(&caps[1]);
+            let mut rec = AuditRecord {
+                event: get(&header, "event"),
+                time: get(&header, "time"),
+                msec: get(&header, "msec"),
+                ..AuditRecord::default()
+            };
+            for el in xml_element_re().captures_iter(&caps[2]) {
+                if let Some(name) = el.get(1) {
+                    let value = unescape(&el[2]);
+                    match name.as_str() {
+                        "path" => rec.paths.push(value),
+                        "text" => rec.texts.push(value),
+                        _ => rec.args.push(value),
+                    }
+                    continue;
+                }
+                let fields = attrs(&el[4]);
+                if &el[3] == "return" {
+                    rec.errval = Some(get(&fields, "errval"));
+                } else {
+                    let [auid, uid, pid, tid] =
+                        ["audit-uid", "uid", "pid", "tid"].map(|k| get(&fields, k));
+                    rec.subject(&auid, &uid, &pid, &tid);
+                }
+            }
+            rec
+        })
+        .collect()
+}
+
+/// One record from praudit's text tokens, each a name and its comma-separated fields.
+fn text_record(tokens: &[(&str, Vec<&str>)]) -> Option<AuditRecord> {
+    let mut rec = AuditRecord::default();
+    for (name, fields) in tokens {
+        match *name {
+            // header,size,version,event,modifier,time,msec; the event name itself may
+            // hold commas ("open(2) - read,write").
+            "header" if fields.len() >= 6 => {
+                let n = fields.len();
+                rec.event = fields[2..n - 3].join(",");
+                rec.time = fields[n - 2].to_string();
+                rec.msec = fields[n - 1].to_string();
+            }
+            "exec arg" => rec.args.extend(fields.iter().map(|f| f.to_string())),
+            "path" => rec.paths.push(fields.join(",")),
+            "text" => rec.texts.push(fields.join(",")),
+            // subject,auid,euid,egid,ruid,rgid,pid,sid,port,address
+            "subject" | "subject_ex" if fields.len() >= 7 => {
+                let terminal = fields[7..].join(" ");
+                rec.subject(fields[0], fields[1], fields[5], &terminal);
+            }
+            "return" => rec.errval = fields.first().map(|f| f.to_string()),
+            _ => {}
+        }
+    }
+    (!rec.event.is_empty()).then_some(rec)
+}
+
+/// Split a line into tokens: the whole line for praudit's default one-token-per-line
+/// output, or every token of the record for `praudit -l`.
+fn line_tokens(line: &str) -> Vec<(&str, Vec<&str>)> {
+    let mut tokens: Vec<(&str, Vec<&str>)> = Vec::new();
+    for field in line.split(',') {
+        match tokens.last_mut() {
+            Some((_, fields)) if !TOKENS.contains(&field) => fields.push(field),
+            _ => tokens.push((field, Vec::new())),
+        }
+    }
+    tokens
+}
+
+/// Records from praudit's text output, default or -l.
+fn text_records(text: &str) -> Vec<AuditRecord> {
+    let mut records = Vec::new();
+    let mut tokens = Vec::new();
+    for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        for token in line_tokens(line) {
+            let end = token.0 == "trailer";
+            if token.0 == "header" {
+                tokens.clear();
+            }
+            tokens.push(token);
+            if end {
+                records.extend(text_record(&tokens));
+                tokens.clear();
+            }
+        }
+    }
+    records
+}
+
+/// Map a record onto the unified schema, or None for events without a mapping.
+fn to_event(rec: AuditRecord) -> Option<Record> {
+    let timestamp = record_time(&rec.time, &rec.msec)?;
+    // "open(2) - write,creat,trunc": the syscall, then what it was asked to do.
+    let (call, modifier) = rec.event.split_once(" - ").unwrap_or((&rec.event, ""));
+    let call = call.split('(').next().unwrap_or_default().trim();
+    // The *at variants (openat, unlinkat, renameat, ...) are the same operation.
+    let base = call.strip_suffix("at").filter(|c| *c != "cre").unwrap_or(call);
+    let (source, kind, extra) = if let Some((_, kind)) =
+        LOGINS.iter().find(|(name, _)| *name == rec.event)
+    {
+        let message = (!rec.texts.is_empty()).then(|| rec.texts.join("; "));
+        ("login", *kind, json!({"remote_address": rec.address, "message": message}))
+    } else {
+        match base {
+            "execve" | "exec" | "posix_spawn" => {
+                let exe = rec.paths.first().cloned();
+                let name = exe.as_deref().map(|p| p.rsplit('/').next().unwrap_or(p).to_string());
+                let extra = json!({"exe": exe, "process_name": name, "argv": rec.args});
+                ("process", "exec", extra)
+            }
+            "open" | "open_extended" | "creat" => {
+                let writes = ["write", "creat", "trunc"].iter().any(|w| modifier.contains(w));
+                let extra = json!({"path": rec.paths.first(), "write": writes || base == "creat"});
+                ("file", "open", extra)
+            }
+            "rename" => {
+                let extra = json!({"path": rec.paths.get(1), "previous_path": rec.paths.first()});
+                ("file", "rename", extra)
+            }
+            "unlink" | "mkdir" | "rmdir" | "truncate" | "link" | "symlink" | "chmod" | "chown" => {
+                ("file", base, json!({"path": rec.paths.first()}))
+            }
+            _ => return None,
+        }
+    };
+    let failure = rec.errval.as_deref().filter(|e| *e != "success");
+    let mut event = record(
+        source,
+        json!({
+            "timestamp": timestamp,
+            "kind": kind,
+            "audit_event": rec.event,
+            "user": rec.audit_user,
+            "uid": rec.uid,
+            "pid": rec.pid,
+            "success": failure.is_none(),
+            "error": failure.map(|e| e.trim_start_matches("failure").trim_start_matches(" : ")),
+        }),
+    );
+    if let Value::Object(extra) = extra {
+        event.extend(extra);
+    }
+    Some(event)
+}
+
+pub(crate) fn parse_trail(text: &str, since: f64) -> Vec<Record> {
+    let records =
+        if text.trim_start().starts_with('<') { xml_records(text) } else { text_records(text) };
+    records
+        .into_iter()
+        .filter_map(to_event)
+        .filter(|e| e["timestamp"].as_f64().is_some_and(|ts| ts > since))
+        .collect()
+}
+
+/// Parse OpenBSM audit records printed by praudit (XML from `praudit -x`, or the
+/// comma-delimited text of plain `praudit` and `praudit -l`) into events in the
+/// unified schema.
+///
+/// Every event has timestamp, kind, audit_event (the BSM event name), user (the
+/// audit user, who logged in), uid (the effective user), pid, success and error.
+/// execve and posix_spawn become {source: "process", kind: "exec", exe,
+/// process_name, argv}; opens become {source: "file", kind: "open", path, write};
+/// rename {kind: "rename", path, previous_path}; unlink, mkdir, rmdir, truncate,
+/// link, symlink, chmod and chown keep their names with path. Logins, logouts,
+/// sudo and user authentication become {source: "login", kind ("ssh", "console",
+/// "remote", "session", "logout", "auth" or "sudo"), remote_address, message}.
+/// Other events are skipped. Only events after since (epoch seconds) are returned,
+/// in trail order.
+#[pyfunction]
+#[pyo3(signature = (text, since=None))]
+pub fn parse_praudit<'py>(
+    py: Python<'py>,
+    text: &str,
+    since: Option<f64>,
+) -> PyResult<Bound<'py, PyList>> {
+    let since = since.unwrap_or(f64::NEG_INFINITY);
+    let events = py.detach(|| parse_trail(text, since));
+    records_to_py(py, events)
+}
+
+/// Run `praudit -x` on an audit trail (by default /var/audit/current, which needs
+/// root) and parse it with parse_praudit.
+///
+/// Raises RuntimeError if praudit fails and IOError if it can't be run.
+#[pyfunction]
+#[pyo3(signature = (path=None, since=None))]
+pub fn audit_trail_events(
+    py: Python<'_>,
+    path: Option<PathBuf>,
+    since: Option<f64>,
+) -> PyResult<Bound<'_, PyList>> {
+    let path = path.unwrap_or_else(|| PathBuf::from(CURRENT_TRAIL));
+    let since = since.unwrap_or(f64::NEG_INFINITY);
+    let events = py.detach(|| -> PyResult<Vec<Record>> {
+        let output = Command::new("praudit")
+            .arg("-x")
+            .arg(&path)
+            .output()
+            .map_err(|e| pyo3::exceptions::PyIOError::new_err(format!("praudit: {e}")))?;
+        if !output.status.success() {
+            return Err(pyo3::exceptions::PyRuntimeError::new_err(format!(
+                "praudit failed ({}): {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(parse_trail(&String::from_utf8_lossy(&output.stdout), since))
+    })?;
+    records_to_py(py, events)
+}
@@ -0,0 +1,217 @@
+#[cfg(feature = "bench")]
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::buffer;
+use crate::parserconfig::ParserConfig;
+use crate::timeutil::parse_iso_ts;
+use crate::{audit, eslogger, installs, logins, power, shell, timemachine, tmux};
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static DEALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static ALLOCATED_BYTES: AtomicU64 = AtomicU64::new(0);
+static FREED_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// The system allocator, counting what passes through it. Only installed with the
+/// bench feature, since every allocation in every thread pays for the counting;
+/// without it the counters stay at zero.
+#[cfg(feature = "bench")]
+struct Counting;
+
+// SAFETY: every call is forwarded unchanged to System; the counters are only atomics.
+#[cfg(feature = "bench")]
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size() as u64, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size() as u64, Ordering::Relaxed);
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        DEALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        FREED_BYTES.fetch_add(layout.size() as u64, Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+
+    // A resize counts as one allocation of the new size and a free of the old.
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        DEALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(new_size as u64, Ordering::Relaxed);
+        FREED_BYTES.fetch_add(layout.size() as u64, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[cfg(feature = "bench")]
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+#[derive(Clone, Copy)]
+struct AllocCounts {
+    allocations: u64,
+    deallocations: u64,
+    allocated_bytes: u64,
+    freed_bytes: u64,
+}
+
+impl AllocCounts {
+    fn now() -> Self {
+        AllocCounts {
+            allocations: ALLOCATIONS.load(Ordering::Relaxed),
+            deallocations: DEALLOCATIONS.load(Ordering::Relaxed),
+            allocated_bytes: ALLOCATED_BYTES.load(Ordering::Relaxed),
+            freed_bytes: FREED_BYTES.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// A parser run over raw input, returning how many records it produced.
+type Parse = fn(&[u8]) -> PyResult<usize>;
+
+fn text(data: &[u8]) -> PyResult<&str> {
+    std::str::from_utf8(data).map_err(|e| PyValueError::new_err(format!("input isn't UTF-8: {e}")))
+}
+
+/// The parsers bench can time, by name. Each is the pure parsing step of the
+/// matching public function, without file reading or conversion to Python objects.
+const PARSERS: &[(&str, Parse)] = &[
+    ("transcript", |data| {
+        let config = ParserConfig::default();
+        let source = Path::new("<bench>");
+        let (events, _) =
+            crate::parse_transcript_lines(data, source, 0, String::new(), String::new(), &config)?;
+        Ok(events.len())
+    }),
+    ("iso_timestamps", |data| Ok(text(data)?.lines().filter_map(parse_iso_ts).count())),
+    ("zsh_history", |data| Ok(shell::parse_zsh(data).0.len())),
+    ("bash_history", |data| Ok(shell::parse_bash(data).0.len())),
+    ("fish_history", |data| Ok(shell::parse_fish(data).0.len())),
+    ("pmset_log", |data| Ok(power::parse_log(text(data)?, 0.0).len())),
+    ("install_log", |data| Ok(installs::parse_log(text(data)?, 0.0).len())),
+    ("backupd_log", |data| Ok(timemachine::parse_log(text(data)?, 0.0).len())),
+    ("last", |data| Ok(logins::parse_output(text(data)?, 0.0).len())),
+    ("praudit", |data| Ok(audit::parse_trail(text(data)?, 0.0).len())),
+    ("eslogger", |data| {
+        let lines: Vec<&str> = text(data)?.lines().collect();
+        Ok(eslogger::parse_lines(&lines, 0.0).len())
+    }),
+    ("tmux_panes", |data| Ok(tmux::parse_panes(text(data)?).len())),
+    ("tmux_sessions", |data| Ok(tmux::parse_sessions(text(data)?).len())),
+];
+
+/// Names of the parsers time_parser accepts.
+#[pyfunction]
+fn parsers() -> Vec<&'static str> {
+    PARSERS.iter().map(|(name, _)| *name).collect()
+}
+
+/// Time one parser over data, repeated iterations times with the GIL released.
+///
+/// data is bytes or any other buffer, in the format the parser reads from disk
+/// (a transcript's JSONL, a zsh history file, `last` output, one ISO timestamp per
+/// line for "iso_timestamps", ...). Returns a dict of parser, iterations, bytes and
+/// lines (per iteration), records (produced per iteration), seconds (total),
+/// lines_per_sec and bytes_per_sec. Built with the bench cargo feature, it also has
+/// allocations and allocated_bytes (per iteration, averaged); these are process-wide,
+/// so other threads busy at the same time (a running Collector, say) inflate them.
+/// Raises ValueError for an unknown parser or text input that isn't UTF-8.
+#[pyfunction]
+#[pyo3(signature = (parser, data, iterations=10))]
+fn time_parser<'py>(
+    py: Python<'py>,
+    parser: &str,
+    data: &Bound<'py, PyAny>,
+    iterations: u32,
+) -> PyResult<Bound<'py, PyDict>> {
+    let Some(&(name, parse)) = PARSERS.iter().find(|(name, _)| *name == parser) else {
+        return Err(PyValueError::new_err(format!(
+            "unknown parser {parser:?}; expected one of {:?}",
+            parsers()
+        )));
+    };
+    let iterations = iterations.max(1);
+    let (bytes, lines, records, seconds, allocs) = buffer::with_bytes(data, |data| {
+        py.detach(|| {
+            let lines = memchr::memchr_iter(b'\n', data).count()
+                + usize::from(data.last().is_some_and(|&b| b != b'\n'));
+            let before = AllocCounts::now();
+            let start = Instant::now();
+            let mut records = 0;
+            for _ in 0..iterations {
+                records = parse(data)?;
+            }
+            let seconds = start.elapsed().as_secs_f64();
+            let after = AllocCounts::now();
+            Ok::<_, PyErr>((data.len(), lines, records, seconds, (before, after)))
+        })
+    })??;
+    let (before, after) = allocs;
+    let per_run = |total: f64| total / f64::from(iterations);
+    let rate = |count: usize| {
+        if seconds > 0.0 {
+            count as f64 * f64::from(iterations) / seconds
+        } else {
+            0.0
+        }
+    };
+    let dict = PyDict::new(py);
+    dict.set_item("parser", name)?;
+    dict.set_item("iterations", iterations)?;
+    dict.set_item("bytes", bytes)?;
+    dict.set_item("lines", lines)?;
+    dict.set_item("records", records)?;
+    dict.set_item("seconds", seconds)?;
+    dict.set_item("lines_per_sec", rate(lines))?;
+    dict.set_item("bytes_per_sec", rate(bytes))?;
+    if cfg!(feature = "bench") {
+        dict.set_item("allocations", per_run((after.allocations - before.allocations) as f64))?;
+        dict.set_item(
+            "allocated_bytes",
+            per_run((after.allocated_bytes - before.allocated_bytes) as f64),
+        )?;
+    }
+    Ok(dict)
+}
+
+/// Running totals of the extension's heap use since import: allocations,
+/// deallocations, allocated_bytes, freed_bytes and live_bytes (allocated, not yet
+/// freed). Take one before and after any call to see what it cost. Only there when the
+/// extension is built with the bench cargo feature.
+#[pyfunction]
+fn allocation_stats(py: Python<'_>) -> PyResult<Bound<'_, PyDict>> {
+    let counts = AllocCounts::now();
+    let dict = PyDict::new(py);
+    dict.set_item("allocations", counts.allocations)?;
+    dict.set_item("deallocations", counts.deallocations)?;
+    dict.set_item("allocated_bytes", counts.allocated_bytes)?;
+    dict.set_item("freed_bytes", counts.freed_bytes)?;
+    dict.set_item("live_bytes", counts.allocated_bytes.saturating_sub(counts.freed_bytes))?;
+    Ok(dict)
+}
+
+/// Add the snoopy_native.bench submodule to m, importable by its dotted name too.
+pub(crate) fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    let bench = PyModule::new(py, "bench")?;
+    bench.add_function(wrap_pyfunction!(parsers, &bench)?)?;
+    bench.add_function(wrap_pyfunction!(time_parser, &bench)?)?;
+    if cfg!(feature = "bench") {
+        bench.add_function(wrap_pyfunction!(allocation_stats, &bench)?)?;
+    }
+    m.add_submodule(&bench)?;
+    py.import("sys")?.getattr("modules")?.set_item("snoopy_native.bench", &bench)?;
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use pyo3::buffer::PyBuffer;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+/// Run f over the bytes of any object supporting the buffer protocol (bytes,
+/// bytearray, memoryview, mmap, numpy arrays of uint8, ...).
+///
+/// bytes and C-contiguous buffers are borrowed in place; anything else is copied
+/// once. While f runs the buffer stays exported, so a bytearray can't be resized
+/// under it and f may release the GIL.
+pub(crate) fn with_bytes<R>(obj: &Bound<'_, PyAny>, f: impl FnOnce(&[u8]) -> R) -> PyResult<R> {
+    if let Ok(bytes) = obj.cast::<PyBytes>() {
+        return Ok(f(bytes.as_bytes()));
+    }
+    let buffer = PyBuffer::<u8>::get(obj)?;
+    if buffer.is_c_contiguous() {
+        // SAFETY: the exporter keeps len_bytes() bytes at buf_ptr() alive and in place
+        // until buffer is released, which is after f returns.
+        let data = unsafe {
+            std::slice::from_raw_parts(buffer.buf_ptr() as *const u8, buffer.len_bytes())
+        };
+        return Ok(f(data));
+    }
+    Ok(f(&buffer.to_vec(obj.py())?))
+}
@@ -0,0 +1,141 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+use rusqlite::Connection;
+use serde_json::json;
+
+use crate::collector::{home_path, record, records_to_py, Record};
+use crate::history::{has_table, with_snapshot};
+
+/// Calendar stores times as seconds since 2001-01-01 (Cocoa reference date).
+const COCOA_EPOCH_OFFSET: f64 = 978_307_200.0;
+/// macOS 14+ keeps the store in a group container; older releases in ~/Library/Calendars.
+const CALENDAR_DBS: [&str; 2] = [
+    "Library/Group Containers/group.com.apple.calendar/Calendar.sqlitedb",
+    "Library/Calendars/Calendar.sqlitedb",
+];
+
+struct Occurrence {
+    item: i64,
+    start: f64,
+    end: f64,
+}
+
+/// Start and end of every event overlapping [start, end), in Cocoa seconds.
+///
+/// CalendarItem holds each event once (a recurring event at its first occurrence);
+/// OccurrenceCache, when present, holds the expanded occurrences Calendar.app has
+/// computed, so recurring meetings show up on every day they happen.
+fn occurrences(conn: &Connection, start: f64, end: f64) -> rusqlite::Result<Vec<Occurrence>> {
+    let mut queries = vec![
+        "SELECT ROWID, start_date, COALESCE(end_date, start_date)
+         FROM CalendarItem
+         WHERE start_date IS NOT NULL AND start_date < ?2
+           AND COALESCE(end_date, start_date) >= ?1",
+    ];
+    if has_table(conn, "OccurrenceCache")? {
+        queries.push(
+            "SELECT id, start, start + length
+             FROM (SELECT o.event_id AS id,
+                          COALESCE(o.occurrence_start_date, o.occurrence_date) AS start,
+                          COALESCE(ci.end_date - ci.start_date, 0) AS length
+                   FROM OccurrenceCache o JOIN CalendarItem ci ON ci.ROWID = o.event_id)
+             WHERE start < ?2 AND start + length >= ?1",
+        );
+    }
+    let mut seen = HashSet::new();
+    let mut found = Vec::new();
+    for sql in queries {
+        let mut stmt = conn.prepare(sql)?;
+        let rows = stmt.query_map([start, end], |row| {
+            Ok(Occurrence { item: row.get(0)?, start: row.get(1)?, end: row.get(2)? })
+        })?;
+        for occurrence in rows {
+            let occurrence = occurrence?;
+            if seen.insert((occurrence.item, occurrence.start.to_bits())) {
+                found.push(occurrence);
+            }
+        }
+    }
+    found.sort_by(|a, b| a.start.total_cmp(&b.start).then(a.item.cmp(&b.item)));
+    Ok(found)
+}
+
+fn read_events(conn: &Connection, start: f64, end: f64) -> rusqlite::Result<Vec<Record>> {
+    let mut item = conn.prepare(
+        "SELECT ci.unique_identifier, ci.summary, ci.all_day, ci.has_recurrences, ci.status,
+                c.title, l.title
+         FROM CalendarItem ci
+         LEFT JOIN Calendar c ON c.ROWID = ci.calendar_id
+         LEFT JOIN Location l ON l.ROWID = ci.location_id
+         WHERE ci.ROWID = ?",
+    )?;
+    let attendee_sql = if has_table(conn, "Identity")? {
+        "SELECT COALESCE(NULLIF(i.display_name, ''), p.email)
+         FROM Participant p LEFT JOIN Identity i ON i.ROWID = p.identity_id
+         WHERE p.owner_id = ? ORDER BY p.ROWID"
+    } else {
+        "SELECT p.email FROM Participant p WHERE p.owner_id = ? ORDER BY p.ROWID"
+    };
+    let mut attendees = conn.prepare(attendee_sql)?;
+
+    let mut events = Vec::new();
+    for occ in occurrences(conn, start - COCOA_EPOCH_OFFSET, end - COCOA_EPOCH_OFFSET)? {
+        let names: Vec<String> = attendees
+            .query_map([occ.item], |row| row.get::<_, Option<String>>(0))?
+            .filter_map(|name| name.transpose())
+            .collect::<rusqlite::Result<_>>()?;
+        let event = item.query_row([occ.item], |row| {
+            let (start, end) = (occ.start + COCOA_EPOCH_OFFSET, occ.end + COCOA_EPOCH_OFFSET);
+            Ok(record(
+                "calendar",
+                json!({
+                    "uid": row.get::<_, Option<String>>(0)?,
+                    "title": row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+                    "timestamp": start,
+                    "end": end,
+                    "duration": (end - start).max(0.0),
+                    "calendar": row.get::<_, Option<String>>(5)?,
+                    "location": row.get::<_, Option<String>>(6)?,
+                    "all_day": row.get::<_, Option<i64>>(2)?.unwrap_or(0) != 0,
+                    "recurring": row.get::<_, Option<i64>>(3)?.unwrap_or(0) != 0,
+                    "status": row.get::<_, Option<i64>>(4)?,
+                    "is_meeting": !names.is_empty(),
+                    "attendees": names,
+                }),
+            ))
+        })?;
+        events.push(event);
+    }
+    Ok(events)
+}
+
+/// Read events from the macOS Calendar store that overlap start..end (epoch seconds).
+///
+/// Returns {source: "calendar", uid, title, timestamp, end, duration, calendar,
+/// location, all_day, recurring, status, is_meeting, attendees} sorted by start time,
+/// with recurring events expanded from Calendar's occurrence cache. is_meeting is
+/// true when the event has invitees; attendees lists their names (or emails). path
+/// defaults to Calendar.sqlitedb in the calendar group container, falling back to
+/// ~/Library/Calendars; reading it needs Full Disk Access.
+#[pyfunction]
+#[pyo3(signature = (path=None, start=None, end=None))]
+pub fn calendar_events<'py>(
+    py: Python<'py>,
+    path: Option<PathBuf>,
+    start: Option<f64>,
+    end: Option<f64>,
+) -> PyResult<Bound<'py, PyList>> {
+    let path = path.unwrap_or_else(|| {
+        CALENDAR_DBS
+            .iter()
+            .map(|p| home_path(p))
+            .find(|p| p.exists())
+            .unwrap_or_else(|| home_path(CALENDAR_DBS[0]))
+    });
+    let (start, end) = (start.unwrap_or(f64::MIN), end.unwrap_or(f64::MAX));
+    let events = py.detach(|| with_snapshot(&path, |conn| read_events(conn, start, end)))?;
+    records_to_py(py, events)
+}
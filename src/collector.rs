@@ -0,0 +1,862 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList, PyTuple};
+use serde_json::{json, Map, Value};
+
+use crate::aio::{self, Next};
+use crate::anonymize::Anonymizer;
+use crate::compress::Compressor;
+use crate::crypto::EncryptionKey;
+use crate::errors::ReadError;
+use crate::frontmost::FrontmostSampler;
+use crate::idle::IdleSampler;
+use crate::input::InputSampler;
+use crate::meetings::{MeetingSampler, DEFAULT_GRACE};
+use crate::processes::{ResourceLimits, ResourceSampler};
+use crate::usb::UsbSampler;
+use crate::wifi::WifiSampler;
+use crate::persistence::PersistenceSampler;
+use crate::privacy::global_filter;
+use crate::rollup::find_jsonl_files;
+use crate::screenshot::{ScreenshotOptions, ScreenshotSampler};
+use crate::shutdown::{deadline, join_within, CLOSE_TIMEOUT};
+use crate::sink::{EventQueue, NdjsonOptions, Overflow, Sink};
+use crate::snapshot::{Snapshot, DEFAULT_TIMEOUT};
+use crate::tcc::TccSampler;
+use crate::timemachine::TimeMachineSampler;
+use crate::tmux::{TmuxOptions, TmuxSampler};
+use crate::parserconfig::ParserConfig;
+use crate::{attributed_body_text, lsof_connections, TranscriptTail};
+
+/// Longest single sleep before a sampler thread rechecks the stop flag.
+const SLEEP_SLICE: Duration = Duration::from_millis(100);
+
+const APPLE_EPOCH_OFFSET: f64 = 978_307_200.0;
+pub(crate) const CONTENT_PREVIEW_LEN: usize = 100_000;
+const DEFAULT_SOURCES: [&str; 3] = ["network", "claude", "messages"];
+const SOURCES: [&str; 15] = [
+    "network",
+    "claude",
+    "messages",
+    "frontmost",
+    "idle",
+    "resources",
+    "wifi",
+    "usb",
+    "input",
+    "screenshot",
+    "tcc",
+    "persistence",
+    "backup",
+    "tmux",
+    "meetings",
+];
+
+/// One collected event: a flat JSON object whose "source" names the sampler.
+pub(crate) type Record = Map<String, Value>;
+
+pub(crate) fn now() -> f64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs_f64()).unwrap_or(0.0)
+}
+
+pub(crate) fn home_path(rel: &str) -> PathBuf {
+    let home = std::env::var_os("HOME").unwrap_or_default();
+    PathBuf::from(home).join(rel)
+}
+
+pub(crate) fn record(source: &str, fields: Value) -> Record {
+    let mut rec = Map::new();
+    rec.insert("source".to_string(), Value::from(source));
+    if let Value::Object(fields) = fields {
+        rec.extend(fields);
+    }
+    rec
+}
+
+/// Convert a JSON value into the equivalent Python object.
+pub(crate) fn json_to_py<'py>(py: Python<'py>, value: &Value) -> PyResult<Bound<'py, PyAny>> {
+    Ok(match value {
+        Value::Null => py.None().into_bound(py),
+        Value::Bool(b) => b.into_pyobject(py)?.to_owned().into_any(),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => i.into_pyobject(py)?.into_any(),
+            None => n.as_f64().unwrap_or(0.0).into_pyobject(py)?.into_any(),
+        },
+        Value::String(s) => s.into_pyobject(py)?.into_any(),
+        Value::Array(items) => {
+            let list = PyList::empty(py);
+            for item in items {
+                list.append(json_to_py(py, item)?)?;
+            }
+            list.into_any()
+        }
+        Value::Object(map) => {
+            let dict = PyDict::new(py);
+            for (k, v) in map {
+                dict.set_item(k, json_to_py(py, v)?)?;
+            }
+            dict.into_any()
+        }
+    })
+}
+
+/// Apply the installed privacy filter and convert records to dicts.
+pub(crate) fn records_to_py<'py>(
+    py: Python<'py>,
+    mut records: Vec<Record>,
+) -> PyResult<Bound<'py, PyList>> {
+    if let Some(filter) = global_filter() {
+        records.retain_mut(|r| filter.apply_record(r));
+    }
+    let out = PyList::empty(py);
+    for record in records {
+        out.append(json_to_py(py, &Value::Object(record))?)?;
+    }
+    Ok(out)
+}
+
+/// A periodic source of events, run on its own thread by Collector.
+pub(crate) trait Sampler: Send {
+    fn name(&self) -> &'static str;
+    fn sample(&mut self) -> Result<Vec<Record>, String>;
+}
+
+/// New established TCP connections since the previous poll, from lsof.
+#[derive(Default)]
+struct NetworkSampler {
+    seen: HashSet<(String, String, u16)>,
+}
+
+impl Sampler for NetworkSampler {
+    fn name(&self) -> &'static str {
+        "network"
+    }
+
+    fn sample(&mut self) -> Result<Vec<Record>, String> {
+        let output = std::process::Command::new("lsof")
+            .args(["-i", "-P", "-n"])
+            .output()
+            .map_err(|e| format!("lsof: {e}"))?;
+        if !output.status.success() {
+            return Ok(Vec::new());
+        }
+        let current = lsof_connections(&String::from_utf8_lossy(&output.stdout));
+        let ts = now();
+        let events = current
+            .difference(&self.seen)
+            .map(|(process, addr, port)| {
+                record(self.name(), json!({
+                    "timestamp": ts,
+                    "process_name": process,
+                    "protocol": "TCP",
+                    "remote_address": addr,
+                    "remote_port": port,
+                }))
+            })
+            .collect();
+        self.seen = current;
+        Ok(events)
+    }
+}
+
+/// New Claude Code transcript events, read incrementally from each file's last offset.
+struct ClaudeSampler {
+    root: PathBuf,
+    tails: HashMap<PathBuf, TranscriptTail>,
+    initialized: bool,
+    /// Transcripts whose last read failed, already warned about.
+    failing: HashSet<PathBuf>,
+}
+
+impl Sampler for ClaudeSampler {
+    fn name(&self) -> &'static str {
+        "claude"
+    }
+
+    fn sample(&mut self) -> Result<Vec<Record>, String> {
+        let mut events = Vec::new();
+        for path in find_jsonl_files(&self.root) {
+            let size = match std::fs::metadata(&path) {
+                Ok(m) => m.len(),
+                Err(_) => continue,
+            };
+            // First run: record current positions without importing history.
+            if !self.initialized {
+                self.tails.insert(path, TranscriptTail::new(size));
+                continue;
+            }
+            let tail = self.tails.entry(path.clone()).or_default();
+            // Truncated or replaced: start over.
+            if size < tail.offset {
+                *tail = TranscriptTail::default();
+            }
+            if size == tail.offset {
+                continue;
+            }
+            let config = ParserConfig::with_preview_len(CONTENT_PREVIEW_LEN);
+            // One unreadable transcript mustn't cost the others their events; it is
+            // retried from where it stopped next time, with a warning only the first.
+            let (parsed, errors) = tail.read(&path, &config);
+            let mut failed = false;
+            for e in errors {
+                match e {
+                    ReadError::Parse(..) => {
+                        tracing::warn!(source = self.name(), "skipping line: {e}");
+                    }
+                    ReadError::Io(..) => {
+                        failed = true;
+                        if !self.failing.contains(&path) {
+                            tracing::warn!(source = self.name(), "skipping transcript: {e}");
+                        }
+                    }
+                }
+            }
+            if failed {
+                self.failing.insert(path);
+            } else {
+                self.failing.remove(&path);
+            }
+            events.extend(parsed.into_iter().filter(|ev| !ev.withheld).map(|ev| {
+                record(self.name(), json!({
+                    "timestamp": ev.timestamp,
+                    "session_id": ev.session_id,
+                    "message_type": ev.message_type,
+                    "content_preview": ev.content_preview,
+                    "project_path": ev.project_path,
+                }))
+            }));
+        }
+        self.initialized = true;
+        Ok(events)
+    }
+}
+
+/// New iMessage rows from chat.db, tracked by ROWID.
+struct MessagesSampler {
+    db_path: PathBuf,
+    last_id: Option<i64>,
+}
+
+impl MessagesSampler {
+    fn read_snapshot(&mut self, db: &Path) -> rusqlite::Result<Vec<Record>> {
+        let conn = rusqlite::Connection::open(db)?;
+        let last_id = match self.last_id {
+            Some(id) => id,
+            None => {
+                let max: Option<i64> =
+                    conn.query_row("SELECT MAX(ROWID) FROM message", [], |r| r.get(0))?;
+                self.last_id = Some(max.unwrap_or(0));
+                return Ok(Vec::new());
+            }
+        };
+        let mut stmt = conn.prepare(
+            "SELECT m.ROWID, m.text, m.is_from_me, m.date, m.service,
+                    m.cache_has_attachments, h.id,
+                    COALESCE(c.display_name, c.chat_identifier, h.id, ''),
+                    m.attributedBody, m.destination_caller_id
+             FROM message m
+             LEFT JOIN handle h ON m.handle_id = h.ROWID
+             LEFT JOIN chat_message_join cmj ON m.ROWID = cmj.message_id
+             LEFT JOIN chat c ON cmj.chat_id = c.ROWID
+             WHERE m.ROWID > ?
+             ORDER BY m.ROWID",
+        )?;
+        let mut rows = stmt.query([last_id])?;
+        let mut events = Vec::new();
+        let mut max_id = last_id;
+        while let Some(row) = rows.next()? {
+            let rowid: i64 = row.get(0)?;
+            let text: Option<String> = row.get(1)?;
+            let date: Option<i64> = row.get(3)?;
+            let has_attach: Option<i64> = row.get(5)?;
+            let handle: Option<String> = row.get(6)?;
+            let body: Option<Vec<u8>> = row.get(8)?;
+            let dest_caller: Option<String> = row.get(9)?;
+
+            let ts = match date {
+                Some(d) if d != 0 => d as f64 / 1e9 + APPLE_EPOCH_OFFSET,
+                _ => now(),
+            };
+            let mut content = text.unwrap_or_default();
+            if content.is_empty() {
+                content = attributed_body_text(body.as_deref().unwrap_or(&[]));
+            }
+            if content.is_empty() && has_attach.unwrap_or(0) != 0 {
+                content = "[attachment]".to_string();
+            }
+            let content = crate::truncate_str(&content, CONTENT_PREVIEW_LEN);
+            events.push(record("messages", json!({
+                "timestamp": ts,
+                "contact": handle.or(dest_caller).unwrap_or_default(),
+                "is_from_me": row.get::<_, Option<i64>>(2)?.unwrap_or(0),
+                "content_preview": content,
+                "has_attachment": has_attach.unwrap_or(0),
+                "service": row.get::<_, Option<String>>(4)?.unwrap_or_default(),
+                "chat_name": row.get::<_, String>(7)?,
+            })));
+            max_id = max_id.max(rowid);
+        }
+        self.last_id = Some(max_id);
+        Ok(events)
+    }
+}
+
+impl Sampler for MessagesSampler {
+    fn name(&self) -> &'static str {
+        "messages"
+    }
+
+    fn sample(&mut self) -> Result<Vec<Record>, String> {
+        if !self.db_path.exists() {
+            return Ok(Vec::new());
+        }
+        let snapshot = Snapshot::take(&self.db_path, DEFAULT_TIMEOUT)
+            .map_err(|e| format!("{}: {e}", self.db_path.display()))?;
+        self.read_snapshot(snapshot.path()).map_err(|e| e.to_string())
+    }
+}
+
+#[derive(Default)]
+struct SourceStatus {
+    running: bool,
+    events: u64,
+    errors: u64,
+    last_error: Option<String>,
+    last_run: Option<f64>,
+    /// Events dropped by sample_ratio.
+    sampled_out: u64,
+    /// Events dropped by max_events_per_second.
+    rate_limited: u64,
+}
+
+/// Per-source sampling and rate limit, applied on the sampler thread before delivery.
+struct Throttle {
+    /// Fraction of events kept, spread evenly (every other one at 0.5).
+    ratio: f64,
+    /// Token bucket refilled at this many events per second, holding up to a
+    /// second's worth, if limited.
+    rate: Option<f64>,
+    kept: f64,
+    tokens: f64,
+    refilled: Instant,
+}
+
+impl Throttle {
+    fn new(ratio: f64, rate: Option<f64>) -> Self {
+        Throttle {
+            ratio,
+            rate,
+            kept: 0.0,
+            tokens: rate.map_or(0.0, |r| r.max(1.0)),
+            refilled: Instant::now(),
+        }
+    }
+
+    /// Drop sampled-out and over-rate events; returns (sampled_out, rate_limited).
+    fn apply(&mut self, events: &mut Vec<Record>) -> (u64, u64) {
+        let before = events.len();
+        if self.ratio < 1.0 {
+            events.retain(|_| {
+                self.kept += self.ratio;
+                let keep = self.kept >= 1.0;
+                if keep {
+                    self.kept -= 1.0;
+                }
+                keep
+            });
+        }
+        let sampled = events.len();
+        if let Some(rate) = self.rate {
+            let now = Instant::now();
+            let elapsed = now.duration_since(self.refilled).as_secs_f64();
+            self.tokens = (self.tokens + elapsed * rate).min(rate.max(1.0));
+            self.refilled = now;
+            let allowed = (self.tokens.floor() as usize).min(events.len());
+            self.tokens -= allowed as f64;
+            events.truncate(allowed);
+        }
+        ((before - sampled) as u64, (sampled - events.len()) as u64)
+    }
+}
+
+/// A per-source setting given as one number for every source or a dict by source.
+fn per_source(
+    value: Option<&Bound<'_, PyAny>>,
+    sources: &[String],
+    what: &str,
+    valid: fn(f64) -> bool,
+) -> PyResult<HashMap<String, f64>> {
+    let values: HashMap<String, f64> = match value {
+        None => return Ok(HashMap::new()),
+        Some(v) if v.is_none() => return Ok(HashMap::new()),
+        Some(v) => match v.extract::<f64>() {
+            Ok(n) => sources.iter().map(|s| (s.clone(), n)).collect(),
+            Err(_) => v.extract()?,
+        },
+    };
+    for (source, n) in &values {
+        if !sources.contains(source) {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "{what} given for {source:?}, which is not one of this collector's sources"
+            )));
+        }
+        if !valid(*n) {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "{what} for {source:?} is out of range: {n}"
+            )));
+        }
+    }
+    Ok(values)
+}
+
+struct Shared {
+    stop: AtomicBool,
+    dropped: AtomicU64,
+    statuses: Mutex<BTreeMap<&'static str, SourceStatus>>,
+}
+
+fn run_sampler(
+    mut sampler: Box<dyn Sampler>,
+    interval: Duration,
+    mut throttle: Throttle,
+    sink: Arc<Sink>,
+    shared: Arc<Shared>,
+) {
+    let name = sampler.name();
+    while !shared.stop.load(Ordering::Relaxed) {
+        let (mut events, error) = match sampler.sample() {
+            Ok(events) => (events, None),
+            Err(e) => (Vec::new(), Some(e)),
+        };
+        if let Some(filter) = global_filter() {
+            events.retain_mut(|ev| filter.apply_record(ev));
+        }
+        let (sampled_out, rate_limited) = throttle.apply(&mut events);
+        let delivery = sink.deliver(events, &shared.stop);
+        shared.dropped.fetch_add(delivery.dropped, Ordering::Relaxed);
+        let error = error.or(delivery.error);
+        if let Ok(mut statuses) = shared.statuses.lock() {
+            let status = statuses.entry(name).or_default();
+            status.last_run = Some(now());
+            status.events += delivery.sent;
+            status.sampled_out += sampled_out;
+            status.rate_limited += rate_limited;
+            if let Some(e) = &error {
+                status.errors += 1;
+                status.last_error = Some(e.clone());
+            }
+        }
+        // Logging takes the GIL, so never while holding statuses: status() holds the
+        // GIL while it waits for that lock.
+        if let Some(e) = error {
+            tracing::warn!(source = name, "{e}");
+        }
+
+        // An interval past what an Instant can represent waits for stop().
+        let deadline = std::time::Instant::now().checked_add(interval);
+        while !shared.stop.load(Ordering::Relaxed) {
+            let remaining = match deadline {
+                Some(d) => d.saturating_duration_since(std::time::Instant::now()),
+                None => SLEEP_SLICE,
+            };
+            if remaining.is_zero() {
+                break;
+            }
+            std::thread::sleep(remaining.min(SLEEP_SLICE));
+        }
+    }
+    if let Ok(mut statuses) = shared.statuses.lock() {
+        statuses.entry(name).or_default().running = false;
+    }
+}
+
+/// Runs native samplers on background threads and buffers their events.
+///
+/// Sources are "network" (new lsof connections), "claude" (new transcript events
+/// under projects_dir) and "messages" (new chat.db rows), plus these opt-in ones:
+/// "frontmost" (focused app and window title changes), "idle" (an "idle" event once
+/// there has been no input for idle_threshold seconds, "active" with the idle period's
+/// duration when it resumes), "resources" (processes that stay over the CPU or memory
+/// thresholds of resource_limits, a ResourceLimits, and when they recover), "wifi"
+/// (joining, roaming between and leaving Wi-Fi networks), "usb" (devices attached
+/// and detached since the collector started), "input" (keystroke, click and scroll
+/// counts per minute and frontmost app, never the keys themselves; see input_counts),
+/// "screenshot" (screen captures and the frontmost app, saved as configured by
+/// screenshots, a ScreenshotOptions, skipping frames that look the same as the last one
+/// saved and capturing nothing while the privacy filter denies the app), "tcc" (privacy
+/// permissions granted, denied or reset, read from tcc_db or the default TCC databases;
+/// see tcc_permissions), "persistence" (launch agents, daemons, login items and
+/// autostart entries added, removed or edited; see persistence_snapshot), "backup"
+/// (Time Machine backups starting and completing, with what they copied; see
+/// time_machine_status), "tmux" (panes opened and closed, and long-running commands
+/// inside them, as configured by tmux, a TmuxOptions) and "meetings" (meetings starting
+/// and ending, with platform and duration, from microphone and camera use, the focused
+/// app and connections; see MeetingDetector).
+///
+/// By default events queue up, at most queue_size of them, until drain() is called
+/// (or, from asyncio code, next_event() or `async for`). overflow decides what a full
+/// queue does: "drop-newest" drops arriving events, "drop-oldest" makes room by
+/// dropping the oldest queued one and "block" holds up the sampler until there is
+/// room. Drops are counted in status(), and stats() reports the queue's depth.
+///
+/// A noisy source can be thinned before it reaches the queue: sample_ratio keeps
+/// that fraction of its events and max_events_per_second caps its rate, allowing
+/// bursts of up to a second's worth. Each takes one number for every source or a
+/// dict by source name, and what they drop is counted per source in status() as
+/// sampled_out and rate_limited.
+///
+/// sink instead pushes each batch straight from the sampler threads: a callable
+/// receives a list of dicts, "ndjson:PATH" appends to a file and "unix:PATH" writes
+/// NDJSON to a Unix socket. The NDJSON file can be zstd-compressed per batch
+/// (compression="zstd", optionally with a dictionary from train_zstd_dictionary) and
+/// encrypted with an EncryptionKey; read it back with read_ndjson. anonymize, an
+/// Anonymizer, writes pseudonyms in place of session ids, paths, contacts and
+/// hostnames, so the file can be shared. The filter installed with set_privacy_filter()
+/// is applied before events reach any sink. A Collector may be shared between threads:
+/// one can drain or await events while another stops it. `with Collector(...) as c:`
+/// starts it and closes it on the way out, even if an exception escapes.
+#[pyclass(frozen)]
+pub struct Collector {
+    sources: Vec<String>,
+    interval: Duration,
+    projects_dir: PathBuf,
+    chat_db: PathBuf,
+    idle_threshold: f64,
+    resource_limits: ResourceLimits,
+    screenshots: Option<ScreenshotOptions>,
+    tcc_db: Option<PathBuf>,
+    tmux: TmuxOptions,
+    sample_ratios: HashMap<String, f64>,
+    max_rates: HashMap<String, f64>,
+    sink: Arc<Sink>,
+    queue: Arc<EventQueue>,
+    shared: Arc<Shared>,
+    threads: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl Collector {
+    fn is_running(&self) -> bool {
+        !self.threads.lock().unwrap_or_else(|e| e.into_inner()).is_empty()
+    }
+
+    fn make_sampler(&self, source: &str) -> Box<dyn Sampler> {
+        match source {
+            "network" => Box::new(NetworkSampler::default()),
+            "claude" => Box::new(ClaudeSampler {
+                root: self.projects_dir.clone(),
+                tails: HashMap::new(),
+                initialized: false,
+                failing: HashSet::new(),
+            }),
+            "frontmost" => Box::new(FrontmostSampler::default()),
+            "idle" => Box::new(IdleSampler::new(self.idle_threshold)),
+            "resources" => Box::new(ResourceSampler::new(self.resource_limits.clone())),
+            "wifi" => Box::new(WifiSampler::default()),
+            "usb" => Box::new(UsbSampler::default()),
+            "input" => Box::new(InputSampler::default()),
+            "screenshot" => Box::new(ScreenshotSampler::new(
+                self.screenshots.clone().expect("checked in Collector::new"),
+            )),
+            "tcc" => Box::new(TccSampler::new(self.tcc_db.clone())),
+            "persistence" => Box::new(PersistenceSampler::default()),
+            "backup" => Box::new(TimeMachineSampler::default()),
+            "tmux" => Box::new(TmuxSampler::new(self.tmux.clone())),
+            "meetings" => Box::new(MeetingSampler::new(DEFAULT_GRACE)),
+            _ => Box::new(MessagesSampler {
+                db_path: self.chat_db.clone(),
+                last_id: None,
+            }),
+        }
+    }
+}
+
+#[pymethods]
+impl Collector {
+    #[new]
+    #[pyo3(signature = (
+        sources=None, interval=5.0, queue_size=10_000, projects_dir=None, chat_db=None, sink=None,
+        encryption=None, compression=None, compression_level=3, compression_dict=None,
+        idle_threshold=300.0, resource_limits=None, screenshots=None, tcc_db=None, tmux=None,
+        sample_ratio=None, max_events_per_second=None, overflow="drop-newest", anonymize=None
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        sources: Option<Vec<String>>,
+        interval: f64,
+        queue_size: usize,
+        projects_dir: Option<PathBuf>,
+        chat_db: Option<PathBuf>,
+        sink: Option<&Bound<'_, PyAny>>,
+        encryption: Option<EncryptionKey>,
+        compression: Option<&str>,
+        compression_level: i32,
+        compression_dict: Option<Vec<u8>>,
+        idle_threshold: f64,
+        resource_limits: Option<ResourceLimits>,
+        screenshots: Option<ScreenshotOptions>,
+        tcc_db: Option<PathBuf>,
+        tmux: Option<TmuxOptions>,
+        sample_ratio: Option<&Bound<'_, PyAny>>,
+        max_events_per_second: Option<&Bound<'_, PyAny>>,
+        overflow: &str,
+        anonymize: Option<Anonymizer>,
+    ) -> PyResult<Self> {
+        let sources =
+            sources.unwrap_or_else(|| DEFAULT_SOURCES.iter().map(|s| s.to_string()).collect());
+        if let Some(bad) = sources.iter().find(|s| !SOURCES.contains(&s.as_str())) {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "unknown source {bad:?}; expected one of {SOURCES:?}"
+            )));
+        }
+        let interval = Duration::try_from_secs_f64(interval)
+            .ok()
+            .filter(|d| !d.is_zero())
+            .ok_or_else(|| {
+                pyo3::exceptions::PyValueError::new_err(format!(
+                    "interval must be a positive number of seconds, got {interval}"
+                ))
+            })?;
+        if queue_size == 0 {
+            return Err(pyo3::exceptions::PyValueError::new_err("queue_size must be positive"));
+        }
+        if idle_threshold.is_nan() || idle_threshold <= 0.0 {
+            return Err(pyo3::exceptions::PyValueError::new_err("idle_threshold must be positive"));
+        }
+        if screenshots.is_none() && sources.iter().any(|s| s == "screenshot") {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "the screenshot source needs screenshots=ScreenshotOptions(...)",
+            ));
+        }
+        let sample_ratios =
+            per_source(sample_ratio, &sources, "sample_ratio", |r| (0.0..=1.0).contains(&r))?;
+        let max_rates =
+            per_source(max_events_per_second, &sources, "max_events_per_second", |r| r > 0.0)?;
+        let queue = Arc::new(EventQueue::new(queue_size, Overflow::parse(overflow)?));
+        let compressor = match compression {
+            None => None,
+            Some("zstd") => Some(Compressor::new(compression_level, compression_dict)?),
+            Some(other) => {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "unsupported compression {other:?}; expected \"zstd\""
+                )))
+            }
+        };
+        let options = NdjsonOptions {
+            encryption,
+            compressor,
+            anonymizer: anonymize,
+        };
+        let sink = Sink::from_py(sink, Arc::clone(&queue), options)?;
+        Ok(Collector {
+            sources,
+            interval,
+            projects_dir: projects_dir.unwrap_or_else(|| home_path(".claude/projects")),
+            chat_db: chat_db.unwrap_or_else(|| home_path("Library/Messages/chat.db")),
+            idle_threshold,
+            resource_limits: resource_limits.unwrap_or_default(),
+            screenshots,
+            tcc_db,
+            tmux: tmux.unwrap_or_default(),
+            sample_ratios,
+            max_rates,
+            sink: Arc::new(sink),
+            queue,
+            shared: Arc::new(Shared {
+                // Stopped until start(), so `async for` on an idle collector ends at once.
+                stop: AtomicBool::new(true),
+                dropped: AtomicU64::new(0),
+                statuses: Mutex::new(BTreeMap::new()),
+            }),
+            threads: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// Start one thread per source. Raises RuntimeError if already running.
+    fn start(&self) -> PyResult<()> {
+        let mut threads = self.threads.lock().unwrap_or_else(|e| e.into_inner());
+        if !threads.is_empty() {
+            return Err(pyo3::exceptions::PyRuntimeError::new_err("collector already running"));
+        }
+        self.shared.stop.store(false, Ordering::Relaxed);
+        for source in self.sources.clone() {
+            let sampler = self.make_sampler(&source);
+            if let Ok(mut statuses) = self.shared.statuses.lock() {
+                statuses.entry(sampler.name()).or_default().running = true;
+            }
+            let (sink, shared) = (Arc::clone(&self.sink), Arc::clone(&self.shared));
+            let interval = self.interval;
+            let throttle = Throttle::new(
+                self.sample_ratios.get(&source).copied().unwrap_or(1.0),
+                self.max_rates.get(&source).copied(),
+            );
+            let handle = std::thread::Builder::new()
+                .name(format!("snoopy-{source}"))
+                .spawn(move || run_sampler(sampler, interval, throttle, sink, shared))
+                .map_err(pyo3::exceptions::PyRuntimeError::new_err)?;
+            threads.push(handle);
+        }
+        Ok(())
+    }
+
+    /// Signal every sampler to stop and wait for the threads to exit.
+    ///
+    /// Queued events are kept and can still be drained.
+    fn stop(&self, py: Python<'_>) {
+        self.close_until(py, None);
+    }
+
+    /// Signal every sampler to stop and wait up to timeout seconds (None: forever)
+    /// for the threads to exit. Returns whether they all did; threads still busy
+    /// keep the collector running until a later close() or stop() reaps them. Raises
+    /// ValueError for a NaN timeout.
+    #[pyo3(signature = (timeout=CLOSE_TIMEOUT))]
+    fn close(&self, py: Python<'_>, timeout: Option<f64>) -> PyResult<bool> {
+        Ok(self.close_until(py, deadline(timeout)?))
+    }
+
+    /// Start the collector unless already running.
+    fn __enter__(slf: PyRef<'_, Self>) -> PyResult<PyRef<'_, Self>> {
+        if !slf.is_running() {
+            slf.start()?;
+        }
+        Ok(slf)
+    }
+
+    /// close() with the default timeout. Exceptions propagate.
+    #[pyo3(signature = (*_args))]
+    fn __exit__(&self, py: Python<'_>, _args: &Bound<'_, PyTuple>) -> PyResult<bool> {
+        self.close(py, Some(CLOSE_TIMEOUT))?;
+        Ok(false)
+    }
+
+    /// Running state, queue depth, drop count and per-source counters.
+    fn status<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("running", self.is_running())?;
+        dict.set_item("sink", self.sink.kind())?;
+        dict.set_item("queued", self.queue.len())?;
+        dict.set_item("dropped", self.shared.dropped.load(Ordering::Relaxed))?;
+        let sources = PyDict::new(py);
+        let statuses = self.shared.statuses.lock().unwrap_or_else(|e| e.into_inner());
+        for source in &self.sources {
+            let s = PyDict::new(py);
+            let status = statuses.get(source.as_str());
+            s.set_item("running", status.is_some_and(|st| st.running))?;
+            s.set_item("events", status.map_or(0, |st| st.events))?;
+            s.set_item("errors", status.map_or(0, |st| st.errors))?;
+            s.set_item("last_error", status.and_then(|st| st.last_error.as_deref()))?;
+            s.set_item("last_run", status.and_then(|st| st.last_run))?;
+            s.set_item("sampled_out", status.map_or(0, |st| st.sampled_out))?;
+            s.set_item("rate_limited", status.map_or(0, |st| st.rate_limited))?;
+            sources.set_item(source, s)?;
+        }
+        dict.set_item("sources", sources)?;
+        Ok(dict)
+    }
+
+    /// The queue's depth, capacity, overflow policy, the events it has dropped and
+    /// the deepest it has been.
+    fn stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("depth", self.queue.len())?;
+        dict.set_item("capacity", self.queue.capacity)?;
+        dict.set_item("overflow", self.queue.overflow.name())?;
+        dict.set_item("dropped", self.queue.dropped.load(Ordering::Relaxed))?;
+        dict.set_item("peak_depth", self.queue.peak.load(Ordering::Relaxed))?;
+        Ok(dict)
+    }
+
+    /// Remove and return queued events, oldest first, up to max_events if given.
+    #[pyo3(signature = (max_events=None))]
+    fn drain<'py>(
+        &self,
+        py: Python<'py>,
+        max_events: Option<usize>,
+    ) -> PyResult<Bound<'py, PyList>> {
+        let batch = self.queue.take(max_events.unwrap_or(usize::MAX));
+        let out = PyList::empty(py);
+        for rec in batch {
+            out.append(record_to_dict(py, &rec)?)?;
+        }
+        Ok(out)
+    }
+
+    /// Await the next queued event, or None once timeout seconds pass or the
+    /// collector stops with nothing left queued.
+    ///
+    /// For asyncio code: the wait happens off the event loop. Raises RuntimeError
+    /// if events go to a sink instead of the queue.
+    #[pyo3(signature = (timeout=None))]
+    fn next_event<'py>(
+        &self,
+        py: Python<'py>,
+        timeout: Option<f64>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        self.next_future(py, timeout, false)
+    }
+
+    fn __aiter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    /// `async for event in collector` yields queued events until stop().
+    fn __anext__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        self.next_future(py, None, true)
+    }
+}
+
+impl Collector {
+    /// close() with a deadline already worked out.
+    fn close_until(&self, py: Python<'_>, deadline: Option<Instant>) -> bool {
+        self.shared.stop.store(true, Ordering::Relaxed);
+        let threads = std::mem::take(&mut *self.threads.lock().unwrap_or_else(|e| e.into_inner()));
+        let pending = py.detach(|| join_within(threads, deadline));
+        let done = pending.is_empty();
+        self.threads.lock().unwrap_or_else(|e| e.into_inner()).extend(pending);
+        done
+    }
+
+    fn next_future<'py>(
+        &self,
+        py: Python<'py>,
+        timeout: Option<f64>,
+        anext: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        if !matches!(*self.sink, Sink::Queue(_)) {
+            return Err(pyo3::exceptions::PyRuntimeError::new_err(
+                "events go to a sink; nothing to await",
+            ));
+        }
+        let (queue, shared) = (Arc::clone(&self.queue), Arc::clone(&self.shared));
+        let next = move |wait: Duration| {
+            Ok(match queue.take_one(wait) {
+                Some(rec) => Next::Item(rec),
+                None if shared.stop.load(Ordering::Relaxed) => Next::Closed,
+                None => Next::Empty,
+            })
+        };
+        aio::next_future(py, timeout, anext, next, |py, rec| {
+            Ok(record_to_dict(py, &rec)?.into_any().unbind())
+        })
+    }
+}
+
+fn record_to_dict<'py>(py: Python<'py>, rec: &Record) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    for (k, v) in rec {
+        dict.set_item(k, json_to_py(py, v)?)?;
+    }
+    Ok(dict)
+}
+
+impl Drop for Collector {
+    fn drop(&mut self) {
+        self.shared.stop.store(true, Ordering::Relaxed);
+    }
+}
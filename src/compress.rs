@@ -0,0 +1,130 @@
+use std::io::Read;
+use std::path::Path;
+
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyList};
+
+use crate::collector::json_to_py;
+use crate::crypto::{decrypt_frames, EncryptionKey};
+use crate::errors::{format_error, io_error};
+
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+const DEFAULT_DICT_SIZE: usize = 112_640;
+
+/// zstd settings for NDJSON exports; each batch becomes one independent frame.
+#[derive(Clone)]
+pub(crate) struct Compressor {
+    level: i32,
+    dictionary: Option<Vec<u8>>,
+}
+
+impl Compressor {
+    pub(crate) fn new(level: i32, dictionary: Option<Vec<u8>>) -> PyResult<Self> {
+        if !zstd::compression_level_range().contains(&level) {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "compression_level must be in {:?}",
+                zstd::compression_level_range()
+            )));
+        }
+        Ok(Compressor { level, dictionary })
+    }
+
+    pub(crate) fn compress(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+        let dict = self.dictionary.as_deref().unwrap_or(&[]);
+        zstd::bulk::Compressor::with_dictionary(self.level, dict)
+            .and_then(|mut c| c.compress(data))
+            .map_err(|e| format!("zstd: {e}"))
+    }
+}
+
+/// Undo Compressor::compress for any number of concatenated frames; data that
+/// isn't zstd is returned unchanged.
+pub(crate) fn maybe_decompress(data: Vec<u8>, dictionary: Option<&[u8]>) -> PyResult<Vec<u8>> {
+    if !data.starts_with(&ZSTD_MAGIC) {
+        return Ok(data);
+    }
+    let dict = dictionary.unwrap_or(&[]);
+    let mut out = Vec::new();
+    zstd::stream::read::Decoder::with_dictionary(data.as_slice(), dict)
+        .and_then(|mut d| d.read_to_end(&mut out))
+        .map_err(|e| format_error(None, format!("zstd: {e}")))?;
+    Ok(out)
+}
+
+/// Train a zstd dictionary from sample records (str or bytes).
+///
+/// Previews are short and repetitive, so a dictionary trained on a few thousand
+/// existing events compresses each batch far better than zstd alone. Pass the result
+/// as compression_dict to Collector and as dictionary to read_ndjson.
+#[pyfunction]
+#[pyo3(signature = (samples, dict_size=DEFAULT_DICT_SIZE))]
+pub fn train_zstd_dictionary<'py>(
+    py: Python<'py>,
+    samples: Vec<Bound<'py, PyAny>>,
+    dict_size: usize,
+) -> PyResult<Bound<'py, PyBytes>> {
+    let mut data: Vec<Vec<u8>> = Vec::with_capacity(samples.len());
+    for sample in &samples {
+        match sample.extract::<String>() {
+            Ok(s) => data.push(s.into_bytes()),
+            Err(_) => data.push(sample.extract::<Vec<u8>>()?),
+        }
+    }
+    let dict = py
+        .detach(|| zstd::dict::from_samples(&data, dict_size))
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("zstd: {e}")))?;
+    Ok(PyBytes::new(py, &dict))
+}
+
+/// Read an NDJSON export written by a Collector sink, as dicts.
+///
+/// Handles plain, zstd-compressed and encrypted files; pass the same encryption key
+/// and compression dictionary the sink was created with. An encrypted file cut short
+/// by an interrupted write is logged and read up to the cut.
+#[pyfunction]
+#[pyo3(signature = (path, encryption=None, dictionary=None))]
+pub fn read_ndjson<'py>(
+    py: Python<'py>,
+    path: &str,
+    encryption: Option<EncryptionKey>,
+    dictionary: Option<Vec<u8>>,
+) -> PyResult<Bound<'py, PyList>> {
+    let values = py.detach(|| -> PyResult<Vec<serde_json::Value>> {
+        let path = Path::new(path);
+        let blocks = match &encryption {
+            Some(key) => {
+                let (frames, complete) = decrypt_frames(path, key)?;
+                if !complete {
+                    tracing::warn!(
+                        "{}: cut short, as by an interrupted write; reading what came before",
+                        path.display()
+                    );
+                }
+                frames
+            }
+            None => {
+                let data = std::fs::read(path).map_err(|e| io_error(path, e))?;
+                vec![data]
+            }
+        };
+        let mut values = Vec::new();
+        for block in blocks {
+            let text = maybe_decompress(block, dictionary.as_deref())?;
+            for line in String::from_utf8_lossy(&text).lines() {
+                match serde_json::from_str(line) {
+                    Ok(v) => values.push(v),
+                    Err(_) if line.trim().is_empty() => {}
+                    Err(e) => {
+                        tracing::debug!(path = %path.display(), "skipping unparseable line: {e}")
+                    }
+                }
+            }
+        }
+        Ok(values)
+    })?;
+    let out = PyList::empty(py);
+    for v in &values {
+        out.append(json_to_py(py, v)?)?;
+    }
+    Ok(out)
+}
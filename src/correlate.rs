@@ -0,0 +1,166 @@
+use std::sync::OnceLock;
+
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use regex::Regex;
+
+use crate::privacy::host_of;
+use crate::sessions::event_timestamp;
+
+/// Programs a Bash command may run that reach the network, and the process names
+/// their connections show up under (lsof truncates these, so prefixes match).
+const NETWORK_PROGRAMS: &[(&str, &[&str])] = &[
+    ("curl", &["curl"]),
+    ("wget", &["wget"]),
+    ("pip", &["pip", "python", "uv"]),
+    ("pip3", &["pip", "python", "uv"]),
+    ("uv", &["uv", "python"]),
+    ("poetry", &["poetry", "python"]),
+    ("npm", &["npm", "node"]),
+    ("npx", &["npm", "node"]),
+    ("yarn", &["yarn", "node"]),
+    ("pnpm", &["pnpm", "node"]),
+    ("bun", &["bun"]),
+    ("cargo", &["cargo"]),
+    ("go", &["go"]),
+    ("gem", &["gem", "ruby"]),
+    ("bundle", &["bundle", "ruby"]),
+    ("brew", &["brew", "curl", "ruby"]),
+    ("git", &["git", "ssh"]),
+    ("gh", &["gh"]),
+    ("docker", &["docker", "com.docker"]),
+    ("ssh", &["ssh"]),
+    ("scp", &["scp", "ssh"]),
+    ("rsync", &["rsync", "ssh"]),
+];
+
+/// Tools that fetch from the network themselves, from Claude Code's own process.
+const WEB_TOOLS: [&str; 2] = ["WebFetch", "WebSearch"];
+const AGENT_PROCESSES: &[&str] = &["claude", "node"];
+
+fn url_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r#"https?://[^\s"'<>]+"#).unwrap())
+}
+
+/// A tool call that may open connections.
+struct ToolCall<'py> {
+    event: Bound<'py, PyDict>,
+    timestamp: f64,
+    processes: Vec<&'static str>,
+    hosts: Vec<String>,
+}
+
+/// The processes and hosts a tool_use event's connections would come from, or None
+/// if the call doesn't reach the network.
+fn network_call(tool: &str, preview: &str) -> Option<(Vec<&'static str>, Vec<String>)> {
+    let hosts: Vec<String> = url_re().find_iter(preview).map(|m| host_of(m.as_str())).collect();
+    if WEB_TOOLS.contains(&tool) {
+        return Some((AGENT_PROCESSES.to_vec(), hosts));
+    }
+    if tool != "Bash" {
+        return None;
+    }
+    let mut processes: Vec<&'static str> = Vec::new();
+    let words = preview.split(|c: char| c.is_whitespace() || ";&|()`$".contains(c));
+    for word in words {
+        let program = word.rsplit('/').next().unwrap_or(word);
+        if let Some((_, names)) = NETWORK_PROGRAMS.iter().find(|(p, _)| *p == program) {
+            for name in *names {
+                if !processes.contains(name) {
+                    processes.push(name);
+                }
+            }
+        }
+    }
+    (!processes.is_empty()).then_some((processes, hosts))
+}
+
+fn text(dict: &Bound<'_, PyDict>, key: &str) -> PyResult<String> {
+    match dict.get_item(key)? {
+        Some(v) if !v.is_none() => Ok(v.str()?.to_string()),
+        _ => Ok(String::new()),
+    }
+}
+
+/// How a connection is tied to a call, strongest first, if it is.
+fn match_kind(call: &ToolCall<'_>, process: &str, address: &str) -> Option<(u8, &'static str)> {
+    let process = process.to_lowercase();
+    let address = address.trim_matches(['[', ']']).to_lowercase();
+    if !address.is_empty() && call.hosts.contains(&address) {
+        Some((2, "host"))
+    } else if !process.is_empty()
+        && call.processes.iter().any(|p| process.starts_with(p) || p.starts_with(&process))
+    {
+        Some((1, "process"))
+    } else {
+        None
+    }
+}
+
+/// Attribute new outbound connections to the agent tool calls that likely made them.
+///
+/// network_events are connection dicts with timestamp, process_name and
+/// remote_address (as the collector's "network" source records them);
+/// transcript_events are parsed transcript events with epoch timestamps, of which
+/// the tool_use events for WebFetch and WebSearch, and for Bash commands that run a
+/// networked program (curl, wget, pip, npm, cargo, git, brew, ssh and the like),
+/// are considered. A connection is matched to a call made at most window_secs
+/// before it whose URL names its remote_address (match "host") or whose programs
+/// include its process (match "process"), preferring a host match and then the
+/// nearest call; connections from other processes are left unmatched. Returns one
+/// dict per matched connection, in the order given:
+/// connection (the network event), tool_event (the transcript event), delay
+/// (seconds from call to connection) and match.
+#[pyfunction]
+#[pyo3(signature = (network_events, transcript_events, window_secs=10.0))]
+pub fn correlate<'py>(
+    py: Python<'py>,
+    network_events: &Bound<'py, PyAny>,
+    transcript_events: &Bound<'py, PyAny>,
+    window_secs: f64,
+) -> PyResult<Bound<'py, PyList>> {
+    if window_secs.is_nan() || window_secs < 0.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("window_secs must be non-negative"));
+    }
+    let mut calls: Vec<ToolCall<'py>> = Vec::new();
+    for item in transcript_events.try_iter()? {
+        let item = item?;
+        let event = item.cast::<PyDict>()?;
+        let message_type = text(event, "message_type")?;
+        let Some(tool) = message_type.strip_prefix("tool_use:") else {
+            continue;
+        };
+        let Some((processes, hosts)) = network_call(tool, &text(event, "content_preview")?) else {
+            continue;
+        };
+        let timestamp = event_timestamp(&item)?;
+        calls.push(ToolCall { event: event.clone(), timestamp, processes, hosts });
+    }
+    calls.sort_by(|a, b| a.timestamp.total_cmp(&b.timestamp));
+
+    let out = PyList::empty(py);
+    for item in network_events.try_iter()? {
+        let item = item?;
+        let conn = item.cast::<PyDict>()?;
+        let ts = event_timestamp(&item)?;
+        let (process, address) = (text(conn, "process_name")?, text(conn, "remote_address")?);
+        let first = calls.partition_point(|c| c.timestamp < ts - window_secs);
+        let best = calls[first..]
+            .iter()
+            .take_while(|c| c.timestamp <= ts)
+            .filter_map(|c| Some((match_kind(c, &process, &address)?, c)))
+            // Stronger match, then the later (nearer) call.
+            .max_by(|((a, _), x), ((b, _), y)| a.cmp(b).then(x.timestamp.total_cmp(&y.timestamp)));
+        let Some(((_, kind), call)) = best else {
+            continue;
+        };
+        let dict = PyDict::new(py);
+        dict.set_item("connection", conn)?;
+        dict.set_item("tool_event", &call.event)?;
+        dict.set_item("delay", ts - call.timestamp)?;
+        dict.set_item("match", kind)?;
+        out.append(dict)?;
+    }
+    Ok(out)
+}
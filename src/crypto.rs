@@ -0,0 +1,369 @@
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use argon2::Argon2;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use pyo3::prelude::*;
+
+use crate::errors::{format_error, io_error};
+
+/// File header: magic, key derivation byte, 16-byte salt.
+const MAGIC: &[u8; 8] = b"SNOOPYE1";
+const HEADER_LEN: usize = 8 + 1 + 16;
+const KDF_RAW: u8 = 0;
+const KDF_ARGON2ID: u8 = 1;
+const NONCE_LEN: usize = 12;
+/// Each frame: nonce, sealed length (u32 LE), sealed bytes.
+const FRAME_PREFIX: usize = NONCE_LEN + 4;
+/// Plaintext bytes per frame when encrypting whole files.
+const FRAME_LEN: usize = 64 * 1024;
+
+fn decrypt_err() -> PyErr {
+    pyo3::exceptions::PyValueError::new_err("decryption failed: wrong key or corrupted data")
+}
+
+/// A passphrase or raw 256-bit key used to encrypt exports.
+///
+/// Files are ChaCha20-Poly1305 encrypted in frames bound to their file and position,
+/// ending in a terminator frame, so they can be appended to and a reordered or cut
+/// short file is rejected. Passphrases are stretched with Argon2id using a random
+/// salt stored in each file's header.
+#[pyclass(frozen, from_py_object)]
+#[derive(Clone)]
+pub struct EncryptionKey {
+    passphrase: Option<String>,
+    key: [u8; 32],
+}
+
+#[pymethods]
+impl EncryptionKey {
+    /// A key derived from a passphrase.
+    #[staticmethod]
+    fn from_passphrase(passphrase: String) -> PyResult<Self> {
+        if passphrase.is_empty() {
+            return Err(pyo3::exceptions::PyValueError::new_err("passphrase must not be empty"));
+        }
+        Ok(EncryptionKey {
+            passphrase: Some(passphrase),
+            key: [0; 32],
+        })
+    }
+
+    /// A key read from a file holding 32 raw bytes or 64 hex digits.
+    #[staticmethod]
+    fn from_key_file(path: &str) -> PyResult<Self> {
+        let data = std::fs::read(path).map_err(|e| io_error(Path::new(path), e))?;
+        let text = String::from_utf8_lossy(&data);
+        let hex = text.trim();
+        let bytes: Vec<u8> = if data.len() == 32 {
+            data.clone()
+        } else if hex.len() == 64 && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            (0..32)
+                .map(|i| u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap_or(0))
+                .collect()
+        } else {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "key file must hold 32 raw bytes or 64 hex digits",
+            ));
+        };
+        let mut key = [0; 32];
+        key.copy_from_slice(&bytes);
+        Ok(EncryptionKey {
+            passphrase: None,
+            key,
+        })
+    }
+
+    /// Write a new random key to path (as hex, mode 0600) and return it.
+    #[staticmethod]
+    fn generate_key_file(path: &str) -> PyResult<Self> {
+        use std::os::unix::fs::OpenOptionsExt;
+
+        let mut key = [0u8; 32];
+        OsRng.fill_bytes(&mut key);
+        let hex: String = key.iter().map(|b| format!("{b:02x}")).collect();
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(0o600)
+            .open(path)
+            .map_err(|e| io_error(Path::new(path), e))?;
+        file.write_all(hex.as_bytes()).map_err(|e| io_error(Path::new(path), e))?;
+        Ok(EncryptionKey {
+            passphrase: None,
+            key,
+        })
+    }
+}
+
+impl EncryptionKey {
+    fn kdf(&self) -> u8 {
+        if self.passphrase.is_some() {
+            KDF_ARGON2ID
+        } else {
+            KDF_RAW
+        }
+    }
+
+    fn cipher(&self, kdf: u8, salt: &[u8]) -> PyResult<ChaCha20Poly1305> {
+        let key = match (&self.passphrase, kdf) {
+            (Some(passphrase), KDF_ARGON2ID) => {
+                let mut key = [0u8; 32];
+                Argon2::default()
+                    .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+                    .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+                key
+            }
+            (None, KDF_RAW) => self.key,
+            _ => {
+                return Err(pyo3::exceptions::PyValueError::new_err(
+                    "file was encrypted with a different kind of key",
+                ))
+            }
+        };
+        Ok(ChaCha20Poly1305::new(Key::from_slice(&key)))
+    }
+
+    /// A header with a fresh salt, and the stream it starts.
+    fn new_stream(&self) -> PyResult<Stream> {
+        let mut salt = [0u8; 16];
+        OsRng.fill_bytes(&mut salt);
+        let mut header = [0u8; HEADER_LEN];
+        header[..8].copy_from_slice(MAGIC);
+        header[8] = self.kdf();
+        header[9..].copy_from_slice(&salt);
+        Ok(Stream { cipher: self.cipher(self.kdf(), &salt)?, header })
+    }
+}
+
+/// The frames of one file, in the STREAM construction: each frame's associated data
+/// is the file header, the frame's index and whether it is the last, so frames can't
+/// be reordered, moved between files or cut off the end without decryption failing.
+/// The last frame is an empty terminator, which appending moves along.
+struct Stream {
+    cipher: ChaCha20Poly1305,
+    header: [u8; HEADER_LEN],
+}
+
+impl Stream {
+    fn parse(data: &[u8], key: &EncryptionKey) -> PyResult<Self> {
+        if data.len() < HEADER_LEN || &data[..8] != MAGIC {
+            return Err(format_error(None, "not a snoopy encrypted file"));
+        }
+        let mut header = [0u8; HEADER_LEN];
+        header.copy_from_slice(&data[..HEADER_LEN]);
+        Ok(Stream { cipher: key.cipher(data[8], &data[9..HEADER_LEN])?, header })
+    }
+
+    fn aad(&self, index: u64, last: bool) -> Vec<u8> {
+        let mut aad = self.header.to_vec();
+        aad.extend_from_slice(&index.to_le_bytes());
+        aad.push(last as u8);
+        aad
+    }
+
+    fn seal(&self, index: u64, last: bool, plaintext: &[u8]) -> PyResult<Vec<u8>> {
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let payload = Payload { msg: plaintext, aad: &self.aad(index, last) };
+        let sealed = self
+            .cipher
+            .encrypt(&nonce, payload)
+            .map_err(|_| pyo3::exceptions::PyValueError::new_err("encryption failed"))?;
+        let mut frame = Vec::with_capacity(FRAME_PREFIX + sealed.len());
+        frame.extend_from_slice(&nonce);
+        frame.extend_from_slice(&(sealed.len() as u32).to_le_bytes());
+        frame.extend_from_slice(&sealed);
+        Ok(frame)
+    }
+
+    /// Decrypt a frame: its nonce, then its sealed bytes.
+    fn open(&self, index: u64, last: bool, nonce: &[u8], sealed: &[u8]) -> PyResult<Vec<u8>> {
+        let payload = Payload { msg: sealed, aad: &self.aad(index, last) };
+        self.cipher.decrypt(Nonce::from_slice(nonce), payload).map_err(|_| decrypt_err())
+    }
+}
+
+/// The sealed length a frame's prefix gives.
+fn sealed_len(prefix: &[u8]) -> usize {
+    u32::from_le_bytes(prefix[NONCE_LEN..FRAME_PREFIX].try_into().unwrap()) as usize
+}
+
+/// Decrypt every frame of an encrypted file, in order, and whether it ended in its
+/// terminator. A file cut off partway through a frame or before its terminator, as an
+/// interrupted append leaves it, gives the frames before the cut; a frame that fails
+/// to decrypt is an error.
+pub(crate) fn decrypt_frames(path: &Path, key: &EncryptionKey) -> PyResult<(Vec<Vec<u8>>, bool)> {
+    let mut data = Vec::new();
+    File::open(path)
+        .and_then(|mut f| f.read_to_end(&mut data))
+        .map_err(|e| io_error(path, e))?;
+    let stream = Stream::parse(&data, key)?;
+    let mut frames = Vec::new();
+    let mut pos = HEADER_LEN;
+    for index in 0.. {
+        if data.len() - pos < FRAME_PREFIX {
+            break;
+        }
+        let start = pos + FRAME_PREFIX;
+        let end = start + sealed_len(&data[pos..start]);
+        if end > data.len() {
+            break;
+        }
+        let (nonce, sealed) = (&data[pos..pos + NONCE_LEN], &data[start..end]);
+        if end == data.len() && stream.open(index, true, nonce, sealed).is_ok() {
+            return Ok((frames, true));
+        }
+        frames.push(stream.open(index, false, nonce, sealed)?);
+        pos = end;
+    }
+    Ok((frames, false))
+}
+
+/// Appends encrypted frames to a file, writing the header if the file is new.
+pub(crate) struct EncryptedWriter {
+    file: File,
+    stream: Stream,
+    /// Index of the next frame, and the offset of the terminator it replaces.
+    index: u64,
+    end: u64,
+}
+
+impl EncryptedWriter {
+    pub(crate) fn open(path: &Path, key: &EncryptionKey) -> PyResult<Self> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .read(true)
+            .write(true)
+            .open(path)
+            .map_err(|e| io_error(path, e))?;
+        let len = file.metadata().map_err(|e| io_error(path, e))?.len();
+        if len == 0 {
+            let stream = key.new_stream()?;
+            let mut start = stream.header.to_vec();
+            start.extend_from_slice(&stream.seal(0, true, &[])?);
+            file.write_all(&start).and_then(|_| file.flush()).map_err(|e| io_error(path, e))?;
+            return Ok(EncryptedWriter { file, stream, index: 0, end: HEADER_LEN as u64 });
+        }
+        let mut header = [0u8; HEADER_LEN];
+        file.read_exact(&mut header).map_err(|e| io_error(path, e))?;
+        let stream = Stream::parse(&header, key)?;
+        // Walk the frame prefixes to the terminator, and check that it is one.
+        let mut pos = HEADER_LEN as u64;
+        let mut prefix = [0u8; FRAME_PREFIX];
+        for index in 0.. {
+            let read = file.seek(SeekFrom::Start(pos)).and_then(|_| file.read_exact(&mut prefix));
+            let end = pos + (FRAME_PREFIX + sealed_len(&prefix)) as u64;
+            if read.is_err() || end > len {
+                // An append was interrupted partway through this frame: drop it.
+                tracing::warn!("{}: dropping an incomplete frame at byte {pos}", path.display());
+                return Self::resume(path, file, stream, index, pos);
+            }
+            if end == len {
+                let mut sealed = vec![0u8; sealed_len(&prefix)];
+                file.read_exact(&mut sealed).map_err(|e| io_error(path, e))?;
+                if stream.open(index, true, &prefix[..NONCE_LEN], &sealed).is_ok() {
+                    return Ok(EncryptedWriter { file, stream, index, end: pos });
+                }
+                // An append was interrupted before the terminator after this frame.
+                stream.open(index, false, &prefix[..NONCE_LEN], &sealed)?;
+                tracing::warn!("{}: restoring the terminator after byte {len}", path.display());
+                return Self::resume(path, file, stream, index + 1, len);
+            }
+            pos = end;
+        }
+        unreachable!()
+    }
+
+    /// Cut the file back to end and write the terminator for frame index there.
+    fn resume(path: &Path, mut file: File, stream: Stream, index: u64, end: u64) -> PyResult<Self> {
+        let terminator = stream.seal(index, true, &[])?;
+        file.set_len(end)
+            .and_then(|_| file.seek(SeekFrom::Start(end)))
+            .and_then(|_| file.write_all(&terminator))
+            .and_then(|_| file.flush())
+            .map_err(|e| io_error(path, e))?;
+        Ok(EncryptedWriter { file, stream, index, end })
+    }
+
+    /// Write plaintext as the next frame, followed by a new terminator.
+    pub(crate) fn write_frame(&mut self, plaintext: &[u8]) -> Result<(), String> {
+        let seal = |index, last, plaintext| {
+            self.stream.seal(index, last, plaintext).map_err(|e| e.to_string())
+        };
+        let mut frames = seal(self.index, false, plaintext)?;
+        let frame_len = frames.len() as u64;
+        frames.extend_from_slice(&seal(self.index + 1, true, &[])?);
+        self.file
+            .seek(SeekFrom::Start(self.end))
+            .and_then(|_| self.file.write_all(&frames))
+            .and_then(|_| self.file.flush())
+            .map_err(|e| e.to_string())?;
+        self.index += 1;
+        self.end += frame_len;
+        Ok(())
+    }
+}
+
+/// Encrypt src into dest, replacing dest.
+///
+/// Writes a temporary file beside dest and renames it over dest, so dest may be src.
+#[pyfunction]
+pub fn encrypt_file(py: Python<'_>, src: &str, dest: &str, key: EncryptionKey) -> PyResult<()> {
+    py.detach(|| {
+        let (src, dest) = (Path::new(src), Path::new(dest));
+        let mut input = File::open(src).map_err(|e| io_error(src, e))?;
+        let name = dest.file_name().and_then(|n| n.to_str()).unwrap_or("encrypted");
+        let tmp = dest.with_file_name(format!(".{name}.{}.tmp", std::process::id()));
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&tmp)
+            .map_err(|e| io_error(&tmp, e))?;
+        let written = (|| {
+            let mut out = BufWriter::new(file);
+            let stream = key.new_stream()?;
+            out.write_all(&stream.header).map_err(|e| io_error(dest, e))?;
+            let mut buf = vec![0u8; FRAME_LEN];
+            for index in 0.. {
+                let n = input.read(&mut buf).map_err(|e| io_error(src, e))?;
+                let frame = stream.seal(index, n == 0, &buf[..n])?;
+                out.write_all(&frame).map_err(|e| io_error(dest, e))?;
+                if n == 0 {
+                    break;
+                }
+            }
+            out.flush().map_err(|e| io_error(dest, e))?;
+            std::fs::rename(&tmp, dest).map_err(|e| io_error(dest, e))
+        })();
+        if written.is_err() {
+            let _ = std::fs::remove_file(&tmp);
+        }
+        written
+    })
+}
+
+/// Decrypt src into dest. Raises ValueError if the key is wrong or src was tampered
+/// with or cut short; when it was cut short, as by an interrupted append, dest still
+/// gets everything before the cut.
+#[pyfunction]
+pub fn decrypt_file(py: Python<'_>, src: &str, dest: &str, key: EncryptionKey) -> PyResult<()> {
+    py.detach(|| {
+        let (frames, complete) = decrypt_frames(Path::new(src), &key)?;
+        let dest = Path::new(dest);
+        let mut out = BufWriter::new(File::create(dest).map_err(|e| io_error(dest, e))?);
+        for frame in &frames {
+            out.write_all(frame).map_err(|e| io_error(dest, e))?;
+        }
+        out.flush().map_err(|e| io_error(dest, e))?;
+        if !complete {
+            let cause = format!("cut short after {} frames", frames.len());
+            return Err(format_error(Some(Path::new(src)), cause));
+        }
+        Ok(())
+    })
+}
@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList, PyString};
+
+use crate::buffer;
+
+/// BLAKE3 of data as 64 lowercase hex digits.
+pub(crate) fn content_hash_hex(data: &[u8]) -> String {
+    blake3::hash(data).to_hex().to_string()
+}
+
+/// The BLAKE3 hash of a string (as UTF-8) or any buffer, as ParserConfig's
+/// content_hash reports it.
+#[pyfunction]
+pub fn content_hash(data: &Bound<'_, PyAny>) -> PyResult<String> {
+    if let Ok(text) = data.cast::<PyString>() {
+        return Ok(content_hash_hex(text.to_str()?.as_bytes()));
+    }
+    buffer::with_bytes(data, content_hash_hex)
+}
+
+/// A string field of an event, or "" if it has none.
+fn field(event: &Bound<'_, PyDict>, name: &str) -> PyResult<String> {
+    match event.get_item(name)? {
+        Some(v) if !v.is_none() => v.extract(),
+        _ => Ok(String::new()),
+    }
+}
+
+/// Collapse exact repeats in a list of event dicts, such as a tool's identical output
+/// on every retry, keeping the first of each.
+///
+/// Events repeat when they share session_id, message_type and content_hash; an event
+/// without content_hash (parsed without ParserConfig(content_hash=True), or from
+/// another source) is hashed by its content_preview instead, and one with neither is
+/// never collapsed. Returns copies of the kept events in their original order, each
+/// with duplicates, the number of later repeats dropped in its favour.
+#[pyfunction]
+pub fn dedup_events<'py>(
+    py: Python<'py>,
+    events: &Bound<'py, PyAny>,
+) -> PyResult<Bound<'py, PyList>> {
+    let mut kept: Vec<Bound<'py, PyDict>> = Vec::new();
+    let mut counts: Vec<u64> = Vec::new();
+    let mut first: HashMap<(String, String, String), usize> = HashMap::new();
+    for item in events.try_iter()? {
+        let item = item?;
+        let event = item.cast::<PyDict>()?;
+        let hash = match event.get_item("content_hash")? {
+            Some(h) if !h.is_none() => Some(h.extract::<String>()?),
+            _ => match event.get_item("content_preview")? {
+                Some(p) if !p.is_none() => {
+                    Some(content_hash_hex(p.extract::<String>()?.as_bytes()))
+                }
+                _ => None,
+            },
+        };
+        if let Some(hash) = hash {
+            let key = (field(event, "session_id")?, field(event, "message_type")?, hash);
+            if let Some(&i) = first.get(&key) {
+                counts[i] += 1;
+                continue;
+            }
+            first.insert(key, kept.len());
+        }
+        kept.push(event.copy()?);
+        counts.push(0);
+    }
+    let out = PyList::empty(py);
+    for (event, count) in kept.into_iter().zip(counts) {
+        event.set_item("duplicates", count)?;
+        out.append(event)?;
+    }
+    Ok(out)
+}
@@ -0,0 +1,287 @@
+use std::collections::{BTreeMap, HashMap};
+use std::process::Command;
+use std::sync::OnceLock;
+
+use chrono::{Local, NaiveDateTime, TimeZone};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use regex::Regex;
+use serde_json::{json, Value};
+
+use crate::collector::{record, records_to_py, Record};
+use crate::timeutil::parse_iso_ts;
+
+const PREDICATE: &str = r#"process == "mDNSResponder""#;
+
+/// One cache record from an mDNSResponder state dump (`killall -INFO mDNSResponder`):
+/// "  39      1765 en0      A     4 gateway.example.net. Addr 17.248.1.2", with the
+/// remaining TTL and interface before the type.
+fn mdns_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(concat!(
+            r"(?:(\d+)\s+(\S+)\s+(?:[-+*U]{1,3}\s+)?)?\b(A|AAAA|CNAME)\s+\d+\s+",
+            r"(\S+?)\.?\s+(?:Addr|AAAA|CNAME)\s+(\S+?)\.?\s*$",
+        ))
+        .unwrap()
+    })
+}
+
+/// A `dscacheutil -cachedump -entries Host` row: "Host   03/01/24 10:15:02
+/// 03/01/24 10:14:02   3   5   60", best-before and last-access times then hits,
+/// refs and TTL. Its "Key: h_name:www.example.com ..." lines follow.
+fn cachedump_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(concat!(
+            r"^\s*Host\s+(\d\d/\d\d/\d\d \d\d:\d\d:\d\d)\s+(\d\d/\d\d/\d\d \d\d:\d\d:\d\d)",
+            r"\s+(\d+)\s+\d+\s+(\d+)",
+        ))
+        .unwrap()
+    })
+}
+
+fn local_time(text: &str) -> Option<f64> {
+    let at = NaiveDateTime::parse_from_str(text, "%m/%d/%y %H:%M:%S").ok()?;
+    Some(Local.from_local_datetime(&at).earliest()?.timestamp() as f64)
+}
+
+/// (timestamp, message) for each line: NDJSON `log show` entries contribute their
+/// message's lines stamped with the entry's time, anything else is taken as is.
+fn lines(text: &str) -> Vec<(Option<f64>, String)> {
+    let mut out = Vec::new();
+    for line in text.lines() {
+        let entry = line
+            .trim_start()
+            .starts_with('{')
+            .then(|| serde_json::from_str::<Value>(line).ok())
+            .flatten();
+        match entry {
+            Some(entry) => {
+                let ts = entry["timestamp"].as_str().and_then(parse_iso_ts);
+                let message = entry["eventMessage"].as_str().unwrap_or_default();
+                out.extend(message.lines().map(|l| (ts, l.to_string())));
+            }
+            None => out.push((None, line.to_string())),
+        }
+    }
+    out
+}
+
+/// A name-to-address or alias-to-name mapping found in the input.
+struct Entry {
+    timestamp: Option<f64>,
+    hostname: String,
+    value: Option<String>,
+    record_type: Option<&'static str>,
+    ttl: Option<i64>,
+    interface: Option<String>,
+    hits: Option<i64>,
+}
+
+fn parse_entries(text: &str) -> Vec<Entry> {
+    let mut entries = Vec::new();
+    // dscacheutil -q host prints "name: ..." then its addresses, one block per host.
+    let mut name: Option<String> = None;
+    // The cachedump row the following Key lines belong to.
+    let mut row: Option<(Option<f64>, i64, i64)> = None;
+    for (ts, line) in lines(text) {
+        let trimmed = line.trim();
+        if let Some(caps) = mdns_re().captures(&line) {
+            let record_type = match &caps[3] {
+                "A" => "A",
+                "AAAA" => "AAAA",
+                _ => "CNAME",
+            };
+            entries.push(Entry {
+                timestamp: ts,
+                hostname: caps[4].to_lowercase(),
+                value: Some(caps[5].to_lowercase()),
+                record_type: Some(record_type),
+                ttl: caps.get(1).and_then(|m| m.as_str().parse().ok()),
+                interface: caps.get(2).map(|m| m.as_str().to_string()),
+                hits: None,
+            });
+        } else if let Some(caps) = cachedump_re().captures(&line) {
+            row = Some((
+                local_time(&caps[2]),
+                caps[3].parse().unwrap_or(0),
+                caps[4].parse().unwrap_or(0),
+            ));
+        } else if let Some(rest) = trimmed.strip_prefix("Key: h_name:") {
+            let hostname = rest.split_whitespace().next().unwrap_or_default().to_lowercase();
+            let (timestamp, hits, ttl) = row.unwrap_or((None, 0, 0));
+            // Each host is listed once per query kind; keep the first.
+            if !entries.iter().any(|e: &Entry| e.value.is_none() && e.hostname == hostname) {
+                entries.push(Entry {
+                    timestamp,
+                    hostname,
+                    value: None,
+                    record_type: None,
+                    ttl: Some(ttl),
+                    interface: None,
+                    hits: Some(hits),
+                });
+            }
+        } else if let Some((key, value)) = trimmed.split_once(": ") {
+            let value = value.trim().to_lowercase();
+            let record_type = match key {
+                "name" => {
+                    name = Some(value);
+                    continue;
+                }
+                "ip_address" => "A",
+                "ipv6_address" => "AAAA",
+                "alias" => "CNAME",
+                _ => continue,
+            };
+            let Some(host) = &name else { continue };
+            // An alias line names another name for this host, so it points here.
+            let (hostname, value) =
+                if record_type == "CNAME" { (value, host.clone()) } else { (host.clone(), value) };
+            entries.push(Entry {
+                timestamp: ts,
+                hostname,
+                value: Some(value),
+                record_type: Some(record_type),
+                ttl: None,
+                interface: None,
+                hits: None,
+            });
+        } else if trimmed.is_empty() {
+            name = None;
+        }
+    }
+    entries
+}
+
+/// Every alias whose CNAME chain ends at each name.
+fn aliases(entries: &[Entry]) -> HashMap<String, Vec<String>> {
+    let cnames: HashMap<&str, &str> = entries
+        .iter()
+        .filter(|e| e.record_type == Some("CNAME"))
+        .filter_map(|e| Some((e.hostname.as_str(), e.value.as_deref()?)))
+        .collect();
+    let mut aliases: HashMap<String, Vec<String>> = HashMap::new();
+    for alias in cnames.keys() {
+        let mut target = *alias;
+        // Bounded, in case a dump catches a CNAME loop.
+        for _ in 0..16 {
+            let Some(next) = cnames.get(target) else { break };
+            target = next;
+        }
+        if target != *alias {
+            aliases.entry(target.to_string()).or_default().push(alias.to_string());
+        }
+    }
+    for list in aliases.values_mut() {
+        list.sort();
+    }
+    aliases
+}
+
+fn parse_cache(text: &str) -> Vec<Record> {
+    let entries = parse_entries(text);
+    let aliases = aliases(&entries);
+    entries
+        .iter()
+        .map(|e| {
+            let address = e.value.as_ref().filter(|_| e.record_type != Some("CNAME"));
+            let cname = e.value.as_ref().filter(|_| e.record_type == Some("CNAME"));
+            record(
+                "dns",
+                json!({
+                    "timestamp": e.timestamp,
+                    "hostname": e.hostname,
+                    "address": address,
+                    "cname": cname,
+                    "record_type": e.record_type,
+                    "aliases": aliases.get(&e.hostname).cloned().unwrap_or_default(),
+                    "ttl": e.ttl,
+                    "interface": e.interface,
+                    "hits": e.hits,
+                }),
+            )
+        })
+        .collect()
+}
+
+/// Address → the names it was looked up by, aliases (what was actually asked for)
+/// before canonical names.
+fn hostnames(text: &str) -> BTreeMap<String, Vec<String>> {
+    let entries = parse_entries(text);
+    let aliases = aliases(&entries);
+    let mut map: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for e in &entries {
+        let (Some(address), Some("A" | "AAAA")) = (&e.value, e.record_type) else { continue };
+        let names = map.entry(address.clone()).or_default();
+        let found = aliases.get(&e.hostname).into_iter().flatten().chain([&e.hostname]);
+        for name in found {
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+    }
+    map
+}
+
+/// Parse DNS cache contents into records, in input order.
+///
+/// text is an mDNSResponder cache dump (the unified log after `sudo killall -INFO
+/// mDNSResponder`, as --style ndjson or plain `log show` output), `dscacheutil -q
+/// host` output, or `dscacheutil -cachedump -entries Host` output. Records are
+/// {source: "dns", timestamp, hostname, address, cname, record_type ("A", "AAAA",
+/// "CNAME"), aliases, ttl, interface, hits}. address is set for A and AAAA records
+/// and cname for CNAMEs; aliases lists the names whose CNAME chain ends at
+/// hostname. cachedump rows only name hosts, so their address and record_type are
+/// None, timestamp is the last access and hits the cache hit count. timestamp is
+/// otherwise the log entry's time, or None.
+#[pyfunction]
+pub fn parse_dns_cache<'py>(py: Python<'py>, text: &str) -> PyResult<Bound<'py, PyList>> {
+    let records = py.detach(|| parse_cache(text));
+    records_to_py(py, records)
+}
+
+/// Map each address in DNS cache contents (anything parse_dns_cache reads) to the
+/// hostnames it was resolved from, e.g. {"93.184.216.34": ["example.com",
+/// "edge.example.net"]}.
+///
+/// Names reached through CNAMEs come first, since they are what was actually looked
+/// up; use it to name addresses in network events when reverse DNS gives nothing
+/// useful.
+#[pyfunction]
+pub fn dns_hostnames<'py>(py: Python<'py>, text: &str) -> PyResult<Bound<'py, PyDict>> {
+    let map = py.detach(|| hostnames(text));
+    let dict = PyDict::new(py);
+    for (address, names) in map {
+        dict.set_item(address, names)?;
+    }
+    Ok(dict)
+}
+
+/// Read mDNSResponder cache dumps from the unified log and parse them with
+/// parse_dns_cache.
+///
+/// mDNSResponder writes its cache to the log when sent SIGINFO (`sudo killall -INFO
+/// mDNSResponder`); last is the `log show --last` window to search. Raises
+/// RuntimeError if `log show` fails and IOError if it can't be run.
+#[pyfunction]
+#[pyo3(signature = (last="10m"))]
+pub fn dns_cache<'py>(py: Python<'py>, last: &str) -> PyResult<Bound<'py, PyList>> {
+    let mut command = Command::new("log");
+    command.args(["show", "--info", "--style", "ndjson", "--last", last, "--predicate", PREDICATE]);
+    let records = py.detach(|| -> PyResult<Vec<Record>> {
+        let output = command
+            .output()
+            .map_err(|e| pyo3::exceptions::PyIOError::new_err(format!("log: {e}")))?;
+        if !output.status.success() {
+            return Err(pyo3::exceptions::PyRuntimeError::new_err(format!(
+                "log show failed ({}): {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(parse_cache(&String::from_utf8_lossy(&output.stdout)))
+    })?;
+    records_to_py(py, records)
+}
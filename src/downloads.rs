@@ -0,0 +1,281 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList, PyTuple};
+use rusqlite::{Connection, OptionalExtension};
+use serde_json::json;
+
+use crate::collector::{home_path, json_to_py, records_to_py, Record};
+use crate::history::{download, quarantine_kind, with_snapshot, QUARANTINE_DB};
+use crate::shutdown::{deadline, CLOSE_TIMEOUT};
+use crate::watcher::Watcher;
+
+const QUARANTINE_XATTR: &str = "com.apple.quarantine";
+const WHERE_FROMS_XATTR: &str = "com.apple.metadata:kMDItemWhereFroms";
+/// Set by wget, curl --xattr and Chromium on Linux (freedesktop.org convention).
+const XDG_ORIGIN_XATTR: &str = "user.xdg.origin.url";
+const XDG_REFERRER_XATTR: &str = "user.xdg.referrer.url";
+
+/// Files browsers write while a download is still in progress.
+const PARTIAL_SUFFIXES: [&str; 5] = [".crdownload", ".download", ".part", ".partial", ".tmp"];
+
+fn xattr_text(path: &Path, name: &str) -> Option<String> {
+    let value = xattr::get(path, name).ok()??;
+    Some(String::from_utf8_lossy(&value).trim_end_matches('\0').to_string())
+}
+
+/// "0083;65e1a2b3;Safari;8F2A7C1E-..." is flags, hex epoch seconds, the downloading
+/// app and the event's identifier in the quarantine database.
+fn parse_quarantine(value: &str) -> (Option<f64>, Option<String>, Option<String>) {
+    let fields: Vec<&str> = value.split(';').collect();
+    let time = fields.get(1).and_then(|t| i64::from_str_radix(t, 16).ok()).map(|t| t as f64);
+    let text = |i: usize| fields.get(i).map(|f| f.to_string()).filter(|f| !f.is_empty());
+    (time, text(2), text(3))
+}
+
+/// kMDItemWhereFroms is a binary plist array: the file's URL, then the page it
+/// was linked from.
+fn where_froms(path: &Path) -> Vec<String> {
+    let Ok(Some(value)) = xattr::get(path, WHERE_FROMS_XATTR) else { return Vec::new() };
+    let Ok(plist) = plist::Value::from_reader(std::io::Cursor::new(value)) else {
+        return Vec::new();
+    };
+    plist
+        .as_array()
+        .map(|a| a.iter().filter_map(|v| v.as_string().map(str::to_string)).collect())
+        .unwrap_or_default()
+}
+
+/// The quarantine database's row for one event identifier.
+fn quarantine_row(conn: &Connection, event_id: &str) -> rusqlite::Result<Option<Record>> {
+    conn.query_row(
+        "SELECT LSQuarantineAgentName, LSQuarantineDataURLString, LSQuarantineOriginURLString,
+                LSQuarantineAgentBundleIdentifier, LSQuarantineTypeNumber, LSQuarantineSenderName
+         FROM LSQuarantineEvent
+         WHERE LSQuarantineEventIdentifier = ?",
+        [event_id],
+        |row| {
+            let fields = json!({
+                "app": row.get::<_, Option<String>>(0)?,
+                "url": row.get::<_, Option<String>>(1)?,
+                "origin": row.get::<_, Option<String>>(2)?,
+                "bundle_id": row.get::<_, Option<String>>(3)?,
+                "kind": quarantine_kind(row.get(4)?),
+                "sender": row.get::<_, Option<String>>(5)?.filter(|s| !s.is_empty()),
+            });
+            Ok(origin_fields(fields))
+        },
+    )
+    .optional()
+}
+
+/// Where a downloaded file came from, from its extended attributes and, on macOS,
+/// the quarantine database. None if the file carries no download metadata.
+fn origin(path: &Path, quarantine_db: &Path, timestamp: Option<f64>) -> Option<Record> {
+    let quarantine = xattr_text(path, QUARANTINE_XATTR);
+    let (quarantined, mut app, event_id) =
+        quarantine.as_deref().map(parse_quarantine).unwrap_or_default();
+    let froms = where_froms(path);
+    let mut url = froms.first().cloned().or_else(|| xattr_text(path, XDG_ORIGIN_XATTR));
+    let mut origin = froms.get(1).cloned().or_else(|| xattr_text(path, XDG_REFERRER_XATTR));
+    if quarantine.is_none() && url.is_none() {
+        return None;
+    }
+    let row = event_id.as_deref().filter(|_| quarantine_db.is_file()).and_then(|id| {
+        with_snapshot(quarantine_db, |conn| quarantine_row(conn, id)).ok().flatten()
+    });
+    let text = |key: &str| row.as_ref().and_then(|r| r[key].as_str()).map(str::to_string);
+    url = text("url").or(url);
+    origin = text("origin").or(origin);
+    app = text("app").or(app);
+    let size = std::fs::metadata(path).ok().map(|m| m.len() as i64);
+    let timestamp = timestamp.or(quarantined).unwrap_or_else(crate::collector::now);
+    let agent = app.as_deref().unwrap_or_default().to_lowercase();
+    let path = path.to_string_lossy().into_owned();
+    let mut rec = download(&agent, timestamp, Some(path), url, origin, size);
+    if let Some(app) = &app {
+        let summary = rec["summary"].as_str().unwrap_or_default().to_string();
+        rec.insert("summary".to_string(), json!(format!("{app} {summary}")));
+    }
+    rec.extend(origin_fields(json!({
+        "app": app,
+        "bundle_id": text("bundle_id"),
+        "event_id": event_id,
+        "kind": text("kind").unwrap_or("download".to_string()),
+        "sender": text("sender"),
+        "quarantined": quarantine.is_some(),
+    })));
+    Some(rec)
+}
+
+fn origin_fields(fields: serde_json::Value) -> Record {
+    fields.as_object().cloned().unwrap_or_default()
+}
+
+/// A file that landed without download metadata.
+fn local_file(path: &Path, timestamp: f64) -> Record {
+    let size = std::fs::metadata(path).ok().map(|m| m.len() as i64);
+    let mut rec =
+        download("", timestamp, Some(path.to_string_lossy().into_owned()), None, None, size);
+    rec.extend(origin_fields(json!({
+        "app": null,
+        "bundle_id": null,
+        "event_id": null,
+        "kind": "local",
+        "sender": null,
+        "quarantined": false,
+    })));
+    rec
+}
+
+fn is_partial(path: &Path) -> bool {
+    let name = path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+    name.starts_with('.') || PARTIAL_SUFFIXES.iter().any(|s| name.ends_with(s))
+}
+
+/// Where a downloaded file came from: the fields of browser_downloads (browser is
+/// the downloading app's name, lowercased) plus app, bundle_id, event_id, kind (as
+/// in quarantine_events), sender and quarantined.
+///
+/// Read from the file's com.apple.quarantine and kMDItemWhereFroms extended
+/// attributes, completed from the LaunchServices quarantine database
+/// (quarantine_db, by default the current user's) when the event is there. On
+/// Linux the user.xdg.origin.url and user.xdg.referrer.url attributes that wget,
+/// curl --xattr and Chromium set are read instead. Returns None if the file has no
+/// download metadata.
+#[pyfunction]
+#[pyo3(signature = (path, quarantine_db=None))]
+pub fn download_origin<'py>(
+    py: Python<'py>,
+    path: PathBuf,
+    quarantine_db: Option<PathBuf>,
+) -> PyResult<Option<Bound<'py, PyDict>>> {
+    let quarantine_db = quarantine_db.unwrap_or_else(|| home_path(QUARANTINE_DB));
+    let Some(rec) = py.detach(|| origin(&path, &quarantine_db, None)) else { return Ok(None) };
+    let dict = PyDict::new(py);
+    for (key, value) in rec {
+        dict.set_item(key, json_to_py(py, &value)?)?;
+    }
+    Ok(Some(dict))
+}
+
+/// Watches a downloads folder and reports each file as it lands, with where it
+/// came from.
+///
+/// Files are reported once they have been quiet for debounce seconds, skipping
+/// in-progress downloads (.crdownload, .download, .part, ...) and hidden files.
+/// Each is a download_origin() record whose timestamp is when it landed and whose
+/// summary reads like "Safari downloaded report.pdf from example.com". Files
+/// without download metadata (copied or saved locally) are reported too when
+/// include_local is set, with kind "local" and url None. path defaults to ~/Downloads; only files
+/// directly inside it are watched. As a context manager it starts on entry and closes
+/// on exit.
+#[pyclass(frozen)]
+pub struct DownloadsMonitor {
+    watcher: Watcher,
+    quarantine_db: PathBuf,
+    include_local: bool,
+    reported: Mutex<HashSet<PathBuf>>,
+}
+
+#[pymethods]
+impl DownloadsMonitor {
+    #[new]
+    #[pyo3(signature = (path=None, debounce=1.0, quarantine_db=None, include_local=false))]
+    fn new(
+        path: Option<PathBuf>,
+        debounce: f64,
+        quarantine_db: Option<PathBuf>,
+        include_local: bool,
+    ) -> PyResult<Self> {
+        let path = path.unwrap_or_else(|| home_path("Downloads"));
+        Ok(DownloadsMonitor {
+            watcher: Watcher::new(vec![path], None, None, debounce, false, None)?,
+            quarantine_db: quarantine_db.unwrap_or_else(|| home_path(QUARANTINE_DB)),
+            include_local,
+            reported: Mutex::new(HashSet::new()),
+        })
+    }
+
+    /// Begin watching. Raises IOError if the folder can't be watched.
+    fn start(&self, py: Python<'_>) -> PyResult<()> {
+        self.watcher.start(py)
+    }
+
+    /// Stop watching. Files still settling are discarded.
+    fn stop(&self, py: Python<'_>) {
+        self.watcher.stop(py)
+    }
+
+    /// Stop watching and wait up to timeout seconds for the watcher thread; see
+    /// Watcher.close().
+    #[pyo3(signature = (timeout=CLOSE_TIMEOUT))]
+    fn close(&self, py: Python<'_>, timeout: Option<f64>) -> PyResult<bool> {
+        self.watcher.close(py, timeout)
+    }
+
+    /// Start watching unless already running.
+    fn __enter__(slf: PyRef<'_, Self>) -> PyResult<PyRef<'_, Self>> {
+        if !slf.watcher.running() {
+            slf.watcher.start(slf.py())?;
+        }
+        Ok(slf)
+    }
+
+    /// close() with the default timeout. Exceptions propagate.
+    #[pyo3(signature = (*_args))]
+    fn __exit__(&self, py: Python<'_>, _args: &Bound<'_, PyTuple>) -> PyResult<bool> {
+        self.close(py, Some(CLOSE_TIMEOUT))?;
+        Ok(false)
+    }
+
+    /// Return downloads that landed since the last poll, waiting up to timeout
+    /// seconds for at least one.
+    ///
+    /// timeout=None waits indefinitely (Ctrl-C still interrupts). Raises IOError if
+    /// the underlying watcher reported an error.
+    #[pyo3(signature = (timeout=0.0))]
+    fn poll<'py>(&self, py: Python<'py>, timeout: Option<f64>) -> PyResult<Bound<'py, PyList>> {
+        let deadline = deadline(timeout)?;
+        loop {
+            let remaining = deadline.map(|d| d.saturating_duration_since(Instant::now()));
+            let changes = self.watcher.wait(py, remaining.map(|r| r.as_secs_f64()))?;
+            let downloads = py.detach(|| {
+                let mut reported = self.reported.lock().unwrap_or_else(|e| e.into_inner());
+                let mut downloads = Vec::new();
+                for change in changes {
+                    if change.kind == "removed" {
+                        reported.remove(&change.path);
+                        continue;
+                    }
+                    if is_partial(&change.path)
+                        || !change.path.is_file()
+                        || reported.contains(&change.path)
+                    {
+                        continue;
+                    }
+                    let found = origin(&change.path, &self.quarantine_db, Some(change.timestamp));
+                    let rec = match found {
+                        Some(rec) => rec,
+                        None if self.include_local => local_file(&change.path, change.timestamp),
+                        None => continue,
+                    };
+                    reported.insert(change.path);
+                    downloads.push(rec);
+                }
+                downloads
+            });
+            if !downloads.is_empty() || deadline.is_some_and(|d| Instant::now() >= d) {
+                return records_to_py(py, downloads);
+            }
+        }
+    }
+
+    #[getter]
+    fn running(&self) -> bool {
+        self.watcher.running()
+    }
+}
@@ -0,0 +1,124 @@
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use pyo3::create_exception;
+use pyo3::exceptions::{PyIOError, PyPermissionError, PyValueError};
+use pyo3::prelude::*;
+
+create_exception!(
+    snoopy_native,
+    ParseError,
+    PyValueError,
+    "Malformed input at a known place; path and offset (bytes) say where, cause why."
+);
+create_exception!(
+    snoopy_native,
+    FormatError,
+    PyValueError,
+    "A file that isn't in the expected format (not SQLite, not a snoopy export, corrupt zstd)."
+);
+create_exception!(
+    snoopy_native,
+    DatabaseLocked,
+    PyIOError,
+    "A SQLite database is locked or busy; retrying later usually works."
+);
+create_exception!(
+    snoopy_native,
+    PermissionDenied,
+    PyPermissionError,
+    "Access was refused, usually for want of Full Disk Access; path names what was refused."
+);
+
+/// Attach path, offset and cause to a native exception so handlers can inspect
+/// them instead of parsing the message.
+fn detailed(err: PyErr, path: Option<&Path>, offset: Option<u64>, cause: &str) -> PyErr {
+    Python::attach(|py| {
+        let value = err.value(py);
+        let _ = value.setattr("path", path.map(|p| p.display().to_string()));
+        let _ = value.setattr("offset", offset);
+        let _ = value.setattr("cause", cause);
+    });
+    err
+}
+
+/// An IOError for path, or PermissionDenied if that's what it was.
+pub(crate) fn io_error(path: &Path, e: io::Error) -> PyErr {
+    let message = format!("{}: {e}", path.display());
+    match e.kind() {
+        io::ErrorKind::PermissionDenied => {
+            detailed(PermissionDenied::new_err(message), Some(path), None, &e.to_string())
+        }
+        _ => PyIOError::new_err(message),
+    }
+}
+
+pub(crate) fn format_error(path: Option<&Path>, cause: impl fmt::Display) -> PyErr {
+    let cause = cause.to_string();
+    let message = match path {
+        Some(path) => format!("{}: {cause}", path.display()),
+        None => cause.clone(),
+    };
+    detailed(FormatError::new_err(message), path, None, &cause)
+}
+
+/// The exception for a SQLite error reading path: DatabaseLocked, PermissionDenied
+/// or FormatError when the error code says so, otherwise ValueError.
+pub(crate) fn sqlite_error(path: &Path, e: rusqlite::Error) -> PyErr {
+    use rusqlite::ErrorCode;
+
+    let message = format!("{}: {e}", path.display());
+    let err = match e.sqlite_error_code() {
+        Some(ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked) => {
+            DatabaseLocked::new_err(message)
+        }
+        Some(ErrorCode::PermissionDenied | ErrorCode::AuthorizationForStatementDenied) => {
+            PermissionDenied::new_err(message)
+        }
+        Some(ErrorCode::NotADatabase) => FormatError::new_err(message),
+        _ => return PyValueError::new_err(message),
+    };
+    detailed(err, Some(path), None, &e.to_string())
+}
+
+/// Why a file couldn't be read, kept typed until it reaches Python.
+#[derive(Debug)]
+pub(crate) enum ReadError {
+    Io(PathBuf, io::Error),
+    /// Undecodable content starting at a byte offset.
+    Parse(PathBuf, u64, String),
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadError::Io(path, e) => write!(f, "{}: {e}", path.display()),
+            ReadError::Parse(path, offset, cause) => {
+                write!(f, "{}: at byte {offset}: {cause}", path.display())
+            }
+        }
+    }
+}
+
+impl From<ReadError> for PyErr {
+    fn from(e: ReadError) -> PyErr {
+        match e {
+            ReadError::Io(path, e) => io_error(&path, e),
+            ReadError::Parse(path, offset, cause) => {
+                let message = format!("{}: at byte {offset}: {cause}", path.display());
+                detailed(ParseError::new_err(message), Some(&path), Some(offset), &cause)
+            }
+        }
+    }
+}
+
+/// Add the exception classes to the module.
+pub(crate) fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    m.add("ParseError", py.get_type::<ParseError>())?;
+    m.add("FormatError", py.get_type::<FormatError>())?;
+    m.add("DatabaseLocked", py.get_type::<DatabaseLocked>())?;
+    m.add("PermissionDenied", py.get_type::<PermissionDenied>())?;
+    Ok(())
+}
@@ -0,0 +1,216 @@
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+use rayon::prelude::*;
+use serde_json::{json, Map, Value};
+
+use crate::collector::{record, records_to_py, Record};
+use crate::timeutil::parse_iso_ts;
+
+/// open(2) fflag bit for write access.
+const FWRITE: i64 = 0x2;
+
+fn basename(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+fn text<'a>(value: &'a Value, pointer: &str) -> Option<&'a str> {
+    value.pointer(pointer).and_then(Value::as_str)
+}
+
+/// A file argument: either {path} or, for files that don't exist yet, {dir, filename}.
+fn file_path(file: &Value) -> Option<String> {
+    if let Some(path) = text(file, "/path") {
+        return Some(path.to_string());
+    }
+    let dir = text(file, "/dir/path")?;
+    Some(format!("{}/{}", dir.trim_end_matches('/'), text(file, "/filename")?))
+}
+
+/// Where a create or rename lands: an existing file it replaces, or a new path.
+fn destination(dest: &Value) -> Option<String> {
+    dest.get("existing_file")
+        .and_then(file_path)
+        .or_else(|| dest.get("new_path").and_then(file_path))
+        .or_else(|| file_path(dest))
+}
+
+/// Fields every event shares, describing the process that caused it.
+fn actor(process: &Value) -> Map<String, Value> {
+    let exe = text(process, "/executable/path").unwrap_or_default();
+    let field = |pointer: &str| process.pointer(pointer).cloned().unwrap_or_default();
+    [
+        ("pid", field("/audit_token/pid")),
+        ("ppid", field("/ppid")),
+        ("uid", field("/audit_token/euid")),
+        ("process_name", basename(exe).into()),
+        ("exe", exe.into()),
+        ("signing_id", field("/signing_id")),
+        ("team_id", field("/team_id")),
+    ]
+    .into_iter()
+    .map(|(k, v)| (k.to_string(), v))
+    .collect()
+}
+
+/// Map one eslogger line into the unified schema; None for lines that aren't events
+/// or event types without a mapping.
+pub(crate) fn parse_event(line: &str) -> Option<Record> {
+    let line = line.trim();
+    if !line.starts_with('{') {
+        return None;
+    }
+    let entry: Value = serde_json::from_str(line).ok()?;
+    let timestamp = parse_iso_ts(entry.get("time")?.as_str()?)?;
+    // "event" has exactly one key naming the type, e.g. {"exec": {...}}.
+    let (kind, body) = entry.get("event")?.as_object()?.iter().next()?;
+    let process = entry.get("process").unwrap_or(&Value::Null);
+    let (source, fields) = match kind.as_str() {
+        "exec" => {
+            let target = body.get("target").unwrap_or(&Value::Null);
+            let mut fields = actor(target);
+            let parent = process.pointer("/audit_token/pid").cloned().unwrap_or_default();
+            fields.insert("parent".to_string(), parent);
+            fields.insert("argv".to_string(), body.get("args").cloned().unwrap_or(json!([])));
+            fields.insert("cwd".to_string(), text(body, "/cwd/path").into());
+            ("process", fields)
+        }
+        "exit" => {
+            let mut fields = actor(process);
+            fields.insert("status".to_string(), body.get("stat").cloned().unwrap_or_default());
+            ("process", fields)
+        }
+        "open" => {
+            let mut fields = actor(process);
+            let writes = body.get("fflag").and_then(Value::as_i64).is_some_and(|f| f & FWRITE != 0);
+            fields.insert("path".to_string(), body.get("file").and_then(file_path).into());
+            fields.insert("write".to_string(), writes.into());
+            ("file", fields)
+        }
+        "close" if body.get("modified") == Some(&Value::Bool(true)) => {
+            let mut fields = actor(process);
+            fields.insert("path".to_string(), body.get("target").and_then(file_path).into());
+            ("file", fields)
+        }
+        "create" | "unlink" | "rename" | "truncate" => {
+            let mut fields = actor(process);
+            let path = match kind.as_str() {
+                "create" | "rename" => body.get("destination").and_then(destination),
+                _ => body.get("target").and_then(file_path),
+            };
+            fields.insert("path".to_string(), path.into());
+            if kind == "rename" {
+                let from = body.get("source").and_then(file_path);
+                fields.insert("previous_path".to_string(), from.into());
+            }
+            ("file", fields)
+        }
+        "uipc_connect" | "uipc_bind" => {
+            let mut fields = actor(process);
+            let path = body.get("file").or(body.get("dir")).and_then(file_path);
+            fields.insert("socket_path".to_string(), path.into());
+            fields.insert("protocol".to_string(), "unix".into());
+            ("network", fields)
+        }
+        "openssh_login" | "login_login" | "lw_session_login" | "screensharing_attach" => {
+            let mut fields = actor(process);
+            for (field, key) in
+                [("user", "username"), ("remote_address", "source_address"), ("success", "success")]
+            {
+                fields.insert(field.to_string(), body.get(key).cloned().unwrap_or_default());
+            }
+            ("login", fields)
+        }
+        _ => return None,
+    };
+    let kind = match kind.as_str() {
+        "close" => "write",
+        "uipc_connect" => "connect",
+        "uipc_bind" => "bind",
+        "openssh_login" => "ssh",
+        "login_login" => "console",
+        "lw_session_login" => "session",
+        "screensharing_attach" => "screen_sharing",
+        other => other,
+    };
+    let mut event = record(source, json!({"timestamp": timestamp, "kind": kind}));
+    event.extend(fields);
+    event.insert("seq".to_string(), entry.get("seq_num").cloned().unwrap_or_default());
+    Some(event)
+}
+
+/// Parse lines in parallel, keeping their order and skipping those before since.
+pub(crate) fn parse_lines(lines: &[&str], since: f64) -> Vec<Record> {
+    lines
+        .par_iter()
+        .filter_map(|line| parse_event(line))
+        .filter(|e| e["timestamp"].as_f64().is_some_and(|ts| ts > since))
+        .collect()
+}
+
+/// Streaming parser for `eslogger --format json` output (Endpoint Security).
+///
+/// feed() takes any chunk of the NDJSON stream, holding a partial trailing line
+/// until the rest arrives, and returns the events it completed; flush() parses what
+/// is left. Complete lines are parsed in parallel. See parse_eslogger for the event
+/// mapping.
+#[pyclass]
+#[derive(Default)]
+pub struct EsloggerParser {
+    partial: String,
+}
+
+#[pymethods]
+impl EsloggerParser {
+    #[new]
+    fn new() -> Self {
+        EsloggerParser::default()
+    }
+
+    /// Parse a chunk of eslogger output and return the completed events.
+    fn feed<'py>(&mut self, py: Python<'py>, text: &str) -> PyResult<Bound<'py, PyList>> {
+        self.partial.push_str(text);
+        let complete = match self.partial.rfind('\n') {
+            Some(end) => self.partial.drain(..=end).collect::<String>(),
+            None => String::new(),
+        };
+        let events = py.detach(|| {
+            let lines: Vec<&str> = complete.lines().collect();
+            parse_lines(&lines, f64::NEG_INFINITY)
+        });
+        records_to_py(py, events)
+    }
+
+    /// Parse a buffered partial line, if any.
+    fn flush<'py>(&mut self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
+        let rest = std::mem::take(&mut self.partial);
+        records_to_py(py, parse_event(&rest).into_iter().collect())
+    }
+}
+
+/// Parse `eslogger --format json` output into events in the unified schema.
+///
+/// Every event has timestamp, kind, seq and the acting process's pid, ppid, uid,
+/// process_name, exe, signing_id and team_id. exec becomes {source: "process",
+/// kind: "exec", argv, cwd, parent} describing the new image, and exit {source:
+/// "process", kind: "exit", status}. open becomes {source: "file", kind: "open",
+/// path, write}; close of a modified file {kind: "write", path}; create, unlink,
+/// rename (with previous_path) and truncate keep their names. uipc_connect and
+/// uipc_bind become {source: "network", kind: "connect" | "bind", protocol: "unix",
+/// socket_path}; openssh_login, login_login, lw_session_login and
+/// screensharing_attach become {source: "login", kind: "ssh" | "console" |
+/// "session" | "screen_sharing", user, remote_address, success}. Other event types
+/// are skipped. Only events after since (epoch seconds) are returned, in input order.
+#[pyfunction]
+#[pyo3(signature = (text, since=None))]
+pub fn parse_eslogger<'py>(
+    py: Python<'py>,
+    text: &str,
+    since: Option<f64>,
+) -> PyResult<Bound<'py, PyList>> {
+    let since = since.unwrap_or(f64::NEG_INFINITY);
+    let events = py.detach(|| {
+        let lines: Vec<&str> = text.lines().collect();
+        parse_lines(&lines, since)
+    });
+    records_to_py(py, events)
+}
@@ -0,0 +1,202 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use rayon::prelude::*;
+
+use crate::privacy::{global_filter, PrivacyFilter};
+use crate::rollup::{decode_project_dir, find_jsonl_files};
+use crate::{tool_input_preview, truncate_str};
+
+/// Failing commands are grouped on this many leading characters of their preview.
+const COMMAND_KEY_LEN: usize = 200;
+
+#[derive(Default)]
+struct ToolCounts {
+    calls: u64,
+    errors: u64,
+}
+
+#[derive(Default)]
+struct FailureStats {
+    tools: BTreeMap<String, ToolCounts>,
+    failing: HashMap<(String, String), u64>,
+}
+
+impl FailureStats {
+    fn merge(&mut self, other: FailureStats) {
+        for (tool, c) in other.tools {
+            let mine = self.tools.entry(tool).or_default();
+            mine.calls += c.calls;
+            mine.errors += c.errors;
+        }
+        for (key, n) in other.failing {
+            *self.failing.entry(key).or_insert(0) += n;
+        }
+    }
+}
+
+/// The file's group key and counts, or None if the privacy filter denies its project.
+fn scan_file(
+    path: &Path,
+    by_project: bool,
+    filter: Option<&PrivacyFilter>,
+) -> Option<(String, FailureStats)> {
+    let mut stats = FailureStats::default();
+    let mut project = path
+        .parent()
+        .and_then(|p| p.file_name())
+        .and_then(|n| n.to_str())
+        .map(decode_project_dir)
+        .unwrap_or_default();
+    let session = path.file_stem().and_then(|s| s.to_str()).unwrap_or("").to_string();
+    let key = |project: String| if by_project { project } else { session.clone() };
+    let file = match File::open(path) {
+        Ok(f) => f,
+        Err(_) => return Some((key(project), stats)),
+    };
+
+    let mut project_from_cwd = false;
+    let mut tool_uses: HashMap<String, (String, String)> = HashMap::new();
+    for line in BufReader::new(file).lines() {
+        let line = match line {
+            Ok(l) => l,
+            Err(_) => break,
+        };
+        let entry: serde_json::Value = match serde_json::from_str(line.trim()) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        if !project_from_cwd {
+            if let Some(cwd) = entry.get("cwd").and_then(|v| v.as_str()) {
+                project = cwd.to_string();
+                project_from_cwd = true;
+            }
+        }
+        let blocks = match entry["message"].get("content").and_then(|v| v.as_array()) {
+            Some(arr) => arr,
+            None => continue,
+        };
+        for block in blocks {
+            match block.get("type").and_then(|v| v.as_str()) {
+                Some("tool_use") => {
+                    let name = block.get("name").and_then(|v| v.as_str()).unwrap_or("");
+                    let preview = tool_input_preview(name, &block["input"]);
+                    if let Some(id) = block.get("id").and_then(|v| v.as_str()) {
+                        tool_uses.insert(id.to_string(), (name.to_string(), preview));
+                    }
+                }
+                Some("tool_result") => {
+                    let id = block.get("tool_use_id").and_then(|v| v.as_str()).unwrap_or("");
+                    let (name, preview) = match tool_uses.get(id) {
+                        Some(t) => t,
+                        None => continue,
+                    };
+                    let counts = stats.tools.entry(name.clone()).or_default();
+                    counts.calls += 1;
+                    if block.get("is_error").and_then(|v| v.as_bool()).unwrap_or(false) {
+                        counts.errors += 1;
+                        let command = truncate_str(preview, COMMAND_KEY_LEN).to_string();
+                        *stats.failing.entry((name.clone(), command)).or_insert(0) += 1;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    if filter.is_some_and(|f| !f.allows_project(&project)) {
+        return None;
+    }
+    Some((key(project), stats))
+}
+
+/// Tool call error counts per session or project.
+///
+/// path is a transcript file or a directory searched recursively. group_by is
+/// "session" or "project". Returns {group: {calls, errors, error_rate,
+/// tools: {name: {calls, errors, error_rate}}, top_failing: [{tool, command, count}]}},
+/// with top_failing limited to the top_n most frequent failing calls. Under an
+/// installed privacy filter, denied projects are left out and failing commands are
+/// dropped or redacted as their text requires.
+#[pyfunction]
+#[pyo3(signature = (path, group_by="session", top_n=10))]
+pub fn tool_failure_stats<'py>(
+    py: Python<'py>,
+    path: &str,
+    group_by: &str,
+    top_n: usize,
+) -> PyResult<Bound<'py, PyDict>> {
+    let by_project = match group_by {
+        "session" => false,
+        "project" => true,
+        _ => {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "group_by must be 'session' or 'project', got {group_by:?}"
+            )))
+        }
+    };
+    let root = PathBuf::from(path);
+    let filter = global_filter();
+    let groups = py.detach(|| {
+        let files = if root.is_dir() { find_jsonl_files(&root) } else { vec![root.clone()] };
+        let mut groups: BTreeMap<String, FailureStats> = BTreeMap::new();
+        let per_file: Vec<(String, FailureStats)> =
+            files.par_iter().filter_map(|f| scan_file(f, by_project, filter.as_deref())).collect();
+        for (key, stats) in per_file {
+            groups.entry(key).or_default().merge(stats);
+        }
+        groups
+    });
+
+    let rate = |errors: u64, calls: u64| {
+        if calls == 0 {
+            0.0
+        } else {
+            errors as f64 / calls as f64
+        }
+    };
+    let out = PyDict::new(py);
+    for (key, stats) in groups {
+        let (mut calls, mut errors) = (0, 0);
+        let tools = PyDict::new(py);
+        for (name, c) in &stats.tools {
+            calls += c.calls;
+            errors += c.errors;
+            let t = PyDict::new(py);
+            t.set_item("calls", c.calls)?;
+            t.set_item("errors", c.errors)?;
+            t.set_item("error_rate", rate(c.errors, c.calls))?;
+            tools.set_item(name, t)?;
+        }
+
+        let mut failing: Vec<_> = stats.failing.into_iter().collect();
+        failing.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        let top = PyList::empty(py);
+        let failing = failing.into_iter().filter_map(|((tool, command), count)| {
+            let command = match &filter {
+                Some(f) => f.content(&command)?.into_owned(),
+                None => command,
+            };
+            Some((tool, command, count))
+        });
+        for (tool, command, count) in failing.take(top_n) {
+            let f = PyDict::new(py);
+            f.set_item("tool", tool)?;
+            f.set_item("command", command)?;
+            f.set_item("count", count)?;
+            top.append(f)?;
+        }
+
+        let dict = PyDict::new(py);
+        dict.set_item("calls", calls)?;
+        dict.set_item("errors", errors)?;
+        dict.set_item("error_rate", rate(errors, calls))?;
+        dict.set_item("tools", tools)?;
+        dict.set_item("top_failing", top)?;
+        out.set_item(key, dict)?;
+    }
+    Ok(out)
+}
@@ -0,0 +1,142 @@
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use serde_json::json;
+
+use crate::collector::{now, record, Record, Sampler};
+
+/// The focused application and its frontmost window.
+#[derive(Clone, PartialEq)]
+pub(crate) struct FrontWindow {
+    pub(crate) app: String,
+    pid: Option<i64>,
+    pub(crate) title: String,
+}
+
+/// The frontmost normal window, from CGWindowListCopyWindowInfo.
+///
+/// The list is ordered front to back, so the first window on layer 0 (ordinary app
+/// windows, as opposed to the menu bar and overlays) belongs to the focused app.
+/// Window titles are empty unless the process has Screen Recording permission.
+#[cfg(target_os = "macos")]
+pub(crate) fn front_window() -> Option<FrontWindow> {
+    use core_foundation::base::{CFType, TCFType};
+    use core_foundation::dictionary::{CFDictionary, CFDictionaryRef};
+    use core_foundation::number::CFNumber;
+    use core_foundation::string::CFString;
+    use core_graphics::window::{
+        copy_window_info, kCGNullWindowID, kCGWindowListExcludeDesktopElements,
+        kCGWindowListOptionOnScreenOnly,
+    };
+
+    let options = kCGWindowListOptionOnScreenOnly | kCGWindowListExcludeDesktopElements;
+    let windows = copy_window_info(options, kCGNullWindowID)?;
+    for item in windows.iter() {
+        // SAFETY: every element of the window info array is a CFDictionary.
+        let info: CFDictionary<CFString, CFType> =
+            unsafe { CFDictionary::wrap_under_get_rule(*item as CFDictionaryRef) };
+        let get = |key: &'static str| info.find(CFString::from_static_string(key));
+        let number = |key| get(key).and_then(|v| v.downcast::<CFNumber>()).and_then(|n| n.to_i64());
+        let string = |key| get(key).and_then(|v| v.downcast::<CFString>()).map(|s| s.to_string());
+        if number("kCGWindowLayer") != Some(0) {
+            continue;
+        }
+        return Some(FrontWindow {
+            app: string("kCGWindowOwnerName").unwrap_or_default(),
+            pid: number("kCGWindowOwnerPID"),
+            title: string("kCGWindowName").unwrap_or_default(),
+        });
+    }
+    None
+}
+
+/// The active X11 window, via xprop's _NET_ACTIVE_WINDOW and the window's
+/// _NET_WM_NAME, WM_CLASS and _NET_WM_PID properties.
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn front_window() -> Option<FrontWindow> {
+    fn xprop(args: &[&str]) -> Option<String> {
+        let output = std::process::Command::new("xprop").args(args).output().ok()?;
+        output.status.success().then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+    /// The value of "NAME(TYPE) = value" for a property, with quotes removed.
+    fn property<'a>(text: &'a str, name: &str) -> Option<&'a str> {
+        text.lines()
+            .find(|l| l.starts_with(name) && l[name.len()..].starts_with('('))
+            .and_then(|l| l.split_once(" = "))
+            .map(|(_, v)| v.trim())
+    }
+    fn unquote(s: &str) -> String {
+        let s = s.strip_prefix('"').and_then(|s| s.strip_suffix('"')).unwrap_or(s);
+        s.replace("\\\"", "\"").replace("\\\\", "\\")
+    }
+
+    let root = xprop(&["-root", "_NET_ACTIVE_WINDOW"])?;
+    let id = root.split_whitespace().last().filter(|id| id.starts_with("0x") && *id != "0x0")?;
+    let props = xprop(&["-id", id, "_NET_WM_NAME", "WM_CLASS", "_NET_WM_PID"])?;
+    // WM_CLASS is "instance", "Class"; the class is the application name.
+    let app = property(&props, "WM_CLASS")
+        .and_then(|v| v.rsplit(", ").next())
+        .map(unquote)
+        .unwrap_or_default();
+    Some(FrontWindow {
+        app,
+        pid: property(&props, "_NET_WM_PID").and_then(|v| v.parse().ok()),
+        title: property(&props, "_NET_WM_NAME").map(unquote).unwrap_or_default(),
+    })
+}
+
+/// Emits an event whenever the focused app or window title changes.
+#[derive(Default)]
+pub(crate) struct FrontmostSampler {
+    current: Option<(FrontWindow, f64)>,
+}
+
+impl Sampler for FrontmostSampler {
+    fn name(&self) -> &'static str {
+        "frontmost"
+    }
+
+    fn sample(&mut self) -> Result<Vec<Record>, String> {
+        let window = match front_window() {
+            Some(w) => w,
+            None => return Ok(Vec::new()),
+        };
+        if self.current.as_ref().is_some_and(|(w, _)| *w == window) {
+            return Ok(Vec::new());
+        }
+        let ts = now();
+        let previous = self.current.replace((window.clone(), ts));
+        let (prev_app, prev_title, prev_duration) = match &previous {
+            Some((w, since)) => (Some(w.app.clone()), Some(w.title.clone()), Some(ts - since)),
+            None => (None, None, None),
+        };
+        Ok(vec![record(
+            self.name(),
+            json!({
+                "timestamp": ts,
+                "app": window.app,
+                "pid": window.pid,
+                "title": window.title,
+                "previous_app": prev_app,
+                "previous_title": prev_title,
+                "previous_duration": prev_duration,
+            }),
+        )])
+    }
+}
+
+/// The focused application and window title right now, or None if unknown.
+///
+/// Returns {app, pid, title}. Uses CGWindowListCopyWindowInfo on macOS (titles need
+/// Screen Recording permission) and xprop on X11. Collector(sources=["frontmost"])
+/// samples this on its interval and emits an event each time it changes.
+#[pyfunction]
+pub fn frontmost_window(py: Python<'_>) -> PyResult<Option<Bound<'_, PyDict>>> {
+    let Some(window) = py.detach(front_window) else {
+        return Ok(None);
+    };
+    let dict = PyDict::new(py);
+    dict.set_item("app", window.app)?;
+    dict.set_item("pid", window.pid)?;
+    dict.set_item("title", window.title)?;
+    Ok(Some(dict))
+}
@@ -0,0 +1,317 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use chrono::{Duration, Local, NaiveTime, TimeZone};
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+use regex::Regex;
+use serde_json::json;
+
+use crate::collector::{now, record, records_to_py, Record};
+use crate::eslogger::parse_event;
+use crate::history::sort_by_timestamp;
+
+/// Files listed per event; file_count still counts all of them.
+const MAX_FILES: usize = 20;
+
+/// What one trace line says happened to a file.
+#[derive(Clone, Copy, PartialEq)]
+enum Access {
+    Open,
+    Write,
+}
+
+/// One file access pulled out of a trace line.
+struct Activity {
+    timestamp: f64,
+    process: String,
+    pid: Option<i64>,
+    path: String,
+    access: Access,
+}
+
+/// "12:34:56.789012  open  F=5 (R_____)  /path/to/file  0.000012 W  Finder.123456".
+fn fs_usage_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"^(\d\d:\d\d:\d\d(?:\.\d+)?)\s+(\S+)\s+(.*?)\s+\d+\.\d+(?:\s+W)?\s+(\S+)$")
+            .unwrap()
+    })
+}
+
+/// opensnoop: "  UID    PID COMM          FD PATH", optionally with a leading TIME.
+fn opensnoop_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^\s*(?:\d+\s+)?\d+\s+(\d+)\s+(\S+)\s+(-?\d+)\s+(/.*)$").unwrap())
+}
+
+/// fs_usage prints only the time of day; anchor it to today, or yesterday if that
+/// would put it in the future (a trace read just after midnight).
+fn time_of_day(text: &str) -> Option<f64> {
+    let time = NaiveTime::parse_from_str(text, "%H:%M:%S%.f").ok()?;
+    let today = Local::now().date_naive();
+    let mut at = Local.from_local_datetime(&today.and_time(time)).earliest()?;
+    if at.timestamp() as f64 > now() + 60.0 {
+        at -= Duration::days(1);
+    }
+    Some(at.timestamp_micros() as f64 / 1e6)
+}
+
+/// Parses fs_usage, opensnoop and eslogger lines, remembering which descriptor each
+/// process opened on which path so later writes by descriptor can be attributed.
+#[derive(Default)]
+struct TraceReader {
+    descriptors: HashMap<(String, String), String>,
+}
+
+impl TraceReader {
+    fn parse(&mut self, line: &str) -> Option<Activity> {
+        if line.trim_start().starts_with('{') {
+            let event = parse_event(line).filter(|e| e["source"] == "file")?;
+            let opened = event["kind"] == "open" && event["write"] != true;
+            return Some(Activity {
+                timestamp: event["timestamp"].as_f64()?,
+                process: event["process_name"].as_str()?.to_string(),
+                pid: event["pid"].as_i64(),
+                path: event["path"].as_str()?.to_string(),
+                access: if opened { Access::Open } else { Access::Write },
+            });
+        }
+        if let Some(caps) = opensnoop_re().captures(line) {
+            if caps[3].starts_with('-') {
+                return None; // failed open
+            }
+            return Some(Activity {
+                timestamp: now(),
+                process: caps[2].to_string(),
+                pid: caps[1].parse().ok(),
+                path: caps[4].trim_end().to_string(),
+                access: Access::Open,
+            });
+        }
+        let caps = fs_usage_re().captures(line.trim_end())?;
+        let call = caps[2].trim_end_matches("_nocancel").trim_end_matches("_extended");
+        let args = &caps[3];
+        // -w appends ".<thread id>" to the process name.
+        let process = caps[4].rsplit_once('.').map_or(&caps[4], |(name, tid)| {
+            if tid.bytes().all(|b| b.is_ascii_digit()) {
+                name
+            } else {
+                &caps[4]
+            }
+        });
+        let fd = args.split_whitespace().find_map(|a| a.strip_prefix("F=")).map(str::to_string);
+        let path = args.find('/').map(|at| args[at..].trim().to_string());
+        let access = match call {
+            "open" | "openat" | "open_dprotected_np" => {
+                let path = path?;
+                if let Some(fd) = fd {
+                    self.descriptors.insert((process.to_string(), fd), path.clone());
+                }
+                // The flags column reads like "(_WC___)" for opens with write access.
+                let writes = args.split_whitespace().any(|a| a.starts_with('(') && a.contains('W'));
+                return Some(Activity {
+                    timestamp: time_of_day(&caps[1])?,
+                    process: process.to_string(),
+                    pid: None,
+                    path,
+                    access: if writes { Access::Write } else { Access::Open },
+                });
+            }
+            "write" | "pwrite" | "writev" | "pwritev" | "ftruncate" | "truncate" | "rename"
+            | "renameat" | "unlink" | "unlinkat" | "mkdir" | "mkdirat" | "rmdir" | "creat"
+            | "link" | "symlink" => Access::Write,
+            "close" => {
+                if let Some(fd) = fd {
+                    self.descriptors.remove(&(process.to_string(), fd));
+                }
+                return None;
+            }
+            _ => return None,
+        };
+        let path = path.or_else(|| {
+            let fd = fd?;
+            self.descriptors.get(&(process.to_string(), fd)).cloned()
+        })?;
+        Some(Activity {
+            timestamp: time_of_day(&caps[1])?,
+            process: process.to_string(),
+            pid: None,
+            path,
+            access,
+        })
+    }
+}
+
+/// Activity by one process under one directory, waiting for its window to close.
+struct Bucket {
+    first: f64,
+    last: f64,
+    opens: u64,
+    writes: u64,
+    files: BTreeSet<String>,
+}
+
+/// Streaming parser for file-activity traces: `fs_usage -w -f filesys`, opensnoop
+/// and `eslogger open close create rename unlink` output.
+///
+/// feed() takes any chunk of trace text (partial lines are held until the rest
+/// arrives) and returns the events whose window has closed. Accesses are grouped by
+/// process and directory (the watched directory containing the file, or the file's
+/// own directory without watch), and each group becomes one event once window
+/// seconds have passed since its first access: {source: "file_activity",
+/// timestamp, end, duration, process_name, pid, directory, opens, writes, files,
+/// file_count, summary}. files lists up to 20 paths. Files outside watch are
+/// ignored. Writes that fs_usage reports by descriptor only are attributed to the
+/// path that descriptor was opened on. Call flush() at the end of the trace for the
+/// groups still open.
+#[pyclass]
+pub struct FileActivityParser {
+    watch: Vec<String>,
+    window: f64,
+    reader: TraceReader,
+    partial: String,
+    clock: f64,
+    buckets: HashMap<(String, Option<i64>, String), Bucket>,
+}
+
+impl FileActivityParser {
+    /// The watched directory containing path, or its parent without watch.
+    fn directory_of(&self, path: &str) -> Option<String> {
+        if self.watch.is_empty() {
+            let parent = path.rsplit_once('/').map_or("/", |(dir, _)| dir);
+            return Some(if parent.is_empty() { "/" } else { parent }.to_string());
+        }
+        self.watch
+            .iter()
+            .filter(|root| {
+                path.strip_prefix(root.as_str()).is_some_and(|rest| {
+                    rest.is_empty() || rest.starts_with('/') || root.ends_with('/')
+                })
+            })
+            .max_by_key(|root| root.len())
+            .cloned()
+    }
+
+    fn add(&mut self, activity: Activity) {
+        self.clock = self.clock.max(activity.timestamp);
+        let Some(directory) = self.directory_of(&activity.path) else { return };
+        let bucket = self
+            .buckets
+            .entry((activity.process, activity.pid, directory))
+            .or_insert_with(|| Bucket {
+                first: activity.timestamp,
+                last: activity.timestamp,
+                opens: 0,
+                writes: 0,
+                files: BTreeSet::new(),
+            });
+        bucket.first = bucket.first.min(activity.timestamp);
+        bucket.last = bucket.last.max(activity.timestamp);
+        match activity.access {
+            Access::Open => bucket.opens += 1,
+            Access::Write => bucket.writes += 1,
+        }
+        bucket.files.insert(activity.path);
+    }
+
+    /// Remove and return the groups whose window closed by the stream's clock, or all
+    /// of them.
+    fn take(&mut self, all: bool) -> Vec<Record> {
+        let cutoff = self.clock - self.window;
+        let due: Vec<_> = self
+            .buckets
+            .iter()
+            .filter(|(_, b)| all || b.first <= cutoff)
+            .map(|(k, _)| k.clone())
+            .collect();
+        let mut events: Vec<Record> = due
+            .into_iter()
+            .filter_map(|key| {
+                let bucket = self.buckets.remove(&key)?;
+                let (process, pid, directory) = key;
+                let verb = if bucket.writes > 0 { "wrote" } else { "read" };
+                let count = bucket.files.len();
+                let noun = if count == 1 { "file" } else { "files" };
+                Some(record(
+                    "file_activity",
+                    json!({
+                        "timestamp": bucket.first,
+                        "end": bucket.last,
+                        "duration": bucket.last - bucket.first,
+                        "summary": format!("{process} {verb} {count} {noun} in {directory}"),
+                        "process_name": process,
+                        "pid": pid,
+                        "directory": directory,
+                        "opens": bucket.opens,
+                        "writes": bucket.writes,
+                        "file_count": count,
+                        "files": bucket.files.into_iter().take(MAX_FILES).collect::<Vec<_>>(),
+                    }),
+                ))
+            })
+            .collect();
+        sort_by_timestamp(&mut events);
+        events
+    }
+}
+
+#[pymethods]
+impl FileActivityParser {
+    #[new]
+    #[pyo3(signature = (watch=None, *, window=60.0))]
+    fn new(watch: Option<Vec<PathBuf>>, window: f64) -> PyResult<Self> {
+        if window.is_nan() || window < 0.0 {
+            return Err(pyo3::exceptions::PyValueError::new_err("window must be non-negative"));
+        }
+        let watch = watch
+            .unwrap_or_default()
+            .iter()
+            .map(|p| {
+                let p = p.to_string_lossy();
+                if p.len() > 1 {
+                    p.trim_end_matches('/').to_string()
+                } else {
+                    p.into_owned()
+                }
+            })
+            .collect();
+        Ok(FileActivityParser {
+            watch,
+            window,
+            reader: TraceReader::default(),
+            partial: String::new(),
+            clock: f64::NEG_INFINITY,
+            buckets: HashMap::new(),
+        })
+    }
+
+    /// Parse a chunk of trace output and return the events that are due.
+    fn feed<'py>(&mut self, py: Python<'py>, text: &str) -> PyResult<Bound<'py, PyList>> {
+        self.partial.push_str(text);
+        let complete = match self.partial.rfind('\n') {
+            Some(end) => self.partial.drain(..=end).collect::<String>(),
+            None => String::new(),
+        };
+        let events = py.detach(|| {
+            for line in complete.lines() {
+                if let Some(activity) = self.reader.parse(line) {
+                    self.add(activity);
+                }
+            }
+            self.take(false)
+        });
+        records_to_py(py, events)
+    }
+
+    /// Parse any buffered partial line and return every pending event.
+    fn flush<'py>(&mut self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
+        let rest = std::mem::take(&mut self.partial);
+        if let Some(activity) = self.reader.parse(&rest) {
+            self.add(activity);
+        }
+        let events = self.take(true);
+        records_to_py(py, events)
+    }
+}
@@ -0,0 +1,369 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use fst::automaton::{Automaton, Levenshtein};
+use fst::{IntoStreamer, Map, Streamer};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{Connection, OpenFlags};
+
+use crate::errors::sqlite_error;
+use crate::history::has_table;
+use crate::privacy::global_filter;
+use crate::timeline::timestamp_of;
+
+/// Event fields whose words are indexed by default.
+const TEXT_FIELDS: [&str; 15] = [
+    "content_preview",
+    "text",
+    "subject",
+    "title",
+    "content",
+    "content_text",
+    "command",
+    "term",
+    "messages",
+    "url",
+    "file_path",
+    "app_name",
+    "contact",
+    "sender",
+    "chat_name",
+];
+
+/// Store tables with text worth searching, read by FuzzyIndex.from_db().
+const STORE_TABLES: [&str; 15] = [
+    "claude_events",
+    "message_events",
+    "notification_events",
+    "mail_events",
+    "slack_events",
+    "whatsapp_events",
+    "browser_events",
+    "search_events",
+    "bookmark_events",
+    "download_events",
+    "page_content_events",
+    "shell_events",
+    "clipboard_events",
+    "note_events",
+    "reminder_events",
+];
+
+/// Longer words are left out of the index; they are hashes and blobs, not words.
+const MAX_WORD_CHARS: usize = 64;
+
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty() && w.chars().count() <= MAX_WORD_CHARS)
+        .map(str::to_lowercase)
+}
+
+/// Typos tolerated in a query word when the caller doesn't say: none for short
+/// words, where one edit reaches too many others, then one, then two.
+fn default_distance(word: &str) -> u32 {
+    match word.chars().count() {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    }
+}
+
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut diag = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = (diag + usize::from(ca != cb)).min(above + 1).min(row[j] + 1);
+            diag = above;
+        }
+    }
+    row[b.len()]
+}
+
+/// How far term is from a query word: 0 for the word itself, a quarter for a word it
+/// begins (when prefix matching), else the edits needed, to the closest of term's
+/// prefixes when prefix matching.
+fn match_cost(word: &str, term: &str, prefix: bool, distance: u32) -> f64 {
+    if word == term {
+        return 0.0;
+    }
+    if prefix && term.starts_with(word) {
+        return 0.25;
+    }
+    let (word, term): (Vec<char>, Vec<char>) = (word.chars().collect(), term.chars().collect());
+    if !prefix {
+        return edit_distance(&word, &term) as f64;
+    }
+    let d = distance as usize;
+    let lengths = word.len().saturating_sub(d)..=(word.len() + d).min(term.len());
+    lengths.map(|n| edit_distance(&word, &term[..n])).min().unwrap_or(d) as f64
+}
+
+fn matching_terms<A: Automaton>(terms: &Map<Vec<u8>>, automaton: A) -> Vec<(String, u64)> {
+    let mut stream = terms.search(automaton).into_stream();
+    let mut found = Vec::new();
+    while let Some((term, postings)) = stream.next() {
+        found.push((String::from_utf8_lossy(term).into_owned(), postings));
+    }
+    found
+}
+
+/// One search hit: the event, its score and the indexed words that matched.
+struct Hit {
+    event: u32,
+    score: f64,
+    terms: Vec<String>,
+}
+
+/// A typo-tolerant word index over event text, for interactive search.
+///
+/// Built from event dicts (FuzzyIndex(events)) or from the event store
+/// (FuzzyIndex.from_db()), it indexes the lowercased words of each event's text
+/// fields (fields, by default content_preview, text, subject, title, content,
+/// content_text, command, term, messages, url, file_path, app_name, contact, sender
+/// and chat_name) in a finite-state transducer, and search() walks it with a
+/// Levenshtein automaton per query word. It complements regex search over the store:
+/// "conection pool" finds "connection pooling" without knowing how it was spelled.
+/// The index is immutable; build a new one to take in new events. Events the
+/// installed privacy filter drops are left out, and the rest are indexed and
+/// returned as it redacts them.
+#[pyclass(frozen)]
+pub struct FuzzyIndex {
+    /// Word to its index in postings.
+    terms: Map<Vec<u8>>,
+    /// Event indexes per word, ascending.
+    postings: Vec<Vec<u32>>,
+    timestamps: Vec<f64>,
+    events: Vec<Py<PyAny>>,
+}
+
+impl FuzzyIndex {
+    fn build(events: Vec<Bound<'_, PyAny>>, fields: Option<Vec<String>>) -> PyResult<FuzzyIndex> {
+        let fields = fields.unwrap_or_else(|| TEXT_FIELDS.iter().map(|f| f.to_string()).collect());
+        let mut words_to_events: BTreeMap<String, Vec<u32>> = BTreeMap::new();
+        let mut timestamps = Vec::with_capacity(events.len());
+        let mut kept = Vec::with_capacity(events.len());
+        let filter = global_filter();
+        for event in events {
+            let Ok(dict) = event.cast::<PyDict>() else {
+                continue;
+            };
+            // What the privacy filter drops is never indexed, and redacted text is
+            // indexed and returned redacted.
+            let dict = match &filter {
+                Some(f) => match f.apply_dict(dict)? {
+                    Some(copy) => copy,
+                    None => continue,
+                },
+                None => dict.clone(),
+            };
+            let n = kept.len() as u32;
+            for field in &fields {
+                let Some(value) = dict.get_item(field)? else {
+                    continue;
+                };
+                let Ok(text) = value.extract::<&str>() else {
+                    continue;
+                };
+                for word in words(text) {
+                    let postings = words_to_events.entry(word).or_default();
+                    if postings.last() != Some(&n) {
+                        postings.push(n);
+                    }
+                }
+            }
+            timestamps.push(timestamp_of(dict.as_any()).unwrap_or(0.0));
+            kept.push(dict.into_any().unbind());
+        }
+        let mut postings = Vec::with_capacity(words_to_events.len());
+        let terms = Map::from_iter(words_to_events.into_iter().map(|(word, events)| {
+            postings.push(events);
+            (word, postings.len() as u64 - 1)
+        }))
+        .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))?;
+        Ok(FuzzyIndex { terms, postings, timestamps, events: kept })
+    }
+
+    fn find(&self, query: &str, max_distance: Option<u32>, prefix: bool) -> PyResult<Vec<Hit>> {
+        let query: Vec<String> = words(query).collect();
+        // Per event: the summed score of the query words matched so far, and the terms.
+        let mut hits: HashMap<u32, (f64, Vec<String>)> = HashMap::new();
+        for (i, word) in query.iter().enumerate() {
+            let distance = max_distance.unwrap_or_else(|| default_distance(word));
+            let lev = Levenshtein::new(word, distance)
+                .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+            // Only the last word may be unfinished, as it is while the user types.
+            let prefix = prefix && i == query.len() - 1;
+            let terms = if prefix {
+                matching_terms(&self.terms, lev.starts_with())
+            } else {
+                matching_terms(&self.terms, lev)
+            };
+            let mut best: HashMap<u32, (f64, &str)> = HashMap::new();
+            for (term, postings) in &terms {
+                let cost = match_cost(word, term, prefix, distance);
+                for &event in &self.postings[*postings as usize] {
+                    let slot = best.entry(event).or_insert((f64::INFINITY, term));
+                    if cost < slot.0 {
+                        *slot = (cost, term);
+                    }
+                }
+            }
+            if i == 0 {
+                hits = best
+                    .into_iter()
+                    .map(|(event, (cost, term))| (event, (1.0 / (1.0 + cost), vec![term.into()])))
+                    .collect();
+            } else {
+                hits.retain(|event, (score, matched)| match best.get(event) {
+                    Some(&(cost, term)) => {
+                        *score += 1.0 / (1.0 + cost);
+                        matched.push(term.to_string());
+                        true
+                    }
+                    None => false,
+                });
+            }
+        }
+        let n = query.len() as f64;
+        let mut hits: Vec<Hit> = hits
+            .into_iter()
+            .map(|(event, (score, terms))| Hit { event, score: score / n, terms })
+            .collect();
+        hits.sort_by(|a, b| {
+            b.score.total_cmp(&a.score).then_with(|| {
+                self.timestamps[b.event as usize].total_cmp(&self.timestamps[a.event as usize])
+            })
+        });
+        Ok(hits)
+    }
+}
+
+/// A store row: its table, and its columns with their values.
+type StoreRow = (String, Vec<(String, SqlValue)>);
+
+/// The rows of the store's searchable tables, oldest first within each table.
+fn read_store(
+    conn: &Connection,
+    tables: &[String],
+    since: Option<f64>,
+) -> rusqlite::Result<Vec<StoreRow>> {
+    let mut rows = Vec::new();
+    for table in tables {
+        if !has_table(conn, table)? {
+            continue;
+        }
+        let mut stmt = conn.prepare(&format!(
+            "SELECT * FROM {table} WHERE timestamp >= ? ORDER BY timestamp, id"
+        ))?;
+        let columns: Vec<String> = stmt.column_names().into_iter().map(String::from).collect();
+        let mut result = stmt.query([since.unwrap_or(f64::NEG_INFINITY)])?;
+        while let Some(row) = result.next()? {
+            let mut values = Vec::with_capacity(columns.len());
+            for (i, column) in columns.iter().enumerate() {
+                values.push((column.clone(), row.get::<_, SqlValue>(i)?));
+            }
+            rows.push((table.clone(), values));
+        }
+    }
+    Ok(rows)
+}
+
+#[pymethods]
+impl FuzzyIndex {
+    /// Index events, an iterable of dicts; other items are skipped. fields names
+    /// the dict keys whose text is indexed.
+    #[new]
+    #[pyo3(signature = (events, fields=None))]
+    fn new(events: &Bound<'_, PyAny>, fields: Option<Vec<String>>) -> PyResult<Self> {
+        let events = events.try_iter()?.collect::<PyResult<Vec<_>>>()?;
+        FuzzyIndex::build(events, fields)
+    }
+
+    /// Index the events in snoopy's SQLite store at db_path, opened read-only.
+    ///
+    /// tables defaults to the tables with text: claude, message, notification,
+    /// mail, slack, whatsapp, browser, search, bookmark, download, page content,
+    /// shell, clipboard, note and reminder events; missing ones are skipped. since
+    /// (epoch seconds) leaves out older rows. Each event is its row as a dict, with
+    /// "table" naming where it came from.
+    #[staticmethod]
+    #[pyo3(signature = (db_path, tables=None, since=None, fields=None))]
+    fn from_db(
+        py: Python<'_>,
+        db_path: &str,
+        tables: Option<Vec<String>>,
+        since: Option<f64>,
+        fields: Option<Vec<String>>,
+    ) -> PyResult<Self> {
+        let tables = tables.unwrap_or_else(|| STORE_TABLES.iter().map(|t| t.to_string()).collect());
+        if let Some(bad) =
+            tables.iter().find(|t| !t.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'))
+        {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "invalid table name {bad:?}"
+            )));
+        }
+        let path = Path::new(db_path);
+        let rows = py
+            .detach(|| {
+                let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+                read_store(&conn, &tables, since)
+            })
+            .map_err(|e| sqlite_error(path, e))?;
+        let mut events = Vec::with_capacity(rows.len());
+        for (table, values) in rows {
+            let dict = PyDict::new(py);
+            dict.set_item("table", table)?;
+            for (column, value) in values {
+                match value {
+                    SqlValue::Null => dict.set_item(column, py.None())?,
+                    SqlValue::Integer(n) => dict.set_item(column, n)?,
+                    SqlValue::Real(x) => dict.set_item(column, x)?,
+                    SqlValue::Text(s) => dict.set_item(column, s)?,
+                    SqlValue::Blob(b) => dict.set_item(column, b)?,
+                }
+            }
+            events.push(dict.into_any());
+        }
+        FuzzyIndex::build(events, fields)
+    }
+
+    /// Events matching every word of query, best first.
+    ///
+    /// Each query word matches indexed words within max_distance edits (by default
+    /// none up to three letters, one up to seven, then two); with prefix=True the
+    /// last word also matches the start of longer words, so results can follow
+    /// typing. A hit scores 1 / (1 + edits) per query word, averaged, with a word
+    /// the query word begins costing a quarter edit, so 1.0 means every word was
+    /// found as typed; ties go to the newer event. Returns at most limit hits as
+    /// [{event, score, terms (the indexed words matched, one per query word)}].
+    #[pyo3(signature = (query, max_distance=None, prefix=true, limit=50))]
+    fn search<'py>(
+        &self,
+        py: Python<'py>,
+        query: &str,
+        max_distance: Option<u32>,
+        prefix: bool,
+        limit: usize,
+    ) -> PyResult<Bound<'py, PyList>> {
+        let hits = py.detach(|| self.find(query, max_distance, prefix))?;
+        let out = PyList::empty(py);
+        for hit in hits.into_iter().take(limit) {
+            let dict = PyDict::new(py);
+            dict.set_item("event", self.events[hit.event as usize].bind(py))?;
+            dict.set_item("score", hit.score)?;
+            dict.set_item("terms", hit.terms)?;
+            out.append(dict)?;
+        }
+        Ok(out)
+    }
+
+    fn __len__(&self) -> usize {
+        self.events.len()
+    }
+}
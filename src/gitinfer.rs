@@ -0,0 +1,315 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+use std::sync::OnceLock;
+
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use regex::Regex;
+
+use crate::errors::io_error;
+use crate::privacy::global_filter;
+use crate::timeutil::parse_iso_ts;
+use crate::tool_result_text;
+
+fn git_command_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"(?:^|&&|\|\||;|\n)\s*(?:cd\s+(\S+)\s*&&\s*)?(?:git\s+(?:-C\s+(\S+)\s+)?(commit|push|checkout|switch|merge)\b([^\n;&|]*)|gh\s+pr\s+(create)\b([^\n;&|]*))"
+        ).unwrap()
+    })
+}
+
+fn heredoc_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?s)<<-?\s*'?\w+'?\n(.*)").unwrap())
+}
+
+fn quoted_arg_regex(flags: &str) -> Regex {
+    Regex::new(&format!(
+        r#"(?:{flags})(?:\s+|=)(?:"((?:[^"\\]|\\.)*)"|'([^']*)'|(\S+))"#
+    ))
+    .unwrap()
+}
+
+fn commit_message_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| quoted_arg_regex("-m|--message"))
+}
+
+fn pr_title_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| quoted_arg_regex("-t|--title"))
+}
+
+fn commit_output_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?m)^\[([^\s\]]+)(?: \(root-commit\))? ([0-9a-f]{7,40})\] (.*)$").unwrap()
+    })
+}
+
+fn push_output_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?m)^\s*[+*!=-]?\s*\S+\s+(\S+)\s+->\s+(\S+)").unwrap())
+}
+
+fn pr_url_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"https://\S+/pull/\d+").unwrap())
+}
+
+#[derive(Default)]
+struct GitEvent {
+    timestamp: f64,
+    session_id: String,
+    event_type: &'static str,
+    repo: String,
+    branch: Option<String>,
+    message: Option<String>,
+    sha: Option<String>,
+    url: Option<String>,
+    command: String,
+    success: bool,
+}
+
+fn first_capture(re: &Regex, text: &str) -> Option<String> {
+    let caps = re.captures(text)?;
+    caps.iter()
+        .skip(1)
+        .flatten()
+        .next()
+        .map(|m| m.as_str().to_string())
+}
+
+/// Pull a message out of a -m/--title argument, following the
+/// `"$(cat <<'EOF' ... EOF)"` heredoc form agents use for multi-line messages.
+fn quoted_value(re: &Regex, command: &str) -> Option<String> {
+    let value = first_capture(re, command)?;
+    if value.contains("<<") {
+        if let Some(caps) = heredoc_regex().captures(command) {
+            return caps[1].lines().find(|l| !l.trim().is_empty()).map(|l| l.trim().to_string());
+        }
+    }
+    Some(value.lines().next().unwrap_or("").to_string())
+}
+
+fn looks_failed(output: &str) -> bool {
+    output.contains("fatal:") || output.contains("error:") || output.contains("[rejected]")
+}
+
+/// The branch argument of a checkout/switch/merge, skipping flags and pathspecs.
+fn branch_arg(args: &str) -> Option<String> {
+    if args.contains(" -- ") || args.trim_end().ends_with(" --") {
+        return None;
+    }
+    let mut words = args.split_whitespace();
+    while let Some(w) = words.next() {
+        match w {
+            "-b" | "-B" | "-c" | "-C" | "--create" => return words.next().map(str::to_string),
+            w if w.starts_with('-') => continue,
+            w => return Some(w.to_string()),
+        }
+    }
+    None
+}
+
+fn analyze_command(command: &str, output: &str, cwd: &str) -> Vec<GitEvent> {
+    let mut out = Vec::new();
+    let success = !looks_failed(output);
+    for caps in git_command_regex().captures_iter(command) {
+        let repo = caps
+            .get(2)
+            .or_else(|| caps.get(1))
+            .map(|m| m.as_str().to_string())
+            .unwrap_or_else(|| cwd.to_string());
+        let mut ev = GitEvent {
+            repo,
+            command: caps[0].trim_start_matches(['&', '|', ';', '\n']).trim().to_string(),
+            success,
+            ..Default::default()
+        };
+
+        if caps.get(5).is_some() {
+            ev.event_type = "pr_create";
+            ev.message = quoted_value(pr_title_regex(), command);
+            ev.url = pr_url_regex().find(output).map(|m| m.as_str().to_string());
+            out.push(ev);
+            continue;
+        }
+
+        let args = caps.get(4).map(|m| m.as_str()).unwrap_or("");
+        match &caps[3] {
+            "commit" => {
+                ev.event_type = "git_commit";
+                let parsed = commit_output_regex().captures(output);
+                ev.branch = parsed.as_ref().map(|c| c[1].to_string());
+                ev.sha = parsed.as_ref().map(|c| c[2].to_string());
+                ev.message = quoted_value(commit_message_regex(), command)
+                    .or_else(|| parsed.as_ref().map(|c| c[3].to_string()));
+                ev.success = success && (parsed.is_some() || output.is_empty());
+            }
+            "push" => {
+                ev.event_type = "git_push";
+                ev.branch = push_output_regex()
+                    .captures(output)
+                    .map(|c| c[1].to_string())
+                    .or_else(|| {
+                        let positional: Vec<&str> =
+                            args.split_whitespace().filter(|w| !w.starts_with('-')).collect();
+                        positional.get(1).map(|b| b.to_string())
+                    });
+            }
+            "checkout" | "switch" => {
+                ev.event_type = "git_checkout";
+                ev.branch = match branch_arg(args) {
+                    Some(b) => Some(b),
+                    None => continue,
+                };
+            }
+            _ => {
+                ev.event_type = "git_merge";
+                ev.branch = branch_arg(args);
+            }
+        }
+        out.push(ev);
+    }
+    out
+}
+
+struct PendingCommand {
+    timestamp: f64,
+    cwd: String,
+    command: String,
+}
+
+fn infer_git_activity_impl(path: &str) -> io::Result<Vec<GitEvent>> {
+    let session_id = Path::new(path)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("")
+        .to_string();
+    let file = File::open(path)?;
+
+    // Bash tool_use id -> command, in order; outputs are matched by tool_use_id.
+    let mut order: Vec<String> = Vec::new();
+    let mut commands: HashMap<String, PendingCommand> = HashMap::new();
+    let mut outputs: HashMap<String, String> = HashMap::new();
+
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        let entry: serde_json::Value = match serde_json::from_str(line.trim()) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        let blocks = match entry["message"].get("content").and_then(|v| v.as_array()) {
+            Some(arr) => arr,
+            None => continue,
+        };
+        let ts = entry
+            .get("timestamp")
+            .and_then(|v| v.as_str())
+            .and_then(parse_iso_ts)
+            .unwrap_or(0.0);
+        let cwd = entry.get("cwd").and_then(|v| v.as_str()).unwrap_or("");
+
+        for block in blocks {
+            match block.get("type").and_then(|v| v.as_str()) {
+                Some("tool_use") => {
+                    if block.get("name").and_then(|v| v.as_str()) != Some("Bash") {
+                        continue;
+                    }
+                    let command = block["input"]
+                        .get("command")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("");
+                    if !command.contains("git") && !command.contains("gh ") {
+                        continue;
+                    }
+                    let id = block.get("id").and_then(|v| v.as_str()).unwrap_or("").to_string();
+                    order.push(id.clone());
+                    commands.insert(id, PendingCommand {
+                        timestamp: ts,
+                        cwd: cwd.to_string(),
+                        command: command.to_string(),
+                    });
+                }
+                Some("tool_result") => {
+                    if let Some(id) = block.get("tool_use_id").and_then(|v| v.as_str()) {
+                        if commands.contains_key(id) {
+                            outputs.insert(id.to_string(), tool_result_text(block));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let filter = global_filter();
+    let mut events = Vec::new();
+    for id in order {
+        let pending = match commands.remove(&id) {
+            Some(p) => p,
+            None => continue,
+        };
+        let output = outputs.get(&id).map(String::as_str).unwrap_or("");
+        for mut ev in analyze_command(&pending.command, output, &pending.cwd) {
+            if let Some(f) = &filter {
+                let allowed = f.allows(|field| match field {
+                    "cwd" => Some(pending.cwd.as_str()),
+                    "repo" => Some(ev.repo.as_str()),
+                    "url" => ev.url.as_deref(),
+                    "command" => Some(ev.command.as_str()),
+                    "message" => ev.message.as_deref(),
+                    _ => None,
+                });
+                if !allowed {
+                    continue;
+                }
+                if let Some(command) = f.redact(&ev.command) {
+                    ev.command = command;
+                }
+                if let Some(message) = ev.message.as_deref().and_then(|m| f.redact(m)) {
+                    ev.message = Some(message);
+                }
+            }
+            ev.timestamp = pending.timestamp;
+            ev.session_id = session_id.clone();
+            events.push(ev);
+        }
+    }
+    Ok(events)
+}
+
+/// Find git and GitHub activity performed through Bash tool calls in a transcript.
+///
+/// Returns event dicts with event_type ("git_commit", "git_push", "git_checkout",
+/// "git_merge" or "pr_create"), timestamp, session_id, repo, branch, message, sha, url,
+/// command and success. Fields that can't be inferred are None. The installed privacy
+/// filter drops and redacts events as it would any other.
+#[pyfunction]
+pub fn infer_git_activity<'py>(py: Python<'py>, path: &str) -> PyResult<Bound<'py, PyList>> {
+    let events = py
+        .detach(|| infer_git_activity_impl(path))
+        .map_err(|e| io_error(Path::new(path), e))?;
+
+    let py_list = PyList::empty(py);
+    for ev in events {
+        let dict = PyDict::new(py);
+        dict.set_item("timestamp", ev.timestamp)?;
+        dict.set_item("session_id", ev.session_id)?;
+        dict.set_item("event_type", ev.event_type)?;
+        dict.set_item("repo", ev.repo)?;
+        dict.set_item("branch", ev.branch)?;
+        dict.set_item("message", ev.message)?;
+        dict.set_item("sha", ev.sha)?;
+        dict.set_item("url", ev.url)?;
+        dict.set_item("command", ev.command)?;
+        dict.set_item("success", ev.success)?;
+        py_list.append(dict)?;
+    }
+    Ok(py_list)
+}
@@ -0,0 +1,242 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use gix::bstr::ByteSlice;
+use gix::revision::walk::Sorting;
+use gix::traverse::commit::simple::CommitTimeOrder;
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+use rayon::prelude::*;
+use serde_json::json;
+
+use crate::collector::{record, records_to_py, Record};
+
+/// Directories never searched for repositories.
+const SKIP_DIRS: [&str; 6] = ["node_modules", "target", "venv", "__pycache__", "build", "dist"];
+
+/// Find git repositories at or below root, not descending into repositories or
+/// hidden directories, up to depth levels down.
+fn find_repos(root: &Path, depth: usize, out: &mut Vec<PathBuf>) {
+    if root.join(".git").exists() {
+        out.push(root.to_path_buf());
+        return;
+    }
+    if depth == 0 {
+        return;
+    }
+    let mut dirs: Vec<PathBuf> = match std::fs::read_dir(root) {
+        Ok(entries) => entries
+            .flatten()
+            .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+            .filter(|e| {
+                let name = e.file_name().to_string_lossy().into_owned();
+                !name.starts_with('.') && !SKIP_DIRS.contains(&name.as_str())
+            })
+            .map(|e| e.path())
+            .collect(),
+        Err(_) => return,
+    };
+    dirs.sort();
+    for dir in dirs {
+        find_repos(&dir, depth - 1, out);
+    }
+}
+
+/// Whose activity to report: everyone, or anyone matching one of these lowercase
+/// names or emails.
+enum Authors {
+    All,
+    Only(Vec<String>),
+}
+
+impl Authors {
+    /// The explicit list, or the repository's configured user.name and user.email.
+    fn for_repo(explicit: &Option<Vec<String>>, all: bool, repo: &gix::Repository) -> Authors {
+        if all {
+            return Authors::All;
+        }
+        let list = match explicit {
+            Some(list) => list.iter().map(|a| a.trim().to_lowercase()).collect(),
+            None => {
+                let config = repo.config_snapshot();
+                ["user.email", "user.name"]
+                    .iter()
+                    .filter_map(|key| config.string(*key))
+                    .map(|v| v.to_str_lossy().trim().to_lowercase())
+                    .collect()
+            }
+        };
+        Authors::Only(list)
+    }
+
+    fn matches(&self, name: &[u8], email: &[u8]) -> bool {
+        match self {
+            Authors::All => true,
+            Authors::Only(list) => {
+                let name = name.to_str_lossy().trim().to_lowercase();
+                let email = email.to_str_lossy().trim().to_lowercase();
+                list.iter().any(|a| *a == name || *a == email)
+            }
+        }
+    }
+}
+
+/// Map a reflog message to an event kind and its detail text.
+fn classify(message: &str) -> Option<(&'static str, &str)> {
+    let (action, detail) = message.split_once(": ").unwrap_or((message, ""));
+    let kind = match action.split_whitespace().next()? {
+        "commit" if action.contains("(merge)") => "merge",
+        "commit" => "commit",
+        "checkout" => "checkout",
+        "merge" | "pull" => "merge",
+        _ => return None,
+    };
+    Some((kind, detail))
+}
+
+struct Scan<'a> {
+    since: f64,
+    authors: &'a Option<Vec<String>>,
+    all_authors: bool,
+    max_commits: usize,
+}
+
+fn scan_repo(path: &Path, opts: &Scan<'_>) -> Result<Vec<Record>, String> {
+    let repo = gix::open(path).map_err(|e| e.to_string())?;
+    let authors = Authors::for_repo(opts.authors, opts.all_authors, &repo);
+    let repo_path = path.to_string_lossy().into_owned();
+    let mut events = Vec::new();
+    let mut seen: HashSet<gix::ObjectId> = HashSet::new();
+
+    // The reflog lists what happened in this clone, newest first; walking it backwards
+    // from the current branch recovers which branch each entry happened on.
+    let head = repo.head().map_err(|e| e.to_string())?;
+    let mut branch = head.referent_name().map(|n| n.shorten().to_string());
+    let mut log = head.log_iter();
+    if let Some(lines) = log.rev().map_err(|e| e.to_string())? {
+        for line in lines.flatten() {
+            let ts = line.signature.time.seconds as f64;
+            if ts <= opts.since {
+                break;
+            }
+            let message = line.message.to_str_lossy();
+            let Some((kind, detail)) = classify(&message) else {
+                continue;
+            };
+            let mut fields = json!({
+                "kind": kind,
+                "timestamp": ts,
+                "repo": repo_path,
+                "branch": branch,
+                "commit": line.new_oid.to_string(),
+                "message": detail,
+                "author": line.signature.name.to_str_lossy(),
+                "email": line.signature.email.to_str_lossy(),
+            });
+            if kind == "checkout" {
+                let moved = detail.strip_prefix("moving from ").and_then(|d| d.split_once(" to "));
+                if let Some((from, to)) = moved {
+                    fields["branch"] = to.into();
+                    fields["from_branch"] = from.into();
+                    branch = Some(from.to_string());
+                }
+            }
+            if !authors.matches(&line.signature.name, &line.signature.email) {
+                continue;
+            }
+            if kind != "checkout" {
+                seen.insert(line.new_oid);
+            }
+            events.push(record("git", fields));
+        }
+    }
+
+    // Commits that never passed through this reflog, e.g. made in another clone or
+    // worktree and fetched since.
+    let refs = repo.references().map_err(|e| e.to_string())?;
+    let branches = refs.local_branches().map_err(|e| e.to_string())?;
+    let tips: Vec<gix::ObjectId> =
+        branches.flatten().filter_map(|r| r.try_id().map(|id| id.detach())).collect();
+    let sorting = if opts.since.is_finite() {
+        Sorting::ByCommitTimeCutoff {
+            order: CommitTimeOrder::NewestFirst,
+            seconds: opts.since as i64,
+        }
+    } else {
+        Sorting::ByCommitTime(CommitTimeOrder::NewestFirst)
+    };
+    let walk = repo.rev_walk(tips).sorting(sorting).all().map_err(|e| e.to_string())?;
+    for info in walk.flatten().take(opts.max_commits) {
+        if seen.contains(&info.id) {
+            continue;
+        }
+        let Ok(commit) = info.object() else { continue };
+        let Ok(author) = commit.author() else { continue };
+        let ts = author.seconds() as f64;
+        if ts <= opts.since || !authors.matches(author.name, author.email) {
+            continue;
+        }
+        let summary = commit.message().map(|m| m.summary().to_string()).unwrap_or_default();
+        events.push(record(
+            "git",
+            json!({
+                "kind": "commit",
+                "timestamp": ts,
+                "repo": repo_path,
+                "branch": null,
+                "commit": info.id.to_string(),
+                "message": summary,
+                "author": author.name.to_str_lossy(),
+                "email": author.email.to_str_lossy(),
+            }),
+        ));
+    }
+    Ok(events)
+}
+
+/// Scan repositories under roots for commit, checkout and merge activity.
+///
+/// Each root is a repository or a directory searched up to max_depth levels for them.
+/// HEAD's reflog supplies commits, checkouts and merges made in each clone, and a walk
+/// of local branches (newest first, at most max_commits per repository) adds commits
+/// that arrived from elsewhere. Events are {source: "git", kind, timestamp, repo,
+/// branch, commit, message, author, email}, with from_branch on checkouts, oldest
+/// first. Only the current user's activity is kept: authors names or emails to match,
+/// defaulting to each repository's user.name and user.email; all_authors=True keeps
+/// everyone's. Repositories that can't be read are skipped.
+#[pyfunction]
+#[pyo3(signature = (
+    roots, since=None, authors=None, all_authors=false, max_depth=3, max_commits=1000
+))]
+pub fn scan_git_activity<'py>(
+    py: Python<'py>,
+    roots: Vec<PathBuf>,
+    since: Option<f64>,
+    authors: Option<Vec<String>>,
+    all_authors: bool,
+    max_depth: usize,
+    max_commits: usize,
+) -> PyResult<Bound<'py, PyList>> {
+    let opts = Scan {
+        since: since.unwrap_or(f64::NEG_INFINITY),
+        authors: &authors,
+        all_authors,
+        max_commits,
+    };
+    let events = py.detach(|| {
+        let mut repos = Vec::new();
+        for root in &roots {
+            find_repos(root, max_depth, &mut repos);
+        }
+        repos.dedup();
+        let mut events: Vec<Record> = repos
+            .par_iter()
+            .filter_map(|repo| scan_repo(repo, &opts).ok())
+            .flatten()
+            .collect();
+        let ts = |r: &Record| r["timestamp"].as_f64().unwrap_or(0.0);
+        events.sort_by(|a, b| ts(a).total_cmp(&ts(b)));
+        events
+    });
+    records_to_py(py, events)
+}
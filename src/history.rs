@@ -0,0 +1,629 @@
+use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
+
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+use regex::Regex;
+use rusqlite::Connection;
+use serde_json::{json, Value};
+
+use crate::collector::{home_path, json_to_py, record, records_to_py, Record};
+use crate::errors::{io_error, sqlite_error};
+use crate::privacy::host_of;
+use crate::snapshot::{Snapshot, DEFAULT_TIMEOUT};
+
+/// Safari stores visit times as seconds since 2001-01-01.
+const SAFARI_EPOCH_OFFSET: f64 = 978_307_200.0;
+/// Chromium stores times as microseconds since 1601-01-01.
+const WEBKIT_EPOCH_OFFSET: f64 = 11_644_473_600.0;
+/// Longest gap to the next visit still counted as time spent on a page.
+const MAX_VISIT_SECS: f64 = 30.0 * 60.0;
+
+/// Chromium-family user data directories, relative to the home directory (macOS, Linux).
+const CHROMIUM_ROOTS: [(&str, &str); 10] = [
+    ("chrome", "Library/Application Support/Google/Chrome"),
+    ("brave", "Library/Application Support/BraveSoftware/Brave-Browser"),
+    ("edge", "Library/Application Support/Microsoft Edge"),
+    ("arc", "Library/Application Support/Arc/User Data"),
+    ("chromium", "Library/Application Support/Chromium"),
+    ("vivaldi", "Library/Application Support/Vivaldi"),
+    ("chrome", ".config/google-chrome"),
+    ("brave", ".config/BraveSoftware/Brave-Browser"),
+    ("edge", ".config/microsoft-edge"),
+    ("chromium", ".config/chromium"),
+];
+
+/// Firefox-family profile directories, relative to the home directory (macOS, Linux).
+const FIREFOX_ROOTS: [(&str, &str); 4] = [
+    ("firefox", "Library/Application Support/Firefox/Profiles"),
+    ("librewolf", "Library/Application Support/librewolf/Profiles"),
+    ("firefox", ".mozilla/firefox"),
+    ("librewolf", ".librewolf"),
+];
+
+/// macOS records every quarantined download here, whichever app fetched it.
+pub(crate) const QUARANTINE_DB: &str = "Library/Preferences/com.apple.LaunchServices.QuarantineEventsV2";
+
+/// Matches a leading notification count in page titles: "(3) Inbox".
+fn notif_count_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^\(\d+\)\s+").unwrap())
+}
+
+fn clean_title(title: Option<String>) -> String {
+    let title = title.unwrap_or_default();
+    notif_count_regex().replace(&title, "").into_owned()
+}
+
+/// Run read against a private copy of a browser or app database.
+///
+/// Browsers hold their history open (Chromium with an exclusive lock) and keep recent
+/// visits in the WAL, so read a Snapshot rather than the file itself.
+pub(crate) fn with_snapshot<T>(
+    db: &Path,
+    read: impl FnOnce(&Connection) -> rusqlite::Result<T>,
+) -> PyResult<T> {
+    let snapshot = Snapshot::take(db, DEFAULT_TIMEOUT).map_err(|e| io_error(db, e))?;
+    snapshot.open().and_then(|conn| read(&conn)).map_err(|e| sqlite_error(db, e))
+}
+
+/// Fill in each visit's duration from the gap to the next one, capped at
+/// MAX_VISIT_SECS; the last visit's duration is unknown.
+fn durations_from_gaps(visits: &mut [Record]) {
+    let times: Vec<f64> = visits.iter().map(|v| v["timestamp"].as_f64().unwrap_or(0.0)).collect();
+    for (i, visit) in visits.iter_mut().enumerate() {
+        let gap = times.get(i + 1).map(|next| (next - times[i]).clamp(0.0, MAX_VISIT_SECS));
+        visit.insert("duration".to_string(), gap.map_or(Value::Null, Value::from));
+    }
+}
+
+fn visit(browser: &str, timestamp: f64, url: String, title: Option<String>) -> Record {
+    record(
+        "browser",
+        json!({
+            "browser": browser,
+            "timestamp": timestamp,
+            "url": url,
+            "title": clean_title(title),
+        }),
+    )
+}
+
+fn read_safari(conn: &Connection, since: f64) -> rusqlite::Result<Vec<Record>> {
+    let mut stmt = conn.prepare(
+        "SELECT hi.url, hv.title, hv.visit_time
+         FROM history_visits hv
+         JOIN history_items hi ON hv.history_item = hi.id
+         WHERE hv.visit_time > ?
+         ORDER BY hv.visit_time, hv.id",
+    )?;
+    let rows = stmt.query_map([since - SAFARI_EPOCH_OFFSET], |row| {
+        let ts = row.get::<_, f64>(2)? + SAFARI_EPOCH_OFFSET;
+        Ok(visit("safari", ts, row.get(0)?, row.get(1)?))
+    })?;
+    let mut visits = rows.collect::<rusqlite::Result<Vec<_>>>()?;
+    durations_from_gaps(&mut visits);
+    Ok(visits)
+}
+
+/// Read page visits from Safari's History.db, oldest first.
+///
+/// Each visit is {source: "browser", browser: "safari", timestamp, url, title,
+/// duration}, with timestamp in epoch seconds so visits can go straight into
+/// merge_timelines. Safari doesn't record how long a page was open, so duration is
+/// the gap to the next visit (capped at 30 minutes), or None for the last one. The
+/// database is read from a snapshot copy, so it can be read while Safari is running.
+/// path defaults to ~/Library/Safari/History.db; only visits after since (epoch
+/// seconds) are returned.
+#[pyfunction]
+#[pyo3(signature = (path=None, since=None))]
+pub fn safari_history<'py>(
+    py: Python<'py>,
+    path: Option<PathBuf>,
+    since: Option<f64>,
+) -> PyResult<Bound<'py, PyList>> {
+    let path = path.unwrap_or_else(|| home_path("Library/Safari/History.db"));
+    let since = since.unwrap_or(f64::NEG_INFINITY);
+    let visits = py.detach(|| with_snapshot(&path, |conn| read_safari(conn, since)))?;
+    records_to_py(py, visits)
+}
+
+/// A history database belonging to one browser profile.
+struct BrowserProfile {
+    browser: String,
+    profile: String,
+    path: PathBuf,
+}
+
+impl BrowserProfile {
+    /// A profile for an explicit database path, named after its directory.
+    fn for_path(browser: &str, path: PathBuf) -> Self {
+        let profile = path.parent().and_then(|p| p.file_name());
+        BrowserProfile {
+            browser: browser.to_string(),
+            profile: profile.map(|p| p.to_string_lossy().into_owned()).unwrap_or_default(),
+            path,
+        }
+    }
+}
+
+/// Find every profile directory holding db_name under the given roots (relative to
+/// the home directory), keeping those whose name passes is_profile.
+fn discover_profiles(
+    roots: &[(&str, &str)],
+    db_name: &str,
+    is_profile: fn(&str) -> bool,
+) -> Vec<BrowserProfile> {
+    let mut found = Vec::new();
+    for (browser, rel) in roots {
+        let entries = match std::fs::read_dir(home_path(rel)) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+        let mut profiles: Vec<BrowserProfile> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let profile = entry.file_name().to_string_lossy().into_owned();
+                let db = entry.path().join(db_name);
+                (is_profile(&profile) && db.is_file()).then(|| BrowserProfile {
+                    browser: browser.to_string(),
+                    profile,
+                    path: db,
+                })
+            })
+            .collect();
+        profiles.sort_by(|a, b| a.profile.cmp(&b.profile));
+        found.extend(profiles);
+    }
+    found
+}
+
+fn profiles_to_py(py: Python<'_>, profiles: Vec<BrowserProfile>) -> PyResult<Bound<'_, PyList>> {
+    let out = PyList::empty(py);
+    for p in profiles {
+        let profile = json!({
+            "browser": p.browser,
+            "profile": p.profile,
+            "path": p.path.to_string_lossy(),
+        });
+        out.append(json_to_py(py, &profile)?)?;
+    }
+    Ok(out)
+}
+
+type ProfileReader = fn(&Connection, &BrowserProfile, f64) -> rusqlite::Result<Vec<Record>>;
+
+/// Read records after since from one profile, tagging each with its profile name.
+fn read_profile(
+    profile: &BrowserProfile,
+    since: f64,
+    read: ProfileReader,
+) -> PyResult<Vec<Record>> {
+    let mut batch = with_snapshot(&profile.path, |conn| read(conn, profile, since))?;
+    for r in &mut batch {
+        r.insert("profile".to_string(), profile.profile.clone().into());
+    }
+    Ok(batch)
+}
+
+/// Read records after since from each profile. One that can't be read, say a database
+/// from an older browser version, is logged and skipped rather than hiding the rest.
+fn read_each_profile(profiles: &[BrowserProfile], since: f64, read: ProfileReader) -> Vec<Record> {
+    let mut records = Vec::new();
+    for profile in profiles {
+        match read_profile(profile, since, read) {
+            Ok(batch) => records.extend(batch),
+            Err(e) => tracing::warn!(browser = profile.browser, "skipping profile: {e}"),
+        }
+    }
+    records
+}
+
+pub(crate) fn sort_by_timestamp(records: &mut [Record]) {
+    let ts = |r: &Record| r["timestamp"].as_f64().unwrap_or(0.0);
+    records.sort_by(|a, b| ts(a).total_cmp(&ts(b)));
+}
+
+/// Read visits after since from profile, or else from each discovered profile, and
+/// merge them oldest first. Only an explicitly given profile's errors are raised.
+fn read_profiles(
+    py: Python<'_>,
+    profile: Option<BrowserProfile>,
+    discover: impl FnOnce() -> Vec<BrowserProfile> + Send,
+    since: Option<f64>,
+    read: ProfileReader,
+) -> PyResult<Bound<'_, PyList>> {
+    let since = since.unwrap_or(f64::NEG_INFINITY);
+    let visits = py.detach(|| -> PyResult<Vec<Record>> {
+        let mut visits = match profile {
+            Some(profile) => read_profile(&profile, since, read)?,
+            None => read_each_profile(&discover(), since, read),
+        };
+        sort_by_timestamp(&mut visits);
+        Ok(visits)
+    })?;
+    records_to_py(py, visits)
+}
+
+fn is_chromium_profile(name: &str) -> bool {
+    name == "Default" || name.starts_with("Profile ")
+}
+
+fn read_chromium(
+    conn: &Connection,
+    profile: &BrowserProfile,
+    since: f64,
+) -> rusqlite::Result<Vec<Record>> {
+    let mut stmt = conn.prepare(
+        "SELECT u.url, u.title, v.visit_time, v.visit_duration
+         FROM visits v
+         JOIN urls u ON v.url = u.id
+         WHERE v.visit_time > ?
+         ORDER BY v.visit_time, v.id",
+    )?;
+    let min_time = ((since + WEBKIT_EPOCH_OFFSET) * 1e6).max(0.0) as i64;
+    let rows = stmt.query_map([min_time], |row| {
+        let ts = row.get::<_, i64>(2)? as f64 / 1e6 - WEBKIT_EPOCH_OFFSET;
+        let mut v = visit(&profile.browser, ts, row.get(0)?, row.get(1)?);
+        let duration = row.get::<_, Option<i64>>(3)?.filter(|d| *d > 0);
+        v.insert("duration".to_string(), duration.map_or(Value::Null, |d| (d as f64 / 1e6).into()));
+        Ok(v)
+    })?;
+    rows.collect()
+}
+
+/// List Chromium-family browser profiles that have a History database.
+///
+/// Looks for Chrome, Brave, Edge, Arc, Chromium and Vivaldi under their usual macOS
+/// and Linux locations, returning {browser, profile, path} dicts.
+#[pyfunction]
+pub fn chromium_profiles(py: Python<'_>) -> PyResult<Bound<'_, PyList>> {
+    profiles_to_py(py, discover_profiles(&CHROMIUM_ROOTS, "History", is_chromium_profile))
+}
+
+/// Read page visits from Chrome, Brave, Edge and other Chromium-family browsers.
+///
+/// With path, reads that History file and labels visits with browser; without it,
+/// reads every profile chromium_profiles() finds. Visits come back oldest first as
+/// {source: "browser", browser, profile, timestamp, url, title, duration}, where
+/// duration is how long Chromium recorded the page as open (None if unknown). Only
+/// visits after since (epoch seconds) are returned.
+#[pyfunction]
+#[pyo3(signature = (path=None, browser="chromium", since=None))]
+pub fn chromium_history<'py>(
+    py: Python<'py>,
+    path: Option<PathBuf>,
+    browser: &str,
+    since: Option<f64>,
+) -> PyResult<Bound<'py, PyList>> {
+    let profile = path.map(|path| BrowserProfile::for_path(browser, path));
+    let discover = || discover_profiles(&CHROMIUM_ROOTS, "History", is_chromium_profile);
+    read_profiles(py, profile, discover, since, read_chromium)
+}
+
+fn read_firefox(
+    conn: &Connection,
+    profile: &BrowserProfile,
+    since: f64,
+) -> rusqlite::Result<Vec<Record>> {
+    let mut stmt = conn.prepare(
+        "SELECT p.url, p.title, v.visit_date
+         FROM moz_historyvisits v
+         JOIN moz_places p ON v.place_id = p.id
+         WHERE v.visit_date > ?
+         ORDER BY v.visit_date, v.id",
+    )?;
+    let min_date = (since * 1e6).max(0.0) as i64;
+    let rows = stmt.query_map([min_date], |row| {
+        let ts = row.get::<_, i64>(2)? as f64 / 1e6;
+        Ok(visit(&profile.browser, ts, row.get(0)?, row.get(1)?))
+    })?;
+    let mut visits = rows.collect::<rusqlite::Result<Vec<_>>>()?;
+    durations_from_gaps(&mut visits);
+    Ok(visits)
+}
+
+/// List Firefox and LibreWolf profiles that have a places.sqlite database.
+///
+/// Looks under the usual macOS and Linux profile directories, returning
+/// {browser, profile, path} dicts.
+#[pyfunction]
+pub fn firefox_profiles(py: Python<'_>) -> PyResult<Bound<'_, PyList>> {
+    profiles_to_py(py, discover_profiles(&FIREFOX_ROOTS, "places.sqlite", |_| true))
+}
+
+/// Read page visits from Firefox and LibreWolf places.sqlite databases.
+///
+/// With path, reads that places.sqlite and labels visits with browser; without it,
+/// reads every profile firefox_profiles() finds. Visits come back oldest first as
+/// {source: "browser", browser, profile, timestamp, url, title, duration}. Firefox
+/// doesn't record how long a page was open, so duration is the gap to the profile's
+/// next visit (capped at 30 minutes), or None for the last one. Only visits after
+/// since (epoch seconds) are returned.
+#[pyfunction]
+#[pyo3(signature = (path=None, browser="firefox", since=None))]
+pub fn firefox_history<'py>(
+    py: Python<'py>,
+    path: Option<PathBuf>,
+    browser: &str,
+    since: Option<f64>,
+) -> PyResult<Bound<'py, PyList>> {
+    let profile = path.map(|path| BrowserProfile::for_path(browser, path));
+    let discover = || discover_profiles(&FIREFOX_ROOTS, "places.sqlite", |_| true);
+    read_profiles(py, profile, discover, since, read_firefox)
+}
+
+pub(crate) fn has_table(conn: &Connection, name: &str) -> rusqlite::Result<bool> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?",
+        [name],
+        |row| row.get::<_, i64>(0),
+    )
+    .map(|n| n > 0)
+}
+
+/// A download event: where the file went, where it came from and how big it is.
+pub(crate) fn download(
+    browser: &str,
+    timestamp: f64,
+    path: Option<String>,
+    url: Option<String>,
+    origin: Option<String>,
+    size: Option<i64>,
+) -> Record {
+    let path = path.filter(|p| !p.is_empty());
+    let url = url.filter(|u| !u.is_empty());
+    let origin = origin.filter(|u| !u.is_empty());
+    let file_name = path
+        .as_deref()
+        .or(url.as_deref())
+        .and_then(|p| p.split(['?', '#']).next())
+        .and_then(|p| p.trim_end_matches('/').rsplit('/').next())
+        .map(|name| percent_encoding::percent_decode_str(name).decode_utf8_lossy().into_owned())
+        .unwrap_or_default();
+    let host = origin.as_deref().or(url.as_deref()).map(host_of).unwrap_or_default();
+    let domain = host.strip_prefix("www.").unwrap_or(&host).to_string();
+    let summary = match domain.as_str() {
+        "" => format!("downloaded {file_name}"),
+        d => format!("downloaded {file_name} from {d}"),
+    };
+    record(
+        "download",
+        json!({
+            "browser": browser,
+            "timestamp": timestamp,
+            "path": path,
+            "file_name": file_name,
+            "size": size.filter(|s| *s > 0),
+            "url": url,
+            "origin": origin,
+            "domain": domain,
+            "summary": summary,
+        }),
+    )
+}
+
+fn read_chromium_downloads(
+    conn: &Connection,
+    profile: &BrowserProfile,
+    since: f64,
+) -> rusqlite::Result<Vec<Record>> {
+    if !has_table(conn, "downloads")? {
+        return Ok(Vec::new());
+    }
+    let chain = if has_table(conn, "downloads_url_chains")? {
+        "(SELECT c.url FROM downloads_url_chains c WHERE c.id = d.id
+          ORDER BY c.chain_index DESC LIMIT 1)"
+    } else {
+        "NULL"
+    };
+    let mut stmt = conn.prepare(&format!(
+        "SELECT d.target_path, {chain}, COALESCE(NULLIF(d.tab_url, ''), d.referrer),
+                MAX(d.total_bytes, d.received_bytes), d.start_time
+         FROM downloads d
+         WHERE d.start_time > ?
+         ORDER BY d.start_time, d.id"
+    ))?;
+    let min_time = ((since + WEBKIT_EPOCH_OFFSET) * 1e6).max(0.0) as i64;
+    let rows = stmt.query_map([min_time], |row| {
+        let ts = row.get::<_, i64>(4)? as f64 / 1e6 - WEBKIT_EPOCH_OFFSET;
+        Ok(download(&profile.browser, ts, row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+    })?;
+    rows.collect()
+}
+
+fn read_firefox_downloads(
+    conn: &Connection,
+    profile: &BrowserProfile,
+    since: f64,
+) -> rusqlite::Result<Vec<Record>> {
+    if !has_table(conn, "moz_annos")? || !has_table(conn, "moz_anno_attributes")? {
+        return Ok(Vec::new());
+    }
+    let mut stmt = conn.prepare(
+        "SELECT a.content, p.url, a.dateAdded,
+                (SELECT m.content FROM moz_annos m
+                 JOIN moz_anno_attributes mn ON m.anno_attribute_id = mn.id
+                 WHERE m.place_id = a.place_id AND mn.name = 'downloads/metaData')
+         FROM moz_annos a
+         JOIN moz_anno_attributes n ON a.anno_attribute_id = n.id
+         JOIN moz_places p ON a.place_id = p.id
+         WHERE n.name = 'downloads/destinationFileURI' AND a.dateAdded > ?
+         ORDER BY a.dateAdded, a.id",
+    )?;
+    let min_date = (since * 1e6).max(0.0) as i64;
+    let rows = stmt.query_map([min_date], |row| {
+        let ts = row.get::<_, i64>(2)? as f64 / 1e6;
+        let path = row.get::<_, Option<String>>(0)?.map(|uri| {
+            let raw = uri.strip_prefix("file://").unwrap_or(&uri).to_string();
+            percent_encoding::percent_decode_str(&raw).decode_utf8_lossy().into_owned()
+        });
+        let meta: Option<String> = row.get(3)?;
+        let size = meta
+            .and_then(|m| serde_json::from_str::<Value>(&m).ok())
+            .and_then(|m| m.get("fileSize").and_then(Value::as_i64));
+        Ok(download(&profile.browser, ts, path, row.get(1)?, None, size))
+    })?;
+    rows.collect()
+}
+
+/// Downloads macOS quarantined, for apps without a readable downloads table.
+///
+/// Quarantine events don't record where the file was saved, so the file is looked
+/// for in ~/Downloads by name to fill in path and size.
+fn read_quarantine(conn: &Connection, since: f64) -> rusqlite::Result<Vec<Record>> {
+    if !has_table(conn, "LSQuarantineEvent")? {
+        return Ok(Vec::new());
+    }
+    let mut stmt = conn.prepare(
+        "SELECT LSQuarantineTimeStamp, LSQuarantineAgentName, LSQuarantineDataURLString,
+                LSQuarantineOriginURLString
+         FROM LSQuarantineEvent
+         WHERE LSQuarantineTimeStamp > ? AND LSQuarantineDataURLString IS NOT NULL
+         ORDER BY LSQuarantineTimeStamp",
+    )?;
+    let downloads_dir = home_path("Downloads");
+    let rows = stmt.query_map([since - SAFARI_EPOCH_OFFSET], |row| {
+        let ts = row.get::<_, f64>(0)? + SAFARI_EPOCH_OFFSET;
+        let agent = row.get::<_, Option<String>>(1)?.unwrap_or_default().to_lowercase();
+        let mut rec = download(&agent, ts, None, row.get(2)?, row.get(3)?, None);
+        find_saved_file(&mut rec, &downloads_dir);
+        Ok(rec)
+    })?;
+    rows.collect()
+}
+
+/// Fill in path and size from a file of the download's name in downloads_dir. The
+/// name comes percent-decoded from a URL, so anything but a plain file name (an
+/// absolute path, a separator, "..") is left alone rather than looked up.
+fn find_saved_file(rec: &mut Record, downloads_dir: &Path) {
+    let name = rec["file_name"].as_str().unwrap_or_default().to_string();
+    let mut components = Path::new(&name).components();
+    let plain = matches!(
+        (components.next(), components.next()),
+        (Some(Component::Normal(_)), None)
+    );
+    let saved = downloads_dir.join(&name);
+    if let (true, Ok(meta)) = (plain && !name.contains('/'), std::fs::metadata(&saved)) {
+        rec.insert("path".to_string(), saved.to_string_lossy().into());
+        rec.insert("size".to_string(), meta.len().into());
+    }
+}
+
+/// LSQuarantineTypeNumber values.
+pub(crate) fn quarantine_kind(kind: Option<i64>) -> &'static str {
+    match kind {
+        Some(0) => "web_download",
+        Some(2) => "email_attachment",
+        Some(3) => "message_attachment",
+        Some(4) => "calendar_attachment",
+        Some(5) => "attachment",
+        _ => "download",
+    }
+}
+
+/// Every quarantine event with the agent app, sender and event kind.
+fn read_quarantine_events(
+    conn: &Connection,
+    since: f64,
+    downloads_dir: &Path,
+) -> rusqlite::Result<Vec<Record>> {
+    let mut stmt = conn.prepare(
+        "SELECT LSQuarantineTimeStamp, LSQuarantineAgentName, LSQuarantineDataURLString,
+                LSQuarantineOriginURLString, LSQuarantineEventIdentifier,
+                LSQuarantineAgentBundleIdentifier, LSQuarantineTypeNumber,
+                LSQuarantineSenderName, LSQuarantineSenderAddress, LSQuarantineOriginTitle
+         FROM LSQuarantineEvent
+         WHERE LSQuarantineTimeStamp > ?
+         ORDER BY LSQuarantineTimeStamp",
+    )?;
+    let rows = stmt.query_map([since - SAFARI_EPOCH_OFFSET], |row| {
+        let ts = row.get::<_, f64>(0)? + SAFARI_EPOCH_OFFSET;
+        let app: Option<String> = row.get(1)?;
+        let agent = app.as_deref().unwrap_or_default().to_lowercase();
+        let mut rec = download(&agent, ts, None, row.get(2)?, row.get(3)?, None);
+        find_saved_file(&mut rec, downloads_dir);
+        let extra = json!({
+            "event_id": row.get::<_, Option<String>>(4)?,
+            "app": app,
+            "bundle_id": row.get::<_, Option<String>>(5)?,
+            "kind": quarantine_kind(row.get(6)?),
+            "sender": row.get::<_, Option<String>>(7)?.filter(|s| !s.is_empty()),
+            "sender_address": row.get::<_, Option<String>>(8)?.filter(|s| !s.is_empty()),
+            "origin_title": row.get::<_, Option<String>>(9)?.filter(|s| !s.is_empty()),
+        });
+        if let Value::Object(extra) = extra {
+            rec.extend(extra);
+        }
+        Ok(rec)
+    })?;
+    rows.collect()
+}
+
+/// Read file downloads from Chromium-family and Firefox-family browsers.
+///
+/// Each download is {source: "download", browser, profile, timestamp, path,
+/// file_name, size, url, origin, domain, summary}, where url is the file's own URL,
+/// origin the page it was downloaded from, and summary reads like "downloaded
+/// report.pdf from example.com". With quarantine (the default), macOS's
+/// LaunchServices quarantine log fills in downloads by other apps such as Safari,
+/// skipping any URL a browser already reported. Downloads come back oldest first;
+/// only those after since (epoch seconds) are returned.
+#[pyfunction]
+#[pyo3(signature = (since=None, quarantine=true))]
+pub fn browser_downloads<'py>(
+    py: Python<'py>,
+    since: Option<f64>,
+    quarantine: bool,
+) -> PyResult<Bound<'py, PyList>> {
+    let chromium = discover_profiles(&CHROMIUM_ROOTS, "History", is_chromium_profile);
+    let firefox = discover_profiles(&FIREFOX_ROOTS, "places.sqlite", |_| true);
+    let since = since.unwrap_or(f64::NEG_INFINITY);
+    let downloads = py.detach(|| -> PyResult<Vec<Record>> {
+        let mut downloads = read_each_profile(&chromium, since, read_chromium_downloads);
+        downloads.extend(read_each_profile(&firefox, since, read_firefox_downloads));
+        let quarantine_db = home_path(QUARANTINE_DB);
+        if quarantine && quarantine_db.is_file() {
+            let seen: std::collections::HashSet<String> = downloads
+                .iter()
+                .filter_map(|d| d["url"].as_str().map(str::to_string))
+                .collect();
+            let events = with_snapshot(&quarantine_db, |conn| read_quarantine(conn, since))?;
+            downloads.extend(
+                events.into_iter().filter(|d| !d["url"].as_str().is_some_and(|u| seen.contains(u))),
+            );
+        }
+        sort_by_timestamp(&mut downloads);
+        Ok(downloads)
+    })?;
+    records_to_py(py, downloads)
+}
+
+/// Read macOS's LaunchServices quarantine log: which app downloaded which file,
+/// from where and when.
+///
+/// Every file an app saves from the network or an attachment is recorded in
+/// com.apple.LaunchServices.QuarantineEventsV2. Each event has the fields of
+/// browser_downloads (browser is the agent app's name, lowercased) plus event_id,
+/// app and bundle_id of the downloading app, kind ("web_download",
+/// "email_attachment", "message_attachment", "calendar_attachment", "attachment" or
+/// "download"), sender and sender_address for attachments and AirDrop, and
+/// origin_title, the title of the page it came from. The log doesn't record where
+/// the file was saved, so path and size are filled in from ~/Downloads by name when
+/// such a file exists. Events come back oldest first; only those after since (epoch
+/// seconds) are returned. Raises IOError if the database doesn't exist.
+#[pyfunction]
+#[pyo3(signature = (path=None, since=None))]
+pub fn quarantine_events<'py>(
+    py: Python<'py>,
+    path: Option<PathBuf>,
+    since: Option<f64>,
+) -> PyResult<Bound<'py, PyList>> {
+    let path = path.unwrap_or_else(|| home_path(QUARANTINE_DB));
+    let since = since.unwrap_or(f64::NEG_INFINITY);
+    let downloads_dir = home_path("Downloads");
+    let events = py.detach(|| {
+        with_snapshot(&path, |conn| read_quarantine_events(conn, since, &downloads_dir))
+    })?;
+    records_to_py(py, events)
+}
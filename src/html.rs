@@ -0,0 +1,322 @@
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+use chrono::NaiveDate;
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict};
+use serde_json::{json, Map, Value};
+
+use crate::collector::Record;
+use crate::errors::io_error;
+use crate::narrate::format_clock;
+use crate::privacy::{global_filter, PrivacyFilter};
+use crate::report::{day_bounds, format_duration, offset_at};
+use crate::timeline::timestamp_of;
+
+const STYLE: &str = r##"
+body { font: 14px/1.4 -apple-system, "Segoe UI", sans-serif; margin: 24px; color: #222; }
+h1 { font-size: 20px; margin: 0 0 4px; }
+#meta { color: #777; margin-bottom: 16px; }
+#days button { border: 1px solid #ccc; background: #fff; border-radius: 4px; padding: 3px 9px;
+  margin: 0 4px 8px 0; cursor: pointer; }
+#days button.on { background: #222; color: #fff; border-color: #222; }
+#chart { position: relative; border-top: 1px solid #ddd; }
+.lane { position: relative; height: 26px; border-bottom: 1px solid #eee; }
+.lane .name { position: absolute; left: 0; width: 110px; top: 4px; color: #555;
+  overflow: hidden; text-overflow: ellipsis; white-space: nowrap; }
+.track { position: absolute; left: 120px; right: 0; top: 0; bottom: 0; }
+.ev { position: absolute; top: 6px; height: 14px; min-width: 3px; border-radius: 3px;
+  opacity: .8; cursor: pointer; }
+.ev:hover, .ev.sel { opacity: 1; outline: 2px solid #222; }
+.axis { position: relative; height: 20px; margin-left: 120px; color: #999; font-size: 11px; }
+.axis span { position: absolute; transform: translateX(-50%); }
+#detail { margin: 16px 0; padding: 10px 12px; background: #f6f6f6; border-radius: 6px;
+  min-height: 20px; white-space: pre-wrap; font-family: ui-monospace, monospace; font-size: 12px; }
+#list { border-collapse: collapse; width: 100%; }
+#list td { padding: 3px 8px; border-bottom: 1px solid #f0f0f0; vertical-align: top; }
+#list td:first-child { color: #777; white-space: nowrap; }
+#list tr { cursor: pointer; }
+#list tr.sel { background: #fff6d6; }
+"##;
+
+const SCRIPT: &str = r##"
+(function () {
+  var data = JSON.parse(document.getElementById("timeline-data").textContent);
+  var colors = ["#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f", "#edc948",
+                "#b07aa1", "#ff9da7", "#9c755f", "#bab0ac"];
+  var sources = [];
+  data.events.forEach(function (e) {
+    if (sources.indexOf(e.source) < 0) sources.push(e.source);
+  });
+  sources.sort();
+  function color(source) { return colors[sources.indexOf(source) % colors.length]; }
+  function el(tag, cls, text) {
+    var node = document.createElement(tag);
+    if (cls) node.className = cls;
+    if (text !== undefined) node.textContent = text;
+    return node;
+  }
+  var selected = null;
+  function select(e) {
+    selected = e;
+    var lines = [e.day + " " + e.time + "  " + e.label];
+    Object.keys(e.fields).sort().forEach(function (k) {
+      var v = e.fields[k];
+      if (v !== null && v !== "") lines.push(k + ": " + v);
+    });
+    document.getElementById("detail").textContent = lines.join("\n");
+    document.querySelectorAll(".sel").forEach(function (n) { n.classList.remove("sel"); });
+    document.querySelectorAll("[data-i='" + e.i + "']").forEach(function (n) {
+      n.classList.add("sel");
+    });
+  }
+  function show(range) {
+    var chart = document.getElementById("chart");
+    var list = document.getElementById("list");
+    chart.textContent = "";
+    list.textContent = "";
+    var span = range.end - range.start;
+    var shown = data.events.filter(function (e) {
+      return e.end >= range.start && e.start < range.end;
+    });
+    sources.forEach(function (source) {
+      var lane = el("div", "lane");
+      lane.appendChild(el("div", "name", source));
+      var track = el("div", "track");
+      shown.forEach(function (e) {
+        if (e.source !== source) return;
+        var left = Math.max(0, (e.start - range.start) / span);
+        var right = Math.min(1, (e.end - range.start) / span);
+        var bar = el("div", "ev");
+        bar.dataset.i = e.i;
+        bar.style.left = (left * 100) + "%";
+        bar.style.width = ((right - left) * 100) + "%";
+        bar.style.background = color(source);
+        bar.title = e.time + " " + e.label;
+        bar.onclick = function () { select(e); };
+        track.appendChild(bar);
+      });
+      lane.appendChild(track);
+      chart.appendChild(lane);
+    });
+    var axis = el("div", "axis");
+    var step = span > 2 * 86400 ? 86400 : span > 12 * 3600 ? 3 * 3600 : 3600;
+    for (var t = 0; t <= span; t += step) {
+      var tick = el("span", null, step === 86400 ? range.labels[t / 86400] || "" :
+        ("0" + Math.floor(t / 3600) % 24).slice(-2) + ":00");
+      tick.style.left = (t / span * 100) + "%";
+      axis.appendChild(tick);
+    }
+    chart.appendChild(axis);
+    shown.forEach(function (e) {
+      var row = el("tr");
+      row.dataset.i = e.i;
+      row.appendChild(el("td", null, e.day + " " + e.time));
+      row.appendChild(el("td", null, e.source));
+      row.appendChild(el("td", null, e.label));
+      row.onclick = function () { select(e); };
+      list.appendChild(row);
+    });
+    if (selected) select(selected);
+  }
+  var ranges = data.days.map(function (d) {
+    return { name: d.label, start: d.start, end: d.end, labels: [] };
+  });
+  if (ranges.length > 1) {
+    ranges.unshift({
+      name: "All", start: data.days[0].start, end: data.days[data.days.length - 1].end,
+      labels: data.days.map(function (d) { return d.label; })
+    });
+  }
+  var buttons = document.getElementById("days");
+  ranges.forEach(function (range, n) {
+    var button = el("button", n === 0 ? "on" : "", range.name);
+    button.onclick = function () {
+      buttons.querySelectorAll("button").forEach(function (b) { b.className = ""; });
+      button.className = "on";
+      show(range);
+    };
+    buttons.appendChild(button);
+  });
+  data.events.forEach(function (e, i) { e.i = i; });
+  show(ranges[0]);
+})();
+"##;
+
+/// One event as the page's script reads it.
+struct Item {
+    start: f64,
+    end: f64,
+    source: String,
+    label: String,
+    fields: Record,
+}
+
+/// An event dict's scalar fields; lists, dicts and other objects are left out.
+fn fields(dict: &Bound<'_, PyDict>) -> PyResult<Record> {
+    let mut out = Map::new();
+    for (key, value) in dict.iter() {
+        let Ok(key) = key.extract::<String>() else { continue };
+        let value = if value.is_none() {
+            Value::Null
+        } else if value.is_instance_of::<PyBool>() {
+            Value::Bool(value.extract()?)
+        } else if let Ok(n) = value.extract::<i64>() {
+            n.into()
+        } else if let Ok(x) = value.extract::<f64>() {
+            if x.is_finite() {
+                x.into()
+            } else {
+                Value::Null
+            }
+        } else if let Ok(s) = value.extract::<String>() {
+            s.into()
+        } else if value.hasattr("timestamp")? {
+            value.call_method0("timestamp")?.extract::<f64>()?.into()
+        } else {
+            continue;
+        };
+        out.insert(key, value);
+    }
+    Ok(out)
+}
+
+fn text(fields: &Record, key: &str) -> Option<String> {
+    fields.get(key).and_then(Value::as_str).filter(|s| !s.trim().is_empty()).map(str::to_string)
+}
+
+/// The event as a timeline item, or None if the privacy filter drops it. The label is
+/// taken after redaction so it can't show what the fields no longer do.
+fn item(event: &Bound<'_, PyAny>, filter: Option<&PrivacyFilter>) -> PyResult<Option<Item>> {
+    let start = timestamp_of(event)?;
+    let dict = event.cast::<PyDict>()?;
+    let mut fields = fields(dict)?;
+    if filter.is_some_and(|f| !f.apply_record(&mut fields)) {
+        return Ok(None);
+    }
+    let end = match fields.get("end").and_then(Value::as_f64) {
+        Some(end) => end,
+        None => start + fields.get("duration").and_then(Value::as_f64).unwrap_or(0.0),
+    };
+    let source = text(&fields, "source").unwrap_or_else(|| "event".to_string());
+    let label = ["summary", "title", "kind"]
+        .iter()
+        .find_map(|key| text(&fields, key))
+        .unwrap_or_else(|| source.clone());
+    Ok(Some(Item { start, end: end.max(start), source, label, fields }))
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn render(items: &[Item], days: &[(NaiveDate, f64, f64)], title: &str, utc: Option<i64>) -> String {
+    let day_of = |ts: f64| days.iter().find(|d| ts < d.2).unwrap_or(&days[days.len() - 1]).0;
+    let events: Vec<Value> = items
+        .iter()
+        .map(|e| {
+            let mut fields = e.fields.clone();
+            if e.end > e.start {
+                fields.insert("duration".into(), format_duration(e.end - e.start).into());
+            }
+            json!({
+                "start": e.start,
+                "end": e.end,
+                "source": e.source,
+                "label": e.label,
+                "day": day_of(e.start).format("%a %b %-d").to_string(),
+                "time": format_clock(e.start, offset_at(e.start, utc)),
+                "fields": fields,
+            })
+        })
+        .collect();
+    let days: Vec<Value> = days
+        .iter()
+        .map(|(date, start, end)| {
+            json!({"label": date.format("%a %b %-d").to_string(), "start": start, "end": end})
+        })
+        .collect();
+    // "</" would end the script element early.
+    let data = json!({"events": events, "days": days}).to_string().replace("</", "<\\/");
+    let title = escape_html(title);
+    let mut sources: Vec<&str> = items.iter().map(|e| e.source.as_str()).collect();
+    sources.sort_unstable();
+    sources.dedup();
+
+    let mut out = String::new();
+    out.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    let _ = writeln!(out, "<title>{title}</title>\n<style>{STYLE}</style>\n</head>\n<body>");
+    let _ = writeln!(out, "<h1>{title}</h1>");
+    let _ = writeln!(
+        out,
+        "<div id=\"meta\">{} event{} from {}</div>",
+        items.len(),
+        if items.len() == 1 { "" } else { "s" },
+        if sources.is_empty() { "no sources".to_string() } else { sources.join(", ") }
+    );
+    out.push_str("<div id=\"days\"></div>\n<div id=\"chart\"></div>\n<div id=\"detail\"></div>\n");
+    out.push_str("<table id=\"list\"></table>\n");
+    let _ = writeln!(out, "<script type=\"application/json\" id=\"timeline-data\">{data}</script>");
+    let _ = writeln!(out, "<script>{SCRIPT}</script>\n</body>\n</html>");
+    out
+}
+
+/// Write a self-contained HTML page with an interactive timeline of events, for
+/// sharing a day or week of activity without snoopy. Returns the number of events
+/// shown.
+///
+/// events is an iterable of event dicts with a timestamp (epoch seconds or
+/// datetime) as returned by any snoopy reader; each is drawn in its source's lane,
+/// as a bar if it has an end or duration, labelled by its summary, title or kind.
+/// The page covers days days from date (a datetime.date; 7 for a week), from local
+/// midnight or midnight at utc_offset (seconds east of UTC); events outside it are
+/// left out. Events are embedded as JSON with the page's script and styles, so it
+/// works offline; clicking one shows its fields. The installed privacy filter
+/// applies. title defaults to the date range. Raises ValueError if days is 0 and
+/// IOError if path can't be written.
+#[pyfunction]
+#[pyo3(signature = (events, path, date, days=1, title=None, utc_offset=None))]
+pub fn export_html_timeline(
+    py: Python<'_>,
+    events: &Bound<'_, PyAny>,
+    path: PathBuf,
+    date: NaiveDate,
+    days: u32,
+    title: Option<String>,
+    utc_offset: Option<i64>,
+) -> PyResult<usize> {
+    if days == 0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("days must be at least 1"));
+    }
+    let mut bounds = Vec::new();
+    for day in date.iter_days().take(days as usize) {
+        let (start, end) = day_bounds(day, utc_offset)?;
+        bounds.push((day, start, end));
+    }
+    let (start, end) = (bounds[0].1, bounds[bounds.len() - 1].2);
+    let filter = global_filter();
+    let mut items = Vec::new();
+    for event in events.try_iter()? {
+        let Some(item) = item(&event?, filter.as_deref())? else {
+            continue;
+        };
+        if item.end < start || item.start >= end {
+            continue;
+        }
+        items.push(item);
+    }
+    items.sort_by(|a, b| a.start.total_cmp(&b.start));
+    let title = title.unwrap_or_else(|| match days {
+        1 => format!("Activity: {}", date.format("%A, %B %-d, %Y")),
+        _ => format!(
+            "Activity: {} – {}",
+            date.format("%B %-d"),
+            bounds[bounds.len() - 1].0.format("%B %-d, %Y")
+        ),
+    });
+    py.detach(|| {
+        std::fs::write(&path, render(&items, &bounds, &title, utc_offset))
+            .map_err(|e| io_error(&path, e))
+    })?;
+    Ok(items.len())
+}
@@ -0,0 +1,235 @@
+use std::path::PathBuf;
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use sha2::{Digest, Sha256};
+
+use crate::collector::now;
+use crate::errors::io_error;
+use crate::privacy::{global_filter, PrivacyFilter};
+use crate::timeutil::epoch_to_datetime;
+
+/// Calendar apps drop or hide zero-length events, so shorter blocks are padded.
+const MIN_EVENT_SECONDS: f64 = 60.0;
+
+/// One activity block as an iCalendar event.
+struct Block {
+    start: f64,
+    end: f64,
+    summary: String,
+    description: Option<String>,
+    location: Option<String>,
+    category: Option<String>,
+    uid: Option<String>,
+}
+
+fn get_str(dict: &Bound<'_, PyDict>, key: &str) -> PyResult<Option<String>> {
+    match dict.get_item(key)? {
+        Some(v) if !v.is_none() => Ok(Some(v.extract()?)),
+        _ => Ok(None),
+    }
+}
+
+fn get_f64(dict: &Bound<'_, PyDict>, key: &str) -> PyResult<Option<f64>> {
+    match dict.get_item(key)? {
+        Some(v) if !v.is_none() => Ok(Some(v.extract()?)),
+        _ => Ok(None),
+    }
+}
+
+/// "coding" on "/Users/me/src/app" → "Coding on app"; "meeting" → "Meeting".
+fn default_summary(kind: Option<&str>, project: Option<&str>) -> String {
+    let name = project.map(|p| p.trim_end_matches('/').rsplit('/').next().unwrap_or(p));
+    let kind = kind.filter(|k| !k.is_empty()).unwrap_or("activity");
+    let kind = kind.replace('_', " ");
+    let mut chars = kind.chars();
+    let label: String =
+        chars.next().into_iter().flat_map(char::to_uppercase).chain(chars).collect();
+    match name {
+        Some(name) if !name.is_empty() => format!("{label} on {name}"),
+        _ => label,
+    }
+}
+
+/// The session as a block, or None if it falls outside what a DATE-TIME can hold.
+fn block(dict: &Bound<'_, PyDict>) -> PyResult<Option<Block>> {
+    let start = match get_f64(dict, "start")? {
+        Some(start) => start,
+        None => get_f64(dict, "timestamp")?
+            .ok_or_else(|| pyo3::exceptions::PyKeyError::new_err("start"))?,
+    };
+    let end = match get_f64(dict, "end")? {
+        Some(end) => end,
+        None => start + get_f64(dict, "duration")?.unwrap_or(0.0),
+    };
+    if !start.is_finite() || !end.is_finite() || end < start {
+        return Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "invalid session times {start}..{end}"
+        )));
+    }
+    let padded = end.max(start + MIN_EVENT_SECONDS);
+    if epoch_to_datetime(start).is_none() || epoch_to_datetime(padded).is_none() {
+        tracing::warn!("skipping session out of range: {start}..{end}");
+        return Ok(None);
+    }
+    let kind = get_str(dict, "kind")?;
+    let project = get_str(dict, "project_path")?;
+    let summary = match get_str(dict, "title")?.or(get_str(dict, "summary")?) {
+        Some(summary) => summary,
+        None => default_summary(kind.as_deref(), project.as_deref()),
+    };
+    let description = match get_str(dict, "description")? {
+        Some(description) => Some(description),
+        None => dict
+            .get_item("event_count")?
+            .filter(|v| !v.is_none())
+            .map(|v| v.extract::<u64>())
+            .transpose()?
+            .map(|n| format!("{n} event{}", if n == 1 { "" } else { "s" })),
+    };
+    Ok(Some(Block {
+        start,
+        end,
+        summary,
+        description,
+        location: get_str(dict, "location")?.or(project),
+        category: kind,
+        uid: get_str(dict, "uid")?,
+    }))
+}
+
+/// The block with its text redacted, or None if the filter denies any of it.
+fn filtered(mut block: Block, filter: &PrivacyFilter) -> Option<Block> {
+    block.summary = filter.content(&block.summary)?.into_owned();
+    for value in [&mut block.description, &mut block.location].into_iter().flatten() {
+        *value = filter.content(value)?.into_owned();
+    }
+    Some(block)
+}
+
+/// RFC 5545 TEXT escaping.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace(['\n', '\r'], "\\n")
+}
+
+/// Append a content line, folded at 75 octets without splitting a character.
+fn push_line(out: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+fn utc(ts: f64) -> String {
+    epoch_to_datetime(ts).map(|dt| dt.format("%Y%m%dT%H%M%SZ").to_string()).unwrap_or_default()
+}
+
+/// A stable UID, so re-exporting the same block updates it rather than adding a copy.
+fn uid(block: &Block) -> String {
+    let digest = Sha256::digest(format!("{}\0{}", block.start, block.summary));
+    let hex: String = digest[..8].iter().map(|b| format!("{b:02x}")).collect();
+    format!("{hex}@snoopy")
+}
+
+fn render(blocks: &[Block], calendar_name: &str) -> String {
+    let mut out = String::new();
+    for line in [
+        "BEGIN:VCALENDAR",
+        "VERSION:2.0",
+        "PRODID:-//snoopy//activity export//EN",
+        "CALSCALE:GREGORIAN",
+    ] {
+        push_line(&mut out, line);
+    }
+    push_line(&mut out, &format!("X-WR-CALNAME:{}", escape(calendar_name)));
+    let stamp = utc(now());
+    for block in blocks {
+        let end = block.end.max(block.start + MIN_EVENT_SECONDS);
+        push_line(&mut out, "BEGIN:VEVENT");
+        push_line(&mut out, &format!("UID:{}", block.uid.as_deref().map_or_else(|| uid(block), escape)));
+        push_line(&mut out, &format!("DTSTAMP:{stamp}"));
+        push_line(&mut out, &format!("DTSTART:{}", utc(block.start)));
+        push_line(&mut out, &format!("DTEND:{}", utc(end)));
+        push_line(&mut out, &format!("SUMMARY:{}", escape(&block.summary)));
+        for (name, value) in [
+            ("DESCRIPTION", &block.description),
+            ("LOCATION", &block.location),
+            ("CATEGORIES", &block.category),
+        ] {
+            if let Some(value) = value {
+                push_line(&mut out, &format!("{name}:{}", escape(value)));
+            }
+        }
+        // Activity shouldn't make the user look busy to anyone reading the calendar.
+        push_line(&mut out, "TRANSP:TRANSPARENT");
+        push_line(&mut out, "END:VEVENT");
+    }
+    push_line(&mut out, "END:VCALENDAR");
+    out
+}
+
+/// Write activity sessions to an iCalendar (.ics) file, one VEVENT each, for
+/// overlaying actual activity on a calendar app. Returns the number written.
+///
+/// Each session is a dict with start and end (epoch seconds; timestamp and
+/// duration also work, so timeline events with a duration can be exported as is)
+/// as returned by sessionize(), plus optional title or summary, kind (e.g.
+/// "coding", "meeting", "messaging"), project_path, description, location and uid.
+/// Without a title, the event is named from kind and project, e.g. "Coding on app".
+/// kind becomes the event's category, project_path its location, and
+/// event_count, when there is no description, becomes one ("42 events"). Events
+/// are marked free (TRANSP:TRANSPARENT) so they don't block time, and padded to a
+/// minute if shorter. UIDs are derived from start and title unless given, so
+/// importing a re-export updates events instead of duplicating them. The installed
+/// privacy filter applies; sessions it denies, and those whose times fall outside
+/// what iCalendar can represent, are left out. Raises ValueError if a session ends
+/// before it starts and IOError if path can't be written.
+#[pyfunction]
+#[pyo3(signature = (sessions, path, calendar_name="snoopy activity"))]
+pub fn export_ics(
+    py: Python<'_>,
+    sessions: &Bound<'_, PyAny>,
+    path: PathBuf,
+    calendar_name: &str,
+) -> PyResult<usize> {
+    let filter = global_filter();
+    let mut blocks = Vec::new();
+    for item in sessions.try_iter()? {
+        let item = item?;
+        let dict = item.cast::<PyDict>()?;
+        let dict = match &filter {
+            Some(f) => match f.apply_dict(dict)? {
+                Some(copy) => copy,
+                None => continue,
+            },
+            None => dict.clone(),
+        };
+        let Some(block) = block(&dict)? else {
+            continue;
+        };
+        let block = match &filter {
+            Some(f) => match filtered(block, f) {
+                Some(block) => block,
+                None => continue,
+            },
+            None => block,
+        };
+        blocks.push(block);
+    }
+    blocks.sort_by(|a, b| a.start.total_cmp(&b.start));
+    py.detach(|| {
+        std::fs::write(&path, render(&blocks, calendar_name))
+            .map_err(|e| io_error(&path, e))
+    })?;
+    Ok(blocks.len())
+}
@@ -0,0 +1,106 @@
+use pyo3::prelude::*;
+use serde_json::json;
+
+use crate::collector::{now, record, Record, Sampler};
+
+/// Seconds since the last keyboard or mouse event in the login session.
+#[cfg(target_os = "macos")]
+fn system_idle() -> Option<f64> {
+    /// kCGEventSourceStateCombinedSessionState
+    const COMBINED_SESSION_STATE: i32 = 0;
+    /// kCGAnyInputEventType
+    const ANY_INPUT_EVENT: u32 = !0;
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGEventSourceSecondsSinceLastEventType(state: i32, event_type: u32) -> f64;
+    }
+
+    // SAFETY: a plain query with no pointers; both arguments are documented constants.
+    let idle =
+        unsafe { CGEventSourceSecondsSinceLastEventType(COMBINED_SESSION_STATE, ANY_INPUT_EVENT) };
+    idle.is_finite().then_some(idle.max(0.0))
+}
+
+/// Seconds since the last input event, from xprintidle (X11 screensaver extension)
+/// or, without a display, the newest access time among /dev/input/event* devices.
+#[cfg(not(target_os = "macos"))]
+fn system_idle() -> Option<f64> {
+    fn xprintidle() -> Option<f64> {
+        let output = std::process::Command::new("xprintidle").output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        let ms: f64 = String::from_utf8_lossy(&output.stdout).trim().parse().ok()?;
+        Some(ms / 1000.0)
+    }
+    fn evdev() -> Option<f64> {
+        let last = std::fs::read_dir("/dev/input")
+            .ok()?
+            .flatten()
+            .filter(|e| e.file_name().to_string_lossy().starts_with("event"))
+            .filter_map(|e| e.metadata().ok()?.accessed().ok())
+            .max()?;
+        Some(last.elapsed().map_or(0.0, |d| d.as_secs_f64()))
+    }
+    xprintidle().or_else(evdev)
+}
+
+/// Emits "idle" when input stops for threshold seconds and "active" when it resumes.
+///
+/// Both events are stamped with the time of the last input, so an idle period runs
+/// from the "idle" event's timestamp to the next "active" event's timestamp.
+pub(crate) struct IdleSampler {
+    threshold: f64,
+    idle_since: Option<f64>,
+}
+
+impl IdleSampler {
+    pub(crate) fn new(threshold: f64) -> Self {
+        IdleSampler {
+            threshold,
+            idle_since: None,
+        }
+    }
+}
+
+impl Sampler for IdleSampler {
+    fn name(&self) -> &'static str {
+        "idle"
+    }
+
+    fn sample(&mut self) -> Result<Vec<Record>, String> {
+        let Some(idle) = system_idle() else {
+            return Ok(Vec::new());
+        };
+        let last_input = now() - idle;
+        let event = match self.idle_since {
+            None if idle >= self.threshold => {
+                self.idle_since = Some(last_input);
+                json!({"timestamp": last_input, "state": "idle", "idle_seconds": idle})
+            }
+            Some(since) if idle < self.threshold => {
+                self.idle_since = None;
+                json!({
+                    "timestamp": last_input,
+                    "state": "active",
+                    "idle_seconds": idle,
+                    "duration": (last_input - since).max(0.0),
+                })
+            }
+            _ => return Ok(Vec::new()),
+        };
+        Ok(vec![record(self.name(), event)])
+    }
+}
+
+/// Seconds since the last keyboard or mouse input, or None if it can't be determined.
+///
+/// Uses CGEventSourceSecondsSinceLastEventType on macOS; on Linux, xprintidle under
+/// X11, falling back to the access times of /dev/input/event* (which needs read
+/// permission on the devices). Collector(sources=["idle"]) turns this into
+/// idle/active transition events.
+#[pyfunction]
+pub fn idle_seconds(py: Python<'_>) -> Option<f64> {
+    py.detach(system_idle)
+}
@@ -0,0 +1,291 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use serde_json::json;
+
+use crate::collector::{now, record, Record, Sampler};
+use crate::frontmost::front_window;
+
+/// Running totals since the listener started: keystrokes, clicks, scroll steps.
+/// Only counts are kept; which key or button was pressed is never recorded.
+static TOTALS: [AtomicU64; 3] = [AtomicU64::new(0), AtomicU64::new(0), AtomicU64::new(0)];
+const KEYS: usize = 0;
+const CLICKS: usize = 1;
+const SCROLLS: usize = 2;
+
+fn bump(counter: usize) {
+    TOTALS[counter].fetch_add(1, Ordering::Relaxed);
+}
+
+fn totals() -> [u64; 3] {
+    [0, 1, 2].map(|i| TOTALS[i].load(Ordering::Relaxed))
+}
+
+/// How long the listener threads wait for input before checking whether to stop.
+const STOP_CHECK: Duration = Duration::from_millis(200);
+
+/// The process-wide listener while anyone uses it: how many do, and the flag that
+/// stops its threads once the last lets go.
+struct Listener {
+    users: usize,
+    stop: Arc<AtomicBool>,
+}
+
+static LISTENER: Mutex<Option<Listener>> = Mutex::new(None);
+
+/// Take a hold on the listener, starting it if nothing holds it yet.
+fn acquire_listener() -> Result<(), String> {
+    let mut listener = LISTENER.lock().unwrap_or_else(|e| e.into_inner());
+    match listener.as_mut() {
+        Some(running) => running.users += 1,
+        None => {
+            let stop = Arc::new(AtomicBool::new(false));
+            // Threads started before a failure stop with the rest.
+            if let Err(e) = start_listener(Arc::clone(&stop)) {
+                stop.store(true, Ordering::Relaxed);
+                return Err(e);
+            }
+            *listener = Some(Listener { users: 1, stop });
+        }
+    }
+    Ok(())
+}
+
+/// Let go of a hold taken with acquire_listener(), stopping the listener if it was
+/// the last.
+fn release_listener() {
+    let mut listener = LISTENER.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(running) = listener.as_mut() {
+        running.users -= 1;
+        if running.users == 0 {
+            running.stop.store(true, Ordering::Relaxed);
+            *listener = None;
+        }
+    }
+}
+
+/// Take a hold on the listener for the rest of the process, once.
+fn hold_listener() -> Result<(), String> {
+    static HELD: Mutex<bool> = Mutex::new(false);
+    let mut held = HELD.lock().unwrap_or_else(|e| e.into_inner());
+    if !*held {
+        acquire_listener()?;
+        *held = true;
+    }
+    Ok(())
+}
+
+/// A listen-only CGEventTap on its own run loop thread, which stops when stop is set.
+/// Creating the tap fails unless the process has Input Monitoring permission.
+#[cfg(target_os = "macos")]
+fn start_listener(stop: Arc<AtomicBool>) -> Result<(), String> {
+    use core_foundation::runloop::{kCFRunLoopCommonModes, kCFRunLoopDefaultMode, CFRunLoop};
+    use core_graphics::event::{
+        CGEventTap, CGEventTapLocation, CGEventTapOptions, CGEventTapPlacement, CGEventType,
+    };
+
+    let (ready, started) = std::sync::mpsc::channel();
+    std::thread::Builder::new()
+        .name("snoopy-input".to_string())
+        .spawn(move || {
+            let tap = CGEventTap::new(
+                CGEventTapLocation::HID,
+                CGEventTapPlacement::TailAppendEventTap,
+                CGEventTapOptions::ListenOnly,
+                vec![
+                    CGEventType::KeyDown,
+                    CGEventType::LeftMouseDown,
+                    CGEventType::RightMouseDown,
+                    CGEventType::OtherMouseDown,
+                    CGEventType::ScrollWheel,
+                ],
+                |_, kind, _| {
+                    match kind {
+                        CGEventType::KeyDown => bump(KEYS),
+                        CGEventType::ScrollWheel => bump(SCROLLS),
+                        CGEventType::LeftMouseDown
+                        | CGEventType::RightMouseDown
+                        | CGEventType::OtherMouseDown => bump(CLICKS),
+                        _ => {}
+                    }
+                    None
+                },
+            );
+            let Ok(tap) = tap else {
+                let _ = ready.send(Err("event tap refused; grant Input Monitoring".to_string()));
+                return;
+            };
+            let Ok(source) = tap.mach_port.create_runloop_source(0) else {
+                let _ = ready.send(Err("event tap has no run loop source".to_string()));
+                return;
+            };
+            // SAFETY: kCFRunLoopCommonModes is an immutable CFString constant.
+            CFRunLoop::get_current().add_source(&source, unsafe { kCFRunLoopCommonModes });
+            tap.enable();
+            let _ = ready.send(Ok(()));
+            while !stop.load(Ordering::Relaxed) {
+                // SAFETY: kCFRunLoopDefaultMode is an immutable CFString constant.
+                CFRunLoop::run_in_mode(unsafe { kCFRunLoopDefaultMode }, STOP_CHECK, false);
+            }
+        })
+        .map_err(|e| format!("input listener: {e}"))?;
+    started.recv().unwrap_or_else(|_| Err("input listener exited".to_string()))
+}
+
+/// One reader thread per readable /dev/input/event* device, decoding the kernel's
+/// struct input_event, until stop is set. Reading the devices needs membership of the
+/// input group.
+#[cfg(not(target_os = "macos"))]
+fn start_listener(stop: Arc<AtomicBool>) -> Result<(), String> {
+    use std::io::Read;
+    use std::os::fd::AsRawFd;
+
+    const EV_KEY: u16 = 1;
+    const EV_REL: u16 = 2;
+    const REL_HWHEEL: u16 = 6;
+    const REL_WHEEL: u16 = 8;
+    /// BTN_LEFT through BTN_TASK: mouse buttons. Lower codes are keyboard keys.
+    const MOUSE_BUTTONS: std::ops::RangeInclusive<u16> = 0x110..=0x117;
+    /// struct timeval (two longs), then u16 type, u16 code and i32 value.
+    const TIME_LEN: usize = 2 * std::mem::size_of::<usize>();
+    const EVENT_LEN: usize = TIME_LEN + 8;
+
+    let entries = std::fs::read_dir("/dev/input").map_err(|e| format!("/dev/input: {e}"))?;
+    let devices: Vec<std::fs::File> = entries
+        .flatten()
+        .filter(|e| e.file_name().to_string_lossy().starts_with("event"))
+        .filter_map(|e| std::fs::File::open(e.path()).ok())
+        .collect();
+    if devices.is_empty() {
+        return Err("no readable /dev/input/event* devices (is the user in the input group?)"
+            .to_string());
+    }
+    for mut device in devices {
+        let stop = Arc::clone(&stop);
+        std::thread::Builder::new()
+            .name("snoopy-input".to_string())
+            .spawn(move || {
+                let mut buf = [0u8; EVENT_LEN];
+                let mut fd =
+                    libc::pollfd { fd: device.as_raw_fd(), events: libc::POLLIN, revents: 0 };
+                let timeout = STOP_CHECK.as_millis() as libc::c_int;
+                while !stop.load(Ordering::Relaxed) {
+                    // SAFETY: fd is a single pollfd for a descriptor device keeps open.
+                    match unsafe { libc::poll(&mut fd, 1, timeout) } {
+                        0 => continue,
+                        n if n > 0 => {}
+                        _ if std::io::Error::last_os_error().kind()
+                            == std::io::ErrorKind::Interrupted =>
+                        {
+                            continue
+                        }
+                        _ => break,
+                    }
+                    if device.read_exact(&mut buf).is_err() {
+                        break;
+                    }
+                    let field = |at: usize| u16::from_ne_bytes([buf[at], buf[at + 1]]);
+                    let (kind, code) = (field(TIME_LEN), field(TIME_LEN + 2));
+                    let value = i32::from_ne_bytes(buf[TIME_LEN + 4..].try_into().unwrap());
+                    match (kind, code) {
+                        // value 1 is a press; 0 is release and 2 autorepeat.
+                        (EV_KEY, c) if value == 1 && MOUSE_BUTTONS.contains(&c) => bump(CLICKS),
+                        (EV_KEY, c) if value == 1 && c < 0x100 => bump(KEYS),
+                        (EV_REL, REL_WHEEL | REL_HWHEEL) => bump(SCROLLS),
+                        _ => {}
+                    }
+                }
+            })
+            .map_err(|e| format!("input listener: {e}"))?;
+    }
+    Ok(())
+}
+
+/// Per-minute keystroke, click and scroll counts, attributed to the app that was
+/// frontmost when each poll's counts were read.
+///
+/// A minute is emitted once it is over, as {timestamp (minute start), duration,
+/// app, keystrokes, clicks, scrolls}; counts still pending when the collector stops
+/// are not reported. The "app" field lets the privacy filter's app lists drop counts
+/// for denied apps before they leave Rust. The sampler holds the listener from its
+/// first poll until the collector stops and drops it.
+#[derive(Default)]
+pub(crate) struct InputSampler {
+    listening: bool,
+    seen: Option<[u64; 3]>,
+    minutes: BTreeMap<(i64, Option<String>), [u64; 3]>,
+}
+
+impl Drop for InputSampler {
+    fn drop(&mut self) {
+        if self.listening {
+            release_listener();
+        }
+    }
+}
+
+impl Sampler for InputSampler {
+    fn name(&self) -> &'static str {
+        "input"
+    }
+
+    fn sample(&mut self) -> Result<Vec<Record>, String> {
+        if !self.listening {
+            acquire_listener()?;
+            self.listening = true;
+        }
+        let current = totals();
+        let seen = self.seen.replace(current).unwrap_or(current);
+        let delta = [0, 1, 2].map(|i| current[i] - seen[i]);
+        let minute = (now() / 60.0).floor() as i64;
+        if delta.iter().any(|&n| n > 0) {
+            let app = front_window().map(|w| w.app);
+            let counts = self.minutes.entry((minute, app)).or_default();
+            for (count, n) in counts.iter_mut().zip(delta) {
+                *count += n;
+            }
+        }
+
+        let pending = self.minutes.split_off(&(minute, None));
+        let done = std::mem::replace(&mut self.minutes, pending);
+        Ok(done
+            .into_iter()
+            .map(|((minute, app), counts)| {
+                record(
+                    self.name(),
+                    json!({
+                        "timestamp": (minute * 60) as f64,
+                        "duration": 60.0,
+                        "app": app,
+                        "keystrokes": counts[KEYS],
+                        "clicks": counts[CLICKS],
+                        "scrolls": counts[SCROLLS],
+                    }),
+                )
+            })
+            .collect())
+    }
+}
+
+/// Keystrokes, clicks and scroll steps counted since the input listener started,
+/// as {keystrokes, clicks, scrolls}.
+///
+/// The first call starts the listener, which then runs until the process exits, so it
+/// returns zeros. Only counts are kept, never which keys were pressed. Needs Input
+/// Monitoring permission on macOS and read access to /dev/input/event* on Linux;
+/// raises RuntimeError without it. Collector(sources=["input"]) reports the counts
+/// per minute and frontmost app.
+#[pyfunction]
+pub fn input_counts(py: Python<'_>) -> PyResult<Bound<'_, PyDict>> {
+    py.detach(hold_listener).map_err(pyo3::exceptions::PyRuntimeError::new_err)?;
+    let counts = totals();
+    let dict = PyDict::new(py);
+    dict.set_item("keystrokes", counts[KEYS])?;
+    dict.set_item("clicks", counts[CLICKS])?;
+    dict.set_item("scrolls", counts[SCROLLS])?;
+    Ok(dict)
+}
@@ -0,0 +1,192 @@
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use chrono::{DateTime, NaiveDateTime};
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+use regex::Regex;
+use serde_json::{json, Value};
+
+use crate::collector::{record, records_to_py, Record};
+use crate::errors::io_error;
+use crate::history::sort_by_timestamp;
+
+const INSTALL_LOG: &str = "/var/log/install.log";
+const BREW_PREFIXES: [&str; 3] = ["/opt/homebrew", "/usr/local", "/home/linuxbrew/.linuxbrew"];
+
+/// "2024-03-01 09:15:02-08 host installd[612]: PackageKit: Installed "Name" (1.2)".
+fn log_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(concat!(
+            r"^(\d{4}-\d\d-\d\d \d\d:\d\d:\d\d)([+-]\d\d(?::?\d\d)?) \S+ ([\w.]+)\[\d+\]: ",
+            r#".*?Installed "([^"]+)" \(([^)]*)\)"#,
+        ))
+        .unwrap()
+    })
+}
+
+fn install(
+    timestamp: f64,
+    installer: &str,
+    kind: &str,
+    package: &str,
+    version: Option<&str>,
+) -> Record {
+    let summary = match version {
+        Some(v) => format!("installed {package} {v}"),
+        None => format!("installed {package}"),
+    };
+    record(
+        "install",
+        json!({
+            "timestamp": timestamp,
+            "installer": installer,
+            "kind": kind,
+            "package": package,
+            "version": version,
+            "summary": summary,
+        }),
+    )
+}
+
+/// Installations from install.log text, oldest first.
+pub(crate) fn parse_log(text: &str, since: f64) -> Vec<Record> {
+    let mut events = Vec::new();
+    for caps in text.lines().filter_map(|l| log_re().captures(l)) {
+        // The log writes the offset as hours only ("-08"); chrono wants minutes too.
+        let offset = caps[2].replace(':', "");
+        let offset = if offset.len() == 3 { format!("{offset}00") } else { offset };
+        let Ok(ts) =
+            DateTime::parse_from_str(&format!("{} {offset}", &caps[1]), "%Y-%m-%d %H:%M:%S %z")
+        else {
+            continue;
+        };
+        let ts = ts.timestamp() as f64;
+        if ts <= since {
+            continue;
+        }
+        let version = Some(caps[5].trim()).filter(|v| !v.is_empty());
+        events.push(install(ts, &caps[3], "system", &caps[4], version));
+    }
+    events
+}
+
+/// Formulae from each Cellar/<name>/<version>/INSTALL_RECEIPT.json.
+fn formula_installs(prefix: &Path, since: f64, events: &mut Vec<Record>) {
+    let Ok(formulae) = std::fs::read_dir(prefix.join("Cellar")) else { return };
+    for formula in formulae.flatten() {
+        let name = formula.file_name().to_string_lossy().into_owned();
+        let Ok(versions) = std::fs::read_dir(formula.path()) else { continue };
+        for version in versions.flatten() {
+            let Ok(text) = std::fs::read_to_string(version.path().join("INSTALL_RECEIPT.json"))
+            else {
+                continue;
+            };
+            let Ok(receipt) = serde_json::from_str::<Value>(&text) else { continue };
+            let Some(ts) = receipt["time"].as_f64().filter(|t| *t > since) else { continue };
+            let version = version.file_name().to_string_lossy().into_owned();
+            let mut event = install(ts, "homebrew", "formula", &name, Some(&version));
+            event.insert("on_request".to_string(), receipt["installed_on_request"].clone());
+            event.insert("tap".to_string(), receipt["source"]["tap"].clone());
+            events.push(event);
+        }
+    }
+}
+
+/// Casks from Caskroom/<name>/.metadata/<version>/<YYYYMMDDhhmmss.fff>/, whose
+/// directory name is the install time in UTC.
+fn cask_installs(prefix: &Path, since: f64, events: &mut Vec<Record>) {
+    let Ok(casks) = std::fs::read_dir(prefix.join("Caskroom")) else { return };
+    for cask in casks.flatten() {
+        let name = cask.file_name().to_string_lossy().into_owned();
+        let Ok(versions) = std::fs::read_dir(cask.path().join(".metadata")) else { continue };
+        for version in versions.flatten() {
+            let Ok(stamps) = std::fs::read_dir(version.path()) else { continue };
+            let version = version.file_name().to_string_lossy().into_owned();
+            for stamp in stamps.flatten() {
+                let stamp = stamp.file_name().to_string_lossy().into_owned();
+                let Ok(at) = NaiveDateTime::parse_from_str(&stamp, "%Y%m%d%H%M%S%.f") else {
+                    continue;
+                };
+                let ts = at.and_utc().timestamp_millis() as f64 / 1000.0;
+                if ts > since {
+                    let mut event = install(ts, "homebrew", "cask", &name, Some(&version));
+                    event.insert("on_request".to_string(), Value::Bool(true));
+                    event.insert("tap".to_string(), Value::Null);
+                    events.push(event);
+                }
+            }
+        }
+    }
+}
+
+/// Parse macOS install.log text into "installed package" events, oldest first.
+///
+/// Each line saying Installed "Name" (version) becomes {source: "install",
+/// timestamp, installer (the logging process, e.g. "installd" or
+/// "softwareupdated"), kind: "system", package, version, summary}. Only events
+/// after since (epoch seconds) are returned.
+#[pyfunction]
+#[pyo3(signature = (text, since=None))]
+pub fn parse_install_log<'py>(
+    py: Python<'py>,
+    text: &str,
+    since: Option<f64>,
+) -> PyResult<Bound<'py, PyList>> {
+    let since = since.unwrap_or(f64::NEG_INFINITY);
+    let events = py.detach(|| parse_log(text, since));
+    records_to_py(py, events)
+}
+
+/// Read /var/log/install.log (or path) and parse it with parse_install_log.
+///
+/// Raises IOError if the log can't be read.
+#[pyfunction]
+#[pyo3(signature = (path=None, since=None))]
+pub fn install_log_events(
+    py: Python<'_>,
+    path: Option<PathBuf>,
+    since: Option<f64>,
+) -> PyResult<Bound<'_, PyList>> {
+    let path = path.unwrap_or_else(|| PathBuf::from(INSTALL_LOG));
+    let since = since.unwrap_or(f64::NEG_INFINITY);
+    let events = py.detach(|| {
+        let bytes = std::fs::read(&path).map_err(|e| io_error(&path, e))?;
+        Ok::<_, PyErr>(parse_log(&String::from_utf8_lossy(&bytes), since))
+    })?;
+    records_to_py(py, events)
+}
+
+/// Homebrew installs from the receipts under the Cellar and Caskroom, oldest first.
+///
+/// Each installed formula version and cask is {source: "install", timestamp,
+/// installer: "homebrew", kind ("formula" or "cask"), package, version, on_request,
+/// tap, summary}, matching install_log_events so both feed one timeline.
+/// on_request is False for formulae pulled in as dependencies. prefix defaults to
+/// every standard Homebrew prefix that exists (/opt/homebrew, /usr/local,
+/// /home/linuxbrew/.linuxbrew). Only installs after since (epoch seconds) are
+/// returned.
+#[pyfunction]
+#[pyo3(signature = (prefix=None, since=None))]
+pub fn homebrew_installs(
+    py: Python<'_>,
+    prefix: Option<PathBuf>,
+    since: Option<f64>,
+) -> PyResult<Bound<'_, PyList>> {
+    let prefixes = match prefix {
+        Some(p) => vec![p],
+        None => BREW_PREFIXES.iter().map(PathBuf::from).collect(),
+    };
+    let since = since.unwrap_or(f64::NEG_INFINITY);
+    let events = py.detach(|| {
+        let mut events = Vec::new();
+        for prefix in &prefixes {
+            formula_installs(prefix, since, &mut events);
+            cask_installs(prefix, since, &mut events);
+        }
+        sort_by_timestamp(&mut events);
+        events
+    });
+    records_to_py(py, events)
+}
@@ -0,0 +1,181 @@
+use std::collections::BTreeMap;
+
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+
+use crate::sessions::sessionize_impl;
+use crate::timeline::timestamp_of;
+
+/// Sources whose incoming events count as interruptions by default.
+const DEFAULT_SOURCES: [&str; 4] = ["messages", "slack", "mail", "notifications"];
+
+/// Fields naming who or what interrupted, in order of preference.
+const FROM_FIELDS: [&str; 5] = ["contact", "sender_name", "sender", "channel", "app"];
+
+/// Transcript message types that show the user is back at the agent.
+const USER_TYPES: [&str; 2] = ["user", "user_interrupt"];
+
+struct Interruption {
+    timestamp: f64,
+    source: String,
+    from: Option<String>,
+    /// Index of the coding session it fell in.
+    session: usize,
+    refocus: Option<f64>,
+}
+
+fn text(dict: &Bound<'_, PyDict>, key: &str) -> PyResult<Option<String>> {
+    match dict.get_item(key)? {
+        Some(v) if !v.is_none() => Ok(Some(v.str()?.to_string())),
+        _ => Ok(None),
+    }
+}
+
+/// Whether a message event is one the user sent rather than received.
+fn outgoing(event: &Bound<'_, PyDict>) -> PyResult<bool> {
+    let from_me = match event.get_item("is_from_me")? {
+        Some(v) if !v.is_none() => v.is_truthy()?,
+        _ => false,
+    };
+    Ok(from_me || text(event, "kind")?.as_deref() == Some("sent"))
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
+
+fn median(values: &[f64]) -> Option<f64> {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let n = sorted.len();
+    match n {
+        0 => None,
+        _ if n % 2 == 1 => Some(sorted[n / 2]),
+        _ => Some((sorted[n / 2 - 1] + sorted[n / 2]) / 2.0),
+    }
+}
+
+/// How often messages and notifications break into coding sessions, and how long
+/// it takes to get back.
+///
+/// events is one stream of dicts from several sources, such as Collector records
+/// or merge_timelines() over parsed transcripts and message exports, in any order;
+/// timestamps may be epoch seconds or datetimes. Transcript events (source
+/// "claude", or no source with a message_type) make up the coding sessions, split
+/// where they pause for more than gap_seconds. An interruption is an event from
+/// one of sources (by default "messages", "slack", "mail" and "notifications") that
+/// arrives during a session and that the user didn't send themselves; its
+/// refocus time runs to the user's next prompt to the agent, or is None if none
+/// came within refocus_timeout seconds. Returns {interruptions, refocused,
+/// mean_refocus_seconds, median_refocus_seconds, lost_seconds (refocus time
+/// summed), by_source: {source: {interruptions, mean_refocus_seconds}}, sessions:
+/// [{start, end, duration, interruptions, lost_seconds}], events: [{timestamp,
+/// source, from, session_start, refocus_seconds}]}.
+#[pyfunction]
+#[pyo3(signature = (events, sources=None, gap_seconds=300.0, refocus_timeout=1800.0))]
+pub fn interruption_stats<'py>(
+    py: Python<'py>,
+    events: &Bound<'py, PyAny>,
+    sources: Option<Vec<String>>,
+    gap_seconds: f64,
+    refocus_timeout: f64,
+) -> PyResult<Bound<'py, PyDict>> {
+    let sources =
+        sources.unwrap_or_else(|| DEFAULT_SOURCES.iter().map(|s| s.to_string()).collect());
+    let mut coding = Vec::new();
+    let mut prompts = Vec::new();
+    let mut candidates: Vec<(f64, String, Option<String>)> = Vec::new();
+    for item in events.try_iter()? {
+        let item = item?;
+        let Ok(event) = item.cast::<PyDict>() else {
+            continue;
+        };
+        let source = text(event, "source")?;
+        let message_type = text(event, "message_type")?;
+        let is_transcript = match source.as_deref() {
+            Some(s) => s == "claude",
+            None => message_type.is_some(),
+        };
+        if is_transcript {
+            let ts = timestamp_of(&item)?;
+            coding.push(ts);
+            if message_type.as_deref().is_some_and(|t| USER_TYPES.contains(&t)) {
+                prompts.push(ts);
+            }
+        } else if let Some(source) = source.filter(|s| sources.contains(s)) {
+            if outgoing(event)? {
+                continue;
+            }
+            let mut from = None;
+            for key in FROM_FIELDS {
+                from = text(event, key)?;
+                if from.is_some() {
+                    break;
+                }
+            }
+            candidates.push((timestamp_of(&item)?, source, from));
+        }
+    }
+
+    let sessions = sessionize_impl(&coding, gap_seconds);
+    prompts.sort_by(f64::total_cmp);
+    candidates.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let mut interruptions = Vec::new();
+    for (ts, source, from) in candidates {
+        let Some(session) = sessions.iter().position(|s| s.start <= ts && ts <= s.end) else {
+            continue;
+        };
+        let next = prompts.partition_point(|&p| p <= ts);
+        let refocus = prompts.get(next).map(|&p| p - ts).filter(|&delay| delay <= refocus_timeout);
+        interruptions.push(Interruption { timestamp: ts, source, from, session, refocus });
+    }
+
+    let delays: Vec<f64> = interruptions.iter().filter_map(|i| i.refocus).collect();
+    let out = PyDict::new(py);
+    out.set_item("interruptions", interruptions.len())?;
+    out.set_item("refocused", delays.len())?;
+    out.set_item("mean_refocus_seconds", mean(&delays))?;
+    out.set_item("median_refocus_seconds", median(&delays))?;
+    out.set_item("lost_seconds", delays.iter().sum::<f64>())?;
+
+    let mut by_source: BTreeMap<&str, (u64, Vec<f64>)> = BTreeMap::new();
+    for i in &interruptions {
+        let entry = by_source.entry(i.source.as_str()).or_default();
+        entry.0 += 1;
+        entry.1.extend(i.refocus);
+    }
+    let sources_dict = PyDict::new(py);
+    for (source, (count, delays)) in by_source {
+        let dict = PyDict::new(py);
+        dict.set_item("interruptions", count)?;
+        dict.set_item("mean_refocus_seconds", mean(&delays))?;
+        sources_dict.set_item(source, dict)?;
+    }
+    out.set_item("by_source", sources_dict)?;
+
+    let session_list = PyList::empty(py);
+    for (n, s) in sessions.iter().enumerate() {
+        let mine = || interruptions.iter().filter(|i| i.session == n);
+        let dict = PyDict::new(py);
+        dict.set_item("start", s.start)?;
+        dict.set_item("end", s.end)?;
+        dict.set_item("duration", s.duration())?;
+        dict.set_item("interruptions", mine().count())?;
+        dict.set_item("lost_seconds", mine().filter_map(|i| i.refocus).sum::<f64>())?;
+        session_list.append(dict)?;
+    }
+    out.set_item("sessions", session_list)?;
+
+    let event_list = PyList::empty(py);
+    for i in &interruptions {
+        let dict = PyDict::new(py);
+        dict.set_item("timestamp", i.timestamp)?;
+        dict.set_item("source", &i.source)?;
+        dict.set_item("from", &i.from)?;
+        dict.set_item("session_start", sessions[i.session].start)?;
+        dict.set_item("refocus_seconds", i.refocus)?;
+        event_list.append(dict)?;
+    }
+    out.set_item("events", event_list)?;
+    Ok(out)
+}
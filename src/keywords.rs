@@ -0,0 +1,298 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use rayon::prelude::*;
+use regex::Regex;
+use serde_json::Value;
+
+use crate::aggregate::FIRST_MONDAY;
+use crate::extract_content;
+use crate::privacy::{global_filter, PrivacyFilter};
+use crate::rollup::{decode_project_dir, find_jsonl_files};
+use crate::timeutil::parse_iso_ts;
+
+/// Words too common in prompts and replies to say what the work was about.
+const STOP_WORDS: &[&str] = &[
+    "about", "above", "after", "again", "all", "also", "and", "any", "are", "because", "been",
+    "before", "being", "below", "between", "both", "but", "can", "could", "did", "does", "doing",
+    "done", "down", "each", "either", "else", "etc", "even", "every", "few", "for", "from",
+    "further", "get", "gets", "getting", "going", "good", "got", "great", "had", "has", "have",
+    "having", "her", "here", "hers", "him", "his", "how", "however", "into", "its", "itself",
+    "just", "know", "let", "like", "look", "looks", "make", "makes", "many", "may", "might",
+    "more", "most", "much", "must", "need", "needs", "new", "not", "now", "off", "okay", "once",
+    "one", "only", "other", "our", "ours", "out", "over", "own", "perfect", "please", "really",
+    "same", "see", "she", "should", "some", "still", "such", "sure", "than", "thank", "thanks",
+    "that", "the", "their", "theirs", "them", "then", "there", "these", "they", "thing", "things",
+    "think", "this", "those", "through", "too", "two", "under", "until", "use", "used", "uses",
+    "using", "very", "want", "was", "way", "well", "were", "what", "when", "where", "whether",
+    "which", "while", "who", "whom", "why", "will", "with", "without", "work", "would", "yes",
+    "yet", "you", "your", "yours",
+];
+
+fn word_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"[A-Za-z][\w'’]*").unwrap())
+}
+
+/// Fenced code blocks, left out so the terms describe the conversation rather than
+/// the syntax of the code pasted into it.
+fn fence_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?s)```.*?(?:```|$)").unwrap())
+}
+
+/// Add text's terms to counts: lowercased words of 3 to 40 characters, skipping stop
+/// words and contractions.
+fn count_terms(text: &str, counts: &mut HashMap<String, u64>) {
+    let prose = fence_re().replace_all(text, " ");
+    for word in word_re().find_iter(&prose).map(|m| m.as_str()) {
+        if word.len() < 3 || word.len() > 40 || word.contains(['\'', '’']) {
+            continue;
+        }
+        let word = word.to_lowercase();
+        if STOP_WORDS.binary_search(&word.as_str()).is_err() {
+            *counts.entry(word).or_default() += 1;
+        }
+    }
+}
+
+/// The text of a user prompt or assistant reply, or None for tool traffic, meta
+/// entries and command wrappers.
+fn message_text(entry: &Value) -> Option<String> {
+    let flag = |name: &str| entry.get(name).and_then(Value::as_bool).unwrap_or(false);
+    let kind = entry.get("type").and_then(Value::as_str)?;
+    if !matches!(kind, "user" | "assistant") || flag("isMeta") || flag("isCompactSummary") {
+        return None;
+    }
+    let text = extract_content(&entry["message"]);
+    let trimmed = text.trim();
+    (!trimmed.is_empty()
+        && !trimmed.starts_with('<')
+        && !trimmed.starts_with("This session is being continued"))
+    .then_some(text)
+}
+
+#[derive(Clone, Copy)]
+enum Period {
+    Day,
+    Week,
+    All,
+}
+
+impl Period {
+    /// Start of ts's period in epoch seconds, with days starting at local midnight.
+    fn start(self, ts: f64, utc_offset: i64) -> Option<i64> {
+        let local = ts.floor() as i64 + utc_offset;
+        match self {
+            Period::Day => Some(local.div_euclid(86400) * 86400 - utc_offset),
+            Period::Week => Some(
+                (local - FIRST_MONDAY).div_euclid(7 * 86400) * 7 * 86400 + FIRST_MONDAY
+                    - utc_offset,
+            ),
+            Period::All => None,
+        }
+    }
+}
+
+/// The messages of one group and period, treated as one document.
+#[derive(Default)]
+struct Document {
+    messages: u64,
+    counts: HashMap<String, u64>,
+}
+
+impl Document {
+    fn merge(&mut self, other: Document) {
+        self.messages += other.messages;
+        for (term, n) in other.counts {
+            *self.counts.entry(term).or_default() += n;
+        }
+    }
+}
+
+type DocKey = (String, Option<i64>);
+
+fn scan_file(
+    path: &Path,
+    by_project: bool,
+    period: Period,
+    utc_offset: i64,
+    since: Option<f64>,
+    filter: Option<&PrivacyFilter>,
+) -> Vec<(DocKey, Document)> {
+    let mut project = path
+        .parent()
+        .and_then(|p| p.file_name())
+        .and_then(|n| n.to_str())
+        .map(decode_project_dir)
+        .unwrap_or_default();
+    let file = match File::open(path) {
+        Ok(f) => f,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut project_from_cwd = false;
+    let mut docs: BTreeMap<Option<i64>, Document> = BTreeMap::new();
+    for line in BufReader::new(file).lines() {
+        let line = match line {
+            Ok(l) => l,
+            Err(_) => break,
+        };
+        let entry: Value = match serde_json::from_str(line.trim()) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        if !project_from_cwd {
+            if let Some(cwd) = entry.get("cwd").and_then(Value::as_str) {
+                project = cwd.to_string();
+                project_from_cwd = true;
+            }
+        }
+        let Some(text) = message_text(&entry) else {
+            continue;
+        };
+        // Redacted text is counted without its placeholder, denied text not at all.
+        let text = match filter {
+            Some(f) if !f.allows_project(&project) => continue,
+            Some(f) => match f.content(&text) {
+                Some(kept) => kept.replace("[REDACTED]", " "),
+                None => continue,
+            },
+            None => text,
+        };
+        let Some(ts) = entry.get("timestamp").and_then(Value::as_str).and_then(parse_iso_ts) else {
+            continue;
+        };
+        if since.is_some_and(|s| ts < s) {
+            continue;
+        }
+        let doc = docs.entry(period.start(ts, utc_offset)).or_default();
+        doc.messages += 1;
+        count_terms(&text, &mut doc.counts);
+    }
+    let key = if by_project {
+        project
+    } else {
+        path.file_stem().and_then(|s| s.to_str()).unwrap_or("").to_string()
+    };
+    docs.into_iter().map(|(start, doc)| ((key.clone(), start), doc)).collect()
+}
+
+/// The top_n terms of each document by TF-IDF, with their scores and counts.
+fn top_terms(docs: &BTreeMap<DocKey, Document>, top_n: usize) -> Vec<Vec<(&str, f64, u64)>> {
+    let mut df: HashMap<&str, u64> = HashMap::new();
+    for doc in docs.values() {
+        for term in doc.counts.keys() {
+            *df.entry(term.as_str()).or_default() += 1;
+        }
+    }
+    let n = docs.len() as f64;
+    docs.values()
+        .map(|doc| {
+            let total = doc.counts.values().sum::<u64>().max(1) as f64;
+            let mut scored: Vec<(&str, f64, u64)> = doc
+                .counts
+                .iter()
+                .map(|(term, &count)| {
+                    let idf = ((1.0 + n) / (1.0 + df[term.as_str()] as f64)).ln() + 1.0;
+                    (term.as_str(), count as f64 / total * idf, count)
+                })
+                .collect();
+            scored.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+            scored.truncate(top_n);
+            scored
+        })
+        .collect()
+}
+
+/// The terms that best describe each project's or session's conversations, per
+/// week or day, by TF-IDF over the user's prompts and the agent's replies.
+///
+/// path is a transcript file or a directory searched recursively. group_by is
+/// "session" or "project"; period is "week" (starting Monday), "day" or "all", with
+/// utc_offset (seconds) aligning boundaries to local midnight; since (epoch seconds)
+/// skips earlier messages. Each group's messages in a period form one document,
+/// scored against all the others, so terms every project uses rank below the ones
+/// particular to this one. Tool calls and results, meta entries, command wrappers
+/// and fenced code blocks are left out, as are stop words, contractions and words
+/// under three letters. Everything runs locally. Returns {group: [{period_start
+/// (None for "all"), messages, terms: [{term, score, count}]}]}, periods in order
+/// and at most top_n terms each, best first. Under an installed privacy filter,
+/// messages it denies don't count and redacted text doesn't become terms.
+#[pyfunction]
+#[pyo3(signature = (path, group_by="project", period="week", top_n=10, utc_offset=0, since=None))]
+pub fn transcript_keywords<'py>(
+    py: Python<'py>,
+    path: &str,
+    group_by: &str,
+    period: &str,
+    top_n: usize,
+    utc_offset: i64,
+    since: Option<f64>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let by_project = match group_by {
+        "session" => false,
+        "project" => true,
+        _ => {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "group_by must be 'session' or 'project', got {group_by:?}"
+            )))
+        }
+    };
+    let period = match period {
+        "day" => Period::Day,
+        "week" => Period::Week,
+        "all" => Period::All,
+        _ => {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "period must be 'day', 'week' or 'all', got {period:?}"
+            )))
+        }
+    };
+    let root = PathBuf::from(path);
+    let filter = global_filter();
+    let docs = py.detach(|| {
+        let files = if root.is_dir() { find_jsonl_files(&root) } else { vec![root.clone()] };
+        let per_file: Vec<Vec<(DocKey, Document)>> = files
+            .par_iter()
+            .map(|f| scan_file(f, by_project, period, utc_offset, since, filter.as_deref()))
+            .collect();
+        let mut docs: BTreeMap<DocKey, Document> = BTreeMap::new();
+        for (key, doc) in per_file.into_iter().flatten() {
+            docs.entry(key).or_default().merge(doc);
+        }
+        docs
+    });
+    let terms = top_terms(&docs, top_n);
+
+    let out = PyDict::new(py);
+    for (((group, start), doc), terms) in docs.iter().zip(terms) {
+        let list = match out.get_item(group)? {
+            Some(list) => list.cast_into::<PyList>()?,
+            None => {
+                let list = PyList::empty(py);
+                out.set_item(group, &list)?;
+                list
+            }
+        };
+        let dict = PyDict::new(py);
+        dict.set_item("period_start", start)?;
+        dict.set_item("messages", doc.messages)?;
+        let term_list = PyList::empty(py);
+        for (term, score, count) in terms {
+            let t = PyDict::new(py);
+            t.set_item("term", term)?;
+            t.set_item("score", score)?;
+            t.set_item("count", count)?;
+            term_list.append(t)?;
+        }
+        dict.set_item("terms", term_list)?;
+        list.append(dict)?;
+    }
+    Ok(out)
+}
@@ -0,0 +1,80 @@
+use std::path::PathBuf;
+
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+use rusqlite::Connection;
+use serde_json::json;
+
+use crate::collector::{home_path, record, records_to_py, Record};
+use crate::history::with_snapshot;
+
+/// CoreDuet stores times as seconds since 2001-01-01 (Cocoa reference date).
+const COCOA_EPOCH_OFFSET: f64 = 978_307_200.0;
+pub(crate) const SYSTEM_DB: &str = "/private/var/db/CoreDuet/Knowledge/knowledgeC.db";
+const USER_DB: &str = "Library/Application Support/Knowledge/knowledgeC.db";
+const DEFAULT_STREAMS: [&str; 2] = ["/app/usage", "/app/inFocus"];
+
+fn read_streams(
+    conn: &Connection,
+    streams: &[String],
+    since: f64,
+) -> rusqlite::Result<Vec<Record>> {
+    let placeholders = vec!["?"; streams.len()].join(", ");
+    let mut stmt = conn.prepare(&format!(
+        "SELECT ZSTREAMNAME, ZVALUESTRING, ZSTARTDATE, ZENDDATE
+         FROM ZOBJECT
+         WHERE ZSTREAMNAME IN ({placeholders}) AND ZENDDATE > ?
+         ORDER BY ZSTARTDATE, Z_PK"
+    ))?;
+    let mut params: Vec<rusqlite::types::Value> =
+        streams.iter().map(|s| s.clone().into()).collect();
+    params.push((since - COCOA_EPOCH_OFFSET).into());
+    let rows = stmt.query_map(rusqlite::params_from_iter(params), |row| {
+        let start = row.get::<_, f64>(2)? + COCOA_EPOCH_OFFSET;
+        let end = row.get::<_, Option<f64>>(3)?.map_or(start, |e| e + COCOA_EPOCH_OFFSET);
+        Ok(record(
+            "screentime",
+            json!({
+                "stream": row.get::<_, String>(0)?,
+                "bundle_id": row.get::<_, Option<String>>(1)?,
+                "timestamp": start,
+                "end": end,
+                "duration": (end - start).max(0.0),
+            }),
+        ))
+    })?;
+    rows.collect()
+}
+
+/// Read per-app usage intervals from the Screen Time (CoreDuet) knowledgeC.db.
+///
+/// Returns {source: "screentime", stream, bundle_id, timestamp, end, duration}
+/// intervals oldest first, with times converted from Cocoa to epoch seconds.
+/// streams defaults to "/app/usage" and "/app/inFocus"; other streams such as
+/// "/display/isBacklit" can be requested by name. path defaults to the system
+/// database (which needs Full Disk Access), falling back to the per-user one. Only
+/// intervals ending after since (epoch seconds) are returned.
+#[pyfunction]
+#[pyo3(signature = (path=None, since=None, streams=None))]
+pub fn screen_time_usage<'py>(
+    py: Python<'py>,
+    path: Option<PathBuf>,
+    since: Option<f64>,
+    streams: Option<Vec<String>>,
+) -> PyResult<Bound<'py, PyList>> {
+    let path = path.unwrap_or_else(|| {
+        let system = PathBuf::from(SYSTEM_DB);
+        if std::fs::File::open(&system).is_ok() {
+            system
+        } else {
+            home_path(USER_DB)
+        }
+    });
+    let streams = streams.unwrap_or_else(|| DEFAULT_STREAMS.map(String::from).to_vec());
+    if streams.is_empty() {
+        return Ok(PyList::empty(py));
+    }
+    let since = since.unwrap_or(f64::NEG_INFINITY);
+    let events = py.detach(|| with_snapshot(&path, |conn| read_streams(conn, &streams, since)))?;
+    records_to_py(py, events)
+}
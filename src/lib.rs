@@ -1,18 +1,113 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
 
 use memchr::memmem;
 use pyo3::prelude::*;
-use pyo3::types::{PyDict, PyList, PySet, PyTuple};
+use pyo3::intern;
+use pyo3::types::{PyDict, PyList, PySet, PyString, PyTuple};
 use regex::Regex;
 
+mod activity;
+mod aggregate;
+mod aio;
+mod anonymize;
+mod audit;
+mod bench;
+mod buffer;
+mod calendar;
+mod collector;
+mod compress;
+mod correlate;
+mod crypto;
+mod dedup;
+mod dns;
+mod downloads;
+mod errors;
+mod eslogger;
+mod failures;
+mod frontmost;
+mod fsactivity;
+mod fuzzy;
+mod gitinfer;
+mod gitscan;
+mod history;
+mod html;
+mod ics;
+mod idle;
+mod input;
+mod interruptions;
+mod installs;
+mod keywords;
+mod knowledge;
+mod lineage;
+mod links;
+mod loc;
+mod logging;
+mod logins;
+mod mail;
+mod meetings;
+mod narrate;
+mod notes;
+mod otp;
+mod parserconfig;
+mod permissions;
+mod persistence;
+mod pipeline;
+mod power;
+mod preflight;
+mod privacy;
+mod processes;
+mod reminders;
+mod replay;
+mod report;
+mod rewrite;
+mod rollup;
+mod screenshot;
+mod sessions;
+mod shell;
+mod shutdown;
+mod simhash;
+mod sink;
+mod slack;
+mod snapshot;
+mod spotlight;
+mod ssh;
+mod tcc;
+mod timeline;
+mod timemachine;
+mod timeutil;
+mod title;
+mod tmux;
+mod tokens;
+mod training;
+mod transcripts;
+mod unifiedlog;
+mod usage;
+mod usb;
+mod vscode;
+mod watcher;
+mod whatsapp;
+mod wifi;
+mod xcode;
+
+use errors::ReadError;
+use parserconfig::{ParserConfig, TimeFormat};
+use timeutil::parse_iso_ts;
+use usage::Usage;
+
 /// Extract plain text from an NSArchiver attributedBody blob.
 ///
-/// Scans for b"NSString" marker, then b"\x01+", reads length byte, slices UTF-8 text.
+/// blob is bytes or any other buffer (bytearray, memoryview, mmap), read in place.
 #[pyfunction]
-fn extract_attributed_body_text(blob: &[u8]) -> String {
+fn extract_attributed_body_text(blob: &Bound<'_, PyAny>) -> PyResult<String> {
+    buffer::with_bytes(blob, attributed_body_text)
+}
+
+/// Scans for b"NSString" marker, then b"\x01+", reads length byte, slices UTF-8 text.
+fn attributed_body_text(blob: &[u8]) -> String {
     if blob.is_empty() {
         return String::new();
     }
@@ -56,9 +151,8 @@ fn lsof_regex() -> &'static Regex {
     })
 }
 
-/// Parse lsof -i -P -n output into a set of (process_name, remote_ip, remote_port) tuples.
-#[pyfunction]
-fn parse_lsof_output<'py>(py: Python<'py>, output: &str) -> PyResult<Bound<'py, PySet>> {
+/// Established TCP connections in lsof -i -P -n output as (process_name, remote_ip, remote_port).
+fn lsof_connections(output: &str) -> HashSet<(String, String, u16)> {
     let re = lsof_regex();
 
     let mut set = HashSet::new();
@@ -70,6 +164,13 @@ fn parse_lsof_output<'py>(py: Python<'py>, output: &str) -> PyResult<Bound<'py,
             set.insert((process, ip, port));
         }
     }
+    set
+}
+
+/// Parse lsof -i -P -n output into a set of (process_name, remote_ip, remote_port) tuples.
+#[pyfunction]
+fn parse_lsof_output<'py>(py: Python<'py>, output: &str) -> PyResult<Bound<'py, PySet>> {
+    let set = lsof_connections(output);
 
     let pyset = PySet::empty(py)?;
     for (process, ip, port) in set {
@@ -104,6 +205,128 @@ fn extract_content(msg: &serde_json::Value) -> String {
     String::new()
 }
 
+/// The text of a transcript message: its content string, or its text blocks joined
+/// with spaces, leaving out tool calls, tool results and images.
+///
+/// message is a message dict or its JSON text; a whole transcript entry works too.
+/// Raises ValueError if the text isn't JSON.
+#[pyfunction]
+fn extract_message_content(message: &Bound<'_, PyAny>) -> PyResult<String> {
+    let text: String = match message.extract() {
+        Ok(text) => text,
+        Err(_) => message.py().import("json")?.call_method1("dumps", (message,))?.extract()?,
+    };
+    let value: serde_json::Value = serde_json::from_str(&text)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+    let msg = match value.get("message") {
+        Some(msg) if value.get("content").is_none() => msg,
+        _ => &value,
+    };
+    Ok(extract_content(msg))
+}
+
+/// Text Claude Code inserts as a user message when generation is stopped.
+const INTERRUPT_MARKER: &str = "[Request interrupted by user";
+
+/// Prefixes of the tool_result text written when a tool call is not allowed to run.
+const DENIAL_MARKERS: [&str; 3] = [
+    "The user doesn't want to proceed with this tool use",
+    "Permission to use ",
+    "Claude requested permissions to ",
+];
+
+fn tool_result_text(block: &serde_json::Value) -> String {
+    match block.get("content") {
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(content @ serde_json::Value::Array(_)) => {
+            extract_content(&serde_json::json!({ "content": content }))
+        }
+        _ => String::new(),
+    }
+}
+
+/// A tool_result block that reports a failure or a refused tool call.
+struct FailedToolResult<'a> {
+    tool_use_id: &'a str,
+    text: String,
+    denied: bool,
+}
+
+/// The failed tool_result blocks of a user message: is_error results and permission denials.
+fn failed_tool_results(msg: &serde_json::Value) -> Vec<FailedToolResult<'_>> {
+    let blocks = match msg.get("content").and_then(|v| v.as_array()) {
+        Some(arr) => arr,
+        None => return Vec::new(),
+    };
+    blocks
+        .iter()
+        .filter(|b| b.get("type").and_then(|v| v.as_str()) == Some("tool_result"))
+        .filter_map(|b| {
+            let tool_use_id = b.get("tool_use_id").and_then(|v| v.as_str())?;
+            let text = tool_result_text(b);
+            let denied = DENIAL_MARKERS.iter().any(|m| text.trim_start().starts_with(m));
+            let is_error = b.get("is_error").and_then(|v| v.as_bool()).unwrap_or(false);
+            (denied || is_error).then_some(FailedToolResult {
+                tool_use_id,
+                text,
+                denied,
+            })
+        })
+        .collect()
+}
+
+/// Decoded size of a base64 payload, without decoding it.
+fn base64_decoded_len(data: &str) -> u64 {
+    let trimmed = data.trim_end_matches('=');
+    (trimmed.len() as u64 * 3) / 4
+}
+
+fn format_bytes(n: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = n as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{n} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+/// (kind, media_type, size_bytes) for each image/document block in a message.
+///
+/// Only metadata is read; the base64 payload is never decoded or copied.
+fn attachments(msg: &serde_json::Value) -> Vec<(String, String, u64)> {
+    let blocks = match msg.get("content").and_then(|v| v.as_array()) {
+        Some(arr) => arr,
+        None => return Vec::new(),
+    };
+    blocks
+        .iter()
+        .filter_map(|b| {
+            let kind = b.get("type")?.as_str()?;
+            if kind != "image" && kind != "document" {
+                return None;
+            }
+            let source = b.get("source")?;
+            let media_type = source
+                .get("media_type")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string();
+            let size = source
+                .get("data")
+                .and_then(|v| v.as_str())
+                .map(base64_decoded_len)
+                .unwrap_or(0);
+            Some((kind.to_string(), media_type, size))
+        })
+        .collect()
+}
+
 fn tool_input_preview(tool_name: &str, tool_input: &serde_json::Value) -> String {
     match tool_name {
         "Bash" => tool_input
@@ -157,78 +380,6 @@ fn tool_input_preview(tool_name: &str, tool_input: &serde_json::Value) -> String
     }
 }
 
-fn parse_iso_ts(ts_str: &str) -> Option<f64> {
-    let s = ts_str.replace('Z', "+00:00");
-
-    let (date_part, rest) = s.split_once('T')?;
-    let date_parts: Vec<&str> = date_part.split('-').collect();
-    if date_parts.len() != 3 {
-        return None;
-    }
-    let year: i64 = date_parts[0].parse().ok()?;
-    let month: i64 = date_parts[1].parse().ok()?;
-    let day: i64 = date_parts[2].parse().ok()?;
-
-    let (time_str, tz_offset_secs) = if let Some(idx) = rest.rfind('+') {
-        if idx > 0 {
-            let tz_str = &rest[idx + 1..];
-            let tz_parts: Vec<&str> = tz_str.split(':').collect();
-            let tz_hours: i64 = tz_parts.first().and_then(|s| s.parse().ok()).unwrap_or(0);
-            let tz_mins: i64 = tz_parts.get(1).and_then(|s| s.parse().ok()).unwrap_or(0);
-            (&rest[..idx], tz_hours * 3600 + tz_mins * 60)
-        } else {
-            (rest, 0i64)
-        }
-    } else if let Some(idx) = rest.rfind('-') {
-        if idx > 6 {
-            let tz_str = &rest[idx + 1..];
-            let tz_parts: Vec<&str> = tz_str.split(':').collect();
-            let tz_hours: i64 = tz_parts.first().and_then(|s| s.parse().ok()).unwrap_or(0);
-            let tz_mins: i64 = tz_parts.get(1).and_then(|s| s.parse().ok()).unwrap_or(0);
-            (&rest[..idx], -(tz_hours * 3600 + tz_mins * 60))
-        } else {
-            (rest, 0i64)
-        }
-    } else {
-        (rest, 0i64)
-    };
-
-    let time_parts: Vec<&str> = time_str.split(':').collect();
-    if time_parts.len() < 2 {
-        return None;
-    }
-    let hour: i64 = time_parts[0].parse().ok()?;
-    let minute: i64 = time_parts[1].parse().ok()?;
-    let second_str = time_parts.get(2).unwrap_or(&"0");
-    let sec_parts: Vec<&str> = second_str.split('.').collect();
-    let sec: i64 = sec_parts[0].parse().ok()?;
-    let frac: f64 = if sec_parts.len() > 1 {
-        let frac_str = sec_parts[1];
-        let frac_val: f64 = frac_str.parse().ok()?;
-        frac_val / 10f64.powi(frac_str.len() as i32)
-    } else {
-        0.0
-    };
-
-    let days = days_from_epoch(year, month, day)?;
-    let epoch_secs = days * 86400 + hour * 3600 + minute * 60 + sec;
-    Some(epoch_secs as f64 + frac - tz_offset_secs as f64)
-}
-
-fn days_from_epoch(year: i64, month: i64, day: i64) -> Option<i64> {
-    let (y, m) = if month <= 2 {
-        (year - 1, month + 9)
-    } else {
-        (year, month - 3)
-    };
-    let era = y / 400;
-    let yoe = y - era * 400;
-    let doy = (153 * m + 2) / 5 + day - 1;
-    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
-    let days = era * 146097 + doe - 719468;
-    Some(days)
-}
-
 fn truncate_str(s: &str, max_len: usize) -> &str {
     if s.len() <= max_len {
         s
@@ -241,57 +392,317 @@ fn truncate_str(s: &str, max_len: usize) -> &str {
     }
 }
 
+#[derive(Default)]
 struct TranscriptEvent {
     timestamp: f64,
     session_id: String,
     message_type: String,
     content_preview: String,
+    /// BLAKE3 of the full text, when the config asks for it.
+    content_hash: Option<String>,
     project_path: String,
+    tokens: u64,
+    cost_usd: f64,
+    /// Set on compaction events once the replacement summary text is attached.
+    has_summary: bool,
+    /// (media_type, size_bytes) for attachment events.
+    attachment: Option<(String, u64)>,
+    hook: Option<HookInfo>,
+    raw: Option<String>,
+    /// Set when a privacy filter's deny_content matched the full text, which the
+    /// truncated preview may no longer show.
+    withheld: bool,
+}
+
+struct HookInfo {
+    /// Hook name as Claude Code reports it, e.g. "PreToolUse:Bash".
+    name: String,
+    /// None while the hook is still running.
+    exit_code: Option<i64>,
+    blocked: bool,
+}
+
+impl HookInfo {
+    /// The hook event ("PreToolUse" for "PreToolUse:Bash").
+    fn event(&self) -> &str {
+        self.name.split(':').next().unwrap_or("")
+    }
+}
+
+fn hook_notice_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"^(\S+) \[.*?\] (?:(completed successfully)|failed with non-blocking status code (\d+)|(blocking error|blocked))"
+        ).unwrap()
+    })
+}
+
+/// Parse the system notice Claude Code writes after a hook command runs.
+fn parse_hook_notice(content: &str) -> Option<HookInfo> {
+    let caps = hook_notice_regex().captures(content)?;
+    let (exit_code, blocked) = if caps.get(2).is_some() {
+        (0, false)
+    } else if let Some(code) = caps.get(3) {
+        (code.as_str().parse().unwrap_or(1), false)
+    } else {
+        (2, true)
+    };
+    Some(HookInfo {
+        name: caps[1].to_string(),
+        exit_code: Some(exit_code),
+        blocked,
+    })
+}
+
+/// Parse a stop_hook_summary system entry.
+fn stop_hook_summary(entry: &serde_json::Value) -> Option<HookInfo> {
+    if entry.get("subtype").and_then(|v| v.as_str()) != Some("stop_hook_summary") {
+        return None;
+    }
+    let has_errors = entry
+        .get("hookErrors")
+        .and_then(|v| v.as_array())
+        .is_some_and(|a| !a.is_empty());
+    let blocked = entry
+        .get("preventedContinuation")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    Some(HookInfo {
+        name: "Stop".to_string(),
+        exit_code: Some(if blocked { 2 } else if has_errors { 1 } else { 0 }),
+        blocked,
+    })
+}
+
+/// How much of the source JSON line to attach to each event.
+#[derive(Clone, Default)]
+enum RawMode {
+    #[default]
+    Off,
+    /// The whole line, verbatim.
+    Full,
+    /// A JSON object holding only these top-level fields.
+    Fields(Vec<String>),
+}
+
+impl RawMode {
+    fn from_py(obj: Option<&Bound<'_, PyAny>>) -> PyResult<RawMode> {
+        let obj = match obj {
+            Some(o) if !o.is_none() => o,
+            _ => return Ok(RawMode::Off),
+        };
+        if let Ok(flag) = obj.extract::<bool>() {
+            return Ok(if flag { RawMode::Full } else { RawMode::Off });
+        }
+        Ok(RawMode::Fields(obj.extract()?))
+    }
+
+    fn render(&self, line: &str, entry: &serde_json::Value) -> Option<String> {
+        match self {
+            RawMode::Off => None,
+            RawMode::Full => Some(line.to_string()),
+            RawMode::Fields(fields) => {
+                let subset: serde_json::Map<String, serde_json::Value> = fields
+                    .iter()
+                    .filter_map(|f| Some((f.clone(), entry.get(f)?.clone())))
+                    .collect();
+                Some(serde_json::Value::Object(subset).to_string())
+            }
+        }
+    }
+}
+
+fn attach_raw(events: &mut [TranscriptEvent], raw: &Option<String>) {
+    if raw.is_some() {
+        for ev in events {
+            ev.raw = raw.clone();
+        }
+    }
 }
 
 fn parse_transcript_impl(
     path: &str,
     since_offset: u64,
-    preview_len: usize,
-) -> Result<(Vec<TranscriptEvent>, u64), String> {
-    let file_path = std::path::Path::new(path);
-    let session_id = file_path
-        .file_stem()
-        .and_then(|s| s.to_str())
-        .unwrap_or("")
-        .to_string();
-    let project_path = file_path
-        .parent()
-        .and_then(|p| p.to_str())
-        .unwrap_or("")
-        .to_string();
-
-    let file = File::open(path).map_err(|e| e.to_string())?;
+    config: &ParserConfig,
+) -> Result<(Vec<TranscriptEvent>, u64), ReadError> {
+    open_transcript(Path::new(path), since_offset)?.read_all(config)
+}
+
+/// A transcript that is still being written, read a little at a time: where the next
+/// read starts, and the parser state carried over from earlier reads so that usage
+/// repeated across an append isn't counted twice and a tool result still finds the
+/// tool_use it answers.
+#[derive(Default)]
+struct TranscriptTail {
+    offset: u64,
+    seen_messages: HashSet<String>,
+    tool_uses: HashMap<String, (String, String)>,
+    /// A compaction read last time, waiting for the summary line that follows it.
+    held: Vec<TranscriptEvent>,
+}
+
+impl TranscriptTail {
+    fn new(offset: u64) -> Self {
+        TranscriptTail { offset, ..Default::default() }
+    }
+
+    /// Parse what was appended to path since the last read. A last line without its
+    /// newline is left for the next read, and a line that isn't UTF-8 is skipped, since
+    /// rereading it would fail the same way every time. Returns the new events and what
+    /// went wrong: a ReadError::Parse for each skipped line, then the error that ended
+    /// the read early, if any, keeping the events before it. Problems are returned
+    /// rather than logged, so callers can report them once they hold no locks.
+    fn read(&mut self, path: &Path, config: &ParserConfig) -> (Vec<TranscriptEvent>, Vec<ReadError>) {
+        let mut lines = match open_transcript(path, self.offset) {
+            Ok(lines) => lines,
+            Err(e) => return (Vec::new(), vec![e]),
+        };
+        lines.complete_only = true;
+        lines.skip_invalid = true;
+        lines.seen_messages = std::mem::take(&mut self.seen_messages);
+        lines.tool_uses = std::mem::take(&mut self.tool_uses);
+        let mut events = std::mem::take(&mut self.held);
+        let stopped = loop {
+            match lines.read_line(config, &mut events) {
+                Ok(true) => {}
+                Ok(false) => break None,
+                Err(e) => break Some(e),
+            }
+        };
+        if events.last().is_some_and(|ev| ev.message_type == "compaction" && !ev.has_summary) {
+            self.held.extend(events.pop());
+        }
+        self.offset = lines.offset;
+        self.seen_messages = lines.seen_messages;
+        self.tool_uses = lines.tool_uses;
+        let mut errors = lines.skipped;
+        errors.extend(stopped);
+        (events, errors)
+    }
+}
+
+/// Open a transcript file at since_offset. The session id is the file's stem and the
+/// project path its directory.
+fn open_transcript(
+    path: &Path,
+    since_offset: u64,
+) -> Result<TranscriptLines<BufReader<File>>, ReadError> {
+    let io_err = |e| ReadError::Io(path.to_path_buf(), e);
+    let session_id = path.file_stem().and_then(|s| s.to_str()).unwrap_or("").to_string();
+    let project_path = path.parent().and_then(|p| p.to_str()).unwrap_or("").to_string();
+
+    let file = File::open(path).map_err(io_err)?;
     let mut reader = BufReader::new(file);
-    reader
-        .seek(SeekFrom::Start(since_offset))
-        .map_err(|e| e.to_string())?;
-
-    let mut events = Vec::new();
-    let mut line_buf = String::new();
-
-    loop {
-        line_buf.clear();
-        let bytes_read = reader.read_line(&mut line_buf).map_err(|e| e.to_string())?;
-        if bytes_read == 0 {
-            break;
+    reader.seek(SeekFrom::Start(since_offset)).map_err(io_err)?;
+    Ok(TranscriptLines::new(reader, path, since_offset, session_id, project_path))
+}
+
+/// Transcript parsing state carried from one line to the next, over any source of
+/// lines. source names the input in errors; offset is where the next line starts.
+struct TranscriptLines<R> {
+    reader: R,
+    source: PathBuf,
+    offset: u64,
+    /// Stop before a last line with no newline, which may be half written.
+    complete_only: bool,
+    /// Skip a line that isn't UTF-8, noting it in skipped, rather than failing on it.
+    skip_invalid: bool,
+    skipped: Vec<ReadError>,
+    session_id: String,
+    project_path: String,
+    line_buf: Vec<u8>,
+    seen_messages: HashSet<String>,
+    // tool_use id -> (tool name, input preview), to describe denied tool calls.
+    tool_uses: HashMap<String, (String, String)>,
+}
+
+impl<R: BufRead> TranscriptLines<R> {
+    fn new(
+        reader: R,
+        source: &Path,
+        offset: u64,
+        session_id: String,
+        project_path: String,
+    ) -> Self {
+        TranscriptLines {
+            reader,
+            source: source.to_path_buf(),
+            offset,
+            complete_only: false,
+            skip_invalid: false,
+            skipped: Vec::new(),
+            session_id,
+            project_path,
+            line_buf: Vec::new(),
+            seen_messages: HashSet::new(),
+            tool_uses: HashMap::new(),
         }
+    }
 
-        let trimmed = line_buf.trim();
-        if trimmed.is_empty() {
-            continue;
+    /// Every event up to the end of input, and the offset where it ended.
+    fn read_all(
+        mut self,
+        config: &ParserConfig,
+    ) -> Result<(Vec<TranscriptEvent>, u64), ReadError> {
+        let mut events = Vec::new();
+        while self.read_line(config, &mut events)? {}
+        Ok((events, self.offset))
+    }
+
+    /// Read one line and append its events to events. Returns false at end of input.
+    fn read_line(
+        &mut self,
+        config: &ParserConfig,
+        events: &mut Vec<TranscriptEvent>,
+    ) -> Result<bool, ReadError> {
+        self.line_buf.clear();
+        let bytes_read = self
+            .reader
+            .read_until(b'\n', &mut self.line_buf)
+            .map_err(|e| ReadError::Io(self.source.clone(), e))?;
+        if bytes_read == 0 || self.complete_only && !self.line_buf.ends_with(b"\n") {
+            return Ok(false);
         }
+        // Byte offset of this line, to say where unparseable input starts.
+        let line_offset = self.offset;
+        self.offset += bytes_read as u64;
 
+        let Ok(line) = std::str::from_utf8(&self.line_buf) else {
+            let cause = "stream did not contain valid UTF-8".to_string();
+            let error = ReadError::Parse(self.source.clone(), line_offset, cause);
+            if self.skip_invalid {
+                self.skipped.push(error);
+                return Ok(true);
+            }
+            return Err(error);
+        };
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            return Ok(true);
+        }
         let entry: serde_json::Value = match serde_json::from_str(trimmed) {
             Ok(v) => v,
-            Err(_) => continue,
+            Err(e) => {
+                let path = self.source.display();
+                tracing::debug!(%path, offset = line_offset, "skipping unparseable line: {e}");
+                return Ok(true);
+            }
         };
+        // Events produced by this line share its raw JSON.
+        let line_raw = config.include_raw.render(trimmed, &entry);
+        let line_start = events.len();
+        self.entry_events(&entry, config, events);
+        attach_raw(&mut events[line_start..], &line_raw);
+        Ok(true)
+    }
 
+    fn entry_events(
+        &mut self,
+        entry: &serde_json::Value,
+        config: &ParserConfig,
+        events: &mut Vec<TranscriptEvent>,
+    ) {
         let event_type = entry
             .get("type")
             .and_then(|v| v.as_str())
@@ -309,30 +720,98 @@ fn parse_transcript_impl(
         match event_type {
             "user" => {
                 let msg = &entry["message"];
+                for failed in failed_tool_results(msg) {
+                    let (tool_name, preview) = self.tool_uses
+                        .get(failed.tool_use_id)
+                        .map(|(n, p)| (n.as_str(), p.as_str()))
+                        .unwrap_or(("", ""));
+                    // Denials show what was asked for; errors show what went wrong.
+                    let (message_type, content) = if failed.denied {
+                        (format!("permission_denied:{tool_name}"), preview)
+                    } else {
+                        (format!("tool_error:{tool_name}"), failed.text.as_str())
+                    };
+                    events.push(TranscriptEvent {
+                        timestamp: ts,
+                        session_id: self.session_id.clone(),
+                        message_type,
+                        project_path: self.project_path.clone(),
+                        ..config.event(content)
+                    });
+                }
+
+                for (kind, media_type, size_bytes) in attachments(msg) {
+                    let preview = format!("{media_type} ({})", format_bytes(size_bytes));
+                    events.push(TranscriptEvent {
+                        timestamp: ts,
+                        session_id: self.session_id.clone(),
+                        message_type: format!("attachment:{kind}"),
+                        content_hash: config.hash(&preview),
+                        content_preview: preview,
+                        project_path: self.project_path.clone(),
+                        attachment: Some((media_type, size_bytes)),
+                        ..Default::default()
+                    });
+                }
+
                 let content = extract_content(msg);
                 let trimmed_content = content.trim();
                 if trimmed_content.is_empty() {
-                    continue;
+                    return;
+                }
+                // The summary that replaces compacted history: attach it to the
+                // preceding compact_boundary, or record the compaction on its own.
+                let is_compact_summary = entry
+                    .get("isCompactSummary")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false)
+                    || trimmed_content.starts_with("This session is being continued");
+                if is_compact_summary {
+                    let summary = config.event(trimmed_content);
+                    match events.last_mut() {
+                        Some(last)
+                            if last.message_type == "compaction" && !last.has_summary =>
+                        {
+                            last.content_preview = summary.content_preview;
+                            last.content_hash = summary.content_hash;
+                            last.withheld |= summary.withheld;
+                            last.has_summary = true;
+                        }
+                        _ => events.push(TranscriptEvent {
+                            timestamp: ts,
+                            session_id: self.session_id.clone(),
+                            message_type: "compaction".to_string(),
+                            project_path: self.project_path.clone(),
+                            has_summary: true,
+                            ..summary
+                        }),
+                    }
+                    return;
                 }
                 // Skip system-generated messages (not actual user input)
-                if trimmed_content.starts_with("<task-notification") ||
-                   trimmed_content.starts_with("This session is being continued") {
-                    continue;
+                if trimmed_content.starts_with("<task-notification") {
+                    return;
                 }
+                let message_type = if trimmed_content.starts_with(INTERRUPT_MARKER) {
+                    "user_interrupt"
+                } else {
+                    "user"
+                };
                 events.push(TranscriptEvent {
                     timestamp: ts,
-                    session_id: session_id.clone(),
-                    message_type: "user".to_string(),
-                    content_preview: truncate_str(&content, preview_len).to_string(),
-                    project_path: project_path.clone(),
+                    session_id: self.session_id.clone(),
+                    message_type: message_type.to_string(),
+                    project_path: self.project_path.clone(),
+                    ..config.event(&content)
                 });
             }
             "assistant" => {
                 let msg = &entry["message"];
                 let content_blocks = match msg.get("content").and_then(|v| v.as_array()) {
                     Some(arr) => arr,
-                    None => continue,
+                    None => return,
                 };
+                let first_event = events.len();
 
                 for block in content_blocks {
                     let block_type = block
@@ -348,10 +827,10 @@ fn parse_transcript_impl(
                                 .unwrap_or("");
                             events.push(TranscriptEvent {
                                 timestamp: ts,
-                                session_id: session_id.clone(),
+                                session_id: self.session_id.clone(),
                                 message_type: "assistant_text".to_string(),
-                                content_preview: truncate_str(text, preview_len).to_string(),
-                                project_path: project_path.clone(),
+                                project_path: self.project_path.clone(),
+                                ..config.event(text)
                             });
                         }
                         "tool_use" => {
@@ -364,18 +843,34 @@ fn parse_transcript_impl(
                                 .get("input")
                                 .unwrap_or(&empty_obj);
                             let preview = tool_input_preview(tool_name, tool_input);
+                            if let Some(id) = block.get("id").and_then(|v| v.as_str()) {
+                                self.tool_uses
+                                    .insert(id.to_string(), (tool_name.to_string(), preview.clone()));
+                            }
                             events.push(TranscriptEvent {
                                 timestamp: ts,
-                                session_id: session_id.clone(),
+                                session_id: self.session_id.clone(),
                                 message_type: format!("tool_use:{tool_name}"),
-                                content_preview: truncate_str(&preview, preview_len)
-                                    .to_string(),
-                                project_path: project_path.clone(),
+                                project_path: self.project_path.clone(),
+                                ..config.event(&preview)
                             });
                         }
                         _ => {}
                     }
                 }
+
+                // Claude Code repeats the usage on every line of a multi-block message,
+                // so attribute it once, to the first event of each message id.
+                let msg_id = msg.get("id").and_then(|v| v.as_str()).unwrap_or("");
+                if msg_id.is_empty() || self.seen_messages.insert(msg_id.to_string()) {
+                    if let (Some(ev), Some(usage)) =
+                        (events.get_mut(first_event), Usage::from_message_or_estimate(msg))
+                    {
+                        let model = msg.get("model").and_then(|v| v.as_str()).unwrap_or("");
+                        ev.tokens = usage.total();
+                        ev.cost_usd = usage.cost_usd(model);
+                    }
+                }
             }
             "progress" => {
                 let data = &entry["data"];
@@ -383,7 +878,30 @@ fn parse_transcript_impl(
                     .get("type")
                     .and_then(|v| v.as_str())
                     .unwrap_or("");
-                if subtype == "tool_result" {
+                if subtype == "hook_progress" {
+                    let name = data
+                        .get("hookName")
+                        .or_else(|| data.get("hookEvent"))
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("");
+                    let command = data
+                        .get("command")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or(name);
+                    let hook = HookInfo {
+                        name: name.to_string(),
+                        exit_code: None,
+                        blocked: false,
+                    };
+                    events.push(TranscriptEvent {
+                        timestamp: ts,
+                        session_id: self.session_id.clone(),
+                        message_type: format!("hook:{}", hook.event()),
+                        project_path: self.project_path.clone(),
+                        hook: Some(hook),
+                        ..config.event(command)
+                    });
+                } else if subtype == "tool_result" {
                     let tool_name = data
                         .get("tool_name")
                         .and_then(|v| v.as_str())
@@ -397,53 +915,375 @@ fn parse_transcript_impl(
                     };
                     events.push(TranscriptEvent {
                         timestamp: ts,
-                        session_id: session_id.clone(),
+                        session_id: self.session_id.clone(),
                         message_type: format!("tool_result:{tool_name}"),
-                        content_preview: truncate_str(&output_str, preview_len).to_string(),
-                        project_path: project_path.clone(),
+                        project_path: self.project_path.clone(),
+                        ..config.event(&output_str)
+                    });
+                }
+            }
+            "summary" => {
+                let summary = entry
+                    .get("summary")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("");
+                if summary.is_empty() {
+                    return;
+                }
+                events.push(TranscriptEvent {
+                    timestamp: ts,
+                    session_id: self.session_id.clone(),
+                    message_type: "session_summary".to_string(),
+                    project_path: self.project_path.clone(),
+                    ..config.event(summary)
+                });
+            }
+            "system" => {
+                let subtype = entry
+                    .get("subtype")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("");
+                let content = entry
+                    .get("content")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("");
+                if subtype == "compact_boundary" {
+                    let meta = &entry["compactMetadata"];
+                    let trigger = meta.get("trigger").and_then(|v| v.as_str()).unwrap_or("");
+                    let pre_tokens = meta.get("preTokens").and_then(|v| v.as_u64()).unwrap_or(0);
+                    let preview = format!("{trigger} compaction at {pre_tokens} tokens");
+                    events.push(TranscriptEvent {
+                        timestamp: ts,
+                        session_id: self.session_id.clone(),
+                        message_type: "compaction".to_string(),
+                        content_hash: config.hash(&preview),
+                        content_preview: preview,
+                        project_path: self.project_path.clone(),
+                        ..Default::default()
+                    });
+                } else if let Some(hook) =
+                    stop_hook_summary(entry).or_else(|| parse_hook_notice(content))
+                {
+                    let preview = if content.is_empty() { hook.name.as_str() } else { content };
+                    let base = config.event(preview);
+                    events.push(TranscriptEvent {
+                        timestamp: ts,
+                        session_id: self.session_id.clone(),
+                        message_type: format!("hook:{}", hook.event()),
+                        project_path: self.project_path.clone(),
+                        hook: Some(hook),
+                        ..base
+                    });
+                } else if !content.is_empty() {
+                    let level = entry.get("level").and_then(|v| v.as_str()).unwrap_or("info");
+                    let kind = if subtype.is_empty() { level } else { subtype };
+                    events.push(TranscriptEvent {
+                        timestamp: ts,
+                        session_id: self.session_id.clone(),
+                        message_type: format!("system:{kind}"),
+                        project_path: self.project_path.clone(),
+                        ..config.event(content)
                     });
                 }
             }
             _ => {}
         }
     }
+}
 
-    let final_offset = reader.stream_position().map_err(|e| e.to_string())?;
-    Ok((events, final_offset))
+/// The transcript parser proper, over any source of lines. source names the input in
+/// errors; offsets count from since_offset, and the returned one is where reading
+/// stopped.
+fn parse_transcript_lines(
+    reader: impl BufRead,
+    source: &Path,
+    since_offset: u64,
+    session_id: String,
+    project_path: String,
+    config: &ParserConfig,
+) -> Result<(Vec<TranscriptEvent>, u64), ReadError> {
+    TranscriptLines::new(reader, source, since_offset, session_id, project_path).read_all(config)
 }
 
 /// Parse a JSONL transcript file into structured events.
 ///
-/// Returns (list_of_event_dicts, final_file_offset).
+/// Returns (list_of_event_dicts, final_file_offset). With datetimes=True the timestamp
+/// field is a UTC-aware datetime.datetime (None when the entry had no timestamp)
+/// instead of epoch seconds. include_raw=True adds the source JSON line to each event as
+/// "raw"; a list of field names keeps only those top-level fields. config is a
+/// ParserConfig carrying these and the other parsing options; preview_len, datetimes
+/// and include_raw override it when given. Raises IOError (PermissionDenied if access
+/// is refused) if path can't be read and ParseError, with the line's byte offset, if it
+/// isn't UTF-8.
 #[pyfunction]
-#[pyo3(signature = (path, since_offset=0, preview_len=500))]
+#[pyo3(signature = (
+    path, since_offset=0, preview_len=None, datetimes=None, include_raw=None, config=None
+))]
 fn parse_transcript<'py>(
     py: Python<'py>,
     path: &str,
     since_offset: u64,
-    preview_len: usize,
+    preview_len: Option<usize>,
+    datetimes: Option<bool>,
+    include_raw: Option<&Bound<'py, PyAny>>,
+    config: Option<ParserConfig>,
 ) -> PyResult<(Bound<'py, PyList>, u64)> {
-    let (events, final_offset) = parse_transcript_impl(path, since_offset, preview_len)
-        .map_err(|e| pyo3::exceptions::PyIOError::new_err(e))?;
+    let config = transcript_config(config, preview_len, datetimes, include_raw)?;
+    let (events, final_offset) = py.detach(|| {
+        let (mut events, final_offset) = parse_transcript_impl(path, since_offset, &config)?;
+        config.filter_events(&mut events);
+        Ok::<_, ReadError>((events, final_offset))
+    })?;
+    Ok((events_to_py(py, &events, config.time_format)?, final_offset))
+}
 
+/// Parse JSONL transcript data already in memory, as parse_transcript does a file.
+///
+/// data is bytes or any other buffer (bytearray, memoryview, mmap), read in place
+/// with the GIL released. session_id and project_path fill in what a file's name and
+/// directory would. Returns the list of event dicts; ParseError offsets count from the
+/// start of data.
+#[pyfunction]
+#[pyo3(signature = (
+    data, session_id="", project_path="", preview_len=None, datetimes=None, include_raw=None,
+    config=None
+))]
+#[allow(clippy::too_many_arguments)]
+fn parse_transcript_bytes<'py>(
+    py: Python<'py>,
+    data: &Bound<'py, PyAny>,
+    session_id: &str,
+    project_path: &str,
+    preview_len: Option<usize>,
+    datetimes: Option<bool>,
+    include_raw: Option<&Bound<'py, PyAny>>,
+    config: Option<ParserConfig>,
+) -> PyResult<Bound<'py, PyList>> {
+    let config = transcript_config(config, preview_len, datetimes, include_raw)?;
+    let events = buffer::with_bytes(data, |bytes| {
+        py.detach(|| {
+            let source = std::path::Path::new("<bytes>");
+            let (session_id, project_path) = (session_id.to_string(), project_path.to_string());
+            let (mut events, _) =
+                parse_transcript_lines(bytes, source, 0, session_id, project_path, &config)?;
+            config.filter_events(&mut events);
+            Ok::<_, ReadError>(events)
+        })
+    })??;
+    events_to_py(py, &events, config.time_format)
+}
+
+/// config with the keyword overrides parse_transcript and friends still accept.
+fn transcript_config(
+    config: Option<ParserConfig>,
+    preview_len: Option<usize>,
+    datetimes: Option<bool>,
+    include_raw: Option<&Bound<'_, PyAny>>,
+) -> PyResult<ParserConfig> {
+    let mut config = config.unwrap_or_default().with_overrides(preview_len, datetimes);
+    if include_raw.is_some_and(|r| !r.is_none()) {
+        config.include_raw = RawMode::from_py(include_raw)?;
+    }
+    Ok(config)
+}
+
+fn events_to_py<'py>(
+    py: Python<'py>,
+    events: &[TranscriptEvent],
+    time_format: TimeFormat,
+) -> PyResult<Bound<'py, PyList>> {
     let py_list = PyList::empty(py);
-    for ev in &events {
-        let dict = PyDict::new(py);
-        dict.set_item("timestamp", ev.timestamp)?;
-        dict.set_item("session_id", &ev.session_id)?;
-        dict.set_item("message_type", &ev.message_type)?;
-        dict.set_item("content_preview", &ev.content_preview)?;
-        dict.set_item("project_path", &ev.project_path)?;
-        py_list.append(dict)?;
+    let mut strings = Interner::default();
+    for ev in events {
+        py_list.append(event_to_dict(py, ev, time_format, &mut strings)?)?;
+    }
+    Ok(py_list)
+}
+
+/// One Python string per distinct value, reused across the events of a parse call.
+///
+/// session_id, project_path and message_type repeat across thousands of events;
+/// sharing the string objects saves both the allocations and the UTF-8 decoding.
+#[derive(Default)]
+struct Interner {
+    strings: HashMap<String, Py<PyString>>,
+}
+
+impl Interner {
+    fn get<'py>(&mut self, py: Python<'py>, value: &str) -> Bound<'py, PyString> {
+        if let Some(s) = self.strings.get(value) {
+            return s.bind(py).clone();
+        }
+        let s = PyString::new(py, value);
+        self.strings.insert(value.to_string(), s.clone().unbind());
+        s
     }
+}
 
-    Ok((py_list, final_offset))
+fn event_to_dict<'py>(
+    py: Python<'py>,
+    ev: &TranscriptEvent,
+    time_format: TimeFormat,
+    strings: &mut Interner,
+) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    dict.set_item(intern!(py, "timestamp"), time_format.convert(py, ev.timestamp)?)?;
+    dict.set_item(intern!(py, "session_id"), strings.get(py, &ev.session_id))?;
+    dict.set_item(intern!(py, "message_type"), strings.get(py, &ev.message_type))?;
+    dict.set_item(intern!(py, "content_preview"), &ev.content_preview)?;
+    if let Some(hash) = &ev.content_hash {
+        dict.set_item(intern!(py, "content_hash"), hash)?;
+    }
+    dict.set_item(intern!(py, "project_path"), strings.get(py, &ev.project_path))?;
+    dict.set_item(intern!(py, "tokens"), ev.tokens)?;
+    dict.set_item(intern!(py, "cost_usd"), ev.cost_usd)?;
+    if let Some((media_type, size_bytes)) = &ev.attachment {
+        dict.set_item(intern!(py, "media_type"), strings.get(py, media_type))?;
+        dict.set_item(intern!(py, "size_bytes"), size_bytes)?;
+    }
+    if let Some(hook) = &ev.hook {
+        dict.set_item(intern!(py, "hook_name"), strings.get(py, &hook.name))?;
+        dict.set_item(intern!(py, "exit_code"), hook.exit_code)?;
+        dict.set_item(intern!(py, "blocked"), hook.blocked)?;
+    }
+    if let Some(raw) = &ev.raw {
+        dict.set_item(intern!(py, "raw"), raw)?;
+    }
+    Ok(dict)
 }
 
-#[pymodule]
+// Nothing here relies on the GIL for exclusion: shared state sits behind atomics and
+// locks, and long-running work releases the interpreter, so free-threaded builds
+// (3.13t) can run collectors and parsers in parallel.
+#[pymodule(gil_used = false)]
 fn snoopy_native(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    errors::register(m)?;
+    logging::install();
     m.add_function(wrap_pyfunction!(extract_attributed_body_text, m)?)?;
     m.add_function(wrap_pyfunction!(parse_lsof_output, m)?)?;
     m.add_function(wrap_pyfunction!(parse_transcript, m)?)?;
+    m.add_function(wrap_pyfunction!(timeutil::parse_iso_timestamp, m)?)?;
+    m.add_function(wrap_pyfunction!(timeutil::parse_iso_timestamps, m)?)?;
+    m.add_function(wrap_pyfunction!(sessions::sessionize, m)?)?;
+    m.add_function(wrap_pyfunction!(rollup::project_rollup, m)?)?;
+    m.add_function(wrap_pyfunction!(aggregate::aggregate_events, m)?)?;
+    m.add_function(wrap_pyfunction!(tokens::estimate_tokens, m)?)?;
+    m.add_function(wrap_pyfunction!(tokens::estimate_tokens_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(simhash::prompt_fingerprint, m)?)?;
+    m.add_function(wrap_pyfunction!(simhash::find_duplicate_prompts, m)?)?;
+    m.add_class::<replay::TranscriptReplay>()?;
+    m.add_function(wrap_pyfunction!(replay::replay_transcript, m)?)?;
+    m.add_function(wrap_pyfunction!(narrate::narrate_session, m)?)?;
+    m.add_function(wrap_pyfunction!(gitinfer::infer_git_activity, m)?)?;
+    m.add_function(wrap_pyfunction!(failures::tool_failure_stats, m)?)?;
+    m.add_function(wrap_pyfunction!(title::infer_title, m)?)?;
+    m.add_class::<timeline::TimelineMerge>()?;
+    m.add_function(wrap_pyfunction!(timeline::merge_timelines, m)?)?;
+    m.add_class::<collector::Collector>()?;
+    m.add_class::<privacy::PrivacyFilter>()?;
+    m.add_function(wrap_pyfunction!(privacy::set_privacy_filter, m)?)?;
+    m.add_class::<crypto::EncryptionKey>()?;
+    m.add_function(wrap_pyfunction!(crypto::encrypt_file, m)?)?;
+    m.add_function(wrap_pyfunction!(crypto::decrypt_file, m)?)?;
+    m.add_function(wrap_pyfunction!(compress::read_ndjson, m)?)?;
+    m.add_function(wrap_pyfunction!(compress::train_zstd_dictionary, m)?)?;
+    m.add_class::<watcher::Watcher>()?;
+    m.add_function(wrap_pyfunction!(history::safari_history, m)?)?;
+    m.add_function(wrap_pyfunction!(history::chromium_history, m)?)?;
+    m.add_function(wrap_pyfunction!(history::chromium_profiles, m)?)?;
+    m.add_function(wrap_pyfunction!(history::firefox_history, m)?)?;
+    m.add_function(wrap_pyfunction!(history::firefox_profiles, m)?)?;
+    m.add_function(wrap_pyfunction!(history::browser_downloads, m)?)?;
+    m.add_function(wrap_pyfunction!(history::quarantine_events, m)?)?;
+    m.add_function(wrap_pyfunction!(shell::parse_shell_history, m)?)?;
+    m.add_function(wrap_pyfunction!(gitscan::scan_git_activity, m)?)?;
+    m.add_function(wrap_pyfunction!(knowledge::screen_time_usage, m)?)?;
+    m.add_function(wrap_pyfunction!(unifiedlog::query_unified_log, m)?)?;
+    m.add_function(wrap_pyfunction!(frontmost::frontmost_window, m)?)?;
+    m.add_function(wrap_pyfunction!(idle::idle_seconds, m)?)?;
+    m.add_function(wrap_pyfunction!(calendar::calendar_events, m)?)?;
+    m.add_function(wrap_pyfunction!(notes::notes_events, m)?)?;
+    m.add_function(wrap_pyfunction!(whatsapp::whatsapp_messages, m)?)?;
+    m.add_function(wrap_pyfunction!(slack::slack_messages, m)?)?;
+    m.add_function(wrap_pyfunction!(spotlight::recent_documents, m)?)?;
+    m.add_function(wrap_pyfunction!(processes::snapshot_processes, m)?)?;
+    m.add_function(wrap_pyfunction!(processes::diff_processes, m)?)?;
+    m.add_class::<processes::ResourceLimits>()?;
+    m.add_function(wrap_pyfunction!(power::parse_pmset_log, m)?)?;
+    m.add_function(wrap_pyfunction!(power::power_events, m)?)?;
+    m.add_function(wrap_pyfunction!(wifi::current_wifi, m)?)?;
+    m.add_function(wrap_pyfunction!(usb::usb_devices, m)?)?;
+    m.add_function(wrap_pyfunction!(input::input_counts, m)?)?;
+    m.add_class::<screenshot::ScreenshotOptions>()?;
+    m.add_function(wrap_pyfunction!(screenshot::perceptual_hash, m)?)?;
+    m.add_function(wrap_pyfunction!(tcc::tcc_permissions, m)?)?;
+    m.add_function(wrap_pyfunction!(persistence::persistence_snapshot, m)?)?;
+    m.add_function(wrap_pyfunction!(installs::parse_install_log, m)?)?;
+    m.add_function(wrap_pyfunction!(installs::install_log_events, m)?)?;
+    m.add_function(wrap_pyfunction!(installs::homebrew_installs, m)?)?;
+    m.add_class::<fsactivity::FileActivityParser>()?;
+    m.add_class::<eslogger::EsloggerParser>()?;
+    m.add_function(wrap_pyfunction!(eslogger::parse_eslogger, m)?)?;
+    m.add_function(wrap_pyfunction!(audit::parse_praudit, m)?)?;
+    m.add_function(wrap_pyfunction!(audit::audit_trail_events, m)?)?;
+    m.add_function(wrap_pyfunction!(logins::parse_last, m)?)?;
+    m.add_function(wrap_pyfunction!(logins::login_sessions, m)?)?;
+    m.add_function(wrap_pyfunction!(timemachine::parse_backupd_log, m)?)?;
+    m.add_function(wrap_pyfunction!(timemachine::time_machine_events, m)?)?;
+    m.add_function(wrap_pyfunction!(timemachine::time_machine_status, m)?)?;
+    m.add_function(wrap_pyfunction!(vscode::vscode_activity, m)?)?;
+    m.add_function(wrap_pyfunction!(xcode::xcode_recent_projects, m)?)?;
+    m.add_function(wrap_pyfunction!(xcode::xcode_builds, m)?)?;
+    m.add("TMUX_PANE_FORMAT", tmux::PANE_FORMAT)?;
+    m.add("TMUX_SESSION_FORMAT", tmux::SESSION_FORMAT)?;
+    m.add_class::<tmux::TmuxOptions>()?;
+    m.add_function(wrap_pyfunction!(tmux::parse_tmux_panes, m)?)?;
+    m.add_function(wrap_pyfunction!(tmux::parse_tmux_sessions, m)?)?;
+    m.add_function(wrap_pyfunction!(tmux::tmux_panes, m)?)?;
+    m.add_function(wrap_pyfunction!(tmux::tmux_sessions, m)?)?;
+    m.add_function(wrap_pyfunction!(ssh::parse_ssh_log, m)?)?;
+    m.add_function(wrap_pyfunction!(ssh::ssh_events, m)?)?;
+    m.add_class::<downloads::DownloadsMonitor>()?;
+    m.add_function(wrap_pyfunction!(downloads::download_origin, m)?)?;
+    m.add_function(wrap_pyfunction!(dns::parse_dns_cache, m)?)?;
+    m.add_function(wrap_pyfunction!(dns::dns_hostnames, m)?)?;
+    m.add_function(wrap_pyfunction!(dns::dns_cache, m)?)?;
+    m.add_function(wrap_pyfunction!(ics::export_ics, m)?)?;
+    m.add_function(wrap_pyfunction!(report::generate_daily_report, m)?)?;
+    m.add_function(wrap_pyfunction!(html::export_html_timeline, m)?)?;
+    m.add_function(wrap_pyfunction!(reminders::reminders_events, m)?)?;
+    m.add_function(wrap_pyfunction!(mail::mail_events, m)?)?;
+    m.add_class::<meetings::MeetingDetector>()?;
+    m.add_function(wrap_pyfunction!(meetings::media_in_use, m)?)?;
+    m.add_function(wrap_pyfunction!(logging::set_log_level, m)?)?;
+    m.add_class::<parserconfig::ParserConfig>()?;
+    m.add_function(wrap_pyfunction!(parse_transcript_bytes, m)?)?;
+    m.add_class::<transcripts::TranscriptMerge>()?;
+    m.add_function(wrap_pyfunction!(transcripts::iter_transcripts, m)?)?;
+    m.add_function(wrap_pyfunction!(parserconfig::truncate_preview, m)?)?;
+    m.add_function(wrap_pyfunction!(extract_message_content, m)?)?;
+    m.add_class::<anonymize::Anonymizer>()?;
+    m.add_class::<rewrite::RewriteRules>()?;
+    m.add_function(wrap_pyfunction!(rewrite::rewrite_transcript, m)?)?;
+    m.add_function(wrap_pyfunction!(dedup::content_hash, m)?)?;
+    m.add_function(wrap_pyfunction!(dedup::dedup_events, m)?)?;
+    m.add_class::<pipeline::TranscriptPipeline>()?;
+    m.add_function(wrap_pyfunction!(activity::session_activity, m)?)?;
+    m.add_function(wrap_pyfunction!(loc::loc_stats, m)?)?;
+    m.add_function(wrap_pyfunction!(permissions::permission_requests, m)?)?;
+    m.add_function(wrap_pyfunction!(lineage::session_lineage, m)?)?;
+    m.add_function(wrap_pyfunction!(correlate::correlate, m)?)?;
+    m.add_function(wrap_pyfunction!(interruptions::interruption_stats, m)?)?;
+    m.add_function(wrap_pyfunction!(otp::find_verification_code, m)?)?;
+    m.add_function(wrap_pyfunction!(otp::verification_events, m)?)?;
+    m.add_function(wrap_pyfunction!(links::extract_urls, m)?)?;
+    m.add_function(wrap_pyfunction!(links::link_events, m)?)?;
+    m.add_function(wrap_pyfunction!(keywords::transcript_keywords, m)?)?;
+    m.add_class::<fuzzy::FuzzyIndex>()?;
+    m.add_function(wrap_pyfunction!(training::export_training_data, m)?)?;
+    m.add_function(wrap_pyfunction!(snapshot::safe_snapshot, m)?)?;
+    m.add_class::<snapshot::DatabaseSnapshot>()?;
+    m.add_function(wrap_pyfunction!(preflight::check_permissions, m)?)?;
+    bench::register(m)?;
     Ok(())
 }
@@ -0,0 +1,209 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use rayon::prelude::*;
+use serde::Deserialize;
+
+use crate::rollup::find_jsonl_files;
+use crate::timeutil::parse_iso_ts;
+
+/// The fields of a transcript entry that tie it to other entries.
+#[derive(Deserialize)]
+struct Entry {
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    uuid: Option<String>,
+    #[serde(rename = "parentUuid")]
+    parent_uuid: Option<String>,
+    #[serde(rename = "leafUuid")]
+    leaf_uuid: Option<String>,
+    timestamp: Option<String>,
+    cwd: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct UuidOnly {
+    uuid: Option<String>,
+}
+
+/// What one transcript says about where it came from.
+#[derive(Default)]
+struct Head {
+    path: PathBuf,
+    session_id: String,
+    project_path: Option<String>,
+    start: Option<f64>,
+    end: Option<f64>,
+    /// Entries in other files this one continues: the message its first entry
+    /// replies to, its first entry itself (copied over on resume), and the leaves its
+    /// summaries describe.
+    probes: Vec<String>,
+}
+
+fn entries<T: for<'de> Deserialize<'de>>(path: &Path) -> impl Iterator<Item = T> {
+    let lines = File::open(path).ok().map(|f| BufReader::new(f).lines());
+    lines.into_iter().flatten().map_while(Result::ok).filter_map(|l| serde_json::from_str(&l).ok())
+}
+
+fn read_head(path: &Path) -> Head {
+    let mut head = Head {
+        path: path.to_path_buf(),
+        session_id: path.file_stem().and_then(|s| s.to_str()).unwrap_or("").to_string(),
+        ..Head::default()
+    };
+    let mut first_seen = false;
+    for entry in entries::<Entry>(path) {
+        if let Some(ts) = entry.timestamp.as_deref().and_then(parse_iso_ts) {
+            head.start = Some(head.start.map_or(ts, |s| s.min(ts)));
+            head.end = Some(head.end.map_or(ts, |e| e.max(ts)));
+        }
+        if head.project_path.is_none() {
+            head.project_path = entry.cwd;
+        }
+        if entry.kind.as_deref() == Some("summary") {
+            head.probes.extend(entry.leaf_uuid);
+        } else if !first_seen && entry.uuid.is_some() {
+            first_seen = true;
+            head.probes.extend(entry.parent_uuid);
+            head.probes.extend(entry.uuid);
+        }
+    }
+    head
+}
+
+/// For each file, the file it continues, if any.
+fn link(heads: &[Head], files: &[PathBuf]) -> Vec<Option<usize>> {
+    let wanted: HashSet<&str> =
+        heads.iter().flat_map(|h| h.probes.iter().map(String::as_str)).collect();
+    let holders: Vec<Vec<&str>> = files
+        .par_iter()
+        .map(|f| {
+            entries::<UuidOnly>(f)
+                .filter_map(|e| e.uuid)
+                .filter_map(|u| wanted.get(u.as_str()).copied())
+                .collect()
+        })
+        .collect();
+    let mut found_in: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (i, uuids) in holders.iter().enumerate() {
+        for uuid in uuids {
+            found_in.entry(uuid).or_default().push(i);
+        }
+    }
+    heads
+        .iter()
+        .enumerate()
+        .map(|(i, head)| {
+            // Files in start order, ties broken by position so no two precede each other.
+            let order = |j: usize| {
+                let start = heads[j].start.unwrap_or(f64::NEG_INFINITY);
+                move |k: usize| {
+                    let other = heads[k].start.unwrap_or(f64::NEG_INFINITY);
+                    other.total_cmp(&start).then(k.cmp(&j))
+                }
+            };
+            // The latest earlier file holding any probe: when a resume was itself
+            // resumed, both hold the copied history, and the newer is the parent.
+            head.probes
+                .iter()
+                .filter_map(|p| found_in.get(p.as_str()))
+                .flatten()
+                .copied()
+                .filter(|&j| order(i)(j).is_lt())
+                .max_by(|&a, &b| order(b)(a))
+        })
+        .collect()
+}
+
+fn find(parent: &mut [usize], i: usize) -> usize {
+    let mut root = i;
+    while parent[root] != root {
+        root = parent[root];
+    }
+    parent[i] = root;
+    root
+}
+
+/// Group Claude Code transcripts into the conversations they continue.
+///
+/// Resuming a session starts a new transcript that carries on an older one, so a
+/// single task can span several files. path is a directory searched recursively
+/// (subagent transcripts, agent-*.jsonl, are left out) or a transcript file. A file
+/// continues another when its first message repeats or replies to one of the
+/// other's, or its summary describes one of the other's messages; the latest such
+/// earlier file is its parent. Returns one dict per conversation, oldest first:
+/// session_ids and files (ordered by start time), parents (each file's parent
+/// session_id, None for the first, aligned with session_ids), project_path, start
+/// and end. Sessions that continue nothing and were never resumed make conversations
+/// of one, unless include_single=False.
+#[pyfunction]
+#[pyo3(signature = (path, include_single=true))]
+pub fn session_lineage<'py>(
+    py: Python<'py>,
+    path: &str,
+    include_single: bool,
+) -> PyResult<Bound<'py, PyList>> {
+    let root = PathBuf::from(path);
+    let (heads, parents) = py.detach(|| {
+        let files: Vec<PathBuf> =
+            if root.is_dir() { find_jsonl_files(&root) } else { vec![root.clone()] }
+                .into_iter()
+                .filter(|f| f.is_file())
+                .filter(|f| {
+                    !f.file_stem().and_then(|s| s.to_str()).is_some_and(|s| s.starts_with("agent-"))
+                })
+                .collect();
+        let heads: Vec<Head> = files.par_iter().map(|f| read_head(f)).collect();
+        let parents = link(&heads, &files);
+        (heads, parents)
+    });
+
+    let mut groups: Vec<usize> = (0..heads.len()).collect();
+    for (i, parent) in parents.iter().enumerate() {
+        if let Some(p) = *parent {
+            let (a, b) = (find(&mut groups, i), find(&mut groups, p));
+            groups[a] = b;
+        }
+    }
+    let mut members: HashMap<usize, Vec<usize>> = HashMap::new();
+    for i in 0..heads.len() {
+        let group = find(&mut groups, i);
+        members.entry(group).or_default().push(i);
+    }
+    let start = |i: usize| heads[i].start.unwrap_or(f64::INFINITY);
+    let mut chains: Vec<Vec<usize>> = members.into_values().collect();
+    for chain in &mut chains {
+        chain.sort_by(|&a, &b| start(a).total_cmp(&start(b)).then(a.cmp(&b)));
+    }
+    chains.sort_by(|a, b| start(a[0]).total_cmp(&start(b[0])).then(a[0].cmp(&b[0])));
+
+    let out = PyList::empty(py);
+    for chain in chains {
+        if chain.len() == 1 && !include_single {
+            continue;
+        }
+        let heads_of = || chain.iter().map(|&i| &heads[i]);
+        let dict = PyDict::new(py);
+        dict.set_item("session_ids", heads_of().map(|h| &h.session_id).collect::<Vec<_>>())?;
+        dict.set_item(
+            "files",
+            heads_of().map(|h| h.path.to_string_lossy().into_owned()).collect::<Vec<_>>(),
+        )?;
+        dict.set_item(
+            "parents",
+            chain
+                .iter()
+                .map(|&i| parents[i].map(|p| heads[p].session_id.as_str()))
+                .collect::<Vec<_>>(),
+        )?;
+        dict.set_item("project_path", heads_of().find_map(|h| h.project_path.as_deref()))?;
+        dict.set_item("start", heads_of().filter_map(|h| h.start).reduce(f64::min))?;
+        dict.set_item("end", heads_of().filter_map(|h| h.end).reduce(f64::max))?;
+        out.append(dict)?;
+    }
+    Ok(out)
+}
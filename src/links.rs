@@ -0,0 +1,179 @@
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use regex::Regex;
+
+use crate::privacy::host_of;
+use crate::timeline::timestamp_of;
+
+/// Event fields that may hold links, across message sources and transcripts.
+const TEXT_FIELDS: [&str; 4] = ["content_preview", "text", "subject", "content"];
+
+/// Fields naming the sender, in order of preference.
+const FROM_FIELDS: [&str; 4] = ["contact", "sender_name", "sender", "chat_name"];
+
+/// Fields carried over from the original event when it has them.
+const CONTEXT_FIELDS: [&str; 3] = ["session_id", "project_path", "chat_name"];
+
+/// An http(s) URL, or a bare "www." address.
+fn url_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r#"(?i)\b(?:https?://|www\.)[^\s<>"'`{}|\\^\[\]]+"#).unwrap())
+}
+
+/// A URL found in a piece of text.
+struct Link {
+    /// The URL, with "https://" added to bare "www." addresses.
+    url: String,
+    domain: String,
+    /// Byte range of the URL as written.
+    span: (usize, usize),
+}
+
+/// Drop what prose puts after a URL: sentence punctuation, and a closing
+/// parenthesis that has no opening one inside the URL.
+fn trim_url(url: &str) -> &str {
+    let mut url = url;
+    loop {
+        let trimmed = url.trim_end_matches(['.', ',', ';', ':', '!', '?', '*', '…']);
+        let trimmed = match trimmed.strip_suffix(')') {
+            Some(inner) if inner.matches('(').count() < inner.matches(')').count() + 1 => inner,
+            _ => trimmed,
+        };
+        if trimmed.len() == url.len() {
+            return url;
+        }
+        url = trimmed;
+    }
+}
+
+fn find_links(text: &str) -> Vec<Link> {
+    url_re()
+        .find_iter(text)
+        .filter_map(|m| {
+            let written = trim_url(m.as_str());
+            let url = if written.contains("://") {
+                written.to_string()
+            } else {
+                format!("https://{written}")
+            };
+            let host = host_of(&url);
+            let domain = host.strip_prefix("www.").unwrap_or(&host).to_string();
+            if !domain.contains('.') && domain != "localhost" {
+                return None;
+            }
+            Some(Link { url, domain, span: (m.start(), m.start() + written.len()) })
+        })
+        .collect()
+}
+
+fn text(dict: &Bound<'_, PyDict>, key: &str) -> PyResult<Option<String>> {
+    match dict.get_item(key)? {
+        Some(v) if !v.is_none() => Ok(Some(v.str()?.to_string())),
+        _ => Ok(None),
+    }
+}
+
+/// The URLs in a piece of text, in order.
+///
+/// Finds http(s) URLs and bare "www." addresses, leaving off trailing sentence
+/// punctuation and unmatched closing parentheses. Returns [{url, domain, start,
+/// end}], where url gets "https://" when the text left the scheme off, domain is
+/// the lowercase host without a leading "www.", and start and end are the
+/// character offsets of the URL as written.
+#[pyfunction]
+pub fn extract_urls<'py>(py: Python<'py>, text: &str) -> PyResult<Bound<'py, PyList>> {
+    let out = PyList::empty(py);
+    for link in find_links(text) {
+        let dict = PyDict::new(py);
+        dict.set_item("url", link.url)?;
+        dict.set_item("domain", link.domain)?;
+        dict.set_item("start", text[..link.span.0].chars().count())?;
+        dict.set_item("end", text[..link.span.1].chars().count())?;
+        out.append(dict)?;
+    }
+    Ok(out)
+}
+
+/// Link events for the URLs in messages and agent transcripts.
+///
+/// events are dicts from any source: Collector records, message exports, or
+/// parsed transcript events (no source, with a message_type). Text is read from
+/// content_preview, text, subject and content, and each distinct URL in an event
+/// becomes {source: "link", timestamp, link_source (the event's source, or
+/// "claude" for transcripts), from, outgoing, url, domain}, plus session_id,
+/// project_path and chat_name when the event has them. outgoing is True for
+/// messages the user sent (is_from_me, or kind "sent") and for the user's own
+/// prompts, so shared links can be told apart from ones received or produced by
+/// the agent. Browser visits aren't repeated here; merge these events with them
+/// for a full picture of the day's links.
+#[pyfunction]
+pub fn link_events<'py>(
+    py: Python<'py>,
+    events: &Bound<'py, PyAny>,
+) -> PyResult<Bound<'py, PyList>> {
+    let out = PyList::empty(py);
+    for item in events.try_iter()? {
+        let item = item?;
+        let Ok(event) = item.cast::<PyDict>() else {
+            continue;
+        };
+        let message_type = text(event, "message_type")?;
+        let source = match text(event, "source")? {
+            Some(source) => source,
+            None if message_type.is_some() => "claude".to_string(),
+            None => continue,
+        };
+        if source == "browser" || source == "link" {
+            continue;
+        }
+        let mut seen = HashSet::new();
+        let mut links = Vec::new();
+        for field in TEXT_FIELDS {
+            if let Some(body) = text(event, field)? {
+                links.extend(find_links(&body).into_iter().filter(|l| seen.insert(l.url.clone())));
+            }
+        }
+        if links.is_empty() {
+            continue;
+        }
+
+        let outgoing = match event.get_item("is_from_me")? {
+            Some(v) if !v.is_none() => v.is_truthy()?,
+            _ => {
+                text(event, "kind")?.as_deref() == Some("sent")
+                    || (source == "claude" && message_type.as_deref() == Some("user"))
+            }
+        };
+        let mut from = None;
+        for key in FROM_FIELDS {
+            from = text(event, key)?;
+            if from.is_some() {
+                break;
+            }
+        }
+        if from.is_none() && source == "claude" {
+            from = message_type;
+        }
+        let timestamp = timestamp_of(&item)?;
+        for link in links {
+            let dict = PyDict::new(py);
+            dict.set_item("source", "link")?;
+            dict.set_item("timestamp", timestamp)?;
+            dict.set_item("link_source", &source)?;
+            dict.set_item("from", &from)?;
+            dict.set_item("outgoing", outgoing)?;
+            dict.set_item("url", link.url)?;
+            dict.set_item("domain", link.domain)?;
+            for key in CONTEXT_FIELDS {
+                if let Some(value) = event.get_item(key)? {
+                    dict.set_item(key, value)?;
+                }
+            }
+            out.append(dict)?;
+        }
+    }
+    Ok(out)
+}
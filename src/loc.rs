@@ -0,0 +1,250 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use rayon::prelude::*;
+use serde_json::Value;
+
+use crate::rollup::{decode_project_dir, find_jsonl_files};
+use crate::timeutil::parse_iso_ts;
+
+/// Above this many line pairs an edit's changed region is counted whole rather than
+/// diffed.
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+/// Lines added and removed turning old into new: the lines outside their longest
+/// common subsequence.
+fn line_diff(old: &str, new: &str) -> (u64, u64) {
+    let (old, new): (Vec<&str>, Vec<&str>) = (old.lines().collect(), new.lines().collect());
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let (old, new) = (&old[prefix..], &new[prefix..]);
+    let suffix = old.iter().rev().zip(new.iter().rev()).take_while(|(a, b)| a == b).count();
+    let (old, new) = (&old[..old.len() - suffix], &new[..new.len() - suffix]);
+    if old.len().saturating_mul(new.len()) > MAX_DIFF_CELLS {
+        return (new.len() as u64, old.len() as u64);
+    }
+    let mut row = vec![0usize; new.len() + 1];
+    for a in old {
+        let mut diag = 0;
+        for (j, b) in new.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if a == b { diag + 1 } else { above.max(row[j]) };
+            diag = above;
+        }
+    }
+    let common = row[new.len()];
+    ((new.len() - common) as u64, (old.len() - common) as u64)
+}
+
+/// What one Edit, MultiEdit or Write call changed in a file.
+struct Change {
+    file: String,
+    added: u64,
+    removed: u64,
+}
+
+fn tool_change(name: &str, input: &Value) -> Option<Change> {
+    let text = |v: &Value, key: &str| v.get(key).and_then(Value::as_str).unwrap_or("").to_string();
+    let file = input.get("file_path").and_then(Value::as_str)?.to_string();
+    let (added, removed) = match name {
+        "Edit" => line_diff(&text(input, "old_string"), &text(input, "new_string")),
+        "MultiEdit" => input.get("edits").and_then(Value::as_array)?.iter().fold(
+            (0, 0),
+            |(added, removed), edit| {
+                let (a, r) = line_diff(&text(edit, "old_string"), &text(edit, "new_string"));
+                (added + a, removed + r)
+            },
+        ),
+        "Write" => (text(input, "content").lines().count() as u64, 0),
+        _ => return None,
+    };
+    Some(Change { file, added, removed })
+}
+
+#[derive(Default)]
+struct Counts {
+    added: u64,
+    removed: u64,
+    files: BTreeSet<String>,
+}
+
+impl Counts {
+    fn add(&mut self, change: &Change) {
+        self.added += change.added;
+        self.removed += change.removed;
+        self.files.insert(change.file.clone());
+    }
+
+    fn merge(&mut self, other: Counts) {
+        self.added += other.added;
+        self.removed += other.removed;
+        self.files.extend(other.files);
+    }
+
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("added", self.added)?;
+        dict.set_item("removed", self.removed)?;
+        dict.set_item("files", self.files.len())?;
+        Ok(dict)
+    }
+}
+
+#[derive(Default)]
+struct LocStats {
+    total: Counts,
+    edits: u64,
+    extensions: BTreeMap<String, Counts>,
+}
+
+impl LocStats {
+    fn add(&mut self, change: &Change) {
+        let ext = Path::new(&change.file)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_lowercase)
+            .unwrap_or_default();
+        self.total.add(change);
+        self.extensions.entry(ext).or_default().add(change);
+        self.edits += 1;
+    }
+
+    fn merge(&mut self, other: LocStats) {
+        self.total.merge(other.total);
+        self.edits += other.edits;
+        for (ext, counts) in other.extensions {
+            self.extensions.entry(ext).or_default().merge(counts);
+        }
+    }
+}
+
+fn scan_file(path: &Path, by_project: bool, since: Option<f64>) -> (String, LocStats) {
+    let mut key = if by_project {
+        path.parent()
+            .and_then(|p| p.file_name())
+            .and_then(|n| n.to_str())
+            .map(decode_project_dir)
+            .unwrap_or_default()
+    } else {
+        path.file_stem().and_then(|s| s.to_str()).unwrap_or("").to_string()
+    };
+    let mut stats = LocStats::default();
+    let file = match File::open(path) {
+        Ok(f) => f,
+        Err(_) => return (key, stats),
+    };
+
+    let mut key_from_cwd = false;
+    // Calls by tool_use id until their result is seen, so failed ones can be dropped.
+    let mut changes: Vec<Option<Change>> = Vec::new();
+    let mut by_id: HashMap<String, usize> = HashMap::new();
+    for line in BufReader::new(file).lines() {
+        let line = match line {
+            Ok(l) => l,
+            Err(_) => break,
+        };
+        let entry: Value = match serde_json::from_str(line.trim()) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        if by_project && !key_from_cwd {
+            if let Some(cwd) = entry.get("cwd").and_then(Value::as_str) {
+                key = cwd.to_string();
+                key_from_cwd = true;
+            }
+        }
+        let blocks = match entry["message"].get("content").and_then(Value::as_array) {
+            Some(arr) => arr,
+            None => continue,
+        };
+        let ts = entry.get("timestamp").and_then(Value::as_str).and_then(parse_iso_ts);
+        for block in blocks {
+            match block.get("type").and_then(Value::as_str) {
+                Some("tool_use") => {
+                    if since.is_some_and(|s| ts.is_some_and(|t| t < s)) {
+                        continue;
+                    }
+                    let name = block.get("name").and_then(Value::as_str).unwrap_or("");
+                    let Some(change) = tool_change(name, &block["input"]) else {
+                        continue;
+                    };
+                    if let Some(id) = block.get("id").and_then(Value::as_str) {
+                        by_id.insert(id.to_string(), changes.len());
+                    }
+                    changes.push(Some(change));
+                }
+                Some("tool_result") => {
+                    let failed = block.get("is_error").and_then(Value::as_bool).unwrap_or(false);
+                    let id = block.get("tool_use_id").and_then(Value::as_str).unwrap_or("");
+                    if let (true, Some(&i)) = (failed, by_id.get(id)) {
+                        changes[i] = None;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    for change in changes.iter().flatten() {
+        stats.add(change);
+    }
+    (key, stats)
+}
+
+/// Lines of code the agent added and removed, per session or project and file
+/// extension, from its Edit, MultiEdit and Write calls.
+///
+/// path is a transcript file or a directory searched recursively. group_by is
+/// "session" or "project"; since (epoch seconds) skips earlier calls. Edits count the
+/// lines outside what old_string and new_string have in common, so changing one line
+/// is one added and one removed; Write counts every line as added, since what it
+/// overwrote isn't recorded. Calls whose result was an error are left out. Returns
+/// {group: {added, removed, files, edits, by_extension: {ext: {added, removed,
+/// files}}}}, with ext lowercased and without its dot ("" for none); groups without
+/// edits are omitted.
+#[pyfunction]
+#[pyo3(signature = (path, group_by="session", since=None))]
+pub fn loc_stats<'py>(
+    py: Python<'py>,
+    path: &str,
+    group_by: &str,
+    since: Option<f64>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let by_project = match group_by {
+        "session" => false,
+        "project" => true,
+        _ => {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "group_by must be 'session' or 'project', got {group_by:?}"
+            )))
+        }
+    };
+    let root = PathBuf::from(path);
+    let groups = py.detach(|| {
+        let files = if root.is_dir() { find_jsonl_files(&root) } else { vec![root.clone()] };
+        let per_file: Vec<(String, LocStats)> =
+            files.par_iter().map(|f| scan_file(f, by_project, since)).collect();
+        let mut groups: BTreeMap<String, LocStats> = BTreeMap::new();
+        for (key, stats) in per_file {
+            if stats.edits > 0 {
+                groups.entry(key).or_default().merge(stats);
+            }
+        }
+        groups
+    });
+
+    let out = PyDict::new(py);
+    for (key, stats) in groups {
+        let dict = stats.total.to_dict(py)?;
+        dict.set_item("edits", stats.edits)?;
+        let extensions = PyDict::new(py);
+        for (ext, counts) in &stats.extensions {
+            extensions.set_item(ext, counts.to_dict(py)?)?;
+        }
+        dict.set_item("by_extension", extensions)?;
+        out.set_item(key, dict)?;
+    }
+    Ok(out)
+}
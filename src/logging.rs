@@ -0,0 +1,121 @@
+use std::fmt::{Debug, Write as _};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use tracing::field::{Field, Visit};
+use tracing::subscriber::Interest;
+use tracing::{Event, Level, Metadata, Subscriber};
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+
+/// Python logging level below which Rust-side records are dropped. WARNING by default so
+/// debug chatter (skipped lines, retries) costs nothing until someone asks for it.
+static MIN_LEVEL: AtomicU8 = AtomicU8::new(30);
+
+const LEVEL_NAMES: [(&str, u8); 8] = [
+    ("CRITICAL", 50),
+    ("FATAL", 50),
+    ("ERROR", 40),
+    ("WARNING", 30),
+    ("WARN", 30),
+    ("INFO", 20),
+    ("DEBUG", 10),
+    ("TRACE", 5),
+];
+
+fn python_level(level: &Level) -> u8 {
+    match *level {
+        Level::ERROR => 40,
+        Level::WARN => 30,
+        Level::INFO => 20,
+        Level::DEBUG => 10,
+        Level::TRACE => 5,
+    }
+}
+
+/// The message plus any structured fields, rendered as "message key=value ...".
+#[derive(Default)]
+struct Message {
+    text: String,
+    fields: String,
+}
+
+impl Visit for Message {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.text.push_str(value);
+        } else {
+            let _ = write!(self.fields, " {}={value}", field.name());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.text, "{value:?}");
+        } else {
+            let _ = write!(self.fields, " {}={value:?}", field.name());
+        }
+    }
+}
+
+/// Forwards tracing events to `logging.getLogger(<module>)`, so `snoopy_native::watcher`
+/// logs as `snoopy_native.watcher` and the usual handler configuration applies.
+struct PythonLayer;
+
+impl<S: Subscriber> Layer<S> for PythonLayer {
+    fn register_callsite(&self, _metadata: &'static Metadata<'static>) -> Interest {
+        // The level can change at runtime, so ask enabled() every time.
+        Interest::sometimes()
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>, _ctx: Context<'_, S>) -> bool {
+        python_level(metadata.level()) >= MIN_LEVEL.load(Ordering::Relaxed)
+    }
+
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut message = Message::default();
+        event.record(&mut message);
+        message.text.push_str(&message.fields);
+        let name = event.metadata().target().replace("::", ".");
+        let level = python_level(event.metadata().level());
+        // Interpreter shutdown: nowhere left to log to.
+        Python::try_attach(|py| {
+            let logged = py
+                .import("logging")
+                .and_then(|logging| logging.call_method1("getLogger", (name,)))
+                .and_then(|logger| logger.call_method1("log", (level, message.text)));
+            if let Err(e) = logged {
+                e.print(py);
+            }
+        });
+    }
+}
+
+/// Route Rust-side tracing events to Python logging. Called once at module import; a
+/// subscriber installed by an embedding application takes precedence.
+pub(crate) fn install() {
+    let subscriber = tracing_subscriber::registry().with(PythonLayer);
+    let _ = tracing::subscriber::set_global_default(subscriber);
+}
+
+/// Set the minimum level of Rust-side records forwarded to Python logging.
+///
+/// level is a logging level number (logging.DEBUG) or name ("debug", "warning", "trace").
+/// Records below it are never formatted, so leaving it at the default WARNING keeps the
+/// hot paths free. Loggers are named after the Rust module, e.g. "snoopy_native.watcher";
+/// their own levels and handlers still apply on top of this.
+#[pyfunction]
+pub fn set_log_level(level: &Bound<'_, PyAny>) -> PyResult<()> {
+    let value = if let Ok(number) = level.extract::<i64>() {
+        u8::try_from(number.max(0)).map_err(|_| PyValueError::new_err("level out of range"))?
+    } else {
+        let name: String = level.extract()?;
+        LEVEL_NAMES
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name.trim()))
+            .map(|&(_, v)| v)
+            .ok_or_else(|| PyValueError::new_err(format!("unknown log level: {name}")))?
+    };
+    MIN_LEVEL.store(value, Ordering::Relaxed);
+    Ok(())
+}
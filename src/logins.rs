@@ -0,0 +1,185 @@
+use std::process::Command;
+use std::sync::OnceLock;
+
+use chrono::{Datelike, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+use regex::Regex;
+use serde_json::json;
+
+use crate::collector::{now, record, records_to_py, Record};
+
+/// "user  tty  [host]  Fri Mar  1 09:15[:02 2024]  <rest>", where rest is "- 09:20
+/// (00:05)", "- Fri Mar  1 09:20:11 2024  (00:05)", "still logged in", "- crash"
+/// and so on. Linux reboots put "system boot" in the tty column.
+fn last_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(concat!(
+            r"^(\S+)\s+(system boot|\S+)\s+(?:(\S+)\s+)??",
+            r"((?:Mon|Tue|Wed|Thu|Fri|Sat|Sun) \w{3} +\d+ \d\d:\d\d(?::\d\d)?(?: \d{4})?)",
+            r"\s*(.*)$",
+        ))
+        .unwrap()
+    })
+}
+
+/// "(00:05)" or "(1+02:03)": days, hours and minutes.
+fn duration_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\((?:(\d+)\+)?(\d+):(\d\d)\)\s*$").unwrap())
+}
+
+fn local_ts(at: NaiveDateTime) -> Option<f64> {
+    Some(Local.from_local_datetime(&at).earliest()?.timestamp() as f64)
+}
+
+/// A start time as `last` prints it. Without -F there is no year, so take the most
+/// recent year, not in the future, whose calendar puts that date on that weekday.
+fn start_time(text: &str) -> Option<f64> {
+    let parts: Vec<&str> = text.split_whitespace().collect();
+    let (weekday, month, day, clock) = (parts[0], parts[1], parts[2], parts[3]);
+    let time = NaiveTime::parse_from_str(clock, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(clock, "%H:%M"))
+        .ok()?;
+    let on = |year: i32| NaiveDate::parse_from_str(&format!("{year} {month} {day}"), "%Y %b %d");
+    if let Some(year) = parts.get(4) {
+        return local_ts(on(year.parse().ok()?).ok()?.and_time(time));
+    }
+    let this_year = Local::now().year();
+    (this_year - 7..=this_year).rev().find_map(|year| {
+        let date = on(year).ok()?;
+        let ts = local_ts(date.and_time(time))?;
+        (date.format("%a").to_string() == weekday && ts <= now() + 86_400.0).then_some(ts)
+    })
+}
+
+pub(crate) fn parse_output(text: &str, since: f64) -> Vec<Record> {
+    let mut events = Vec::new();
+    for line in text.lines() {
+        // "wtmp begins Mon Jan  1 00:00:00 2024" closes the listing.
+        if line.contains(" begins ") {
+            continue;
+        }
+        let Some(caps) = last_re().captures(line.trim_end()) else { continue };
+        let Some(timestamp) = start_time(&caps[4]) else { continue };
+        if timestamp <= since {
+            continue;
+        }
+        let (user, tty) = (&caps[1], &caps[2]);
+        let host = caps.get(3).map(|m| m.as_str()).filter(|h| *h != "~");
+        let rest = caps[5].trim();
+        let duration = duration_re().captures(rest).map(|d| {
+            let days: f64 = d.get(1).map_or(0.0, |m| m.as_str().parse().unwrap_or(0.0));
+            let (hours, minutes): (f64, f64) =
+                (d[2].parse().unwrap_or(0.0), d[3].parse().unwrap_or(0.0));
+            days * 86_400.0 + hours * 3600.0 + minutes * 60.0
+        });
+        // With -F the end is a full date, which is more precise than "(hh:mm)".
+        let end = rest
+            .strip_prefix('-')
+            .map(|end| end.split_whitespace().take(5).collect::<Vec<_>>())
+            .filter(|words| words.len() == 5 && words[4].len() == 4)
+            .and_then(|words| start_time(&words.join(" ")))
+            .or(duration.map(|d| timestamp + d));
+        let duration = end.map(|end| end - timestamp);
+        let ongoing = rest.starts_with("still");
+        // How the session ended when not a normal logout: crash, down (shut down while
+        // logged in) or gone (the session vanished without logging out).
+        let ending = rest.strip_prefix('-').map(str::trim).and_then(|end| {
+            end.split_whitespace()
+                .next()
+                .filter(|w| matches!(*w, "crash" | "down"))
+                .map(String::from)
+        });
+        let ending = if rest.starts_with("gone") { Some("gone".to_string()) } else { ending };
+        let kind = match user {
+            "reboot" => "reboot",
+            "shutdown" => "shutdown",
+            _ if host.is_some_and(|h| !h.starts_with(':')) && tty != "system boot" => "ssh",
+            _ if tty == "console" || tty.starts_with("tty") && !tty.starts_with("ttys") => {
+                "console"
+            }
+            _ => "terminal",
+        };
+        // A reboot's "host" on Linux is the kernel version it booted.
+        let (host, kernel) = if kind == "reboot" { (None, host) } else { (host, None) };
+        let summary = match kind {
+            "reboot" => "rebooted".to_string(),
+            "shutdown" => "shut down".to_string(),
+            "ssh" => format!("{user} ssh login from {}", host.unwrap_or_default()),
+            _ => format!("{user} {kind} login on {tty}"),
+        };
+        events.push(record(
+            "login",
+            json!({
+                "timestamp": timestamp,
+                "end": end,
+                "duration": duration,
+                "kind": kind,
+                "user": user,
+                "tty": tty,
+                "remote_address": host,
+                "kernel": kernel,
+                "ongoing": ongoing,
+                "ending": ending,
+                "summary": summary,
+            }),
+        ));
+    }
+    // last lists newest first; timelines read oldest first.
+    events.reverse();
+    events
+}
+
+/// Parse `last` output (macOS or Linux, with or without -F) into login, reboot and
+/// shutdown events, oldest first.
+///
+/// Each line becomes {source: "login", timestamp, end, duration, kind, user, tty,
+/// remote_address, kernel, ongoing, ending, summary}. kind is "ssh" for sessions
+/// from a remote host, "console" for the console and Linux virtual terminals,
+/// "terminal" for other local terminals, or "reboot" / "shutdown". end comes from
+/// the full end date with -F, otherwise from the "(hh:mm)" column; end and duration
+/// (seconds) are None while ongoing (still logged in or running). ending is "crash",
+/// "down" or "gone" when the session didn't end with a logout. kernel is the booted
+/// kernel for Linux reboot lines. Without a year in the output, each date gets the
+/// latest year that matches its weekday. Only events starting after since (epoch
+/// seconds) are returned.
+#[pyfunction]
+#[pyo3(signature = (text, since=None))]
+pub fn parse_last<'py>(
+    py: Python<'py>,
+    text: &str,
+    since: Option<f64>,
+) -> PyResult<Bound<'py, PyList>> {
+    let since = since.unwrap_or(f64::NEG_INFINITY);
+    let events = py.detach(|| parse_output(text, since));
+    records_to_py(py, events)
+}
+
+/// Run `last` (with -F on Linux, for full dates) and parse it with parse_last.
+///
+/// Raises RuntimeError if last fails and IOError if it can't be run.
+#[pyfunction]
+#[pyo3(signature = (since=None))]
+pub fn login_sessions(py: Python<'_>, since: Option<f64>) -> PyResult<Bound<'_, PyList>> {
+    let since = since.unwrap_or(f64::NEG_INFINITY);
+    let events = py.detach(|| -> PyResult<Vec<Record>> {
+        let mut command = Command::new("last");
+        if cfg!(target_os = "linux") {
+            command.arg("-F");
+        }
+        let output = command
+            .output()
+            .map_err(|e| pyo3::exceptions::PyIOError::new_err(format!("last: {e}")))?;
+        if !output.status.success() {
+            return Err(pyo3::exceptions::PyRuntimeError::new_err(format!(
+                "last failed ({}): {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(parse_output(&String::from_utf8_lossy(&output.stdout), since))
+    })?;
+    records_to_py(py, events)
+}
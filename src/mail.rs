@@ -0,0 +1,132 @@
+use std::path::PathBuf;
+
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+use rusqlite::Connection;
+use serde_json::json;
+
+use crate::collector::{home_path, record, records_to_py, Record};
+use crate::history::{has_table, with_snapshot};
+
+/// Mailbox names that hold the user's own outgoing mail, across IMAP, Exchange and
+/// Gmail accounts.
+const SENT_MAILBOXES: [&str; 4] = ["sent messages", "sent items", "sent mail", "sent"];
+
+/// The newest Mail data version's Envelope Index (V10 on macOS 13+, V9, ...).
+fn default_index() -> PathBuf {
+    let mail = home_path("Library/Mail");
+    let newest = std::fs::read_dir(&mail)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().into_owned();
+            name.strip_prefix('V')?.parse::<u32>().ok().map(|v| (v, e.path()))
+        })
+        .max_by_key(|(v, _)| *v);
+    newest.map_or_else(|| mail.join("V10"), |(_, dir)| dir).join("MailData/Envelope Index")
+}
+
+/// "imap://6F1C.../%5BGmail%5D/Sent%20Mail" → "[Gmail]/Sent Mail".
+fn mailbox_name(url: &str) -> String {
+    let path = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let path = path.split_once('/').map_or("", |(_, path)| path);
+    percent_encoding::percent_decode_str(path).decode_utf8_lossy().into_owned()
+}
+
+fn is_sent(mailbox: &str) -> bool {
+    let last = mailbox.rsplit('/').next().unwrap_or(mailbox).to_lowercase();
+    SENT_MAILBOXES.contains(&last.as_str())
+}
+
+fn read_mail(conn: &Connection, since: f64, include_body: bool) -> rusqlite::Result<Vec<Record>> {
+    let preview = if include_body && has_table(conn, "summaries")? {
+        "(SELECT s.summary FROM summaries s WHERE s.ROWID = m.summary)"
+    } else {
+        "NULL"
+    };
+    let sql = format!(
+        "SELECT m.ROWID, m.message_id, a.address, a.comment,
+                COALESCE(m.subject_prefix, '') || COALESCE(s.subject, ''), mb.url,
+                m.date_sent, m.date_received, m.read, m.flagged, {preview}
+         FROM messages m
+         LEFT JOIN addresses a ON a.ROWID = m.sender
+         LEFT JOIN subjects s ON s.ROWID = m.subject
+         LEFT JOIN mailboxes mb ON mb.ROWID = m.mailbox
+         WHERE COALESCE(m.deleted, 0) = 0
+           AND MAX(COALESCE(m.date_sent, 0), COALESCE(m.date_received, 0)) > ?
+         ORDER BY m.ROWID"
+    );
+    let mut recipients = if has_table(conn, "recipients")? {
+        Some(conn.prepare(
+            "SELECT a.address FROM recipients r JOIN addresses a ON a.ROWID = r.address
+             WHERE r.message = ? ORDER BY r.type, r.position",
+        )?)
+    } else {
+        None
+    };
+    let mut stmt = conn.prepare(&sql)?;
+    let mut rows = stmt.query([since])?;
+    let mut events = Vec::new();
+    while let Some(row) = rows.next()? {
+        let mailbox = row.get::<_, Option<String>>(5)?.map(|url| mailbox_name(&url));
+        let sent = mailbox.as_deref().is_some_and(is_sent);
+        let ts: Option<f64> = if sent { row.get(6)? } else { row.get(7)? };
+        let Some(ts) = ts.or(row.get(7)?).filter(|ts| *ts > since) else { continue };
+        let to: Vec<String> = match &mut recipients {
+            Some(stmt) => stmt
+                .query_map([row.get::<_, i64>(0)?], |r| r.get::<_, Option<String>>(0))?
+                .filter_map(|a| a.transpose())
+                .collect::<rusqlite::Result<_>>()?,
+            None => Vec::new(),
+        };
+        let subject: String = row.get(4)?;
+        events.push(record(
+            "mail",
+            json!({
+                "kind": if sent { "sent" } else { "received" },
+                "timestamp": ts,
+                "message_id": row.get::<_, Option<i64>>(1)?,
+                "sender": row.get::<_, Option<String>>(2)?,
+                "sender_name": row.get::<_, Option<String>>(3)?.filter(|n| !n.is_empty()),
+                "recipient": to.first(),
+                "recipients": to,
+                "subject": (!subject.is_empty()).then_some(subject),
+                "mailbox": mailbox,
+                "read": row.get::<_, Option<i64>>(8)?.unwrap_or(0) != 0,
+                "flagged": row.get::<_, Option<i64>>(9)?.unwrap_or(0) != 0,
+                "content_preview": row.get::<_, Option<String>>(10)?,
+            }),
+        ));
+    }
+    events.sort_by(|a, b| {
+        let ts = |r: &Record| r["timestamp"].as_f64().unwrap_or_default();
+        ts(a).total_cmp(&ts(b))
+    });
+    Ok(events)
+}
+
+/// Read sent and received mail from Apple Mail's Envelope Index.
+///
+/// Events are {source: "mail", kind ("sent" or "received"), timestamp, message_id,
+/// sender, sender_name, recipient, recipients, subject, mailbox, read, flagged,
+/// content_preview}, oldest first. Messages in a Sent mailbox are "sent" at their
+/// send time, everything else "received" at its arrival; deleted messages are
+/// skipped. recipient is the first of recipients (To before Cc). Only the index is
+/// read, never the message files; content_preview, the snippet Mail shows in its
+/// message list, is None unless include_body is set. Only events after since (epoch
+/// seconds) are returned. path defaults to the newest ~/Library/Mail/V*/MailData
+/// index, which needs Full Disk Access.
+#[pyfunction]
+#[pyo3(signature = (path=None, since=None, include_body=false))]
+pub fn mail_events<'py>(
+    py: Python<'py>,
+    path: Option<PathBuf>,
+    since: Option<f64>,
+    include_body: bool,
+) -> PyResult<Bound<'py, PyList>> {
+    let path = path.unwrap_or_else(default_index);
+    let since = since.unwrap_or(f64::NEG_INFINITY);
+    let events = py.detach(|| with_snapshot(&path, |conn| read_mail(conn, since, include_body)))?;
+    records_to_py(py, events)
+}
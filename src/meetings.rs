@@ -0,0 +1,461 @@
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use serde_json::json;
+
+use crate::collector::{now, record, records_to_py, Record, Sampler};
+use crate::frontmost::front_window;
+
+/// How to recognise a meeting service: its apps and helper processes, window title
+/// fragments (for browser-based meetings) and the remote ports its media uses.
+struct Platform {
+    name: &'static str,
+    apps: &'static [&'static str],
+    titles: &'static [&'static str],
+    ports: &'static [(u16, u16)],
+}
+
+const PLATFORMS: [Platform; 6] = [
+    Platform {
+        name: "zoom",
+        // CptHost only runs while Zoom is in a meeting.
+        apps: &["zoom.us", "zoom", "cpthost", "zoom workplace"],
+        titles: &["zoom meeting", "zoom webinar"],
+        ports: &[(8801, 8810)],
+    },
+    Platform {
+        name: "meet",
+        apps: &[],
+        titles: &["meet - ", "google meet", "meet.google.com"],
+        ports: &[(19302, 19309)],
+    },
+    Platform {
+        name: "teams",
+        apps: &["microsoft teams", "msteams", "teams", "microsoft teams (work or school)"],
+        titles: &["| microsoft teams"],
+        ports: &[],
+    },
+    Platform {
+        name: "webex",
+        apps: &["webex", "cisco webex meetings", "webexmeetings"],
+        titles: &["webex"],
+        ports: &[],
+    },
+    Platform { name: "facetime", apps: &["facetime"], titles: &[], ports: &[] },
+    Platform { name: "slack", apps: &["slack"], titles: &["huddle"], ports: &[] },
+];
+/// Seconds without microphone or camera before a meeting is over.
+pub(crate) const DEFAULT_GRACE: f64 = 60.0;
+
+/// Signals sampled at one moment.
+#[derive(Default)]
+pub(crate) struct Signals {
+    mic: bool,
+    camera: bool,
+    app: Option<String>,
+    title: Option<String>,
+    /// (process name, remote port) of open connections.
+    connections: Vec<(String, u16)>,
+}
+
+/// The meeting service the signals point to: the focused app or its window title
+/// first, then a meeting client's connections, then media ports.
+fn identify(signals: &Signals) -> Option<(&'static str, Option<String>)> {
+    let app = signals.app.as_deref().map(str::to_lowercase).unwrap_or_default();
+    let title = signals.title.as_deref().map(str::to_lowercase).unwrap_or_default();
+    for p in &PLATFORMS {
+        if p.apps.contains(&app.as_str()) || p.titles.iter().any(|t| title.contains(t)) {
+            return Some((p.name, signals.app.clone()));
+        }
+    }
+    for (process, _) in &signals.connections {
+        let name = process.to_lowercase();
+        if let Some(p) = PLATFORMS.iter().find(|p| p.apps.contains(&name.as_str())) {
+            return Some((p.name, Some(process.clone())));
+        }
+    }
+    for (process, port) in &signals.connections {
+        let in_range = |p: &&Platform| p.ports.iter().any(|(lo, hi)| (lo..=hi).contains(&port));
+        if let Some(p) = PLATFORMS.iter().find(in_range) {
+            return Some((p.name, Some(process.clone())));
+        }
+    }
+    None
+}
+
+struct Meeting {
+    platform: &'static str,
+    app: Option<String>,
+    started: f64,
+    last_seen: f64,
+    camera: bool,
+}
+
+/// Turns samples of microphone and camera use, the focused app and network
+/// connections into "meeting_started" and "meeting_ended" events.
+///
+/// A meeting is under way while the microphone or camera is in use and the
+/// signals point to a meeting service (Zoom, Google Meet, Teams, Webex, FaceTime or
+/// a Slack huddle): its app or a browser tab titled for it is focused, or its client
+/// or media traffic is connected. Once identified, a meeting keeps its platform
+/// while the user switches to other apps. It ends when neither microphone nor camera
+/// has been in use for grace seconds, so muting briefly or a device hiccup doesn't
+/// split it; the end is the last time either was in use.
+///
+/// update() takes one sample and returns the events it completes: {source:
+/// "meetings", kind, timestamp, platform, app}, plus started, duration and camera
+/// (whether the camera was on at any point) for "meeting_ended". finish() ends a
+/// meeting in progress. Collector(sources=["meetings"]) samples the system itself.
+#[pyclass]
+pub struct MeetingDetector {
+    grace: f64,
+    current: Option<Meeting>,
+}
+
+impl MeetingDetector {
+    pub(crate) fn new(grace: f64) -> Self {
+        MeetingDetector { grace, current: None }
+    }
+
+    pub(crate) fn observe(&mut self, ts: f64, signals: &Signals) -> Vec<Record> {
+        let in_use = signals.mic || signals.camera;
+        if let Some(meeting) = &mut self.current {
+            if in_use {
+                meeting.last_seen = ts;
+                meeting.camera |= signals.camera;
+            } else if ts - meeting.last_seen >= self.grace {
+                return self.end().into_iter().collect();
+            }
+            return Vec::new();
+        }
+        let Some((platform, app)) = in_use.then(|| identify(signals)).flatten() else {
+            return Vec::new();
+        };
+        self.current = Some(Meeting {
+            platform,
+            app: app.clone(),
+            started: ts,
+            last_seen: ts,
+            camera: signals.camera,
+        });
+        vec![record(
+            "meetings",
+            json!({"kind": "meeting_started", "timestamp": ts, "platform": platform, "app": app}),
+        )]
+    }
+
+    fn end(&mut self) -> Option<Record> {
+        let meeting = self.current.take()?;
+        Some(record(
+            "meetings",
+            json!({
+                "kind": "meeting_ended",
+                "timestamp": meeting.last_seen,
+                "platform": meeting.platform,
+                "app": meeting.app,
+                "started": meeting.started,
+                "duration": meeting.last_seen - meeting.started,
+                "camera": meeting.camera,
+            }),
+        ))
+    }
+}
+
+#[pymethods]
+impl MeetingDetector {
+    #[new]
+    #[pyo3(signature = (grace=DEFAULT_GRACE))]
+    fn py_new(grace: f64) -> PyResult<Self> {
+        if grace.is_nan() || grace < 0.0 {
+            return Err(pyo3::exceptions::PyValueError::new_err("grace must be non-negative"));
+        }
+        Ok(MeetingDetector::new(grace))
+    }
+
+    /// Take one sample. app and title are the focused app and window title;
+    /// connections are network events (dicts with process_name and remote_port).
+    /// timestamp defaults to now.
+    #[pyo3(signature = (
+        mic=false, camera=false, app=None, title=None, connections=None, timestamp=None
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn update<'py>(
+        &mut self,
+        py: Python<'py>,
+        mic: bool,
+        camera: bool,
+        app: Option<String>,
+        title: Option<String>,
+        connections: Option<Vec<Bound<'py, PyDict>>>,
+        timestamp: Option<f64>,
+    ) -> PyResult<Bound<'py, PyList>> {
+        let mut signals = Signals { mic, camera, app, title, connections: Vec::new() };
+        for conn in connections.unwrap_or_default() {
+            let process: Option<String> = match conn.get_item("process_name")? {
+                Some(v) if !v.is_none() => Some(v.extract()?),
+                _ => None,
+            };
+            let port: Option<u16> = match conn.get_item("remote_port")? {
+                Some(v) if !v.is_none() => Some(v.extract()?),
+                _ => None,
+            };
+            signals.connections.push((process.unwrap_or_default(), port.unwrap_or(0)));
+        }
+        let events = self.observe(timestamp.unwrap_or_else(now), &signals);
+        records_to_py(py, events)
+    }
+
+    /// End the meeting in progress, if any, at the last time it was seen.
+    fn finish<'py>(&mut self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
+        records_to_py(py, self.end().into_iter().collect())
+    }
+
+    /// The platform of the meeting in progress, or None.
+    #[getter]
+    fn platform(&self) -> Option<&'static str> {
+        self.current.as_ref().map(|m| m.platform)
+    }
+}
+
+/// Whether the default microphone is running, via CoreAudio's
+/// kAudioDevicePropertyDeviceIsRunningSomewhere.
+#[cfg(target_os = "macos")]
+fn mic_in_use() -> Option<bool> {
+    use std::ffi::c_void;
+
+    #[repr(C)]
+    struct PropertyAddress {
+        selector: u32,
+        scope: u32,
+        element: u32,
+    }
+    const SYSTEM_OBJECT: u32 = 1;
+    const DEFAULT_INPUT_DEVICE: u32 = u32::from_be_bytes(*b"dIn ");
+    const IS_RUNNING_SOMEWHERE: u32 = u32::from_be_bytes(*b"gone");
+    const SCOPE_GLOBAL: u32 = u32::from_be_bytes(*b"glob");
+
+    #[link(name = "CoreAudio", kind = "framework")]
+    extern "C" {
+        fn AudioObjectGetPropertyData(
+            object: u32,
+            address: *const PropertyAddress,
+            qualifier_size: u32,
+            qualifier: *const c_void,
+            data_size: *mut u32,
+            data: *mut c_void,
+        ) -> i32;
+    }
+
+    let get = |object: u32, selector: u32| -> Option<u32> {
+        let address = PropertyAddress { selector, scope: SCOPE_GLOBAL, element: 0 };
+        let mut value: u32 = 0;
+        let mut size = std::mem::size_of::<u32>() as u32;
+        // SAFETY: both properties are UInt32s and size matches the buffer passed.
+        let status = unsafe {
+            AudioObjectGetPropertyData(
+                object,
+                &address,
+                0,
+                std::ptr::null(),
+                &mut size,
+                &mut value as *mut u32 as *mut c_void,
+            )
+        };
+        (status == 0).then_some(value)
+    };
+    let device = get(SYSTEM_OBJECT, DEFAULT_INPUT_DEVICE).filter(|d| *d != 0)?;
+    Some(get(device, IS_RUNNING_SOMEWHERE)? != 0)
+}
+
+/// Whether any camera is running, via CoreMediaIO's
+/// kCMIODevicePropertyDeviceIsRunningSomewhere on each video device.
+#[cfg(target_os = "macos")]
+fn camera_in_use() -> Option<bool> {
+    use std::ffi::c_void;
+
+    #[repr(C)]
+    struct PropertyAddress {
+        selector: u32,
+        scope: u32,
+        element: u32,
+    }
+    const SYSTEM_OBJECT: u32 = 1;
+    const DEVICES: u32 = u32::from_be_bytes(*b"dev#");
+    const IS_RUNNING_SOMEWHERE: u32 = u32::from_be_bytes(*b"gone");
+    const SCOPE_GLOBAL: u32 = u32::from_be_bytes(*b"glob");
+
+    #[link(name = "CoreMediaIO", kind = "framework")]
+    extern "C" {
+        fn CMIOObjectGetPropertyDataSize(
+            object: u32,
+            address: *const PropertyAddress,
+            qualifier_size: u32,
+            qualifier: *const c_void,
+            data_size: *mut u32,
+        ) -> i32;
+        fn CMIOObjectGetPropertyData(
+            object: u32,
+            address: *const PropertyAddress,
+            qualifier_size: u32,
+            qualifier: *const c_void,
+            data_size: u32,
+            data_used: *mut u32,
+            data: *mut c_void,
+        ) -> i32;
+    }
+
+    let devices_address = PropertyAddress { selector: DEVICES, scope: SCOPE_GLOBAL, element: 0 };
+    let mut size: u32 = 0;
+    // SAFETY: only writes the property's size into size.
+    let status = unsafe {
+        CMIOObjectGetPropertyDataSize(
+            SYSTEM_OBJECT,
+            &devices_address,
+            0,
+            std::ptr::null(),
+            &mut size,
+        )
+    };
+    if status != 0 {
+        return None;
+    }
+    let mut devices = vec![0u32; size as usize / std::mem::size_of::<u32>()];
+    let mut used: u32 = 0;
+    // SAFETY: devices holds size bytes of CMIOObjectIDs (UInt32s).
+    let status = unsafe {
+        CMIOObjectGetPropertyData(
+            SYSTEM_OBJECT,
+            &devices_address,
+            0,
+            std::ptr::null(),
+            size,
+            &mut used,
+            devices.as_mut_ptr() as *mut c_void,
+        )
+    };
+    if status != 0 {
+        return None;
+    }
+    devices.truncate(used as usize / std::mem::size_of::<u32>());
+    let running_address =
+        PropertyAddress { selector: IS_RUNNING_SOMEWHERE, scope: SCOPE_GLOBAL, element: 0 };
+    Some(devices.iter().any(|device| {
+        let mut running: u32 = 0;
+        let mut used: u32 = 0;
+        // SAFETY: the property is a UInt32, matching the size passed.
+        let status = unsafe {
+            CMIOObjectGetPropertyData(
+                *device,
+                &running_address,
+                0,
+                std::ptr::null(),
+                std::mem::size_of::<u32>() as u32,
+                &mut used,
+                &mut running as *mut u32 as *mut c_void,
+            )
+        };
+        status == 0 && running != 0
+    }))
+}
+
+/// Whether any ALSA capture stream is running.
+#[cfg(not(target_os = "macos"))]
+fn mic_in_use() -> Option<bool> {
+    let cards = std::fs::read_dir("/proc/asound").ok()?;
+    let mut running = false;
+    for card in cards.flatten().filter(|c| c.file_name().to_string_lossy().starts_with("card")) {
+        let Ok(pcms) = std::fs::read_dir(card.path()) else { continue };
+        for pcm in pcms.flatten() {
+            let name = pcm.file_name().to_string_lossy().into_owned();
+            if !(name.starts_with("pcm") && name.ends_with('c')) {
+                continue;
+            }
+            let Ok(subs) = std::fs::read_dir(pcm.path()) else { continue };
+            running |= subs.flatten().any(|sub| {
+                std::fs::read_to_string(sub.path().join("status"))
+                    .is_ok_and(|s| s.contains("state: RUNNING"))
+            });
+        }
+    }
+    Some(running)
+}
+
+/// Whether any process has a /dev/video* device open.
+#[cfg(not(target_os = "macos"))]
+fn camera_in_use() -> Option<bool> {
+    let procs = std::fs::read_dir("/proc").ok()?;
+    Some(procs.flatten().any(|p| {
+        std::fs::read_dir(p.path().join("fd")).is_ok_and(|fds| {
+            fds.flatten().any(|fd| {
+                std::fs::read_link(fd.path())
+                    .is_ok_and(|target| target.to_string_lossy().starts_with("/dev/video"))
+            })
+        })
+    }))
+}
+
+/// (process, remote port) for each connected socket in `lsof -i -n -P` output,
+/// UDP as well as TCP, since meeting media mostly travels over UDP.
+fn lsof_endpoints(output: &str) -> Vec<(String, u16)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let process = line.split_whitespace().next()?.replace("\\x20", " ");
+            let (_, remote) = line.split_once("->")?;
+            let remote = remote.split_whitespace().next()?;
+            let port = remote.rsplit_once(':')?.1.parse().ok()?;
+            Some((process, port))
+        })
+        .collect()
+}
+
+fn sample_signals() -> Signals {
+    let window = front_window();
+    let connections = std::process::Command::new("lsof")
+        .args(["-i", "-n", "-P"])
+        .output()
+        .map(|o| lsof_endpoints(&String::from_utf8_lossy(&o.stdout)))
+        .unwrap_or_default();
+    Signals {
+        mic: mic_in_use().unwrap_or(false),
+        camera: camera_in_use().unwrap_or(false),
+        app: window.as_ref().map(|w| w.app.clone()),
+        title: window.map(|w| w.title),
+        connections,
+    }
+}
+
+/// Samples microphone, camera, focus and connections into a MeetingDetector.
+pub(crate) struct MeetingSampler {
+    detector: MeetingDetector,
+}
+
+impl MeetingSampler {
+    pub(crate) fn new(grace: f64) -> Self {
+        MeetingSampler { detector: MeetingDetector::new(grace) }
+    }
+}
+
+impl Sampler for MeetingSampler {
+    fn name(&self) -> &'static str {
+        "meetings"
+    }
+
+    fn sample(&mut self) -> Result<Vec<Record>, String> {
+        let signals = sample_signals();
+        Ok(self.detector.observe(now(), &signals))
+    }
+}
+
+/// Whether the microphone and camera are in use right now: {mic, camera}, each None
+/// if it can't be determined.
+///
+/// Uses CoreAudio and CoreMediaIO on macOS; on Linux, running ALSA capture streams
+/// and processes holding /dev/video* open.
+#[pyfunction]
+pub fn media_in_use(py: Python<'_>) -> PyResult<Bound<'_, PyDict>> {
+    let (mic, camera) = py.detach(|| (mic_in_use(), camera_in_use()));
+    let dict = PyDict::new(py);
+    dict.set_item("mic", mic)?;
+    dict.set_item("camera", camera)?;
+    Ok(dict)
+}
@@ -0,0 +1,151 @@
+use pyo3::prelude::*;
+
+use crate::parserconfig::ParserConfig;
+use crate::privacy::filter_transcript_events;
+use crate::{parse_transcript_impl, truncate_str, TranscriptEvent};
+
+const PROMPT_SNIPPET_LEN: usize = 60;
+
+/// One step of agent activity; consecutive steps with the same verb are merged.
+struct Action {
+    verb: String,
+    objects: Vec<String>,
+}
+
+impl Action {
+    fn render(&self) -> String {
+        let n = self.objects.len();
+        match self.verb.as_str() {
+            "read" | "edited" | "wrote" => {
+                let mut distinct = self.objects.clone();
+                distinct.sort();
+                distinct.dedup();
+                if distinct.len() == 1 {
+                    format!("{} {}", self.verb, distinct[0])
+                } else {
+                    format!("{} {} files", self.verb, distinct.len())
+                }
+            }
+            verb if n > 1 => format!("{verb} {n}×"),
+            verb => verb.to_string(),
+        }
+    }
+}
+
+fn basename(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+/// Map a transcript event to a (verb, object) pair, or None if it isn't narrated.
+fn describe(ev: &TranscriptEvent) -> Option<(String, String)> {
+    let preview = ev.content_preview.as_str();
+    let (kind, name) = ev
+        .message_type
+        .split_once(':')
+        .unwrap_or((ev.message_type.as_str(), ""));
+    let step = match (kind, name) {
+        ("tool_use", "Read") => ("read".to_string(), basename(preview).to_string()),
+        ("tool_use", "Edit" | "MultiEdit" | "NotebookEdit") => {
+            ("edited".to_string(), basename(preview).to_string())
+        }
+        ("tool_use", "Write") => {
+            let path = preview.split(" (").next().unwrap_or(preview);
+            ("wrote".to_string(), basename(path).to_string())
+        }
+        ("tool_use", "Bash") => {
+            let program = preview
+                .split_whitespace()
+                .find(|w| !w.contains('='))
+                .map(basename)
+                .unwrap_or("a command");
+            (format!("ran {program}"), String::new())
+        }
+        ("tool_use", "Grep" | "Glob") => ("searched the code".to_string(), String::new()),
+        ("tool_use", "WebFetch" | "WebSearch") => ("browsed the web".to_string(), String::new()),
+        ("tool_use", "Task") => (format!("delegated \"{preview}\""), String::new()),
+        ("tool_use", "TodoWrite") => ("updated the todo list".to_string(), String::new()),
+        ("tool_use", tool) => (format!("used {tool}"), String::new()),
+        ("permission_denied", tool) => (format!("user denied {tool}"), String::new()),
+        ("user_interrupt", _) => ("user interrupted".to_string(), String::new()),
+        ("compaction", _) => ("context compacted".to_string(), String::new()),
+        _ => return None,
+    };
+    Some(step)
+}
+
+pub(crate) fn format_clock(ts: f64, utc_offset: i64) -> String {
+    let secs = (ts.floor() as i64 + utc_offset).rem_euclid(86400);
+    format!("{:02}:{:02}", secs / 3600, (secs % 3600) / 60)
+}
+
+fn prompt_snippet(text: &str) -> String {
+    let first_line = text.lines().find(|l| !l.trim().is_empty()).unwrap_or("").trim();
+    let snippet = truncate_str(first_line, PROMPT_SNIPPET_LEN);
+    if snippet.len() < first_line.len() || text.trim().lines().count() > 1 {
+        format!("{snippet}…")
+    } else {
+        snippet.to_string()
+    }
+}
+
+/// Emit one line for the current turn: header followed by the merged agent actions.
+fn flush_turn(header: &mut Option<String>, actions: &mut Vec<Action>, lines: &mut Vec<String>) {
+    if header.is_none() && actions.is_empty() {
+        return;
+    }
+    let mut parts: Vec<String> = header.take().into_iter().collect();
+    for (i, action) in actions.drain(..).enumerate() {
+        let step = action.render();
+        parts.push(if i == 0 { format!("agent {step}") } else { step });
+    }
+    lines.push(parts.join(" → "));
+}
+
+pub(crate) fn narrate_events(events: &[TranscriptEvent], utc_offset: i64) -> String {
+    let mut lines = Vec::new();
+    let mut header: Option<String> = None;
+    let mut actions: Vec<Action> = Vec::new();
+
+    for ev in events {
+        if ev.message_type == "user" {
+            flush_turn(&mut header, &mut actions, &mut lines);
+            header = Some(format!(
+                "{} user asked: {}",
+                format_clock(ev.timestamp, utc_offset),
+                prompt_snippet(&ev.content_preview),
+            ));
+            continue;
+        }
+        let (verb, object) = match describe(ev) {
+            Some(step) => step,
+            None => continue,
+        };
+        if header.is_none() && actions.is_empty() {
+            header = Some(format_clock(ev.timestamp, utc_offset));
+        }
+        match actions.last_mut() {
+            Some(last) if last.verb == verb => last.objects.push(object),
+            _ => actions.push(Action {
+                verb,
+                objects: vec![object],
+            }),
+        }
+    }
+    flush_turn(&mut header, &mut actions, &mut lines);
+    lines.join("\n")
+}
+
+/// Render a transcript as a compact text timeline, one line per user turn.
+///
+/// e.g. "09:12 user asked: fix flaky test → agent read 4 files → ran pytest 3× →
+/// edited test_foo.py". utc_offset (seconds) shifts the clock times to local time.
+/// The installed privacy filter applies as in parse_transcript.
+#[pyfunction]
+#[pyo3(signature = (path, utc_offset=0))]
+pub fn narrate_session(py: Python<'_>, path: &str, utc_offset: i64) -> PyResult<String> {
+    py.detach(|| {
+        let (mut events, _) = parse_transcript_impl(path, 0, &ParserConfig::default())?;
+        filter_transcript_events(&mut events);
+        Ok(narrate_events(&events, utc_offset))
+    })
+}
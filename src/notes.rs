@@ -0,0 +1,176 @@
+use std::io::Read;
+use std::path::PathBuf;
+
+use flate2::read::GzDecoder;
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+use rusqlite::Connection;
+use serde_json::json;
+
+use crate::collector::{home_path, record, records_to_py, Record};
+use crate::history::with_snapshot;
+
+/// Notes stores times as seconds since 2001-01-01 (Core Data reference date).
+const COCOA_EPOCH_OFFSET: f64 = 978_307_200.0;
+const NOTES_DB: &str = "Library/Group Containers/group.com.apple.notes/NoteStore.sqlite";
+/// NoteStoreProto.document (2) -> Document.note (3) -> Note.note_text (2).
+const NOTE_TEXT_PATH: [u64; 3] = [2, 3, 2];
+const MAX_DEPTH: usize = 6;
+
+/// One protobuf field: its number and, for length-delimited fields, the payload.
+struct Field<'a> {
+    number: u64,
+    bytes: Option<&'a [u8]>,
+}
+
+fn varint(data: &[u8], pos: &mut usize) -> Option<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *data.get(*pos)?;
+        *pos += 1;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// Decode the top-level fields of a message, or None if it isn't valid wire format.
+fn fields(data: &[u8]) -> Option<Vec<Field<'_>>> {
+    let mut pos = 0;
+    let mut out = Vec::new();
+    while pos < data.len() {
+        let tag = varint(data, &mut pos)?;
+        let number = tag >> 3;
+        if number == 0 {
+            return None;
+        }
+        let bytes = match tag & 7 {
+            0 => {
+                varint(data, &mut pos)?;
+                None
+            }
+            1 | 5 => {
+                pos += if tag & 7 == 1 { 8 } else { 4 };
+                None
+            }
+            2 => {
+                let len = usize::try_from(varint(data, &mut pos)?).ok()?;
+                let chunk = data.get(pos..pos.checked_add(len)?)?;
+                pos += len;
+                Some(chunk)
+            }
+            _ => return None,
+        };
+        out.push(Field { number, bytes });
+    }
+    (pos == data.len()).then_some(out)
+}
+
+fn printable(text: &str) -> bool {
+    !text.is_empty() && text.chars().all(|c| !c.is_control() || matches!(c, '\n' | '\r' | '\t'))
+}
+
+/// The longest printable string anywhere in the message, for blobs whose layout
+/// doesn't match the known NoteStoreProto path.
+fn longest_string(data: &[u8], depth: usize) -> Option<&str> {
+    if depth > MAX_DEPTH {
+        return None;
+    }
+    fields(data)?
+        .into_iter()
+        .filter_map(|f| f.bytes)
+        .filter_map(|chunk| match std::str::from_utf8(chunk) {
+            Ok(text) if printable(text) => Some(text),
+            _ => longest_string(chunk, depth + 1),
+        })
+        .max_by_key(|text| text.len())
+}
+
+/// Plain text of a note from its ZICNOTEDATA.ZDATA blob (gzip-compressed protobuf).
+fn note_text(zdata: &[u8]) -> String {
+    let mut proto = Vec::new();
+    if GzDecoder::new(zdata).read_to_end(&mut proto).is_err() {
+        return String::new();
+    }
+    let mut message: &[u8] = &proto;
+    for number in NOTE_TEXT_PATH {
+        match fields(message).and_then(|fs| fs.into_iter().find(|f| f.number == number)) {
+            Some(Field { bytes: Some(chunk), .. }) => message = chunk,
+            _ => return longest_string(&proto, 0).unwrap_or_default().to_string(),
+        }
+    }
+    String::from_utf8_lossy(message).into_owned()
+}
+
+fn read_notes(conn: &Connection, since: f64) -> rusqlite::Result<Vec<Record>> {
+    let mut stmt = conn.prepare(
+        "SELECT c1.ZIDENTIFIER, c1.ZTITLE1, c1.ZCREATIONDATE3, c1.ZMODIFICATIONDATE1,
+                c2.ZTITLE2, c5.ZNAME, n.ZDATA
+         FROM ZICCLOUDSYNCINGOBJECT c1
+         LEFT JOIN ZICCLOUDSYNCINGOBJECT c2 ON c2.Z_PK = c1.ZFOLDER
+         LEFT JOIN ZICCLOUDSYNCINGOBJECT c5 ON c5.Z_PK = c1.ZACCOUNT2
+         LEFT JOIN ZICNOTEDATA n ON n.Z_PK = c1.ZNOTEDATA
+         WHERE c1.ZTITLE1 IS NOT NULL
+           AND MAX(COALESCE(c1.ZCREATIONDATE3, 0), COALESCE(c1.ZMODIFICATIONDATE1, 0)) > ?",
+    )?;
+    let rows = stmt.query_map([since - COCOA_EPOCH_OFFSET], |row| {
+        let created = row.get::<_, Option<f64>>(2)?.map(|t| t + COCOA_EPOCH_OFFSET);
+        let modified = row.get::<_, Option<f64>>(3)?.map(|t| t + COCOA_EPOCH_OFFSET);
+        let note = json!({
+            "note_id": row.get::<_, Option<String>>(0)?,
+            "title": row.get::<_, String>(1)?,
+            "folder": row.get::<_, Option<String>>(4)?,
+            "account": row.get::<_, Option<String>>(5)?,
+            "content": row.get::<_, Option<Vec<u8>>>(6)?.map(|z| note_text(&z)).unwrap_or_default(),
+            "created": created,
+        });
+        Ok((note, created, modified))
+    })?;
+
+    let mut events = Vec::new();
+    for row in rows {
+        let (note, created, modified) = row?;
+        let mut emit = |kind: &str, ts: f64| {
+            if ts > since {
+                let mut fields = note.clone();
+                fields["kind"] = json!(kind);
+                fields["timestamp"] = json!(ts);
+                events.push(record("notes", fields));
+            }
+        };
+        if let Some(created) = created {
+            emit("created", created);
+        }
+        if let Some(modified) = modified.filter(|m| created.is_none_or(|c| *m > c)) {
+            emit("modified", modified);
+        }
+    }
+    events.sort_by(|a, b| {
+        let ts = |r: &Record| r["timestamp"].as_f64().unwrap_or_default();
+        ts(a).total_cmp(&ts(b))
+    });
+    Ok(events)
+}
+
+/// Read note create and modify events from the Apple Notes NoteStore.sqlite.
+///
+/// Each note yields a {kind: "created"} event at its creation time and, if it was
+/// edited later, a {kind: "modified"} event at its last modification. Events are
+/// {source: "notes", kind, timestamp, note_id, title, folder, account, content,
+/// created}, oldest first, where content is the plain text decoded from the note's
+/// gzip-compressed protobuf body. Only events after since (epoch seconds) are
+/// returned. path defaults to the Notes group container, which needs Full Disk Access.
+#[pyfunction]
+#[pyo3(signature = (path=None, since=None))]
+pub fn notes_events<'py>(
+    py: Python<'py>,
+    path: Option<PathBuf>,
+    since: Option<f64>,
+) -> PyResult<Bound<'py, PyList>> {
+    let path = path.unwrap_or_else(|| home_path(NOTES_DB));
+    let since = since.unwrap_or(f64::NEG_INFINITY);
+    let events = py.detach(|| with_snapshot(&path, |conn| read_notes(conn, since)))?;
+    records_to_py(py, events)
+}
@@ -0,0 +1,233 @@
+use std::sync::OnceLock;
+
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use regex::Regex;
+
+use crate::timeline::timestamp_of;
+
+/// Message fields that may hold a verification code, across message sources.
+const TEXT_FIELDS: [&str; 3] = ["content_preview", "text", "subject"];
+
+/// Fields naming the sender, in order of preference.
+const FROM_FIELDS: [&str; 4] = ["contact", "sender_name", "sender", "chat_name"];
+
+/// Words that mark a message as carrying a one-time code.
+fn keyword_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(concat!(
+            r"(?i)\b(?:code|passcode|otp|one[- ]time|verification|verify|2fa|two[- ]factor|",
+            r"pin|security|log ?in|sign[- ]?in|authenticat\w*|confirm\w*)\b",
+        ))
+        .unwrap()
+    })
+}
+
+/// A 4 to 8 digit number, possibly split in two ("123 456", "123-456"). ASCII digits
+/// only: \d would also match other scripts' digits, which the code is built without.
+fn code_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?:^|[^\w])([0-9]{3}[- ][0-9]{3}|[0-9]{4,8})(?:$|[^\w])").unwrap()
+    })
+}
+
+/// The service a code is for: "Apple: ...", "[Acme] ...", "Your Acme code ...".
+fn service_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(concat!(
+            r"^\s*(?:<#>\s*)?(?:\[([^\]\n]{1,30})\]|([A-Z][\w&.' -]{1,30}?)\s*:)",
+            r"|\b[Yy]our ([A-Z][\w&.'-]*(?: [A-Z][\w&.'-]*){0,2}) ",
+            r"(?:verification |security |login |sign-in )?",
+            r"(?:code|passcode|PIN)\b",
+            r"|\b(?:for|to) ([A-Z][\w&.'-]{1,30})\b",
+        ))
+        .unwrap()
+    })
+}
+
+/// A verification code found in a message.
+struct Found {
+    /// The code's digits, without separators.
+    code: String,
+    service: Option<String>,
+    /// Byte range of the code in the text, separators included.
+    span: (usize, usize),
+}
+
+/// Whether the number at start..end of text could be a code rather than part of a
+/// date, time, price, version or phone number.
+fn is_code_context(text: &str, start: usize, end: usize) -> bool {
+    let mut before = text[..start].chars().rev();
+    let before_ok = match (before.next(), before.next()) {
+        (Some(c), _) if "$€£#./:+".contains(c) => false,
+        (Some(' ' | '-'), Some(d)) => !d.is_ascii_digit(),
+        _ => true,
+    };
+    // A code may end a sentence, but not run on into more digits.
+    let mut after = text[end..].chars();
+    let after_ok = match (after.next(), after.next()) {
+        (Some(c), Some(d)) => !("/.:,- ".contains(c) && d.is_ascii_digit()),
+        _ => true,
+    };
+    before_ok && after_ok
+}
+
+fn find_code(text: &str) -> Option<Found> {
+    let keywords: Vec<usize> = keyword_re().find_iter(text).map(|m| m.start()).collect();
+    if keywords.is_empty() {
+        return None;
+    }
+    // Codes never overlap, so scanning from the end of each match is enough.
+    let mut candidates = Vec::new();
+    let mut at = 0;
+    while let Some(c) = code_re().captures_at(text, at) {
+        let digits = c.get(1).unwrap();
+        at = digits.end();
+        if is_code_context(text, digits.start(), digits.end()) {
+            let distance = keywords.iter().map(|&k| k.abs_diff(digits.start())).min()?;
+            candidates.push((distance, digits));
+        }
+    }
+    // The candidate nearest a keyword.
+    let (_, code) = candidates.into_iter().min_by_key(|(distance, _)| *distance)?;
+    let service = service_re().captures_iter(text).find_map(|c| {
+        let name = c.iter().skip(1).flatten().next()?.as_str().trim();
+        (!keyword_re().is_match(name)).then(|| name.to_string())
+    });
+    Some(Found {
+        code: code.as_str().chars().filter(char::is_ascii_digit).collect(),
+        service,
+        span: (code.start(), code.end()),
+    })
+}
+
+/// text with every copy of code replaced by "[REDACTED]", however its digits are
+/// separated, since SMS one-time-code messages repeat it ("... #482913").
+fn redact_code(text: &str, code: &str) -> String {
+    // An empty pattern would match between every character.
+    if code.is_empty() {
+        return text.to_string();
+    }
+    let pattern = code.chars().map(String::from).collect::<Vec<_>>().join("[- ]?");
+    let Ok(re) = Regex::new(&pattern) else {
+        return text.to_string();
+    };
+    let digit_at = |i: Option<char>| i.is_some_and(|c| c.is_ascii_digit());
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    for m in re.find_iter(text) {
+        // Part of a longer number, not a copy of the code.
+        if digit_at(text[..m.start()].chars().next_back())
+            || digit_at(text[m.end()..].chars().next())
+        {
+            continue;
+        }
+        out.push_str(&text[last..m.start()]);
+        out.push_str("[REDACTED]");
+        last = m.end();
+    }
+    out.push_str(&text[last..]);
+    out
+}
+
+fn text(dict: &Bound<'_, PyDict>, key: &str) -> PyResult<Option<String>> {
+    match dict.get_item(key)? {
+        Some(v) if !v.is_none() => Ok(Some(v.str()?.to_string())),
+        _ => Ok(None),
+    }
+}
+
+/// The verification code in a message's text, or None.
+///
+/// A code is a 4 to 8 digit number (or two groups of three, "123 456") in text that
+/// mentions a code, verification, login, PIN or the like; the one nearest such a
+/// word wins, and dates, times, prices and version numbers are skipped. Returns
+/// {code (digits only), service (the sender's name for itself, when the text says,
+/// such as "Apple" or "Acme"), start, end (the code's character offsets in text)}.
+#[pyfunction]
+pub fn find_verification_code<'py>(
+    py: Python<'py>,
+    text: &str,
+) -> PyResult<Option<Bound<'py, PyDict>>> {
+    let Some(found) = find_code(text) else {
+        return Ok(None);
+    };
+    let dict = PyDict::new(py);
+    dict.set_item("code", found.code)?;
+    dict.set_item("service", found.service)?;
+    dict.set_item("start", text[..found.span.0].chars().count())?;
+    dict.set_item("end", text[..found.span.1].chars().count())?;
+    Ok(Some(dict))
+}
+
+/// Verification events for the incoming messages that carry a one-time code.
+///
+/// events are message dicts from any source (the Collector's "messages" source,
+/// whatsapp_messages, slack_messages, mail_messages and the like); the text is
+/// looked for in content_preview, text and subject, and messages the user sent
+/// (is_from_me, or kind "sent") are skipped. Each hit becomes {source:
+/// "verification", timestamp, message_source, from, service, code,
+/// content_preview}. With redact=True (the default) the code is "[REDACTED]" in
+/// code and wherever it appears in content_preview, so the timeline shows the
+/// login without keeping the secret; redact=False keeps it, for hooks that act
+/// on it.
+#[pyfunction]
+#[pyo3(signature = (events, redact=true))]
+pub fn verification_events<'py>(
+    py: Python<'py>,
+    events: &Bound<'py, PyAny>,
+    redact: bool,
+) -> PyResult<Bound<'py, PyList>> {
+    let out = PyList::empty(py);
+    for item in events.try_iter()? {
+        let item = item?;
+        let Ok(event) = item.cast::<PyDict>() else {
+            continue;
+        };
+        let from_me = match event.get_item("is_from_me")? {
+            Some(v) if !v.is_none() => v.is_truthy()?,
+            _ => false,
+        };
+        if from_me || text(event, "kind")?.as_deref() == Some("sent") {
+            continue;
+        }
+        let mut hit = None;
+        for field in TEXT_FIELDS {
+            if let Some(body) = text(event, field)? {
+                if let Some(found) = find_code(&body) {
+                    hit = Some((body, found));
+                    break;
+                }
+            }
+        }
+        let Some((body, found)) = hit else {
+            continue;
+        };
+        let (code, body) = if redact {
+            let body = redact_code(&body, &found.code);
+            ("[REDACTED]".to_string(), body)
+        } else {
+            (found.code, body)
+        };
+        let mut from = None;
+        for key in FROM_FIELDS {
+            from = text(event, key)?;
+            if from.is_some() {
+                break;
+            }
+        }
+        let dict = PyDict::new(py);
+        dict.set_item("source", "verification")?;
+        dict.set_item("timestamp", timestamp_of(&item)?)?;
+        dict.set_item("message_source", text(event, "source")?)?;
+        dict.set_item("from", from)?;
+        dict.set_item("service", found.service)?;
+        dict.set_item("code", code)?;
+        dict.set_item("content_preview", body)?;
+        out.append(dict)?;
+    }
+    Ok(out)
+}
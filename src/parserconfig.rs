@@ -0,0 +1,417 @@
+use std::borrow::Cow;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict, PyList, PyTuple};
+use serde::{Deserialize, Serialize};
+
+use crate::dedup::content_hash_hex;
+use crate::privacy::{filter_transcript_events, global_filter, FilterOptions, PrivacyFilter};
+use crate::timeutil::Zone;
+use crate::{truncate_str, RawMode, TranscriptEvent};
+
+/// Default content_preview length, in bytes.
+pub(crate) const DEFAULT_PREVIEW_LEN: usize = 500;
+
+/// How content longer than preview_len is shortened.
+#[derive(Clone, Copy, PartialEq)]
+enum Truncation {
+    /// Keep the start.
+    End,
+    /// Keep the start and the end around an ellipsis.
+    Middle,
+    /// Keep everything.
+    None,
+}
+
+impl Truncation {
+    fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "end" => Ok(Truncation::End),
+            "middle" => Ok(Truncation::Middle),
+            "none" => Ok(Truncation::None),
+            _ => Err(PyValueError::new_err(format!(
+                "truncation must be 'end', 'middle' or 'none', not {name:?}"
+            ))),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Truncation::End => "end",
+            Truncation::Middle => "middle",
+            Truncation::None => "none",
+        }
+    }
+}
+
+/// How event timestamps are handed to Python.
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum TimeFormat {
+    /// Epoch seconds as a float.
+    Epoch,
+    /// An aware datetime.datetime in the zone.
+    Datetime(Zone),
+    /// An ISO 8601 string in the zone, e.g. "2024-03-01T12:00:00.000Z" for UTC.
+    Iso(Zone),
+}
+
+impl TimeFormat {
+    fn parse(name: &str, zone: Zone) -> PyResult<Self> {
+        match name {
+            "epoch" => Ok(TimeFormat::Epoch),
+            "datetime" => Ok(TimeFormat::Datetime(zone)),
+            "iso" => Ok(TimeFormat::Iso(zone)),
+            _ => Err(PyValueError::new_err(format!(
+                "time_format must be 'epoch', 'datetime' or 'iso', not {name:?}"
+            ))),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            TimeFormat::Epoch => "epoch",
+            TimeFormat::Datetime(_) => "datetime",
+            TimeFormat::Iso(_) => "iso",
+        }
+    }
+
+    /// The timestamp in this format; None for the 0.0 "no timestamp" sentinel unless epoch.
+    pub(crate) fn convert<'py>(self, py: Python<'py>, ts: f64) -> PyResult<Bound<'py, PyAny>> {
+        match self {
+            TimeFormat::Epoch => Ok(ts.into_pyobject(py)?.into_any()),
+            TimeFormat::Datetime(zone) => zone.datetime(py, ts),
+            TimeFormat::Iso(zone) => Ok(zone.iso(ts).into_pyobject(py)?.into_any()),
+        }
+    }
+}
+
+/// Transcript parsing options shared by parse_transcript and replay_transcript.
+///
+/// preview_len caps content_preview in bytes; truncation is "end" (keep the start),
+/// "middle" (keep both ends around "…") or "none". include_raw is as in
+/// parse_transcript. message_types keeps only events of those types, where "hook" or
+/// "system" also matches "hook:Stop" and friends. privacy is a PrivacyFilter applied
+/// after the process-wide one. time_format is "epoch" (float seconds), "datetime" or
+/// "iso"; the last two are in timezone, "UTC" unless given "local" or an IANA name
+/// such as "Europe/Paris". content_hash adds a "content_hash" field to each event, the
+/// BLAKE3 hash (hex) of its full text before truncation, for dedup_events and storage
+/// that wants to collapse repeats. Configs pickle and round-trip through
+/// to_json()/from_json().
+#[pyclass(frozen, from_py_object, module = "snoopy_native")]
+#[derive(Clone)]
+pub struct ParserConfig {
+    pub(crate) preview_len: usize,
+    truncation: Truncation,
+    pub(crate) include_raw: RawMode,
+    message_types: Option<Vec<String>>,
+    privacy: Option<PrivacyFilter>,
+    pub(crate) time_format: TimeFormat,
+    timezone: Zone,
+    content_hash: bool,
+}
+
+/// include_raw as written by to_json(): a flag or a list of fields.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum RawOption {
+    Flag(bool),
+    Fields(Vec<String>),
+}
+
+/// ParserConfig's constructor keywords, as stored by to_json().
+#[derive(Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ConfigOptions {
+    preview_len: usize,
+    truncation: String,
+    include_raw: RawOption,
+    message_types: Option<Vec<String>>,
+    privacy: Option<FilterOptions>,
+    time_format: String,
+    timezone: String,
+    content_hash: bool,
+}
+
+impl Default for ConfigOptions {
+    fn default() -> Self {
+        ConfigOptions {
+            preview_len: DEFAULT_PREVIEW_LEN,
+            truncation: "end".to_string(),
+            include_raw: RawOption::Flag(false),
+            message_types: None,
+            privacy: None,
+            time_format: "epoch".to_string(),
+            timezone: "UTC".to_string(),
+            content_hash: false,
+        }
+    }
+}
+
+impl Default for ParserConfig {
+    fn default() -> Self {
+        ParserConfig {
+            preview_len: DEFAULT_PREVIEW_LEN,
+            truncation: Truncation::End,
+            include_raw: RawMode::Off,
+            message_types: None,
+            privacy: None,
+            time_format: TimeFormat::Epoch,
+            timezone: Zone::UTC,
+            content_hash: false,
+        }
+    }
+}
+
+impl ParserConfig {
+    pub(crate) fn with_preview_len(preview_len: usize) -> Self {
+        ParserConfig { preview_len, ..Default::default() }
+    }
+
+    /// Apply the preview_len and datetimes keywords some functions still take directly.
+    pub(crate) fn with_overrides(
+        mut self,
+        preview_len: Option<usize>,
+        datetimes: Option<bool>,
+    ) -> Self {
+        if let Some(len) = preview_len {
+            self.preview_len = len;
+        }
+        match datetimes {
+            Some(true) => self.time_format = TimeFormat::Datetime(self.timezone),
+            Some(false) => self.time_format = TimeFormat::Epoch,
+            None => {}
+        }
+        self
+    }
+
+    /// Shorten text for content_preview.
+    pub(crate) fn preview(&self, text: &str) -> String {
+        let max = self.preview_len;
+        if text.len() <= max {
+            return text.to_string();
+        }
+        match self.truncation {
+            Truncation::None => text.to_string(),
+            Truncation::End => truncate_str(text, max).to_string(),
+            // Too short for the ellipsis itself: keep the start.
+            Truncation::Middle if max < '…'.len_utf8() => truncate_str(text, max).to_string(),
+            Truncation::Middle => {
+                let budget = max - '…'.len_utf8();
+                let head = truncate_str(text, budget - budget / 2);
+                let mut start = text.len() - budget / 2;
+                while !text.is_char_boundary(start) {
+                    start += 1;
+                }
+                format!("{head}…{}", &text[start..])
+            }
+        }
+    }
+
+    /// The content_hash of text, if the config asks for one. The text is hashed as the
+    /// privacy filters leave it, since a short redacted secret could otherwise be
+    /// recovered by hashing guesses.
+    pub(crate) fn hash(&self, text: &str) -> Option<String> {
+        if !self.content_hash {
+            return None;
+        }
+        Some(content_hash_hex(self.screen(text).0.as_bytes()))
+    }
+
+    /// An event carrying text, to fill in the rest of: its content_preview and
+    /// content_hash, and withheld if deny_content matches. The filters see the full
+    /// text, so a secret or keyword past preview_len is still caught.
+    pub(crate) fn event(&self, text: &str) -> TranscriptEvent {
+        let (text, withheld) = self.screen(text);
+        TranscriptEvent {
+            content_preview: self.preview(&text),
+            content_hash: self.content_hash.then(|| content_hash_hex(text.as_bytes())),
+            withheld,
+            ..Default::default()
+        }
+    }
+
+    /// text with the installed privacy filter's redactions and then this config's
+    /// applied, and whether either filter's deny_content matches it.
+    fn screen<'a>(&self, text: &'a str) -> (Cow<'a, str>, bool) {
+        let global = global_filter();
+        let mut text = Cow::Borrowed(text);
+        let mut denied = false;
+        for filter in global.as_deref().into_iter().chain(self.privacy.as_ref()) {
+            denied |= filter.denies_content(&text);
+            if let Some(redacted) = filter.redact(&text) {
+                text = Cow::Owned(redacted);
+            }
+        }
+        (text, denied)
+    }
+
+    fn wants(&self, message_type: &str) -> bool {
+        match &self.message_types {
+            None => true,
+            Some(types) => {
+                let family = message_type.split(':').next().unwrap_or(message_type);
+                types.iter().any(|t| t == message_type || t == family)
+            }
+        }
+    }
+
+    /// Apply the process-wide privacy filter, then this config's filter and type list.
+    pub(crate) fn filter_events(&self, events: &mut Vec<TranscriptEvent>) {
+        filter_transcript_events(events);
+        if let Some(privacy) = &self.privacy {
+            privacy.apply_transcript(events);
+        }
+        if self.message_types.is_some() {
+            events.retain(|ev| self.wants(&ev.message_type));
+        }
+    }
+}
+
+/// Shorten text to at most max_len bytes the way content_preview is, never splitting a
+/// character. truncation is "end", "middle" or "none", as in ParserConfig.
+#[pyfunction]
+#[pyo3(signature = (text, max_len=DEFAULT_PREVIEW_LEN, truncation="end"))]
+pub fn truncate_preview(text: &str, max_len: usize, truncation: &str) -> PyResult<String> {
+    let config = ParserConfig {
+        preview_len: max_len,
+        truncation: Truncation::parse(truncation)?,
+        ..Default::default()
+    };
+    Ok(config.preview(text))
+}
+
+#[pymethods]
+impl ParserConfig {
+    #[new]
+    #[pyo3(signature = (
+        *, preview_len=DEFAULT_PREVIEW_LEN, truncation="end", include_raw=None,
+        message_types=None, privacy=None, time_format="epoch", timezone="UTC",
+        content_hash=false
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        preview_len: usize,
+        truncation: &str,
+        include_raw: Option<&Bound<'_, PyAny>>,
+        message_types: Option<Vec<String>>,
+        privacy: Option<PrivacyFilter>,
+        time_format: &str,
+        timezone: &str,
+        content_hash: bool,
+    ) -> PyResult<Self> {
+        let timezone = Zone::parse(timezone)?;
+        Ok(ParserConfig {
+            preview_len,
+            truncation: Truncation::parse(truncation)?,
+            include_raw: RawMode::from_py(include_raw)?,
+            message_types,
+            privacy,
+            time_format: TimeFormat::parse(time_format, timezone)?,
+            timezone,
+            content_hash,
+        })
+    }
+
+    /// Rebuild a config from to_json() output. Raises ValueError if it doesn't parse.
+    #[staticmethod]
+    fn from_json(text: &str) -> PyResult<Self> {
+        let options: ConfigOptions =
+            serde_json::from_str(text).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        let timezone = Zone::parse(&options.timezone)?;
+        Ok(ParserConfig {
+            preview_len: options.preview_len,
+            truncation: Truncation::parse(&options.truncation)?,
+            include_raw: match options.include_raw {
+                RawOption::Flag(true) => RawMode::Full,
+                RawOption::Flag(false) => RawMode::Off,
+                RawOption::Fields(fields) => RawMode::Fields(fields),
+            },
+            message_types: options.message_types,
+            privacy: options.privacy.map(PrivacyFilter::from_options).transpose()?,
+            time_format: TimeFormat::parse(&options.time_format, timezone)?,
+            timezone,
+            content_hash: options.content_hash,
+        })
+    }
+
+    /// The config as a JSON object of constructor keywords, privacy included.
+    fn to_json(&self) -> String {
+        let options = ConfigOptions {
+            preview_len: self.preview_len,
+            truncation: self.truncation.name().to_string(),
+            include_raw: match &self.include_raw {
+                RawMode::Off => RawOption::Flag(false),
+                RawMode::Full => RawOption::Flag(true),
+                RawMode::Fields(fields) => RawOption::Fields(fields.clone()),
+            },
+            message_types: self.message_types.clone(),
+            privacy: self.privacy.as_ref().map(PrivacyFilter::options),
+            time_format: self.time_format.name().to_string(),
+            timezone: self.timezone.name().to_string(),
+            content_hash: self.content_hash,
+        };
+        serde_json::to_string(&options).unwrap_or_default()
+    }
+
+    fn __getnewargs_ex__<'py>(
+        &self,
+        py: Python<'py>,
+    ) -> PyResult<(Bound<'py, PyTuple>, Bound<'py, PyDict>)> {
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("preview_len", self.preview_len)?;
+        kwargs.set_item("truncation", self.truncation.name())?;
+        kwargs.set_item("include_raw", self.include_raw(py)?)?;
+        kwargs.set_item("message_types", self.message_types.clone())?;
+        kwargs.set_item("privacy", self.privacy.clone())?;
+        kwargs.set_item("time_format", self.time_format.name())?;
+        kwargs.set_item("timezone", self.timezone.name())?;
+        kwargs.set_item("content_hash", self.content_hash)?;
+        Ok((PyTuple::empty(py), kwargs))
+    }
+
+    #[getter]
+    fn preview_len(&self) -> usize {
+        self.preview_len
+    }
+
+    #[getter]
+    fn truncation(&self) -> &'static str {
+        self.truncation.name()
+    }
+
+    /// False, True or the list of kept fields.
+    #[getter]
+    fn include_raw<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        match &self.include_raw {
+            RawMode::Off => Ok(PyBool::new(py, false).to_owned().into_any()),
+            RawMode::Full => Ok(PyBool::new(py, true).to_owned().into_any()),
+            RawMode::Fields(fields) => PyList::new(py, fields).map(|l| l.into_any()),
+        }
+    }
+
+    #[getter]
+    fn message_types(&self) -> Option<Vec<String>> {
+        self.message_types.clone()
+    }
+
+    #[getter]
+    fn privacy(&self) -> Option<PrivacyFilter> {
+        self.privacy.clone()
+    }
+
+    #[getter]
+    fn time_format(&self) -> &'static str {
+        self.time_format.name()
+    }
+
+    #[getter]
+    fn timezone(&self) -> &'static str {
+        self.timezone.name()
+    }
+
+    #[getter]
+    fn content_hash(&self) -> bool {
+        self.content_hash
+    }
+}
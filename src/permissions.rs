@@ -0,0 +1,237 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use rayon::prelude::*;
+use serde_json::Value;
+
+use crate::privacy::{global_filter, PrivacyFilter};
+use crate::rollup::find_jsonl_files;
+use crate::timeutil::parse_iso_ts;
+use crate::{tool_input_preview, tool_result_text, DENIAL_MARKERS};
+
+/// Tools Claude Code asks before running unless a mode or allow rule approves them.
+const GATED_TOOLS: [&str; 7] =
+    ["Bash", "Edit", "MultiEdit", "Write", "NotebookEdit", "WebFetch", "WebSearch"];
+
+/// Tools acceptEdits mode approves without asking.
+const EDIT_TOOLS: [&str; 4] = ["Edit", "MultiEdit", "Write", "NotebookEdit"];
+
+/// What the user (or their settings) said to a tool call.
+#[derive(Clone, Copy, PartialEq)]
+enum Decision {
+    Allowed,
+    /// Rejected at the prompt.
+    Denied,
+    /// Refused by a deny rule or because no one could be asked.
+    Blocked,
+    /// No result yet.
+    Pending,
+}
+
+impl Decision {
+    fn name(self) -> &'static str {
+        match self {
+            Decision::Allowed => "allowed",
+            Decision::Denied => "denied",
+            Decision::Blocked => "blocked",
+            Decision::Pending => "pending",
+        }
+    }
+}
+
+struct Request {
+    timestamp: f64,
+    session_id: String,
+    project_path: String,
+    tool: String,
+    command: String,
+    tool_use_id: String,
+    /// The session's working directory, for the privacy filter.
+    cwd: String,
+    mode: String,
+    decision: Decision,
+    feedback: Option<String>,
+    response_seconds: Option<f64>,
+}
+
+impl Request {
+    /// Whether the session's permission mode approved this call without a prompt.
+    fn auto_approved(&self) -> bool {
+        self.decision == Decision::Allowed
+            && (self.mode == "bypassPermissions"
+                || self.mode == "acceptEdits" && EDIT_TOOLS.contains(&self.tool.as_str()))
+    }
+
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("timestamp", self.timestamp)?;
+        dict.set_item("session_id", &self.session_id)?;
+        dict.set_item("project_path", &self.project_path)?;
+        dict.set_item("tool", &self.tool)?;
+        dict.set_item("command", &self.command)?;
+        dict.set_item("tool_use_id", &self.tool_use_id)?;
+        dict.set_item("decision", self.decision.name())?;
+        dict.set_item("permission_mode", &self.mode)?;
+        dict.set_item("auto_approved", self.auto_approved())?;
+        dict.set_item("feedback", &self.feedback)?;
+        dict.set_item("response_seconds", self.response_seconds)?;
+        Ok(dict)
+    }
+}
+
+/// The decision a tool result records, and what the user said when rejecting it.
+fn decision_of(block: &Value) -> (Decision, Option<String>) {
+    let text = tool_result_text(block);
+    let text = text.trim_start();
+    if text.starts_with(DENIAL_MARKERS[0]) {
+        let feedback = text
+            .split_once("the user said:")
+            .map(|(_, said)| said.trim().to_string())
+            .filter(|s| !s.is_empty());
+        (Decision::Denied, feedback)
+    } else if DENIAL_MARKERS[1..].iter().any(|m| text.starts_with(m)) {
+        (Decision::Blocked, None)
+    } else {
+        (Decision::Allowed, None)
+    }
+}
+
+fn scan_file(path: &Path, gated: &[String], filter: Option<&PrivacyFilter>) -> Vec<Request> {
+    let session_id = path.file_stem().and_then(|s| s.to_str()).unwrap_or("").to_string();
+    let project_path = path.parent().and_then(|p| p.to_str()).unwrap_or("").to_string();
+    let file = match File::open(path) {
+        Ok(f) => f,
+        Err(_) => return Vec::new(),
+    };
+    let mut requests: Vec<Request> = Vec::new();
+    let mut by_id: HashMap<String, usize> = HashMap::new();
+    let mut mode = String::from("default");
+    let mut cwd = String::new();
+    for line in BufReader::new(file).lines() {
+        let line = match line {
+            Ok(l) => l,
+            Err(_) => break,
+        };
+        let entry: Value = match serde_json::from_str(line.trim()) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        if let Some(m) = entry.get("permissionMode").and_then(Value::as_str) {
+            mode = m.to_string();
+        }
+        if let Some(dir) = entry.get("cwd").and_then(Value::as_str) {
+            cwd = dir.to_string();
+        }
+        let blocks = match entry["message"].get("content").and_then(Value::as_array) {
+            Some(arr) => arr,
+            None => continue,
+        };
+        let ts = entry.get("timestamp").and_then(Value::as_str).and_then(parse_iso_ts);
+        for block in blocks {
+            match block.get("type").and_then(Value::as_str) {
+                Some("tool_use") => {
+                    let tool = block.get("name").and_then(Value::as_str).unwrap_or("");
+                    let id = block.get("id").and_then(Value::as_str).unwrap_or("");
+                    if id.is_empty() {
+                        continue;
+                    }
+                    by_id.insert(id.to_string(), requests.len());
+                    requests.push(Request {
+                        timestamp: ts.unwrap_or(0.0),
+                        session_id: session_id.clone(),
+                        project_path: project_path.clone(),
+                        tool: tool.to_string(),
+                        command: tool_input_preview(tool, &block["input"]),
+                        tool_use_id: id.to_string(),
+                        cwd: cwd.clone(),
+                        mode: mode.clone(),
+                        decision: Decision::Pending,
+                        feedback: None,
+                        response_seconds: None,
+                    });
+                }
+                Some("tool_result") => {
+                    let id = block.get("tool_use_id").and_then(Value::as_str).unwrap_or("");
+                    let Some(req) = by_id.get(id).map(|&i| &mut requests[i]) else {
+                        continue;
+                    };
+                    (req.decision, req.feedback) = decision_of(block);
+                    req.response_seconds =
+                        ts.filter(|_| req.timestamp > 0.0).map(|t| (t - req.timestamp).max(0.0));
+                }
+                _ => {}
+            }
+        }
+    }
+    // A refusal shows the tool was gated even if it isn't usually.
+    requests.retain(|r| {
+        matches!(r.decision, Decision::Denied | Decision::Blocked)
+            || gated.iter().any(|g| g == &r.tool)
+            || r.tool.starts_with("mcp__") && gated.iter().any(|g| g == "mcp__*")
+    });
+    if let Some(f) = filter {
+        requests.retain_mut(|r| {
+            let allowed = f.allows(|field| match field {
+                "cwd" => Some(r.cwd.as_str()),
+                "command" => Some(r.command.as_str()),
+                "message" => r.feedback.as_deref(),
+                _ => None,
+            });
+            if let Some(command) = f.redact(&r.command) {
+                r.command = command;
+            }
+            if let Some(feedback) = r.feedback.as_deref().and_then(|t| f.redact(t)) {
+                r.feedback = Some(feedback);
+            }
+            allowed
+        });
+    }
+    requests
+}
+
+/// Tool calls that needed approval, and what became of each, from Claude Code
+/// transcripts.
+///
+/// path is a transcript file or a directory searched recursively. tools names the
+/// tools that ask before running (by default Bash, the file-editing tools, WebFetch,
+/// WebSearch and, as "mcp__*", every MCP tool); calls to other tools appear only if
+/// they were refused. Transcripts record refusals but not approvals, so a call whose
+/// tool ran is "allowed" whether the user clicked yes or an allow rule let it
+/// through; auto_approved is True when the session's permission mode
+/// (bypassPermissions, or acceptEdits for file edits) approved it without asking.
+/// decision is "allowed", "denied" (rejected at the prompt, with the user's feedback
+/// when they gave some), "blocked" (refused by a deny rule or with no one to ask) or
+/// "pending" (no result yet). Returns a list of dicts of timestamp, session_id,
+/// project_path, tool, command, tool_use_id, decision, permission_mode,
+/// auto_approved, feedback and response_seconds (from request to result, so it
+/// includes the time the tool ran), ordered by timestamp. The installed privacy
+/// filter drops and redacts requests by working directory, command and feedback.
+#[pyfunction]
+#[pyo3(signature = (path, tools=None))]
+pub fn permission_requests<'py>(
+    py: Python<'py>,
+    path: &str,
+    tools: Option<Vec<String>>,
+) -> PyResult<Bound<'py, PyList>> {
+    let gated = tools.unwrap_or_else(|| {
+        GATED_TOOLS.iter().map(|t| t.to_string()).chain(["mcp__*".to_string()]).collect()
+    });
+    let root = PathBuf::from(path);
+    let filter = global_filter();
+    let requests = py.detach(|| {
+        let files = if root.is_dir() { find_jsonl_files(&root) } else { vec![root.clone()] };
+        let mut requests: Vec<Request> =
+            files.par_iter().flat_map_iter(|f| scan_file(f, &gated, filter.as_deref())).collect();
+        requests.sort_by(|a, b| a.timestamp.total_cmp(&b.timestamp));
+        requests
+    });
+    let out = PyList::empty(py);
+    for req in &requests {
+        out.append(req.to_dict(py)?)?;
+    }
+    Ok(out)
+}
@@ -0,0 +1,276 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+use crate::collector::{home_path, now, record, records_to_py, Record, Sampler};
+
+/// Where things that start by themselves are registered: (directory, type, scope).
+/// A leading "~/" is relative to the home directory.
+#[cfg(target_os = "macos")]
+const LOCATIONS: [(&str, &str, &str); 3] = [
+    ("~/Library/LaunchAgents", "launch_agent", "user"),
+    ("/Library/LaunchAgents", "launch_agent", "system"),
+    ("/Library/LaunchDaemons", "launch_daemon", "system"),
+];
+
+#[cfg(not(target_os = "macos"))]
+const LOCATIONS: [(&str, &str, &str); 5] = [
+    ("~/.config/autostart", "autostart", "user"),
+    ("/etc/xdg/autostart", "autostart", "system"),
+    ("~/.config/systemd/user", "systemd_unit", "user"),
+    ("/etc/systemd/user", "systemd_unit", "system"),
+    ("/etc/systemd/system", "systemd_unit", "system"),
+];
+
+/// What a registration runs, whatever file format it came from.
+#[derive(Default)]
+struct Launch {
+    label: Option<String>,
+    program: Option<String>,
+    arguments: Vec<String>,
+    run_at_load: bool,
+    keep_alive: bool,
+    disabled: bool,
+}
+
+/// A launchd job: Program, or the first of ProgramArguments, is what runs.
+fn parse_plist(path: &Path) -> Option<Launch> {
+    let value = plist::Value::from_file(path).ok()?;
+    let dict = value.as_dictionary()?;
+    let text = |key: &str| dict.get(key).and_then(plist::Value::as_string).map(str::to_string);
+    let flag = |key: &str| dict.get(key).and_then(plist::Value::as_boolean).unwrap_or(false);
+    let arguments: Vec<String> = dict
+        .get("ProgramArguments")
+        .and_then(plist::Value::as_array)
+        .map(|args| args.iter().filter_map(|a| a.as_string().map(str::to_string)).collect())
+        .unwrap_or_default();
+    Some(Launch {
+        label: text("Label"),
+        program: text("Program").or_else(|| arguments.first().cloned()),
+        arguments,
+        run_at_load: flag("RunAtLoad"),
+        // KeepAlive is either a bool or a dictionary of conditions.
+        keep_alive: dict
+            .get("KeepAlive")
+            .is_some_and(|k| k.as_boolean().unwrap_or(k.as_dictionary().is_some())),
+        disabled: flag("Disabled"),
+    })
+}
+
+/// The first key=value in an INI-style file (.desktop entry or systemd unit).
+fn ini_value<'a>(text: &'a str, key: &str) -> Option<&'a str> {
+    text.lines()
+        .filter_map(|l| l.split_once('='))
+        .find(|(k, _)| k.trim() == key)
+        .map(|(_, v)| v.trim())
+}
+
+/// An XDG autostart entry or a systemd unit.
+fn parse_ini(path: &Path) -> Option<Launch> {
+    let text = std::fs::read_to_string(path).ok()?;
+    let unit = path.extension().is_some_and(|e| e != "desktop");
+    let command = ini_value(&text, if unit { "ExecStart" } else { "Exec" }).unwrap_or_default();
+    // systemd prefixes like "-" or "@" change how the command runs, not what runs.
+    let command = command.trim_start_matches(['-', '@', '+', '!']);
+    let arguments: Vec<String> = command.split_whitespace().map(String::from).collect();
+    let yes = |key: &str| ini_value(&text, key).is_some_and(|v| v.eq_ignore_ascii_case("true"));
+    Some(Launch {
+        label: ini_value(&text, if unit { "Description" } else { "Name" }).map(String::from),
+        program: arguments.first().cloned(),
+        arguments,
+        run_at_load: !unit || ini_value(&text, "WantedBy").is_some(),
+        keep_alive: unit && ini_value(&text, "Restart").is_some_and(|v| v != "no"),
+        disabled: yes("Hidden") || ini_value(&text, "X-GNOME-Autostart-enabled") == Some("false"),
+    })
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data).iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn scan_location(dir: &Path, kind: &str, scope: &str, items: &mut Vec<Record>) {
+    let Ok(entries) = std::fs::read_dir(dir) else { return };
+    let mut paths: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+    paths.sort();
+    for path in paths {
+        let parsed = match path.extension().and_then(|e| e.to_str()) {
+            Some("plist") => parse_plist(&path),
+            Some("desktop" | "service" | "timer") => parse_ini(&path),
+            _ => continue,
+        };
+        let Ok(data) = std::fs::read(&path) else { continue };
+        let launch = parsed.unwrap_or_default();
+        let modified = std::fs::metadata(&path)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs_f64());
+        let stem = path.file_stem().map(|s| s.to_string_lossy().into_owned());
+        items.push(record(
+            "persistence",
+            json!({
+                "type": kind,
+                "scope": scope,
+                "path": path.to_string_lossy(),
+                "label": launch.label.or(stem),
+                "program": launch.program,
+                "arguments": launch.arguments,
+                "run_at_load": launch.run_at_load,
+                "keep_alive": launch.keep_alive,
+                "disabled": launch.disabled,
+                "sha256": sha256_hex(&data),
+                "modified": modified,
+            }),
+        ));
+    }
+}
+
+/// Login items from System Events; needs Automation permission for it.
+#[cfg(target_os = "macos")]
+fn login_items(items: &mut Vec<Record>) {
+    let script = "tell application \"System Events\" to get the path of every login item";
+    let Ok(output) = std::process::Command::new("osascript").args(["-e", script]).output() else {
+        return;
+    };
+    if !output.status.success() {
+        return;
+    }
+    for app in String::from_utf8_lossy(&output.stdout).trim().split(", ") {
+        if app.is_empty() {
+            continue;
+        }
+        let label = Path::new(app).file_stem().map(|s| s.to_string_lossy().into_owned());
+        items.push(record(
+            "persistence",
+            json!({
+                "type": "login_item",
+                "scope": "user",
+                "path": app,
+                "label": label,
+                "program": app,
+                "arguments": [],
+                "run_at_load": true,
+                "keep_alive": false,
+                "disabled": false,
+                "sha256": sha256_hex(app.as_bytes()),
+                "modified": null,
+            }),
+        ));
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn login_items(_items: &mut Vec<Record>) {}
+
+fn scan() -> Vec<Record> {
+    let mut items = Vec::new();
+    for (dir, kind, scope) in LOCATIONS {
+        let dir = match dir.strip_prefix("~/") {
+            Some(rel) => home_path(rel),
+            None => PathBuf::from(dir),
+        };
+        scan_location(&dir, kind, scope, &mut items);
+    }
+    login_items(&mut items);
+    items
+}
+
+/// One hash over every item's path and content hash, so two scans can be compared
+/// without diffing them.
+fn digest(items: &[Record]) -> String {
+    let mut keys: Vec<String> = items
+        .iter()
+        .map(|i| format!("{}\0{}", i["path"].as_str().unwrap_or_default(), i["sha256"]))
+        .collect();
+    keys.sort();
+    sha256_hex(keys.join("\n").as_bytes())
+}
+
+/// Emits "added", "removed" and "changed" events as persistence items come and go.
+/// Events are the item plus kind, timestamp and summary; "changed" also carries
+/// previous_sha256.
+#[derive(Default)]
+pub(crate) struct PersistenceSampler {
+    digest: Option<String>,
+    known: HashMap<String, Record>,
+}
+
+impl Sampler for PersistenceSampler {
+    fn name(&self) -> &'static str {
+        "persistence"
+    }
+
+    fn sample(&mut self) -> Result<Vec<Record>, String> {
+        let items = scan();
+        let digest = digest(&items);
+        let first = self.digest.is_none();
+        if self.digest.as_ref() == Some(&digest) {
+            return Ok(Vec::new());
+        }
+        self.digest = Some(digest);
+        let current: HashMap<String, Record> = items
+            .into_iter()
+            .map(|i| (i["path"].as_str().unwrap_or_default().to_string(), i))
+            .collect();
+        let known = std::mem::replace(&mut self.known, current);
+        // The first poll only establishes what is already installed.
+        if first {
+            return Ok(Vec::new());
+        }
+        let ts = now();
+        let event = |kind: &str, item: &Record| {
+            let mut event = item.clone();
+            let summary = format!(
+                "{kind} {} {}",
+                item["type"].as_str().unwrap_or_default().replace('_', " "),
+                item["label"].as_str().unwrap_or_default()
+            );
+            event.insert("kind".to_string(), json!(kind));
+            event.insert("timestamp".to_string(), json!(ts));
+            event.insert("summary".to_string(), json!(summary));
+            event
+        };
+        let mut events: Vec<Record> = known
+            .iter()
+            .filter(|(path, _)| !self.known.contains_key(*path))
+            .map(|(_, item)| event("removed", item))
+            .collect();
+        for (path, item) in &self.known {
+            match known.get(path) {
+                None => events.push(event("added", item)),
+                Some(old) if old["sha256"] != item["sha256"] => {
+                    let mut changed = event("changed", item);
+                    changed.insert("previous_sha256".to_string(), old["sha256"].clone());
+                    events.push(changed);
+                }
+                Some(_) => {}
+            }
+        }
+        events.sort_by(|a, b| a["path"].as_str().cmp(&b["path"].as_str()));
+        Ok(events)
+    }
+}
+
+/// Everything registered to start on its own: launchd agents and daemons and login
+/// items on macOS, XDG autostart entries and systemd units on Linux.
+///
+/// Returns {digest, items}. Each item is {source: "persistence", type
+/// ("launch_agent", "launch_daemon", "login_item", "autostart" or "systemd_unit"),
+/// scope ("user" or "system"), path, label, program, arguments, run_at_load,
+/// keep_alive, disabled, sha256, modified}, where sha256 hashes the file's contents
+/// (a login item's path, for login items). digest is one SHA-256 over the whole
+/// set, so an unchanged digest means nothing was added, removed or edited.
+/// Collector(sources=["persistence"]) reports additions, removals and changes as
+/// they happen.
+#[pyfunction]
+pub fn persistence_snapshot(py: Python<'_>) -> PyResult<Bound<'_, PyDict>> {
+    let items = py.detach(scan);
+    let dict = PyDict::new(py);
+    dict.set_item("digest", digest(&items))?;
+    dict.set_item("items", records_to_py(py, items)?)?;
+    Ok(dict)
+}
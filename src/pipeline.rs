@@ -0,0 +1,233 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList, PyTuple};
+
+use crate::collector::home_path;
+use crate::errors::ReadError;
+use crate::parserconfig::ParserConfig;
+use crate::rollup::find_jsonl_files;
+use crate::shutdown::{deadline, CLOSE_TIMEOUT};
+use crate::watcher::Watcher;
+use crate::{events_to_py, TranscriptEvent, TranscriptTail};
+
+#[derive(Default)]
+struct Files {
+    /// Where parsing stopped in each transcript seen so far.
+    offsets: HashMap<PathBuf, u64>,
+    /// Parser state for each transcript read so far, so a tool use and its result or a
+    /// compaction and its summary are matched across polls. Taken out while the file is
+    /// being read.
+    tails: HashMap<PathBuf, TranscriptTail>,
+    /// Transcripts to read from their offsets on the next poll.
+    pending: BTreeSet<PathBuf>,
+}
+
+/// Parsed events from every transcript under a directory tree, as they are written.
+///
+/// Discovery, file watching and incremental parsing in one: on start() the tree is
+/// searched for *.jsonl files and then watched, recursively, for new and growing
+/// ones, and each poll() parses whatever was appended since the last, tracking every
+/// file's offset itself. path defaults to ~/.claude/projects; config is a
+/// ParserConfig for the events. include_existing=False skips what the transcripts
+/// already hold at start() and reports only what comes after. offsets, a dict of
+/// path to byte offset as the offsets property returns, resumes files from where an
+/// earlier pipeline left off, whatever include_existing says.
+#[pyclass(frozen)]
+pub struct TranscriptPipeline {
+    watcher: Watcher,
+    root: PathBuf,
+    config: ParserConfig,
+    include_existing: bool,
+    files: Mutex<Files>,
+}
+
+impl TranscriptPipeline {
+    /// Queue the transcripts already under root, or note where they end.
+    fn scan(&self) {
+        let found = find_jsonl_files(&self.root);
+        let mut files = self.files.lock().unwrap_or_else(|e| e.into_inner());
+        for path in found {
+            if self.include_existing || files.offsets.contains_key(&path) {
+                files.pending.insert(path);
+            } else if let Ok(meta) = std::fs::metadata(&path) {
+                files.offsets.insert(path, meta.len());
+            }
+        }
+    }
+
+    /// Parse the pending transcripts plus those in changes, returning their new events
+    /// and the files or lines that couldn't be read. The files lock is only held to
+    /// pick up and put back each transcript's state, never while parsing.
+    fn read(&self, changes: Vec<crate::watcher::Change>) -> (Vec<TranscriptEvent>, Vec<ReadError>) {
+        let mut taken = Vec::new();
+        {
+            let mut files = self.files.lock().unwrap_or_else(|e| e.into_inner());
+            for change in changes {
+                if change.kind == "removed" {
+                    files.offsets.remove(&change.path);
+                    files.tails.remove(&change.path);
+                    files.pending.remove(&change.path);
+                } else {
+                    files.pending.insert(change.path);
+                }
+            }
+            for path in std::mem::take(&mut files.pending) {
+                let offset = files.offsets.get(&path).copied().unwrap_or(0);
+                let tail = match files.tails.remove(&path) {
+                    Some(tail) if tail.offset == offset => tail,
+                    _ => TranscriptTail::new(offset),
+                };
+                taken.push((path, tail));
+            }
+        }
+        let (mut events, mut errors) = (Vec::new(), Vec::new());
+        let mut done = Vec::new();
+        for (path, mut tail) in taken {
+            let size = match std::fs::metadata(&path) {
+                Ok(m) if m.is_file() => m.len(),
+                _ => {
+                    done.push((path, None));
+                    continue;
+                }
+            };
+            // Truncated or replaced: start over.
+            if size < tail.offset {
+                tail = TranscriptTail::new(0);
+            }
+            if size > tail.offset {
+                let (parsed, read_errors) = tail.read(&path, &self.config);
+                events.extend(parsed);
+                errors.extend(read_errors);
+            }
+            done.push((path, Some(tail)));
+        }
+        let mut files = self.files.lock().unwrap_or_else(|e| e.into_inner());
+        for (path, tail) in done {
+            match tail {
+                Some(tail) => {
+                    files.offsets.insert(path.clone(), tail.offset);
+                    files.tails.insert(path, tail);
+                }
+                None => {
+                    files.offsets.remove(&path);
+                }
+            }
+        }
+        drop(files);
+        self.config.filter_events(&mut events);
+        (events, errors)
+    }
+}
+
+#[pymethods]
+impl TranscriptPipeline {
+    #[new]
+    #[pyo3(signature = (
+        path=None, config=None, *, include_existing=true, offsets=None, debounce=0.2
+    ))]
+    fn new(
+        path: Option<PathBuf>,
+        config: Option<ParserConfig>,
+        include_existing: bool,
+        offsets: Option<HashMap<PathBuf, u64>>,
+        debounce: f64,
+    ) -> PyResult<Self> {
+        let root = path.unwrap_or_else(|| home_path(".claude/projects"));
+        let patterns = Some(vec!["*.jsonl".to_string()]);
+        Ok(TranscriptPipeline {
+            watcher: Watcher::new(vec![root.clone()], patterns, None, debounce, true, None)?,
+            root,
+            config: config.unwrap_or_default(),
+            include_existing,
+            files: Mutex::new(Files { offsets: offsets.unwrap_or_default(), ..Files::default() }),
+        })
+    }
+
+    /// Begin watching, then queue the transcripts already there for the next poll.
+    /// Starting again after stop() catches up on what was written in between. Raises
+    /// IOError if the directory can't be watched.
+    fn start(&self, py: Python<'_>) -> PyResult<()> {
+        self.watcher.start(py)?;
+        py.detach(|| self.scan());
+        Ok(())
+    }
+
+    /// Stop watching. Offsets are kept.
+    fn stop(&self, py: Python<'_>) {
+        self.watcher.stop(py)
+    }
+
+    /// Stop watching and wait up to timeout seconds for the watcher thread; see
+    /// Watcher.close().
+    #[pyo3(signature = (timeout=CLOSE_TIMEOUT))]
+    fn close(&self, py: Python<'_>, timeout: Option<f64>) -> PyResult<bool> {
+        self.watcher.close(py, timeout)
+    }
+
+    /// Start watching unless already running.
+    fn __enter__(slf: PyRef<'_, Self>) -> PyResult<PyRef<'_, Self>> {
+        if !slf.watcher.running() {
+            slf.start(slf.py())?;
+        }
+        Ok(slf)
+    }
+
+    /// close() with the default timeout. Exceptions propagate.
+    #[pyo3(signature = (*_args))]
+    fn __exit__(&self, py: Python<'_>, _args: &Bound<'_, PyTuple>) -> PyResult<bool> {
+        self.close(py, Some(CLOSE_TIMEOUT))?;
+        Ok(false)
+    }
+
+    /// Return the events written since the last poll (on the first, everything the
+    /// transcripts already held), waiting up to timeout seconds for at least one.
+    ///
+    /// timeout=None waits indefinitely (Ctrl-C still interrupts). A transcript that
+    /// can't be read is logged and skipped until it changes again, and a line that
+    /// isn't UTF-8 is logged and passed over. Raises IOError if the underlying watcher
+    /// reported an error.
+    #[pyo3(signature = (timeout=0.0))]
+    fn poll<'py>(&self, py: Python<'py>, timeout: Option<f64>) -> PyResult<Bound<'py, PyList>> {
+        let deadline = deadline(timeout)?;
+        loop {
+            let pending = !self.files.lock().unwrap_or_else(|e| e.into_inner()).pending.is_empty();
+            let remaining = match deadline {
+                _ if pending => Some(0.0),
+                Some(d) => Some(d.saturating_duration_since(Instant::now()).as_secs_f64()),
+                None => None,
+            };
+            let changes = self.watcher.wait(py, remaining)?;
+            let (events, errors) = py.detach(|| self.read(changes));
+            for e in errors {
+                match e {
+                    ReadError::Parse(..) => tracing::warn!("skipping line: {e}"),
+                    ReadError::Io(..) => tracing::warn!("skipping transcript: {e}"),
+                }
+            }
+            if !events.is_empty() || deadline.is_some_and(|d| Instant::now() >= d) {
+                return events_to_py(py, &events, self.config.time_format);
+            }
+        }
+    }
+
+    #[getter]
+    fn running(&self) -> bool {
+        self.watcher.running()
+    }
+
+    /// Byte offset parsed up to in each transcript, by path; pass it back as offsets
+    /// to resume.
+    #[getter]
+    fn offsets<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let files = self.files.lock().unwrap_or_else(|e| e.into_inner());
+        let dict = PyDict::new(py);
+        for (path, offset) in &files.offsets {
+            dict.set_item(path.to_string_lossy(), offset)?;
+        }
+        Ok(dict)
+    }
+}
@@ -0,0 +1,152 @@
+use std::process::Command;
+use std::sync::OnceLock;
+
+use chrono::DateTime;
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+use regex::Regex;
+use serde_json::json;
+
+use crate::collector::{record, records_to_py, Record};
+
+/// "Using AC (Charge:80%)" / "Using Batt(Charge: 78%)" inside a pmset message.
+fn charge_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"Using (AC|BATT|Batt)\s*\(Charge:\s*(\d+)%?\)").unwrap())
+}
+
+/// The reason pmset gives: "due to 'Clamshell Sleep'" or "due to EC.LidOpen/Lid Open".
+fn reason_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"due to '([^']*)'|due to ([^:]+?)(?: Using |\s*$|:)").unwrap())
+}
+
+/// One "YYYY-MM-DD HH:MM:SS ±zzzz Type<tab>Message<tab>N secs" line.
+struct LogLine<'a> {
+    timestamp: f64,
+    kind: &'a str,
+    message: &'a str,
+    duration: Option<f64>,
+}
+
+fn parse_line(line: &str) -> Option<LogLine<'_>> {
+    let mut columns = line.split('\t');
+    let head = columns.next()?;
+    let (stamp, kind) = (head.get(..25)?, head.get(25..)?.trim());
+    let timestamp = DateTime::parse_from_str(stamp, "%Y-%m-%d %H:%M:%S %z").ok()?;
+    let message = columns.next().unwrap_or_default().trim();
+    let duration = columns
+        .next()
+        .and_then(|d| d.trim().strip_suffix(" secs"))
+        .and_then(|d| d.trim().parse().ok());
+    Some(LogLine { timestamp: timestamp.timestamp() as f64, kind, message, duration })
+}
+
+fn reason(message: &str) -> Option<&str> {
+    let caps = reason_re().captures(message)?;
+    caps.get(1).or(caps.get(2)).map(|m| m.as_str().trim())
+}
+
+/// Turn pmset's log into sleep/wake/darkwake, lid and charge events.
+pub(crate) fn parse_log(text: &str, since: f64) -> Vec<Record> {
+    let mut events = Vec::new();
+    let mut last_power: Option<(String, i64)> = None;
+    for line in text.lines() {
+        let Some(entry) = parse_line(line) else { continue };
+        let ts = entry.timestamp;
+        let charge = charge_re().captures(entry.message).map(|c| {
+            let source = if &c[1] == "AC" { "AC" } else { "Battery" };
+            (source.to_string(), c[2].parse::<i64>().unwrap_or_default())
+        });
+        let why = reason(entry.message);
+        let mut push = |fields: serde_json::Value| {
+            if ts > since {
+                events.push(record("power", fields));
+            }
+        };
+
+        let kind = match entry.kind {
+            "Sleep" => Some("sleep"),
+            "Wake" => Some("wake"),
+            "DarkWake" => Some("darkwake"),
+            _ => None,
+        };
+        if let Some(kind) = kind {
+            push(json!({
+                "kind": kind,
+                "timestamp": ts,
+                "reason": why,
+                "power_source": charge.as_ref().map(|(s, _)| s),
+                "charge": charge.as_ref().map(|(_, c)| c),
+                "duration": entry.duration,
+            }));
+            let lid = match (kind, why) {
+                ("sleep", Some(r)) if r.contains("Clamshell") => Some("closed"),
+                ("wake" | "darkwake", Some(r)) if r.contains("Lid") => Some("open"),
+                _ => None,
+            };
+            if let Some(state) = lid {
+                push(json!({"kind": "lid", "timestamp": ts, "state": state, "reason": why}));
+            }
+        }
+        if let Some(current) = charge {
+            if last_power.as_ref() != Some(&current) {
+                push(json!({
+                    "kind": "charge",
+                    "timestamp": ts,
+                    "power_source": current.0,
+                    "charge": current.1,
+                    "previous_source": last_power.as_ref().map(|(s, _)| s),
+                    "previous_charge": last_power.as_ref().map(|(_, c)| c),
+                }));
+                last_power = Some(current);
+            }
+        }
+    }
+    events
+}
+
+/// Parse `pmset -g log` output into power events, oldest first.
+///
+/// Sleep, Wake and DarkWake entries become {source: "power", kind: "sleep" | "wake"
+/// | "darkwake", timestamp, reason, power_source, charge, duration}, where duration
+/// is the time pmset says was spent in that state. A clamshell sleep or a lid-open
+/// wake also yields {kind: "lid", state: "closed" | "open"}, and every change of
+/// power source or charge percentage yields {kind: "charge", power_source ("AC" or
+/// "Battery"), charge, previous_source, previous_charge}. Only events after since
+/// (epoch seconds) are returned; charge changes are still tracked before it.
+#[pyfunction]
+#[pyo3(signature = (text, since=None))]
+pub fn parse_pmset_log<'py>(
+    py: Python<'py>,
+    text: &str,
+    since: Option<f64>,
+) -> PyResult<Bound<'py, PyList>> {
+    let since = since.unwrap_or(f64::NEG_INFINITY);
+    let events = py.detach(|| parse_log(text, since));
+    records_to_py(py, events)
+}
+
+/// Run `pmset -g log` and parse it with parse_pmset_log.
+///
+/// Raises RuntimeError if pmset fails and IOError if it can't be run.
+#[pyfunction]
+#[pyo3(signature = (since=None))]
+pub fn power_events(py: Python<'_>, since: Option<f64>) -> PyResult<Bound<'_, PyList>> {
+    let since = since.unwrap_or(f64::NEG_INFINITY);
+    let events = py.detach(|| -> PyResult<Vec<Record>> {
+        let output = Command::new("pmset")
+            .args(["-g", "log"])
+            .output()
+            .map_err(|e| pyo3::exceptions::PyIOError::new_err(format!("pmset: {e}")))?;
+        if !output.status.success() {
+            return Err(pyo3::exceptions::PyRuntimeError::new_err(format!(
+                "pmset failed ({}): {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(parse_log(&String::from_utf8_lossy(&output.stdout), since))
+    })?;
+    records_to_py(py, events)
+}
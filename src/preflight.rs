@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+
+use crate::collector::home_path;
+use crate::errors::io_error;
+
+/// A protected file snoopy reads, and what reads it.
+struct Probe {
+    name: &'static str,
+    /// Absolute, or relative to the home directory.
+    path: &'static str,
+    needed_by: &'static [&'static str],
+    /// Why the file may not exist, and what creates it.
+    if_missing: &'static str,
+}
+
+const PROBES: [Probe; 4] = [
+    Probe {
+        name: "messages",
+        path: "Library/Messages/chat.db",
+        needed_by: &["Collector (messages)", "snoopy.collectors.messages"],
+        if_missing: "Messages hasn't been set up on this Mac; signing in to iMessage creates it.",
+    },
+    Probe {
+        name: "safari",
+        path: "Library/Safari/History.db",
+        needed_by: &["safari_history", "snoopy.collectors.browser"],
+        if_missing: "Safari hasn't been opened yet.",
+    },
+    Probe {
+        name: "knowledge",
+        path: crate::knowledge::SYSTEM_DB,
+        needed_by: &["screen_time_usage"],
+        if_missing: "Screen Time has no data yet; turn it on in System Settings > Screen Time.",
+    },
+    Probe {
+        name: "tcc",
+        path: crate::tcc::SYSTEM_DB,
+        needed_by: &["tcc_permissions", "Collector (tcc)"],
+        if_missing: "Not found; this Mac keeps its privacy database somewhere else.",
+    },
+];
+
+/// What opening a probe's file found.
+enum Status {
+    Granted,
+    Denied(io::Error),
+    Missing,
+    Error(io::Error),
+}
+
+impl Status {
+    fn name(&self) -> &'static str {
+        match self {
+            Status::Granted => "granted",
+            Status::Denied(_) => "denied",
+            Status::Missing => "missing",
+            Status::Error(_) => "error",
+        }
+    }
+}
+
+/// Open path and read a byte, which is where TCC refuses a process without Full
+/// Disk Access (with EPERM, whatever the file's mode says).
+fn probe(path: &Path) -> Status {
+    let read = File::open(path).and_then(|mut f| f.read(&mut [0u8; 1]));
+    match read {
+        Ok(_) => Status::Granted,
+        Err(e) if e.kind() == io::ErrorKind::PermissionDenied => Status::Denied(e),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Status::Missing,
+        Err(e) => Status::Error(e),
+    }
+}
+
+/// The app TCC holds responsible is the one that started snoopy, so name both it
+/// and the executable actually running.
+fn grant_hint(executable: &str) -> String {
+    format!(
+        "Grant Full Disk Access to the app that runs snoopy (your terminal, or {executable} \
+         for the daemon) in System Settings > Privacy & Security > Full Disk Access, then \
+         restart it."
+    )
+}
+
+/// Check up front that snoopy can read the protected files its parsers need.
+///
+/// Probes Messages' chat.db, Safari's History.db, the system knowledgeC.db (Screen
+/// Time) and the system TCC.db by opening each and reading a byte. Returns {ok,
+/// full_disk_access, executable, checks}: checks is a list of {name, path, status,
+/// needed_by, error, hint} with name "messages", "safari", "knowledge" or "tcc" and
+/// status "granted", "denied" (refused, which on macOS means the app running
+/// snoopy lacks Full Disk Access), "missing" or "error"; hint says what to do
+/// about anything but "granted". ok is False if any check was denied.
+/// full_disk_access is False if any check was denied, True if none were and a file
+/// was readable, and None if there was nothing to tell by. paths overrides where a check
+/// looks ({name: path}). Each check named in require must not be denied, or
+/// PermissionDenied is raised with the hint in its message, so a caller can fail
+/// early rather than have a parser fail later with a bare EPERM. Raises ValueError
+/// for an unknown check name.
+#[pyfunction]
+#[pyo3(signature = (require=None, paths=None))]
+pub fn check_permissions<'py>(
+    py: Python<'py>,
+    require: Option<Vec<String>>,
+    paths: Option<HashMap<String, PathBuf>>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let require = require.unwrap_or_default();
+    let mut paths = paths.unwrap_or_default();
+    for name in require.iter().chain(paths.keys()) {
+        if !PROBES.iter().any(|p| p.name == name) {
+            let names: Vec<&str> = PROBES.iter().map(|p| p.name).collect();
+            return Err(PyValueError::new_err(format!(
+                "unknown check {name:?}; expected one of {}",
+                names.join(", ")
+            )));
+        }
+    }
+    let executable = std::env::current_exe()
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|_| "python".to_string());
+    let checks: Vec<(&Probe, PathBuf, Status)> = py.detach(|| {
+        PROBES
+            .iter()
+            .map(|p| {
+                let path = paths.remove(p.name).unwrap_or_else(|| home_path(p.path));
+                let status = probe(&path);
+                (p, path, status)
+            })
+            .collect()
+    });
+
+    if let Some((_, path, Status::Denied(e))) = checks
+        .iter()
+        .find(|(p, _, s)| matches!(s, Status::Denied(_)) && require.iter().any(|r| r == p.name))
+    {
+        let cause = format!("{e}. {}", grant_hint(&executable));
+        return Err(io_error(path, io::Error::new(io::ErrorKind::PermissionDenied, cause)));
+    }
+
+    let granted = checks.iter().any(|(_, _, s)| matches!(s, Status::Granted));
+    let denied = checks.iter().any(|(_, _, s)| matches!(s, Status::Denied(_)));
+    let list = PyList::empty(py);
+    for (p, path, status) in &checks {
+        let check = PyDict::new(py);
+        check.set_item("name", p.name)?;
+        check.set_item("path", path.display().to_string())?;
+        check.set_item("status", status.name())?;
+        check.set_item("needed_by", p.needed_by.to_vec())?;
+        let (error, hint) = match status {
+            Status::Granted => (None, None),
+            Status::Denied(e) => (Some(e.to_string()), Some(grant_hint(&executable))),
+            Status::Missing => (None, Some(p.if_missing.to_string())),
+            Status::Error(e) => {
+                (Some(e.to_string()), Some(format!("{} couldn't be read.", path.display())))
+            }
+        };
+        check.set_item("error", error)?;
+        check.set_item("hint", hint)?;
+        list.append(check)?;
+    }
+    let report = PyDict::new(py);
+    report.set_item("ok", !denied)?;
+    let full_disk_access = if denied { Some(false) } else { granted.then_some(true) };
+    report.set_item("full_disk_access", full_disk_access)?;
+    report.set_item("executable", executable)?;
+    report.set_item("checks", list)?;
+    Ok(report)
+}
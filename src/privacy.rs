@@ -0,0 +1,389 @@
+use std::borrow::Cow;
+use std::sync::{Arc, RwLock};
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList, PyTuple};
+use regex::{Regex, RegexSet};
+use serde::{Deserialize, Serialize};
+
+use crate::collector::{json_to_py, Record};
+use crate::TranscriptEvent;
+
+/// Event fields consulted for each filter dimension, across all sources.
+const APP_FIELDS: [&str; 4] = ["app", "app_name", "process_name", "bundle_id"];
+const CONTACT_FIELDS: [&str; 4] = ["contact", "chat_name", "sender", "recipient"];
+const PROJECT_FIELDS: [&str; 3] = ["project_path", "cwd", "repo"];
+const DOMAIN_FIELDS: [&str; 5] = ["domain", "url", "host", "hostname", "remote_address"];
+const CONTENT_FIELDS: [&str; 6] =
+    ["content_preview", "title", "text", "message", "command", "output"];
+
+static GLOBAL_FILTER: RwLock<Option<Arc<PrivacyFilter>>> = RwLock::new(None);
+
+/// The filter installed with set_privacy_filter(), if any.
+pub(crate) fn global_filter() -> Option<Arc<PrivacyFilter>> {
+    GLOBAL_FILTER.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Allow/deny lists for one dimension. An empty allow list allows everything.
+#[derive(Clone, Default)]
+struct Rule {
+    allow: Vec<String>,
+    deny: Vec<String>,
+}
+
+impl Rule {
+    fn new(
+        allow: Option<Vec<String>>,
+        deny: Option<Vec<String>>,
+        normalize: fn(&str) -> String,
+    ) -> Self {
+        let norm = |v: Option<Vec<String>>| {
+            let values = v.unwrap_or_default();
+            values.iter().map(|s| normalize(s)).filter(|s| !s.is_empty()).collect()
+        };
+        Rule {
+            allow: norm(allow),
+            deny: norm(deny),
+        }
+    }
+
+    fn permits(&self, value: &str, matches: fn(&str, &str) -> bool) -> bool {
+        if self.deny.iter().any(|d| matches(value, d)) {
+            return false;
+        }
+        self.allow.is_empty() || self.allow.iter().any(|a| matches(value, a))
+    }
+}
+
+fn lowercase(s: &str) -> String {
+    s.trim().to_lowercase()
+}
+
+fn trim_slash(s: &str) -> String {
+    s.trim().trim_end_matches('/').to_string()
+}
+
+fn same(value: &str, pattern: &str) -> bool {
+    value.trim().to_lowercase() == pattern
+}
+
+/// Path equality or containment on whole components: /a/b matches /a/b and /a/b/c.
+fn under_path(value: &str, root: &str) -> bool {
+    let value = value.trim_end_matches('/');
+    value == root || value.strip_prefix(root).is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// Domain equality or subdomain: example.com matches example.com and www.example.com.
+fn under_domain(value: &str, domain: &str) -> bool {
+    let host = host_of(value);
+    host == domain || host.strip_suffix(domain).is_some_and(|rest| rest.ends_with('.'))
+}
+
+/// The lowercase host of a URL, or the value itself if it isn't one.
+pub(crate) fn host_of(value: &str) -> String {
+    let rest = value.split_once("://").map(|(_, r)| r).unwrap_or(value);
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or("");
+    let host = authority.rsplit_once('@').map(|(_, h)| h).unwrap_or(authority);
+    let host = match host.strip_prefix('[') {
+        Some(v6) => v6.split(']').next().unwrap_or(""),
+        None => host.split(':').next().unwrap_or(""),
+    };
+    host.trim_end_matches('.').to_lowercase()
+}
+
+/// Central allow/deny configuration applied to events before they leave Rust.
+///
+/// apps, contacts, projects and domains each take allow and deny lists; deny wins,
+/// and a non-empty allow list drops anything not on it. Apps and contacts match
+/// case-insensitively, projects match the path or anything under it, and domains
+/// match the domain or any subdomain (URLs are reduced to their host). Events whose
+/// text fields match any of deny_content's regexes are dropped; redact_content's
+/// matches are replaced with "[REDACTED]". Fields an event doesn't have are not
+/// checked. Filters pickle, so they can be handed to worker processes, and
+/// round-trip through to_json()/from_json() for storing alongside other settings.
+#[pyclass(frozen, from_py_object, module = "snoopy_native")]
+#[derive(Clone)]
+pub struct PrivacyFilter {
+    apps: Rule,
+    contacts: Rule,
+    projects: Rule,
+    domains: Rule,
+    deny_content: Option<RegexSet>,
+    redact_content: Vec<Regex>,
+}
+
+/// PrivacyFilter's constructor keywords, as pickled and stored by to_json().
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct FilterOptions {
+    allow_apps: Vec<String>,
+    deny_apps: Vec<String>,
+    allow_contacts: Vec<String>,
+    deny_contacts: Vec<String>,
+    allow_projects: Vec<String>,
+    deny_projects: Vec<String>,
+    allow_domains: Vec<String>,
+    deny_domains: Vec<String>,
+    deny_content: Vec<String>,
+    redact_content: Vec<String>,
+}
+
+fn compile_set(patterns: &[String]) -> PyResult<Option<RegexSet>> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+    RegexSet::new(patterns)
+        .map(Some)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+impl PrivacyFilter {
+    pub(crate) fn from_options(options: FilterOptions) -> PyResult<Self> {
+        let redact_content = options
+            .redact_content
+            .iter()
+            .map(|p| Regex::new(p))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        let rule = |allow, deny, normalize| Rule::new(Some(allow), Some(deny), normalize);
+        Ok(PrivacyFilter {
+            apps: rule(options.allow_apps, options.deny_apps, lowercase),
+            contacts: rule(options.allow_contacts, options.deny_contacts, lowercase),
+            projects: rule(options.allow_projects, options.deny_projects, trim_slash),
+            domains: rule(options.allow_domains, options.deny_domains, lowercase),
+            deny_content: compile_set(&options.deny_content)?,
+            redact_content,
+        })
+    }
+
+    pub(crate) fn options(&self) -> FilterOptions {
+        let deny_content = self.deny_content.as_ref().map(|s| s.patterns().to_vec());
+        FilterOptions {
+            allow_apps: self.apps.allow.clone(),
+            deny_apps: self.apps.deny.clone(),
+            allow_contacts: self.contacts.allow.clone(),
+            deny_contacts: self.contacts.deny.clone(),
+            allow_projects: self.projects.allow.clone(),
+            deny_projects: self.projects.deny.clone(),
+            allow_domains: self.domains.allow.clone(),
+            deny_domains: self.domains.deny.clone(),
+            deny_content: deny_content.unwrap_or_default(),
+            redact_content: self.redact_content.iter().map(|r| r.as_str().to_string()).collect(),
+        }
+    }
+
+    /// Decide whether an event may be surfaced; get looks up a field by name.
+    pub(crate) fn allows<'a>(&self, get: impl Fn(&str) -> Option<&'a str>) -> bool {
+        let all = |fields: &[&str], pred: &dyn Fn(&str) -> bool| {
+            fields.iter().filter_map(|f| get(f)).filter(|v| !v.is_empty()).all(pred)
+        };
+        all(&APP_FIELDS, &|v| self.apps.permits(v, same))
+            && all(&CONTACT_FIELDS, &|v| self.contacts.permits(v, same))
+            && all(&PROJECT_FIELDS, &|v| self.projects.permits(v, under_path))
+            && all(&DOMAIN_FIELDS, &|v| self.domains.permits(v, under_domain))
+            && match &self.deny_content {
+                Some(set) => all(&CONTENT_FIELDS, &|v| !set.is_match(v)),
+                None => true,
+            }
+    }
+
+    /// Apply redact_content to one text value.
+    pub(crate) fn redact(&self, text: &str) -> Option<String> {
+        let mut out: Option<String> = None;
+        for re in &self.redact_content {
+            let current = out.as_deref().unwrap_or(text);
+            if re.is_match(current) {
+                out = Some(re.replace_all(current, "[REDACTED]").into_owned());
+            }
+        }
+        out
+    }
+
+    /// Filter and redact a collector record in place; false means drop it.
+    pub(crate) fn apply_record(&self, rec: &mut Record) -> bool {
+        if !self.allows(|f| rec.get(f).and_then(|v| v.as_str())) {
+            return false;
+        }
+        for field in CONTENT_FIELDS {
+            let redacted = rec.get(field).and_then(|v| v.as_str()).and_then(|t| self.redact(t));
+            if let Some(text) = redacted {
+                rec.insert(field.to_string(), text.into());
+            }
+        }
+        true
+    }
+
+    /// Drop and redact transcript events in place. The raw line (from include_raw)
+    /// holds the full text, so it is checked and redacted like the preview; the
+    /// preview and content_hash were already taken of redacted text, and events whose
+    /// full text was denied come marked withheld.
+    pub(crate) fn apply_transcript(&self, events: &mut Vec<TranscriptEvent>) {
+        events.retain_mut(|ev| {
+            if ev.withheld {
+                return false;
+            }
+            let (project, preview) = (ev.project_path.as_str(), ev.content_preview.as_str());
+            let allowed = self.allows(|field| match field {
+                "project_path" => Some(project),
+                "content_preview" => Some(preview),
+                _ => None,
+            }) && !ev.raw.as_deref().is_some_and(|raw| self.denies_content(raw));
+            if allowed {
+                if let Some(text) = self.redact(&ev.content_preview) {
+                    ev.content_preview = text;
+                }
+                if let Some(raw) = ev.raw.as_deref().and_then(|raw| self.redact(raw)) {
+                    ev.raw = Some(raw);
+                }
+            }
+            allowed
+        });
+    }
+
+    pub(crate) fn denies_content(&self, text: &str) -> bool {
+        self.deny_content.as_ref().is_some_and(|set| set.is_match(text))
+    }
+
+    /// Whether what happens in the named app may be surfaced.
+    pub(crate) fn allows_app(&self, app: &str) -> bool {
+        self.allows(|field| (field == "app").then_some(app))
+    }
+
+    /// Whether what comes from the project at path may be surfaced.
+    pub(crate) fn allows_project(&self, project: &str) -> bool {
+        self.allows(|field| (field == "project_path").then_some(project))
+    }
+
+    /// Text derived from an event, as it may be surfaced: None if deny_content
+    /// matches it, otherwise with redact_content applied.
+    pub(crate) fn content<'a>(&self, text: &'a str) -> Option<Cow<'a, str>> {
+        if self.denies_content(text) {
+            return None;
+        }
+        Some(self.redact(text).map_or(Cow::Borrowed(text), Cow::Owned))
+    }
+
+    pub(crate) fn apply_dict<'py>(
+        &self,
+        event: &Bound<'py, PyDict>,
+    ) -> PyResult<Option<Bound<'py, PyDict>>> {
+        let names = APP_FIELDS.iter().chain(&CONTACT_FIELDS).chain(&PROJECT_FIELDS);
+        let mut fields: Vec<(&str, String)> = Vec::new();
+        for name in names.chain(&DOMAIN_FIELDS).chain(&CONTENT_FIELDS) {
+            if let Some(v) = event.get_item(name)? {
+                if let Ok(s) = v.extract::<String>() {
+                    fields.push((name, s));
+                }
+            }
+        }
+        let lookup = |name: &str| fields.iter().find(|(n, _)| *n == name).map(|(_, v)| v.as_str());
+        if !self.allows(lookup) {
+            return Ok(None);
+        }
+        let out = event.copy()?;
+        for (name, value) in &fields {
+            if CONTENT_FIELDS.contains(name) {
+                if let Some(text) = self.redact(value) {
+                    out.set_item(name, text)?;
+                }
+            }
+        }
+        Ok(Some(out))
+    }
+}
+
+#[pymethods]
+impl PrivacyFilter {
+    #[new]
+    #[pyo3(signature = (
+        *, allow_apps=None, deny_apps=None, allow_contacts=None, deny_contacts=None,
+        allow_projects=None, deny_projects=None, allow_domains=None, deny_domains=None,
+        deny_content=None, redact_content=None
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        allow_apps: Option<Vec<String>>,
+        deny_apps: Option<Vec<String>>,
+        allow_contacts: Option<Vec<String>>,
+        deny_contacts: Option<Vec<String>>,
+        allow_projects: Option<Vec<String>>,
+        deny_projects: Option<Vec<String>>,
+        allow_domains: Option<Vec<String>>,
+        deny_domains: Option<Vec<String>>,
+        deny_content: Option<Vec<String>>,
+        redact_content: Option<Vec<String>>,
+    ) -> PyResult<Self> {
+        PrivacyFilter::from_options(FilterOptions {
+            allow_apps: allow_apps.unwrap_or_default(),
+            deny_apps: deny_apps.unwrap_or_default(),
+            allow_contacts: allow_contacts.unwrap_or_default(),
+            deny_contacts: deny_contacts.unwrap_or_default(),
+            allow_projects: allow_projects.unwrap_or_default(),
+            deny_projects: deny_projects.unwrap_or_default(),
+            allow_domains: allow_domains.unwrap_or_default(),
+            deny_domains: deny_domains.unwrap_or_default(),
+            deny_content: deny_content.unwrap_or_default(),
+            redact_content: redact_content.unwrap_or_default(),
+        })
+    }
+
+    /// Rebuild a filter from to_json() output. Raises ValueError if it doesn't parse.
+    #[staticmethod]
+    fn from_json(text: &str) -> PyResult<Self> {
+        let options =
+            serde_json::from_str(text).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        PrivacyFilter::from_options(options)
+    }
+
+    /// The filter's settings as a JSON object of constructor keywords.
+    fn to_json(&self) -> String {
+        serde_json::to_string(&self.options()).unwrap_or_default()
+    }
+
+    fn __getnewargs_ex__<'py>(
+        &self,
+        py: Python<'py>,
+    ) -> PyResult<(Bound<'py, PyTuple>, Bound<'py, PyAny>)> {
+        let options = serde_json::to_value(self.options()).unwrap_or_default();
+        Ok((PyTuple::empty(py), json_to_py(py, &options)?))
+    }
+
+    /// The event with redactions applied, or None if the filter drops it.
+    fn apply<'py>(&self, event: &Bound<'py, PyDict>) -> PyResult<Option<Bound<'py, PyDict>>> {
+        self.apply_dict(event)
+    }
+
+    /// Apply the filter to a list of events, returning the survivors.
+    fn filter<'py>(
+        &self,
+        py: Python<'py>,
+        events: &Bound<'py, PyAny>,
+    ) -> PyResult<Bound<'py, PyList>> {
+        let out = PyList::empty(py);
+        for item in events.try_iter()? {
+            let item = item?;
+            if let Some(kept) = self.apply_dict(item.cast::<PyDict>()?)? {
+                out.append(kept)?;
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// Drop and redact transcript events according to the installed filter.
+pub(crate) fn filter_transcript_events(events: &mut Vec<TranscriptEvent>) {
+    if let Some(filter) = global_filter() {
+        filter.apply_transcript(events);
+    }
+}
+
+/// Install a PrivacyFilter process-wide, or remove it with None.
+///
+/// The installed filter is applied by Collector, parse_transcript and
+/// replay_transcript before events reach Python.
+#[pyfunction]
+#[pyo3(signature = (filter))]
+pub fn set_privacy_filter(filter: Option<PrivacyFilter>) {
+    *GLOBAL_FILTER.write().unwrap_or_else(|e| e.into_inner()) = filter.map(Arc::new);
+}
@@ -0,0 +1,284 @@
+use std::collections::HashMap;
+
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use serde_json::json;
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+
+use crate::collector::{now, record, records_to_py, Record, Sampler};
+
+const MIB: u64 = 1024 * 1024;
+
+/// Identity of a process across snapshots: pid and start time bits, which guard
+/// against pid reuse.
+type ProcessKey = (i64, Option<u64>);
+
+fn list_processes() -> Vec<Record> {
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::nothing()
+            .without_tasks()
+            .with_exe(UpdateKind::OnlyIfNotSet)
+            .with_cmd(UpdateKind::OnlyIfNotSet),
+    );
+    let mut processes: Vec<Record> = system
+        .processes()
+        .values()
+        .map(|p| {
+            record(
+                "process",
+                json!({
+                    "pid": p.pid().as_u32(),
+                    "name": p.name().to_string_lossy(),
+                    "exe": p.exe().map(|e| e.to_string_lossy()),
+                    "argv": p.cmd().iter().map(|a| a.to_string_lossy()).collect::<Vec<_>>(),
+                    "start_time": p.start_time() as f64,
+                    "parent": p.parent().map(|pid| pid.as_u32()),
+                }),
+            )
+        })
+        .collect();
+    processes.sort_by_key(|p| p["pid"].as_u64());
+    processes
+}
+
+/// Every running process: {source: "process", pid, name, exe, argv, start_time,
+/// parent}, sorted by pid.
+///
+/// start_time is in epoch seconds; exe and parent are None when the OS won't say
+/// (other users' processes usually hide exe and argv). Pass two snapshots to
+/// diff_processes to turn them into launch and exit events.
+#[pyfunction]
+pub fn snapshot_processes(py: Python<'_>) -> PyResult<Bound<'_, PyList>> {
+    let processes = py.detach(list_processes);
+    records_to_py(py, processes)
+}
+
+fn keyed<'py>(snapshot: &Bound<'py, PyList>) -> PyResult<HashMap<ProcessKey, Bound<'py, PyDict>>> {
+    let mut map = HashMap::new();
+    for item in snapshot.iter() {
+        let process = item.cast_into::<PyDict>()?;
+        let pid: i64 = match process.get_item("pid")? {
+            Some(pid) => pid.extract()?,
+            None => return Err(pyo3::exceptions::PyValueError::new_err("process without pid")),
+        };
+        let start: Option<f64> =
+            process.get_item("start_time")?.map(|s| s.extract()).transpose()?;
+        map.insert((pid, start.map(f64::to_bits)), process);
+    }
+    Ok(map)
+}
+
+fn event<'py>(process: &Bound<'py, PyDict>, kind: &str, ts: f64) -> PyResult<Bound<'py, PyDict>> {
+    let event = process.copy()?;
+    event.set_item("source", "process")?;
+    event.set_item("kind", kind)?;
+    event.set_item("timestamp", ts)?;
+    Ok(event)
+}
+
+/// Compare two snapshot_processes() results and return launch and exit events.
+///
+/// A process is matched on pid and start_time, so a recycled pid counts as an exit
+/// plus a launch. Each event is the process dict plus {source: "process", kind,
+/// timestamp}: "exit" events (stamped with the current time, since the exact moment
+/// isn't known) come first, then "launch" events stamped with the process start
+/// time, each group in timestamp then pid order.
+#[pyfunction]
+pub fn diff_processes<'py>(
+    py: Python<'py>,
+    before: &Bound<'py, PyList>,
+    after: &Bound<'py, PyList>,
+) -> PyResult<Bound<'py, PyList>> {
+    let (old, new) = (keyed(before)?, keyed(after)?);
+    let ts = now();
+    let mut exits: Vec<_> = old.iter().filter(|(k, _)| !new.contains_key(*k)).collect();
+    exits.sort_by_key(|((pid, _), _)| *pid);
+    let mut launches: Vec<_> = new.iter().filter(|(k, _)| !old.contains_key(*k)).collect();
+    let start = |bits: &Option<u64>| bits.map_or(ts, f64::from_bits);
+    launches.sort_by(|((a_pid, a_start), _), ((b_pid, b_start), _)| {
+        start(a_start).total_cmp(&start(b_start)).then(a_pid.cmp(b_pid))
+    });
+
+    let events = PyList::empty(py);
+    for (_, process) in exits {
+        events.append(event(process, "exit", ts)?)?;
+    }
+    for ((_, started), process) in launches {
+        events.append(event(process, "launch", start(started))?)?;
+    }
+    Ok(events)
+}
+
+/// Thresholds for the Collector's "resources" source.
+///
+/// processes lists process names to watch (case-insensitive; default all).
+/// cpu_percent is per-core percent, so a process saturating two cores reads 200.
+/// rss_mb is resident memory in MiB. A process must stay over a threshold for
+/// sustain seconds before it is reported.
+#[pyclass(frozen, from_py_object)]
+#[derive(Clone)]
+pub struct ResourceLimits {
+    processes: Vec<String>,
+    cpu_percent: Option<f64>,
+    rss_bytes: Option<u64>,
+    sustain: f64,
+}
+
+impl Default for ResourceLimits {
+    fn default() -> Self {
+        ResourceLimits {
+            processes: Vec::new(),
+            cpu_percent: Some(90.0),
+            rss_bytes: Some(2048 * MIB),
+            sustain: 300.0,
+        }
+    }
+}
+
+#[pymethods]
+impl ResourceLimits {
+    #[new]
+    #[pyo3(signature = (processes=None, *, cpu_percent=None, rss_mb=None, sustain=300.0))]
+    fn new(
+        processes: Option<Vec<String>>,
+        cpu_percent: Option<f64>,
+        rss_mb: Option<f64>,
+        sustain: f64,
+    ) -> PyResult<Self> {
+        if cpu_percent.is_none() && rss_mb.is_none() {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "set at least one of cpu_percent and rss_mb",
+            ));
+        }
+        if sustain.is_nan() || sustain < 0.0 {
+            return Err(pyo3::exceptions::PyValueError::new_err("sustain must be non-negative"));
+        }
+        Ok(ResourceLimits {
+            processes: processes.unwrap_or_default().iter().map(|p| p.to_lowercase()).collect(),
+            cpu_percent,
+            rss_bytes: rss_mb.map(|mb| (mb * MIB as f64) as u64),
+            sustain,
+        })
+    }
+}
+
+/// A process that is over one threshold.
+struct Breach {
+    name: String,
+    threshold: f64,
+    since: f64,
+    peak: f64,
+    reported: bool,
+}
+
+/// Tracks CPU and RSS of matching processes and emits an "exceeded" event once one
+/// has stayed over a threshold for limits.sustain seconds, then "recovered" when it
+/// drops back under (or exits).
+pub(crate) struct ResourceSampler {
+    limits: ResourceLimits,
+    system: System,
+    breaches: HashMap<(u32, &'static str), Breach>,
+}
+
+impl ResourceSampler {
+    pub(crate) fn new(limits: ResourceLimits) -> Self {
+        ResourceSampler { limits, system: System::new(), breaches: HashMap::new() }
+    }
+
+    fn watched(&self, name: &str) -> bool {
+        self.limits.processes.is_empty() || self.limits.processes.contains(&name.to_lowercase())
+    }
+}
+
+fn reading(process: &sysinfo::Process, metric: &str) -> f64 {
+    match metric {
+        "cpu" => f64::from(process.cpu_usage()),
+        _ => process.memory() as f64,
+    }
+}
+
+fn breach_event(
+    kind: &str,
+    (pid, metric): (u32, &str),
+    breach: &Breach,
+    value: Option<f64>,
+    ts: f64,
+) -> Record {
+    record(
+        "resources",
+        json!({
+            "kind": kind,
+            "timestamp": ts,
+            "pid": pid,
+            "name": breach.name,
+            "metric": metric,
+            "value": value,
+            "peak": breach.peak,
+            "threshold": breach.threshold,
+            "since": breach.since,
+            "duration": ts - breach.since,
+        }),
+    )
+}
+
+impl Sampler for ResourceSampler {
+    fn name(&self) -> &'static str {
+        "resources"
+    }
+
+    fn sample(&mut self) -> Result<Vec<Record>, String> {
+        self.system.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::nothing().without_tasks().with_cpu().with_memory(),
+        );
+        let ts = now();
+        let limits =
+            [("cpu", self.limits.cpu_percent), ("rss", self.limits.rss_bytes.map(|b| b as f64))];
+        let mut over = HashMap::new();
+        for (pid, process) in self.system.processes() {
+            let name = process.name().to_string_lossy();
+            if !self.watched(&name) {
+                continue;
+            }
+            for (metric, threshold) in limits {
+                let value = reading(process, metric);
+                if let Some(threshold) = threshold.filter(|t| value > *t) {
+                    over.insert((pid.as_u32(), metric), (name.to_string(), value, threshold));
+                }
+            }
+        }
+
+        let mut events = Vec::new();
+        // Breaches that ended: the process dropped under the threshold or exited.
+        let ended: Vec<_> =
+            self.breaches.keys().filter(|k| !over.contains_key(*k)).copied().collect();
+        for key in ended {
+            let Some(breach) = self.breaches.remove(&key) else { continue };
+            if breach.reported {
+                let process = self.system.process(sysinfo::Pid::from_u32(key.0));
+                let value = process.map(|p| reading(p, key.1));
+                events.push(breach_event("recovered", key, &breach, value, ts));
+            }
+        }
+        for (key, (name, value, threshold)) in over {
+            let breach = self.breaches.entry(key).or_insert(Breach {
+                name,
+                threshold,
+                since: ts,
+                peak: value,
+                reported: false,
+            });
+            breach.peak = breach.peak.max(value);
+            if !breach.reported && ts - breach.since >= self.limits.sustain {
+                breach.reported = true;
+                events.push(breach_event("exceeded", key, breach, Some(value), ts));
+            }
+        }
+        events.sort_by_key(|e| (e["pid"].as_u64(), e["metric"].as_str().map(String::from)));
+        Ok(events)
+    }
+}
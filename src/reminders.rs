@@ -0,0 +1,113 @@
+use std::path::{Path, PathBuf};
+
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+use rusqlite::Connection;
+use serde_json::json;
+
+use crate::collector::{home_path, record, records_to_py, Record};
+use crate::history::{has_table, with_snapshot};
+
+/// Reminders stores times as seconds since 2001-01-01 (Core Data reference date).
+const COCOA_EPOCH_OFFSET: f64 = 978_307_200.0;
+/// One Data-<uuid>.sqlite store per account (iCloud, On My Mac, ...).
+const STORES_DIR: &str = "Library/Group Containers/group.com.apple.reminders/Container_v1/Stores";
+
+fn read_reminders(conn: &Connection, since: f64) -> rusqlite::Result<Vec<Record>> {
+    // Lists moved to ZREMCDBASELIST in macOS 13; older stores only have the reminders.
+    let list = if has_table(conn, "ZREMCDBASELIST")? {
+        "(SELECT l.ZNAME FROM ZREMCDBASELIST l WHERE l.Z_PK = r.ZLIST)"
+    } else {
+        "NULL"
+    };
+    let sql = format!(
+        "SELECT r.ZCKIDENTIFIER, r.ZTITLE, r.ZNOTES, {list}, r.ZCREATIONDATE,
+                r.ZCOMPLETED, r.ZCOMPLETIONDATE, r.ZDUEDATE, r.ZPRIORITY, r.ZFLAGGED
+         FROM ZREMCDREMINDER r
+         WHERE COALESCE(r.ZMARKEDFORDELETION, 0) = 0
+           AND MAX(COALESCE(r.ZCREATIONDATE, 0), COALESCE(r.ZCOMPLETIONDATE, 0)) > ?"
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let cocoa = |t: Option<f64>| t.map(|t| t + COCOA_EPOCH_OFFSET);
+    let rows = stmt.query_map([since - COCOA_EPOCH_OFFSET], |row| {
+        let created = cocoa(row.get(4)?);
+        let completed = row.get::<_, Option<i64>>(5)?.unwrap_or(0) != 0;
+        let completed_at = cocoa(row.get(6)?).filter(|_| completed);
+        let reminder = json!({
+            "reminder_id": row.get::<_, Option<String>>(0)?,
+            "title": row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+            "notes": row.get::<_, Option<String>>(2)?,
+            "list": row.get::<_, Option<String>>(3)?,
+            "created": created,
+            "due": cocoa(row.get(7)?),
+            "priority": row.get::<_, Option<i64>>(8)?.unwrap_or(0),
+            "flagged": row.get::<_, Option<i64>>(9)?.unwrap_or(0) != 0,
+            "completed": completed,
+        });
+        Ok((reminder, created, completed_at))
+    })?;
+
+    let mut events = Vec::new();
+    for row in rows {
+        let (reminder, created, completed_at) = row?;
+        for (kind, ts) in [("created", created), ("completed", completed_at)] {
+            let Some(ts) = ts.filter(|ts| *ts > since) else { continue };
+            let mut fields = reminder.clone();
+            fields["kind"] = json!(kind);
+            fields["timestamp"] = json!(ts);
+            events.push(record("reminders", fields));
+        }
+    }
+    Ok(events)
+}
+
+/// The stores to read: path itself, or every Data-*.sqlite in it if it's a directory.
+fn stores(path: &Path) -> Vec<PathBuf> {
+    if !path.is_dir() {
+        return vec![path.to_path_buf()];
+    }
+    let mut found: Vec<PathBuf> = std::fs::read_dir(path)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| {
+            let name = p.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+            name.starts_with("Data-") && name.ends_with(".sqlite")
+        })
+        .collect();
+    found.sort();
+    found
+}
+
+/// Read reminder created and completed events from the Apple Reminders stores.
+///
+/// Each reminder yields a {kind: "created"} event at its creation time and, once
+/// checked off, a {kind: "completed"} event at its completion time. Events are
+/// {source: "reminders", kind, timestamp, reminder_id, title, notes, list, created,
+/// due, priority, flagged, completed}, oldest first; deleted reminders are skipped.
+/// Only events after since (epoch seconds) are returned. path is a store or a
+/// directory of Data-*.sqlite stores, one per account, and defaults to the Reminders
+/// group container, which needs Full Disk Access.
+#[pyfunction]
+#[pyo3(signature = (path=None, since=None))]
+pub fn reminders_events<'py>(
+    py: Python<'py>,
+    path: Option<PathBuf>,
+    since: Option<f64>,
+) -> PyResult<Bound<'py, PyList>> {
+    let path = path.unwrap_or_else(|| home_path(STORES_DIR));
+    let since = since.unwrap_or(f64::NEG_INFINITY);
+    let events = py.detach(|| -> PyResult<Vec<Record>> {
+        let mut events = Vec::new();
+        for store in stores(&path) {
+            events.extend(with_snapshot(&store, |conn| read_reminders(conn, since))?);
+        }
+        events.sort_by(|a, b| {
+            let ts = |r: &Record| r["timestamp"].as_f64().unwrap_or_default();
+            ts(a).total_cmp(&ts(b))
+        });
+        Ok(events)
+    })?;
+    records_to_py(py, events)
+}
@@ -0,0 +1,119 @@
+use std::time::{Duration, Instant};
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::errors::ReadError;
+use crate::parserconfig::{ParserConfig, TimeFormat};
+use crate::{event_to_dict, parse_transcript_impl, Interner, TranscriptEvent};
+
+/// Longest single sleep before checking for KeyboardInterrupt.
+const SLEEP_SLICE: Duration = Duration::from_millis(100);
+
+/// Iterator yielding transcript events spaced by their original timing.
+#[pyclass]
+pub struct TranscriptReplay {
+    events: std::vec::IntoIter<TranscriptEvent>,
+    prev_ts: Option<f64>,
+    speed: f64,
+    max_delay: Option<f64>,
+    time_format: TimeFormat,
+    strings: Interner,
+}
+
+impl TranscriptReplay {
+    fn delay_before(&mut self, ts: f64) -> Duration {
+        if ts <= 0.0 {
+            return Duration::ZERO;
+        }
+        let delay = match self.prev_ts.replace(ts) {
+            Some(prev) => ((ts - prev) / self.speed).max(0.0),
+            None => 0.0,
+        };
+        let delay = match self.max_delay {
+            Some(max) => delay.min(max),
+            None => delay,
+        };
+        // A tiny speed can scale a gap past what a Duration holds.
+        Duration::try_from_secs_f64(delay).unwrap_or(Duration::MAX)
+    }
+}
+
+/// Sleep with the GIL released, waking periodically so Ctrl-C still works.
+fn interruptible_sleep(py: Python<'_>, total: Duration) -> PyResult<()> {
+    // Past what an Instant can represent, sleep until interrupted.
+    let deadline = Instant::now().checked_add(total);
+    loop {
+        let remaining = match deadline {
+            Some(d) => d.saturating_duration_since(Instant::now()),
+            None => SLEEP_SLICE,
+        };
+        if remaining.is_zero() {
+            return Ok(());
+        }
+        py.detach(|| std::thread::sleep(remaining.min(SLEEP_SLICE)));
+        py.check_signals()?;
+    }
+}
+
+#[pymethods]
+impl TranscriptReplay {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__<'py>(&mut self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyDict>>> {
+        let ev = match self.events.next() {
+            Some(ev) => ev,
+            None => return Ok(None),
+        };
+        let delay = self.delay_before(ev.timestamp);
+        interruptible_sleep(py, delay)?;
+        event_to_dict(py, &ev, self.time_format, &mut self.strings).map(Some)
+    }
+
+    fn __len__(&self) -> usize {
+        self.events.len()
+    }
+}
+
+/// Replay a transcript, yielding events with the original gaps between them.
+///
+/// speed > 1 plays faster; max_delay caps any single wait (in seconds, after scaling)
+/// so long idle stretches don't stall the replay. preview_len, datetimes and config work
+/// as in parse_transcript. Raises ValueError for a speed that isn't positive or a
+/// negative max_delay.
+#[pyfunction]
+#[pyo3(signature = (
+    path, speed=1.0, max_delay=None, preview_len=None, datetimes=None, config=None
+))]
+pub fn replay_transcript(
+    py: Python<'_>,
+    path: &str,
+    speed: f64,
+    max_delay: Option<f64>,
+    preview_len: Option<usize>,
+    datetimes: Option<bool>,
+    config: Option<ParserConfig>,
+) -> PyResult<TranscriptReplay> {
+    if speed.is_nan() || speed <= 0.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("speed must be positive"));
+    }
+    if max_delay.is_some_and(|d| d.is_nan() || d < 0.0) {
+        return Err(pyo3::exceptions::PyValueError::new_err("max_delay must be non-negative"));
+    }
+    let config = config.unwrap_or_default().with_overrides(preview_len, datetimes);
+    let events = py.detach(|| {
+        let (mut events, _) = parse_transcript_impl(path, 0, &config)?;
+        config.filter_events(&mut events);
+        Ok::<_, ReadError>(events)
+    })?;
+    Ok(TranscriptReplay {
+        events: events.into_iter(),
+        prev_ts: None,
+        speed,
+        max_delay,
+        time_format: config.time_format,
+        strings: Interner::default(),
+    })
+}
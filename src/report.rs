@@ -0,0 +1,571 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use chrono::{Local, NaiveDate, Offset, TimeZone};
+use pyo3::prelude::*;
+use rusqlite::{Connection, OpenFlags};
+use serde_json::json;
+
+use crate::collector::{home_path, record, Record};
+use crate::errors::sqlite_error;
+use crate::history::has_table;
+use crate::narrate::format_clock;
+use crate::privacy::{global_filter, host_of};
+use crate::rollup::find_jsonl_files;
+use crate::sessions::sessionize_impl;
+use crate::timeutil::parse_iso_ts;
+use crate::usage::Usage;
+
+pub(crate) const REPORT_SOURCES: [&str; 4] = ["claude", "messages", "network", "browser"];
+/// Gap that splits agent activity into separately counted stretches, as in
+/// project_rollup.
+const GAP_SECONDS: f64 = 300.0;
+const TOP: usize = 5;
+const MAX_DESTINATIONS: usize = 15;
+
+/// [start, end) of a calendar day in epoch seconds, in local time or at utc_offset.
+pub(crate) fn day_bounds(date: NaiveDate, utc_offset: Option<i64>) -> PyResult<(f64, f64)> {
+    let midnight = |d: NaiveDate| -> Option<f64> {
+        let at = d.and_hms_opt(0, 0, 0)?;
+        match utc_offset {
+            Some(offset) => Some((at.and_utc().timestamp() - offset) as f64),
+            None => Some(Local.from_local_datetime(&at).earliest()?.timestamp() as f64),
+        }
+    };
+    let next = date.succ_opt();
+    match (midnight(date), next.and_then(midnight)) {
+        (Some(start), Some(end)) => Ok((start, end)),
+        _ => Err(pyo3::exceptions::PyValueError::new_err(format!("invalid date {date}"))),
+    }
+}
+
+/// Seconds east of UTC at ts: utc_offset if given, else the local zone's.
+pub(crate) fn offset_at(ts: f64, utc_offset: Option<i64>) -> i64 {
+    utc_offset.unwrap_or_else(|| {
+        Local
+            .timestamp_opt(ts as i64, 0)
+            .single()
+            .map_or(0, |dt| dt.offset().fix().local_minus_utc() as i64)
+    })
+}
+
+/// "2h 05m", "12m", "40s".
+pub(crate) fn format_duration(seconds: f64) -> String {
+    let seconds = seconds.max(0.0).round() as u64;
+    match (seconds / 3600, (seconds % 3600) / 60) {
+        (0, 0) => format!("{seconds}s"),
+        (0, m) => format!("{m}m"),
+        (h, m) => format!("{h}h {m:02}m"),
+    }
+}
+
+/// Pipes and newlines would break a Markdown table cell.
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace(['\n', '\r'], " ")
+}
+
+fn shorten(text: &str, max_chars: usize) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() <= max_chars {
+        return text;
+    }
+    let cut: String = text.chars().take(max_chars - 1).collect();
+    format!("{}…", cut.trim_end())
+}
+
+fn basename(path: &str) -> &str {
+    let path = path.trim_end_matches('/');
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+/// Drop what the installed privacy filter wouldn't let out, and redact the rest.
+fn filtered(mut records: Vec<Record>) -> Vec<Record> {
+    if let Some(filter) = global_filter() {
+        records.retain_mut(|r| filter.apply_record(r));
+    }
+    records
+}
+
+/// The day's activity in one transcript: its timestamps, cost and tool calls.
+struct Transcript {
+    session_id: String,
+    project_path: Option<String>,
+    subagent: bool,
+    timestamps: Vec<f64>,
+    cost_usd: f64,
+    tool_calls: u64,
+    path: PathBuf,
+}
+
+fn scan_transcript(path: &Path, start: f64, end: f64) -> Option<Transcript> {
+    // Skip files untouched since the day began without reading them.
+    let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    let modified = modified.duration_since(std::time::UNIX_EPOCH).ok()?.as_secs_f64();
+    if modified < start {
+        return None;
+    }
+    let stem = path.file_stem()?.to_string_lossy().into_owned();
+    let mut transcript = Transcript {
+        subagent: stem.starts_with("agent-"),
+        session_id: stem,
+        project_path: None,
+        timestamps: Vec::new(),
+        cost_usd: 0.0,
+        tool_calls: 0,
+        path: path.to_path_buf(),
+    };
+    let mut seen_messages = HashSet::new();
+    for line in BufReader::new(File::open(path).ok()?).lines() {
+        let Ok(line) = line else { break };
+        let Ok(entry) = serde_json::from_str::<serde_json::Value>(line.trim()) else { continue };
+        let Some(ts) = entry["timestamp"].as_str().and_then(parse_iso_ts) else { continue };
+        if ts < start || ts >= end {
+            continue;
+        }
+        if transcript.project_path.is_none() {
+            transcript.project_path = entry["cwd"].as_str().map(str::to_string);
+        }
+        // Subagent files carry their parent's sessionId, so their cost folds into it.
+        if let Some(id) = entry["sessionId"].as_str().filter(|id| !id.is_empty()) {
+            id.clone_into(&mut transcript.session_id);
+        }
+        transcript.timestamps.push(ts);
+        if entry["type"].as_str() != Some("assistant") {
+            continue;
+        }
+        let msg = &entry["message"];
+        let msg_id = msg["id"].as_str().unwrap_or("");
+        if msg_id.is_empty() || seen_messages.insert(msg_id.to_string()) {
+            if let Some(usage) = Usage::from_message_or_estimate(msg) {
+                transcript.cost_usd += usage.cost_usd(msg["model"].as_str().unwrap_or(""));
+            }
+        }
+        let blocks = msg["content"].as_array().into_iter().flatten();
+        transcript.tool_calls += blocks.filter(|b| b["type"] == "tool_use").count() as u64;
+    }
+    (!transcript.timestamps.is_empty()).then_some(transcript)
+}
+
+fn active_seconds(timestamps: &[f64]) -> f64 {
+    sessionize_impl(timestamps, GAP_SECONDS).iter().map(|s| s.duration()).sum()
+}
+
+/// Read-only, so a report never contends with the daemon writing the store.
+fn open_store(db: &Path) -> PyResult<Option<Connection>> {
+    if !db.is_file() {
+        return Ok(None);
+    }
+    Connection::open_with_flags(db, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map(Some)
+        .map_err(|e| sqlite_error(db, e))
+}
+
+fn query<T>(
+    conn: &Connection,
+    table: &str,
+    sql: &str,
+    params: impl rusqlite::Params,
+    map: impl FnMut(&rusqlite::Row<'_>) -> rusqlite::Result<T>,
+) -> rusqlite::Result<Vec<T>> {
+    if !has_table(conn, table)? {
+        return Ok(Vec::new());
+    }
+    let mut stmt = conn.prepare(sql)?;
+    let rows = stmt.query_map(params, map)?;
+    rows.collect()
+}
+
+/// Per-project totals for the day.
+#[derive(Default)]
+struct Project {
+    timestamps: Vec<f64>,
+    sessions: HashSet<String>,
+    tool_calls: u64,
+    cost_usd: f64,
+}
+
+/// A conversation's messages for the day.
+#[derive(Default)]
+struct Conversation {
+    sent: u64,
+    received: u64,
+    last_received: Option<(f64, String)>,
+}
+
+struct Report {
+    start: f64,
+    end: f64,
+    utc_offset: Option<i64>,
+    headline: Vec<String>,
+    body: String,
+}
+
+impl Report {
+    fn clock(&self, ts: f64) -> String {
+        format_clock(ts, offset_at(ts, self.utc_offset))
+    }
+
+    fn section(&mut self, title: &str, empty: bool) -> bool {
+        let _ = write!(self.body, "\n## {title}\n\n");
+        if empty {
+            self.body.push_str("_Nothing recorded._\n");
+        }
+        !empty
+    }
+
+    fn claude(&mut self, root: &Path) {
+        let filter = global_filter();
+        let mut transcripts: Vec<Transcript> = find_jsonl_files(root)
+            .iter()
+            .filter_map(|p| scan_transcript(p, self.start, self.end))
+            .filter(|t| {
+                let project = t.project_path.as_deref();
+                filter.as_ref().is_none_or(|f| {
+                    f.allows(|field| if field == "project_path" { project } else { None })
+                })
+            })
+            .collect();
+        transcripts.sort_by(|a, b| a.timestamps[0].total_cmp(&b.timestamps[0]));
+
+        let mut projects: BTreeMap<String, Project> = BTreeMap::new();
+        for t in &transcripts {
+            let key = t.project_path.clone().unwrap_or_else(|| "(unknown)".to_string());
+            let project = projects.entry(key).or_default();
+            project.timestamps.extend(&t.timestamps);
+            project.sessions.insert(t.session_id.clone());
+            project.tool_calls += t.tool_calls;
+            project.cost_usd += t.cost_usd;
+        }
+        let mut ranked: Vec<(String, f64, Project)> = projects
+            .into_iter()
+            .map(|(path, p)| (path, active_seconds(&p.timestamps), p))
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        let total_active: f64 = ranked.iter().map(|r| r.1).sum();
+        let total_cost: f64 = ranked.iter().map(|r| r.2.cost_usd).sum();
+        if !ranked.is_empty() {
+            let n = ranked.len();
+            self.headline.push(format!(
+                "{} of agent time across {n} project{}, ${total_cost:.2}",
+                format_duration(total_active),
+                if n == 1 { "" } else { "s" }
+            ));
+        }
+
+        if self.section("Top projects", ranked.is_empty()) {
+            self.body.push_str("| Project | Active | Sessions | Tool calls | Cost |\n");
+            self.body.push_str("|---|---|---|---|---|\n");
+            for (path, active, p) in ranked.iter().take(TOP) {
+                let _ = writeln!(
+                    self.body,
+                    "| {} | {} | {} | {} | ${:.2} |",
+                    cell(basename(path)),
+                    format_duration(*active),
+                    p.sessions.len(),
+                    p.tool_calls,
+                    p.cost_usd
+                );
+            }
+        }
+
+        // Subagent transcripts carry their parent's sessionId; count them there.
+        let mut sessions: Vec<(String, Vec<&Transcript>)> = Vec::new();
+        for t in &transcripts {
+            match sessions.iter_mut().find(|(id, _)| *id == t.session_id) {
+                Some((_, parts)) => parts.push(t),
+                None => sessions.push((t.session_id.clone(), vec![t])),
+            }
+        }
+        if self.section("Agent sessions", sessions.is_empty()) {
+            self.body.push_str("| Time | Session | Project | Active | Cost |\n");
+            self.body.push_str("|---|---|---|---|---|\n");
+            for (id, parts) in &sessions {
+                let mut timestamps: Vec<f64> =
+                    parts.iter().flat_map(|t| t.timestamps.iter().copied()).collect();
+                timestamps.sort_by(f64::total_cmp);
+                let main = parts.iter().find(|t| !t.subagent).unwrap_or(&parts[0]);
+                let title = crate::title::infer_title_impl(&main.path.to_string_lossy(), 60)
+                    .ok()
+                    .flatten()
+                    .unwrap_or_else(|| id.chars().take(8).collect());
+                let project = main.project_path.as_deref().map(basename).unwrap_or("");
+                let (first, last) = (timestamps[0], timestamps[timestamps.len() - 1]);
+                let _ = writeln!(
+                    self.body,
+                    "| {}–{} | {} | {} | {} | ${:.2} |",
+                    self.clock(first),
+                    self.clock(last),
+                    cell(&title),
+                    cell(project),
+                    format_duration(active_seconds(&timestamps)),
+                    parts.iter().map(|t| t.cost_usd).sum::<f64>()
+                );
+            }
+        }
+    }
+
+    fn messages(&mut self, conn: &Connection) -> rusqlite::Result<()> {
+        let rows = query(
+            conn,
+            "message_events",
+            "SELECT timestamp, contact, is_from_me, content_preview, chat_name
+             FROM message_events WHERE timestamp >= ? AND timestamp < ? ORDER BY timestamp",
+            [self.start, self.end],
+            |row| {
+                Ok(record(
+                    "messages",
+                    json!({
+                        "timestamp": row.get::<_, f64>(0)?,
+                        "contact": row.get::<_, Option<String>>(1)?,
+                        "is_from_me": row.get::<_, Option<bool>>(2)?.unwrap_or(false),
+                        "content_preview": row.get::<_, Option<String>>(3)?,
+                        "chat_name": row.get::<_, Option<String>>(4)?,
+                    }),
+                ))
+            },
+        )?;
+        let rows = filtered(rows);
+        let mut conversations: HashMap<String, Conversation> = HashMap::new();
+        for row in &rows {
+            let name = row["chat_name"]
+                .as_str()
+                .filter(|n| !n.is_empty())
+                .or(row["contact"].as_str())
+                .unwrap_or("(unknown)");
+            let conversation = conversations.entry(name.to_string()).or_default();
+            if row["is_from_me"] == true {
+                conversation.sent += 1;
+            } else {
+                conversation.received += 1;
+                let preview = row["content_preview"].as_str().unwrap_or_default();
+                if !preview.trim().is_empty() {
+                    let ts = row["timestamp"].as_f64().unwrap_or_default();
+                    conversation.last_received = Some((ts, preview.to_string()));
+                }
+            }
+        }
+        let mut ranked: Vec<(String, Conversation)> = conversations.into_iter().collect();
+        ranked.sort_by(|a, b| {
+            let total = |c: &Conversation| c.sent + c.received;
+            total(&b.1).cmp(&total(&a.1)).then_with(|| a.0.cmp(&b.0))
+        });
+        if !rows.is_empty() {
+            self.headline.push(format!(
+                "{} message{} in {} conversation{}",
+                rows.len(),
+                if rows.len() == 1 { "" } else { "s" },
+                ranked.len(),
+                if ranked.len() == 1 { "" } else { "s" }
+            ));
+        }
+        if self.section("Notable messages", ranked.is_empty()) {
+            for (name, c) in ranked.iter().take(TOP) {
+                let total = c.sent + c.received;
+                let _ = write!(
+                    self.body,
+                    "- **{}**: {total} message{} ({} sent)",
+                    cell(name),
+                    if total == 1 { "" } else { "s" },
+                    c.sent
+                );
+                if let Some((ts, preview)) = &c.last_received {
+                    let _ = write!(
+                        self.body,
+                        ", last at {}: \"{}\"",
+                        self.clock(*ts),
+                        shorten(preview, 80)
+                    );
+                }
+                self.body.push('\n');
+            }
+        }
+        Ok(())
+    }
+
+    fn network(&mut self, conn: &Connection) -> rusqlite::Result<()> {
+        let rows = query(
+            conn,
+            "network_events",
+            "SELECT timestamp, process_name, remote_address, remote_port
+             FROM network_events WHERE timestamp < ? ORDER BY timestamp",
+            [self.end],
+            |row| {
+                Ok((
+                    row.get::<_, f64>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    row.get::<_, Option<i64>>(3)?,
+                ))
+            },
+        )?;
+        // Destinations first contacted today, in the order they were reached.
+        let mut seen = HashSet::new();
+        let mut new = Vec::new();
+        for (ts, process, address, port) in rows {
+            let Some(address) = address.filter(|a| !a.is_empty()) else { continue };
+            if seen.insert(address.clone()) && ts >= self.start {
+                new.push(record(
+                    "network",
+                    json!({
+                        "timestamp": ts,
+                        "process_name": process,
+                        "remote_address": address,
+                        "remote_port": port,
+                    }),
+                ));
+            }
+        }
+        let new = filtered(new);
+        if !new.is_empty() {
+            let n = new.len();
+            self.headline
+                .push(format!("{n} new network destination{}", if n == 1 { "" } else { "s" }));
+        }
+        if self.section("New network destinations", new.is_empty()) {
+            for rec in new.iter().take(MAX_DESTINATIONS) {
+                let ts = rec["timestamp"].as_f64().unwrap_or_default();
+                let address = rec["remote_address"].as_str().unwrap_or_default();
+                let target = match rec["remote_port"].as_i64() {
+                    Some(port) if address.contains(':') => format!("[{address}]:{port}"),
+                    Some(port) => format!("{address}:{port}"),
+                    None => address.to_string(),
+                };
+                let process = rec["process_name"].as_str().unwrap_or("?");
+                let _ = writeln!(self.body, "- {} `{}` → {target}", self.clock(ts), process);
+            }
+            if new.len() > MAX_DESTINATIONS {
+                let _ = writeln!(self.body, "- …and {} more", new.len() - MAX_DESTINATIONS);
+            }
+        }
+        Ok(())
+    }
+
+    fn browser(&mut self, conn: &Connection) -> rusqlite::Result<()> {
+        let rows = query(
+            conn,
+            "browser_events",
+            "SELECT timestamp, url, title, visit_duration_s
+             FROM browser_events WHERE timestamp >= ? AND timestamp < ?",
+            [self.start, self.end],
+            |row| {
+                let url: Option<String> = row.get(1)?;
+                let host = url.as_deref().map(host_of).unwrap_or_default();
+                Ok(record(
+                    "browser",
+                    json!({
+                        "timestamp": row.get::<_, f64>(0)?,
+                        "url": url,
+                        "domain": host.strip_prefix("www.").unwrap_or(&host),
+                        "title": row.get::<_, Option<String>>(2)?,
+                        "duration": row.get::<_, Option<f64>>(3)?,
+                    }),
+                ))
+            },
+        )?;
+        let rows = filtered(rows);
+        let mut sites: HashMap<String, (u64, f64)> = HashMap::new();
+        for row in &rows {
+            let Some(domain) = row["domain"].as_str().filter(|d| !d.is_empty()) else { continue };
+            let site = sites.entry(domain.to_string()).or_default();
+            site.0 += 1;
+            site.1 += row["duration"].as_f64().unwrap_or(0.0);
+        }
+        let mut ranked: Vec<(String, (u64, f64))> = sites.into_iter().collect();
+        ranked.sort_by(|a, b| b.1 .0.cmp(&a.1 .0).then_with(|| a.0.cmp(&b.0)));
+        if !rows.is_empty() {
+            let n = rows.len();
+            self.headline.push(format!("{n} page visit{}", if n == 1 { "" } else { "s" }));
+        }
+        if self.section("Top sites", ranked.is_empty()) {
+            self.body.push_str("| Site | Visits | Time |\n|---|---|---|\n");
+            for (domain, (visits, seconds)) in ranked.iter().take(TOP) {
+                let _ = writeln!(
+                    self.body,
+                    "| {} | {visits} | {} |",
+                    cell(domain),
+                    format_duration(*seconds)
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// snoopy.db in $SNOOPY_DATA_DIR, else ~/.snoopy.
+fn default_store() -> PathBuf {
+    match std::env::var_os("SNOOPY_DATA_DIR") {
+        Some(dir) => PathBuf::from(dir).join("snoopy.db"),
+        None => home_path(".snoopy/snoopy.db"),
+    }
+}
+
+fn render(
+    date: NaiveDate,
+    sources: &[String],
+    db: &Path,
+    claude_root: &Path,
+    utc_offset: Option<i64>,
+) -> PyResult<String> {
+    let (start, end) = day_bounds(date, utc_offset)?;
+    let mut report = Report { start, end, utc_offset, headline: Vec::new(), body: String::new() };
+    let store = open_store(db)?;
+    let db_error = |e| sqlite_error(db, e);
+    for source in sources {
+        match (source.as_str(), &store) {
+            ("claude", _) => report.claude(claude_root),
+            ("messages", Some(conn)) => report.messages(conn).map_err(db_error)?,
+            ("network", Some(conn)) => report.network(conn).map_err(db_error)?,
+            ("browser", Some(conn)) => report.browser(conn).map_err(db_error)?,
+            ("messages", None) => drop(report.section("Notable messages", true)),
+            ("network", None) => drop(report.section("New network destinations", true)),
+            _ => drop(report.section("Top sites", true)),
+        }
+    }
+    let mut out = format!("# Daily report: {}\n\n", date.format("%A, %B %-d, %Y"));
+    if report.headline.is_empty() {
+        out.push_str("No activity recorded.\n");
+    } else {
+        let _ = writeln!(out, "{}.", report.headline.join(" · "));
+    }
+    out.push_str(&report.body);
+    Ok(out)
+}
+
+/// Render a Markdown digest of one day's activity.
+///
+/// date is a datetime.date. sources picks the sections, in order, from "claude"
+/// (top projects by active agent time, and each agent session with its title,
+/// times and cost, from the transcripts under claude_root, by default
+/// ~/.claude/projects), "messages" (the busiest conversations with their latest
+/// received message), "network" (destinations first contacted that day) and
+/// "browser" (most visited sites); the default is all of them. Messages, network
+/// and browser activity come from snoopy's store at db_path (by default snoopy.db in
+/// $SNOOPY_DATA_DIR or ~/.snoopy), opened read-only. The day runs from local
+/// midnight, or from midnight at utc_offset (seconds east of UTC) if given. The
+/// installed privacy filter applies throughout. Raises ValueError for an unknown
+/// source, and DatabaseLocked, PermissionDenied, FormatError or ValueError if the store
+/// can't be read.
+#[pyfunction]
+#[pyo3(signature = (date, sources=None, db_path=None, claude_root=None, utc_offset=None))]
+pub fn generate_daily_report(
+    py: Python<'_>,
+    date: NaiveDate,
+    sources: Option<Vec<String>>,
+    db_path: Option<PathBuf>,
+    claude_root: Option<PathBuf>,
+    utc_offset: Option<i64>,
+) -> PyResult<String> {
+    let sources = sources.unwrap_or_else(|| REPORT_SOURCES.iter().map(|s| s.to_string()).collect());
+    if let Some(unknown) = sources.iter().find(|s| !REPORT_SOURCES.contains(&s.as_str())) {
+        return Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "unknown report source {unknown:?}; expected one of {}",
+            REPORT_SOURCES.join(", ")
+        )));
+    }
+    let db = db_path.unwrap_or_else(default_store);
+    let claude_root = claude_root.unwrap_or_else(|| home_path(".claude/projects"));
+    py.detach(|| render(date, &sources, &db, &claude_root, utc_offset))
+}
@@ -0,0 +1,302 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::OnceLock;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use regex::Regex;
+use serde_json::{Map, Value};
+
+use crate::anonymize::Anonymizer;
+use crate::errors::{io_error, ReadError};
+
+/// Keys whose string values are file or directory paths in Claude Code transcripts.
+const PATH_KEYS: [&str; 6] = ["cwd", "file_path", "filePath", "notebook_path", "path", "paths"];
+
+/// Content block types that carry attachment data.
+const ATTACHMENT_TYPES: [&str; 2] = ["image", "document"];
+
+/// Credentials that commonly turn up in prompts, commands and tool output: API keys
+/// (OpenAI/Anthropic, GitHub, Slack, AWS), bearer tokens, secrets passed as URL or
+/// environment assignments, and PEM private keys.
+fn secrets_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(concat!(
+            r"sk-(?:ant-)?[A-Za-z0-9_-]{20,}",
+            r"|gh[pousr]_[A-Za-z0-9]{36,}|github_pat_[A-Za-z0-9_]{22,}",
+            r"|xox[abprs]-[A-Za-z0-9-]{10,}",
+            r"|AKIA[0-9A-Z]{16}",
+            r"|(?i:bearer)\s+[A-Za-z0-9._~+/=-]{20,}",
+            r"|(?i:api[_-]?key|secret|password|passwd|token)=[^\s&'\x22]+",
+            r"|-----BEGIN [A-Z ]*PRIVATE KEY-----[\s\S]*?-----END [A-Z ]*PRIVATE KEY-----",
+        ))
+        .unwrap()
+    })
+}
+
+/// What rewrite_transcript changes in the copy it writes.
+///
+/// redact_secrets replaces API keys, tokens, passwords and private keys with
+/// "[REDACTED]", as does any match of the regexes in redact. anonymize, an
+/// Anonymizer, replaces paths (cwd, file_path and the like, and the working
+/// directories where they appear inside other text) with its pseudonyms.
+/// strip_attachments swaps image and document blocks for a text placeholder.
+#[pyclass(frozen, from_py_object)]
+#[derive(Clone)]
+pub struct RewriteRules {
+    redact_secrets: bool,
+    redact: Vec<Regex>,
+    anonymize: Option<Anonymizer>,
+    strip_attachments: bool,
+}
+
+impl Default for RewriteRules {
+    fn default() -> Self {
+        RewriteRules {
+            redact_secrets: true,
+            redact: Vec::new(),
+            anonymize: None,
+            strip_attachments: true,
+        }
+    }
+}
+
+#[pymethods]
+impl RewriteRules {
+    #[new]
+    #[pyo3(signature = (
+        *, redact_secrets=true, redact=None, anonymize=None, strip_attachments=true
+    ))]
+    fn new(
+        redact_secrets: bool,
+        redact: Option<Vec<String>>,
+        anonymize: Option<Anonymizer>,
+        strip_attachments: bool,
+    ) -> PyResult<Self> {
+        let redact = redact
+            .unwrap_or_default()
+            .iter()
+            .map(|p| Regex::new(p))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(RewriteRules { redact_secrets, redact, anonymize, strip_attachments })
+    }
+}
+
+/// Counts of what a rewrite changed.
+#[derive(Default)]
+struct Changes {
+    lines: u64,
+    redactions: u64,
+    paths: u64,
+    attachments: u64,
+}
+
+/// Applies the rules to one transcript, remembering the working directories seen so
+/// far so they can be found inside free text.
+pub(crate) struct Rewriter<'a> {
+    rules: &'a RewriteRules,
+    /// Working directories and their pseudonyms, longest first.
+    dirs: Vec<(String, String)>,
+    changes: Changes,
+}
+
+impl<'a> Rewriter<'a> {
+    pub(crate) fn new(rules: &'a RewriteRules) -> Self {
+        Rewriter { rules, dirs: Vec::new(), changes: Changes::default() }
+    }
+
+    /// Apply the rules to a parsed transcript entry in place.
+    pub(crate) fn entry(&mut self, entry: &mut Value) {
+        self.value(None, entry);
+    }
+
+    pub(crate) fn redactions(&self) -> u64 {
+        self.changes.redactions
+    }
+
+    fn redact(&mut self, text: &mut String) {
+        let secrets = self.rules.redact_secrets.then(secrets_re);
+        for re in secrets.into_iter().chain(&self.rules.redact) {
+            let found = re.find_iter(text).count() as u64;
+            if found > 0 {
+                *text = re.replace_all(text, "[REDACTED]").into_owned();
+                self.changes.redactions += found;
+            }
+        }
+    }
+
+    fn anonymize_text(&mut self, text: &mut String) {
+        for (dir, pseudonym) in &self.dirs {
+            if text.contains(dir.as_str()) {
+                *text = text.replace(dir.as_str(), pseudonym);
+                self.changes.paths += 1;
+            }
+        }
+    }
+
+    fn remember_dir(&mut self, anonymizer: &Anonymizer, dir: &str) {
+        let dir = dir.trim_end_matches('/');
+        if dir.is_empty() || self.dirs.iter().any(|(d, _)| d == dir) {
+            return;
+        }
+        self.dirs.push((dir.to_string(), anonymizer.path(dir)));
+        self.dirs.sort_by_key(|(d, _)| std::cmp::Reverse(d.len()));
+    }
+
+    fn string(&mut self, key: Option<&str>, text: &mut String) {
+        self.redact(text);
+        let rules = self.rules;
+        let Some(anonymizer) = &rules.anonymize else {
+            return;
+        };
+        if key.is_some_and(|k| PATH_KEYS.contains(&k)) && text.starts_with(['/', '~']) {
+            if key == Some("cwd") {
+                self.remember_dir(anonymizer, text);
+            }
+            *text = anonymizer.path(text);
+            self.changes.paths += 1;
+        } else {
+            self.anonymize_text(text);
+        }
+    }
+
+    fn value(&mut self, key: Option<&str>, value: &mut Value) {
+        match value {
+            Value::String(text) => self.string(key, text),
+            Value::Array(items) => {
+                for item in items {
+                    if self.rules.strip_attachments && is_attachment(item) {
+                        *item = serde_json::json!({"type": "text", "text": "[attachment removed]"});
+                        self.changes.attachments += 1;
+                    } else {
+                        self.value(key, item);
+                    }
+                }
+            }
+            Value::Object(map) => self.object(map),
+            _ => {}
+        }
+    }
+
+    fn object(&mut self, map: &mut Map<String, Value>) {
+        // See the entry's working directory before any text that mentions it.
+        let rules = self.rules;
+        if let (Some(anonymizer), Some(Value::String(cwd))) = (&rules.anonymize, map.get("cwd")) {
+            self.remember_dir(anonymizer, cwd);
+        }
+        for (key, value) in map.iter_mut() {
+            self.value(Some(key), value);
+        }
+    }
+
+    /// The rewritten line, or the line itself with text rules applied if it isn't JSON.
+    fn line(&mut self, line: &str) -> String {
+        self.changes.lines += 1;
+        match serde_json::from_str::<Value>(line) {
+            Ok(mut entry) => {
+                self.value(None, &mut entry);
+                entry.to_string()
+            }
+            Err(_) => {
+                let mut text = line.to_string();
+                self.string(None, &mut text);
+                text
+            }
+        }
+    }
+}
+
+fn is_attachment(value: &Value) -> bool {
+    value.get("type").and_then(Value::as_str).is_some_and(|t| ATTACHMENT_TYPES.contains(&t))
+}
+
+fn rewrite_lines(
+    input: &mut impl BufRead,
+    out: &mut impl Write,
+    path: &Path,
+    out_path: &Path,
+    rules: &RewriteRules,
+) -> PyResult<Changes> {
+    let mut rewriter = Rewriter::new(rules);
+    let (mut buf, mut offset) = (Vec::new(), 0u64);
+    loop {
+        buf.clear();
+        let n = input.read_until(b'\n', &mut buf).map_err(|e| io_error(path, e))?;
+        if n == 0 {
+            break;
+        }
+        let line = std::str::from_utf8(&buf).map_err(|e| {
+            ReadError::Parse(path.to_path_buf(), offset + e.valid_up_to() as u64, e.to_string())
+        })?;
+        offset += n as u64;
+        let (body, ending) = match line.strip_suffix('\n') {
+            Some(body) => (body.strip_suffix('\r').unwrap_or(body), "\n"),
+            None => (line, ""),
+        };
+        if !body.trim().is_empty() {
+            out.write_all(rewriter.line(body).as_bytes()).map_err(|e| io_error(out_path, e))?;
+        }
+        out.write_all(ending.as_bytes()).map_err(|e| io_error(out_path, e))?;
+    }
+    out.flush().map_err(|e| io_error(out_path, e))?;
+    Ok(rewriter.changes)
+}
+
+/// Rewrite into a temporary file beside out_path and rename it over out_path only once
+/// the whole transcript has been read, so a failure leaves out_path as it was.
+fn rewrite(path: &Path, out_path: &Path, rules: &RewriteRules) -> PyResult<Changes> {
+    let mut input = BufReader::new(File::open(path).map_err(|e| io_error(path, e))?);
+    let name = out_path.file_name().and_then(|n| n.to_str()).unwrap_or("rewritten");
+    let tmp = out_path.with_file_name(format!(".{name}.{}.tmp", std::process::id()));
+    let file = File::create(&tmp).map_err(|e| io_error(&tmp, e))?;
+    let written = rewrite_lines(&mut input, &mut BufWriter::new(file), path, out_path, rules)
+        .and_then(|changes| {
+            std::fs::rename(&tmp, out_path).map_err(|e| io_error(out_path, e))?;
+            Ok(changes)
+        });
+    if written.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    written
+}
+
+/// Write a sanitized copy of a JSONL transcript to out_path, replacing it.
+///
+/// The transcript is streamed a line at a time and each entry keeps its structure
+/// (fields, nesting, one entry per line), so the copy still loads in Claude Code
+/// tooling; only string values change, as rules (a RewriteRules, by default
+/// redacting secrets and stripping attachments) say. Object keys come out in sorted
+/// order, and lines that aren't JSON get the text rules only. Returns a dict of
+/// lines, redactions, paths and attachments counting what changed. out_path is only
+/// replaced once the whole transcript has been rewritten. Raises ValueError if
+/// out_path is path, IOError if either can't be opened and ParseError if a line isn't
+/// UTF-8.
+#[pyfunction]
+#[pyo3(signature = (path, out_path, rules=None))]
+pub fn rewrite_transcript<'py>(
+    py: Python<'py>,
+    path: &str,
+    out_path: &str,
+    rules: Option<RewriteRules>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let (path, out_path) = (Path::new(path), Path::new(out_path));
+    let same = match (path.canonicalize(), out_path.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    };
+    if same {
+        return Err(PyValueError::new_err("out_path must differ from path"));
+    }
+    let rules = rules.unwrap_or_default();
+    let changes = py.detach(|| rewrite(path, out_path, &rules))?;
+    let dict = PyDict::new(py);
+    dict.set_item("lines", changes.lines)?;
+    dict.set_item("redactions", changes.redactions)?;
+    dict.set_item("paths", changes.paths)?;
+    dict.set_item("attachments", changes.attachments)?;
+    Ok(dict)
+}
@@ -0,0 +1,231 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use rayon::prelude::*;
+
+use crate::sessions::sessionize_impl;
+use crate::timeutil::parse_iso_ts;
+use crate::usage::Usage;
+
+/// Recursively collect every *.jsonl file under root, sorted by path.
+pub(crate) fn find_jsonl_files(root: &Path) -> Vec<PathBuf> {
+    let mut out = Vec::new();
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(e) => e,
+            Err(_) => continue,
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let file_type = match entry.file_type() {
+                Ok(t) => t,
+                Err(_) => continue,
+            };
+            if file_type.is_dir() {
+                stack.push(path);
+            } else if path.extension().is_some_and(|e| e == "jsonl") {
+                out.push(path);
+            }
+        }
+    }
+    out.sort();
+    out
+}
+
+/// Turn a Claude Code project directory name back into a filesystem path.
+///
+/// "-Users-me-src-app" -> "/Users/me/src/app". Lossy for paths containing dashes,
+/// which is why the cwd recorded in the transcript is preferred when present.
+pub(crate) fn decode_project_dir(name: &str) -> String {
+    if name.starts_with('-') {
+        name.replace('-', "/")
+    } else {
+        name.to_string()
+    }
+}
+
+#[derive(Default)]
+struct ProjectStats {
+    project_path: Option<String>,
+    sessions: u64,
+    active_seconds: f64,
+    usage: Usage,
+    cost_usd: f64,
+    tools: BTreeMap<String, u64>,
+    files: BTreeSet<String>,
+}
+
+impl ProjectStats {
+    fn merge(&mut self, other: ProjectStats) {
+        if self.project_path.is_none() {
+            self.project_path = other.project_path;
+        }
+        self.sessions += other.sessions;
+        self.active_seconds += other.active_seconds;
+        self.usage.add(&other.usage);
+        self.cost_usd += other.cost_usd;
+        for (tool, n) in other.tools {
+            *self.tools.entry(tool).or_insert(0) += n;
+        }
+        self.files.extend(other.files);
+    }
+}
+
+fn scan_file(path: &Path, gap_seconds: f64) -> ProjectStats {
+    let mut stats = ProjectStats::default();
+    let file = match File::open(path) {
+        Ok(f) => f,
+        Err(_) => return stats,
+    };
+
+    let is_subagent = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .is_some_and(|s| s.starts_with("agent-"));
+    if !is_subagent {
+        stats.sessions = 1;
+    }
+
+    let mut timestamps = Vec::new();
+    // Claude Code repeats the same usage on every line of a multi-block message.
+    let mut seen_messages = HashSet::new();
+
+    for line in BufReader::new(file).lines() {
+        let line = match line {
+            Ok(l) => l,
+            Err(_) => break,
+        };
+        let entry: serde_json::Value = match serde_json::from_str(line.trim()) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+
+        if stats.project_path.is_none() {
+            if let Some(cwd) = entry.get("cwd").and_then(|v| v.as_str()) {
+                stats.project_path = Some(cwd.to_string());
+            }
+        }
+        if let Some(ts) = entry
+            .get("timestamp")
+            .and_then(|v| v.as_str())
+            .and_then(parse_iso_ts)
+        {
+            timestamps.push(ts);
+        }
+
+        if entry.get("type").and_then(|v| v.as_str()) != Some("assistant") {
+            continue;
+        }
+        let msg = &entry["message"];
+        let msg_id = msg.get("id").and_then(|v| v.as_str()).unwrap_or("");
+        if msg_id.is_empty() || seen_messages.insert(msg_id.to_string()) {
+            if let Some(usage) = Usage::from_message_or_estimate(msg) {
+                let model = msg.get("model").and_then(|v| v.as_str()).unwrap_or("");
+                stats.cost_usd += usage.cost_usd(model);
+                stats.usage.add(&usage);
+            }
+        }
+
+        let blocks = match msg.get("content").and_then(|v| v.as_array()) {
+            Some(arr) => arr,
+            None => continue,
+        };
+        for block in blocks {
+            if block.get("type").and_then(|v| v.as_str()) != Some("tool_use") {
+                continue;
+            }
+            let name = block.get("name").and_then(|v| v.as_str()).unwrap_or("");
+            *stats.tools.entry(name.to_string()).or_insert(0) += 1;
+            let input = &block["input"];
+            if let Some(fp) = input
+                .get("file_path")
+                .or_else(|| input.get("notebook_path"))
+                .and_then(|v| v.as_str())
+            {
+                stats.files.insert(fp.to_string());
+            }
+        }
+    }
+
+    stats.active_seconds = sessionize_impl(&timestamps, gap_seconds)
+        .iter()
+        .map(|s| s.duration())
+        .sum();
+    stats
+}
+
+fn project_key(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .ok()
+        .and_then(|rel| rel.components().next())
+        .filter(|_| path.parent() != Some(root))
+        .and_then(|c| c.as_os_str().to_str())
+        .unwrap_or("")
+        .to_string()
+}
+
+fn project_rollup_impl(root: &Path, gap_seconds: f64) -> BTreeMap<String, ProjectStats> {
+    let files = find_jsonl_files(root);
+    let per_file: Vec<(String, ProjectStats)> = files
+        .par_iter()
+        .map(|path| (project_key(root, path), scan_file(path, gap_seconds)))
+        .collect();
+
+    let mut projects: BTreeMap<String, ProjectStats> = BTreeMap::new();
+    for (key, stats) in per_file {
+        projects.entry(key).or_default().merge(stats);
+    }
+
+    let mut decoded = BTreeMap::new();
+    for (key, mut stats) in projects {
+        let path = stats
+            .project_path
+            .take()
+            .unwrap_or_else(|| decode_project_dir(&key));
+        match decoded.get_mut(&path) {
+            Some(existing) => ProjectStats::merge(existing, stats),
+            None => {
+                decoded.insert(path, stats);
+            }
+        }
+    }
+    decoded
+}
+
+/// Aggregate every transcript under root by project.
+///
+/// Returns {project_path: {sessions, active_seconds, input_tokens, output_tokens,
+/// cache_creation_tokens, cache_read_tokens, total_tokens, cost_usd, tools, files_touched}}.
+/// Files are scanned in parallel with the GIL released.
+#[pyfunction]
+#[pyo3(signature = (root, gap_seconds=300.0))]
+pub fn project_rollup<'py>(
+    py: Python<'py>,
+    root: &str,
+    gap_seconds: f64,
+) -> PyResult<Bound<'py, PyDict>> {
+    let root = PathBuf::from(root);
+    let projects = py.detach(|| project_rollup_impl(&root, gap_seconds));
+
+    let out = PyDict::new(py);
+    for (path, stats) in projects {
+        let dict = PyDict::new(py);
+        dict.set_item("sessions", stats.sessions)?;
+        dict.set_item("active_seconds", stats.active_seconds)?;
+        dict.set_item("input_tokens", stats.usage.input_tokens)?;
+        dict.set_item("output_tokens", stats.usage.output_tokens)?;
+        dict.set_item("cache_creation_tokens", stats.usage.cache_creation_tokens)?;
+        dict.set_item("cache_read_tokens", stats.usage.cache_read_tokens)?;
+        dict.set_item("total_tokens", stats.usage.total())?;
+        dict.set_item("cost_usd", stats.cost_usd)?;
+        dict.set_item("tools", stats.tools)?;
+        dict.set_item("files_touched", stats.files.into_iter().collect::<Vec<_>>())?;
+        out.set_item(path, dict)?;
+    }
+    Ok(out)
+}
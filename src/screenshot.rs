@@ -0,0 +1,253 @@
+use std::path::{Path, PathBuf};
+
+use pyo3::prelude::*;
+use serde_json::json;
+
+use crate::collector::{now, record, Record, Sampler};
+use crate::errors::io_error;
+use crate::frontmost::front_window;
+use crate::privacy::global_filter;
+
+/// An 8-bit RGB image, row-major with no padding.
+struct Frame {
+    width: usize,
+    height: usize,
+    rgb: Vec<u8>,
+}
+
+impl Frame {
+    fn pixel(&self, x: usize, y: usize) -> &[u8] {
+        let at = (y * self.width + x) * 3;
+        &self.rgb[at..at + 3]
+    }
+
+    /// Box-filter resize: each output pixel averages the source pixels it covers.
+    fn resize(&self, width: usize, height: usize) -> Frame {
+        // Source pixels covering output index i of n, always at least one.
+        let span = |i: usize, n: usize, len: usize| {
+            let start = (i * len / n).min(len - 1);
+            start..((i + 1) * len / n).clamp(start + 1, len)
+        };
+        let mut rgb = Vec::with_capacity(width * height * 3);
+        for y in 0..height {
+            let rows = span(y, height, self.height);
+            for x in 0..width {
+                let cols = span(x, width, self.width);
+                let mut sum = [0usize; 3];
+                for sy in rows.clone() {
+                    for sx in cols.clone() {
+                        for (s, v) in sum.iter_mut().zip(self.pixel(sx, sy)) {
+                            *s += usize::from(*v);
+                        }
+                    }
+                }
+                let n = rows.len() * cols.len();
+                rgb.extend(sum.map(|s| (s / n) as u8));
+            }
+        }
+        Frame { width, height, rgb }
+    }
+
+    /// At most max_width pixels wide, keeping the aspect ratio.
+    fn downscale(self, max_width: usize) -> Frame {
+        if self.width <= max_width {
+            return self;
+        }
+        let height = (self.height * max_width / self.width).max(1);
+        self.resize(max_width, height)
+    }
+
+    /// 64-bit difference hash: the frame shrunk to 9x8 grey cells, one bit per cell
+    /// saying whether it is brighter than its right-hand neighbour. Unlike a byte hash
+    /// it barely moves for a blinking cursor or a clock tick, but flips many bits when
+    /// the layout of the screen changes.
+    fn dhash(&self) -> u64 {
+        let cells = self.resize(9, 8);
+        // ITU-R BT.601 luma, scaled by 1000.
+        let luma = |x, y| {
+            let p = cells.pixel(x, y);
+            299 * u32::from(p[0]) + 587 * u32::from(p[1]) + 114 * u32::from(p[2])
+        };
+        let mut hash = 0u64;
+        for y in 0..8 {
+            for x in 0..8 {
+                hash = (hash << 1) | u64::from(luma(x, y) > luma(x + 1, y));
+            }
+        }
+        hash
+    }
+}
+
+fn decode_png(data: &[u8]) -> Result<Frame, String> {
+    let mut decoder = png::Decoder::new(std::io::Cursor::new(data));
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().map_err(|e| format!("png: {e}"))?;
+    let mut buf = vec![0; reader.output_buffer_size().ok_or("png: image too large")?];
+    let info = reader.next_frame(&mut buf).map_err(|e| format!("png: {e}"))?;
+    let channels = info.color_type.samples();
+    let (width, height) = (info.width as usize, info.height as usize);
+    if width == 0 || height == 0 {
+        return Err("png: empty image".to_string());
+    }
+    let mut rgb = Vec::with_capacity(width * height * 3);
+    for row in buf.chunks(info.line_size).take(height) {
+        for px in row[..width * channels].chunks(channels) {
+            match px.len() {
+                1 | 2 => rgb.extend([px[0]; 3]),
+                _ => rgb.extend(&px[..3]),
+            }
+        }
+    }
+    Ok(Frame { width, height, rgb })
+}
+
+fn encode_png(frame: &Frame, path: &Path) -> Result<(), String> {
+    let file = std::fs::File::create(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    let mut encoder =
+        png::Encoder::new(std::io::BufWriter::new(file), frame.width as u32, frame.height as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(|e| format!("png: {e}"))?;
+    writer.write_image_data(&frame.rgb).map_err(|e| format!("png: {e}"))?;
+    writer.finish().map_err(|e| format!("png: {e}"))
+}
+
+/// The main display via CGDisplayCreateImage. Without Screen Recording permission
+/// macOS returns only the desktop wallpaper and menu bar.
+#[cfg(target_os = "macos")]
+fn capture() -> Result<Frame, String> {
+    use core_graphics::display::CGDisplay;
+
+    let image = CGDisplay::main().image().ok_or("CGDisplayCreateImage failed")?;
+    if image.bits_per_pixel() != 32 {
+        return Err(format!("unsupported {}-bit display image", image.bits_per_pixel()));
+    }
+    let (width, height, stride) = (image.width(), image.height(), image.bytes_per_row());
+    let data = image.data();
+    let bytes = data.bytes();
+    let mut rgb = Vec::with_capacity(width * height * 3);
+    for row in bytes.chunks(stride).take(height) {
+        // Display images are BGRA (32-bit little-endian, alpha first).
+        for px in row[..width * 4].chunks(4) {
+            rgb.extend([px[2], px[1], px[0]]);
+        }
+    }
+    Ok(Frame { width, height, rgb })
+}
+
+/// The whole screen as PNG from grim under Wayland or ImageMagick's import under X11.
+#[cfg(not(target_os = "macos"))]
+fn capture() -> Result<Frame, String> {
+    let (program, args): (&str, &[&str]) = if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        ("grim", &["-t", "png", "-"])
+    } else {
+        ("import", &["-silent", "-window", "root", "png:-"])
+    };
+    let output = std::process::Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("{program}: {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{program} failed ({}): {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    decode_png(&output.stdout)
+}
+
+/// Settings for the Collector's "screenshot" source.
+///
+/// Frames are downscaled to at most max_width pixels wide and saved as PNG under
+/// directory, but only when their perceptual hash differs from the last saved
+/// frame's in more than threshold of its 64 bits, so an unchanged screen costs
+/// nothing on disk.
+#[pyclass(frozen, from_py_object)]
+#[derive(Clone)]
+pub struct ScreenshotOptions {
+    directory: PathBuf,
+    threshold: u32,
+    max_width: usize,
+}
+
+#[pymethods]
+impl ScreenshotOptions {
+    #[new]
+    #[pyo3(signature = (directory, *, threshold=6, max_width=1280))]
+    fn new(directory: PathBuf, threshold: u32, max_width: usize) -> PyResult<Self> {
+        if threshold >= 64 {
+            return Err(pyo3::exceptions::PyValueError::new_err("threshold must be below 64"));
+        }
+        if max_width == 0 {
+            return Err(pyo3::exceptions::PyValueError::new_err("max_width must be positive"));
+        }
+        Ok(ScreenshotOptions { directory, threshold, max_width })
+    }
+}
+
+/// Captures the screen each poll and saves the frames that changed, with the app
+/// that was frontmost. Nothing is captured while the privacy filter denies that app,
+/// since the frame would already be on disk by the time the record was dropped.
+pub(crate) struct ScreenshotSampler {
+    options: ScreenshotOptions,
+    last_hash: Option<u64>,
+}
+
+impl ScreenshotSampler {
+    pub(crate) fn new(options: ScreenshotOptions) -> Self {
+        ScreenshotSampler { options, last_hash: None }
+    }
+}
+
+impl Sampler for ScreenshotSampler {
+    fn name(&self) -> &'static str {
+        "screenshot"
+    }
+
+    fn sample(&mut self) -> Result<Vec<Record>, String> {
+        let ts = now();
+        let app = front_window().map(|w| w.app);
+        if app.as_deref().is_some_and(|app| global_filter().is_some_and(|f| !f.allows_app(app))) {
+            return Ok(Vec::new());
+        }
+        let frame = capture()?.downscale(self.options.max_width);
+        let hash = frame.dhash();
+        let distance = self.last_hash.map(|last| (last ^ hash).count_ones());
+        if distance.is_some_and(|d| d <= self.options.threshold) {
+            return Ok(Vec::new());
+        }
+        let dir = &self.options.directory;
+        std::fs::create_dir_all(dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+        let path = dir.join(format!("{}.png", (ts * 1000.0) as i64));
+        encode_png(&frame, &path)?;
+        self.last_hash = Some(hash);
+        Ok(vec![record(
+            self.name(),
+            json!({
+                "timestamp": ts,
+                "app": app,
+                "path": path.to_string_lossy(),
+                "width": frame.width,
+                "height": frame.height,
+                "phash": format!("{hash:016x}"),
+                "distance": distance,
+            }),
+        )])
+    }
+}
+
+/// The 64-bit perceptual (difference) hash of a PNG file, as 16 hex digits.
+///
+/// Two images look alike when few bits differ: compare with
+/// bin(int(a, 16) ^ int(b, 16)).count("1"). This is the hash the "screenshot"
+/// Collector source uses to skip unchanged frames. Raises IOError if the file can't
+/// be read and ValueError if it isn't a PNG.
+#[pyfunction]
+pub fn perceptual_hash(py: Python<'_>, path: PathBuf) -> PyResult<String> {
+    py.detach(|| {
+        let data = std::fs::read(&path).map_err(|e| io_error(&path, e))?;
+        let frame = decode_png(&data).map_err(pyo3::exceptions::PyValueError::new_err)?;
+        Ok(format!("{:016x}", frame.dhash()))
+    })
+}
@@ -0,0 +1,82 @@
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+
+pub(crate) struct Session {
+    pub start: f64,
+    pub end: f64,
+    pub event_count: usize,
+}
+
+impl Session {
+    pub fn duration(&self) -> f64 {
+        self.end - self.start
+    }
+}
+
+/// Read the timestamp of an event passed in from Python.
+///
+/// Accepts either an event dict (as returned by the parse_* functions) or a bare number.
+pub(crate) fn event_timestamp(obj: &Bound<'_, PyAny>) -> PyResult<f64> {
+    if let Ok(dict) = obj.cast::<PyDict>() {
+        return match dict.get_item("timestamp")? {
+            Some(v) => v.extract(),
+            None => Err(pyo3::exceptions::PyKeyError::new_err("timestamp")),
+        };
+    }
+    obj.extract()
+}
+
+pub(crate) fn collect_timestamps(events: &Bound<'_, PyAny>) -> PyResult<Vec<f64>> {
+    let mut out = Vec::new();
+    for item in events.try_iter()? {
+        out.push(event_timestamp(&item?)?);
+    }
+    Ok(out)
+}
+
+pub(crate) fn sessionize_impl(timestamps: &[f64], gap_seconds: f64) -> Vec<Session> {
+    let mut sorted: Vec<f64> = timestamps.iter().copied().filter(|t| t.is_finite()).collect();
+    sorted.sort_by(f64::total_cmp);
+
+    let mut sessions: Vec<Session> = Vec::new();
+    for ts in sorted {
+        match sessions.last_mut() {
+            Some(cur) if ts - cur.end <= gap_seconds => {
+                cur.end = ts;
+                cur.event_count += 1;
+            }
+            _ => sessions.push(Session {
+                start: ts,
+                end: ts,
+                event_count: 1,
+            }),
+        }
+    }
+    sessions
+}
+
+/// Split an event stream into activity sessions separated by idle gaps.
+///
+/// A new session starts whenever two consecutive events are more than gap_seconds apart.
+/// Returns a list of dicts with start, end, duration and event_count.
+#[pyfunction]
+#[pyo3(signature = (events, gap_seconds=300.0))]
+pub fn sessionize<'py>(
+    py: Python<'py>,
+    events: &Bound<'py, PyAny>,
+    gap_seconds: f64,
+) -> PyResult<Bound<'py, PyList>> {
+    let timestamps = collect_timestamps(events)?;
+    let sessions = py.detach(|| sessionize_impl(&timestamps, gap_seconds));
+
+    let py_list = PyList::empty(py);
+    for s in &sessions {
+        let dict = PyDict::new(py);
+        dict.set_item("start", s.start)?;
+        dict.set_item("end", s.end)?;
+        dict.set_item("duration", s.duration())?;
+        dict.set_item("event_count", s.event_count)?;
+        py_list.append(dict)?;
+    }
+    Ok(py_list)
+}
@@ -0,0 +1,293 @@
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::OnceLock;
+
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+use regex::Regex;
+use serde_json::json;
+
+use crate::collector::{record, records_to_py, Record};
+use crate::errors::io_error;
+
+/// zsh stores non-ASCII bytes as this marker followed by the byte XOR 0x20.
+const ZSH_META: u8 = 0x83;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Shell {
+    Zsh,
+    Bash,
+    Fish,
+}
+
+impl Shell {
+    fn name(self) -> &'static str {
+        match self {
+            Shell::Zsh => "zsh",
+            Shell::Bash => "bash",
+            Shell::Fish => "fish",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "zsh" => Some(Shell::Zsh),
+            "bash" => Some(Shell::Bash),
+            "fish" => Some(Shell::Fish),
+            _ => None,
+        }
+    }
+
+    /// Guess the format from the file name, then from the first non-empty line.
+    fn detect(path: &Path, data: &[u8]) -> Shell {
+        let name = path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+        if name.contains("zsh") {
+            return Shell::Zsh;
+        }
+        if name.contains("fish") {
+            return Shell::Fish;
+        }
+        if name.contains("bash") {
+            return Shell::Bash;
+        }
+        let first = data.split(|b| *b == b'\n').find(|l| !l.is_empty()).unwrap_or_default();
+        if first.starts_with(b": ") {
+            Shell::Zsh
+        } else if first.starts_with(b"- cmd:") {
+            Shell::Fish
+        } else {
+            Shell::Bash
+        }
+    }
+}
+
+fn zsh_extended_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?s)^: *(\d+):(\d+);(.*)$").unwrap())
+}
+
+fn bash_timestamp_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^#(\d{9,})$").unwrap())
+}
+
+/// One history entry.
+#[derive(Default)]
+pub(crate) struct Command {
+    timestamp: Option<f64>,
+    duration: Option<f64>,
+    command: String,
+}
+
+/// Complete lines in data with the offset just past each one's newline.
+fn lines(data: &[u8]) -> impl Iterator<Item = (&[u8], usize)> {
+    let mut start = 0;
+    std::iter::from_fn(move || {
+        let len = data[start..].iter().position(|b| *b == b'\n')?;
+        let line = &data[start..start + len];
+        start += len + 1;
+        Some((line, start))
+    })
+}
+
+fn unmetafy(line: &[u8]) -> String {
+    let mut out = Vec::with_capacity(line.len());
+    let mut bytes = line.iter();
+    while let Some(&b) = bytes.next() {
+        if b == ZSH_META {
+            if let Some(&next) = bytes.next() {
+                out.push(next ^ 0x20);
+            }
+        } else {
+            out.push(b);
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// zsh history, plain or EXTENDED_HISTORY (": <start>:<elapsed>;<command>"). Lines
+/// ending in a backslash continue onto the next line.
+pub(crate) fn parse_zsh(data: &[u8]) -> (Vec<Command>, usize) {
+    let (mut commands, mut consumed) = (Vec::new(), 0);
+    let mut pending: Option<String> = None;
+    for (line, end) in lines(data) {
+        let line = unmetafy(line);
+        let mut text = match pending.take() {
+            Some(prev) => prev + "\n" + &line,
+            None => line,
+        };
+        if text.ends_with('\\') {
+            text.pop();
+            pending = Some(text);
+            continue;
+        }
+        consumed = end;
+        let cmd = match zsh_extended_regex().captures(&text) {
+            Some(c) => Command {
+                timestamp: c[1].parse().ok(),
+                duration: c[2].parse().ok(),
+                command: c[3].to_string(),
+            },
+            None => Command {
+                command: text,
+                ..Default::default()
+            },
+        };
+        if !cmd.command.trim().is_empty() {
+            commands.push(cmd);
+        }
+    }
+    (commands, consumed)
+}
+
+/// bash history. With HISTTIMEFORMAT set, each entry is a "#<epoch>" line followed by
+/// the command, whose continuation lines run up to the next timestamp; otherwise
+/// every line is its own command. A timestamp with no command after it yet is left
+/// for the next read.
+pub(crate) fn parse_bash(data: &[u8]) -> (Vec<Command>, usize) {
+    let mut commands: Vec<Command> = Vec::new();
+    let mut timed: Option<Command> = None;
+    let (mut consumed, mut entry_start) = (0, 0);
+    for (line, end) in lines(data) {
+        let line_start = consumed;
+        consumed = end;
+        let line = String::from_utf8_lossy(line);
+        if let Some(c) = bash_timestamp_regex().captures(&line) {
+            commands.extend(timed.take().filter(|c| !c.command.is_empty()));
+            entry_start = line_start;
+            timed = Some(Command {
+                timestamp: c[1].parse().ok(),
+                ..Default::default()
+            });
+            continue;
+        }
+        match &mut timed {
+            Some(cmd) if cmd.command.is_empty() => cmd.command = line.into_owned(),
+            Some(cmd) => {
+                cmd.command.push('\n');
+                cmd.command.push_str(&line);
+            }
+            None if !line.trim().is_empty() => commands.push(Command {
+                command: line.into_owned(),
+                ..Default::default()
+            }),
+            None => {}
+        }
+    }
+    match timed {
+        Some(cmd) if cmd.command.is_empty() => consumed = entry_start,
+        timed => commands.extend(timed),
+    }
+    (commands, consumed)
+}
+
+/// Undo fish's escaping of backslashes and newlines in history commands.
+fn fish_unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('n')) => {
+                out.push('\n');
+                chars.next();
+            }
+            ('\\', Some('\\')) => {
+                out.push('\\');
+                chars.next();
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// fish history: YAML-like "- cmd: <command>" entries with a "  when: <epoch>" line.
+/// An entry whose when line hasn't been written yet is left for the next read.
+pub(crate) fn parse_fish(data: &[u8]) -> (Vec<Command>, usize) {
+    let mut commands: Vec<Command> = Vec::new();
+    let (mut consumed, mut entry_start) = (0, None);
+    for (line, end) in lines(data) {
+        let line_start = consumed;
+        consumed = end;
+        let line = String::from_utf8_lossy(line);
+        if let Some(cmd) = line.strip_prefix("- cmd: ") {
+            commands.push(Command {
+                command: fish_unescape(cmd),
+                ..Default::default()
+            });
+            entry_start = Some(line_start);
+        } else if let Some(when) = line.trim_start().strip_prefix("when: ") {
+            if let Some(last) = commands.last_mut() {
+                last.timestamp = when.trim().parse().ok();
+            }
+            entry_start = None;
+        }
+    }
+    if let Some(start) = entry_start {
+        commands.pop();
+        consumed = start;
+    }
+    (commands, consumed)
+}
+
+/// Parse a zsh, bash or fish history file into timestamped command events.
+///
+/// Returns (list_of_event_dicts, final_file_offset); pass the offset back as
+/// since_offset to read only commands added since (a file that shrank is re-read
+/// from the start). Each event is {source: "shell", shell, timestamp, duration,
+/// command}; timestamp is epoch seconds, or None when the history doesn't record
+/// one, and duration is only known for zsh EXTENDED_HISTORY. shell is detected from
+/// the file name and contents unless given as "zsh", "bash" or "fish". Multi-line
+/// commands are returned as one event. An entry still being written at the end of
+/// the file (an unfinished zsh continuation, a bash timestamp with no command yet, a
+/// fish command without its when line) is left for the next read.
+#[pyfunction]
+#[pyo3(signature = (path, shell=None, since_offset=0))]
+pub fn parse_shell_history<'py>(
+    py: Python<'py>,
+    path: &str,
+    shell: Option<&str>,
+    since_offset: u64,
+) -> PyResult<(Bound<'py, PyList>, u64)> {
+    let forced = match shell {
+        Some(name) => Some(Shell::from_name(name).ok_or_else(|| {
+            pyo3::exceptions::PyValueError::new_err(format!(
+                "unknown shell {name:?}; expected \"zsh\", \"bash\" or \"fish\""
+            ))
+        })?),
+        None => None,
+    };
+    let (events, offset) = py
+        .detach(|| -> std::io::Result<(Vec<Record>, u64)> {
+            let mut file = File::open(path)?;
+            let size = file.metadata()?.len();
+            let start = if since_offset > size { 0 } else { since_offset };
+            file.seek(SeekFrom::Start(start))?;
+            let mut data = Vec::new();
+            file.read_to_end(&mut data)?;
+            let shell = forced.unwrap_or_else(|| Shell::detect(Path::new(path), &data));
+            let (commands, consumed) = match shell {
+                Shell::Zsh => parse_zsh(&data),
+                Shell::Bash => parse_bash(&data),
+                Shell::Fish => parse_fish(&data),
+            };
+            let events = commands
+                .into_iter()
+                .map(|c| {
+                    record(
+                        "shell",
+                        json!({
+                            "shell": shell.name(),
+                            "timestamp": c.timestamp,
+                            "duration": c.duration,
+                            "command": c.command,
+                        }),
+                    )
+                })
+                .collect();
+            Ok((events, start + consumed as u64))
+        })
+        .map_err(|e| io_error(Path::new(path), e))?;
+    Ok((records_to_py(py, events)?, offset))
+}
@@ -0,0 +1,46 @@
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use pyo3::exceptions::PyValueError;
+use pyo3::PyResult;
+
+/// Seconds close() and leaving a `with` block wait for background threads.
+pub(crate) const CLOSE_TIMEOUT: f64 = 5.0;
+
+/// How often join_within checks whether the threads have exited.
+const POLL: Duration = Duration::from_millis(10);
+
+/// The instant timeout seconds from now, negative counting as 0. None for no timeout,
+/// or for one too far off to represent (such as inf), which waits as long. Raises
+/// ValueError for NaN.
+pub(crate) fn deadline(timeout: Option<f64>) -> PyResult<Option<Instant>> {
+    match timeout {
+        Some(t) if t.is_nan() => Err(PyValueError::new_err("timeout must not be NaN")),
+        Some(t) => Ok(Duration::try_from_secs_f64(t.max(0.0))
+            .ok()
+            .and_then(|d| Instant::now().checked_add(d))),
+        None => Ok(None),
+    }
+}
+
+/// Wait until deadline (None: forever) for threads that have been told to stop.
+///
+/// Returns the threads still running at the deadline, so the owner can keep them
+/// and report itself as running until a later close() or stop() reaps them.
+pub(crate) fn join_within(
+    threads: Vec<JoinHandle<()>>,
+    deadline: Option<Instant>,
+) -> Vec<JoinHandle<()>> {
+    let mut pending = threads;
+    loop {
+        let (done, rest): (Vec<_>, Vec<_>) = pending.into_iter().partition(|t| t.is_finished());
+        for thread in done {
+            let _ = thread.join();
+        }
+        if rest.is_empty() || deadline.is_some_and(|d| Instant::now() >= d) {
+            return rest;
+        }
+        pending = rest;
+        std::thread::sleep(POLL);
+    }
+}
@@ -0,0 +1,152 @@
+use std::collections::BTreeMap;
+
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+
+/// FNV-1a, used instead of std's hasher so fingerprints are stable across builds.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for b in bytes {
+        hash ^= *b as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+fn normalized_words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+/// 64-bit simhash over word bigrams (unigrams for one-word texts).
+pub(crate) fn simhash(text: &str) -> u64 {
+    let words = normalized_words(text);
+    let shingles: Vec<String> = if words.len() < 2 {
+        words
+    } else {
+        words.windows(2).map(|w| w.join(" ")).collect()
+    };
+    if shingles.is_empty() {
+        return 0;
+    }
+
+    let mut weights = [0i32; 64];
+    for shingle in &shingles {
+        let h = fnv1a(shingle.as_bytes());
+        for (bit, weight) in weights.iter_mut().enumerate() {
+            if h & (1 << bit) != 0 {
+                *weight += 1;
+            } else {
+                *weight -= 1;
+            }
+        }
+    }
+    weights
+        .iter()
+        .enumerate()
+        .filter(|(_, w)| **w > 0)
+        .fold(0u64, |acc, (bit, _)| acc | (1 << bit))
+}
+
+fn find(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+/// Group fingerprints whose Hamming distance is at most max_distance.
+fn cluster(fingerprints: &[u64], max_distance: u32) -> Vec<Vec<usize>> {
+    let mut parent: Vec<usize> = (0..fingerprints.len()).collect();
+    for i in 0..fingerprints.len() {
+        for j in (i + 1)..fingerprints.len() {
+            if (fingerprints[i] ^ fingerprints[j]).count_ones() <= max_distance {
+                let (a, b) = (find(&mut parent, i), find(&mut parent, j));
+                if a != b {
+                    parent[b] = a;
+                }
+            }
+        }
+    }
+    let mut groups: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for i in 0..fingerprints.len() {
+        let root = find(&mut parent, i);
+        groups.entry(root).or_default().push(i);
+    }
+    groups.into_values().collect()
+}
+
+/// Compute the 64-bit simhash fingerprint of a prompt.
+#[pyfunction]
+pub fn prompt_fingerprint(text: &str) -> u64 {
+    simhash(text)
+}
+
+/// Find clusters of near-identical user prompts.
+///
+/// events are parse_transcript dicts; only message_type == "user" events are considered.
+/// Returns clusters of at least min_size prompts, largest first, each a dict with
+/// count, representative, sessions and timestamps.
+#[pyfunction]
+#[pyo3(signature = (events, max_distance=3, min_size=2))]
+pub fn find_duplicate_prompts<'py>(
+    py: Python<'py>,
+    events: &Bound<'py, PyAny>,
+    max_distance: u32,
+    min_size: usize,
+) -> PyResult<Bound<'py, PyList>> {
+    let mut prompts: Vec<(String, String, f64)> = Vec::new();
+    for item in events.try_iter()? {
+        let item = item?;
+        let dict = item.cast::<PyDict>()?;
+        let is_user = match dict.get_item("message_type")? {
+            Some(v) => v.extract::<String>()? == "user",
+            None => false,
+        };
+        if !is_user {
+            continue;
+        }
+        let text: String = match dict.get_item("content_preview")? {
+            Some(v) => v.extract()?,
+            None => continue,
+        };
+        let session_id: String = match dict.get_item("session_id")? {
+            Some(v) => v.extract()?,
+            None => String::new(),
+        };
+        let ts: f64 = match dict.get_item("timestamp")? {
+            Some(v) => v.extract()?,
+            None => 0.0,
+        };
+        prompts.push((text, session_id, ts));
+    }
+
+    let clusters = py.detach(|| {
+        let fingerprints: Vec<u64> = prompts.iter().map(|(t, _, _)| simhash(t)).collect();
+        let mut clusters: Vec<Vec<usize>> = cluster(&fingerprints, max_distance)
+            .into_iter()
+            .filter(|c| c.len() >= min_size.max(1))
+            .collect();
+        clusters.sort_by_key(|c| std::cmp::Reverse(c.len()));
+        clusters
+    });
+
+    let py_list = PyList::empty(py);
+    for members in clusters {
+        let mut sessions: Vec<&str> = members.iter().map(|&i| prompts[i].1.as_str()).collect();
+        sessions.sort_unstable();
+        sessions.dedup();
+        let timestamps: Vec<f64> = members.iter().map(|&i| prompts[i].2).collect();
+
+        let dict = PyDict::new(py);
+        dict.set_item("count", members.len())?;
+        dict.set_item("representative", &prompts[members[0]].0)?;
+        dict.set_item("sessions", sessions)?;
+        dict.set_item("timestamps", timestamps)?;
+        py_list.append(dict)?;
+    }
+    Ok(py_list)
+}
@@ -0,0 +1,369 @@
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::time::Duration;
+
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+
+use crate::anonymize::Anonymizer;
+use crate::collector::{json_to_py, Record};
+use crate::compress::Compressor;
+use crate::crypto::{EncryptedWriter, EncryptionKey};
+
+/// Longest a blocked sampler waits before checking whether it should stop.
+const BLOCK_SLICE: Duration = Duration::from_millis(100);
+
+/// What a full queue does with one more event.
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Overflow {
+    /// Discard the new event.
+    DropNewest,
+    /// Discard the oldest queued event to make room.
+    DropOldest,
+    /// Hold up the sampler until there is room or the collector stops.
+    Block,
+}
+
+impl Overflow {
+    pub(crate) fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "drop-newest" => Ok(Overflow::DropNewest),
+            "drop-oldest" => Ok(Overflow::DropOldest),
+            "block" => Ok(Overflow::Block),
+            _ => Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "overflow must be 'drop-newest', 'drop-oldest' or 'block', not {name:?}"
+            ))),
+        }
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Overflow::DropNewest => "drop-newest",
+            Overflow::DropOldest => "drop-oldest",
+            Overflow::Block => "block",
+        }
+    }
+}
+
+/// The Collector's bounded event queue, filled by samplers and emptied by drain().
+pub(crate) struct EventQueue {
+    items: Mutex<VecDeque<Record>>,
+    /// Signalled when events arrive.
+    ready: Condvar,
+    /// Signalled when events are taken.
+    room: Condvar,
+    pub(crate) capacity: usize,
+    pub(crate) overflow: Overflow,
+    pub(crate) dropped: AtomicU64,
+    /// Deepest the queue has been.
+    pub(crate) peak: AtomicU64,
+}
+
+impl EventQueue {
+    pub(crate) fn new(capacity: usize, overflow: Overflow) -> Self {
+        EventQueue {
+            items: Mutex::new(VecDeque::new()),
+            ready: Condvar::new(),
+            room: Condvar::new(),
+            capacity,
+            overflow,
+            dropped: AtomicU64::new(0),
+            peak: AtomicU64::new(0),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, VecDeque<Record>> {
+        self.items.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Queue a batch under the overflow policy. Blocking gives up once stop is set,
+    /// dropping what's left. Under drop-oldest the displaced events count as dropped
+    /// and the new ones as sent.
+    fn push(&self, events: Vec<Record>, stop: &AtomicBool) -> Delivery {
+        let mut out = Delivery::default();
+        let mut items = self.lock();
+        for ev in events {
+            while items.len() >= self.capacity
+                && self.overflow == Overflow::Block
+                && !stop.load(Ordering::Relaxed)
+            {
+                items = match self.room.wait_timeout(items, BLOCK_SLICE) {
+                    Ok((guard, _)) => guard,
+                    Err(e) => e.into_inner().0,
+                };
+            }
+            if items.len() >= self.capacity {
+                out.dropped += 1;
+                if self.overflow != Overflow::DropOldest {
+                    continue;
+                }
+                items.pop_front();
+            }
+            items.push_back(ev);
+            out.sent += 1;
+            self.peak.fetch_max(items.len() as u64, Ordering::Relaxed);
+            self.ready.notify_all();
+        }
+        self.dropped.fetch_add(out.dropped, Ordering::Relaxed);
+        out
+    }
+
+    /// Take up to max events, oldest first.
+    pub(crate) fn take(&self, max: usize) -> Vec<Record> {
+        let mut items = self.lock();
+        let n = max.min(items.len());
+        let batch: Vec<Record> = items.drain(..n).collect();
+        if !batch.is_empty() {
+            self.room.notify_all();
+        }
+        batch
+    }
+
+    /// Take the oldest event, waiting up to wait for one.
+    pub(crate) fn take_one(&self, wait: Duration) -> Option<Record> {
+        let mut items = self.lock();
+        if items.is_empty() && !wait.is_zero() {
+            items = match self.ready.wait_timeout(items, wait) {
+                Ok((guard, _)) => guard,
+                Err(e) => e.into_inner().0,
+            };
+        }
+        let item = items.pop_front();
+        if item.is_some() {
+            self.room.notify_all();
+        }
+        item
+    }
+}
+
+/// Where a Collector delivers the events its samplers produce.
+pub(crate) enum Sink {
+    /// The collector's bounded queue, emptied by drain().
+    Queue(Arc<EventQueue>),
+    /// A Python callable invoked with each batch as a list of dicts.
+    Callback(Py<PyAny>),
+    /// Newline-delimited JSON appended to a file, optionally compressed and encrypted.
+    Ndjson {
+        out: Mutex<NdjsonFile>,
+        compressor: Option<Compressor>,
+        anonymizer: Option<Anonymizer>,
+    },
+    /// Newline-delimited JSON written to a Unix stream socket, reconnecting as needed.
+    Unix {
+        path: PathBuf,
+        stream: Mutex<Option<UnixStream>>,
+    },
+}
+
+pub(crate) enum NdjsonFile {
+    Plain(File),
+    /// Each batch becomes one encrypted frame.
+    Encrypted(EncryptedWriter),
+}
+
+/// Export options that only apply to the "ndjson:PATH" sink.
+#[derive(Default)]
+pub(crate) struct NdjsonOptions {
+    pub encryption: Option<EncryptionKey>,
+    pub compressor: Option<Compressor>,
+    pub anonymizer: Option<Anonymizer>,
+}
+
+impl NdjsonOptions {
+    fn is_empty(&self) -> bool {
+        self.encryption.is_none() && self.compressor.is_none() && self.anonymizer.is_none()
+    }
+}
+
+/// Outcome of delivering one batch.
+#[derive(Default)]
+pub(crate) struct Delivery {
+    pub sent: u64,
+    pub dropped: u64,
+    pub error: Option<String>,
+}
+
+impl Delivery {
+    fn failed(count: usize, error: String) -> Self {
+        Delivery {
+            sent: 0,
+            dropped: count as u64,
+            error: Some(error),
+        }
+    }
+}
+
+fn ndjson(events: &[Record]) -> String {
+    let mut out = String::new();
+    for ev in events {
+        out.push_str(&serde_json::Value::Object(ev.clone()).to_string());
+        out.push('\n');
+    }
+    out
+}
+
+impl Sink {
+    /// Build a sink from the Collector's sink argument.
+    ///
+    /// None selects the internal queue; a callable becomes a callback sink; strings
+    /// "ndjson:PATH" and "unix:PATH" select the file and socket sinks. options are
+    /// only valid with "ndjson:PATH".
+    pub(crate) fn from_py(
+        obj: Option<&Bound<'_, PyAny>>,
+        queue: Arc<EventQueue>,
+        options: NdjsonOptions,
+    ) -> PyResult<Sink> {
+        let obj = match obj {
+            Some(o) if !o.is_none() => Some(o),
+            _ => None,
+        };
+        let spec: Option<String> = match obj {
+            Some(o) if !o.is_callable() => Some(o.extract()?),
+            _ => None,
+        };
+        let ndjson_path = spec.as_deref().and_then(|s| s.strip_prefix("ndjson:"));
+        if ndjson_path.is_none() && !options.is_empty() {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "encryption, compression and anonymize are only supported with an \"ndjson:PATH\" \
+                 sink",
+            ));
+        }
+        let obj = match obj {
+            Some(o) => o,
+            None => return Ok(Sink::Queue(queue)),
+        };
+        if obj.is_callable() {
+            return Ok(Sink::Callback(obj.clone().unbind()));
+        }
+        if let Some(path) = ndjson_path {
+            let out = match &options.encryption {
+                Some(key) => NdjsonFile::Encrypted(EncryptedWriter::open(Path::new(path), key)?),
+                None => NdjsonFile::Plain(
+                    OpenOptions::new()
+                        .create(true)
+                        .append(true)
+                        .open(path)
+                        .map_err(pyo3::exceptions::PyIOError::new_err)?,
+                ),
+            };
+            return Ok(Sink::Ndjson {
+                out: Mutex::new(out),
+                compressor: options.compressor,
+                anonymizer: options.anonymizer,
+            });
+        }
+        let spec = spec.unwrap_or_default();
+        match spec.split_once(':') {
+            Some(("unix", path)) => Ok(Sink::Unix {
+                path: PathBuf::from(path),
+                stream: Mutex::new(None),
+            }),
+            _ => Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "unsupported sink {spec:?}; expected a callable, \"ndjson:PATH\" or \"unix:PATH\""
+            ))),
+        }
+    }
+
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            Sink::Queue(_) => "queue",
+            Sink::Callback(_) => "callback",
+            Sink::Ndjson { out, .. } => match *out.lock().unwrap_or_else(|e| e.into_inner()) {
+                NdjsonFile::Plain(_) => "ndjson",
+                NdjsonFile::Encrypted(_) => "encrypted_ndjson",
+            },
+            Sink::Unix { .. } => "unix",
+        }
+    }
+
+    /// Deliver a batch. stop cuts short a queue blocked by the "block" policy.
+    pub(crate) fn deliver(&self, events: Vec<Record>, stop: &AtomicBool) -> Delivery {
+        if events.is_empty() {
+            return Delivery::default();
+        }
+        let count = events.len();
+        match self {
+            Sink::Queue(queue) => queue.push(events, stop),
+            Sink::Callback(callback) => {
+                let result = Python::attach(|py| -> PyResult<()> {
+                    let batch = PyList::empty(py);
+                    for ev in &events {
+                        batch.append(json_to_py(py, &serde_json::Value::Object(ev.clone()))?)?;
+                    }
+                    callback.call1(py, (batch,))?;
+                    Ok(())
+                });
+                match result {
+                    Ok(()) => Delivery {
+                        sent: count as u64,
+                        ..Default::default()
+                    },
+                    Err(e) => Delivery::failed(count, format!("callback: {e}")),
+                }
+            }
+            Sink::Ndjson { out, compressor, anonymizer } => {
+                let mut events = events;
+                if let Some(anonymizer) = anonymizer {
+                    events.iter_mut().for_each(|ev| anonymizer.apply_record(ev));
+                }
+                let text = ndjson(&events).into_bytes();
+                let block = match compressor {
+                    Some(c) => match c.compress(&text) {
+                        Ok(block) => block,
+                        Err(e) => return Delivery::failed(count, e),
+                    },
+                    None => text,
+                };
+                let mut out = out.lock().unwrap_or_else(|e| e.into_inner());
+                let written = match &mut *out {
+                    NdjsonFile::Plain(file) => {
+                        file.write_all(&block).and_then(|_| file.flush()).map_err(|e| e.to_string())
+                    }
+                    NdjsonFile::Encrypted(writer) => writer.write_frame(&block),
+                };
+                match written {
+                    Ok(()) => Delivery {
+                        sent: count as u64,
+                        ..Default::default()
+                    },
+                    Err(e) => Delivery::failed(count, format!("ndjson: {e}")),
+                }
+            }
+            Sink::Unix { path, stream } => {
+                let mut stream = stream.lock().unwrap_or_else(|e| e.into_inner());
+                if stream.is_none() {
+                    match UnixStream::connect(path) {
+                        Ok(s) => *stream = Some(s),
+                        Err(e) => {
+                            return Delivery::failed(count, format!("{}: {e}", path.display()))
+                        }
+                    }
+                }
+                let written = stream
+                    .as_mut()
+                    .map(|s| s.write_all(ndjson(&events).as_bytes()))
+                    .unwrap_or(Ok(()));
+                match written {
+                    Ok(()) => Delivery {
+                        sent: count as u64,
+                        ..Default::default()
+                    },
+                    Err(e) => {
+                        // Drop the broken connection; the next batch reconnects.
+                        *stream = None;
+                        Delivery::failed(count, format!("{}: {e}", path.display()))
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+use regex::Regex;
+use serde_json::{json, Map, Value};
+
+use crate::collector::{home_path, record, records_to_py, Record, CONTENT_PREVIEW_LEN};
+use crate::errors::io_error;
+
+/// Slack data directories: the direct download, then the Mac App Store sandbox.
+const SLACK_DIRS: [&str; 2] = [
+    "Library/Application Support/Slack",
+    "Library/Containers/com.tinyspeck.slackmacgap/Data/Library/Application Support/Slack",
+];
+
+/// The start of a JSON object that looks like a Slack message.
+fn message_start_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r#"\{"(?:type|subtype|client_msg_id|user|ts|text|channel|blocks)":"#).unwrap()
+    })
+}
+
+/// Whether dir is a LevelDB store: Local Storage's "leveldb" or IndexedDB's "*.leveldb".
+fn is_leveldb_dir(dir: &Path) -> bool {
+    dir.file_name().is_some_and(|n| n == "leveldb")
+        || dir.extension().is_some_and(|e| e == "leveldb")
+}
+
+/// LevelDB table and log files under root.
+fn leveldb_files(root: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(root) else { return };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(kind) = entry.file_type() else { continue };
+        if kind.is_dir() {
+            leveldb_files(&path, out);
+        } else if is_leveldb_dir(root) && path.extension().is_some_and(|e| e == "log" || e == "ldb")
+        {
+            out.push(path);
+        }
+    }
+}
+
+fn text_field<'a>(message: &'a Map<String, Value>, key: &str) -> Option<&'a str> {
+    message.get(key).and_then(Value::as_str).filter(|s| !s.is_empty())
+}
+
+/// Convert a decoded JSON object into an event if it has a Slack ts and text.
+fn parse_message(message: &Map<String, Value>) -> Option<Record> {
+    let ts = text_field(message, "ts")?;
+    let timestamp: f64 = ts.parse().ok()?;
+    let text = text_field(message, "text")?;
+    let profile = message.get("user_profile").and_then(Value::as_object);
+    let sender = profile
+        .and_then(|p| text_field(p, "display_name").or_else(|| text_field(p, "real_name")))
+        .or_else(|| text_field(message, "username"))
+        .or_else(|| text_field(message, "user"))
+        .or_else(|| text_field(message, "bot_id"));
+    Some(record(
+        "slack",
+        json!({
+            "timestamp": timestamp,
+            "ts": ts,
+            "channel": text_field(message, "channel"),
+            "sender": sender,
+            "user_id": text_field(message, "user"),
+            "text": crate::truncate_str(text, CONTENT_PREVIEW_LEN),
+            "thread_ts": text_field(message, "thread_ts"),
+        }),
+    ))
+}
+
+/// Every Slack message object embedded as plain JSON in a cache file. Values that
+/// LevelDB stored compressed, or that Chromium serialized in its binary IndexedDB
+/// format, aren't recoverable this way and are skipped.
+fn scan_file(path: &Path, since: f64, seen: &mut HashSet<(String, String)>) -> Vec<Record> {
+    let Ok(bytes) = std::fs::read(path) else { return Vec::new() };
+    let text = String::from_utf8_lossy(&bytes);
+    let mut events = Vec::new();
+    let mut resume = 0;
+    for found in message_start_re().find_iter(&text) {
+        if found.start() < resume {
+            continue;
+        }
+        let mut objects = serde_json::Deserializer::from_str(&text[found.start()..])
+            .into_iter::<Map<String, Value>>();
+        let Some(Ok(object)) = objects.next() else { continue };
+        resume = found.start() + objects.byte_offset();
+        let Some(event) = parse_message(&object) else { continue };
+        let key = (
+            event["channel"].as_str().unwrap_or_default().to_string(),
+            event["ts"].as_str().unwrap_or_default().to_string(),
+        );
+        if event["timestamp"].as_f64().unwrap_or_default() > since && seen.insert(key) {
+            events.push(event);
+        }
+    }
+    events
+}
+
+/// Best-effort recovery of recent messages from the Slack desktop app's local caches.
+///
+/// Scans the LevelDB files under the app's Local Storage and IndexedDB for message
+/// objects stored as plain JSON and returns {source: "slack", timestamp, ts, channel,
+/// sender, user_id, text, thread_ts}, oldest first and deduplicated by channel and
+/// ts. sender is the profile's display or real name when cached, else the user ID.
+/// Coverage depends on what Slack happens to keep uncompressed, so treat the result
+/// as a sample, not a full history. path defaults to the Slack data directory (direct
+/// download or App Store); only messages after since (epoch seconds) are returned.
+#[pyfunction]
+#[pyo3(signature = (path=None, since=None))]
+pub fn slack_messages<'py>(
+    py: Python<'py>,
+    path: Option<PathBuf>,
+    since: Option<f64>,
+) -> PyResult<Bound<'py, PyList>> {
+    let root = path.unwrap_or_else(|| {
+        SLACK_DIRS
+            .iter()
+            .map(|d| home_path(d))
+            .find(|p| p.is_dir())
+            .unwrap_or_else(|| home_path(SLACK_DIRS[0]))
+    });
+    if !root.is_dir() {
+        let missing = std::io::Error::new(std::io::ErrorKind::NotFound, "no such directory");
+        return Err(io_error(&root, missing));
+    }
+    let since = since.unwrap_or(f64::NEG_INFINITY);
+    let events = py.detach(|| {
+        let mut files = Vec::new();
+        leveldb_files(&root, &mut files);
+        files.sort();
+        let mut seen = HashSet::new();
+        let mut events: Vec<Record> =
+            files.iter().flat_map(|f| scan_file(f, since, &mut seen)).collect();
+        events.sort_by(|a, b| {
+            let ts = |r: &Record| r["timestamp"].as_f64().unwrap_or_default();
+            ts(a).total_cmp(&ts(b))
+        });
+        events
+    });
+    records_to_py(py, events)
+}
@@ -0,0 +1,239 @@
+use std::fs::DirBuilder;
+use std::os::unix::fs::DirBuilderExt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyTuple;
+use rusqlite::backup::{Backup, StepResult};
+use rusqlite::{Connection, OpenFlags};
+
+use crate::errors::io_error;
+
+/// Wait used by the readers that take snapshots for a busy or locked source.
+pub(crate) const DEFAULT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Times a file copy is retried when the source changes while it is copied.
+const COPY_ATTEMPTS: u32 = 5;
+const RETRY_PAUSE: Duration = Duration::from_millis(50);
+
+static SNAPSHOTS: AtomicU64 = AtomicU64::new(0);
+
+/// How a snapshot was taken.
+#[derive(Clone, Copy)]
+pub(crate) enum Method {
+    /// SQLite's online backup API, a consistent copy read through the source's locks.
+    Backup,
+    /// A file copy of the database and its -wal and -shm files, for when the source
+    /// can't be opened (an exclusive lock, or a read-only directory without -shm).
+    Copy,
+}
+
+impl Method {
+    fn name(self) -> &'static str {
+        match self {
+            Method::Backup => "backup",
+            Method::Copy => "copy",
+        }
+    }
+}
+
+/// A private, consistent copy of a SQLite database that other processes keep open
+/// and write to, removed when dropped.
+pub(crate) struct Snapshot {
+    dir: PathBuf,
+    path: PathBuf,
+    method: Method,
+}
+
+impl Snapshot {
+    /// Copy src into a new private temporary directory, through the backup API if
+    /// possible and as files otherwise, waiting up to timeout for a busy source.
+    pub(crate) fn take(src: &Path, timeout: Duration) -> std::io::Result<Snapshot> {
+        let dir = std::env::temp_dir().join(format!(
+            "snoopy-snapshot-{}-{}",
+            std::process::id(),
+            SNAPSHOTS.fetch_add(1, Ordering::Relaxed)
+        ));
+        DirBuilder::new().mode(0o700).create(&dir)?;
+        let path = dir.join("snapshot.db");
+        // From here on dropping the snapshot cleans up after a failure.
+        let mut snapshot = Snapshot { dir, path, method: Method::Backup };
+        if let Err(e) = backup(src, &snapshot.path, timeout) {
+            tracing::debug!(path = %src.display(), "backup failed, copying files: {e}");
+            snapshot.method = Method::Copy;
+            for side in ["", "-wal", "-shm", "-journal"] {
+                let _ = std::fs::remove_file(with_suffix(&snapshot.path, side));
+            }
+            stable_copy(src, &snapshot.path)?;
+        }
+        Ok(snapshot)
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub(crate) fn open(&self) -> rusqlite::Result<Connection> {
+        Connection::open(&self.path)
+    }
+}
+
+impl Drop for Snapshot {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    PathBuf::from(format!("{}{suffix}", path.display()))
+}
+
+/// Copy src to dest page by page in one read transaction, so the copy includes
+/// what is still in the WAL and never sees half a write.
+fn backup(src: &Path, dest: &Path, timeout: Duration) -> rusqlite::Result<()> {
+    let source = Connection::open_with_flags(
+        src,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )?;
+    // SQLite takes the busy timeout as milliseconds in an int.
+    source.busy_timeout(timeout.min(Duration::from_millis(i32::MAX as u64)))?;
+    let mut copy = Connection::open(dest)?;
+    let backup = Backup::new(&source, &mut copy)?;
+    let deadline = Instant::now().checked_add(timeout);
+    loop {
+        match backup.step(-1)? {
+            StepResult::Done => return Ok(()),
+            StepResult::More => {}
+            StepResult::Busy | StepResult::Locked
+                if deadline.is_none_or(|d| Instant::now() < d) =>
+            {
+                std::thread::sleep(RETRY_PAUSE);
+            }
+            _ => {
+                return Err(rusqlite::Error::SqliteFailure(
+                    rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_BUSY),
+                    Some("source stayed locked".to_string()),
+                ))
+            }
+        }
+    }
+}
+
+/// Size and modification time of the database and its WAL, to tell whether a copy
+/// raced a write.
+fn stamp(src: &Path) -> Vec<Option<(u64, SystemTime)>> {
+    ["", "-wal"]
+        .iter()
+        .map(|suffix| {
+            let meta = std::fs::metadata(with_suffix(src, suffix)).ok()?;
+            Some((meta.len(), meta.modified().ok()?))
+        })
+        .collect()
+}
+
+/// Copy the database with its -wal and -shm files, again if the source changed
+/// meanwhile; after COPY_ATTEMPTS the last copy is kept, as good as the files were.
+fn stable_copy(src: &Path, dest: &Path) -> std::io::Result<()> {
+    for attempt in 1..=COPY_ATTEMPTS {
+        let before = stamp(src);
+        std::fs::copy(src, dest)?;
+        for suffix in ["-wal", "-shm"] {
+            let side = with_suffix(src, suffix);
+            if side.exists() {
+                std::fs::copy(&side, with_suffix(dest, suffix))?;
+            }
+        }
+        if stamp(src) == before || attempt == COPY_ATTEMPTS {
+            break;
+        }
+        std::thread::sleep(RETRY_PAUSE);
+    }
+    Ok(())
+}
+
+/// A private copy of a SQLite database, taken by safe_snapshot().
+///
+/// path is the copy to open; it and its directory are removed by close(), on
+/// leaving a with block, or when the handle is garbage collected.
+#[pyclass(frozen)]
+pub struct DatabaseSnapshot {
+    source: String,
+    path: String,
+    method: &'static str,
+    snapshot: Mutex<Option<Snapshot>>,
+}
+
+#[pymethods]
+impl DatabaseSnapshot {
+    /// The database the snapshot was taken from.
+    #[getter]
+    fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// The snapshot's database file.
+    #[getter]
+    fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// "backup" if SQLite's backup API made the copy, "copy" if the files were
+    /// copied because the source couldn't be opened.
+    #[getter]
+    fn method(&self) -> &str {
+        self.method
+    }
+
+    #[getter]
+    fn closed(&self) -> bool {
+        self.snapshot.lock().unwrap().is_none()
+    }
+
+    /// Remove the copy. Further calls do nothing.
+    fn close(&self) {
+        self.snapshot.lock().unwrap().take();
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    #[pyo3(signature = (*_args))]
+    fn __exit__(&self, _args: &Bound<'_, PyTuple>) -> bool {
+        self.close();
+        false
+    }
+}
+
+/// Take a consistent private copy of a SQLite database that another app has open.
+///
+/// Messages' chat.db, browser History files and knowledgeC.db are written while
+/// they're read, often with uncheckpointed pages in the WAL, and reading them in
+/// place fails with "database is locked". The copy is made with SQLite's online
+/// backup API, which reads the source (WAL included) in one transaction, waiting
+/// up to timeout seconds if it's busy; if the source can't be opened that way, the
+/// database and its -wal and -shm files are copied instead, again if they change
+/// mid-copy. The copy lives in a new directory only the user can read. Returns a
+/// DatabaseSnapshot to open with sqlite3 and close() when done, or use in a with
+/// block. Raises ValueError if timeout is negative, infinite or too large to wait
+/// on, and IOError if path can't be read.
+#[pyfunction]
+#[pyo3(signature = (path, timeout=5.0))]
+pub fn safe_snapshot(py: Python<'_>, path: &str, timeout: f64) -> PyResult<DatabaseSnapshot> {
+    let timeout = Duration::try_from_secs_f64(timeout)
+        .map_err(|_| PyValueError::new_err(format!("timeout must be a finite number >= 0, got {timeout}")))?;
+    let src = Path::new(path);
+    let snapshot = py
+        .detach(|| Snapshot::take(src, timeout))
+        .map_err(|e| io_error(src, e))?;
+    Ok(DatabaseSnapshot {
+        source: path.to_string(),
+        path: snapshot.path().to_string_lossy().into_owned(),
+        method: snapshot.method.name(),
+        snapshot: Mutex::new(Some(snapshot)),
+    })
+}
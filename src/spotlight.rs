@@ -0,0 +1,116 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::UNIX_EPOCH;
+
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+use serde_json::json;
+
+use crate::collector::{now, record, records_to_py, Record};
+use crate::timeutil::epoch_to_datetime;
+
+/// Default lookback when since isn't given.
+const DEFAULT_WINDOW_SECS: f64 = 24.0 * 3600.0;
+/// Restrict the default query to user documents rather than caches and databases.
+const DEFAULT_QUERY: &str = r#"kMDItemContentTypeTree == "public.content""#;
+
+fn epoch_secs(time: std::io::Result<std::time::SystemTime>) -> Option<f64> {
+    time.ok()?.duration_since(UNIX_EPOCH).ok().map(|d| d.as_secs_f64())
+}
+
+/// Quote a value for a Spotlight query string.
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn build_query(query: Option<&str>, name: Option<&str>, since: f64) -> PyResult<String> {
+    let since = epoch_to_datetime(since)
+        .ok_or_else(|| pyo3::exceptions::PyValueError::new_err(format!("invalid time {since}")))?;
+    let mut clauses = vec![format!("({})", query.unwrap_or(DEFAULT_QUERY))];
+    if let Some(name) = name {
+        clauses.push(format!("kMDItemFSName == {}cd", quote(&format!("*{name}*"))));
+    }
+    clauses.push(format!(
+        "kMDItemFSContentChangeDate >= $time.iso({})",
+        since.format("%Y-%m-%dT%H:%M:%SZ")
+    ));
+    Ok(clauses.join(" && "))
+}
+
+/// Stat a match and classify it as created or modified within the window.
+fn document(path: &Path, since: f64) -> Option<Record> {
+    let meta = std::fs::metadata(path).ok()?;
+    let modified = epoch_secs(meta.modified())?;
+    let created = epoch_secs(meta.created());
+    let kind = if created.is_some_and(|c| c >= since) { "created" } else { "modified" };
+    Some(record(
+        "spotlight",
+        json!({
+            "kind": kind,
+            "timestamp": modified,
+            "path": path.to_string_lossy(),
+            "name": path.file_name().map(|n| n.to_string_lossy()),
+            "extension": path.extension().map(|e| e.to_string_lossy().to_lowercase()),
+            "created": created,
+            "size": meta.len(),
+            "is_dir": meta.is_dir(),
+        }),
+    ))
+}
+
+/// Recently created or modified documents according to the Spotlight index.
+///
+/// Runs `mdfind` for items whose content changed since since (epoch seconds, default
+/// the last 24 hours) and returns {source: "spotlight", kind, timestamp, path, name,
+/// extension, created, size, is_dir}, oldest first by last modification (timestamp).
+/// kind is "created" when the file was born inside the window, else "modified".
+/// query is a raw Spotlight predicate (default: any user content, e.g.
+/// 'kMDItemContentType == "com.apple.keynote.key"'); name matches a case-insensitive
+/// substring of the file name; onlyin limits the search to a directory. limit keeps
+/// the most recent matches. Raises RuntimeError if mdfind fails.
+#[pyfunction]
+#[pyo3(signature = (query=None, name=None, since=None, onlyin=None, limit=None))]
+pub fn recent_documents<'py>(
+    py: Python<'py>,
+    query: Option<&str>,
+    name: Option<&str>,
+    since: Option<f64>,
+    onlyin: Option<PathBuf>,
+    limit: Option<usize>,
+) -> PyResult<Bound<'py, PyList>> {
+    let since = since.unwrap_or_else(|| now() - DEFAULT_WINDOW_SECS);
+    let mut cmd = Command::new("mdfind");
+    cmd.arg("-0");
+    if let Some(dir) = &onlyin {
+        cmd.arg("-onlyin").arg(dir);
+    }
+    cmd.arg(build_query(query, name, since)?);
+
+    let events = py.detach(|| -> PyResult<Vec<Record>> {
+        let output = cmd
+            .output()
+            .map_err(|e| pyo3::exceptions::PyIOError::new_err(format!("mdfind: {e}")))?;
+        if !output.status.success() {
+            return Err(pyo3::exceptions::PyRuntimeError::new_err(format!(
+                "mdfind failed ({}): {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        let mut events: Vec<Record> = output
+            .stdout
+            .split(|&b| b == 0)
+            .filter(|p| !p.is_empty())
+            .filter_map(|p| document(Path::new(&*String::from_utf8_lossy(p)), since))
+            .collect();
+        events.sort_by(|a, b| {
+            let ts = |r: &Record| r["timestamp"].as_f64().unwrap_or_default();
+            ts(a).total_cmp(&ts(b))
+        });
+        if let Some(limit) = limit {
+            events.drain(..events.len().saturating_sub(limit));
+        }
+        Ok(events)
+    })?;
+    records_to_py(py, events)
+}
@@ -0,0 +1,346 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+
+use chrono::{DateTime, Datelike, Local, NaiveDateTime, TimeZone};
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+use regex::Regex;
+use serde_json::{json, Value};
+
+use crate::collector::{now, record, records_to_py, Record};
+use crate::errors::io_error;
+use crate::timeutil::parse_iso_ts;
+
+const PREDICATE: &str = r#"process == "sshd" OR process == "sshd-session" OR process == "ssh""#;
+const AUTH_LOGS: [&str; 2] = ["/var/log/auth.log", "/var/log/secure"];
+
+/// A plain `log show` line: "2024-03-01 10:00:00.123456-0800  0x1a2b  Default  0x0
+/// 612  0  sshd: Accepted ...". The pid is the fifth column.
+fn log_line_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(concat!(
+            r"^(\d{4}-\d\d-\d\d \d\d:\d\d:\d\d(?:\.\d+)?[+-]\d{4})\s+\S+\s+\S+\s+\S+\s+(\d+)",
+            r"\s+\S+\s+([\w.-]+)(?:\[\d+\])?:\s*(.*)$",
+        ))
+        .unwrap()
+    })
+}
+
+/// A syslog line: "Mar  1 09:15:02 host sshd[1234]: ..." or, with rsyslog's
+/// high-precision format, "2024-03-01T09:15:02.123456+00:00 host sshd[1234]: ...".
+fn syslog_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(concat!(
+            r"^(\w{3} +\d+ \d\d:\d\d:\d\d|\d{4}-\d\d-\d\dT\S+)\s+\S+\s+",
+            r"([\w.-]+)(?:\[(\d+)\])?:\s*(.*)$",
+        ))
+        .unwrap()
+    })
+}
+
+/// "Accepted publickey for alice from 10.0.0.5 port 52314 ssh2: ED25519 SHA256:abc".
+fn accepted_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(concat!(
+            r"^Accepted (\S+) for (\S+) from (\S+) port (\d+)",
+            r"(?: ssh2)?(?:: (\S+) (SHA256:\S+|MD5:\S+))?",
+        ))
+        .unwrap()
+    })
+}
+
+/// "Failed password for [invalid user ]bob from 10.0.0.9 port 4022 ssh2" and
+/// "Invalid user bob from 10.0.0.9 port 4022".
+fn failed_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(concat!(
+            r"^(?:Failed (\S+) for (?:invalid user )?|Invalid user )",
+            r"(\S*) from (\S+) port (\d+)",
+        ))
+        .unwrap()
+    })
+}
+
+/// "Disconnected from user alice 10.0.0.5 port 52314".
+fn disconnected_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^Disconnected from user (\S+) (\S+) port (\d+)").unwrap())
+}
+
+/// The ssh client's own messages (with `-y`, or LogLevel set for syslog):
+/// "Authenticating to box.example.com:22 as 'alice'", "Server host key: ssh-ed25519
+/// SHA256:abc", "Authenticated to box.example.com ([10.0.0.7]:22) using
+/// \"publickey\"." and "Transferred: sent 3024, received 2920 bytes, in 12.5 seconds".
+fn client_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(concat!(
+            r"Authenticating to (\S+?):(\d+) as '([^']*)'",
+            r#"|Server host key: (\S+) (SHA256:\S+|MD5:\S+)"#,
+            r#"|Authenticated to (\S+) \(\[?([^\]\s]*?)\]?:(\d+)\) using "([^"]+)""#,
+            r"|Transferred: sent \d+, received \d+ bytes, in ([\d.]+) seconds",
+        ))
+        .unwrap()
+    })
+}
+
+/// "ED25519" → "ed25519"; the client says "ssh-ed25519" for the same thing.
+fn key_type(name: &str) -> String {
+    name.trim_start_matches("ssh-").to_lowercase()
+}
+
+/// A syslog timestamp without a year is this year, unless that would put it in the
+/// future.
+fn syslog_time(text: &str) -> Option<f64> {
+    if text.contains('T') {
+        return parse_iso_ts(text);
+    }
+    let this_year = Local::now().year();
+    [this_year, this_year - 1].into_iter().find_map(|year| {
+        let at =
+            NaiveDateTime::parse_from_str(&format!("{year} {text}"), "%Y %b %e %H:%M:%S").ok()?;
+        let ts = Local.from_local_datetime(&at).earliest()?.timestamp() as f64;
+        (ts <= now() + 86_400.0).then_some(ts)
+    })
+}
+
+/// (timestamp, process, pid, message) from an NDJSON `log show` entry, a plain log
+/// line or a syslog line.
+fn log_entry(line: &str) -> Option<(f64, String, Option<i64>, String)> {
+    let line = line.trim();
+    if line.starts_with('{') {
+        let entry: Value = serde_json::from_str(line).ok()?;
+        let ts = parse_iso_ts(entry.get("timestamp")?.as_str()?)?;
+        let image = entry.get("processImagePath").and_then(Value::as_str).unwrap_or_default();
+        let process = image.rsplit('/').next().unwrap_or(image).to_string();
+        let pid = entry.get("processID").and_then(Value::as_i64);
+        return Some((ts, process, pid, entry.get("eventMessage")?.as_str()?.to_string()));
+    }
+    if let Some(caps) = log_line_re().captures(line) {
+        let ts = DateTime::parse_from_str(&caps[1], "%Y-%m-%d %H:%M:%S%.f%z").ok()?;
+        let ts = ts.timestamp_micros() as f64 / 1e6;
+        return Some((ts, caps[3].to_string(), caps[2].parse().ok(), caps[4].to_string()));
+    }
+    let caps = syslog_re().captures(line)?;
+    let pid = caps.get(3).and_then(|m| m.as_str().parse().ok());
+    Some((syslog_time(&caps[1])?, caps[2].to_string(), pid, caps[4].to_string()))
+}
+
+/// An inbound login waiting for its disconnect, or an outbound connection being
+/// set up.
+#[derive(Default, Clone)]
+struct Session {
+    started: Option<f64>,
+    user: Option<String>,
+    host: Option<String>,
+    /// The IP an outbound host name resolved to.
+    address: Option<String>,
+    port: Option<i64>,
+    method: Option<String>,
+    key_type: Option<String>,
+    fingerprint: Option<String>,
+    pid: Option<i64>,
+}
+
+fn session_event(kind: &str, direction: &str, ts: f64, session: &Session) -> Record {
+    let user = session.user.as_deref().unwrap_or("?");
+    let host = session.host.as_deref().unwrap_or("?");
+    let summary = match (kind, direction) {
+        ("login", "inbound") => format!("ssh login by {user} from {host}"),
+        ("login", _) => format!("ssh to {user}@{host}"),
+        ("failed", _) => format!("failed ssh login for {user} from {host}"),
+        (_, "inbound") => format!("ssh session by {user} from {host} ended"),
+        _ => format!("ssh to {host} ended"),
+    };
+    let duration = session.started.filter(|_| kind == "logout").map(|s| ts - s);
+    record(
+        "ssh",
+        json!({
+            "timestamp": ts,
+            "kind": kind,
+            "direction": direction,
+            "user": session.user,
+            "host": session.host,
+            "remote_address": session.address.as_ref().or(session.host.as_ref()),
+            "port": session.port,
+            "method": session.method,
+            "key_type": session.key_type,
+            "fingerprint": session.fingerprint,
+            "pid": session.pid,
+            "duration": duration,
+            "summary": summary,
+        }),
+    )
+}
+
+fn parse_log(text: &str, since: f64) -> Vec<Record> {
+    let mut events = Vec::new();
+    // Inbound sessions by (host, port); outbound ones by client pid.
+    let mut inbound: HashMap<(String, i64), Session> = HashMap::new();
+    let mut outbound: HashMap<Option<i64>, Session> = HashMap::new();
+    let mut push = |event: Record, ts: f64| {
+        if ts > since {
+            events.push(event);
+        }
+    };
+    for (ts, process, pid, message) in text.lines().filter_map(log_entry) {
+        if process == "ssh" {
+            let Some(caps) = client_re().captures(&message) else { continue };
+            let session =
+                outbound.entry(pid).or_insert_with(|| Session { pid, ..Session::default() });
+            if let Some(host) = caps.get(1) {
+                session.host = Some(host.as_str().to_string());
+                session.port = caps[2].parse().ok();
+                session.user = Some(caps[3].to_string());
+            } else if let Some(kind) = caps.get(4) {
+                session.key_type = Some(key_type(kind.as_str()));
+                session.fingerprint = Some(caps[5].to_string());
+            } else if let Some(host) = caps.get(6) {
+                session.host = Some(host.as_str().to_string());
+                session.address = caps.get(7).map(|m| m.as_str().to_string());
+                session.port = caps[8].parse().ok();
+                session.method = Some(caps[9].to_string());
+                session.started = Some(ts);
+                push(session_event("login", "outbound", ts, session), ts);
+            } else if let Some(seconds) = caps.get(10) {
+                let mut session = outbound.remove(&pid).unwrap_or_default();
+                if session.method.is_none() {
+                    continue;
+                }
+                // The client's own count is more exact than our two log timestamps.
+                if let Ok(seconds) = seconds.as_str().parse::<f64>() {
+                    session.started = Some(ts - seconds);
+                }
+                push(session_event("logout", "outbound", ts, &session), ts);
+            }
+            continue;
+        }
+        if !process.starts_with("sshd") {
+            continue;
+        }
+        if let Some(caps) = accepted_re().captures(&message) {
+            let session = Session {
+                started: Some(ts),
+                user: Some(caps[2].to_string()),
+                host: Some(caps[3].to_string()),
+                port: caps[4].parse().ok(),
+                method: Some(caps[1].to_string()),
+                key_type: caps.get(5).map(|m| key_type(m.as_str())),
+                fingerprint: caps.get(6).map(|m| m.as_str().to_string()),
+                pid,
+                ..Session::default()
+            };
+            push(session_event("login", "inbound", ts, &session), ts);
+            inbound.insert((caps[3].to_string(), session.port.unwrap_or(0)), session);
+        } else if let Some(caps) = failed_re().captures(&message) {
+            // "Failed none" is the client asking which methods are allowed.
+            if caps.get(1).is_some_and(|m| m.as_str() == "none") {
+                continue;
+            }
+            let session = Session {
+                user: Some(caps[2].to_string()).filter(|u| !u.is_empty()),
+                host: Some(caps[3].to_string()),
+                port: caps[4].parse().ok(),
+                method: caps.get(1).map(|m| m.as_str().to_string()),
+                pid,
+                ..Session::default()
+            };
+            push(session_event("failed", "inbound", ts, &session), ts);
+        } else if let Some(caps) = disconnected_re().captures(&message) {
+            let port = caps[3].parse().unwrap_or(0);
+            let session = inbound.remove(&(caps[2].to_string(), port)).unwrap_or(Session {
+                user: Some(caps[1].to_string()),
+                host: Some(caps[2].to_string()),
+                port: Some(port),
+                pid,
+                ..Session::default()
+            });
+            push(session_event("logout", "inbound", ts, &session), ts);
+        }
+    }
+    events
+}
+
+fn read_logs(path: Option<PathBuf>, since: Option<f64>) -> PyResult<String> {
+    if let Some(path) = path {
+        return std::fs::read_to_string(&path).map_err(|e| io_error(&path, e));
+    }
+    if cfg!(target_os = "macos") {
+        let mut command = Command::new("log");
+        command.args(["show", "--info", "--style", "ndjson", "--predicate", PREDICATE]);
+        match since {
+            Some(since) => command.args(["--start", &crate::unifiedlog::log_time(since)?]),
+            None => command.args(["--last", "1d"]),
+        };
+        let output = command
+            .output()
+            .map_err(|e| pyo3::exceptions::PyIOError::new_err(format!("log: {e}")))?;
+        if !output.status.success() {
+            return Err(pyo3::exceptions::PyRuntimeError::new_err(format!(
+                "log show failed ({}): {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+    }
+    let path = AUTH_LOGS.iter().find(|p| std::path::Path::new(p).exists()).ok_or_else(|| {
+        pyo3::exceptions::PyIOError::new_err(format!("none of {} exist", AUTH_LOGS.join(", ")))
+    })?;
+    std::fs::read(path)
+        .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+        .map_err(|e| io_error(Path::new(path), e))
+}
+
+/// Parse sshd and ssh log messages into session events, oldest first.
+///
+/// text is `log show` output (--style ndjson or the default style) or syslog lines
+/// from /var/log/auth.log. Events are {source: "ssh", timestamp, kind, direction,
+/// user, host, remote_address, port, method, key_type, fingerprint, pid, duration,
+/// summary}.
+/// Inbound (sshd) events are "login" ("Accepted ..."), "failed" (a rejected
+/// password or key, or an unknown user) and "logout", whose duration pairs it with
+/// its login. Outbound events come from the ssh client's own log lines (`ssh -y`
+/// or a LogLevel that reaches syslog): "login" once authenticated and "logout"
+/// when it reports what it transferred. fingerprint is the user's key for inbound
+/// logins and the server's host key for outbound ones, e.g. "SHA256:...". host is
+/// the client's address or the server's name as typed; remote_address is always an
+/// address, so sessions line up with the Collector's "network" events. Only events
+/// after since (epoch seconds) are returned.
+#[pyfunction]
+#[pyo3(signature = (text, since=None))]
+pub fn parse_ssh_log<'py>(
+    py: Python<'py>,
+    text: &str,
+    since: Option<f64>,
+) -> PyResult<Bound<'py, PyList>> {
+    let since = since.unwrap_or(f64::NEG_INFINITY);
+    let events = py.detach(|| parse_log(text, since));
+    records_to_py(py, events)
+}
+
+/// SSH session events from this machine's logs, parsed with parse_ssh_log.
+///
+/// path reads that log file. Otherwise macOS queries the unified log for sshd and
+/// ssh (since the given epoch seconds, or the last day) and Linux reads
+/// /var/log/auth.log or /var/log/secure. Raises IOError if the log can't be read and
+/// RuntimeError if `log show` fails.
+#[pyfunction]
+#[pyo3(signature = (since=None, path=None))]
+pub fn ssh_events(
+    py: Python<'_>,
+    since: Option<f64>,
+    path: Option<PathBuf>,
+) -> PyResult<Bound<'_, PyList>> {
+    let events = py.detach(|| -> PyResult<Vec<Record>> {
+        let text = read_logs(path, since)?;
+        Ok(parse_log(&text, since.unwrap_or(f64::NEG_INFINITY)))
+    })?;
+    records_to_py(py, events)
+}
@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+use rusqlite::Connection;
+use serde_json::{json, Value};
+
+use crate::collector::{home_path, now, record, records_to_py, Record, Sampler};
+use crate::history::with_snapshot;
+
+const USER_DB: &str = "Library/Application Support/com.apple.TCC/TCC.db";
+/// Holds Full Disk Access and other machine-wide grants; readable only with Full
+/// Disk Access itself.
+pub(crate) const SYSTEM_DB: &str = "/Library/Application Support/com.apple.TCC/TCC.db";
+
+/// Friendly names for the services people usually ask about.
+const SERVICES: [(&str, &str); 8] = [
+    ("kTCCServiceScreenCapture", "screen_recording"),
+    ("kTCCServiceMicrophone", "microphone"),
+    ("kTCCServiceCamera", "camera"),
+    ("kTCCServiceSystemPolicyAllFiles", "full_disk_access"),
+    ("kTCCServiceAccessibility", "accessibility"),
+    ("kTCCServiceListenEvent", "input_monitoring"),
+    ("kTCCServiceAppleEvents", "automation"),
+    ("kTCCServicePhotos", "photos"),
+];
+
+fn service_name(id: &str) -> String {
+    SERVICES.iter().find(|(s, _)| *s == id).map_or_else(
+        || id.strip_prefix("kTCCService").unwrap_or(id).to_lowercase(),
+        |(_, name)| name.to_string(),
+    )
+}
+
+/// auth_value: 0 denied, 1 unknown, 2 allowed, 3 limited (e.g. selected photos).
+fn auth_name(value: i64) -> &'static str {
+    match value {
+        0 => "denied",
+        2 => "allowed",
+        3 => "limited",
+        _ => "unknown",
+    }
+}
+
+fn read_access(conn: &Connection, database: &str) -> rusqlite::Result<Vec<Record>> {
+    // Big Sur replaced the allowed flag with auth_value; map the old 0/1 onto it.
+    let columns: Vec<String> = conn
+        .prepare("SELECT name FROM pragma_table_info('access')")?
+        .query_map([], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+    let has = |name: &str| columns.iter().any(|c| c == name);
+    let auth =
+        if has("auth_value") { "auth_value" } else { "CASE allowed WHEN 1 THEN 2 ELSE 0 END" };
+    let modified = if has("last_modified") { "last_modified" } else { "NULL" };
+    let mut stmt = conn.prepare(&format!(
+        "SELECT service, client, client_type, {auth}, {modified} FROM access
+         ORDER BY service, client"
+    ))?;
+    let rows = stmt.query_map([], |row| {
+        let service: String = row.get(0)?;
+        let auth = auth_name(row.get::<_, Option<i64>>(3)?.unwrap_or(1));
+        let by_path = row.get::<_, Option<i64>>(2)? == Some(1);
+        Ok(record(
+            "tcc",
+            json!({
+                "service": service_name(&service),
+                "service_id": service,
+                "client": row.get::<_, String>(1)?,
+                "client_type": if by_path { "path" } else { "bundle" },
+                "auth": auth,
+                "allowed": matches!(auth, "allowed" | "limited"),
+                "last_modified": row.get::<_, Option<f64>>(4)?,
+                "database": database,
+            }),
+        ))
+    })?;
+    rows.collect()
+}
+
+/// Entries from the given database, or from the per-user one plus the system one
+/// when it is readable.
+fn read_permissions(path: Option<&Path>) -> PyResult<Vec<Record>> {
+    if let Some(path) = path {
+        return with_snapshot(path, |conn| read_access(conn, "user"));
+    }
+    let mut entries = with_snapshot(&home_path(USER_DB), |conn| read_access(conn, "user"))?;
+    // Without Full Disk Access the copy fails; the user database still answers most
+    // questions, so carry on without it.
+    if let Ok(system) = with_snapshot(Path::new(SYSTEM_DB), |conn| read_access(conn, "system")) {
+        entries.extend(system);
+    }
+    Ok(entries)
+}
+
+fn entry_key(entry: &Record) -> String {
+    ["database", "service_id", "client"]
+        .map(|k| entry.get(k).and_then(Value::as_str).unwrap_or_default())
+        .join("|")
+}
+
+/// Emits "granted", "denied" and "removed" events when TCC entries change.
+pub(crate) struct TccSampler {
+    path: Option<PathBuf>,
+    known: Option<HashMap<String, Record>>,
+}
+
+impl TccSampler {
+    pub(crate) fn new(path: Option<PathBuf>) -> Self {
+        TccSampler { path, known: None }
+    }
+}
+
+impl Sampler for TccSampler {
+    fn name(&self) -> &'static str {
+        "tcc"
+    }
+
+    fn sample(&mut self) -> Result<Vec<Record>, String> {
+        let db = self.path.clone().unwrap_or_else(|| home_path(USER_DB));
+        if !db.exists() {
+            return Ok(Vec::new());
+        }
+        let current: HashMap<String, Record> = read_permissions(self.path.as_deref())
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(|e| (entry_key(&e), e))
+            .collect();
+        // The first poll only establishes the existing grants.
+        let Some(known) = self.known.replace(current.clone()) else {
+            return Ok(Vec::new());
+        };
+        let ts = now();
+        let event = |kind: &str, entry: &Record, previous: Option<&Record>| {
+            let mut event = entry.clone();
+            let summary = format!(
+                "{} {kind} {}",
+                entry["client"].as_str().unwrap_or_default(),
+                entry["service"].as_str().unwrap_or_default()
+            );
+            event.insert("kind".to_string(), json!(kind));
+            event.insert("timestamp".to_string(), json!(ts));
+            event.insert("previous_auth".to_string(), json!(previous.map(|p| &p["auth"])));
+            event.insert("summary".to_string(), json!(summary));
+            event
+        };
+        let mut events: Vec<Record> = known
+            .iter()
+            .filter(|(key, _)| !current.contains_key(*key))
+            .map(|(_, entry)| event("removed", entry, Some(entry)))
+            .collect();
+        for (key, entry) in &current {
+            let previous = known.get(key);
+            if previous.is_some_and(|p| p["auth"] == entry["auth"]) {
+                continue;
+            }
+            let kind = if entry["allowed"] == json!(true) { "granted" } else { "denied" };
+            events.push(event(kind, entry, previous));
+        }
+        events.sort_by_key(entry_key);
+        Ok(events)
+    }
+}
+
+/// Privacy permissions from the TCC database: which apps may record the screen,
+/// use the microphone or camera, read every file (Full Disk Access), and so on.
+///
+/// Each entry is {source: "tcc", service, service_id, client, client_type, auth,
+/// allowed, last_modified, database}. service is a short name such as
+/// "screen_recording", "microphone", "camera", "full_disk_access", "accessibility"
+/// or "input_monitoring" (other services keep their kTCCService suffix, lowercased);
+/// service_id is the raw kTCCService identifier. client is a bundle id or, when
+/// client_type is "path", an executable path. auth is "allowed", "denied",
+/// "limited" or "unknown". Without path, the per-user database is read along with
+/// the system one (which holds Full Disk Access) if this process may read it;
+/// database says which an entry came from. Collector(sources=["tcc"]) reports
+/// changes as events. Raises IOError if the database can't be read.
+#[pyfunction]
+#[pyo3(signature = (path=None))]
+pub fn tcc_permissions(py: Python<'_>, path: Option<PathBuf>) -> PyResult<Bound<'_, PyList>> {
+    let entries = py.detach(|| read_permissions(path.as_deref()))?;
+    records_to_py(py, entries)
+}
@@ -0,0 +1,180 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyIterator};
+
+/// A buffered event ordered by (corrected timestamp, source index, arrival order).
+struct Pending {
+    ts: f64,
+    source: usize,
+    seq: u64,
+    event: Py<PyAny>,
+}
+
+impl Pending {
+    fn key(&self) -> (f64, usize, u64) {
+        (self.ts, self.source, self.seq)
+    }
+}
+
+impl PartialEq for Pending {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Pending {}
+
+impl PartialOrd for Pending {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Pending {
+    // Reversed so BinaryHeap pops the earliest event first.
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (a, b) = (self.key(), other.key());
+        b.0.total_cmp(&a.0).then(b.1.cmp(&a.1)).then(b.2.cmp(&a.2))
+    }
+}
+
+struct Source {
+    iter: Option<Py<PyIterator>>,
+    offset: f64,
+    /// Highest corrected timestamp pulled so far; nothing earlier than
+    /// watermark - window can still arrive from this source.
+    watermark: f64,
+}
+
+/// Read an event's timestamp: a number, a datetime, or a dict holding either.
+pub(crate) fn timestamp_of(event: &Bound<'_, PyAny>) -> PyResult<f64> {
+    let value = match event.cast::<PyDict>() {
+        Ok(dict) => match dict.get_item("timestamp")? {
+            Some(v) => v,
+            None => return Err(pyo3::exceptions::PyKeyError::new_err("timestamp")),
+        },
+        Err(_) => event.clone(),
+    };
+    match value.extract::<f64>() {
+        Ok(ts) => Ok(ts),
+        Err(_) => value.call_method0("timestamp")?.extract(),
+    }
+}
+
+/// Iterator merging several event streams into one stream ordered by timestamp.
+#[pyclass]
+pub struct TimelineMerge {
+    sources: Vec<Source>,
+    heap: BinaryHeap<Pending>,
+    window: f64,
+    seq: u64,
+}
+
+impl TimelineMerge {
+    /// Pull the next event from source i into the heap, retiring the source when exhausted.
+    fn pull(&mut self, py: Python<'_>, i: usize) -> PyResult<()> {
+        let next = match &self.sources[i].iter {
+            Some(iter) => iter.bind(py).clone().next(),
+            None => return Ok(()),
+        };
+        let source = &mut self.sources[i];
+        let event = match next {
+            Some(event) => event?,
+            None => {
+                source.iter = None;
+                return Ok(());
+            }
+        };
+        let ts = timestamp_of(&event)? + source.offset;
+        source.watermark = source.watermark.max(ts);
+        self.seq += 1;
+        self.heap.push(Pending {
+            ts,
+            source: i,
+            seq: self.seq,
+            event: event.unbind(),
+        });
+        Ok(())
+    }
+
+    /// A live source that could still produce an event sorting before head, if any.
+    fn blocking_source(&self, head: Option<&Pending>) -> Option<usize> {
+        let live = self.sources.iter().enumerate().filter(|(_, s)| s.iter.is_some());
+        let live = live.map(|(i, s)| (i, s.watermark));
+        match head {
+            None => live.min_by(|a, b| a.1.total_cmp(&b.1)).map(|(i, _)| i),
+            Some(head) => live
+                .filter(|&(i, wm)| {
+                    let bound = head.ts + self.window;
+                    bound > wm || (bound == wm && i < head.source)
+                })
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(i, _)| i),
+        }
+    }
+}
+
+#[pymethods]
+impl TimelineMerge {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<Py<PyAny>>> {
+        loop {
+            match self.blocking_source(self.heap.peek()) {
+                Some(i) => self.pull(py, i)?,
+                None => return Ok(self.heap.pop().map(|p| p.event)),
+            }
+        }
+    }
+}
+
+/// Merge event streams from different sources into one stream ordered by timestamp.
+///
+/// Each iterable yields dicts with a "timestamp" (epoch seconds or datetime) or bare
+/// timestamps, and is consumed lazily. Events with equal timestamps come out in source
+/// order, then in the order their source produced them. window (seconds) tolerates
+/// sources that are only roughly sorted: an event may arrive up to that much earlier than
+/// one already seen from the same source and still be placed correctly. offsets, one per
+/// source, are added to that source's timestamps for ordering to correct clock skew; the
+/// events themselves are yielded unchanged.
+#[pyfunction]
+#[pyo3(signature = (iterables, window=0.0, offsets=None))]
+pub fn merge_timelines(
+    iterables: &Bound<'_, PyAny>,
+    window: f64,
+    offsets: Option<Vec<f64>>,
+) -> PyResult<TimelineMerge> {
+    if window.is_nan() || window < 0.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("window must be non-negative"));
+    }
+    let mut sources = Vec::new();
+    for iterable in iterables.try_iter()? {
+        sources.push(Source {
+            iter: Some(iterable?.try_iter()?.unbind()),
+            offset: 0.0,
+            watermark: f64::NEG_INFINITY,
+        });
+    }
+    if let Some(offsets) = offsets {
+        if offsets.len() != sources.len() {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "expected {} offsets, got {}",
+                sources.len(),
+                offsets.len()
+            )));
+        }
+        for (source, offset) in sources.iter_mut().zip(offsets) {
+            source.offset = offset;
+        }
+    }
+    Ok(TimelineMerge {
+        sources,
+        heap: BinaryHeap::new(),
+        window,
+        seq: 0,
+    })
+}
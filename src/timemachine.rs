@@ -0,0 +1,327 @@
+use std::process::Command;
+use std::sync::OnceLock;
+
+use chrono::DateTime;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use regex::Regex;
+use serde_json::{json, Value};
+
+use crate::collector::{json_to_py, now, record, records_to_py, Record, Sampler};
+use crate::format_bytes;
+use crate::timeutil::parse_iso_ts;
+
+const PREDICATE: &str = r#"subsystem == "com.apple.TimeMachine""#;
+
+/// A plain `log show` line: "2024-03-01 10:00:00.123456-0800  0x1a2b  Default ...".
+fn log_line_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"^(\d{4}-\d\d-\d\d \d\d:\d\d:\d\d(?:\.\d+)?[+-]\d{4})\s+(.*)$").unwrap()
+    })
+}
+
+/// "Starting automatic backup", "Starting manual backup".
+fn start_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"Starting (?:(\w+) )?backup").unwrap())
+}
+
+/// "Backup failed (19: BACKUP_FAILED_DESTINATION_UNAVAILABLE)", "Backup failed with
+/// error: 19", "Backup canceled.".
+fn end_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"Backup (failed|canceled)(?: with error:)?[\s:]*\(?([^)]*?)\)?\.?\s*$").unwrap()
+    })
+}
+
+/// "Copied 62.2 MB of 80 MB, 1357 of 2000 items" (progress) or "Copied 1357 items
+/// (62.2 MB)" (a finished volume).
+fn copied_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(concat!(
+            r"Copied (?:([\d.,]+) ?([KMGT]?B|bytes)\b[^,]*(?:, ([\d,]+)(?: of [\d,]+)? items)?",
+            r"|([\d,]+) items \(([\d.,]+) ?([KMGT]?B|bytes)\))",
+        ))
+        .unwrap()
+    })
+}
+
+/// backupd reports sizes in decimal units.
+fn to_bytes(value: &str, unit: &str) -> Option<u64> {
+    let value: f64 = value.replace(',', "").parse().ok()?;
+    let scale = match unit {
+        "KB" => 1e3,
+        "MB" => 1e6,
+        "GB" => 1e9,
+        "TB" => 1e12,
+        _ => 1.0,
+    };
+    Some((value * scale).round() as u64)
+}
+
+fn count(value: &str) -> Option<u64> {
+    value.replace(',', "").parse().ok()
+}
+
+/// (timestamp, message) from an NDJSON `log show` entry or a plain log line.
+fn log_entry(line: &str) -> Option<(f64, String)> {
+    let line = line.trim();
+    if line.starts_with('{') {
+        let entry: Value = serde_json::from_str(line).ok()?;
+        let ts = parse_iso_ts(entry.get("timestamp")?.as_str()?)?;
+        return Some((ts, entry.get("eventMessage")?.as_str()?.to_string()));
+    }
+    let caps = log_line_re().captures(line)?;
+    let ts = DateTime::parse_from_str(&caps[1], "%Y-%m-%d %H:%M:%S%.f%z").ok()?;
+    Some((ts.timestamp_micros() as f64 / 1e6, caps[2].to_string()))
+}
+
+/// The backup in progress while reading a log.
+#[derive(Default)]
+struct Backup {
+    started: Option<f64>,
+    bytes: Option<u64>,
+    items: Option<u64>,
+}
+
+fn backup_event(
+    kind: &str,
+    timestamp: f64,
+    backup: &Backup,
+    error: Option<&str>,
+    message: Option<&str>,
+) -> Record {
+    let size = backup.bytes.map(format_bytes);
+    let summary = match (kind, error, &size) {
+        (_, Some(error), _) => format!("Time Machine backup {kind}: {error}"),
+        ("completed", None, Some(size)) => format!("Time Machine backup completed: {size}"),
+        _ => format!("Time Machine backup {kind}"),
+    };
+    record(
+        "backup",
+        json!({
+            "timestamp": timestamp,
+            "kind": kind,
+            "bytes": backup.bytes,
+            "items": backup.items,
+            "duration": backup.started.map(|s| timestamp - s),
+            "error": error,
+            "message": message,
+            "summary": summary,
+        }),
+    )
+}
+
+pub(crate) fn parse_log(text: &str, since: f64) -> Vec<Record> {
+    let mut events = Vec::new();
+    let mut backup = Backup::default();
+    for (ts, message) in text.lines().filter_map(log_entry) {
+        if let Some(caps) = copied_re().captures(&message) {
+            if let (Some(value), Some(unit)) = (caps.get(1), caps.get(2)) {
+                backup.bytes = to_bytes(value.as_str(), unit.as_str());
+                backup.items = caps.get(3).and_then(|m| count(m.as_str())).or(backup.items);
+            } else {
+                // Finished volumes add up; a multi-volume backup logs one per volume.
+                let bytes = to_bytes(&caps[5], &caps[6]).unwrap_or(0);
+                backup.bytes = Some(backup.bytes.unwrap_or(0) + bytes);
+                backup.items = Some(backup.items.unwrap_or(0) + count(&caps[4]).unwrap_or(0));
+            }
+            continue;
+        }
+        let event = if let Some(caps) = start_re().captures(&message) {
+            backup = Backup { started: Some(ts), ..Backup::default() };
+            let trigger = caps.get(1).map(|m| m.as_str());
+            let summary = match trigger {
+                Some(trigger) => format!("Time Machine backup started ({trigger})"),
+                None => "Time Machine backup started".to_string(),
+            };
+            Some(record(
+                "backup",
+                json!({
+                    "timestamp": ts,
+                    "kind": "started",
+                    "trigger": trigger,
+                    "bytes": null,
+                    "items": null,
+                    "duration": null,
+                    "error": null,
+                    "message": message,
+                    "summary": summary,
+                }),
+            ))
+        } else if message.contains("Backup completed successfully") {
+            Some(backup_event("completed", ts, &backup, None, Some(&message)))
+        } else if let Some(caps) = end_re().captures(&message) {
+            let error = caps.get(2).map(|m| m.as_str().trim()).filter(|e| !e.is_empty());
+            Some(backup_event(&caps[1], ts, &backup, error, Some(&message)))
+        } else {
+            None
+        };
+        if let Some(event) = event {
+            if ts > since {
+                events.push(event);
+            }
+        }
+    }
+    events
+}
+
+/// `tmutil status` prints an old-style plist: "Running = 1;", "bytes = 1234;" and
+/// so on, with Progress's keys nested one level down.
+fn parse_status(text: &str) -> Record {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| Regex::new(r#"(?m)^\s*"?([\w.]+)"?\s*=\s*"?([^";{]*)"?;"#).unwrap());
+    let mut values = std::collections::HashMap::new();
+    for caps in re.captures_iter(text) {
+        values.insert(caps[1].to_string(), caps[2].trim().to_string());
+    }
+    let number = |key: &str| values.get(key).and_then(|v| v.parse::<f64>().ok());
+    let int = |key: &str| number(key).map(|v| v as u64);
+    let running = values.get("Running").is_some_and(|v| v == "1");
+    record(
+        "backup",
+        json!({
+            "timestamp": now(),
+            "running": running,
+            "phase": values.get("BackupPhase"),
+            "percent": number("Percent").filter(|p| *p >= 0.0).map(|p| p * 100.0),
+            "bytes": int("bytes"),
+            "total_bytes": int("totalBytes"),
+            "files": int("files"),
+            "total_files": int("totalFiles"),
+            "time_remaining": number("TimeRemaining"),
+        }),
+    )
+}
+
+fn tmutil_status() -> Result<Record, String> {
+    let output =
+        Command::new("tmutil").arg("status").output().map_err(|e| format!("tmutil: {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "tmutil failed ({}): {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(parse_status(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Polls `tmutil status`, emitting "started" when a backup begins and "completed"
+/// when it stops, with the bytes and files it had copied.
+#[derive(Default)]
+pub(crate) struct TimeMachineSampler {
+    running: Option<Record>,
+    started: Option<f64>,
+    polled: bool,
+}
+
+impl Sampler for TimeMachineSampler {
+    fn name(&self) -> &'static str {
+        "backup"
+    }
+
+    fn sample(&mut self) -> Result<Vec<Record>, String> {
+        let status = tmutil_status()?;
+        let first = !std::mem::replace(&mut self.polled, true);
+        let ts = now();
+        let running = status["running"] == true;
+        let mut events = Vec::new();
+        match (self.running.take(), running) {
+            (None, true) => {
+                // A backup already under way when we start has no known start.
+                if !first {
+                    self.started = Some(ts);
+                    let phase = status["phase"].clone();
+                    let mut event = record("backup", json!({"timestamp": ts, "kind": "started"}));
+                    event.insert("phase".to_string(), phase);
+                    event.insert("summary".to_string(), json!("Time Machine backup started"));
+                    events.push(event);
+                }
+                self.running = Some(status);
+            }
+            (Some(_), true) => self.running = Some(status),
+            (Some(last), false) => {
+                let backup = Backup {
+                    started: self.started.take(),
+                    bytes: last["bytes"].as_u64(),
+                    items: last["files"].as_u64(),
+                };
+                events.push(backup_event("completed", ts, &backup, None, None));
+            }
+            (None, false) => {}
+        }
+        Ok(events)
+    }
+}
+
+/// Parse Time Machine (backupd) unified-log output into backup events, oldest first.
+///
+/// text is `log show --predicate 'subsystem == "com.apple.TimeMachine"'` output,
+/// either --style ndjson or the default text style. Events are {source: "backup",
+/// timestamp, kind, bytes, items, duration, error, message, summary}: kind is
+/// "started" (with trigger, e.g. "automatic" or "manual"), "completed", "failed" or
+/// "canceled". bytes and items are what the backup had copied by then, from its
+/// "Copied ..." progress lines, and duration is seconds since it started; both are
+/// None when the start wasn't in the log. Only events after since (epoch seconds)
+/// are returned.
+#[pyfunction]
+#[pyo3(signature = (text, since=None))]
+pub fn parse_backupd_log<'py>(
+    py: Python<'py>,
+    text: &str,
+    since: Option<f64>,
+) -> PyResult<Bound<'py, PyList>> {
+    let since = since.unwrap_or(f64::NEG_INFINITY);
+    let events = py.detach(|| parse_log(text, since));
+    records_to_py(py, events)
+}
+
+/// Query the unified log for Time Machine messages (since the given epoch seconds,
+/// or the last day) and parse them with parse_backupd_log.
+///
+/// Raises RuntimeError if `log show` fails and IOError if it can't be run.
+#[pyfunction]
+#[pyo3(signature = (since=None))]
+pub fn time_machine_events(py: Python<'_>, since: Option<f64>) -> PyResult<Bound<'_, PyList>> {
+    let mut command = Command::new("log");
+    command.args(["show", "--style", "ndjson", "--predicate", PREDICATE]);
+    match since {
+        Some(since) => command.args(["--start", &crate::unifiedlog::log_time(since)?]),
+        None => command.args(["--last", "1d"]),
+    };
+    let since = since.unwrap_or(f64::NEG_INFINITY);
+    let events = py.detach(|| -> PyResult<Vec<Record>> {
+        let output = command
+            .output()
+            .map_err(|e| pyo3::exceptions::PyIOError::new_err(format!("log: {e}")))?;
+        if !output.status.success() {
+            return Err(pyo3::exceptions::PyRuntimeError::new_err(format!(
+                "log show failed ({}): {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(parse_log(&String::from_utf8_lossy(&output.stdout), since))
+    })?;
+    records_to_py(py, events)
+}
+
+/// The current `tmutil status`: {running, phase, percent (0-100, None before it is
+/// known), bytes, total_bytes, files, total_files, time_remaining (seconds)}.
+/// Collector(sources=["backup"]) turns changes in running into started and completed
+/// events. Raises RuntimeError if tmutil can't be run or fails.
+#[pyfunction]
+pub fn time_machine_status(py: Python<'_>) -> PyResult<Bound<'_, PyDict>> {
+    let mut status = py.detach(tmutil_status).map_err(pyo3::exceptions::PyRuntimeError::new_err)?;
+    status.remove("source");
+    status.remove("timestamp");
+    let dict = PyDict::new(py);
+    for (key, value) in status {
+        dict.set_item(key, json_to_py(py, &value)?)?;
+    }
+    Ok(dict)
+}
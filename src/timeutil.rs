@@ -0,0 +1,191 @@
+use chrono::{
+    DateTime, Local, LocalResult, NaiveDate, NaiveDateTime, Offset, SecondsFormat, TimeDelta,
+    TimeZone, Utc,
+};
+use chrono_tz::Tz;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rayon::prelude::*;
+
+/// Formats with an explicit UTC offset, tried after RFC 3339.
+const OFFSET_FORMATS: [&str; 5] = [
+    "%Y-%m-%dT%H:%M:%S%.f%#z",
+    "%Y-%m-%d %H:%M:%S%.f%#z",
+    "%Y-%m-%dT%H:%M%#z",
+    "%Y%m%dT%H%M%S%.f%#z",
+    "%Y%m%dT%H%M%#z",
+];
+
+/// Formats without an offset; these are interpreted in the caller's zone, UTC by default.
+const NAIVE_FORMATS: [&str; 5] = [
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M",
+    "%Y%m%dT%H%M%S%.f",
+    "%Y%m%dT%H%M",
+];
+
+const DATE_FORMATS: [&str; 2] = ["%Y-%m-%d", "%Y%m%d"];
+
+fn to_epoch(dt: &NaiveDateTime) -> f64 {
+    let utc = dt.and_utc();
+    utc.timestamp() as f64 + utc.timestamp_subsec_nanos() as f64 / 1e9
+}
+
+/// A time zone to read timestamps without an offset in, or to render timestamps in.
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Zone {
+    /// The system's zone.
+    Local,
+    /// An IANA zone such as "Europe/Paris", or UTC.
+    Named(Tz),
+}
+
+impl Zone {
+    pub(crate) const UTC: Zone = Zone::Named(Tz::UTC);
+
+    /// "local", "UTC" or an IANA zone name. Raises ValueError for anything else.
+    pub(crate) fn parse(name: &str) -> PyResult<Zone> {
+        if name.eq_ignore_ascii_case("local") {
+            return Ok(Zone::Local);
+        }
+        name.parse()
+            .map(Zone::Named)
+            .map_err(|_| PyValueError::new_err(format!("unknown time zone {name:?}")))
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Zone::Local => "local",
+            Zone::Named(tz) => tz.name(),
+        }
+    }
+
+    /// Epoch seconds of a wall-clock time in this zone. A time that occurs twice when
+    /// clocks go back is the first; one skipped when they go forward is read with the
+    /// offset from before the change.
+    fn resolve(self, dt: &NaiveDateTime) -> Option<f64> {
+        fn in_zone<Z: TimeZone>(zone: &Z, dt: &NaiveDateTime) -> Option<f64> {
+            let offset = match zone.offset_from_local_datetime(dt) {
+                LocalResult::Single(o) | LocalResult::Ambiguous(o, _) => o.fix(),
+                LocalResult::None => {
+                    zone.offset_from_local_datetime(&(*dt - TimeDelta::hours(1))).earliest()?.fix()
+                }
+            };
+            Some(to_epoch(&(*dt - offset)))
+        }
+        match self {
+            Zone::Named(Tz::UTC) => Some(to_epoch(dt)),
+            Zone::Named(tz) => in_zone(&tz, dt),
+            Zone::Local => in_zone(&Local, dt),
+        }
+    }
+
+    /// The timestamp as an aware datetime.datetime in this zone (tzinfo is a ZoneInfo
+    /// for named zones), or None for the 0.0 "no timestamp" sentinel.
+    pub(crate) fn datetime<'py>(self, py: Python<'py>, ts: f64) -> PyResult<Bound<'py, PyAny>> {
+        let Some(utc) = epoch_to_datetime(ts) else {
+            return Ok(py.None().into_bound(py));
+        };
+        let dt = match self {
+            Zone::Named(Tz::UTC) => utc.into_pyobject(py)?,
+            Zone::Named(tz) => utc.with_timezone(&tz).into_pyobject(py)?,
+            Zone::Local => utc.with_timezone(&Local).fixed_offset().into_pyobject(py)?,
+        };
+        Ok(dt.into_any())
+    }
+
+    /// The timestamp as ISO 8601 with milliseconds and this zone's offset ("Z" for UTC).
+    pub(crate) fn iso(self, ts: f64) -> Option<String> {
+        let utc = epoch_to_datetime(ts)?;
+        Some(match self {
+            Zone::Named(tz) => utc.with_timezone(&tz).to_rfc3339_opts(SecondsFormat::Millis, true),
+            Zone::Local => utc.with_timezone(&Local).to_rfc3339_opts(SecondsFormat::Millis, true),
+        })
+    }
+}
+
+/// Parse an ISO 8601 / RFC 3339 timestamp into epoch seconds.
+///
+/// Accepts the extended and basic ("20260225T100000Z") forms, offsets with or without
+/// a colon, leap seconds, signed or 5+ digit years, and bare dates. Timestamps without
+/// an offset are taken as UTC.
+pub(crate) fn parse_iso_ts(ts_str: &str) -> Option<f64> {
+    parse_iso_ts_in(ts_str, Zone::UTC)
+}
+
+/// parse_iso_ts, reading timestamps without an offset in zone. A trailing IANA zone
+/// name ("2026-02-25 10:00:00 Europe/Paris") overrides zone.
+pub(crate) fn parse_iso_ts_in(ts_str: &str, zone: Zone) -> Option<f64> {
+    let s = ts_str.trim();
+    if s.is_empty() {
+        return None;
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Some(to_epoch(&dt.naive_utc()));
+    }
+
+    // chrono's %z family doesn't accept "Z", so spell it as an offset.
+    let with_offset = match s.strip_suffix(['Z', 'z']) {
+        Some(rest) => format!("{rest}+00:00"),
+        None => s.to_string(),
+    };
+    for fmt in OFFSET_FORMATS {
+        if let Ok(dt) = DateTime::parse_from_str(&with_offset, fmt) {
+            return Some(to_epoch(&dt.naive_utc()));
+        }
+    }
+    let (s, zone) = match s.rsplit_once(' ').map(|(rest, name)| (rest, name.parse::<Tz>())) {
+        Some((rest, Ok(tz))) => (rest.trim_end(), Zone::Named(tz)),
+        _ => (s, zone),
+    };
+    for fmt in NAIVE_FORMATS {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(s, fmt) {
+            return zone.resolve(&dt);
+        }
+    }
+    for fmt in DATE_FORMATS {
+        if let Ok(d) = NaiveDate::parse_from_str(s, fmt) {
+            return zone.resolve(&d.and_hms_opt(0, 0, 0)?);
+        }
+    }
+    None
+}
+
+/// Convert epoch seconds to a UTC datetime; None for the 0.0 "no timestamp" sentinel.
+pub(crate) fn epoch_to_datetime(ts: f64) -> Option<DateTime<Utc>> {
+    if !ts.is_finite() || ts == 0.0 {
+        return None;
+    }
+    let secs = ts.floor();
+    let nanos = ((ts - secs) * 1e9).round().min(999_999_999.0) as u32;
+    DateTime::from_timestamp(secs as i64, nanos)
+}
+
+/// Parse an ISO 8601 timestamp into epoch seconds, or None if it isn't one.
+///
+/// tz is the zone for timestamps that carry no offset: "UTC" (the default), "local"
+/// or an IANA name such as "America/New_York". A zone name after the time, as in
+/// "2026-02-25 10:00:00 Europe/Paris", takes precedence. Raises ValueError for an
+/// unknown tz.
+#[pyfunction]
+#[pyo3(signature = (ts, tz=None))]
+pub fn parse_iso_timestamp(ts: &str, tz: Option<&str>) -> PyResult<Option<f64>> {
+    let zone = tz.map(Zone::parse).transpose()?.unwrap_or(Zone::UTC);
+    Ok(parse_iso_ts_in(ts, zone))
+}
+
+/// Parse many ISO 8601 timestamps in one call with the GIL released.
+///
+/// Returns epoch seconds in input order, with None for unparseable entries. tz is as
+/// in parse_iso_timestamp.
+#[pyfunction]
+#[pyo3(signature = (timestamps, tz=None))]
+pub fn parse_iso_timestamps(
+    py: Python<'_>,
+    timestamps: Vec<String>,
+    tz: Option<&str>,
+) -> PyResult<Vec<Option<f64>>> {
+    let zone = tz.map(Zone::parse).transpose()?.unwrap_or(Zone::UTC);
+    Ok(py.detach(|| timestamps.par_iter().map(|s| parse_iso_ts_in(s, zone)).collect()))
+}
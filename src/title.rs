@@ -0,0 +1,135 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+use std::sync::OnceLock;
+
+use pyo3::prelude::*;
+use regex::Regex;
+
+use crate::errors::io_error;
+use crate::privacy::global_filter;
+use crate::{extract_content, INTERRUPT_MARKER};
+
+/// Prefixes of user-role text that Claude Code generates rather than the user typing it.
+const GENERATED_PREFIXES: [&str; 6] = [
+    "<command-name>",
+    "<command-message>",
+    "<local-command-stdout>",
+    "<task-notification",
+    "Caveat:",
+    "This session is being continued",
+];
+
+fn log_line_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"^\s*(?:\d{4}-\d{2}-\d{2}|\d{2}:\d{2}:\d{2}|\[|\{|at |File |Traceback|\$ |>>> |[A-Z]+:|\w*(?:Error|Exception|ERROR|WARN|INFO|DEBUG)\b)"
+        ).unwrap()
+    })
+}
+
+/// Whether a line reads like something a person wrote, as opposed to log or code output.
+fn is_prose(line: &str) -> bool {
+    if line.starts_with("    ") || line.starts_with('\t') {
+        return false;
+    }
+    let line = line.trim();
+    if line.starts_with("```")
+        || line.split_whitespace().count() < 2
+        || log_line_regex().is_match(line)
+    {
+        return false;
+    }
+    let letters = line.chars().filter(|c| c.is_alphabetic() || c.is_whitespace()).count();
+    letters as f64 / line.chars().count() as f64 > 0.7
+}
+
+/// Shorten to at most max_len bytes on a word boundary, adding an ellipsis if cut.
+fn shorten(text: &str, max_len: usize) -> String {
+    let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let cleaned = collapsed.trim_start_matches(['#', '>', '-', '*', ' ']).trim();
+    let mut title = if cleaned.len() <= max_len {
+        cleaned.to_string()
+    } else {
+        let cut = crate::truncate_str(cleaned, max_len);
+        let cut = cut.rsplit_once(' ').map(|(head, _)| head).unwrap_or(cut);
+        format!("{}…", cut.trim_end_matches([',', '.', ':', ';', ' ']))
+    };
+    if let Some(first) = title.chars().next() {
+        let upper: String = first.to_uppercase().collect();
+        title.replace_range(..first.len_utf8(), &upper);
+    }
+    title
+}
+
+/// The title candidate from one user message, if it has substantive prose.
+fn title_from_prompt(text: &str) -> Option<&str> {
+    let trimmed = text.trim();
+    if trimmed.starts_with('/')
+        || trimmed.starts_with(INTERRUPT_MARKER)
+        || GENERATED_PREFIXES.iter().any(|p| trimmed.starts_with(p))
+    {
+        return None;
+    }
+    trimmed.lines().find(|l| is_prose(l))
+}
+
+pub(crate) fn infer_title_impl(path: &str, max_len: usize) -> io::Result<Option<String>> {
+    let file = File::open(path)?;
+    let filter = global_filter();
+    let mut project: Option<String> = None;
+    let mut summary: Option<String> = None;
+    let mut first_prompt: Option<String> = None;
+
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        let entry: serde_json::Value = match serde_json::from_str(line.trim()) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        if project.is_none() {
+            project = entry.get("cwd").and_then(|v| v.as_str()).map(str::to_string);
+        }
+        match entry.get("type").and_then(|v| v.as_str()) {
+            Some("summary") => {
+                if let Some(s) = entry.get("summary").and_then(|v| v.as_str()) {
+                    let s = filter.as_ref().map_or(Some(s.into()), |f| f.content(s));
+                    if let Some(s) = s.filter(|s| !s.trim().is_empty()) {
+                        summary = Some(s.into_owned());
+                    }
+                }
+            }
+            Some("user") if first_prompt.is_none() => {
+                let is_meta = entry.get("isMeta").and_then(|v| v.as_bool()).unwrap_or(false);
+                if is_meta {
+                    continue;
+                }
+                let content = extract_content(&entry["message"]);
+                let content =
+                    filter.as_ref().map_or(Some(content.as_str().into()), |f| f.content(&content));
+                first_prompt = content.and_then(|c| title_from_prompt(&c).map(str::to_string));
+            }
+            _ => {}
+        }
+    }
+    if let (Some(f), Some(project)) = (&filter, &project) {
+        if !f.allows_project(project) {
+            return Ok(None);
+        }
+    }
+
+    Ok(summary.or(first_prompt).map(|t| shorten(&t, max_len)))
+}
+
+/// Derive a short display title for a transcript.
+///
+/// Prefers the session's summary entry; otherwise uses the first user message with real
+/// prose, skipping slash commands, generated notices and pasted logs. Returns None if
+/// nothing suitable is found. Under an installed privacy filter, text it denies is
+/// passed over, redactions carry into the title, and a denied project has none.
+#[pyfunction]
+#[pyo3(signature = (path, max_len=60))]
+pub fn infer_title(py: Python<'_>, path: &str, max_len: usize) -> PyResult<Option<String>> {
+    py.detach(|| infer_title_impl(path, max_len)).map_err(|e| io_error(Path::new(path), e))
+}
@@ -0,0 +1,402 @@
+use std::collections::{HashMap, VecDeque};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::OnceLock;
+
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+use regex::Regex;
+use serde_json::json;
+
+use crate::collector::{now, record, records_to_py, Record, Sampler};
+use crate::history::sort_by_timestamp;
+
+/// Tab-separated so names with spaces survive; parse_tmux_panes reads this back.
+pub(crate) const PANE_FORMAT: &str = "#{session_name}\t#{window_index}\t#{window_name}\t\
+     #{pane_index}\t#{pane_id}\t#{pane_pid}\t#{pane_current_command}\t#{pane_current_path}\t\
+     #{pane_active}";
+pub(crate) const SESSION_FORMAT: &str = "#{session_name}\t#{session_id}\t#{session_created}\t\
+     #{session_attached}\t#{session_windows}\t#{session_activity}";
+
+/// Foreground commands that mean the pane is sitting at a prompt.
+const SHELLS: [&str; 12] =
+    ["bash", "zsh", "fish", "sh", "dash", "ksh", "tcsh", "csh", "nu", "elvish", "xonsh", "pwsh"];
+
+#[derive(Clone)]
+pub(crate) struct Pane {
+    session: String,
+    window: i64,
+    window_name: String,
+    index: i64,
+    id: String,
+    pid: Option<i64>,
+    command: String,
+    cwd: String,
+    active: bool,
+}
+
+impl Pane {
+    fn fields(&self) -> Record {
+        record(
+            "tmux",
+            json!({
+                "session": self.session,
+                "window": self.window,
+                "window_name": self.window_name,
+                "pane": self.index,
+                "pane_id": self.id,
+                "pid": self.pid,
+                "command": self.command,
+                "cwd": self.cwd,
+                "active": self.active,
+            }),
+        )
+    }
+
+    fn target(&self) -> String {
+        format!("{}:{}.{}", self.session, self.window, self.index)
+    }
+}
+
+pub(crate) fn parse_panes(text: &str) -> Vec<Pane> {
+    text.lines()
+        .filter_map(|line| {
+            let f: Vec<&str> = line.split('\t').collect();
+            if f.len() < 9 {
+                return None;
+            }
+            Some(Pane {
+                session: f[0].to_string(),
+                window: f[1].parse().ok()?,
+                window_name: f[2].to_string(),
+                index: f[3].parse().ok()?,
+                id: f[4].to_string(),
+                pid: f[5].parse().ok(),
+                command: f[6].to_string(),
+                cwd: f[7].to_string(),
+                active: f[8] == "1",
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn parse_sessions(text: &str) -> Vec<Record> {
+    text.lines()
+        .filter_map(|line| {
+            let f: Vec<&str> = line.split('\t').collect();
+            if f.len() < 6 {
+                return None;
+            }
+            Some(record(
+                "tmux",
+                json!({
+                    "timestamp": f[2].parse::<f64>().ok(),
+                    "session": f[0],
+                    "session_id": f[1],
+                    "attached": f[3].parse::<i64>().unwrap_or(0),
+                    "windows": f[4].parse::<i64>().unwrap_or(0),
+                    "last_activity": f[5].parse::<f64>().ok(),
+                }),
+            ))
+        })
+        .collect()
+}
+
+/// Run tmux with args. No server running just means no sessions, so that is an
+/// empty answer rather than an error.
+fn tmux(args: &[&str]) -> Result<String, String> {
+    let output = Command::new("tmux").args(args).output().map_err(|e| format!("tmux: {e}"))?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() {
+        if stderr.contains("no server running") || stderr.contains("error connecting") {
+            return Ok(String::new());
+        }
+        return Err(format!("tmux failed ({}): {}", output.status, stderr.trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Escape sequences (colors, cursor movement, window titles) and carriage returns,
+/// which mean nothing once the output is read as plain lines.
+fn ansi_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"\x1b\[[0-9;?]*[ -/]*[@-~]|\x1b\][^\x07\x1b]*(?:\x07|\x1b\\)|\x1b[()][A-Z0-9]|\r",
+        )
+        .unwrap()
+    })
+}
+
+/// The last capacity bytes a pane printed.
+struct RingBuffer {
+    data: VecDeque<u8>,
+    capacity: usize,
+}
+
+impl RingBuffer {
+    fn new(capacity: usize) -> Self {
+        RingBuffer { data: VecDeque::with_capacity(capacity), capacity }
+    }
+
+    fn push(&mut self, bytes: &[u8]) {
+        let bytes = &bytes[bytes.len().saturating_sub(self.capacity)..];
+        let overflow = (self.data.len() + bytes.len()).saturating_sub(self.capacity);
+        self.data.drain(..overflow);
+        self.data.extend(bytes);
+    }
+
+    /// The last n non-empty lines, with escape sequences removed.
+    fn tail(&self, n: usize) -> String {
+        let (a, b) = self.data.as_slices();
+        let text = String::from_utf8_lossy(&[a, b].concat()).into_owned();
+        let text = ansi_re().replace_all(&text, "");
+        let lines: Vec<&str> = text.lines().filter(|l| !l.trim().is_empty()).collect();
+        lines[lines.len().saturating_sub(n)..].join("\n")
+    }
+}
+
+/// Settings for the Collector's "tmux" source.
+///
+/// A command running in a pane's foreground (anything but the shell) is reported
+/// when it finishes if it ran for at least min_duration seconds. With
+/// capture_output, each pane's output is piped (`tmux pipe-pane`) into a ring buffer
+/// of buffer_size bytes, and command events carry the last output_lines lines.
+#[pyclass(frozen, from_py_object)]
+#[derive(Clone)]
+pub struct TmuxOptions {
+    min_duration: f64,
+    capture_output: bool,
+    output_lines: usize,
+    buffer_size: usize,
+}
+
+impl Default for TmuxOptions {
+    fn default() -> Self {
+        TmuxOptions {
+            min_duration: 10.0,
+            capture_output: false,
+            output_lines: 20,
+            buffer_size: 65536,
+        }
+    }
+}
+
+#[pymethods]
+impl TmuxOptions {
+    #[new]
+    #[pyo3(signature = (
+        *, min_duration=10.0, capture_output=false, output_lines=20, buffer_size=65536
+    ))]
+    fn new(
+        min_duration: f64,
+        capture_output: bool,
+        output_lines: usize,
+        buffer_size: usize,
+    ) -> PyResult<Self> {
+        if min_duration.is_nan() || min_duration < 0.0 {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "min_duration must be non-negative",
+            ));
+        }
+        if buffer_size == 0 {
+            return Err(pyo3::exceptions::PyValueError::new_err("buffer_size must be positive"));
+        }
+        Ok(TmuxOptions { min_duration, capture_output, output_lines, buffer_size })
+    }
+}
+
+/// What the sampler remembers about one pane.
+struct PaneState {
+    pane: Pane,
+    opened: Option<f64>,
+    /// The foreground command and when it was first seen.
+    running: Option<(String, f64)>,
+    output: Option<(PathBuf, RingBuffer)>,
+}
+
+/// Polls `tmux list-panes`, emitting "pane_opened" and "pane_closed" events and a
+/// "command" event when a long enough foreground command finishes.
+pub(crate) struct TmuxSampler {
+    options: TmuxOptions,
+    panes: HashMap<String, PaneState>,
+    pipe_dir: PathBuf,
+    polled: bool,
+}
+
+impl TmuxSampler {
+    pub(crate) fn new(options: TmuxOptions) -> Self {
+        let pipe_dir = std::env::temp_dir().join(format!("snoopy-tmux-{}", std::process::id()));
+        TmuxSampler { options, panes: HashMap::new(), pipe_dir, polled: false }
+    }
+
+    /// Start copying a pane's output to a file the sampler drains each poll.
+    fn pipe(&self, pane: &Pane) -> Option<(PathBuf, RingBuffer)> {
+        std::fs::create_dir_all(&self.pipe_dir).ok()?;
+        let path = self.pipe_dir.join(format!("{}.log", pane.id.trim_start_matches('%')));
+        std::fs::File::create(&path).ok()?;
+        let command = format!("cat >> '{}'", path.display());
+        tmux(&["pipe-pane", "-o", "-t", &pane.id, &command]).ok()?;
+        Some((path, RingBuffer::new(self.options.buffer_size)))
+    }
+
+    fn event(&self, kind: &str, state: &PaneState, ts: f64) -> Record {
+        let mut event = state.pane.fields();
+        let target = state.pane.target();
+        let summary = match kind {
+            "pane_opened" => format!("new tmux pane {target}"),
+            _ => format!("closed tmux pane {target}"),
+        };
+        event.insert("timestamp".to_string(), json!(ts));
+        event.insert("kind".to_string(), json!(kind));
+        event.insert("duration".to_string(), json!(state.opened.map(|o| ts - o)));
+        event.insert("summary".to_string(), json!(summary));
+        event
+    }
+
+    /// The command event for a foreground command that just ended, if it ran long
+    /// enough to count.
+    fn finished(&self, state: &mut PaneState, ts: f64) -> Option<Record> {
+        let (command, started) = state.running.take()?;
+        let duration = ts - started;
+        if duration < self.options.min_duration {
+            return None;
+        }
+        let mut event = state.pane.fields();
+        event.insert("timestamp".to_string(), json!(started));
+        event.insert("end".to_string(), json!(ts));
+        event.insert("duration".to_string(), json!(duration));
+        event.insert("kind".to_string(), json!("command"));
+        event.insert("command".to_string(), json!(command));
+        let output =
+            state.output.as_ref().map(|(_, buffer)| buffer.tail(self.options.output_lines));
+        event.insert("output".to_string(), json!(output));
+        let summary =
+            format!("{command} ran for {:.0}s in tmux pane {}", duration, state.pane.target());
+        event.insert("summary".to_string(), json!(summary));
+        Some(event)
+    }
+}
+
+/// Move whatever a pane printed since the last poll into its ring buffer.
+fn drain_output(output: &mut (PathBuf, RingBuffer)) {
+    let Ok(mut file) = std::fs::OpenOptions::new().read(true).write(true).open(&output.0) else {
+        return;
+    };
+    let mut bytes = Vec::new();
+    if file.read_to_end(&mut bytes).is_ok() && !bytes.is_empty() {
+        output.1.push(&bytes);
+        // cat appends, so it carries on at the new end; anything written between the
+        // read and here is lost, which is fine for a tail.
+        let _ = file.set_len(0);
+        let _ = file.seek(SeekFrom::Start(0));
+    }
+}
+
+impl Sampler for TmuxSampler {
+    fn name(&self) -> &'static str {
+        "tmux"
+    }
+
+    fn sample(&mut self) -> Result<Vec<Record>, String> {
+        let panes = parse_panes(&tmux(&["list-panes", "-a", "-F", PANE_FORMAT])?);
+        let first = !std::mem::replace(&mut self.polled, true);
+        let ts = now();
+        let mut events = Vec::new();
+        let mut previous = std::mem::take(&mut self.panes);
+        for pane in panes {
+            let mut state = match previous.remove(&pane.id) {
+                Some(state) => state,
+                None => {
+                    let output = if self.options.capture_output { self.pipe(&pane) } else { None };
+                    let state = PaneState {
+                        pane: pane.clone(),
+                        opened: (!first).then_some(ts),
+                        running: None,
+                        output,
+                    };
+                    if !first {
+                        events.push(self.event("pane_opened", &state, ts));
+                    }
+                    state
+                }
+            };
+            state.pane = pane;
+            if let Some(output) = state.output.as_mut() {
+                drain_output(output);
+            }
+            let command = state.pane.command.trim_start_matches('-').to_string();
+            let at_prompt = SHELLS.contains(&command.as_str()) || command.is_empty();
+            if state.running.as_ref().is_some_and(|(running, _)| at_prompt || *running != command) {
+                events.extend(self.finished(&mut state, ts));
+            }
+            if !at_prompt && state.running.is_none() {
+                state.running = Some((command, ts));
+            }
+            self.panes.insert(state.pane.id.clone(), state);
+        }
+        for (_, mut state) in previous {
+            if let Some(output) = state.output.as_mut() {
+                drain_output(output);
+                let _ = std::fs::remove_file(&output.0);
+            }
+            events.extend(self.finished(&mut state, ts));
+            events.push(self.event("pane_closed", &state, ts));
+        }
+        sort_by_timestamp(&mut events);
+        Ok(events)
+    }
+}
+
+impl Drop for TmuxSampler {
+    fn drop(&mut self) {
+        for state in self.panes.values() {
+            if state.output.is_some() {
+                // pipe-pane without a command closes the pane's pipe.
+                let _ = tmux(&["pipe-pane", "-t", &state.pane.id]);
+            }
+        }
+        let _ = std::fs::remove_dir_all(&self.pipe_dir);
+    }
+}
+
+/// Parse `tmux list-panes -a -F <TMUX_PANE_FORMAT>` output.
+///
+/// Each line becomes {source: "tmux", session, window, window_name, pane, pane_id,
+/// pid, command (the foreground process), cwd, active}. tmux_panes() runs the
+/// command itself; Collector(sources=["tmux"]) turns changes into events.
+#[pyfunction]
+pub fn parse_tmux_panes<'py>(py: Python<'py>, text: &str) -> PyResult<Bound<'py, PyList>> {
+    let panes = parse_panes(text).iter().map(Pane::fields).collect();
+    records_to_py(py, panes)
+}
+
+/// Parse `tmux list-sessions -F <TMUX_SESSION_FORMAT>` output.
+///
+/// Each line becomes {source: "tmux", timestamp (when the session was created),
+/// session, session_id, attached (client count), windows, last_activity}.
+#[pyfunction]
+pub fn parse_tmux_sessions<'py>(py: Python<'py>, text: &str) -> PyResult<Bound<'py, PyList>> {
+    records_to_py(py, parse_sessions(text))
+}
+
+/// Every pane of every tmux session, as parse_tmux_panes returns them; empty when no
+/// tmux server is running. Raises RuntimeError if tmux can't be run.
+#[pyfunction]
+pub fn tmux_panes(py: Python<'_>) -> PyResult<Bound<'_, PyList>> {
+    let text = py
+        .detach(|| tmux(&["list-panes", "-a", "-F", PANE_FORMAT]))
+        .map_err(pyo3::exceptions::PyRuntimeError::new_err)?;
+    records_to_py(py, parse_panes(&text).iter().map(Pane::fields).collect())
+}
+
+/// Every tmux session, as parse_tmux_sessions returns them; empty when no tmux
+/// server is running. Raises RuntimeError if tmux can't be run.
+#[pyfunction]
+pub fn tmux_sessions(py: Python<'_>) -> PyResult<Bound<'_, PyList>> {
+    let text = py
+        .detach(|| tmux(&["list-sessions", "-F", SESSION_FORMAT]))
+        .map_err(pyo3::exceptions::PyRuntimeError::new_err)?;
+    records_to_py(py, parse_sessions(&text))
+}
@@ -0,0 +1,48 @@
+use pyo3::prelude::*;
+use rayon::prelude::*;
+use serde_json::Value;
+use tiktoken_rs::cl100k_base_singleton;
+
+use crate::usage::Usage;
+
+/// Approximate token count for text.
+///
+/// Uses the cl100k BPE, which tracks Claude's tokenizer closely enough for cost estimates.
+pub(crate) fn estimate(text: &str) -> u64 {
+    if text.is_empty() {
+        return 0;
+    }
+    cl100k_base_singleton().encode_ordinary(text).len() as u64
+}
+
+/// Estimate output usage for an assistant message that carries no `usage` object.
+pub(crate) fn estimate_usage(msg: &Value) -> Option<Usage> {
+    let blocks = msg.get("content")?.as_array()?;
+    let mut output_tokens = 0;
+    for block in blocks {
+        output_tokens += match block.get("type").and_then(|v| v.as_str()) {
+            Some("text") => estimate(block.get("text").and_then(|v| v.as_str()).unwrap_or("")),
+            Some("thinking") => {
+                estimate(block.get("thinking").and_then(|v| v.as_str()).unwrap_or(""))
+            }
+            Some("tool_use") => estimate(&block["input"].to_string()),
+            _ => 0,
+        };
+    }
+    Some(Usage {
+        output_tokens,
+        ..Default::default()
+    })
+}
+
+/// Estimate the number of tokens in text.
+#[pyfunction]
+pub fn estimate_tokens(py: Python<'_>, text: &str) -> u64 {
+    py.detach(|| estimate(text))
+}
+
+/// Estimate token counts for many texts in one call, in parallel with the GIL released.
+#[pyfunction]
+pub fn estimate_tokens_batch(py: Python<'_>, texts: Vec<String>) -> Vec<u64> {
+    py.detach(|| texts.par_iter().map(|t| estimate(t)).collect())
+}
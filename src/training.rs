@@ -0,0 +1,445 @@
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use rayon::prelude::*;
+use serde_json::{json, Value};
+
+use crate::errors::io_error;
+use crate::privacy::{global_filter, PrivacyFilter};
+use crate::rewrite::{RewriteRules, Rewriter};
+use crate::rollup::find_jsonl_files;
+use crate::timeutil::parse_iso_ts;
+use crate::{tool_result_text, INTERRUPT_MARKER};
+
+/// Prompt text Claude Code writes for slash commands and their output rather than
+/// the user typing it.
+const WRAPPER_PREFIXES: [&str; 3] = ["<command-", "<local-command-", "Caveat: "];
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    OpenAi,
+    Anthropic,
+}
+
+enum Part {
+    Text(String),
+    ToolUse {
+        id: String,
+        name: String,
+        input: Value,
+    },
+    ToolResult {
+        id: String,
+        content: String,
+        is_error: bool,
+    },
+    /// An image or document block, kept as written.
+    Attachment(Value),
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Role {
+    User,
+    Assistant,
+}
+
+struct Turn {
+    role: Role,
+    parts: Vec<Part>,
+}
+
+/// What the export did with one transcript.
+#[derive(Default)]
+struct Counts {
+    sessions: u64,
+    messages: u64,
+    tool_calls: u64,
+    redactions: u64,
+    skipped: u64,
+}
+
+impl Counts {
+    fn merge(&mut self, other: &Counts) {
+        self.sessions += other.sessions;
+        self.messages += other.messages;
+        self.tool_calls += other.tool_calls;
+        self.redactions += other.redactions;
+        self.skipped += other.skipped;
+    }
+}
+
+/// Options shared by every transcript in an export.
+struct Options {
+    format: Format,
+    rules: RewriteRules,
+    system: Option<String>,
+    include_tools: bool,
+    since: Option<f64>,
+    /// The installed privacy filter, applied after rules.
+    privacy: Option<Arc<PrivacyFilter>>,
+}
+
+/// The parts of an entry worth training on, or None for entries that aren't part of
+/// the conversation: metadata, meta and compaction entries, sidechains, synthetic
+/// replies, interrupt markers and command wrappers.
+fn entry_turn(entry: &Value, include_tools: bool) -> Option<Turn> {
+    let flag = |name: &str| entry.get(name).and_then(Value::as_bool).unwrap_or(false);
+    if flag("isMeta") || flag("isCompactSummary") || flag("isSidechain") {
+        return None;
+    }
+    let msg = &entry["message"];
+    let role = match entry.get("type").and_then(Value::as_str)? {
+        "user" => Role::User,
+        "assistant" if msg["model"].as_str() != Some("<synthetic>") => Role::Assistant,
+        _ => return None,
+    };
+    let keep = |text: &str| {
+        let text = text.trim();
+        !text.is_empty()
+            && (role == Role::Assistant
+                || !text.starts_with(INTERRUPT_MARKER)
+                    && !WRAPPER_PREFIXES.iter().any(|p| text.starts_with(p)))
+    };
+    let mut parts = Vec::new();
+    match &msg["content"] {
+        Value::String(text) if keep(text) => {
+            parts.push(Part::Text(text.trim().to_string()));
+        }
+        Value::Array(blocks) => {
+            for block in blocks {
+                match block.get("type").and_then(Value::as_str) {
+                    Some("text") => {
+                        let text = block["text"].as_str().unwrap_or("");
+                        if keep(text) {
+                            parts.push(Part::Text(text.trim().to_string()));
+                        }
+                    }
+                    Some("tool_use") if include_tools => parts.push(Part::ToolUse {
+                        id: block["id"].as_str().unwrap_or("").to_string(),
+                        name: block["name"].as_str().unwrap_or("").to_string(),
+                        input: block.get("input").cloned().unwrap_or_else(|| json!({})),
+                    }),
+                    Some("tool_result") if include_tools => parts.push(Part::ToolResult {
+                        id: block["tool_use_id"].as_str().unwrap_or("").to_string(),
+                        content: tool_result_text(block),
+                        is_error: block["is_error"].as_bool().unwrap_or(false),
+                    }),
+                    Some("image" | "document") => parts.push(Part::Attachment(block.clone())),
+                    _ => {}
+                }
+            }
+        }
+        _ => {}
+    }
+    (!parts.is_empty()).then_some(Turn { role, parts })
+}
+
+/// Redact a tool input's strings in place; false if the filter denies any of them.
+fn filter_value(value: &mut Value, filter: &PrivacyFilter) -> bool {
+    match value {
+        Value::String(text) => match filter.content(text) {
+            Some(Cow::Owned(redacted)) => {
+                *text = redacted;
+                true
+            }
+            Some(Cow::Borrowed(_)) => true,
+            None => false,
+        },
+        Value::Array(items) => items.iter_mut().all(|v| filter_value(v, filter)),
+        Value::Object(map) => map.values_mut().all(|v| filter_value(v, filter)),
+        _ => true,
+    }
+}
+
+/// The turn's parts as the privacy filter lets them out: denied ones are dropped
+/// (tidy then drops what they leave unpaired) and the rest redacted.
+fn filter_turn(turn: Turn, filter: &PrivacyFilter) -> Option<Turn> {
+    let parts: Vec<Part> = turn
+        .parts
+        .into_iter()
+        .filter_map(|part| match part {
+            Part::Text(text) => Some(Part::Text(filter.content(&text)?.into_owned())),
+            Part::ToolUse { id, name, mut input } => {
+                filter_value(&mut input, filter).then_some(Part::ToolUse { id, name, input })
+            }
+            Part::ToolResult { id, content, is_error } => {
+                let content = filter.content(&content)?.into_owned();
+                Some(Part::ToolResult { id, content, is_error })
+            }
+            Part::Attachment(block) => Some(Part::Attachment(block)),
+        })
+        .collect();
+    (!parts.is_empty()).then_some(Turn { role: turn.role, parts })
+}
+
+/// Make the turns a valid conversation: calls and results paired up, consecutive
+/// turns of one role merged, starting with the user and ending with the assistant.
+fn tidy(turns: Vec<Turn>) -> Vec<Turn> {
+    let mut calls = HashSet::new();
+    let mut results = HashSet::new();
+    for part in turns.iter().flat_map(|t| &t.parts) {
+        match part {
+            Part::ToolUse { id, .. } => calls.insert(id.clone()),
+            Part::ToolResult { id, .. } => results.insert(id.clone()),
+            _ => false,
+        };
+    }
+    let mut merged: Vec<Turn> = Vec::new();
+    for mut turn in turns {
+        turn.parts.retain(|p| match p {
+            Part::ToolUse { id, .. } => results.contains(id),
+            Part::ToolResult { id, .. } => calls.contains(id),
+            _ => true,
+        });
+        if turn.parts.is_empty() {
+            continue;
+        }
+        match merged.last_mut() {
+            Some(last) if last.role == turn.role => last.parts.append(&mut turn.parts),
+            None if turn.role == Role::Assistant => {}
+            _ => merged.push(turn),
+        }
+    }
+    while merged.last().is_some_and(|t| t.role == Role::User) {
+        merged.pop();
+    }
+    merged
+}
+
+fn join_text(parts: &[Part]) -> Option<String> {
+    let texts: Vec<&str> = parts
+        .iter()
+        .filter_map(|p| match p {
+            Part::Text(text) => Some(text.as_str()),
+            Part::Attachment(_) => Some("[attachment]"),
+            _ => None,
+        })
+        .collect();
+    (!texts.is_empty()).then(|| texts.join("\n\n"))
+}
+
+fn openai_messages(turns: &[Turn], system: Option<&str>) -> Vec<Value> {
+    let mut messages: Vec<Value> =
+        system.map(|s| json!({"role": "system", "content": s})).into_iter().collect();
+    for turn in turns {
+        match turn.role {
+            Role::User => {
+                for part in &turn.parts {
+                    if let Part::ToolResult { id, content, .. } = part {
+                        messages
+                            .push(json!({"role": "tool", "tool_call_id": id, "content": content}));
+                    }
+                }
+                if let Some(text) = join_text(&turn.parts) {
+                    messages.push(json!({"role": "user", "content": text}));
+                }
+            }
+            Role::Assistant => {
+                let calls: Vec<Value> = turn
+                    .parts
+                    .iter()
+                    .filter_map(|p| match p {
+                        Part::ToolUse { id, name, input } => Some(json!({
+                            "id": id,
+                            "type": "function",
+                            "function": {"name": name, "arguments": input.to_string()},
+                        })),
+                        _ => None,
+                    })
+                    .collect();
+                let mut message = json!({"role": "assistant", "content": join_text(&turn.parts)});
+                if !calls.is_empty() {
+                    message["tool_calls"] = Value::Array(calls);
+                }
+                messages.push(message);
+            }
+        }
+    }
+    messages
+}
+
+fn anthropic_messages(turns: &[Turn]) -> Vec<Value> {
+    turns
+        .iter()
+        .map(|turn| {
+            // Tool results have to lead a user message's content.
+            let (results, rest): (Vec<&Part>, Vec<&Part>) =
+                turn.parts.iter().partition(|p| matches!(p, Part::ToolResult { .. }));
+            let content: Vec<Value> = results
+                .into_iter()
+                .chain(rest)
+                .map(|part| match part {
+                    Part::Text(text) => json!({"type": "text", "text": text}),
+                    Part::ToolUse { id, name, input } => {
+                        json!({"type": "tool_use", "id": id, "name": name, "input": input})
+                    }
+                    Part::ToolResult { id, content, is_error } => {
+                        let mut block =
+                            json!({"type": "tool_result", "tool_use_id": id, "content": content});
+                        if *is_error {
+                            block["is_error"] = Value::Bool(true);
+                        }
+                        block
+                    }
+                    Part::Attachment(block) => block.clone(),
+                })
+                .collect();
+            let role = if turn.role == Role::User { "user" } else { "assistant" };
+            json!({"role": role, "content": content})
+        })
+        .collect()
+}
+
+/// One transcript as an export line, with what went into it.
+fn export_file(path: &Path, options: &Options) -> (Option<String>, Counts) {
+    let mut counts = Counts::default();
+    let Ok(file) = File::open(path) else {
+        counts.skipped += 1;
+        return (None, counts);
+    };
+    let mut rewriter = Rewriter::new(&options.rules);
+    let mut turns = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let Ok(line) = line else { break };
+        let Ok(mut entry) = serde_json::from_str::<Value>(line.trim()) else { continue };
+        if let Some(since) = options.since {
+            let ts = entry.get("timestamp").and_then(Value::as_str).and_then(parse_iso_ts);
+            if ts.is_some_and(|ts| ts < since) {
+                continue;
+            }
+        }
+        rewriter.entry(&mut entry);
+        let Some(turn) = entry_turn(&entry, options.include_tools) else { continue };
+        match &options.privacy {
+            Some(filter) => {
+                let cwd = entry.get("cwd").and_then(Value::as_str);
+                if cwd.is_some_and(|cwd| !filter.allows_project(cwd)) {
+                    counts.skipped += 1;
+                    return (None, counts);
+                }
+                turns.extend(filter_turn(turn, filter));
+            }
+            None => turns.push(turn),
+        }
+    }
+    let turns = tidy(turns);
+    if turns.is_empty() {
+        counts.skipped += 1;
+        return (None, counts);
+    }
+    counts.sessions = 1;
+    counts.redactions = rewriter.redactions();
+    counts.tool_calls =
+        turns.iter().flat_map(|t| &t.parts).filter(|p| matches!(p, Part::ToolUse { .. })).count()
+            as u64;
+    let record = match options.format {
+        Format::OpenAi => {
+            let messages = openai_messages(&turns, options.system.as_deref());
+            counts.messages = messages.len() as u64;
+            json!({"messages": messages})
+        }
+        Format::Anthropic => {
+            let messages = anthropic_messages(&turns);
+            counts.messages = messages.len() as u64;
+            let mut record = json!({"messages": messages});
+            if let Some(system) = &options.system {
+                record["system"] = json!(system);
+            }
+            record
+        }
+    };
+    (Some(record.to_string()), counts)
+}
+
+/// Export transcripts as chat-format JSONL for replay, fine-tuning or evals.
+///
+/// path is a transcript file or a directory searched recursively; each session
+/// becomes one line of out_path (replaced), in path order. format is "openai" ({
+/// messages: [{role: system|user|assistant|tool, content, tool_calls?,
+/// tool_call_id?}]}, tool inputs as JSON strings) or "anthropic" ({system?, messages:
+/// [{role: user|assistant, content: [text, tool_use and tool_result blocks]}]}).
+/// system, if given, is the system prompt of every conversation. Every entry first
+/// goes through rules (a RewriteRules, by default redacting secrets and stripping
+/// attachments), so the export holds nothing rewrite_transcript wouldn't. Meta and
+/// compaction entries, sidechains, slash-command wrappers, interrupt markers,
+/// synthetic replies and thinking blocks are left out, as are tool calls and results
+/// with include_tools=False; what remains is paired up so every call has its result,
+/// consecutive messages of one role are merged, and each conversation starts with
+/// the user and ends with the agent. since (epoch seconds) skips earlier entries.
+/// Subagent transcripts (agent-*.jsonl) are only read with include_subagents=True.
+/// The installed privacy filter applies after rules: sessions in a denied project
+/// are skipped, denied messages and tool calls left out, and the rest redacted.
+/// Returns {sessions, messages, tool_calls, redactions, skipped (transcripts with
+/// nothing to export)}. Raises ValueError for an unknown format and IOError if
+/// out_path can't be written.
+#[pyfunction]
+#[pyo3(signature = (
+    path, out_path, format="openai", *, rules=None, system=None, include_tools=true,
+    include_subagents=false, since=None
+))]
+#[allow(clippy::too_many_arguments)]
+pub fn export_training_data<'py>(
+    py: Python<'py>,
+    path: &str,
+    out_path: &str,
+    format: &str,
+    rules: Option<RewriteRules>,
+    system: Option<String>,
+    include_tools: bool,
+    include_subagents: bool,
+    since: Option<f64>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let format = match format {
+        "openai" => Format::OpenAi,
+        "anthropic" => Format::Anthropic,
+        _ => {
+            return Err(PyValueError::new_err(format!(
+                "format must be 'openai' or 'anthropic', got {format:?}"
+            )))
+        }
+    };
+    let options = Options {
+        format,
+        rules: rules.unwrap_or_default(),
+        system,
+        include_tools,
+        since,
+        privacy: global_filter(),
+    };
+    let (root, out_path) = (PathBuf::from(path), Path::new(out_path));
+    let counts = py.detach(|| -> PyResult<Counts> {
+        let mut files = if root.is_dir() { find_jsonl_files(&root) } else { vec![root.clone()] };
+        if !include_subagents {
+            files.retain(|f| {
+                !f.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with("agent-"))
+            });
+        }
+        let exported: Vec<(Option<String>, Counts)> =
+            files.par_iter().map(|f| export_file(f, &options)).collect();
+        let mut out = BufWriter::new(File::create(out_path).map_err(|e| io_error(out_path, e))?);
+        let mut counts = Counts::default();
+        for (line, file_counts) in &exported {
+            if let Some(line) = line {
+                writeln!(out, "{line}").map_err(|e| io_error(out_path, e))?;
+            }
+            counts.merge(file_counts);
+        }
+        out.flush().map_err(|e| io_error(out_path, e))?;
+        Ok(counts)
+    })?;
+
+    let dict = PyDict::new(py);
+    dict.set_item("sessions", counts.sessions)?;
+    dict.set_item("messages", counts.messages)?;
+    dict.set_item("tool_calls", counts.tool_calls)?;
+    dict.set_item("redactions", counts.redactions)?;
+    dict.set_item("skipped", counts.skipped)?;
+    Ok(dict)
+}
@@ -0,0 +1,180 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, VecDeque};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::PathBuf;
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::errors::ReadError;
+use crate::parserconfig::ParserConfig;
+use crate::rollup::find_jsonl_files;
+use crate::{event_to_dict, open_transcript, Interner, TranscriptEvent, TranscriptLines};
+
+/// One transcript read a line at a time.
+struct FileStream {
+    /// The open file, until it has been read to the end.
+    lines: Option<TranscriptLines<BufReader<File>>>,
+    /// Parsed, filtered events not yet handed to the merge.
+    ready: VecDeque<TranscriptEvent>,
+    /// Events of the lines read so far that may still change: a compaction waits for
+    /// the summary line that follows it.
+    unsettled: Vec<TranscriptEvent>,
+    /// Latest timestamp seen, which orders events that have none.
+    last_ts: f64,
+}
+
+impl FileStream {
+    fn next(&mut self, config: &ParserConfig) -> Result<Option<TranscriptEvent>, ReadError> {
+        loop {
+            if let Some(ev) = self.ready.pop_front() {
+                return Ok(Some(ev));
+            }
+            let Some(lines) = &mut self.lines else {
+                return Ok(None);
+            };
+            if !lines.read_line(config, &mut self.unsettled)? {
+                self.lines = None;
+            }
+            let held = match self.unsettled.last() {
+                Some(ev) if self.lines.is_some() && ev.message_type == "compaction" => {
+                    usize::from(!ev.has_summary)
+                }
+                _ => 0,
+            };
+            let end = self.unsettled.len() - held;
+            let mut settled: Vec<_> = self.unsettled.drain(..end).collect();
+            config.filter_events(&mut settled);
+            self.ready.extend(settled);
+        }
+    }
+}
+
+/// The next event of one file, ordered by (timestamp, file index).
+struct Head {
+    ts: f64,
+    file: usize,
+    event: TranscriptEvent,
+}
+
+impl PartialEq for Head {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Head {}
+
+impl PartialOrd for Head {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Head {
+    // Reversed so BinaryHeap pops the earliest event first.
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.ts.total_cmp(&self.ts).then(other.file.cmp(&self.file))
+    }
+}
+
+/// Iterator over the events of several transcripts in timestamp order.
+#[pyclass]
+pub struct TranscriptMerge {
+    files: Vec<FileStream>,
+    heap: BinaryHeap<Head>,
+    /// The file whose head was handed out last, to be pulled from before the next
+    /// one, so a read error doesn't swallow an event already taken.
+    refill: Option<usize>,
+    config: ParserConfig,
+    strings: Interner,
+}
+
+impl TranscriptMerge {
+    /// Move file i's next event, if any, into the heap.
+    fn pull(&mut self, i: usize) -> Result<(), ReadError> {
+        let file = &mut self.files[i];
+        if let Some(event) = file.next(&self.config)? {
+            if event.timestamp > 0.0 {
+                file.last_ts = event.timestamp;
+            }
+            self.heap.push(Head { ts: file.last_ts, file: i, event });
+        }
+        Ok(())
+    }
+
+    fn advance(&mut self) -> Result<Option<TranscriptEvent>, ReadError> {
+        if let Some(i) = self.refill.take() {
+            self.pull(i)?;
+        }
+        let head = match self.heap.pop() {
+            Some(head) => head,
+            None => return Ok(None),
+        };
+        self.refill = Some(head.file);
+        Ok(Some(head.event))
+    }
+}
+
+#[pymethods]
+impl TranscriptMerge {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__<'py>(&mut self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyDict>>> {
+        match py.detach(|| self.advance())? {
+            Some(ev) => {
+                event_to_dict(py, &ev, self.config.time_format, &mut self.strings).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+/// Iterate over the events of many transcripts as one stream in timestamp order.
+///
+/// paths are transcript files or directories searched recursively for *.jsonl files.
+/// Files are read a line at a time as the merge needs them, so memory stays
+/// proportional to the number of files rather than their size; each stays open until
+/// it has been read to the end. Each file's events
+/// are taken in file order, which for Claude Code transcripts is timestamp order;
+/// events without a timestamp stay after the event before them, and ties go to the
+/// earlier path. preview_len, datetimes and config work as in parse_transcript.
+/// Raises IOError if a file can't be opened, and ParseError mid-iteration if one turns
+/// out not to be UTF-8.
+#[pyfunction]
+#[pyo3(signature = (paths, preview_len=None, datetimes=None, config=None))]
+pub fn iter_transcripts(
+    py: Python<'_>,
+    paths: Vec<PathBuf>,
+    preview_len: Option<usize>,
+    datetimes: Option<bool>,
+    config: Option<ParserConfig>,
+) -> PyResult<TranscriptMerge> {
+    let config = config.unwrap_or_default().with_overrides(preview_len, datetimes);
+    let mut merge = py.detach(|| {
+        let mut files = Vec::new();
+        for path in paths {
+            let found = if path.is_dir() { find_jsonl_files(&path) } else { vec![path] };
+            for file in found {
+                files.push(FileStream {
+                    lines: Some(open_transcript(&file, 0)?),
+                    ready: VecDeque::new(),
+                    unsettled: Vec::new(),
+                    last_ts: 0.0,
+                });
+            }
+        }
+        Ok::<_, ReadError>(TranscriptMerge {
+            files,
+            heap: BinaryHeap::new(),
+            refill: None,
+            config,
+            strings: Interner::default(),
+        })
+    })?;
+    py.detach(|| (0..merge.files.len()).try_for_each(|i| merge.pull(i)))?;
+    Ok(merge)
+}
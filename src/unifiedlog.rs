@@ -0,0 +1,145 @@
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Command, Stdio};
+
+use chrono::{Local, TimeZone};
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+use regex::Regex;
+use serde_json::{json, Value};
+
+use crate::collector::{record, records_to_py, Record};
+use crate::timeutil::parse_iso_ts;
+
+fn basename(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+/// Format epoch seconds the way `log show --start/--end` expects (local time).
+pub(crate) fn log_time(ts: f64) -> PyResult<String> {
+    Local
+        .timestamp_opt(ts.floor() as i64, 0)
+        .single()
+        .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+        .ok_or_else(|| pyo3::exceptions::PyValueError::new_err(format!("invalid time {ts}")))
+}
+
+/// Convert one `log show --style ndjson` line into an event; None for lines that
+/// aren't log entries (the leading banner, the trailing summary).
+fn parse_entry(line: &str) -> Option<Record> {
+    let entry: Value = serde_json::from_str(line).ok()?;
+    let timestamp = parse_iso_ts(entry.get("timestamp")?.as_str()?)?;
+    let text = |key: &str| entry.get(key).and_then(Value::as_str).unwrap_or_default();
+    Some(record(
+        "unifiedlog",
+        json!({
+            "timestamp": timestamp,
+            "process_name": basename(text("processImagePath")),
+            "pid": entry.get("processID").and_then(Value::as_i64),
+            "sender": basename(text("senderImagePath")),
+            "subsystem": text("subsystem"),
+            "category": text("category"),
+            "level": text("messageType"),
+            "message": text("eventMessage"),
+        }),
+    ))
+}
+
+/// Query the macOS unified log with `log show` and return matching entries.
+///
+/// predicate is passed to `log show --predicate` (e.g. 'subsystem ==
+/// "com.apple.bluetooth"'). The window is start to end (epoch seconds) or last (a
+/// `log show --last` duration such as "10m"); with neither, the last 5 minutes.
+/// info and debug include those levels. Output is streamed and parsed as it arrives;
+/// message_pattern (a regex) further filters on the message text, and limit stops
+/// the query once that many entries matched. Entries are {source: "unifiedlog",
+/// timestamp, process_name, pid, sender, subsystem, category, level, message}, oldest
+/// first. Raises RuntimeError if `log show` fails.
+#[pyfunction]
+#[pyo3(signature = (
+    predicate=None, start=None, end=None, last=None, info=false, debug=false,
+    message_pattern=None, limit=None
+))]
+#[allow(clippy::too_many_arguments)]
+pub fn query_unified_log<'py>(
+    py: Python<'py>,
+    predicate: Option<&str>,
+    start: Option<f64>,
+    end: Option<f64>,
+    last: Option<&str>,
+    info: bool,
+    debug: bool,
+    message_pattern: Option<&str>,
+    limit: Option<usize>,
+) -> PyResult<Bound<'py, PyList>> {
+    let pattern = message_pattern
+        .map(Regex::new)
+        .transpose()
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+    let mut cmd = Command::new("log");
+    cmd.args(["show", "--style", "ndjson"]);
+    if let Some(predicate) = predicate {
+        cmd.args(["--predicate", predicate]);
+    }
+    match (start, last) {
+        (Some(start), _) => {
+            cmd.args(["--start", &log_time(start)?]);
+        }
+        (None, last) => {
+            cmd.args(["--last", last.unwrap_or("5m")]);
+        }
+    }
+    if let Some(end) = end {
+        cmd.args(["--end", &log_time(end)?]);
+    }
+    if info {
+        cmd.arg("--info");
+    }
+    if debug {
+        cmd.arg("--debug");
+    }
+
+    let events = py.detach(|| -> PyResult<Vec<Record>> {
+        let mut child = cmd
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| pyo3::exceptions::PyIOError::new_err(format!("log: {e}")))?;
+        // Drain stderr on its own thread so a chatty error can't fill the pipe and stall us.
+        let mut stderr = child.stderr.take();
+        let errors = std::thread::spawn(move || {
+            let mut text = String::new();
+            if let Some(err) = stderr.as_mut() {
+                let _ = err.read_to_string(&mut text);
+            }
+            text
+        });
+        let mut events = Vec::new();
+        let mut truncated = false;
+        if let Some(stdout) = child.stdout.take() {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else { break };
+                let Some(event) = parse_entry(&line) else { continue };
+                let message = event["message"].as_str().unwrap_or_default();
+                if pattern.as_ref().is_some_and(|re| !re.is_match(message)) {
+                    continue;
+                }
+                if limit.is_some_and(|n| events.len() >= n) {
+                    truncated = true;
+                    let _ = child.kill();
+                    break;
+                }
+                events.push(event);
+            }
+        }
+        let status = child.wait().map_err(pyo3::exceptions::PyIOError::new_err)?;
+        let stderr = errors.join().unwrap_or_default();
+        if !status.success() && !truncated {
+            return Err(pyo3::exceptions::PyRuntimeError::new_err(format!(
+                "log show failed ({status}): {}",
+                stderr.trim()
+            )));
+        }
+        Ok(events)
+    })?;
+    records_to_py(py, events)
+}
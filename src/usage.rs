@@ -0,0 +1,66 @@
+use serde_json::Value;
+
+/// Token counts reported in an assistant message's `usage` object.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct Usage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+}
+
+impl Usage {
+    /// Usage reported by the message, falling back to a local estimate when absent.
+    pub fn from_message_or_estimate(msg: &Value) -> Option<Usage> {
+        Usage::from_message(msg).or_else(|| crate::tokens::estimate_usage(msg))
+    }
+
+    pub fn from_message(msg: &Value) -> Option<Usage> {
+        let usage = msg.get("usage")?.as_object()?;
+        let get = |key: &str| usage.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+        Some(Usage {
+            input_tokens: get("input_tokens"),
+            output_tokens: get("output_tokens"),
+            cache_creation_tokens: get("cache_creation_input_tokens"),
+            cache_read_tokens: get("cache_read_input_tokens"),
+        })
+    }
+
+    pub fn add(&mut self, other: &Usage) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_creation_tokens += other.cache_creation_tokens;
+        self.cache_read_tokens += other.cache_read_tokens;
+    }
+
+    pub fn total(&self) -> u64 {
+        self.input_tokens + self.output_tokens + self.cache_creation_tokens + self.cache_read_tokens
+    }
+
+    /// Estimated cost in USD using list prices for the model family.
+    pub fn cost_usd(&self, model: &str) -> f64 {
+        let (input, output) = model_prices(model);
+        let per_token = |price_per_mtok: f64| price_per_mtok / 1_000_000.0;
+        self.input_tokens as f64 * per_token(input)
+            + self.output_tokens as f64 * per_token(output)
+            + self.cache_creation_tokens as f64 * per_token(input * 1.25)
+            + self.cache_read_tokens as f64 * per_token(input * 0.1)
+    }
+}
+
+/// (input, output) USD per million tokens, matched on the model id.
+fn model_prices(model: &str) -> (f64, f64) {
+    let m = model.to_ascii_lowercase();
+    if m.contains("opus-4-5") || m.contains("opus-4-6") {
+        (5.0, 25.0)
+    } else if m.contains("opus") {
+        (15.0, 75.0)
+    } else if m.contains("haiku-4") {
+        (1.0, 5.0)
+    } else if m.contains("haiku") {
+        (0.8, 4.0)
+    } else {
+        // Sonnet pricing; also the fallback for unknown models.
+        (3.0, 15.0)
+    }
+}
@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+use serde_json::{json, Value};
+
+use crate::collector::{json_to_py, now, record, Record, Sampler};
+
+/// A connected USB device.
+#[derive(Clone)]
+pub(crate) struct UsbDevice {
+    name: String,
+    vendor: Option<String>,
+    vendor_id: Option<String>,
+    product_id: Option<String>,
+    serial: Option<String>,
+    location: String,
+}
+
+impl UsbDevice {
+    /// Identity across polls: the same device replugged into another port is a
+    /// detach plus an attach, which is what happened.
+    fn key(&self) -> String {
+        format!(
+            "{}|{}|{}|{}",
+            self.vendor_id.as_deref().unwrap_or_default(),
+            self.product_id.as_deref().unwrap_or_default(),
+            self.serial.as_deref().unwrap_or_default(),
+            self.location
+        )
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "name": self.name,
+            "vendor": self.vendor,
+            "vendor_id": self.vendor_id,
+            "product_id": self.product_id,
+            "serial": self.serial,
+            "location": self.location,
+        })
+    }
+}
+
+/// "0x05ac  (Apple Inc.)" -> ("05ac", Some("Apple Inc.")).
+#[cfg(target_os = "macos")]
+fn split_id(value: &str) -> (String, Option<String>) {
+    let (id, rest) = value.split_once(' ').unwrap_or((value, ""));
+    let label = rest.trim().strip_prefix('(').and_then(|r| r.strip_suffix(')'));
+    (id.trim_start_matches("0x").to_lowercase(), label.map(str::to_string))
+}
+
+/// Devices from `system_profiler -json`, walking each bus's nested _items (hubs).
+#[cfg(target_os = "macos")]
+fn list_devices() -> Result<Vec<UsbDevice>, String> {
+    fn walk(items: &[Value], out: &mut Vec<UsbDevice>) {
+        for item in items {
+            let text = |key: &str| item.get(key).and_then(Value::as_str);
+            if let Some(location) = text("location_id") {
+                let (vendor_id, vendor_label) = text("vendor_id").map(split_id).unzip();
+                out.push(UsbDevice {
+                    name: text("_name").unwrap_or_default().to_string(),
+                    vendor: text("manufacturer").map(str::to_string).or(vendor_label.flatten()),
+                    vendor_id,
+                    product_id: text("product_id").map(|p| split_id(p).0),
+                    serial: text("serial_num").map(str::to_string),
+                    location: location.to_string(),
+                });
+            }
+            if let Some(children) = item.get("_items").and_then(Value::as_array) {
+                walk(children, out);
+            }
+        }
+    }
+
+    // macOS 15 moved USB to SPUSBHostDataType; older releases only have SPUSBDataType.
+    let output = std::process::Command::new("system_profiler")
+        .args(["-json", "SPUSBDataType", "SPUSBHostDataType"])
+        .output()
+        .map_err(|e| format!("system_profiler: {e}"))?;
+    let report: Value = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("system_profiler output: {e}"))?;
+    let mut devices = Vec::new();
+    for key in ["SPUSBDataType", "SPUSBHostDataType"] {
+        if let Some(buses) = report.get(key).and_then(Value::as_array) {
+            for bus in buses {
+                if let Some(items) = bus.get("_items").and_then(Value::as_array) {
+                    walk(items, &mut devices);
+                }
+            }
+        }
+    }
+    Ok(devices)
+}
+
+/// Devices from sysfs: every /sys/bus/usb/devices entry with an idVendor, skipping
+/// the root hubs the host controllers register.
+#[cfg(not(target_os = "macos"))]
+fn list_devices() -> Result<Vec<UsbDevice>, String> {
+    let Ok(entries) = std::fs::read_dir("/sys/bus/usb/devices") else {
+        return Ok(Vec::new());
+    };
+    let mut devices = Vec::new();
+    for entry in entries.flatten() {
+        let location = entry.file_name().to_string_lossy().into_owned();
+        if location.starts_with("usb") {
+            continue;
+        }
+        let dir = entry.path();
+        let attr = |name: &str| {
+            std::fs::read_to_string(dir.join(name))
+                .ok()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        let Some(vendor_id) = attr("idVendor") else { continue };
+        devices.push(UsbDevice {
+            name: attr("product").unwrap_or_else(|| format!("{vendor_id}:device")),
+            vendor: attr("manufacturer"),
+            vendor_id: Some(vendor_id),
+            product_id: attr("idProduct"),
+            serial: attr("serial"),
+            location,
+        });
+    }
+    Ok(devices)
+}
+
+/// Emits "attached" and "detached" events by diffing the device list each poll.
+#[derive(Default)]
+pub(crate) struct UsbSampler {
+    known: Option<HashMap<String, UsbDevice>>,
+}
+
+impl Sampler for UsbSampler {
+    fn name(&self) -> &'static str {
+        "usb"
+    }
+
+    fn sample(&mut self) -> Result<Vec<Record>, String> {
+        let current: HashMap<String, UsbDevice> =
+            list_devices()?.into_iter().map(|d| (d.key(), d)).collect();
+        // The first poll only establishes what was already plugged in.
+        let Some(known) = self.known.replace(current.clone()) else {
+            return Ok(Vec::new());
+        };
+        let ts = now();
+        let event = |kind: &str, device: &UsbDevice| {
+            let mut fields = device.to_json();
+            fields["kind"] = json!(kind);
+            fields["timestamp"] = json!(ts);
+            fields["summary"] = json!(format!("{kind} {}", device.name));
+            record("usb", fields)
+        };
+        let mut events: Vec<Record> = known
+            .iter()
+            .filter(|(key, _)| !current.contains_key(*key))
+            .map(|(_, d)| event("detached", d))
+            .collect();
+        events.extend(
+            current
+                .iter()
+                .filter(|(key, _)| !known.contains_key(*key))
+                .map(|(_, d)| event("attached", d)),
+        );
+        Ok(events)
+    }
+}
+
+/// USB devices connected right now: {name, vendor, vendor_id, product_id, serial,
+/// location}.
+///
+/// vendor_id and product_id are lowercase hex without "0x". Uses system_profiler on
+/// macOS and sysfs on Linux. Collector(sources=["usb"]) reports attach and detach
+/// events as they happen. Raises RuntimeError if the device list can't be read.
+#[pyfunction]
+pub fn usb_devices(py: Python<'_>) -> PyResult<Bound<'_, PyList>> {
+    let devices = py.detach(list_devices).map_err(pyo3::exceptions::PyRuntimeError::new_err)?;
+    let list = PyList::empty(py);
+    for device in devices {
+        list.append(json_to_py(py, &device.to_json())?)?;
+    }
+    Ok(list)
+}
@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+use rusqlite::{Connection, OptionalExtension};
+use serde_json::{json, Value};
+
+use crate::collector::{home_path, record, records_to_py, Record};
+use crate::history::with_snapshot;
+
+#[cfg(target_os = "macos")]
+const USER_DIR: &str = "Library/Application Support/Code/User";
+#[cfg(not(target_os = "macos"))]
+const USER_DIR: &str = ".config/Code/User";
+
+/// Split a VS Code URI into (remote authority, path): "file:///Users/me/x" is local,
+/// "vscode-remote://ssh-remote+box/home/me/x" is on "ssh-remote+box".
+fn uri_path(uri: &str) -> (Option<String>, String) {
+    let (remote, path) = match uri.split_once("://") {
+        Some(("file", rest)) => (None, rest.to_string()),
+        Some((_, rest)) => {
+            let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+            (Some(authority.to_string()), format!("/{path}"))
+        }
+        None => (None, uri.to_string()),
+    };
+    let path = percent_encoding::percent_decode_str(&path).decode_utf8_lossy().into_owned();
+    (
+        remote.map(|r| percent_encoding::percent_decode_str(&r).decode_utf8_lossy().into_owned()),
+        path,
+    )
+}
+
+fn item(conn: &Connection, key: &str) -> rusqlite::Result<Option<Value>> {
+    // Values are JSON, stored as TEXT or as BLOB depending on the version.
+    let value: Option<Vec<u8>> = conn
+        .query_row("SELECT value FROM ItemTable WHERE key = ?", [key], |row| {
+            Ok(row.get_ref(0)?.as_bytes().map(<[u8]>::to_vec).unwrap_or_default())
+        })
+        .optional()?;
+    Ok(value.and_then(|v| serde_json::from_slice(&v).ok()))
+}
+
+fn modified(path: &Path) -> Option<f64> {
+    let time = std::fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    Some(time.duration_since(std::time::UNIX_EPOCH).ok()?.as_secs_f64())
+}
+
+/// Each workspaceStorage/<id>/ folder belongs to one folder or .code-workspace file;
+/// its state.vscdb is written whenever that window is used. Keyed by URI.
+fn workspace_storage(user_dir: &Path) -> HashMap<String, f64> {
+    let mut used = HashMap::new();
+    let Ok(entries) = std::fs::read_dir(user_dir.join("workspaceStorage")) else { return used };
+    for entry in entries.flatten() {
+        let dir = entry.path();
+        let Ok(text) = std::fs::read_to_string(dir.join("workspace.json")) else { continue };
+        let Ok(meta) = serde_json::from_str::<Value>(&text) else { continue };
+        let Some(uri) = meta["folder"].as_str().or(meta["workspace"].as_str()) else { continue };
+        let Some(ts) = modified(&dir.join("state.vscdb")).or_else(|| modified(&dir)) else {
+            continue;
+        };
+        let last = used.entry(uri.to_string()).or_insert(ts);
+        *last = last.max(ts);
+    }
+    used
+}
+
+fn read_activity(user_dir: &Path, since: f64) -> PyResult<Vec<Record>> {
+    let (recent, commands) = with_snapshot(&user_dir.join("globalStorage/state.vscdb"), |conn| {
+        Ok((
+            item(conn, "history.recentlyOpenedPathsList")?,
+            item(conn, "terminal.history.entries.commands")?,
+        ))
+    })?;
+    let mut used = workspace_storage(user_dir);
+    let mut events = Vec::new();
+    let keep = |ts: Option<f64>| ts.map_or(since == f64::NEG_INFINITY, |ts| ts > since);
+
+    // Most recent first; folders and .code-workspace files are workspaces.
+    let entries =
+        recent.as_ref().and_then(|r| r["entries"].as_array()).cloned().unwrap_or_default();
+    let mut folders: Vec<String> = Vec::new();
+    for (rank, entry) in entries.iter().enumerate() {
+        let folder = entry["folderUri"].as_str();
+        let workspace = entry["workspace"]["configPath"].as_str();
+        if let Some(uri) = folder.or(workspace) {
+            let (remote, path) = uri_path(uri);
+            let ts = used.remove(uri);
+            if folder.is_some() {
+                folders.push(path.clone());
+            }
+            if keep(ts) {
+                events.push(record(
+                    "vscode",
+                    json!({
+                        "timestamp": ts,
+                        "kind": "workspace",
+                        "project_path": path,
+                        "workspace_file": workspace.map(|_| &path),
+                        "remote": remote,
+                        "rank": rank,
+                    }),
+                ));
+            }
+        } else if let Some(uri) = entry["fileUri"].as_str() {
+            let (remote, path) = uri_path(uri);
+            let project = folders
+                .iter()
+                .filter(|f| path.starts_with(&format!("{f}/")))
+                .max_by_key(|f| f.len());
+            if keep(None) {
+                events.push(record(
+                    "vscode",
+                    json!({
+                        "timestamp": null,
+                        "kind": "file",
+                        "path": path,
+                        "project_path": project,
+                        "remote": remote,
+                        "rank": rank,
+                    }),
+                ));
+            }
+        }
+    }
+    // Windows opened long enough ago to have dropped off the recent list.
+    let mut older: Vec<(String, f64)> = used.into_iter().filter(|(_, ts)| *ts > since).collect();
+    older.sort_by(|a, b| b.1.total_cmp(&a.1));
+    for (uri, ts) in older {
+        let (remote, path) = uri_path(&uri);
+        let workspace = path.ends_with(".code-workspace").then(|| path.clone());
+        events.push(record(
+            "vscode",
+            json!({
+                "timestamp": ts,
+                "kind": "workspace",
+                "project_path": path,
+                "workspace_file": workspace,
+                "remote": remote,
+                "rank": null,
+            }),
+        ));
+    }
+
+    // The terminal history is stored oldest first.
+    let commands =
+        commands.as_ref().and_then(|c| c["entries"].as_array()).cloned().unwrap_or_default();
+    if keep(None) {
+        for (rank, entry) in commands.iter().rev().enumerate() {
+            let Some(command) = entry["key"].as_str() else { continue };
+            events.push(record(
+                "vscode",
+                json!({
+                    "timestamp": null,
+                    "kind": "terminal_command",
+                    "command": command,
+                    "shell": entry["value"]["shellType"],
+                    "rank": rank,
+                }),
+            ));
+        }
+    }
+    Ok(events)
+}
+
+/// Recent VS Code activity from its state.vscdb and workspaceStorage: workspaces,
+/// files and integrated-terminal commands.
+///
+/// Events are {source: "vscode", kind, timestamp, rank, ...}. kind "workspace" has
+/// project_path (the folder, or the .code-workspace file), workspace_file and
+/// remote (e.g. "ssh-remote+host" for Remote windows, else None); its timestamp is
+/// when that window last saved state. kind "file" has path and project_path (the
+/// recent folder containing it, if any). kind "terminal_command" has command and
+/// shell. rank orders the recent list (0 is most recent); files and commands have
+/// no timestamp in VS Code's storage, so theirs is None. The recent list comes
+/// first, in its order, then older workspaces by timestamp, then terminal commands.
+/// project_path matches the field transcript events carry, so editor activity can
+/// be grouped by project alongside them. user_dir defaults to the Code user
+/// directory; pass another (e.g. VSCodium's or Cursor's) to read that editor. With
+/// since, only timestamped events after it are returned. Raises IOError if the
+/// state database can't be read.
+#[pyfunction]
+#[pyo3(signature = (user_dir=None, since=None))]
+pub fn vscode_activity(
+    py: Python<'_>,
+    user_dir: Option<PathBuf>,
+    since: Option<f64>,
+) -> PyResult<Bound<'_, PyList>> {
+    let user_dir = user_dir.unwrap_or_else(|| home_path(USER_DIR));
+    let since = since.unwrap_or(f64::NEG_INFINITY);
+    let events = py.detach(|| read_activity(&user_dir, since))?;
+    records_to_py(py, events)
+}
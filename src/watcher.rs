@@ -0,0 +1,447 @@
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use notify::event::{EventKind, ModifyKind, RenameMode};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher as _};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList, PyTuple};
+
+use crate::aio::{self, Next};
+use crate::errors::io_error;
+use crate::shutdown::{deadline, join_within, CLOSE_TIMEOUT};
+
+/// How often the debounce thread wakes to flush settled paths.
+const TICK: Duration = Duration::from_millis(50);
+
+pub(crate) struct Change {
+    pub(crate) path: PathBuf,
+    pub(crate) kind: &'static str,
+    pub(crate) timestamp: f64,
+}
+
+impl Change {
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("path", self.path.to_string_lossy())?;
+        dict.set_item("kind", self.kind)?;
+        dict.set_item("timestamp", self.timestamp)?;
+        Ok(dict)
+    }
+}
+
+fn change_kind(kind: &EventKind) -> Option<&'static str> {
+    match kind {
+        EventKind::Create(_) => Some("created"),
+        EventKind::Remove(_) => Some("removed"),
+        EventKind::Modify(ModifyKind::Name(RenameMode::From)) => Some("removed"),
+        EventKind::Modify(ModifyKind::Name(_)) => Some("created"),
+        EventKind::Modify(ModifyKind::Metadata(_)) => None,
+        EventKind::Modify(_) | EventKind::Any | EventKind::Other => Some("modified"),
+        EventKind::Access(_) => None,
+    }
+}
+
+/// Combine a burst of events on one path into the kind reported after debouncing.
+fn merge_kind(prev: &'static str, next: &'static str) -> &'static str {
+    match (prev, next) {
+        ("created", "modified") => "created",
+        ("removed", "created") => "modified",
+        (_, next) => next,
+    }
+}
+
+fn build_globset(patterns: &[String]) -> PyResult<Option<GlobSet>> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+    let mut builder = GlobSetBuilder::new();
+    for p in patterns {
+        let glob = Glob::new(p);
+        builder.add(glob.map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?);
+    }
+    builder
+        .build()
+        .map(Some)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+/// Include/exclude globs, matched against the full path or the file name.
+struct PathFilter {
+    include: Option<GlobSet>,
+    exclude: Option<GlobSet>,
+}
+
+impl PathFilter {
+    fn matches(&self, path: &std::path::Path) -> bool {
+        let name = path.file_name().map(std::path::Path::new);
+        let hit = |set: &GlobSet| set.is_match(path) || name.is_some_and(|n| set.is_match(n));
+        self.include.as_ref().is_none_or(hit) && !self.exclude.as_ref().is_some_and(hit)
+    }
+}
+
+#[derive(Default)]
+struct Outbox {
+    changes: VecDeque<Change>,
+    error: Option<String>,
+}
+
+struct Shared {
+    outbox: Mutex<Outbox>,
+    ready: Condvar,
+}
+
+impl Shared {
+    /// Take the oldest queued change, waiting up to wait for one.
+    fn next_change(&self, wait: Duration, stopped: &AtomicBool) -> PyResult<Next<Change>> {
+        let mut outbox = self.outbox.lock().unwrap_or_else(|e| e.into_inner());
+        if outbox.changes.is_empty() && outbox.error.is_none() && !wait.is_zero() {
+            outbox = match self.ready.wait_timeout(outbox, wait) {
+                Ok((guard, _)) => guard,
+                Err(e) => e.into_inner().0,
+            };
+        }
+        if let Some(e) = outbox.error.take() {
+            return Err(pyo3::exceptions::PyIOError::new_err(e));
+        }
+        Ok(match outbox.changes.pop_front() {
+            Some(change) => Next::Item(change),
+            None if stopped.load(Ordering::Relaxed) => Next::Closed,
+            None => Next::Empty,
+        })
+    }
+}
+
+fn now() -> f64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs_f64()).unwrap_or(0.0)
+}
+
+fn deliver(changes: Vec<Change>, callback: &Option<Py<PyAny>>, shared: &Shared) {
+    if changes.is_empty() {
+        return;
+    }
+    if let Some(callback) = callback {
+        Python::attach(|py| {
+            let batch = PyList::empty(py);
+            for change in &changes {
+                if let Ok(d) = change.to_dict(py) {
+                    let _ = batch.append(d);
+                }
+            }
+            if let Err(e) = callback.call1(py, (batch,)) {
+                e.print(py);
+            }
+        });
+        return;
+    }
+    let mut outbox = shared.outbox.lock().unwrap_or_else(|e| e.into_inner());
+    outbox.changes.extend(changes);
+    shared.ready.notify_all();
+}
+
+/// Collect raw notify events, wait for each path to settle, then deliver it once.
+fn debounce_loop(
+    raw: Receiver<notify::Result<notify::Event>>,
+    filter: PathFilter,
+    debounce: Duration,
+    callback: Option<Py<PyAny>>,
+    shared: Arc<Shared>,
+    stop: Arc<AtomicBool>,
+) {
+    let mut pending: HashMap<PathBuf, (&'static str, Instant)> = HashMap::new();
+    while !stop.load(Ordering::Relaxed) {
+        match raw.recv_timeout(TICK) {
+            Ok(Ok(event)) => {
+                if let Some(kind) = change_kind(&event.kind) {
+                    for path in event.paths.into_iter().filter(|p| filter.matches(p)) {
+                        let entry = pending.entry(path).or_insert((kind, Instant::now()));
+                        *entry = (merge_kind(entry.0, kind), Instant::now());
+                    }
+                }
+            }
+            Ok(Err(e)) => {
+                tracing::warn!("watch error: {e}");
+                let mut outbox = shared.outbox.lock().unwrap_or_else(|e| e.into_inner());
+                outbox.error = Some(e.to_string());
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+        let settled: Vec<PathBuf> = pending
+            .iter()
+            .filter(|(_, (_, seen))| seen.elapsed() >= debounce)
+            .map(|(p, _)| p.clone())
+            .collect();
+        let mut changes: Vec<Change> = settled
+            .into_iter()
+            .filter_map(|path| {
+                let (kind, _) = pending.remove(&path)?;
+                Some(Change {
+                    path,
+                    kind,
+                    timestamp: now(),
+                })
+            })
+            .collect();
+        changes.sort_by(|a, b| a.path.cmp(&b.path));
+        deliver(changes, &callback, &shared);
+    }
+}
+
+/// The IOError for a path that couldn't be watched, or PermissionDenied.
+fn watch_error(path: &Path, e: notify::Error) -> PyErr {
+    match e.kind {
+        notify::ErrorKind::Io(e) => io_error(path, e),
+        notify::ErrorKind::PathNotFound => io_error(path, std::io::ErrorKind::NotFound.into()),
+        kind => pyo3::exceptions::PyIOError::new_err(format!(
+            "{}: {}",
+            path.display(),
+            notify::Error::new(kind)
+        )),
+    }
+}
+
+/// Native file watcher (FSEvents on macOS, inotify on Linux, kqueue on BSD).
+///
+/// Watches paths for changes whose path or file name matches one of patterns and
+/// none of ignore (globs, e.g. "*.jsonl"). Bursts of events on a path are merged and
+/// reported once the path has been quiet for debounce seconds, as
+/// {path, kind, timestamp} with kind "created", "modified" or "removed". Changes go to
+/// callback (a list per flush, called from a background thread) if given, otherwise
+/// they queue up for poll(), or one at a time for next_event() and `async for`.
+/// Safe to share between threads: one can poll while another stops it. As a context
+/// manager it starts on entry and closes on exit.
+#[pyclass(frozen)]
+pub struct Watcher {
+    paths: Vec<PathBuf>,
+    recursive: bool,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    debounce: Duration,
+    callback: Option<Py<PyAny>>,
+    shared: Arc<Shared>,
+    stop: Arc<AtomicBool>,
+    /// The OS watch while running.
+    os_watch: Mutex<Option<RecommendedWatcher>>,
+    /// The debounce thread, until it has been joined.
+    thread: Mutex<Option<JoinHandle<()>>>,
+}
+
+#[pymethods]
+impl Watcher {
+    #[new]
+    #[pyo3(signature = (
+        paths, patterns=None, ignore=None, debounce=0.5, recursive=true, callback=None
+    ))]
+    pub(crate) fn new(
+        paths: Vec<PathBuf>,
+        patterns: Option<Vec<String>>,
+        ignore: Option<Vec<String>>,
+        debounce: f64,
+        recursive: bool,
+        callback: Option<Py<PyAny>>,
+    ) -> PyResult<Self> {
+        let debounce = Duration::try_from_secs_f64(debounce).map_err(|_| {
+            pyo3::exceptions::PyValueError::new_err("debounce must be a finite number >= 0")
+        })?;
+        let (include, exclude) = (patterns.unwrap_or_default(), ignore.unwrap_or_default());
+        // Validate globs up front rather than on start().
+        build_globset(&include)?;
+        build_globset(&exclude)?;
+        Ok(Watcher {
+            paths,
+            recursive,
+            include,
+            exclude,
+            debounce,
+            callback,
+            shared: Arc::new(Shared {
+                outbox: Mutex::new(Outbox::default()),
+                ready: Condvar::new(),
+            }),
+            // Stopped until start(), so `async for` on an idle watcher ends at once.
+            stop: Arc::new(AtomicBool::new(true)),
+            os_watch: Mutex::new(None),
+            thread: Mutex::new(None),
+        })
+    }
+
+    /// Begin watching. Raises IOError if a path can't be watched.
+    pub(crate) fn start(&self, py: Python<'_>) -> PyResult<()> {
+        let mut thread = self.thread.lock().unwrap_or_else(|e| e.into_inner());
+        if thread.is_some() {
+            return Err(pyo3::exceptions::PyRuntimeError::new_err("watcher already running"));
+        }
+        let (tx, rx) = channel();
+        let mut watcher = notify::recommended_watcher(tx)
+            .map_err(|e| pyo3::exceptions::PyIOError::new_err(e.to_string()))?;
+        let mode = if self.recursive {
+            RecursiveMode::Recursive
+        } else {
+            RecursiveMode::NonRecursive
+        };
+        for path in &self.paths {
+            watcher.watch(path, mode).map_err(|e| watch_error(path, e))?;
+        }
+        let filter = PathFilter {
+            include: build_globset(&self.include)?,
+            exclude: build_globset(&self.exclude)?,
+        };
+        self.stop.store(false, Ordering::Relaxed);
+        let (shared, stop) = (Arc::clone(&self.shared), Arc::clone(&self.stop));
+        let debounce = self.debounce;
+        let callback = self.callback.as_ref().map(|c| c.clone_ref(py));
+        let handle = std::thread::Builder::new()
+            .name("snoopy-watcher".to_string())
+            .spawn(move || debounce_loop(rx, filter, debounce, callback, shared, stop))
+            .map_err(pyo3::exceptions::PyRuntimeError::new_err)?;
+        *self.os_watch.lock().unwrap_or_else(|e| e.into_inner()) = Some(watcher);
+        *thread = Some(handle);
+        Ok(())
+    }
+
+    /// Stop watching and wait for the debounce thread. Pending changes are discarded.
+    pub(crate) fn stop(&self, py: Python<'_>) {
+        self.close_until(py, None);
+    }
+
+    /// Stop watching and wait up to timeout seconds (None: forever) for the debounce
+    /// thread. Returns whether it exited; if not, the watcher still counts as running
+    /// and a later close() or stop() waits again. Raises ValueError for a NaN timeout.
+    #[pyo3(signature = (timeout=CLOSE_TIMEOUT))]
+    pub(crate) fn close(&self, py: Python<'_>, timeout: Option<f64>) -> PyResult<bool> {
+        Ok(self.close_until(py, deadline(timeout)?))
+    }
+
+    /// Start watching unless already running.
+    fn __enter__(slf: PyRef<'_, Self>) -> PyResult<PyRef<'_, Self>> {
+        if !slf.running() {
+            slf.start(slf.py())?;
+        }
+        Ok(slf)
+    }
+
+    /// close() with the default timeout. Exceptions propagate.
+    #[pyo3(signature = (*_args))]
+    fn __exit__(&self, py: Python<'_>, _args: &Bound<'_, PyTuple>) -> PyResult<bool> {
+        self.close(py, Some(CLOSE_TIMEOUT))?;
+        Ok(false)
+    }
+
+    /// Return queued changes, waiting up to timeout seconds for at least one.
+    ///
+    /// timeout=None waits indefinitely (Ctrl-C still interrupts). Raises IOError if
+    /// the underlying watcher reported an error since the last poll.
+    #[pyo3(signature = (timeout=0.0))]
+    fn poll<'py>(&self, py: Python<'py>, timeout: Option<f64>) -> PyResult<Bound<'py, PyList>> {
+        let out = PyList::empty(py);
+        for change in self.wait(py, timeout)? {
+            out.append(change.to_dict(py)?)?;
+        }
+        Ok(out)
+    }
+
+    /// Await the next change, or None once timeout seconds pass or the watcher stops.
+    ///
+    /// For asyncio code: the wait happens off the event loop. Raises IOError as
+    /// poll() does, and RuntimeError if changes go to a callback instead.
+    #[pyo3(signature = (timeout=None))]
+    fn next_event<'py>(
+        &self,
+        py: Python<'py>,
+        timeout: Option<f64>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        self.next_future(py, timeout, false)
+    }
+
+    fn __aiter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    /// `async for change in watcher` yields changes until stop().
+    fn __anext__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        self.next_future(py, None, true)
+    }
+
+    #[getter]
+    pub(crate) fn running(&self) -> bool {
+        self.thread.lock().unwrap_or_else(|e| e.into_inner()).is_some()
+    }
+}
+
+impl Watcher {
+    fn next_future<'py>(
+        &self,
+        py: Python<'py>,
+        timeout: Option<f64>,
+        anext: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        if self.callback.is_some() {
+            return Err(pyo3::exceptions::PyRuntimeError::new_err(
+                "changes go to the callback; nothing to await",
+            ));
+        }
+        let (shared, stop) = (Arc::clone(&self.shared), Arc::clone(&self.stop));
+        aio::next_future(
+            py,
+            timeout,
+            anext,
+            move |wait| shared.next_change(wait, &stop),
+            |py, change| Ok(change.to_dict(py)?.into_any().unbind()),
+        )
+    }
+
+    /// close() with a deadline already worked out.
+    pub(crate) fn close_until(&self, py: Python<'_>, deadline: Option<Instant>) -> bool {
+        self.stop.store(true, Ordering::Relaxed);
+        drop(self.os_watch.lock().unwrap_or_else(|e| e.into_inner()).take());
+        let thread = self.thread.lock().unwrap_or_else(|e| e.into_inner()).take();
+        let pending = py.detach(|| join_within(thread.into_iter().collect(), deadline));
+        match pending.into_iter().next() {
+            None => true,
+            Some(thread) => {
+                self.thread.lock().unwrap_or_else(|e| e.into_inner()).get_or_insert(thread);
+                false
+            }
+        }
+    }
+
+    /// poll() without the conversion, for monitors built on a Watcher.
+    pub(crate) fn wait(&self, py: Python<'_>, timeout: Option<f64>) -> PyResult<Vec<Change>> {
+        let deadline = deadline(timeout)?;
+        loop {
+            let (changes, error) = py.detach(|| {
+                let mut outbox = self.shared.outbox.lock().unwrap_or_else(|e| e.into_inner());
+                if outbox.changes.is_empty() && outbox.error.is_none() {
+                    let wait = match deadline {
+                        Some(d) => d.saturating_duration_since(Instant::now()).min(TICK * 2),
+                        None => TICK * 2,
+                    };
+                    outbox = match self.shared.ready.wait_timeout(outbox, wait) {
+                        Ok((guard, _)) => guard,
+                        Err(e) => e.into_inner().0,
+                    };
+                }
+                (outbox.changes.drain(..).collect::<Vec<_>>(), outbox.error.take())
+            });
+            if let Some(e) = error {
+                return Err(pyo3::exceptions::PyIOError::new_err(e));
+            }
+            if !changes.is_empty() || deadline.is_some_and(|d| Instant::now() >= d) {
+                return Ok(changes);
+            }
+            py.check_signals()?;
+        }
+    }
+}
+
+impl Drop for Watcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
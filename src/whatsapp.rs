@@ -0,0 +1,89 @@
+use std::path::PathBuf;
+
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+use rusqlite::Connection;
+use serde_json::json;
+
+use crate::collector::{home_path, record, records_to_py, Record, CONTENT_PREVIEW_LEN};
+use crate::history::{has_table, with_snapshot};
+
+/// WhatsApp stores times as seconds since 2001-01-01 (Cocoa reference date).
+const COCOA_EPOCH_OFFSET: f64 = 978_307_200.0;
+const CHAT_STORAGE: &str =
+    "Library/Group Containers/group.net.whatsapp.WhatsApp.shared/ChatStorage.sqlite";
+/// ZMESSAGETYPE values that carry no media: plain text and system notices.
+const TEXT_MESSAGE_TYPES: [i64; 2] = [0, 6];
+
+fn read_messages(conn: &Connection, since: f64) -> rusqlite::Result<Vec<Record>> {
+    // Group senders live in ZWAGROUPMEMBER; older stores don't have it.
+    let (member_join, member) = if has_table(conn, "ZWAGROUPMEMBER")? {
+        (
+            "LEFT JOIN ZWAGROUPMEMBER g ON g.Z_PK = m.ZGROUPMEMBER",
+            "COALESCE(NULLIF(g.ZCONTACTNAME, ''), g.ZMEMBERJID)",
+        )
+    } else {
+        ("", "NULL")
+    };
+    let mut stmt = conn.prepare(&format!(
+        "SELECT m.ZMESSAGEDATE, m.ZISFROMME, m.ZTEXT, m.ZMESSAGETYPE, m.ZMEDIAITEM,
+                {member}, m.ZFROMJID, s.ZCONTACTJID,
+                COALESCE(NULLIF(s.ZPARTNERNAME, ''), s.ZCONTACTJID, '')
+         FROM ZWAMESSAGE m
+         LEFT JOIN ZWACHATSESSION s ON s.Z_PK = m.ZCHATSESSION
+         {member_join}
+         WHERE m.ZMESSAGEDATE > ?
+         ORDER BY m.ZMESSAGEDATE, m.Z_PK"
+    ))?;
+    let rows = stmt.query_map([since - COCOA_EPOCH_OFFSET], |row| {
+        let is_from_me = row.get::<_, Option<i64>>(1)?.unwrap_or(0);
+        let message_type = row.get::<_, Option<i64>>(3)?.unwrap_or(0);
+        let has_media =
+            row.get::<_, Option<i64>>(4)?.is_some() || !TEXT_MESSAGE_TYPES.contains(&message_type);
+        // The other party: the chat for outgoing messages, otherwise the group member
+        // or sender JID.
+        let contact = if is_from_me != 0 {
+            row.get::<_, Option<String>>(7)?
+        } else {
+            row.get::<_, Option<String>>(5)?.or(row.get::<_, Option<String>>(6)?)
+        };
+        let mut content = row.get::<_, Option<String>>(2)?.unwrap_or_default();
+        if content.is_empty() && has_media {
+            content = "[attachment]".to_string();
+        }
+        Ok(record(
+            "messages",
+            json!({
+                "timestamp": row.get::<_, f64>(0)? + COCOA_EPOCH_OFFSET,
+                "contact": contact.unwrap_or_default(),
+                "is_from_me": is_from_me,
+                "content_preview": crate::truncate_str(&content, CONTENT_PREVIEW_LEN),
+                "has_attachment": i64::from(has_media),
+                "service": "WhatsApp",
+                "chat_name": row.get::<_, String>(8)?,
+            }),
+        ))
+    })?;
+    rows.collect()
+}
+
+/// Read messages from WhatsApp's ChatStorage.sqlite (macOS app or an iOS backup).
+///
+/// Rows use the same schema as the Collector's "messages" (iMessage) source:
+/// {source: "messages", timestamp, contact, is_from_me, content_preview,
+/// has_attachment, service: "WhatsApp", chat_name}, oldest first, so both apps feed
+/// one message stream. Media messages without a caption read "[attachment]". Only
+/// messages after since (epoch seconds) are returned. path defaults to the macOS
+/// app's group container.
+#[pyfunction]
+#[pyo3(signature = (path=None, since=None))]
+pub fn whatsapp_messages<'py>(
+    py: Python<'py>,
+    path: Option<PathBuf>,
+    since: Option<f64>,
+) -> PyResult<Bound<'py, PyList>> {
+    let path = path.unwrap_or_else(|| home_path(CHAT_STORAGE));
+    let since = since.unwrap_or(f64::NEG_INFINITY);
+    let events = py.detach(|| with_snapshot(&path, |conn| read_messages(conn, since)))?;
+    records_to_py(py, events)
+}
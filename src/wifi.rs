@@ -0,0 +1,142 @@
+use std::process::Command;
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use serde_json::json;
+
+use crate::collector::{now, record, Record, Sampler};
+
+/// The Wi-Fi network an interface is associated with.
+#[derive(Clone, PartialEq)]
+pub(crate) struct Network {
+    interface: String,
+    ssid: String,
+    bssid: Option<String>,
+}
+
+fn run(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// The value of a "  KEY : value" line in `ipconfig getsummary` output.
+#[cfg(target_os = "macos")]
+fn summary_field<'a>(summary: &'a str, key: &str) -> Option<&'a str> {
+    summary
+        .lines()
+        .filter_map(|l| l.trim().split_once(" : "))
+        .find(|(k, _)| k.trim() == key)
+        .map(|(_, v)| v.trim())
+        .filter(|v| !v.is_empty() && *v != "<redacted>")
+}
+
+/// The associated network from `ipconfig getsummary` on the Wi-Fi device that
+/// `networksetup -listallhardwareports` reports. BSSIDs need Location Services
+/// permission and otherwise come back redacted (None).
+#[cfg(target_os = "macos")]
+fn current_network() -> Option<Network> {
+    let ports = run("networksetup", &["-listallhardwareports"]).unwrap_or_default();
+    let interface = ports
+        .split("\n\n")
+        .find(|block| block.contains("Hardware Port: Wi-Fi"))
+        .and_then(|block| block.lines().find_map(|l| l.strip_prefix("Device: ")))
+        .unwrap_or("en0")
+        .trim()
+        .to_string();
+    let summary = run("ipconfig", &["getsummary", &interface])?;
+    let ssid = summary_field(&summary, "SSID")?.to_string();
+    let bssid = summary_field(&summary, "BSSID").map(str::to_string);
+    Some(Network { interface, ssid, bssid })
+}
+
+/// Split one line of `nmcli -t` output on unescaped colons.
+#[cfg(not(target_os = "macos"))]
+fn terse_fields(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => fields.last_mut().unwrap().extend(chars.next()),
+            ':' => fields.push(String::new()),
+            _ => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields
+}
+
+/// The active access point according to NetworkManager.
+#[cfg(not(target_os = "macos"))]
+fn current_network() -> Option<Network> {
+    let list = run(
+        "nmcli",
+        &["-t", "-f", "ACTIVE,SSID,BSSID,DEVICE", "device", "wifi", "list", "--rescan", "no"],
+    )?;
+    list.lines().map(terse_fields).find(|f| f.len() == 4 && f[0] == "yes").map(|f| Network {
+        interface: f[3].clone(),
+        ssid: f[1].clone(),
+        bssid: Some(f[2].to_lowercase()).filter(|b| !b.is_empty()),
+    })
+}
+
+/// Emits "joined" when the SSID changes, "roamed" when only the access point
+/// (BSSID) does, and "disconnected" when Wi-Fi drops.
+#[derive(Default)]
+pub(crate) struct WifiSampler {
+    current: Option<(Network, f64)>,
+}
+
+impl Sampler for WifiSampler {
+    fn name(&self) -> &'static str {
+        "wifi"
+    }
+
+    fn sample(&mut self) -> Result<Vec<Record>, String> {
+        let network = current_network();
+        let previous = self.current.as_ref().map(|(n, _)| n);
+        let kind = match (previous, &network) {
+            (Some(old), Some(new)) if old == new => return Ok(Vec::new()),
+            (Some(old), Some(new)) if old.ssid == new.ssid => "roamed",
+            (_, Some(_)) => "joined",
+            (Some(_), None) => "disconnected",
+            (None, None) => return Ok(Vec::new()),
+        };
+        let ts = now();
+        let previous = std::mem::replace(&mut self.current, network.clone().map(|n| (n, ts)));
+        let summary = match &network {
+            Some(n) if kind == "joined" => format!("joined network {}", n.ssid),
+            Some(n) => format!("roamed to another access point on {}", n.ssid),
+            None => "disconnected from Wi-Fi".to_string(),
+        };
+        Ok(vec![record(
+            self.name(),
+            json!({
+                "kind": kind,
+                "timestamp": ts,
+                "interface": network.as_ref().map(|n| &n.interface),
+                "ssid": network.as_ref().map(|n| &n.ssid),
+                "bssid": network.as_ref().and_then(|n| n.bssid.as_ref()),
+                "previous_ssid": previous.as_ref().map(|(n, _)| &n.ssid),
+                "previous_bssid": previous.as_ref().and_then(|(n, _)| n.bssid.as_ref()),
+                "previous_duration": previous.as_ref().map(|(_, since)| ts - since),
+                "summary": summary,
+            }),
+        )])
+    }
+}
+
+/// The Wi-Fi network this machine is on right now, or None if not associated.
+///
+/// Returns {interface, ssid, bssid}. Uses `ipconfig getsummary` on macOS (the BSSID
+/// needs Location Services permission and is None without it) and NetworkManager's
+/// nmcli on Linux. Collector(sources=["wifi"]) reports changes as events.
+#[pyfunction]
+pub fn current_wifi(py: Python<'_>) -> PyResult<Option<Bound<'_, PyDict>>> {
+    let Some(network) = py.detach(current_network) else {
+        return Ok(None);
+    };
+    let dict = PyDict::new(py);
+    dict.set_item("interface", network.interface)?;
+    dict.set_item("ssid", network.ssid)?;
+    dict.set_item("bssid", network.bssid)?;
+    Ok(Some(dict))
+}
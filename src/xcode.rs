@@ -0,0 +1,195 @@
+use std::path::{Path, PathBuf};
+
+use plist::Value;
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+use serde_json::json;
+
+use crate::collector::{home_path, record, records_to_py, Record};
+use crate::history::sort_by_timestamp;
+
+const COCOA_EPOCH_OFFSET: f64 = 978_307_200.0;
+const DERIVED_DATA: &str = "Library/Developer/Xcode/DerivedData";
+
+/// DerivedData/<Name>-<hash>/ folders, one per project or workspace Xcode opened.
+fn project_dirs(derived_data: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(derived_data) else { return Vec::new() };
+    let mut dirs: Vec<PathBuf> =
+        entries.flatten().map(|e| e.path()).filter(|p| p.join("info.plist").is_file()).collect();
+    dirs.sort();
+    dirs
+}
+
+/// (WorkspacePath, LastAccessedDate) from a DerivedData folder's info.plist.
+fn project_info(dir: &Path) -> (Option<String>, Option<f64>) {
+    let Ok(info) = Value::from_file(dir.join("info.plist")) else { return (None, None) };
+    let Some(dict) = info.as_dictionary() else { return (None, None) };
+    let path = dict.get("WorkspacePath").and_then(Value::as_string).map(str::to_string);
+    let accessed = dict.get("LastAccessedDate").and_then(Value::as_date).and_then(|d| {
+        let time = std::time::SystemTime::from(d);
+        time.duration_since(std::time::UNIX_EPOCH).ok().map(|d| d.as_secs_f64())
+    });
+    (path, accessed)
+}
+
+fn project_name(path: &str) -> String {
+    let file = path.rsplit('/').next().unwrap_or(path);
+    file.rsplit_once('.').map_or(file, |(stem, _)| stem).to_string()
+}
+
+fn read_projects(derived_data: &Path) -> Vec<Record> {
+    let mut projects: Vec<Record> = project_dirs(derived_data)
+        .into_iter()
+        .filter_map(|dir| {
+            let (path, accessed) = project_info(&dir);
+            let path = path?;
+            Some(record(
+                "xcode",
+                json!({
+                    "timestamp": accessed,
+                    "kind": "project",
+                    "name": project_name(&path),
+                    "project_path": path,
+                    "derived_data": dir.to_string_lossy(),
+                }),
+            ))
+        })
+        .collect();
+    sort_by_timestamp(&mut projects);
+    projects.reverse();
+    projects
+}
+
+/// "Build MyApp" → "built", "Test MyApp" → "tested" and so on.
+fn past_tense(action: &str) -> String {
+    match action {
+        "Build" => "built".to_string(),
+        "Test" => "tested".to_string(),
+        "Clean" => "cleaned".to_string(),
+        "Archive" => "archived".to_string(),
+        "Analyze" => "analyzed".to_string(),
+        "Run" => "ran".to_string(),
+        other => other.to_lowercase(),
+    }
+}
+
+/// Each log listed in Logs/<kind>/LogStoreManifest.plist, which records a build's
+/// title, scheme, start and stop times and issue counts without opening the log.
+fn read_logs(dir: &Path, project_path: Option<&str>, since: f64, events: &mut Vec<Record>) {
+    for kind in ["Build", "Test"] {
+        let logs_dir = dir.join("Logs").join(kind);
+        let Ok(manifest) = Value::from_file(logs_dir.join("LogStoreManifest.plist")) else {
+            continue;
+        };
+        let Some(logs) = manifest.as_dictionary().and_then(|m| m.get("logs")) else { continue };
+        for log in logs.as_dictionary().into_iter().flat_map(|l| l.values()) {
+            let Some(log) = log.as_dictionary() else { continue };
+            let text = |key: &str| log.get(key).and_then(Value::as_string);
+            let real = |key: &str| {
+                log.get(key).and_then(|v| v.as_real().or(v.as_signed_integer().map(|i| i as f64)))
+            };
+            let Some(start) = real("timeStartedRecording").map(|t| t + COCOA_EPOCH_OFFSET) else {
+                continue;
+            };
+            if start <= since {
+                continue;
+            }
+            let end = real("timeStoppedRecording").map(|t| t + COCOA_EPOCH_OFFSET);
+            let observable = log.get("primaryObservable").and_then(Value::as_dictionary);
+            let count = |key: &str| {
+                observable.and_then(|o| o.get(key)).and_then(Value::as_signed_integer).unwrap_or(0)
+            };
+            let (errors, warnings) = (count("totalNumberOfErrors"), count("totalNumberOfWarnings"));
+            let status = match observable
+                .and_then(|o| o.get("highLevelStatus"))
+                .and_then(Value::as_string)
+            {
+                Some("S") => "succeeded",
+                Some("W") => "warnings",
+                Some("E") => "failed",
+                _ => "unknown",
+            };
+            let title = text("title").unwrap_or_default();
+            let (action, target) = title.split_once(' ').unwrap_or((title, ""));
+            let scheme = text("schemeIdentifier-schemeName").unwrap_or(target);
+            let mut summary = if status == "failed" {
+                format!("failed to {} {scheme}", action.to_lowercase())
+            } else {
+                format!("{} {scheme}", past_tense(action))
+            };
+            for (n, noun) in [(errors, "error"), (warnings, "warning")] {
+                if n > 0 {
+                    summary.push_str(&format!(", {n} {noun}{}", if n == 1 { "" } else { "s" }));
+                }
+            }
+            let log_path =
+                text("fileName").map(|f| logs_dir.join(f).to_string_lossy().into_owned());
+            events.push(record(
+                "xcode",
+                json!({
+                    "timestamp": start,
+                    "end": end,
+                    "duration": end.map(|e| e - start),
+                    "kind": action.to_lowercase(),
+                    "title": title,
+                    "scheme": scheme,
+                    "project_path": project_path,
+                    "status": status,
+                    "errors": errors,
+                    "warnings": warnings,
+                    "analyzer_issues": count("totalNumberOfAnalyzerIssues"),
+                    "test_failures": count("totalNumberOfTestFailures"),
+                    "log_path": log_path,
+                    "summary": summary,
+                }),
+            ));
+        }
+    }
+}
+
+/// Projects and workspaces Xcode has opened, most recently used first.
+///
+/// Read from each DerivedData folder's info.plist: {source: "xcode", kind:
+/// "project", timestamp (when Xcode last used it), name, project_path (the
+/// .xcodeproj, .xcworkspace or package directory), derived_data}. derived_data
+/// defaults to ~/Library/Developer/Xcode/DerivedData.
+#[pyfunction]
+#[pyo3(signature = (derived_data=None))]
+pub fn xcode_recent_projects(
+    py: Python<'_>,
+    derived_data: Option<PathBuf>,
+) -> PyResult<Bound<'_, PyList>> {
+    let derived_data = derived_data.unwrap_or_else(|| home_path(DERIVED_DATA));
+    let projects = py.detach(|| read_projects(&derived_data));
+    records_to_py(py, projects)
+}
+
+/// Builds and test runs from Xcode's DerivedData logs, oldest first.
+///
+/// Each log in a project's Logs/Build and Logs/Test store becomes {source: "xcode",
+/// timestamp, end, duration, kind ("build", "test", "clean", ...), title, scheme,
+/// project_path, status ("succeeded", "warnings", "failed" or "unknown"), errors,
+/// warnings, analyzer_issues, test_failures, log_path (the .xcactivitylog),
+/// summary}, e.g. summary "built MyApp, 42 warnings". derived_data defaults to
+/// ~/Library/Developer/Xcode/DerivedData. Only builds started after since (epoch
+/// seconds) are returned.
+#[pyfunction]
+#[pyo3(signature = (derived_data=None, since=None))]
+pub fn xcode_builds(
+    py: Python<'_>,
+    derived_data: Option<PathBuf>,
+    since: Option<f64>,
+) -> PyResult<Bound<'_, PyList>> {
+    let derived_data = derived_data.unwrap_or_else(|| home_path(DERIVED_DATA));
+    let since = since.unwrap_or(f64::NEG_INFINITY);
+    let events = py.detach(|| {
+        let mut events = Vec::new();
+        for dir in project_dirs(&derived_data) {
+            let (path, _) = project_info(&dir);
+            read_logs(&dir, path.as_deref(), since, &mut events);
+        }
+        sort_by_timestamp(&mut events);
+        events
+    });
+    records_to_py(py, events)
+}
@@ -0,0 +1,19 @@
+"""Fixtures shared across the test modules."""
+
+import json
+
+import pytest
+
+
+@pytest.fixture
+def write_transcript():
+    """Write entries to path as a JSONL transcript, creating its directory; returns path."""
+
+    def write(path, entries):
+        path.parent.mkdir(parents=True, exist_ok=True)
+        with open(path, "w") as f:
+            for e in entries:
+                f.write(json.dumps(e) + "\n")
+        return path
+
+    return write
@@ -0,0 +1,92 @@
+"""Tests for per-session active time (Rust native via PyO3)."""
+
+import pytest
+
+from snoopy._native import session_activity
+
+
+def _ts(seconds):
+    return f"2026-02-25T10:{seconds // 60:02d}:{seconds % 60:02d}Z"
+
+
+def _prompt(seconds, text="fix the tests"):
+    return {"type": "user", "timestamp": _ts(seconds), "cwd": "/Users/me/app",
+            "message": {"role": "user", "content": text}}
+
+
+def _reply(seconds):
+    return {"type": "assistant", "timestamp": _ts(seconds),
+            "message": {"content": [{"type": "text", "text": "done"}]}}
+
+
+def _tool_result(seconds):
+    return {"type": "user", "timestamp": _ts(seconds),
+            "message": {"content": [{"type": "tool_result", "tool_use_id": "t1",
+                                     "content": "ok"}]}}
+
+
+@pytest.fixture
+def projects(tmp_path, write_transcript):
+    write_transcript(tmp_path / "-Users-me-app" / "s1.jsonl", [
+        _prompt(0),
+        _reply(10),        # agent works 10s
+        _tool_result(40),  # tool runs 30s
+        _reply(50),        # agent works 10s
+        _prompt(80),       # user reads and types for 30s
+        _reply(90),        # agent works 10s
+        _prompt(1500),     # away for 1410s
+        _reply(1520),      # agent works 20s
+    ])
+    write_transcript(tmp_path / "-Users-me-app" / "agent-abc.jsonl", [
+        _prompt(0), _reply(5),
+    ])
+    write_transcript(tmp_path / "-Users-me-app" / "empty.jsonl", [
+        {"type": "summary", "summary": "No timestamps"},
+    ])
+    return tmp_path
+
+
+class TestSessionActivity:
+    def test_active_idle_split(self, projects):
+        stats = session_activity(str(projects))
+        assert list(stats) == ["s1"]
+        s1 = stats["s1"]
+        assert s1["project_path"] == "/Users/me/app"
+        assert s1["wall_seconds"] == 1520
+        assert s1["active_seconds"] == 110
+        assert s1["idle_seconds"] == 1410
+        assert s1["idle_gaps"] == 1
+        assert s1["user_wait_seconds"] == 30
+        assert s1["agent_work_seconds"] == 80
+        assert s1["prompts"] == 3
+        assert s1["active_ratio"] == pytest.approx(110 / 1520)
+        assert s1["end"] - s1["start"] == s1["wall_seconds"]
+
+    def test_threshold(self, projects):
+        s1 = session_activity(str(projects), idle_threshold=20)["s1"]
+        assert s1["active_seconds"] == 50
+        assert s1["idle_gaps"] == 3
+        s1 = session_activity(str(projects), idle_threshold=2000)["s1"]
+        assert s1["active_seconds"] == s1["wall_seconds"]
+        assert s1["user_wait_seconds"] == 30 + 1410
+
+    def test_single_file(self, projects):
+        stats = session_activity(str(projects / "-Users-me-app" / "agent-abc.jsonl"))
+        assert stats == {}
+        stats = session_activity(str(projects / "-Users-me-app" / "s1.jsonl"))
+        assert list(stats) == ["s1"]
+
+    def test_generated_messages_are_not_prompts(self, tmp_path, write_transcript):
+        write_transcript(tmp_path / "s.jsonl", [
+            _prompt(0),
+            {**_prompt(5, "<command-name>/clear</command-name>"), "isMeta": True},
+            _prompt(10, "<task-notification>done</task-notification>"),
+        ])
+        s = session_activity(str(tmp_path))["s"]
+        assert s["prompts"] == 1
+        assert s["agent_work_seconds"] == 10
+        assert s["project_path"] == "/Users/me/app"
+
+    def test_invalid_threshold(self, tmp_path):
+        with pytest.raises(ValueError):
+            session_activity(str(tmp_path), idle_threshold=-1)
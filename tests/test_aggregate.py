@@ -0,0 +1,51 @@
+"""Tests for time-bucket event aggregation (Rust native via PyO3)."""
+
+import pytest
+
+from snoopy._native import aggregate_events
+
+
+def _ev(ts, message_type, tokens=0, cost=0.0):
+    return {"timestamp": ts, "message_type": message_type, "tokens": tokens, "cost_usd": cost}
+
+
+class TestAggregateEvents:
+    def test_hourly_counts_per_type(self):
+        events = [
+            _ev(3600.0, "user"),
+            _ev(3700.0, "assistant_text", tokens=100, cost=0.5),
+            _ev(3800.0, "assistant_text", tokens=50, cost=0.25),
+            _ev(7300.0, "user"),
+        ]
+        rows = aggregate_events(events, bucket="hour")
+
+        assert [(r["bucket_start"], r["message_type"], r["count"]) for r in rows] == [
+            (3600.0, "assistant_text", 2),
+            (3600.0, "user", 1),
+            (7200.0, "user", 1),
+        ]
+        assert rows[0]["tokens"] == 150
+        assert rows[0]["cost_usd"] == 0.75
+
+    def test_daily_with_utc_offset(self):
+        # 23:30 UTC is 01:30 the next day at UTC+2.
+        rows = aggregate_events([_ev(86400.0 - 1800, "user")], bucket="day", utc_offset=7200)
+        assert rows[0]["bucket_start"] == 86400.0 - 7200
+
+    def test_weeks_start_on_monday(self):
+        monday = 1736121600.0  # 2025-01-06 00:00 UTC
+        rows = aggregate_events([_ev(monday + 2.5 * 86400, "user")], bucket="week")
+        assert rows[0]["bucket_start"] == monday
+        # Sunday 22:30 UTC is already Monday at UTC+2.
+        rows = aggregate_events([_ev(monday - 5400, "user")], bucket="week", utc_offset=7200)
+        assert rows[0]["bucket_start"] == monday - 7200
+
+    def test_missing_optional_keys(self):
+        rows = aggregate_events([{"timestamp": 10.0}], bucket="minute")
+        assert rows == [{
+            "bucket_start": 0.0, "message_type": "", "count": 1, "tokens": 0, "cost_usd": 0.0,
+        }]
+
+    def test_unknown_bucket(self):
+        with pytest.raises(ValueError):
+            aggregate_events([], bucket="fortnight")
@@ -0,0 +1,83 @@
+"""Tests for Anonymizer and anonymized NDJSON exports (Rust native via PyO3)."""
+
+import json
+import pickle
+import re
+import time
+
+import pytest
+
+from snoopy._native import Anonymizer, Collector, read_ndjson
+
+EVENT = {
+    "source": "messages",
+    "timestamp": 1772013600.0,
+    "session_id": "8f0c2a",
+    "project_path": "/Users/me/secret-app",
+    "path": "/Users/me/secret-app/src/main.rs",
+    "contact": "Alice@Example.com",
+    "recipients": ["bob@example.com", None],
+    "url": "https://intranet.example.com/wiki/launch",
+    "content_preview": "see you at launch",
+}
+
+
+class TestAnonymizer:
+    def test_pseudonymizes_identifiers(self):
+        out = Anonymizer(b"k").apply(EVENT)
+        assert re.fullmatch(r"session-[0-9a-f]{12}", out["session_id"])
+        assert re.fullmatch(r"contact-[0-9a-f]{12}", out["contact"])
+        assert re.fullmatch(r"https://host-[0-9a-f]{12}", out["url"])
+        assert out["recipients"][1] is None
+        assert out["recipients"][0].startswith("contact-")
+        assert out["content_preview"] == "see you at launch"
+        assert out["timestamp"] == EVENT["timestamp"]
+        assert EVENT["contact"] == "Alice@Example.com"
+        assert "secret" not in json.dumps(out) and "example" not in json.dumps(out)
+
+    def test_paths_keep_shape(self):
+        out = Anonymizer(b"k").apply(EVENT)
+        assert re.fullmatch(r"/([0-9a-f]{8}/){2}[0-9a-f]{8}", out["project_path"])
+        assert out["path"].startswith(out["project_path"] + "/")
+        assert out["path"].endswith(".rs")
+
+    def test_stable_per_key(self):
+        a, b = Anonymizer("key"), Anonymizer(b"key")
+        assert a.apply(EVENT) == b.apply(EVENT)
+        assert a.pseudonym("alice@example.com", "contact") == a.apply(EVENT)["contact"]
+        assert Anonymizer(b"other").apply(EVENT)["contact"] != a.apply(EVENT)["contact"]
+        random = Anonymizer()
+        assert random.apply(EVENT) == random.apply(EVENT)
+        assert pickle.loads(pickle.dumps(random)).apply(EVENT) == random.apply(EVENT)
+        assert random.anonymize([EVENT, EVENT]) == [random.apply(EVENT)] * 2
+
+    def test_invalid(self):
+        with pytest.raises(ValueError):
+            Anonymizer(b"")
+        with pytest.raises(ValueError):
+            Anonymizer().pseudonym("x", "email")
+        with pytest.raises(ValueError):
+            Collector(["claude"], anonymize=Anonymizer())
+
+    def test_anonymized_export(self, tmp_path):
+        projects = tmp_path / "projects" / "-Users-me-app"
+        projects.mkdir(parents=True)
+        transcript = projects / "s.jsonl"
+        transcript.touch()
+        out = tmp_path / "events.ndjson"
+        anonymizer = Anonymizer(b"k")
+        with Collector(["claude"], interval=0.05, projects_dir=str(projects.parent),
+                       sink=f"ndjson:{out}", anonymize=anonymizer) as collector:
+            time.sleep(0.2)
+            with open(transcript, "a") as f:
+                f.write(json.dumps({"type": "user", "timestamp": "2026-02-25T10:00:00Z",
+                                    "cwd": "/Users/me/app",
+                                    "message": {"content": "hello"}}) + "\n")
+            deadline = time.time() + 5
+            while collector.status()["sources"]["claude"]["events"] < 1 \
+                    and time.time() < deadline:
+                time.sleep(0.02)
+        (event,) = read_ndjson(str(out))
+        assert event["content_preview"] == "hello"
+        assert event["session_id"] == anonymizer.pseudonym("s", "session")
+        assert "me" not in event["project_path"].split("/")
@@ -0,0 +1,114 @@
+"""Tests for the OpenBSM praudit parser (Rust native via PyO3)."""
+
+import time
+
+import pytest
+
+from snoopy._native import audit_trail_events, parse_praudit
+
+T0 = time.mktime((2024, 3, 1, 10, 0, 0, 0, 0, -1))
+
+XML = """\
+<?xml version='1.0' ?>
+<audit>
+<record event="execve(2)" time="Fri Mar  1 10:00:00 2024" msec=" + 250 msec" >
+<exec_args><arg>ls</arg><arg>-l</arg><arg>a&amp;b</arg></exec_args>
+<path>/bin/ls</path>
+<subject audit-uid="me" uid="me" pid="1234" sid="100" tid="50331650 0.0.0.0" />
+<return errval="success" retval="0" />
+<trailer trailer-size="150" />
+</record>
+<record event="open(2) - write,creat,trunc" time="Fri Mar  1 10:00:01 2024" msec=" + 0 msec" >
+<path>/Users/me/notes.txt</path>
+<subject audit-uid="me" uid="me" pid="1235" sid="100" tid="50331650 0.0.0.0" />
+<return errval="failure : Permission denied" retval="-1" />
+<trailer trailer-size="120" />
+</record>
+<record event="OpenSSH login" time="Fri Mar  1 10:00:02 2024" msec=" + 0 msec" >
+<subject_ex audit-uid="alice" uid="alice" pid="999" sid="999" tid="22 10.0.0.5" />
+<text>successful login alice</text>
+<return errval="success" retval="0" />
+<trailer trailer-size="110" />
+</record>
+<record event="fork(2)" time="Fri Mar  1 10:00:03 2024" msec=" + 0 msec" >
+<return errval="success" retval="0" />
+<trailer trailer-size="60" />
+</record>
+</audit>
+"""
+
+TEXT = """\
+header,120,11,rename(2),0,Fri Mar  1 10:00:04 2024, + 0 msec
+path,/Users/me/a.txt
+path,/Users/me/b.txt
+subject,me,me,staff,me,staff,1240,100,50331650,0.0.0.0
+return,success,0
+trailer,120
+header,110,11,open(2) - read,0,Fri Mar  1 10:00:05 2024, + 0 msec
+path,/etc/hosts
+subject,me,me,staff,me,staff,1241,100,50331650,0.0.0.0
+return,success,0
+trailer,110
+"""
+
+ONE_LINE = (
+    "header,90,11,loginwindow login,0,Fri Mar  1 10:00:06 2024, + 0 msec,"
+    "subject,me,me,staff,me,staff,88,88,0,0.0.0.0,text,successful login me,"
+    "return,success,0,trailer,90\n"
+    "header,90,11,unlinkat(2),0,Fri Mar  1 10:00:07 2024, + 0 msec,"
+    "path,/tmp/x,subject,me,me,staff,me,staff,89,88,0,0.0.0.0,return,success,0,trailer,90\n"
+)
+
+
+class TestParsePraudit:
+    def test_xml(self):
+        events = parse_praudit(XML)
+        assert [(e["source"], e["kind"]) for e in events] == [
+            ("process", "exec"),
+            ("file", "open"),
+            ("login", "ssh"),
+        ]
+        exec_, opened, login = events
+        assert exec_["timestamp"] == pytest.approx(T0 + 0.25)
+        assert exec_["argv"] == ["ls", "-l", "a&b"]
+        assert exec_["exe"] == "/bin/ls"
+        assert exec_["process_name"] == "ls"
+        assert exec_["pid"] == 1234
+        assert exec_["user"] == "me"
+        assert exec_["success"] is True
+        assert exec_["error"] is None
+        assert opened["write"] is True
+        assert opened["success"] is False
+        assert opened["error"] == "Permission denied"
+        assert opened["audit_event"] == "open(2) - write,creat,trunc"
+        assert login["user"] == "alice"
+        assert login["remote_address"] == "10.0.0.5"
+        assert login["message"] == "successful login alice"
+
+    def test_text(self):
+        renamed, opened = parse_praudit(TEXT)
+        assert renamed["kind"] == "rename"
+        assert renamed["path"] == "/Users/me/b.txt"
+        assert renamed["previous_path"] == "/Users/me/a.txt"
+        assert renamed["pid"] == 1240
+        assert opened["kind"] == "open"
+        assert opened["write"] is False
+        assert opened["path"] == "/etc/hosts"
+        assert opened["timestamp"] == pytest.approx(T0 + 5)
+
+    def test_one_line_records(self):
+        login, unlink = parse_praudit(ONE_LINE)
+        assert (login["source"], login["kind"]) == ("login", "session")
+        assert login["remote_address"] is None
+        assert login["message"] == "successful login me"
+        assert (unlink["source"], unlink["kind"], unlink["path"]) == ("file", "unlink", "/tmp/x")
+        assert unlink["pid"] == 89
+
+    def test_since(self):
+        events = parse_praudit(XML, since=T0 + 1.5)
+        assert [e["kind"] for e in events] == ["ssh"]
+
+    def test_missing_praudit(self, tmp_path, monkeypatch):
+        monkeypatch.setenv("PATH", str(tmp_path))
+        with pytest.raises(IOError):
+            audit_trail_events(tmp_path / "trail")
@@ -0,0 +1,68 @@
+"""Tests for the snoopy_native.bench submodule (Rust native via PyO3)."""
+
+import json
+
+import pytest
+
+from snoopy._native import bench
+
+# Allocation counting needs the extension built with the bench cargo feature.
+counting = pytest.mark.skipif(
+    not hasattr(bench, "allocation_stats"), reason="built without the bench feature"
+)
+
+
+def _transcript(n):
+    entry = {"type": "user", "timestamp": "2026-02-25T10:00:00Z",
+             "message": {"role": "user", "content": "hello there"}}
+    return b"".join(json.dumps(entry).encode() + b"\n" for _ in range(n))
+
+
+class TestBench:
+    def test_submodule_importable(self):
+        import snoopy_native.bench
+
+        assert snoopy_native.bench is bench
+        assert "transcript" in bench.parsers()
+        assert "iso_timestamps" in bench.parsers()
+
+    def test_time_parser(self):
+        data = _transcript(200)
+        result = bench.time_parser("transcript", data, iterations=3)
+        assert result["parser"] == "transcript"
+        assert result["iterations"] == 3
+        assert result["bytes"] == len(data)
+        assert result["lines"] == 200
+        assert result["records"] == 200
+        assert result["seconds"] > 0
+        assert result["lines_per_sec"] > 0
+        assert result["bytes_per_sec"] == pytest.approx(
+            result["lines_per_sec"] * len(data) / 200
+        )
+
+    @counting
+    def test_time_parser_counts_allocations(self):
+        result = bench.time_parser("transcript", _transcript(200), iterations=3)
+        # At least one event per line is built.
+        assert result["allocations"] >= 200
+        assert result["allocated_bytes"] > 0
+
+    def test_accepts_buffers(self):
+        data = bytearray(b"2026-02-25T10:00:00Z\ngarbage\n2026-02-25 10:00:01")
+        result = bench.time_parser("iso_timestamps", memoryview(data), iterations=1)
+        assert result["lines"] == 3
+        assert result["records"] == 2
+
+    @counting
+    def test_allocation_stats(self):
+        before = bench.allocation_stats()
+        bench.time_parser("transcript", _transcript(50), iterations=1)
+        after = bench.allocation_stats()
+        assert after["allocations"] > before["allocations"]
+        assert after["allocated_bytes"] - after["freed_bytes"] == after["live_bytes"]
+
+    def test_invalid_input(self):
+        with pytest.raises(ValueError, match="unknown parser"):
+            bench.time_parser("nope", b"")
+        with pytest.raises(ValueError):
+            bench.time_parser("pmset_log", b"\xff\xfe")
@@ -0,0 +1,86 @@
+"""Tests for calendar_events (Rust native via PyO3)."""
+
+import sqlite3
+
+import pytest
+
+from snoopy._native import calendar_events
+
+COCOA_EPOCH = 978307200
+T0 = 1_700_000_000.0
+
+
+def _calendar_db(path, items, occurrences=(), participants=()):
+    conn = sqlite3.connect(path)
+    conn.executescript("""
+        CREATE TABLE Calendar (ROWID INTEGER PRIMARY KEY, title TEXT);
+        CREATE TABLE Location (ROWID INTEGER PRIMARY KEY, title TEXT);
+        CREATE TABLE CalendarItem (ROWID INTEGER PRIMARY KEY, unique_identifier TEXT,
+            summary TEXT, start_date REAL, end_date REAL, all_day INTEGER,
+            has_recurrences INTEGER, status INTEGER, calendar_id INTEGER, location_id INTEGER);
+        CREATE TABLE OccurrenceCache (ROWID INTEGER PRIMARY KEY, day REAL, event_id INTEGER,
+            occurrence_date REAL, occurrence_start_date REAL, occurrence_end_date REAL);
+        CREATE TABLE Participant (ROWID INTEGER PRIMARY KEY, owner_id INTEGER,
+            identity_id INTEGER, email TEXT);
+        CREATE TABLE Identity (ROWID INTEGER PRIMARY KEY, display_name TEXT, address TEXT);
+        INSERT INTO Calendar VALUES (1, 'Work');
+        INSERT INTO Location VALUES (1, 'Gates 200');
+        INSERT INTO Identity VALUES (1, 'Sarah Chen', 'mailto:sarah@example.com');
+    """)
+    for rowid, uid, title, start, end, recurring in items:
+        conn.execute(
+            "INSERT INTO CalendarItem VALUES (?, ?, ?, ?, ?, 0, ?, 1, 1, 1)",
+            (rowid, uid, title, start - COCOA_EPOCH, end - COCOA_EPOCH, recurring),
+        )
+    for event_id, start in occurrences:
+        conn.execute(
+            "INSERT INTO OccurrenceCache (event_id, occurrence_date, occurrence_start_date)"
+            " VALUES (?, ?, ?)",
+            (event_id, start - COCOA_EPOCH, start - COCOA_EPOCH),
+        )
+    for owner, identity, email in participants:
+        conn.execute(
+            "INSERT INTO Participant (owner_id, identity_id, email) VALUES (?, ?, ?)",
+            (owner, identity, email),
+        )
+    conn.commit()
+    conn.close()
+
+
+class TestCalendarEvents:
+    def test_events_with_attendees(self, tmp_path):
+        db = tmp_path / "Calendar.sqlitedb"
+        _calendar_db(
+            db,
+            [(1, "ABC-123", "Staff Mtg", T0, T0 + 3600, 0),
+             (2, "DEF-456", "Focus time", T0 - 7200, T0 - 3600, 0)],
+            participants=[(1, 1, "sarah@example.com"), (1, None, "diyi@example.com")],
+        )
+        events = calendar_events(str(db))
+        assert [e["title"] for e in events] == ["Focus time", "Staff Mtg"]
+        meeting = events[1]
+        assert meeting["source"] == "calendar"
+        assert meeting["uid"] == "ABC-123"
+        assert meeting["timestamp"] == pytest.approx(T0)
+        assert meeting["duration"] == pytest.approx(3600)
+        assert meeting["calendar"] == "Work"
+        assert meeting["location"] == "Gates 200"
+        assert meeting["is_meeting"] is True
+        assert meeting["attendees"] == ["Sarah Chen", "diyi@example.com"]
+        assert events[0]["is_meeting"] is False
+
+    def test_recurring_occurrences_in_range(self, tmp_path):
+        db = tmp_path / "Calendar.sqlitedb"
+        day = 86400
+        _calendar_db(
+            db,
+            [(1, "REC-1", "Standup", T0, T0 + 900, 1)],
+            occurrences=[(1, T0), (1, T0 + day), (1, T0 + 2 * day)],
+        )
+        events = calendar_events(str(db), start=T0 + day - 60, end=T0 + 3 * day)
+        assert [e["timestamp"] for e in events] == [T0 + day, T0 + 2 * day]
+        assert all(e["recurring"] and e["end"] - e["timestamp"] == 900 for e in events)
+
+    def test_missing_database_raises(self, tmp_path):
+        with pytest.raises(IOError):
+            calendar_events(str(tmp_path / "Calendar.sqlitedb"))
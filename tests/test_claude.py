@@ -1,10 +1,14 @@
 """Tests for Claude collector — verifies JSONL transcript parsing + hook flow."""
 
+import base64
 import json
 import time
+from datetime import datetime, timezone
 
 import pytest
 
+from snoopy._native import ParseError, extract_message_content, parse_transcript_bytes
+from snoopy._native import parse_transcript as parse_transcript_rs
 from snoopy.buffer import EventBuffer
 from snoopy.collectors.claude import ClaudeCollector, parse_transcript
 from snoopy.db import Database
@@ -83,6 +87,215 @@ class TestParseTranscript:
         assert len(events2) == 1
         assert events2[0]["content_preview"] == "new"
 
+    def test_usage_attributed_once_per_message(self, tmp_path):
+        """Claude Code repeats usage on each line of a multi-block message.
+        Tokens and cost should land on the first event of the message only."""
+
+        transcript = tmp_path / "session-usage.jsonl"
+        msg = {"id": "msg_1", "model": "claude-sonnet-4",
+               "usage": {"input_tokens": 100, "output_tokens": 50}}
+        _write_transcript(transcript, [
+            {"type": "assistant", "timestamp": "2026-02-25T10:00:00Z",
+             "message": {**msg, "content": [{"type": "text", "text": "a"}]}},
+            {"type": "assistant", "timestamp": "2026-02-25T10:00:00Z",
+             "message": {**msg, "content": [{"type": "text", "text": "b"}]}},
+        ])
+
+        events, _ = parse_transcript(transcript)
+        assert [e["tokens"] for e in events] == [150, 0]
+        assert events[0]["cost_usd"] > 0
+        assert events[1]["cost_usd"] == 0.0
+
+    def test_interrupt_and_permission_denial(self, tmp_path):
+        """An interrupted turn becomes user_interrupt; a rejected tool_result becomes
+        permission_denied:<tool> carrying the preview of the denied call."""
+
+        transcript = tmp_path / "session-deny.jsonl"
+        _write_transcript(transcript, [
+            {"type": "assistant", "timestamp": "2026-02-25T10:00:00Z",
+             "message": {"content": [
+                 {"type": "tool_use", "id": "toolu_1", "name": "Bash",
+                  "input": {"command": "rm -rf build"}},
+             ]}},
+            {"type": "user", "timestamp": "2026-02-25T10:00:05Z",
+             "message": {"content": [
+                 {"type": "tool_result", "tool_use_id": "toolu_1", "is_error": True,
+                  "content": "The user doesn't want to proceed with this tool use. "
+                             "The tool use was rejected."},
+             ]}},
+            {"type": "user", "timestamp": "2026-02-25T10:00:06Z",
+             "message": {"content": [
+                 {"type": "text", "text": "[Request interrupted by user for tool use]"},
+             ]}},
+        ])
+
+        events, _ = parse_transcript(transcript)
+        types = [e["message_type"] for e in events]
+        assert types == ["tool_use:Bash", "permission_denied:Bash", "user_interrupt"]
+        assert events[1]["content_preview"] == "rm -rf build"
+
+    def test_summary_and_compaction_entries(self, tmp_path):
+        """summary entries become session_summary; a compact_boundary followed by the
+        continuation message becomes one compaction event carrying the summary text."""
+
+        transcript = tmp_path / "session-compact.jsonl"
+        _write_transcript(transcript, [
+            {"type": "summary", "summary": "Fix flaky parser test", "leafUuid": "u1"},
+            {"type": "system", "subtype": "compact_boundary",
+             "timestamp": "2026-02-25T10:00:00Z", "content": "Conversation compacted",
+             "compactMetadata": {"trigger": "auto", "preTokens": 155000}},
+            {"type": "user", "timestamp": "2026-02-25T10:00:01Z", "isCompactSummary": True,
+             "message": {"content": "This session is being continued from a previous "
+                                    "conversation. Summary: tests fixed."}},
+        ])
+
+        events, _ = parse_transcript(transcript)
+        assert [e["message_type"] for e in events] == ["session_summary", "compaction"]
+        assert events[0]["content_preview"] == "Fix flaky parser test"
+        assert events[1]["content_preview"].startswith("This session is being continued")
+
+    def test_image_attachment_metadata(self, tmp_path):
+        """Image blocks produce attachment events with media type and decoded size,
+        without carrying the base64 payload."""
+
+        transcript = tmp_path / "session-image.jsonl"
+        payload = base64.b64encode(b"x" * 3000).decode()
+        _write_transcript(transcript, [
+            {"type": "user", "timestamp": "2026-02-25T10:00:00Z",
+             "message": {"content": [
+                 {"type": "image",
+                  "source": {"type": "base64", "media_type": "image/png", "data": payload}},
+                 {"type": "text", "text": "what is wrong here?"},
+             ]}},
+        ])
+
+        events, _ = parse_transcript(transcript)
+        assert [e["message_type"] for e in events] == ["attachment:image", "user"]
+        assert events[0]["media_type"] == "image/png"
+        assert events[0]["size_bytes"] == 3000
+        assert events[0]["content_preview"] == "image/png (2.9 KB)"
+        assert "media_type" not in events[1]
+
+    def test_hook_and_system_entries(self, tmp_path):
+        """Hook progress and hook result notices become hook:<event> events with name,
+        exit status and blocked flag; other system notices become system:<subtype>."""
+
+        transcript = tmp_path / "session-hooks.jsonl"
+        _write_transcript(transcript, [
+            {"type": "progress", "timestamp": "2026-02-25T10:00:00Z",
+             "data": {"type": "hook_progress", "hookEvent": "PreToolUse",
+                      "hookName": "PreToolUse:Bash", "command": "~/guard.sh"}},
+            {"type": "system", "timestamp": "2026-02-25T10:00:01Z", "level": "warning",
+             "content": "PreToolUse:Bash [~/guard.sh] failed with non-blocking status code 1: "
+                        "oops"},
+            {"type": "system", "timestamp": "2026-02-25T10:00:02Z",
+             "subtype": "stop_hook_summary", "hookErrors": [], "preventedContinuation": True},
+            {"type": "system", "timestamp": "2026-02-25T10:00:03Z", "subtype": "api_error",
+             "level": "error", "content": "Overloaded"},
+        ])
+
+        events, _ = parse_transcript(transcript)
+        assert [e["message_type"] for e in events] == [
+            "hook:PreToolUse", "hook:PreToolUse", "hook:Stop", "system:api_error",
+        ]
+        assert events[0]["exit_code"] is None
+        assert events[0]["content_preview"] == "~/guard.sh"
+        assert (events[1]["hook_name"], events[1]["exit_code"], events[1]["blocked"]) == (
+            "PreToolUse:Bash", 1, False,
+        )
+        assert events[2]["blocked"] is True
+        assert "hook_name" not in events[3]
+
+    def test_datetimes_option(self, tmp_path):
+        """datetimes=True returns UTC-aware datetime objects; missing timestamps are None."""
+
+        transcript = tmp_path / "session-dt.jsonl"
+        _write_transcript(transcript, [
+            {"type": "user", "timestamp": "2026-02-25T10:00:00.500+02:00",
+             "message": {"content": "hi"}},
+            {"type": "summary", "summary": "Greeting"},
+        ])
+
+        events, _ = parse_transcript_rs(str(transcript), datetimes=True)
+        assert events[0]["timestamp"] == datetime(2026, 2, 25, 8, 0, 0, 500000, tzinfo=timezone.utc)
+        assert events[0]["timestamp"].tzinfo is not None
+        assert events[1]["timestamp"] is None
+
+    def test_include_raw(self, tmp_path):
+        """include_raw=True attaches the source line to every event it produced;
+        a list of fields keeps only those keys."""
+
+        transcript = tmp_path / "session-raw.jsonl"
+        entry = {"type": "assistant", "timestamp": "2026-02-25T10:00:00Z", "uuid": "u-1",
+                 "requestId": "req_1",
+                 "message": {"content": [{"type": "text", "text": "a"},
+                                         {"type": "text", "text": "b"}]}}
+        _write_transcript(transcript, [entry])
+
+        events, _ = parse_transcript_rs(str(transcript))
+        assert "raw" not in events[0]
+
+        events, _ = parse_transcript_rs(str(transcript), include_raw=True)
+        assert [json.loads(e["raw"]) for e in events] == [entry, entry]
+
+        events, _ = parse_transcript_rs(str(transcript), include_raw=["uuid", "requestId", "x"])
+        assert json.loads(events[0]["raw"]) == {"uuid": "u-1", "requestId": "req_1"}
+
+
+    def test_repeated_values_share_strings(self, tmp_path):
+        """session_id, project_path and message_type are one object per distinct value."""
+
+        transcript = tmp_path / "session-many.jsonl"
+        _write_transcript(transcript, [
+            {"type": "user", "timestamp": f"2026-02-25T10:00:0{i}Z",
+             "message": {"role": "user", "content": f"prompt {i}"}}
+            for i in range(3)
+        ])
+
+        events, _ = parse_transcript_rs(str(transcript))
+        assert len(events) == 3
+        for key in ("session_id", "project_path", "message_type"):
+            assert events[0][key] is events[1][key] is events[2][key]
+        assert events[0]["content_preview"] != events[1]["content_preview"]
+
+    def test_from_bytes(self, tmp_path):
+        """parse_transcript_bytes reads in-memory data from any buffer like a file."""
+
+        transcript = tmp_path / "session-mem.jsonl"
+        _write_transcript(transcript, [
+            {"type": "user", "timestamp": "2026-02-25T10:00:00Z",
+             "message": {"role": "user", "content": "hello"}},
+            {"type": "assistant", "timestamp": "2026-02-25T10:00:01Z",
+             "message": {"role": "assistant", "content": [{"type": "text", "text": "hi"}]}},
+        ])
+        data = transcript.read_bytes()
+        from_file, _ = parse_transcript_rs(str(transcript))
+
+        for buf in (data, bytearray(data), memoryview(data)):
+            events = parse_transcript_bytes(buf, session_id="session-mem",
+                                            project_path=str(tmp_path))
+            assert events == from_file
+
+        events = parse_transcript_bytes(data, preview_len=2)
+        assert [e["content_preview"] for e in events] == ["he", "hi"]
+        assert events[0]["session_id"] == ""
+
+        with pytest.raises(ParseError) as err:
+            parse_transcript_bytes(data + b"\xff\xfe\n")
+        assert err.value.offset == len(data)
+
+    def test_extract_message_content(self):
+        message = {"role": "assistant", "content": [
+            {"type": "text", "text": "one"},
+            {"type": "tool_use", "name": "Bash", "input": {"command": "ls"}},
+            {"type": "text", "text": "two"},
+        ]}
+        assert extract_message_content(message) == "one two"
+        assert extract_message_content(json.dumps(message)) == "one two"
+        assert extract_message_content({"type": "user", "message": {"content": "hi"}}) == "hi"
+        assert extract_message_content({"content": None}) == ""
+        with pytest.raises(ValueError):
+            extract_message_content("{not json")
 
 class TestClaudeCollector:
     def test_first_run_skips_then_collects_new(self, buf, db, tmp_path, monkeypatch):
@@ -0,0 +1,385 @@
+"""Tests for the background Collector (Rust native via PyO3)."""
+
+import asyncio
+import json
+import socket
+import sqlite3
+import threading
+import time
+
+import pytest
+
+from snoopy._native import Collector
+
+
+def _append(path, entries):
+    with open(path, "a") as f:
+        for e in entries:
+            f.write(json.dumps(e) + "\n")
+
+
+def _wait_for(collector, n, timeout=5.0):
+    events = []
+    deadline = time.time() + timeout
+    while len(events) < n and time.time() < deadline:
+        events.extend(collector.drain())
+        time.sleep(0.02)
+    return events
+
+
+def _tail_new_prompt(tmp_path, collector_kwargs, wait):
+    transcript = tmp_path / "projects" / "-Users-me-app" / "s1.jsonl"
+    transcript.parent.mkdir(parents=True)
+    transcript.touch()
+    collector = Collector(["claude"], interval=0.05, projects_dir=str(tmp_path / "projects"),
+                          **collector_kwargs)
+    collector.start()
+    try:
+        time.sleep(0.2)
+        _append(transcript, [{"type": "user", "timestamp": "2026-02-25T10:05:00Z",
+                              "message": {"content": "new prompt"}}])
+        wait(collector)
+    finally:
+        collector.stop()
+    return collector
+
+
+def _poll(predicate, timeout=5.0):
+    deadline = time.time() + timeout
+    while not predicate() and time.time() < deadline:
+        time.sleep(0.02)
+
+
+def _make_chat_db(path):
+    conn = sqlite3.connect(path)
+    conn.executescript("""
+        CREATE TABLE message (ROWID INTEGER PRIMARY KEY, text TEXT, is_from_me INTEGER,
+            date INTEGER, service TEXT, cache_has_attachments INTEGER, handle_id INTEGER,
+            attributedBody BLOB, destination_caller_id TEXT);
+        CREATE TABLE handle (ROWID INTEGER PRIMARY KEY, id TEXT);
+        CREATE TABLE chat (ROWID INTEGER PRIMARY KEY, display_name TEXT, chat_identifier TEXT);
+        CREATE TABLE chat_message_join (chat_id INTEGER, message_id INTEGER);
+        INSERT INTO handle VALUES (1, '+15550001111');
+        INSERT INTO message VALUES (1, 'old', 0, 0, 'iMessage', 0, 1, NULL, NULL);
+    """)
+    conn.commit()
+    return conn
+
+
+class TestCollector:
+    def test_claude_source_tails_new_events(self, tmp_path):
+        transcript = tmp_path / "-Users-me-app" / "s1.jsonl"
+        transcript.parent.mkdir()
+        _append(transcript, [{"type": "user", "timestamp": "2026-02-25T10:00:00Z",
+                              "message": {"content": "history"}}])
+        collector = Collector(["claude"], interval=0.05, projects_dir=str(tmp_path))
+        collector.start()
+        try:
+            time.sleep(0.2)
+            _append(transcript, [{"type": "user", "timestamp": "2026-02-25T10:05:00Z",
+                                  "sessionId": "s1", "message": {"content": "new prompt"}}])
+            events = _wait_for(collector, 1)
+        finally:
+            collector.stop()
+        assert [e["content_preview"] for e in events] == ["new prompt"]
+        assert events[0]["source"] == "claude"
+        assert events[0]["message_type"] == "user"
+
+    def test_messages_source(self, tmp_path):
+        db = tmp_path / "chat.db"
+        conn = _make_chat_db(db)
+        collector = Collector(["messages"], interval=0.05, chat_db=str(db))
+        collector.start()
+        try:
+            time.sleep(0.2)
+            conn.execute("INSERT INTO message VALUES "
+                         "(2, 'hello', 1, 0, 'iMessage', 0, 1, NULL, NULL)")
+            conn.commit()
+            events = _wait_for(collector, 1)
+        finally:
+            collector.stop()
+            conn.close()
+        assert len(events) == 1
+        assert events[0]["content_preview"] == "hello"
+        assert events[0]["contact"] == "+15550001111"
+        assert events[0]["is_from_me"] == 1
+
+    def test_bounded_queue_drops_and_drain_limit(self, tmp_path):
+        transcript = tmp_path / "p" / "s.jsonl"
+        transcript.parent.mkdir()
+        transcript.touch()
+        collector = Collector(["claude"], interval=0.05, queue_size=2,
+                              projects_dir=str(tmp_path))
+        collector.start()
+        try:
+            time.sleep(0.2)
+            _append(transcript, [{"type": "user", "timestamp": "2026-02-25T10:00:00Z",
+                                  "message": {"content": f"m{i}"}} for i in range(5)])
+            deadline = time.time() + 5
+            while collector.status()["dropped"] < 3 and time.time() < deadline:
+                time.sleep(0.02)
+        finally:
+            collector.stop()
+        status = collector.status()
+        assert status["dropped"] == 3
+        assert status["queued"] == 2
+        assert len(collector.drain(max_events=1)) == 1
+        assert collector.status()["queued"] == 1
+
+    def test_sampling_and_rate_limit(self, tmp_path):
+        transcript = tmp_path / "p" / "s.jsonl"
+        transcript.parent.mkdir()
+        transcript.touch()
+        collector = Collector(["claude", "network"], interval=0.05, projects_dir=str(tmp_path),
+                              sample_ratio={"claude": 0.5}, max_events_per_second=3)
+        collector.start()
+        try:
+            time.sleep(0.2)
+            _append(transcript, [{"type": "user", "timestamp": "2026-02-25T10:00:00Z",
+                                  "message": {"content": f"m{i}"}} for i in range(20)])
+            _poll(lambda: collector.status()["sources"]["claude"]["sampled_out"])
+        finally:
+            collector.stop()
+        events = collector.drain()
+        claude = collector.status()["sources"]["claude"]
+        assert [e["content_preview"] for e in events if e["source"] == "claude"] == \
+            ["m1", "m3", "m5"]
+        assert claude["sampled_out"] == 10
+        assert claude["rate_limited"] == 7
+
+    @pytest.mark.parametrize("overflow, kept, dropped", [
+        ("drop-newest", ["m0", "m1"], 3),
+        ("drop-oldest", ["m3", "m4"], 3),
+        ("block", ["m0", "m1", "m2", "m3", "m4"], 0),
+    ])
+    def test_overflow_policies(self, tmp_path, overflow, kept, dropped):
+        transcript = tmp_path / "p" / "s.jsonl"
+        transcript.parent.mkdir()
+        transcript.touch()
+        collector = Collector(["claude"], interval=0.05, queue_size=2, overflow=overflow,
+                              projects_dir=str(tmp_path))
+        collector.start()
+        events = []
+        try:
+            time.sleep(0.2)
+            _append(transcript, [{"type": "user", "timestamp": "2026-02-25T10:00:00Z",
+                                  "message": {"content": f"m{i}"}} for i in range(5)])
+            _poll(lambda: collector.stats()["depth"] == 2)
+            if overflow == "block":
+                events = _wait_for(collector, 5)
+            else:
+                _poll(lambda: collector.stats()["dropped"] == dropped)
+        finally:
+            collector.stop()
+        events += collector.drain()
+        assert [e["content_preview"] for e in events] == kept
+        stats = collector.stats()
+        assert stats == {"depth": 0, "capacity": 2, "overflow": overflow,
+                         "dropped": dropped, "peak_depth": 2}
+
+    def test_async_next_event(self, tmp_path):
+        transcript = tmp_path / "p" / "s.jsonl"
+        transcript.parent.mkdir()
+        transcript.touch()
+        collector = Collector(["claude"], interval=0.05, projects_dir=str(tmp_path))
+
+        async def main():
+            collector.start()
+            try:
+                assert await collector.next_event(timeout=0.2) is None
+                _append(transcript, [{"type": "user", "timestamp": "2026-02-25T10:00:00Z",
+                                      "message": {"content": f"m{i}"}} for i in range(2)])
+                first = await asyncio.wait_for(collector.next_event(), 5)
+            finally:
+                collector.stop()
+            # Stopped: async for drains what is left, then ends.
+            return first, [e async for e in collector]
+
+        first, rest = asyncio.run(main())
+        assert [e["content_preview"] for e in [first, *rest]] == ["m0", "m1"]
+        assert collector.status()["queued"] == 0
+
+    def test_drain_while_stopping(self, tmp_path):
+        collector = Collector(["claude", "network"], interval=0.05, projects_dir=str(tmp_path))
+        collector.start()
+        errors = []
+        stopping = threading.Event()
+
+        def drain():
+            try:
+                while not stopping.is_set():
+                    collector.drain()
+                    collector.status()
+            except Exception as e:
+                errors.append(e)
+
+        drainer = threading.Thread(target=drain)
+        drainer.start()
+        time.sleep(0.1)
+        collector.stop()
+        stopping.set()
+        drainer.join()
+        assert errors == []
+
+    def test_status_and_lifecycle(self, tmp_path):
+        collector = Collector(["claude"], interval=0.05, projects_dir=str(tmp_path))
+        assert collector.status()["running"] is False
+        collector.start()
+        try:
+            with pytest.raises(RuntimeError):
+                collector.start()
+            status = collector.status()
+            assert status["running"] is True
+            assert status["sources"]["claude"]["running"] is True
+        finally:
+            collector.stop()
+        assert collector.status()["sources"]["claude"]["running"] is False
+
+    def test_context_manager_closes_on_error(self, tmp_path):
+        with pytest.raises(KeyError):
+            with Collector(["claude", "network"], interval=0.05,
+                           projects_dir=str(tmp_path)) as collector:
+                assert collector.status()["running"] is True
+                raise KeyError("boom")
+        assert collector.status()["running"] is False
+        assert collector.close(timeout=0) is True
+        with pytest.raises(ValueError):
+            collector.close(timeout=float("nan"))
+        assert collector.close(timeout=float("inf")) is True
+
+    def test_bad_transcript_does_not_block_others(self, tmp_path):
+        bad = tmp_path / "-Users-me-app" / "bad.jsonl"
+        good = tmp_path / "-Users-me-app" / "good.jsonl"
+        bad.parent.mkdir()
+        bad.touch()
+        good.touch()
+        collector = Collector(["claude"], interval=0.05, projects_dir=str(tmp_path))
+        collector.start()
+        try:
+            time.sleep(0.2)
+            bad.write_bytes(b"\xff\xfe not utf-8\n")
+            _append(good, [{"type": "user", "timestamp": "2026-02-25T10:05:00Z",
+                            "message": {"content": "still here"}}])
+            events = _wait_for(collector, 1)
+        finally:
+            collector.stop()
+        assert [e["content_preview"] for e in events] == ["still here"]
+
+    def test_invalid_line_is_skipped(self, tmp_path):
+        path = tmp_path / "-Users-me-app" / "s.jsonl"
+        path.parent.mkdir()
+        path.touch()
+        collector = Collector(["claude"], interval=0.05, projects_dir=str(tmp_path))
+        collector.start()
+        try:
+            time.sleep(0.2)
+            _append(path, [{"type": "user", "timestamp": "2026-02-25T10:05:00Z",
+                            "message": {"content": "before"}}])
+            with open(path, "ab") as f:
+                f.write(b'{"type": "user", "x": "\xff\xfe"}\n')
+            _append(path, [{"type": "user", "timestamp": "2026-02-25T10:06:00Z",
+                            "message": {"content": "after"}}])
+            events = _wait_for(collector, 2)
+        finally:
+            collector.stop()
+        assert [e["content_preview"] for e in events] == ["before", "after"]
+
+    def test_tool_use_remembered_between_ticks(self, tmp_path):
+        path = tmp_path / "-Users-me-app" / "s.jsonl"
+        path.parent.mkdir()
+        path.touch()
+        collector = Collector(["claude"], interval=0.05, projects_dir=str(tmp_path))
+        collector.start()
+        try:
+            time.sleep(0.2)
+            _append(path, [{"type": "assistant", "timestamp": "2026-02-25T10:00:00Z",
+                            "message": {"content": [
+                                {"type": "tool_use", "id": "toolu_1", "name": "Bash",
+                                 "input": {"command": "rm -rf build"}}]}}])
+            events = _wait_for(collector, 1)
+            _append(path, [{"type": "user", "timestamp": "2026-02-25T10:00:05Z",
+                            "message": {"content": [
+                                {"type": "tool_result", "tool_use_id": "toolu_1",
+                                 "content": "The user doesn't want to proceed with this "
+                                            "tool use."}]}}])
+            events += _wait_for(collector, 1)
+        finally:
+            collector.stop()
+        assert [e["message_type"] for e in events] == ["tool_use:Bash", "permission_denied:Bash"]
+        assert events[1]["content_preview"] == "rm -rf build"
+
+    def test_long_interval_stops_promptly(self, tmp_path):
+        collector = Collector(["claude"], interval=1e19, projects_dir=str(tmp_path))
+        collector.start()
+        time.sleep(0.1)
+        start = time.monotonic()
+        collector.stop()
+        assert time.monotonic() - start < 2.0
+
+    def test_invalid_arguments(self):
+        with pytest.raises(ValueError):
+            Collector(["bogus"])
+        with pytest.raises(ValueError):
+            Collector(interval=0)
+        with pytest.raises(ValueError):
+            Collector(interval=float("inf"))
+        with pytest.raises(ValueError):
+            Collector(interval=1e20)
+        with pytest.raises(ValueError):
+            Collector(overflow="drop-random")
+        with pytest.raises(ValueError):
+            Collector(["claude"], sample_ratio=1.5)
+        with pytest.raises(ValueError):
+            Collector(["claude"], max_events_per_second={"network": 10})
+
+
+class TestSinks:
+    def test_callback_sink(self, tmp_path):
+        batches = []
+        collector = _tail_new_prompt(tmp_path, {"sink": batches.append},
+                                     lambda c: _poll(lambda: batches))
+        assert [e["content_preview"] for e in batches[0]] == ["new prompt"]
+        assert collector.status()["sink"] == "callback"
+        assert collector.drain() == []
+
+    def test_ndjson_sink(self, tmp_path):
+        out = tmp_path / "events.ndjson"
+        _tail_new_prompt(tmp_path, {"sink": f"ndjson:{out}"},
+                         lambda c: _poll(lambda: out.read_text()))
+        lines = [json.loads(line) for line in out.read_text().splitlines()]
+        assert [e["content_preview"] for e in lines] == ["new prompt"]
+        assert lines[0]["source"] == "claude"
+
+    def test_unix_socket_sink(self, tmp_path):
+        sock_path = tmp_path / "s.sock"
+        server = socket.socket(socket.AF_UNIX, socket.SOCK_STREAM)
+        server.bind(str(sock_path))
+        server.listen(1)
+        received = []
+
+        def serve():
+            conn, _ = server.accept()
+            received.append(conn.makefile().readline())
+            conn.close()
+
+        thread = threading.Thread(target=serve, daemon=True)
+        thread.start()
+        _tail_new_prompt(tmp_path, {"sink": f"unix:{sock_path}"}, lambda c: thread.join(5))
+        server.close()
+        assert json.loads(received[0])["content_preview"] == "new prompt"
+
+    def test_callback_errors_reported(self, tmp_path):
+        def boom(batch):
+            raise RuntimeError("nope")
+
+        def wait(collector):
+            _poll(lambda: collector.status()["sources"]["claude"]["errors"])
+
+        collector = _tail_new_prompt(tmp_path, {"sink": boom}, wait)
+        status = collector.status()
+        assert status["dropped"] == 1
+        assert "nope" in status["sources"]["claude"]["last_error"]
+
+    def test_unsupported_sink(self):
+        with pytest.raises(ValueError):
+            Collector(["claude"], sink="kafka:topic")
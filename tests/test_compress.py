@@ -0,0 +1,72 @@
+"""Tests for zstd-compressed NDJSON exports (Rust native via PyO3)."""
+
+import json
+import time
+
+import pytest
+
+from snoopy._native import Collector, EncryptionKey, read_ndjson, train_zstd_dictionary
+
+
+def _collect(tmp_path, prompts, **kwargs):
+    projects = tmp_path / "projects" / "-Users-me-app"
+    projects.mkdir(parents=True)
+    transcript = projects / "s.jsonl"
+    transcript.touch()
+    out = tmp_path / "events.ndjson.zst"
+    collector = Collector(["claude"], interval=0.05, projects_dir=str(projects.parent),
+                          sink=f"ndjson:{out}", **kwargs)
+    collector.start()
+    try:
+        time.sleep(0.2)
+        with open(transcript, "a") as f:
+            for p in prompts:
+                f.write(json.dumps({"type": "user", "timestamp": "2026-02-25T10:00:00Z",
+                                    "message": {"content": p}}) + "\n")
+        deadline = time.time() + 5
+        while collector.status()["sources"]["claude"]["events"] < len(prompts) \
+                and time.time() < deadline:
+            time.sleep(0.02)
+    finally:
+        collector.stop()
+    return out
+
+
+class TestZstdExports:
+    def test_compressed_sink_round_trip(self, tmp_path):
+        prompts = [f"please run the test suite again for module {i}" for i in range(200)]
+        out = _collect(tmp_path, prompts, compression="zstd")
+        assert out.read_bytes()[:4] == b"\x28\xb5\x2f\xfd"
+        events = read_ndjson(str(out))
+        assert [e["content_preview"] for e in events] == prompts
+        assert out.stat().st_size < len(json.dumps(events)) / 4
+
+    def test_dictionary(self, tmp_path):
+        samples = [json.dumps({"source": "claude", "message_type": "user",
+                               "content_preview": f"fix the failing test in file_{i}.py"})
+                   for i in range(500)]
+        dictionary = train_zstd_dictionary(samples, dict_size=4096)
+        assert isinstance(dictionary, bytes) and 0 < len(dictionary) <= 4096
+        out = _collect(tmp_path, ["fix the failing test in file_9.py"], compression="zstd",
+                       compression_dict=dictionary)
+        events = read_ndjson(str(out), dictionary=dictionary)
+        assert events[0]["content_preview"] == "fix the failing test in file_9.py"
+        with pytest.raises(ValueError):
+            read_ndjson(str(out))
+
+    def test_compressed_and_encrypted(self, tmp_path):
+        key = EncryptionKey.from_passphrase("pw")
+        out = _collect(tmp_path, ["secret plan"], compression="zstd", encryption=key)
+        assert b"secret plan" not in out.read_bytes()
+        assert read_ndjson(str(out), encryption=key)[0]["content_preview"] == "secret plan"
+
+    def test_plain_ndjson(self, tmp_path):
+        path = tmp_path / "plain.ndjson"
+        path.write_text('{"a": 1}\n{"a": 2}\n')
+        assert read_ndjson(str(path)) == [{"a": 1}, {"a": 2}]
+
+    def test_invalid_options(self, tmp_path):
+        with pytest.raises(ValueError):
+            Collector(["claude"], sink=f"ndjson:{tmp_path / 'x'}", compression="gzip")
+        with pytest.raises(ValueError):
+            Collector(["claude"], compression="zstd")
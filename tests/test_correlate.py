@@ -0,0 +1,62 @@
+"""Tests for network/tool correlation (Rust native via PyO3)."""
+
+import pytest
+
+from snoopy._native import correlate
+
+
+def _tool(ts, tool, preview):
+    return {"timestamp": ts, "session_id": "s1", "message_type": f"tool_use:{tool}",
+            "content_preview": preview, "project_path": "/p"}
+
+
+def _conn(ts, process, address, port=443):
+    return {"timestamp": ts, "source": "network", "process_name": process, "protocol": "TCP",
+            "remote_address": address, "remote_port": port}
+
+
+TRANSCRIPT = [
+    _tool(100.0, "Bash", "cd web && npm install"),
+    {"timestamp": 101.0, "session_id": "s1", "message_type": "assistant_text",
+     "content_preview": "curl https://example.com", "project_path": "/p"},
+    _tool(102.0, "Bash", "ls -la"),
+    _tool(110.0, "WebFetch", '{"url":"https://docs.rs/regex","prompt":"summarize"}'),
+    _tool(111.0, "Bash", "/usr/bin/curl -s https://api.github.com/repos"),
+]
+
+
+class TestCorrelate:
+    def test_matches(self):
+        network = [
+            _conn(101.5, "node", "104.16.0.1"),
+            _conn(103.0, "Safari", "17.0.0.1"),
+            _conn(112.0, "curl", "api.github.com"),
+            _conn(112.5, "claude", "docs.rs"),
+            _conn(200.0, "curl", "1.1.1.1"),
+        ]
+        annotations = correlate(network, TRANSCRIPT)
+        assert [a["connection"]["timestamp"] for a in annotations] == [101.5, 112.0, 112.5]
+        npm, curl, fetch = annotations
+        assert npm["tool_event"]["content_preview"] == "cd web && npm install"
+        assert npm["match"] == "process"
+        assert npm["delay"] == pytest.approx(1.5)
+        assert curl["tool_event"]["message_type"] == "tool_use:Bash"
+        assert curl["match"] == "host"
+        assert fetch["tool_event"]["message_type"] == "tool_use:WebFetch"
+        assert fetch["match"] == "host"
+
+    def test_window(self):
+        network = [_conn(105.0, "node", "104.16.0.1")]
+        assert len(correlate(network, TRANSCRIPT, window_secs=10)) == 1
+        assert correlate(network, TRANSCRIPT, window_secs=2) == []
+        # A connection before the call isn't caused by it.
+        assert correlate([_conn(99.0, "node", "104.16.0.1")], TRANSCRIPT) == []
+
+    def test_truncated_process_names(self):
+        transcript = [_tool(10.0, "Bash", "git push origin main")]
+        (annotation,) = correlate([_conn(11.0, "git-remot", "140.82.112.3")], transcript)
+        assert annotation["match"] == "process"
+
+    def test_invalid_window(self):
+        with pytest.raises(ValueError):
+            correlate([], [], window_secs=-1)
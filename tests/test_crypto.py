@@ -0,0 +1,179 @@
+"""Tests for encrypted exports (Rust native via PyO3)."""
+
+import json
+import time
+
+import pytest
+
+from snoopy._native import (
+    Collector,
+    EncryptionKey,
+    decrypt_file,
+    encrypt_file,
+    read_ndjson,
+)
+
+
+class TestEncryption:
+    def test_passphrase_round_trip(self, tmp_path):
+        src = tmp_path / "events.ndjson"
+        src.write_bytes(b'{"a": 1}\n' * 20_000)
+        key = EncryptionKey.from_passphrase("correct horse")
+        encrypt_file(str(src), str(tmp_path / "events.enc"), key)
+        assert b'"a"' not in (tmp_path / "events.enc").read_bytes()
+        decrypt_file(str(tmp_path / "events.enc"), str(tmp_path / "out.ndjson"),
+                     EncryptionKey.from_passphrase("correct horse"))
+        assert (tmp_path / "out.ndjson").read_bytes() == src.read_bytes()
+
+    def test_wrong_key_and_tampering_rejected(self, tmp_path):
+        src = tmp_path / "plain.txt"
+        src.write_text("secret")
+        enc = tmp_path / "plain.enc"
+        encrypt_file(str(src), str(enc), EncryptionKey.from_passphrase("one"))
+        with pytest.raises(ValueError):
+            decrypt_file(str(enc), str(tmp_path / "x"), EncryptionKey.from_passphrase("two"))
+        data = bytearray(enc.read_bytes())
+        data[-1] ^= 1
+        enc.write_bytes(bytes(data))
+        with pytest.raises(ValueError):
+            decrypt_file(str(enc), str(tmp_path / "x"), EncryptionKey.from_passphrase("one"))
+
+    def test_encrypt_in_place(self, tmp_path):
+        path = tmp_path / "events.ndjson"
+        path.write_bytes(b'{"a": 1}\n' * 20_000)
+        key = EncryptionKey.from_passphrase("pw")
+        encrypt_file(str(path), str(path), key)
+        assert b'"a"' not in path.read_bytes()
+        decrypt_file(str(path), str(tmp_path / "out.ndjson"), key)
+        assert (tmp_path / "out.ndjson").read_bytes() == b'{"a": 1}\n' * 20_000
+        assert sorted(p.name for p in tmp_path.iterdir()) == ["events.ndjson", "out.ndjson"]
+
+    def test_truncated_or_reordered_rejected(self, tmp_path):
+        src = tmp_path / "plain.txt"
+        src.write_bytes(b"x" * (2 * 64 * 1024))
+        enc = tmp_path / "plain.enc"
+        key = EncryptionKey.from_passphrase("pw")
+        encrypt_file(str(src), str(enc), key)
+        data = enc.read_bytes()
+        header, frame = 25, 12 + 4 + 64 * 1024 + 16
+        first, second = data[header:header + frame], data[header + frame:header + 2 * frame]
+        for tampered in (data[:header + 2 * frame],
+                         data[:header] + second + first + data[header + 2 * frame:]):
+            enc.write_bytes(tampered)
+            with pytest.raises(ValueError):
+                decrypt_file(str(enc), str(tmp_path / "x"), key)
+
+    def test_cut_mid_frame_keeps_earlier_frames(self, tmp_path):
+        src = tmp_path / "plain.txt"
+        src.write_bytes(b"x" * (64 * 1024) + b"y" * 100)
+        enc = tmp_path / "plain.enc"
+        key = EncryptionKey.from_passphrase("pw")
+        encrypt_file(str(src), str(enc), key)
+        data = enc.read_bytes()
+        header, frame = 25, 12 + 4 + 64 * 1024 + 16
+        enc.write_bytes(data[:header + frame + 40])
+        with pytest.raises(ValueError):
+            decrypt_file(str(enc), str(tmp_path / "out.txt"), key)
+        assert (tmp_path / "out.txt").read_bytes() == b"x" * (64 * 1024)
+
+    def test_key_file(self, tmp_path):
+        key_path = tmp_path / "snoopy.key"
+        key = EncryptionKey.generate_key_file(str(key_path))
+        assert len(key_path.read_text()) == 64
+        assert key_path.stat().st_mode & 0o777 == 0o600
+        src = tmp_path / "a.txt"
+        src.write_text("hello")
+        encrypt_file(str(src), str(tmp_path / "a.enc"), key)
+        decrypt_file(str(tmp_path / "a.enc"), str(tmp_path / "b.txt"),
+                     EncryptionKey.from_key_file(str(key_path)))
+        assert (tmp_path / "b.txt").read_text() == "hello"
+        with pytest.raises(ValueError):
+            decrypt_file(str(tmp_path / "a.enc"), str(tmp_path / "c.txt"),
+                         EncryptionKey.from_passphrase("hello"))
+
+    def test_encrypted_collector_sink(self, tmp_path):
+        projects = tmp_path / "projects" / "-Users-me-app"
+        projects.mkdir(parents=True)
+        transcript = projects / "s.jsonl"
+        transcript.touch()
+        out = tmp_path / "events.ndjson.enc"
+        key = EncryptionKey.from_passphrase("pw")
+        collector = Collector(["claude"], interval=0.05, projects_dir=str(projects.parent),
+                              sink=f"ndjson:{out}", encryption=key)
+        assert collector.status()["sink"] == "encrypted_ndjson"
+        collector.start()
+        try:
+            time.sleep(0.2)
+            with open(transcript, "a") as f:
+                f.write(json.dumps({"type": "user", "timestamp": "2026-02-25T10:00:00Z",
+                                    "message": {"content": "private prompt"}}) + "\n")
+            deadline = time.time() + 5
+            while out.stat().st_size < 100 and time.time() < deadline:
+                time.sleep(0.02)
+        finally:
+            collector.stop()
+        assert b"private prompt" not in out.read_bytes()
+        events = read_ndjson(str(out), encryption=key)
+        assert [e["content_preview"] for e in events] == ["private prompt"]
+
+    def test_collector_appends_to_encrypted_file(self, tmp_path):
+        projects = tmp_path / "projects" / "-Users-me-app"
+        projects.mkdir(parents=True)
+        transcript = projects / "s.jsonl"
+        transcript.touch()
+        out = tmp_path / "events.ndjson.enc"
+        key = EncryptionKey.from_passphrase("pw")
+        (tmp_path / "first.ndjson").write_text(json.dumps({"content_preview": "earlier"}) + "\n")
+        encrypt_file(str(tmp_path / "first.ndjson"), str(out), key)
+        size = out.stat().st_size
+        collector = Collector(["claude"], interval=0.05, projects_dir=str(projects.parent),
+                              sink=f"ndjson:{out}", encryption=key)
+        collector.start()
+        try:
+            time.sleep(0.2)
+            with open(transcript, "a") as f:
+                f.write(json.dumps({"type": "user", "timestamp": "2026-02-25T10:00:00Z",
+                                    "message": {"content": "later"}}) + "\n")
+            deadline = time.time() + 5
+            while out.stat().st_size < size + 100 and time.time() < deadline:
+                time.sleep(0.02)
+        finally:
+            collector.stop()
+        events = read_ndjson(str(out), encryption=key)
+        assert [e["content_preview"] for e in events] == ["earlier", "later"]
+
+    def test_collector_resumes_after_interrupted_append(self, tmp_path):
+        projects = tmp_path / "projects" / "-Users-me-app"
+        projects.mkdir(parents=True)
+        transcript = projects / "s.jsonl"
+        transcript.touch()
+        out = tmp_path / "events.ndjson.enc"
+        key = EncryptionKey.from_passphrase("pw")
+        (tmp_path / "first.ndjson").write_text(json.dumps({"content_preview": "earlier"}) + "\n")
+        encrypt_file(str(tmp_path / "first.ndjson"), str(out), key)
+        # Cut into the terminator, as an append stopped partway would.
+        out.write_bytes(out.read_bytes()[:-10])
+        assert [e["content_preview"] for e in read_ndjson(str(out), encryption=key)] == [
+            "earlier",
+        ]
+        size = out.stat().st_size
+        collector = Collector(["claude"], interval=0.05, projects_dir=str(projects.parent),
+                              sink=f"ndjson:{out}", encryption=key)
+        collector.start()
+        try:
+            time.sleep(0.2)
+            with open(transcript, "a") as f:
+                f.write(json.dumps({"type": "user", "timestamp": "2026-02-25T10:00:00Z",
+                                    "message": {"content": "later"}}) + "\n")
+            deadline = time.time() + 5
+            while out.stat().st_size < size + 100 and time.time() < deadline:
+                time.sleep(0.02)
+        finally:
+            collector.stop()
+        decrypt_file(str(out), str(tmp_path / "all.ndjson"), key)
+        events = read_ndjson(str(out), encryption=key)
+        assert [e["content_preview"] for e in events] == ["earlier", "later"]
+
+    def test_encryption_requires_ndjson_sink(self):
+        with pytest.raises(ValueError):
+            Collector(["claude"], encryption=EncryptionKey.from_passphrase("pw"))
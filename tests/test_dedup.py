@@ -0,0 +1,67 @@
+"""Tests for content hashing and dedup_events (Rust native via PyO3)."""
+
+import json
+
+from snoopy._native import ParserConfig, content_hash, dedup_events, parse_transcript
+
+OUTPUT = "x" * 1000 + " tests failed"
+
+
+def _write_retries(path):
+    entries = [
+        {"type": "progress", "timestamp": f"2026-02-25T10:00:0{i}Z",
+         "data": {"type": "tool_result", "tool_name": "Bash", "output": OUTPUT}}
+        for i in range(3)
+    ]
+    entries.append({"type": "progress", "timestamp": "2026-02-25T10:00:05Z",
+                    "data": {"type": "tool_result", "tool_name": "Bash",
+                             "output": "x" * 1000 + " tests passed"}})
+    path.write_text("".join(json.dumps(e) + "\n" for e in entries))
+    return str(path)
+
+
+class TestContentHash:
+    def test_known_value(self):
+        # BLAKE3 of the empty input.
+        assert content_hash("") == (
+            "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"
+        )
+        assert content_hash("héllo") == content_hash("héllo".encode())
+        assert content_hash(bytearray(b"abc")) == content_hash(b"abc")
+
+    def test_parser_option(self, tmp_path):
+        path = _write_retries(tmp_path / "s.jsonl")
+        events, _ = parse_transcript(path)
+        assert "content_hash" not in events[0]
+        config = ParserConfig(preview_len=20, content_hash=True)
+        assert config.content_hash is True
+        events, _ = parse_transcript(path, config=config)
+        # Hashes cover the full output, so the previews match but the hashes don't.
+        assert events[0]["content_preview"] == events[3]["content_preview"]
+        assert events[0]["content_hash"] == content_hash(OUTPUT)
+        assert events[3]["content_hash"] != events[0]["content_hash"]
+        assert ParserConfig.from_json(config.to_json()).content_hash is True
+
+
+class TestDedupEvents:
+    def test_collapses_retries(self, tmp_path):
+        path = _write_retries(tmp_path / "s.jsonl")
+        events, _ = parse_transcript(path, config=ParserConfig(preview_len=20, content_hash=True))
+        kept = dedup_events(events)
+        assert [e["duplicates"] for e in kept] == [2, 0]
+        assert kept[0]["timestamp"] == events[0]["timestamp"]
+        assert "duplicates" not in events[0]
+
+    def test_falls_back_to_preview(self):
+        events = [
+            {"session_id": "a", "message_type": "user", "content_preview": "hi"},
+            {"session_id": "b", "message_type": "user", "content_preview": "hi"},
+            {"session_id": "a", "message_type": "user", "content_preview": "hi"},
+            {"source": "wifi", "ssid": "home"},
+            {"source": "wifi", "ssid": "home"},
+        ]
+        kept = dedup_events(events)
+        assert [(e.get("session_id"), e["duplicates"]) for e in kept] == [
+            ("a", 1), ("b", 0), (None, 0), (None, 0)
+        ]
+        assert dedup_events([]) == []
@@ -0,0 +1,94 @@
+"""Tests for DNS cache parsing (Rust native via PyO3)."""
+
+import json
+
+from snoopy._native import dns_hostnames, parse_dns_cache
+
+MDNS_DUMP = """\
+   Slt Q     TTL if     U Type rdlen
+    12      1765 en0      A     4 edge.example.net. Addr 93.184.216.34
+    13      1765 en0   AAAA    16 edge.example.net. AAAA 2606:2800:220:1::1946
+    40       299 en0  CNAME    18 www.example.com. CNAME cdn.example.com.
+    41       299 en0  CNAME    18 cdn.example.com. CNAME edge.example.net.
+    57      3599 en0      A     4 api.other.org. Addr 203.0.113.7
+"""
+
+QUERY = """\
+name: Mail.Example.com
+alias: imap.example.com
+ip_address: 198.51.100.20
+
+name: localhost
+ip_address: 127.0.0.1
+"""
+
+CACHEDUMP = """\
+DirectoryService Cache Overview:
+    AAAA Queries  - Enabled
+
+Cache entries (ordered as stored in the cache):
+
+     Category         Best Before         Last Access      Hits    Refs       TTL    Neg
+    ----------  ------------------  ------------------  --------  ------  --------  -----
+        Host   03/01/24 10:15:02   03/01/24 10:14:02         3       5        60
+                 Key: h_name:www.example.com ipv4:1 ipv6:0
+                 Key: h_name:www.example.com ipv4:1
+"""
+
+
+class TestParseDnsCache:
+    def test_mdns_dump(self):
+        records = parse_dns_cache(MDNS_DUMP)
+        assert len(records) == 5
+        a = records[0]
+        assert a["source"] == "dns"
+        assert a["hostname"] == "edge.example.net"
+        assert a["address"] == "93.184.216.34"
+        assert a["record_type"] == "A"
+        assert a["ttl"] == 1765
+        assert a["interface"] == "en0"
+        assert a["aliases"] == ["cdn.example.com", "www.example.com"]
+        assert records[1]["address"] == "2606:2800:220:1::1946"
+        cname = records[2]
+        assert cname["record_type"] == "CNAME"
+        assert cname["cname"] == "cdn.example.com"
+        assert cname["address"] is None
+
+    def test_log_show_ndjson(self):
+        entry = {
+            "timestamp": "2024-03-01 10:00:00.000000+0000",
+            "processImagePath": "/usr/sbin/mDNSResponder",
+            "eventMessage": MDNS_DUMP,
+        }
+        records = parse_dns_cache(json.dumps(entry))
+        assert len(records) == 5
+        assert {r["timestamp"] for r in records} == {1709287200.0}
+
+    def test_dscacheutil_query(self):
+        records = parse_dns_cache(QUERY)
+        assert [(r["hostname"], r["record_type"]) for r in records] == [
+            ("imap.example.com", "CNAME"),
+            ("mail.example.com", "A"),
+            ("localhost", "A"),
+        ]
+        assert records[1]["aliases"] == ["imap.example.com"]
+
+    def test_cachedump(self):
+        (record,) = parse_dns_cache(CACHEDUMP)
+        assert record["hostname"] == "www.example.com"
+        assert record["address"] is None
+        assert record["hits"] == 3
+        assert record["ttl"] == 60
+        assert record["timestamp"] is not None
+
+
+class TestDnsHostnames:
+    def test_maps_addresses_through_cnames(self):
+        names = dns_hostnames(MDNS_DUMP + QUERY)
+        assert names["93.184.216.34"] == ["cdn.example.com", "www.example.com", "edge.example.net"]
+        assert names["2606:2800:220:1::1946"][-1] == "edge.example.net"
+        assert names["203.0.113.7"] == ["api.other.org"]
+        assert names["198.51.100.20"] == ["imap.example.com", "mail.example.com"]
+
+    def test_empty(self):
+        assert dns_hostnames("") == {}
@@ -0,0 +1,101 @@
+"""Tests for download origin lookup and the DownloadsMonitor (Rust native via PyO3)."""
+
+import os
+import time
+
+import pytest
+
+from snoopy._native import DownloadsMonitor, download_origin
+
+URL = "https://files.example.com/report.pdf?token=1"
+REFERRER = "https://www.example.com/reports"
+
+
+def _tag(path, url=URL, referrer=REFERRER):
+    os.setxattr(path, "user.xdg.origin.url", url.encode())
+    os.setxattr(path, "user.xdg.referrer.url", referrer.encode())
+
+
+def _poll_until(monitor, n, timeout=5.0):
+    downloads = []
+    deadline = time.time() + timeout
+    while len(downloads) < n and time.time() < deadline:
+        downloads.extend(monitor.poll(timeout=0.1))
+    return downloads
+
+
+@pytest.fixture
+def xattrs(tmp_path):
+    probe = tmp_path / "probe"
+    probe.write_text("")
+    try:
+        os.setxattr(probe, "user.test", b"1")
+    except (AttributeError, OSError):
+        pytest.skip("user extended attributes not supported here")
+    return tmp_path
+
+
+class TestDownloadOrigin:
+    def test_reads_xdg_attributes(self, xattrs):
+        path = xattrs / "report.pdf"
+        path.write_bytes(b"%PDF" * 10)
+        _tag(path)
+        origin = download_origin(path, quarantine_db=xattrs / "missing.db")
+        assert origin["path"] == str(path)
+        assert origin["file_name"] == "report.pdf"
+        assert origin["size"] == 40
+        assert origin["url"] == URL
+        assert origin["origin"] == REFERRER
+        assert origin["domain"] == "example.com"
+        assert origin["app"] is None
+        assert origin["quarantined"] is False
+        assert origin["summary"] == "downloaded report.pdf from example.com"
+
+    def test_no_metadata(self, tmp_path):
+        path = tmp_path / "notes.txt"
+        path.write_text("local")
+        assert download_origin(path) is None
+
+
+class TestDownloadsMonitor:
+    def test_reports_finished_downloads(self, xattrs):
+        monitor = DownloadsMonitor(xattrs, debounce=0.1, quarantine_db=xattrs / "missing.db")
+        monitor.start()
+        try:
+            partial = xattrs / "report.pdf.crdownload"
+            partial.write_bytes(b"%PDF")
+            _tag(partial)
+            time.sleep(0.3)
+            partial.rename(xattrs / "report.pdf")
+            (xattrs / "notes.txt").write_text("saved locally")
+            downloads = _poll_until(monitor, 1)
+            time.sleep(0.3)
+            downloads.extend(monitor.poll())
+        finally:
+            monitor.stop()
+        assert [d["file_name"] for d in downloads] == ["report.pdf"]
+        assert downloads[0]["url"] == URL
+        assert downloads[0]["timestamp"] == pytest.approx(time.time(), abs=5)
+
+    def test_include_local(self, xattrs):
+        monitor = DownloadsMonitor(xattrs, debounce=0.1, include_local=True)
+        monitor.start()
+        try:
+            (xattrs / "notes.txt").write_text("saved locally")
+            downloads = _poll_until(monitor, 1)
+        finally:
+            monitor.stop()
+        assert [d["file_name"] for d in downloads] == ["notes.txt"]
+        assert downloads[0]["kind"] == "local"
+        assert downloads[0]["url"] is None
+
+    def test_running(self, tmp_path):
+        monitor = DownloadsMonitor(tmp_path)
+        assert not monitor.running
+        monitor.start()
+        assert monitor.running
+        monitor.stop()
+        assert not monitor.running
+        with monitor:
+            assert monitor.running
+        assert not monitor.running
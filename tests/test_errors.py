@@ -0,0 +1,149 @@
+"""Tests for the native exception hierarchy (Rust native via PyO3)."""
+
+import datetime
+import os
+import subprocess
+import sys
+
+import pytest
+
+from snoopy._native import (
+    DatabaseLocked,
+    EncryptionKey,
+    FormatError,
+    ParseError,
+    PermissionDenied,
+    chromium_history,
+    generate_daily_report,
+    infer_git_activity,
+    infer_title,
+    install_log_events,
+    parse_shell_history,
+    parse_transcript,
+    perceptual_hash,
+    read_ndjson,
+)
+
+# Readers of a single file that report it the same way when it can't be opened.
+FILE_READERS = [
+    lambda path: infer_title(str(path)),
+    lambda path: infer_git_activity(str(path)),
+    lambda path: parse_shell_history(str(path)),
+    lambda path: perceptual_hash(path),
+    lambda path: install_log_events(path),
+]
+from snoopy.db import Database
+
+LOCK_HOLDER = """
+import sqlite3, sys, time
+conn = sqlite3.connect(sys.argv[1], isolation_level=None)
+conn.execute("PRAGMA journal_mode=DELETE")
+conn.execute("BEGIN EXCLUSIVE")
+print("locked", flush=True)
+time.sleep(60)
+"""
+
+
+class TestHierarchy:
+    def test_bases(self):
+        assert issubclass(ParseError, ValueError)
+        assert issubclass(FormatError, ValueError)
+        assert issubclass(DatabaseLocked, IOError)
+        assert issubclass(PermissionDenied, PermissionError)
+        assert issubclass(PermissionDenied, IOError)
+
+
+class TestParseError:
+    def test_invalid_utf8_in_transcript(self, tmp_path):
+        path = tmp_path / "s.jsonl"
+        good = b'{"type": "user", "message": {"content": "hi"}}\n'
+        path.write_bytes(good + b'{"type": "user", "x": "\xff\xfe"}\n')
+        with pytest.raises(ParseError) as info:
+            parse_transcript(str(path))
+        assert info.value.path == str(path)
+        assert info.value.offset == len(good)
+        assert "UTF-8" in info.value.cause
+
+    def test_missing_file_is_plain_ioerror(self, tmp_path):
+        with pytest.raises(IOError) as info:
+            parse_transcript(str(tmp_path / "missing.jsonl"))
+        assert not isinstance(info.value, PermissionDenied)
+
+    @pytest.mark.parametrize("read", FILE_READERS)
+    def test_missing_file_names_path(self, tmp_path, read):
+        path = tmp_path / "missing"
+        with pytest.raises(IOError) as info:
+            read(path)
+        assert str(path) in str(info.value)
+
+
+class TestFormatError:
+    def test_not_a_database(self, tmp_path):
+        db = tmp_path / "History"
+        db.write_bytes(b"this is not sqlite" * 100)
+        with pytest.raises(FormatError) as info:
+            chromium_history(str(db))
+        assert info.value.path == str(db)
+        assert info.value.offset is None
+        assert info.value.cause
+
+    def test_not_encrypted(self, tmp_path):
+        path = tmp_path / "events.ndjson"
+        path.write_text('{"a": 1}\n')
+        with pytest.raises(FormatError):
+            read_ndjson(str(path), encryption=EncryptionKey.from_passphrase("pw"))
+
+    def test_corrupt_zstd(self, tmp_path):
+        path = tmp_path / "events.ndjson"
+        path.write_bytes(b"\x28\xb5\x2f\xfd" + b"\x00" * 16)
+        with pytest.raises(FormatError, match="zstd"):
+            read_ndjson(str(path))
+
+
+class TestDatabaseLocked:
+    def test_locked_store(self, tmp_path):
+        db = tmp_path / "snoopy.db"
+        with Database(path=db):
+            pass
+        # Another process, since SQLite locks don't conflict within one.
+        holder = subprocess.Popen(
+            [sys.executable, "-c", LOCK_HOLDER, str(db)], stdout=subprocess.PIPE, text=True
+        )
+        try:
+            assert holder.stdout.readline().strip() == "locked"
+            with pytest.raises(DatabaseLocked) as info:
+                generate_daily_report(datetime.date(2024, 3, 1), ["messages"], db_path=db)
+        finally:
+            holder.kill()
+            holder.wait()
+        assert info.value.path == str(db)
+        assert "locked" in info.value.cause
+
+
+class TestPermissionDenied:
+    def test_unreadable_transcript(self, tmp_path):
+        if os.geteuid() == 0:
+            pytest.skip("root bypasses file permissions")
+        path = tmp_path / "s.jsonl"
+        path.write_text("{}\n")
+        path.chmod(0)
+        try:
+            with pytest.raises(PermissionDenied) as info:
+                parse_transcript(str(path))
+        finally:
+            path.chmod(0o600)
+        assert info.value.path == str(path)
+
+    @pytest.mark.parametrize("read", FILE_READERS)
+    def test_unreadable_file(self, tmp_path, read):
+        if os.geteuid() == 0:
+            pytest.skip("root bypasses file permissions")
+        path = tmp_path / "unreadable"
+        path.write_text("{}\n")
+        path.chmod(0)
+        try:
+            with pytest.raises(PermissionDenied) as info:
+                read(path)
+        finally:
+            path.chmod(0o600)
+        assert info.value.path == str(path)
@@ -0,0 +1,145 @@
+"""Tests for the eslogger NDJSON parser (Rust native via PyO3)."""
+
+import json
+
+from snoopy._native import EsloggerParser, FileActivityParser, parse_eslogger
+
+
+def proc(pid, exe, ppid=1, uid=501):
+    return {
+        "audit_token": {"pid": pid, "euid": uid},
+        "ppid": ppid,
+        "executable": {"path": exe},
+        "signing_id": "com.example." + exe.rsplit("/", 1)[-1],
+        "team_id": None,
+    }
+
+
+def line(seq, time, event, process):
+    return json.dumps({"seq_num": seq, "time": time, "event": event, "process": process})
+
+
+ZSH = proc(400, "/bin/zsh")
+EDITOR = proc(500, "/usr/bin/vim", ppid=400)
+
+EVENTS = [
+    line(
+        1,
+        "2024-03-01T10:00:00.000000Z",
+        {
+            "exec": {
+                "target": EDITOR,
+                "args": ["vim", "notes.md"],
+                "cwd": {"path": "/Users/me/Documents"},
+            }
+        },
+        ZSH,
+    ),
+    line(
+        2,
+        "2024-03-01T10:00:01.000000Z",
+        {"open": {"file": {"path": "/Users/me/Documents/notes.md"}, "fflag": 3}},
+        EDITOR,
+    ),
+    line(
+        3,
+        "2024-03-01T10:00:02.000000Z",
+        {"close": {"target": {"path": "/Users/me/Documents/notes.md"}, "modified": True}},
+        EDITOR,
+    ),
+    line(
+        4,
+        "2024-03-01T10:00:03.000000Z",
+        {"close": {"target": {"path": "/etc/hosts"}, "modified": False}},
+        EDITOR,
+    ),
+    line(
+        5,
+        "2024-03-01T10:00:04.000000Z",
+        {
+            "rename": {
+                "source": {"path": "/Users/me/Documents/.notes.md.swp"},
+                "destination": {
+                    "new_path": {"dir": {"path": "/Users/me/Documents/"}, "filename": "x.md"}
+                },
+            }
+        },
+        EDITOR,
+    ),
+    line(
+        6,
+        "2024-03-01T10:00:05.000000Z",
+        {"uipc_connect": {"file": {"path": "/var/run/mDNSResponder"}}},
+        EDITOR,
+    ),
+    line(
+        7,
+        "2024-03-01T10:00:06.000000Z",
+        {"openssh_login": {"username": "me", "source_address": "10.0.0.5", "success": True}},
+        proc(600, "/usr/sbin/sshd"),
+    ),
+    line(8, "2024-03-01T10:00:07.000000Z", {"mmap": {}}, EDITOR),
+]
+NDJSON = "\n".join(EVENTS) + "\n"
+
+
+class TestParseEslogger:
+    def test_maps_event_types(self):
+        events = parse_eslogger(NDJSON)
+        assert [(e["source"], e["kind"], e["seq"]) for e in events] == [
+            ("process", "exec", 1),
+            ("file", "open", 2),
+            ("file", "write", 3),
+            ("file", "rename", 5),
+            ("network", "connect", 6),
+            ("login", "ssh", 7),
+        ]
+        exec_, opened, written, renamed, connect, login = events
+        assert exec_["pid"] == 500
+        assert exec_["parent"] == 400
+        assert exec_["process_name"] == "vim"
+        assert exec_["argv"] == ["vim", "notes.md"]
+        assert exec_["cwd"] == "/Users/me/Documents"
+        assert opened["write"] is True
+        assert opened["path"] == "/Users/me/Documents/notes.md"
+        assert written["path"] == "/Users/me/Documents/notes.md"
+        assert renamed["path"] == "/Users/me/Documents/x.md"
+        assert renamed["previous_path"] == "/Users/me/Documents/.notes.md.swp"
+        assert connect["socket_path"] == "/var/run/mDNSResponder"
+        assert connect["protocol"] == "unix"
+        assert login["user"] == "me"
+        assert login["remote_address"] == "10.0.0.5"
+        assert login["success"] is True
+        assert login["process_name"] == "sshd"
+
+    def test_since_and_junk(self):
+        text = "not json\n{broken\n" + NDJSON
+        events = parse_eslogger(text, since=1709287203.0)
+        assert [e["seq"] for e in events] == [5, 6, 7]
+
+
+class TestEsloggerParser:
+    def test_feed_holds_partial_lines(self):
+        parser = EsloggerParser()
+        head, tail = NDJSON[:50], NDJSON[50:-10]
+        assert parser.feed(head) == []
+        events = parser.feed(tail)
+        assert [e["seq"] for e in events] == [1, 2, 3, 5, 6, 7]
+        assert parser.feed(NDJSON[-10:]) == []
+        assert parser.flush() == []
+
+    def test_flush_parses_last_line(self):
+        parser = EsloggerParser()
+        assert parser.feed(EVENTS[0]) == []
+        assert [e["kind"] for e in parser.flush()] == ["exec"]
+        assert parser.flush() == []
+
+
+def test_file_activity_accepts_eslogger():
+    parser = FileActivityParser(["/Users/me/Documents"])
+    assert parser.feed(NDJSON) == []
+    (event,) = parser.flush()
+    assert event["process_name"] == "vim"
+    assert event["pid"] == 500
+    assert (event["opens"], event["writes"]) == (0, 3)
+    assert event["files"] == ["/Users/me/Documents/notes.md", "/Users/me/Documents/x.md"]
@@ -0,0 +1,56 @@
+"""Tests for tool failure analytics (Rust native via PyO3)."""
+
+import pytest
+
+from snoopy._native import parse_transcript, tool_failure_stats
+
+
+def _call(tool_id, command, output, is_error):
+    return [
+        {"type": "assistant", "timestamp": "2026-02-25T10:00:00Z",
+         "message": {"content": [{"type": "tool_use", "id": tool_id, "name": "Bash",
+                                  "input": {"command": command}}]}},
+        {"type": "user", "timestamp": "2026-02-25T10:00:01Z",
+         "message": {"content": [{"type": "tool_result", "tool_use_id": tool_id,
+                                  "content": output, "is_error": is_error}]}},
+    ]
+
+
+@pytest.fixture
+def projects(tmp_path, write_transcript):
+    write_transcript(tmp_path / "-Users-me-app" / "s1.jsonl", [
+        *_call("t1", "pytest", "1 failed", True),
+        *_call("t2", "pytest", "1 failed", True),
+        *_call("t3", "ls", "a b", False),
+    ])
+    write_transcript(tmp_path / "-Users-me-app" / "s2.jsonl", [
+        *_call("t4", "cargo build", "error[E0308]", True),
+    ])
+    return tmp_path
+
+
+class TestToolFailureStats:
+    def test_per_session(self, projects):
+        stats = tool_failure_stats(str(projects))
+
+        s1 = stats["s1"]
+        assert (s1["calls"], s1["errors"]) == (3, 2)
+        assert s1["error_rate"] == pytest.approx(2 / 3)
+        assert s1["tools"]["Bash"]["errors"] == 2
+        assert s1["top_failing"] == [{"tool": "Bash", "command": "pytest", "count": 2}]
+        assert stats["s2"]["errors"] == 1
+
+    def test_per_project(self, projects):
+        stats = tool_failure_stats(str(projects), group_by="project", top_n=1)
+        assert list(stats) == ["/Users/me/app"]
+        assert (stats["/Users/me/app"]["calls"], stats["/Users/me/app"]["errors"]) == (4, 3)
+        assert len(stats["/Users/me/app"]["top_failing"]) == 1
+
+    def test_invalid_group_by(self, projects):
+        with pytest.raises(ValueError):
+            tool_failure_stats(str(projects), group_by="day")
+
+    def test_parse_transcript_emits_tool_errors(self, projects):
+        events, _ = parse_transcript(str(projects / "-Users-me-app" / "s2.jsonl"))
+        assert [e["message_type"] for e in events] == ["tool_use:Bash", "tool_error:Bash"]
+        assert events[1]["content_preview"] == "error[E0308]"
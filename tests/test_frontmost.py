@@ -0,0 +1,82 @@
+"""Tests for frontmost_window and the frontmost Collector source (Rust native via PyO3)."""
+
+import os
+import sys
+import time
+
+import pytest
+
+from snoopy._native import Collector, frontmost_window
+
+pytestmark = pytest.mark.skipif(sys.platform == "darwin", reason="uses the X11 xprop path")
+
+
+def _window_props(title, wm_class='"code", "Code"', pid=4242):
+    return (
+        f'_NET_WM_NAME(UTF8_STRING) = "{title}"\n'
+        f"WM_CLASS(STRING) = {wm_class}\n"
+        f"_NET_WM_PID(CARDINAL) = {pid}\n"
+    )
+
+
+def _fake_xprop(tmp_path, monkeypatch, props, active="0x3a00007", exit_code=0):
+    bin_dir = tmp_path / "bin"
+    bin_dir.mkdir()
+    state = tmp_path / "props"
+    state.write_text(props)
+    script = bin_dir / "xprop"
+    script.write_text(
+        "#!/bin/sh\n"
+        f"[ {exit_code} -ne 0 ] && exit {exit_code}\n"
+        'if [ "$1" = "-root" ]; then\n'
+        f'  echo "_NET_ACTIVE_WINDOW(WINDOW): window id # {active}"\n'
+        "else\n"
+        f'  cat "{state}"\n'
+        "fi\n"
+    )
+    script.chmod(0o755)
+    monkeypatch.setenv("PATH", f"{bin_dir}{os.pathsep}{os.environ['PATH']}")
+    return state
+
+
+class TestFrontmostWindow:
+    def test_reads_active_window(self, tmp_path, monkeypatch):
+        _fake_xprop(tmp_path, monkeypatch, _window_props('main.rs \\"draft\\" - snoopy'))
+        assert frontmost_window() == {
+            "app": "Code",
+            "pid": 4242,
+            "title": 'main.rs "draft" - snoopy',
+        }
+
+    def test_no_active_window(self, tmp_path, monkeypatch):
+        _fake_xprop(tmp_path, monkeypatch, _window_props("x"), active="0x0")
+        assert frontmost_window() is None
+
+    def test_xprop_failure(self, tmp_path, monkeypatch):
+        _fake_xprop(tmp_path, monkeypatch, "", exit_code=1)
+        assert frontmost_window() is None
+
+    def test_collector_emits_changes(self, tmp_path, monkeypatch):
+        state = _fake_xprop(tmp_path, monkeypatch, _window_props("notes.md"))
+        collector = Collector(["frontmost"], interval=0.05)
+        collector.start()
+        try:
+            time.sleep(0.3)
+            state.write_text(_window_props("Inbox", wm_class='"firefox", "Firefox"', pid=7))
+            events = []
+            deadline = time.time() + 5.0
+            while len(events) < 2 and time.time() < deadline:
+                events.extend(collector.drain())
+                time.sleep(0.02)
+        finally:
+            collector.stop()
+        assert [(e["app"], e["title"]) for e in events] == [
+            ("Code", "notes.md"),
+            ("Firefox", "Inbox"),
+        ]
+        assert all(e["source"] == "frontmost" for e in events)
+        assert events[0]["previous_app"] is None
+        assert events[1]["previous_app"] == "Code"
+        assert events[1]["previous_title"] == "notes.md"
+        assert events[1]["previous_duration"] >= 0.2
+        assert events[1]["pid"] == 7
@@ -0,0 +1,60 @@
+"""Tests for FileActivityParser (Rust native via PyO3)."""
+
+import pytest
+
+from snoopy._native import FileActivityParser
+
+FS_USAGE = """\
+10:00:00.000100  open   F=5   (R_____)  /Users/me/Documents/a.txt   0.000012   TextEdit.1001
+10:00:00.000200  read   F=5   B=0x200   0.000010   TextEdit.1001
+10:00:01.000000  open   F=6   (_WC_T_)  /Users/me/Documents/notes/b.md  0.000020   TextEdit.1001
+10:00:01.500000  write   F=6   B=0x10   0.000008   TextEdit.1001
+10:00:02.000000  close   F=6   0.000003   TextEdit.1001
+10:00:03.000000  open   F=3   (R_____)  /tmp/cache/x.db   0.000005   mds_stores.2002
+10:00:04.000000  unlink   /Users/me/Documents/old copy.txt  0.000030 W  Finder.77
+"""
+
+
+class TestFileActivityParser:
+    def test_groups_by_process_and_watched_dir(self):
+        parser = FileActivityParser(["/Users/me/Documents/"], window=60)
+        assert parser.feed(FS_USAGE) == []
+        events = parser.flush()
+        assert [(e["process_name"], e["opens"], e["writes"]) for e in events] == [
+            ("TextEdit", 1, 2),
+            ("Finder", 0, 1),
+        ]
+        edit = events[0]
+        assert edit["source"] == "file_activity"
+        assert edit["directory"] == "/Users/me/Documents"
+        assert edit["files"] == ["/Users/me/Documents/a.txt", "/Users/me/Documents/notes/b.md"]
+        assert edit["file_count"] == 2
+        assert edit["duration"] == pytest.approx(1.4999, abs=1e-3)
+        assert edit["summary"] == "TextEdit wrote 2 files in /Users/me/Documents"
+        assert events[1]["files"] == ["/Users/me/Documents/old copy.txt"]
+
+    def test_window_throttles_and_partial_lines(self):
+        parser = FileActivityParser(window=2.5)
+        lines = FS_USAGE.splitlines(keepends=True)
+        head, tail = lines[0][:30], lines[0][30:]
+        assert parser.feed(head) == []
+        assert parser.feed(tail + "".join(lines[1:5])) == []
+        # The /tmp open moves the trace clock to 10:00:03, closing only the first group.
+        due = parser.feed(lines[5])
+        assert [(e["directory"], e["opens"]) for e in due] == [("/Users/me/Documents", 1)]
+        rest = parser.flush()
+        assert {e["directory"] for e in rest} == {"/Users/me/Documents/notes", "/tmp/cache"}
+
+    def test_opensnoop(self):
+        parser = FileActivityParser(["/etc"])
+        parser.feed("  UID    PID COMM          FD PATH\n"
+                    "    0    412 sshd           4 /etc/ssh/sshd_config\n"
+                    "  501    990 curl          -1 /etc/missing\n"
+                    "  501    990 curl           3 /usr/lib/libz.dylib\n")
+        [event] = parser.flush()
+        assert (event["process_name"], event["pid"], event["opens"]) == ("sshd", 412, 1)
+        assert event["summary"] == "sshd read 1 file in /etc"
+
+    def test_rejects_negative_window(self):
+        with pytest.raises(ValueError):
+            FileActivityParser(window=-1)
@@ -0,0 +1,90 @@
+"""Tests for the fuzzy event index (Rust native via PyO3)."""
+
+import sqlite3
+
+import pytest
+
+from snoopy._native import FuzzyIndex
+
+EVENTS = [
+    {"source": "claude", "timestamp": 100.0, "session_id": "s1",
+     "content_preview": "Fixed the connection pooling bug in the database layer"},
+    {"source": "messages", "timestamp": 200.0, "contact": "Alex",
+     "content_preview": "can you review the connection pool PR?"},
+    {"source": "shell", "timestamp": 300.0, "command": "cargo test --release"},
+    {"source": "browser", "timestamp": 400.0, "title": "Connexion — Café Résumé",
+     "url": "https://example.com/pooling"},
+    "not an event",
+]
+
+
+@pytest.fixture
+def index():
+    return FuzzyIndex(EVENTS)
+
+
+class TestFuzzyIndex:
+    def test_exact_and_typo(self, index):
+        assert len(index) == 4
+        hits = index.search("conection pool", prefix=False)
+        # "pooling" is no match for a finished word.
+        assert [h["event"]["timestamp"] for h in hits] == [200.0]
+        assert hits[0]["terms"] == ["connection", "pool"]
+        assert hits[0]["score"] == pytest.approx((0.5 + 1.0) / 2)
+        assert hits[0]["event"] is EVENTS[1]
+        assert index.search("conection pool", prefix=False, max_distance=0) == []
+
+    def test_prefix(self, index):
+        hits = index.search("connection poo")
+        assert [h["event"]["timestamp"] for h in hits] == [200.0, 100.0, 400.0]
+        assert hits[0]["terms"] == ["connection", "pool"]
+        assert hits[1]["terms"] == ["connection", "pooling"]
+        assert hits[0]["score"] == hits[1]["score"] == pytest.approx((1.0 + 0.8) / 2)
+        assert hits[2]["terms"] == ["connexion", "pooling"]
+        assert index.search("connection poo", prefix=False) == []
+
+    def test_ranking_ties_newest_first(self, index):
+        hits = index.search("connexion", max_distance=2)
+        assert [h["event"]["timestamp"] for h in hits] == [400.0, 200.0, 100.0]
+        assert hits[0]["score"] == 1.0
+
+    def test_unicode_short_words_and_limit(self, index):
+        assert [h["event"]["source"] for h in index.search("resume")] == []
+        assert [h["event"]["source"] for h in index.search("résumé")] == ["browser"]
+        # Short words need an exact match unless asked otherwise.
+        assert index.search("cargp", prefix=False)[0]["terms"] == ["cargo"]
+        assert index.search("tst", prefix=False) == []
+        assert len(index.search("tst", prefix=False, max_distance=1)) == 1
+        assert len(index.search("connection", limit=1)) == 1
+        assert index.search("") == []
+        assert index.search("zzzz") == []
+
+    def test_fields(self):
+        index = FuzzyIndex(EVENTS, fields=["command"])
+        assert index.search("connection") == []
+        assert len(index.search("release")) == 1
+
+    def test_from_db(self, tmp_path):
+        db = tmp_path / "snoopy.db"
+        conn = sqlite3.connect(db)
+        conn.executescript("""
+            CREATE TABLE claude_events (id INTEGER PRIMARY KEY, timestamp REAL NOT NULL,
+                session_id TEXT, message_type TEXT, content_preview TEXT, project_path TEXT);
+            CREATE TABLE shell_events (id INTEGER PRIMARY KEY, timestamp REAL NOT NULL,
+                command TEXT, elapsed_seconds REAL);
+            INSERT INTO claude_events VALUES (1, 10.0, 's1', 'user', 'refactor the parser', '/p');
+            INSERT INTO claude_events VALUES (2, 20.0, 's1', 'assistant_text', NULL, '/p');
+            INSERT INTO shell_events VALUES (1, 30.0, 'git commit -m parser', 1.5);
+        """)
+        conn.commit()
+        conn.close()
+        index = FuzzyIndex.from_db(str(db))
+        assert len(index) == 3
+        hits = index.search("parsr")
+        assert [(h["event"]["table"], h["event"]["id"]) for h in hits] == [
+            ("shell_events", 1), ("claude_events", 1)]
+        assert hits[0]["event"]["elapsed_seconds"] == 1.5
+        assert len(FuzzyIndex.from_db(str(db), since=15.0)) == 2
+        assert len(FuzzyIndex.from_db(str(db), tables=["shell_events", "missing"])) == 1
+        with pytest.raises(ValueError):
+            FuzzyIndex.from_db(str(db), tables=["x; DROP TABLE y"])
@@ -0,0 +1,62 @@
+"""Tests for git activity inference from transcripts (Rust native via PyO3)."""
+
+from snoopy._native import infer_git_activity
+
+
+def _bash(tool_id, command, output, cwd="/Users/me/app"):
+    return [
+        {"type": "assistant", "timestamp": "2026-02-25T10:00:00Z", "cwd": cwd,
+         "message": {"content": [{"type": "tool_use", "id": tool_id, "name": "Bash",
+                                  "input": {"command": command}}]}},
+        {"type": "user", "timestamp": "2026-02-25T10:00:01Z", "cwd": cwd,
+         "message": {"content": [{"type": "tool_result", "tool_use_id": tool_id,
+                                  "content": output}]}},
+    ]
+
+
+class TestInferGitActivity:
+    def test_commit_with_heredoc_message(self, tmp_path, write_transcript):
+        transcript = tmp_path / "s.jsonl"
+        command = (
+            "git add -A && git commit -m \"$(cat <<'EOF'\nFix flaky parser test\n\n"
+            "Longer description.\nEOF\n)\""
+        )
+        write_transcript(transcript, _bash(
+            "t1", command, "[main 1a2b3c4] Fix flaky parser test\n 1 file changed",
+        ))
+
+        events = infer_git_activity(str(transcript))
+        assert len(events) == 1
+        ev = events[0]
+        assert ev["event_type"] == "git_commit"
+        assert ev["message"] == "Fix flaky parser test"
+        assert (ev["branch"], ev["sha"], ev["repo"]) == ("main", "1a2b3c4", "/Users/me/app")
+        assert ev["success"] is True
+        assert ev["session_id"] == "s"
+
+    def test_push_checkout_and_pr(self, tmp_path, write_transcript):
+        transcript = tmp_path / "s.jsonl"
+        write_transcript(transcript, [
+            *_bash("t1", "git checkout -b feature/x", "Switched to a new branch 'feature/x'"),
+            *_bash("t2", "cd /tmp/other && git push -u origin feature/x",
+                   "To github.com:me/app.git\n * [new branch]      feature/x -> feature/x"),
+            *_bash("t3", 'gh pr create --title "Add x" --body "..."',
+                   "https://github.com/me/app/pull/42"),
+            *_bash("t4", "git checkout -- README.md", ""),
+        ])
+
+        events = infer_git_activity(str(transcript))
+        assert [e["event_type"] for e in events] == ["git_checkout", "git_push", "pr_create"]
+        assert events[0]["branch"] == "feature/x"
+        assert events[1]["branch"] == "feature/x"
+        assert events[1]["repo"] == "/tmp/other"
+        assert events[2]["message"] == "Add x"
+        assert events[2]["url"] == "https://github.com/me/app/pull/42"
+
+    def test_failed_push(self, tmp_path, write_transcript):
+        transcript = tmp_path / "s.jsonl"
+        write_transcript(transcript, _bash(
+            "t1", "git push", " ! [rejected]        main -> main (fetch first)\nerror: failed",
+        ))
+        events = infer_git_activity(str(transcript))
+        assert events[0]["success"] is False
@@ -0,0 +1,93 @@
+"""Tests for scan_git_activity (Rust native via PyO3)."""
+
+import os
+import subprocess
+
+from snoopy._native import scan_git_activity
+
+ME = ("Ada", "ada@example.com")
+OTHER = ("Bob", "bob@example.com")
+
+
+def _git(repo, *args, who=ME, when=1_700_000_000):
+    env = dict(
+        os.environ,
+        GIT_AUTHOR_NAME=who[0], GIT_AUTHOR_EMAIL=who[1],
+        GIT_COMMITTER_NAME=who[0], GIT_COMMITTER_EMAIL=who[1],
+        GIT_AUTHOR_DATE=f"@{when} +0000", GIT_COMMITTER_DATE=f"@{when} +0000",
+        GIT_CONFIG_NOSYSTEM="1", HOME=str(repo),
+    )
+    subprocess.run(["git", *args], cwd=repo, env=env, check=True, capture_output=True)
+
+
+def _repo(path):
+    path.mkdir(parents=True)
+    _git(path, "init", "-q", "-b", "main")
+    _git(path, "config", "user.name", ME[0])
+    _git(path, "config", "user.email", ME[1])
+    return path
+
+
+def _commit(repo, message, **kw):
+    _git(repo, "commit", "-q", "--allow-empty", "-m", message, **kw)
+
+
+class TestScanGitActivity:
+    def test_reflog_commits_checkouts_and_merges(self, tmp_path):
+        repo = _repo(tmp_path / "proj")
+        _commit(repo, "first", when=1_700_000_000)
+        _git(repo, "checkout", "-q", "-b", "feature", when=1_700_000_010)
+        _commit(repo, "add feature", when=1_700_000_020)
+        _git(repo, "checkout", "-q", "main", when=1_700_000_030)
+        _git(repo, "merge", "-q", "--no-ff", "-m", "Merge feature", "feature", when=1_700_000_040)
+
+        events = scan_git_activity([str(tmp_path)])
+        assert [(e["kind"], e["branch"], e["timestamp"]) for e in events] == [
+            ("commit", "main", 1_700_000_000),
+            ("checkout", "feature", 1_700_000_010),
+            ("commit", "feature", 1_700_000_020),
+            ("checkout", "main", 1_700_000_030),
+            ("merge", "main", 1_700_000_040),
+        ]
+        assert events[0]["source"] == "git"
+        assert events[0]["repo"] == str(repo)
+        assert events[0]["email"] == ME[1]
+        assert events[2]["message"] == "add feature"
+        assert events[1]["from_branch"] == "main"
+        assert len(events[2]["commit"]) == 40
+
+    def test_filters_to_current_user(self, tmp_path):
+        repo = _repo(tmp_path / "proj")
+        _commit(repo, "mine", when=1_700_000_000)
+        _commit(repo, "theirs", who=OTHER, when=1_700_000_010)
+
+        mine = scan_git_activity([str(repo)])
+        assert [e["message"] for e in mine] == ["mine"]
+        everyone = scan_git_activity([str(repo)], all_authors=True)
+        assert [e["message"] for e in everyone] == ["mine", "theirs"]
+        bob = scan_git_activity([str(repo)], authors=["BOB@example.com"])
+        assert [e["message"] for e in bob] == ["theirs"]
+
+    def test_walks_commits_missing_from_reflog(self, tmp_path):
+        upstream = _repo(tmp_path / "elsewhere" / "upstream")
+        _commit(upstream, "made in another clone", when=1_700_000_000)
+        repo = _repo(tmp_path / "work" / "proj")
+        _commit(repo, "local", when=1_700_000_050)
+        _git(repo, "fetch", "-q", str(upstream), "main:side", when=1_700_000_060)
+
+        events = scan_git_activity([str(tmp_path / "work")])
+        assert [(e["message"], e["branch"]) for e in events] == [
+            ("made in another clone", None),
+            ("local", "main"),
+        ]
+
+    def test_since_and_discovery(self, tmp_path):
+        _commit(_repo(tmp_path / "a" / "b"), "deep", when=1_700_000_000)
+        _commit(_repo(tmp_path / "c"), "old", when=1_600_000_000)
+        _commit(_repo(tmp_path / "node_modules" / "dep"), "skipped", when=1_700_000_000)
+        _commit(_repo(tmp_path / ".hidden" / "repo"), "skipped", when=1_700_000_000)
+        (tmp_path / "not-a-repo").mkdir()
+
+        events = scan_git_activity([str(tmp_path)], since=1_650_000_000)
+        assert [e["message"] for e in events] == ["deep"]
+        assert scan_git_activity([str(tmp_path)], max_depth=1, since=1_650_000_000) == []
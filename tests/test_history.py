@@ -0,0 +1,454 @@
+"""Tests for browser history readers (Rust native via PyO3)."""
+
+import sqlite3
+
+import pytest
+
+from snoopy._native import (
+    PrivacyFilter,
+    browser_downloads,
+    chromium_history,
+    chromium_profiles,
+    firefox_history,
+    firefox_profiles,
+    quarantine_events,
+    safari_history,
+    set_privacy_filter,
+)
+
+SAFARI_EPOCH = 978307200
+WEBKIT_EPOCH = 11644473600
+
+
+def _safari_db(path, visits):
+    conn = sqlite3.connect(path)
+    conn.executescript(
+        """
+        CREATE TABLE history_items (id INTEGER PRIMARY KEY, url TEXT);
+        CREATE TABLE history_visits (
+            id INTEGER PRIMARY KEY, history_item INTEGER, visit_time REAL, title TEXT
+        );
+        """
+    )
+    for i, (url, title, ts) in enumerate(visits, 1):
+        conn.execute("INSERT INTO history_items VALUES (?, ?)", (i, url))
+        conn.execute(
+            "INSERT INTO history_visits VALUES (?, ?, ?, ?)", (i, i, ts - SAFARI_EPOCH, title)
+        )
+    conn.commit()
+    return conn
+
+
+def _chromium_db(path, visits):
+    path.parent.mkdir(parents=True, exist_ok=True)
+    conn = sqlite3.connect(path)
+    conn.executescript(
+        """
+        CREATE TABLE urls (id INTEGER PRIMARY KEY, url TEXT, title TEXT);
+        CREATE TABLE visits (
+            id INTEGER PRIMARY KEY, url INTEGER, visit_time INTEGER, visit_duration INTEGER
+        );
+        """
+    )
+    for i, (url, title, ts, duration) in enumerate(visits, 1):
+        conn.execute("INSERT INTO urls VALUES (?, ?, ?)", (i, url, title))
+        conn.execute(
+            "INSERT INTO visits VALUES (?, ?, ?, ?)",
+            (i, i, int((ts + WEBKIT_EPOCH) * 1_000_000), int(duration * 1_000_000)),
+        )
+    conn.commit()
+    conn.close()
+
+
+def _places_db(path, visits):
+    path.parent.mkdir(parents=True, exist_ok=True)
+    conn = sqlite3.connect(path)
+    conn.executescript(
+        """
+        CREATE TABLE moz_places (id INTEGER PRIMARY KEY, url TEXT, title TEXT);
+        CREATE TABLE moz_historyvisits (
+            id INTEGER PRIMARY KEY, place_id INTEGER, visit_date INTEGER
+        );
+        """
+    )
+    for i, (url, title, ts) in enumerate(visits, 1):
+        conn.execute("INSERT INTO moz_places VALUES (?, ?, ?)", (i, url, title))
+        conn.execute(
+            "INSERT INTO moz_historyvisits VALUES (?, ?, ?)", (i, i, int(ts * 1_000_000))
+        )
+    conn.commit()
+    conn.close()
+
+
+class TestSafariHistory:
+    def test_reads_visits_in_order(self, tmp_path):
+        db = tmp_path / "History.db"
+        _safari_db(db, [
+            ("https://example.com/b", "(3) Inbox", 1_700_000_060.0),
+            ("https://example.com/a", "A", 1_700_000_000.0),
+        ]).close()
+        visits = safari_history(str(db))
+        assert [v["url"] for v in visits] == ["https://example.com/a", "https://example.com/b"]
+        assert visits[0]["timestamp"] == pytest.approx(1_700_000_000.0)
+        assert visits[0]["browser"] == "safari"
+        assert visits[0]["source"] == "browser"
+        assert visits[1]["title"] == "Inbox"
+
+    def test_duration_is_gap_to_next_visit(self, tmp_path):
+        db = tmp_path / "History.db"
+        _safari_db(db, [
+            ("https://a.test", "A", 1_700_000_000.0),
+            ("https://b.test", "B", 1_700_000_045.0),
+            ("https://c.test", "C", 1_700_090_000.0),
+        ]).close()
+        visits = safari_history(str(db))
+        assert visits[0]["duration"] == pytest.approx(45.0)
+        assert visits[1]["duration"] == pytest.approx(1800.0)
+        assert visits[2]["duration"] is None
+
+    def test_since_filters_older_visits(self, tmp_path):
+        db = tmp_path / "History.db"
+        _safari_db(db, [
+            ("https://old.test", "Old", 1_600_000_000.0),
+            ("https://new.test", "New", 1_700_000_000.0),
+        ]).close()
+        visits = safari_history(str(db), since=1_650_000_000.0)
+        assert [v["url"] for v in visits] == ["https://new.test"]
+
+    def test_reads_uncheckpointed_wal_while_open(self, tmp_path):
+        db = tmp_path / "History.db"
+        conn = _safari_db(db, [])
+        conn.execute("PRAGMA journal_mode=WAL")
+        conn.execute("PRAGMA wal_autocheckpoint=0")
+        conn.execute("INSERT INTO history_items VALUES (1, 'https://wal.test')")
+        conn.execute("INSERT INTO history_visits VALUES (1, 1, 700000000.0, 'WAL')")
+        conn.commit()
+        conn.execute("BEGIN EXCLUSIVE")
+        try:
+            visits = safari_history(str(db))
+        finally:
+            conn.rollback()
+            conn.close()
+        assert [v["url"] for v in visits] == ["https://wal.test"]
+
+    def test_privacy_filter_applies(self, tmp_path):
+        db = tmp_path / "History.db"
+        _safari_db(db, [
+            ("https://bank.example.com/login", "Bank", 1_700_000_000.0),
+            ("https://news.test", "News", 1_700_000_010.0),
+        ]).close()
+        set_privacy_filter(PrivacyFilter(deny_domains=["example.com"]))
+        try:
+            visits = safari_history(str(db))
+        finally:
+            set_privacy_filter(None)
+        assert [v["url"] for v in visits] == ["https://news.test"]
+
+    def test_missing_file_raises(self, tmp_path):
+        with pytest.raises(IOError):
+            safari_history(str(tmp_path / "missing.db"))
+
+
+class TestChromiumHistory:
+    def test_converts_webkit_epoch_and_duration(self, tmp_path):
+        db = tmp_path / "Default" / "History"
+        _chromium_db(db, [
+            ("https://a.test", "(12) A", 1_700_000_000.5, 12.5),
+            ("https://b.test", "B", 1_700_000_100.0, 0),
+        ])
+        visits = chromium_history(str(db), browser="brave")
+        assert visits[0]["timestamp"] == pytest.approx(1_700_000_000.5)
+        assert visits[0]["duration"] == pytest.approx(12.5)
+        assert visits[0]["title"] == "A"
+        assert visits[0]["browser"] == "brave"
+        assert visits[0]["profile"] == "Default"
+        assert visits[1]["duration"] is None
+
+    def test_since_filters_older_visits(self, tmp_path):
+        db = tmp_path / "Default" / "History"
+        _chromium_db(db, [
+            ("https://old.test", "Old", 1_600_000_000.0, 1),
+            ("https://new.test", "New", 1_700_000_000.0, 1),
+        ])
+        visits = chromium_history(str(db), since=1_650_000_000.0)
+        assert [v["url"] for v in visits] == ["https://new.test"]
+
+    def test_discovers_profiles_across_browsers(self, tmp_path, monkeypatch):
+        monkeypatch.setenv("HOME", str(tmp_path))
+        chrome = tmp_path / ".config" / "google-chrome"
+        brave = tmp_path / "Library/Application Support/BraveSoftware/Brave-Browser"
+        _chromium_db(chrome / "Default" / "History", [("https://c1.test", "C1", 300.0, 1)])
+        _chromium_db(chrome / "Profile 2" / "History", [("https://c2.test", "C2", 100.0, 1)])
+        _chromium_db(brave / "Default" / "History", [("https://b.test", "B", 200.0, 1)])
+        (chrome / "System Profile").mkdir()
+        (chrome / "System Profile" / "History").write_text("")
+
+        profiles = chromium_profiles()
+        assert sorted((p["browser"], p["profile"]) for p in profiles) == [
+            ("brave", "Default"),
+            ("chrome", "Default"),
+            ("chrome", "Profile 2"),
+        ]
+        visits = chromium_history()
+        assert [(v["browser"], v["url"]) for v in visits] == [
+            ("chrome", "https://c2.test"),
+            ("brave", "https://b.test"),
+            ("chrome", "https://c1.test"),
+        ]
+
+    def test_skips_unreadable_profile(self, tmp_path, monkeypatch):
+        monkeypatch.setenv("HOME", str(tmp_path))
+        chrome = tmp_path / ".config" / "google-chrome"
+        _chromium_db(chrome / "Default" / "History", [("https://c1.test", "C1", 300.0, 1)])
+        (chrome / "Profile 2").mkdir()
+        sqlite3.connect(chrome / "Profile 2" / "History").close()
+        visits = chromium_history()
+        assert [v["url"] for v in visits] == ["https://c1.test"]
+
+    def test_not_a_history_db_raises(self, tmp_path):
+        db = tmp_path / "History"
+        sqlite3.connect(db).close()
+        with pytest.raises(ValueError):
+            chromium_history(str(db))
+
+
+class TestFirefoxHistory:
+    def test_reads_visits_with_gap_durations(self, tmp_path):
+        db = tmp_path / "abc.default-release" / "places.sqlite"
+        _places_db(db, [
+            ("https://a.test", "A", 1_700_000_000.0),
+            ("https://b.test", None, 1_700_000_030.0),
+        ])
+        visits = firefox_history(str(db))
+        assert [v["url"] for v in visits] == ["https://a.test", "https://b.test"]
+        assert visits[0]["timestamp"] == pytest.approx(1_700_000_000.0)
+        assert visits[0]["duration"] == pytest.approx(30.0)
+        assert visits[1]["duration"] is None
+        assert visits[1]["title"] == ""
+        assert visits[0]["browser"] == "firefox"
+        assert visits[0]["profile"] == "abc.default-release"
+
+    def test_since_filters_older_visits(self, tmp_path):
+        db = tmp_path / "p" / "places.sqlite"
+        _places_db(db, [
+            ("https://old.test", "Old", 1_600_000_000.0),
+            ("https://new.test", "New", 1_700_000_000.0),
+        ])
+        visits = firefox_history(str(db), since=1_650_000_000.0)
+        assert [v["url"] for v in visits] == ["https://new.test"]
+
+    def test_discovers_firefox_and_librewolf_profiles(self, tmp_path, monkeypatch):
+        monkeypatch.setenv("HOME", str(tmp_path))
+        _places_db(tmp_path / ".mozilla/firefox/x1.default/places.sqlite", [
+            ("https://ff.test", "FF", 200.0),
+        ])
+        _places_db(tmp_path / ".librewolf/y2.default/places.sqlite", [
+            ("https://lw.test", "LW", 100.0),
+        ])
+        (tmp_path / ".mozilla/firefox/Crash Reports").mkdir()
+
+        profiles = firefox_profiles()
+        assert sorted((p["browser"], p["profile"]) for p in profiles) == [
+            ("firefox", "x1.default"),
+            ("librewolf", "y2.default"),
+        ]
+        visits = firefox_history()
+        assert [(v["browser"], v["url"]) for v in visits] == [
+            ("librewolf", "https://lw.test"),
+            ("firefox", "https://ff.test"),
+        ]
+
+
+def _add_chromium_download(path, target, url, tab_url, size, ts):
+    conn = sqlite3.connect(path)
+    conn.executescript(
+        """
+        CREATE TABLE IF NOT EXISTS downloads (
+            id INTEGER PRIMARY KEY, target_path TEXT, tab_url TEXT, referrer TEXT,
+            total_bytes INTEGER, received_bytes INTEGER, start_time INTEGER
+        );
+        CREATE TABLE IF NOT EXISTS downloads_url_chains (
+            id INTEGER, chain_index INTEGER, url TEXT
+        );
+        """
+    )
+    cur = conn.execute(
+        "INSERT INTO downloads (target_path, tab_url, referrer, total_bytes, received_bytes,"
+        " start_time) VALUES (?, ?, '', ?, ?, ?)",
+        (target, tab_url, 0, size, int((ts + WEBKIT_EPOCH) * 1_000_000)),
+    )
+    conn.execute(
+        "INSERT INTO downloads_url_chains VALUES (?, 0, 'https://redirect.test/x')",
+        (cur.lastrowid,),
+    )
+    conn.execute("INSERT INTO downloads_url_chains VALUES (?, 1, ?)", (cur.lastrowid, url))
+    conn.commit()
+    conn.close()
+
+
+def _quarantine_db(home, events):
+    path = home / "Library/Preferences/com.apple.LaunchServices.QuarantineEventsV2"
+    path.parent.mkdir(parents=True, exist_ok=True)
+    conn = sqlite3.connect(path)
+    conn.execute(
+        "CREATE TABLE LSQuarantineEvent (LSQuarantineEventIdentifier TEXT,"
+        " LSQuarantineTimeStamp REAL, LSQuarantineAgentName TEXT,"
+        " LSQuarantineDataURLString TEXT, LSQuarantineOriginURLString TEXT)"
+    )
+    for i, (agent, url, origin, ts) in enumerate(events):
+        conn.execute(
+            "INSERT INTO LSQuarantineEvent VALUES (?, ?, ?, ?, ?)",
+            (str(i), ts - SAFARI_EPOCH, agent, url, origin),
+        )
+    conn.commit()
+    conn.close()
+
+
+class TestBrowserDownloads:
+    def test_chromium_download_with_origin(self, tmp_path, monkeypatch):
+        monkeypatch.setenv("HOME", str(tmp_path))
+        db = tmp_path / ".config/google-chrome/Default/History"
+        _chromium_db(db, [])
+        _add_chromium_download(
+            db, "/Users/me/Downloads/report.pdf", "https://cdn.example.com/report.pdf",
+            "https://www.example.com/reports", 2048, 1_700_000_000.0,
+        )
+        [dl] = browser_downloads(quarantine=False)
+        assert dl["source"] == "download"
+        assert dl["browser"] == "chrome"
+        assert dl["profile"] == "Default"
+        assert dl["timestamp"] == pytest.approx(1_700_000_000.0)
+        assert dl["path"] == "/Users/me/Downloads/report.pdf"
+        assert dl["file_name"] == "report.pdf"
+        assert dl["size"] == 2048
+        assert dl["url"] == "https://cdn.example.com/report.pdf"
+        assert dl["domain"] == "example.com"
+        assert dl["summary"] == "downloaded report.pdf from example.com"
+
+    def test_firefox_download_annotations(self, tmp_path, monkeypatch):
+        monkeypatch.setenv("HOME", str(tmp_path))
+        db = tmp_path / ".mozilla/firefox/x.default/places.sqlite"
+        _places_db(db, [("https://files.test/My%20Notes.txt", "", 1_700_000_000.0)])
+        conn = sqlite3.connect(db)
+        conn.executescript(
+            """
+            CREATE TABLE moz_anno_attributes (id INTEGER PRIMARY KEY, name TEXT);
+            CREATE TABLE moz_annos (
+                id INTEGER PRIMARY KEY, place_id INTEGER, anno_attribute_id INTEGER,
+                content TEXT, dateAdded INTEGER
+            );
+            INSERT INTO moz_anno_attributes VALUES
+                (1, 'downloads/destinationFileURI'), (2, 'downloads/metaData');
+            INSERT INTO moz_annos VALUES
+                (1, 1, 1, 'file:///home/me/Downloads/My%20Notes.txt', 1700000000000000),
+                (2, 1, 2, '{"state": 1, "fileSize": 512}', 1700000000000000);
+            """
+        )
+        conn.close()
+        [dl] = browser_downloads(quarantine=False)
+        assert dl["browser"] == "firefox"
+        assert dl["path"] == "/home/me/Downloads/My Notes.txt"
+        assert dl["size"] == 512
+        assert dl["summary"] == "downloaded My Notes.txt from files.test"
+
+    def test_quarantine_fills_in_other_apps(self, tmp_path, monkeypatch):
+        monkeypatch.setenv("HOME", str(tmp_path))
+        db = tmp_path / ".config/google-chrome/Default/History"
+        _chromium_db(db, [])
+        _add_chromium_download(
+            db, "/d/a.zip", "https://a.test/a.zip", "https://a.test/", 10, 1_700_000_000.0,
+        )
+        (tmp_path / "Downloads").mkdir()
+        (tmp_path / "Downloads" / "b.dmg").write_bytes(b"x" * 300)
+        _quarantine_db(tmp_path, [
+            ("Google Chrome", "https://a.test/a.zip", "https://a.test/", 1_700_000_001.0),
+            ("Safari", "https://b.test/b.dmg", "https://b.test/get", 1_700_000_100.0),
+        ])
+        downloads = browser_downloads()
+        assert [(d["browser"], d["file_name"]) for d in downloads] == [
+            ("chrome", "a.zip"),
+            ("safari", "b.dmg"),
+        ]
+        assert downloads[1]["size"] == 300
+        assert downloads[1]["path"] == str(tmp_path / "Downloads" / "b.dmg")
+
+    def test_since_filters_older_downloads(self, tmp_path, monkeypatch):
+        monkeypatch.setenv("HOME", str(tmp_path))
+        _quarantine_db(tmp_path, [
+            ("Safari", "https://old.test/a.txt", None, 1_600_000_000.0),
+            ("Safari", "https://new.test/b.txt", None, 1_700_000_000.0),
+        ])
+        downloads = browser_downloads(since=1_650_000_000.0)
+        assert [d["summary"] for d in downloads] == ["downloaded b.txt from new.test"]
+        assert downloads[0]["path"] is None
+
+    def test_encoded_separators_stay_in_downloads(self, tmp_path, monkeypatch):
+        monkeypatch.setenv("HOME", str(tmp_path))
+        (tmp_path / "Downloads").mkdir()
+        (tmp_path / "secret.txt").write_text("x")
+        _quarantine_db(tmp_path, [
+            ("Safari", "https://a.test/..%2Fsecret.txt", None, 1_700_000_000.0),
+            ("Safari", f"https://b.test/%2F{tmp_path}%2Fsecret.txt", None, 1_700_000_001.0),
+            ("Safari", "https://c.test/..", None, 1_700_000_002.0),
+        ])
+        downloads = browser_downloads()
+        assert len(downloads) == 3
+        assert all(d["path"] is None and d.get("size") is None for d in downloads)
+
+
+class TestQuarantineEvents:
+    def _db(self, path, rows):
+        conn = sqlite3.connect(path)
+        conn.execute(
+            "CREATE TABLE LSQuarantineEvent (LSQuarantineEventIdentifier TEXT PRIMARY KEY,"
+            " LSQuarantineTimeStamp REAL, LSQuarantineAgentBundleIdentifier TEXT,"
+            " LSQuarantineAgentName TEXT, LSQuarantineDataURLString TEXT,"
+            " LSQuarantineSenderName TEXT, LSQuarantineSenderAddress TEXT,"
+            " LSQuarantineTypeNumber INTEGER, LSQuarantineOriginTitle TEXT,"
+            " LSQuarantineOriginURLString TEXT, LSQuarantineOriginAlias BLOB)"
+        )
+        conn.executemany(
+            "INSERT INTO LSQuarantineEvent VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, NULL)",
+            [(str(i), ts - SAFARI_EPOCH, *rest) for i, (ts, *rest) in enumerate(rows)],
+        )
+        conn.commit()
+        conn.close()
+
+    def test_reports_app_url_and_kind(self, tmp_path, monkeypatch):
+        monkeypatch.setenv("HOME", str(tmp_path))
+        (tmp_path / "Downloads").mkdir()
+        (tmp_path / "Downloads" / "tool.dmg").write_bytes(b"x" * 42)
+        db = tmp_path / "QuarantineEventsV2"
+        self._db(db, [
+            (1_700_000_100.0, "com.apple.mail", "Mail", None, "Ana", "ana@example.com", 2,
+             None, None),
+            (1_700_000_000.0, "com.apple.Safari", "Safari", "https://dl.example.com/tool.dmg",
+             None, None, 0, "Get the tool", "https://www.example.com/download"),
+        ])
+        web, mail = quarantine_events(db)
+        assert web["source"] == "download"
+        assert (web["app"], web["bundle_id"], web["kind"]) == (
+            "Safari", "com.apple.Safari", "web_download")
+        assert web["url"] == "https://dl.example.com/tool.dmg"
+        assert web["origin_title"] == "Get the tool"
+        assert web["summary"] == "downloaded tool.dmg from example.com"
+        assert web["path"] == str(tmp_path / "Downloads" / "tool.dmg")
+        assert web["size"] == 42
+        assert web["timestamp"] == pytest.approx(1_700_000_000.0)
+        assert (mail["kind"], mail["sender"], mail["sender_address"]) == (
+            "email_attachment", "Ana", "ana@example.com")
+        assert mail["url"] is None
+
+    def test_since_and_missing_db(self, tmp_path):
+        db = tmp_path / "QuarantineEventsV2"
+        self._db(db, [
+            (1_600_000_000.0, "com.apple.Safari", "Safari", "https://a.test/a", None, None, 0,
+             None, None),
+            (1_700_000_000.0, "com.apple.Safari", "Safari", "https://b.test/b", None, None, 0,
+             None, None),
+        ])
+        assert [e["url"] for e in quarantine_events(db, since=1_650_000_000.0)] == [
+            "https://b.test/b"
+        ]
+        with pytest.raises(IOError):
+            quarantine_events(tmp_path / "missing")
@@ -0,0 +1,89 @@
+"""Tests for the HTML timeline export (Rust native via PyO3)."""
+
+import datetime
+import json
+import re
+
+import pytest
+
+from snoopy._native import PrivacyFilter, export_html_timeline, set_privacy_filter
+
+DAY = datetime.date(2024, 3, 1)
+MIDNIGHT = 1709251200.0  # 2024-03-01T00:00:00Z
+
+EVENTS = [
+    {"source": "browser", "timestamp": MIDNIGHT + 3600 * 10, "title": "Docs",
+     "url": "https://docs.rs/x", "tags": ["a"]},
+    {"source": "claude", "timestamp": MIDNIGHT + 3600 * 9, "duration": 1800.0,
+     "summary": "Fix </script> escaping", "project_path": "/src/app"},
+    {"source": "messages", "timestamp": datetime.datetime(2024, 3, 1, 12, 30,
+                                                          tzinfo=datetime.timezone.utc),
+     "kind": "message", "contact": "Alice"},
+    {"source": "browser", "timestamp": MIDNIGHT - 60, "title": "Yesterday"},
+    {"source": "browser", "timestamp": MIDNIGHT + 86400 * 2, "title": "Sunday"},
+]
+
+
+def _data(html):
+    match = re.search(r'<script type="application/json" id="timeline-data">(.*?)</script>',
+                      html, re.S)
+    return json.loads(match.group(1))
+
+
+class TestExportHtmlTimeline:
+    def test_single_day(self, tmp_path):
+        path = tmp_path / "day.html"
+        assert export_html_timeline(EVENTS, path, DAY, utc_offset=0) == 3
+        html = path.read_text()
+        assert html.startswith("<!DOCTYPE html>")
+        assert "<title>Activity: Friday, March 1, 2024</title>" in html
+        assert "3 events from browser, claude, messages" in html
+        data = _data(html)
+        assert [d["label"] for d in data["days"]] == ["Fri Mar 1"]
+        assert [e["label"] for e in data["events"]] == [
+            "Fix </script> escaping", "Docs", "message",
+        ]
+        claude, browser, message = data["events"]
+        assert claude["time"] == "09:00"
+        assert claude["end"] - claude["start"] == 1800
+        assert claude["fields"]["duration"] == "30m"
+        assert browser["fields"]["url"] == "https://docs.rs/x"
+        assert "tags" not in browser["fields"]
+        assert message["start"] == MIDNIGHT + 3600 * 12.5
+        # Embedded text can't close the data element early.
+        assert html.count("</script>") == 2
+
+    def test_week(self, tmp_path):
+        path = tmp_path / "week.html"
+        count = export_html_timeline(EVENTS, path, DAY, days=7, title="My <week>",
+                                     utc_offset=3600)
+        assert count == 5
+        html = path.read_text()
+        assert "<h1>My &lt;week&gt;</h1>" in html
+        data = _data(html)
+        assert len(data["days"]) == 7
+        assert data["days"][0]["start"] == MIDNIGHT - 3600
+        assert data["events"][0]["label"] == "Yesterday"
+        assert data["events"][-1]["day"] == "Sun Mar 3"
+
+    def test_privacy_filter(self, tmp_path):
+        set_privacy_filter(PrivacyFilter(deny_domains=["docs.rs"]))
+        try:
+            count = export_html_timeline(EVENTS, tmp_path / "p.html", DAY, utc_offset=0)
+        finally:
+            set_privacy_filter(None)
+        assert count == 2
+
+    def test_privacy_filter_redacts_label(self, tmp_path):
+        path = tmp_path / "r.html"
+        events = [{"source": "browser", "timestamp": MIDNIGHT + 3600, "title": "token hunter2"}]
+        set_privacy_filter(PrivacyFilter(redact_content=[r"hunter\d"]))
+        try:
+            export_html_timeline(events, path, DAY, utc_offset=0)
+        finally:
+            set_privacy_filter(None)
+        assert "hunter2" not in path.read_text()
+
+    def test_zero_days(self, tmp_path):
+        with pytest.raises(ValueError):
+            export_html_timeline(EVENTS, tmp_path / "x.html", DAY, days=0)
@@ -0,0 +1,120 @@
+"""Tests for iCalendar export of activity sessions (Rust native via PyO3)."""
+
+import pytest
+
+from snoopy._native import PrivacyFilter, export_ics, sessionize, set_privacy_filter
+
+START = 1709287200.0  # 2024-03-01 10:00:00 UTC
+
+
+def read(path):
+    """The file's content lines, unfolded."""
+    return path.read_bytes().decode().replace("\r\n ", "")
+
+
+class TestExportIcs:
+    def test_writes_vevents(self, tmp_path):
+        path = tmp_path / "activity.ics"
+        sessions = [
+            {
+                "start": START + 3600,
+                "end": START + 5400,
+                "kind": "meeting",
+                "title": "Standup; planning, Q2",
+            },
+            {
+                "start": START,
+                "end": START + 1800,
+                "event_count": 42,
+                "kind": "coding",
+                "project_path": "/Users/me/src/app",
+            },
+        ]
+        assert export_ics(sessions, path) == 2
+        text = read(path)
+        assert text.startswith("BEGIN:VCALENDAR\r\n")
+        assert text.endswith("END:VCALENDAR\r\n")
+        events = text.split("BEGIN:VEVENT")[1:]
+        assert "DTSTART:20240301T100000Z" in events[0]
+        assert "DTEND:20240301T103000Z" in events[0]
+        assert "SUMMARY:Coding on app" in events[0]
+        assert "DESCRIPTION:42 events" in events[0]
+        assert "LOCATION:/Users/me/src/app" in events[0]
+        assert "CATEGORIES:coding" in events[0]
+        assert r"SUMMARY:Standup\; planning\, Q2" in events[1]
+        assert all("TRANSP:TRANSPARENT" in e for e in events)
+
+    def test_uids_are_stable(self, tmp_path):
+        sessions = [{"start": START, "end": START + 60, "kind": "messaging"}]
+        first, second = tmp_path / "a.ics", tmp_path / "b.ics"
+        export_ics(sessions, first)
+        export_ics(sessions, second)
+        uid = [line for line in first.read_text().splitlines() if line.startswith("UID:")]
+        assert uid and uid[0] in second.read_text()
+
+    def test_sessionize_output_and_short_blocks(self, tmp_path):
+        path = tmp_path / "s.ics"
+        sessions = sessionize([START, START + 10, START + 7200])
+        assert export_ics(sessions, path, calendar_name="Work") == 2
+        text = read(path)
+        assert "X-WR-CALNAME:Work" in text
+        assert "SUMMARY:Activity" in text
+        # The single-event session is padded to a minute.
+        assert "DTEND:20240301T120100Z" in text
+
+    def test_timeline_events(self, tmp_path):
+        path = tmp_path / "t.ics"
+        event = {"timestamp": START, "duration": 90.0, "summary": "built App, 2 warnings"}
+        export_ics([event], path)
+        text = read(path)
+        assert "DTEND:20240301T100130Z" in text
+        assert r"SUMMARY:built App\, 2 warnings" in text
+
+    def test_long_lines_are_folded(self, tmp_path):
+        path = tmp_path / "long.ics"
+        export_ics([{"start": START, "end": START + 60, "title": "é" * 100}], path)
+        lines = path.read_bytes().split(b"\r\n")
+        assert max(len(line) for line in lines) <= 75
+        assert "SUMMARY:" + "é" * 100 in read(path)
+
+    def test_rejects_backwards_sessions(self, tmp_path):
+        with pytest.raises(ValueError):
+            export_ics([{"start": START, "end": START - 1}], tmp_path / "x.ics")
+
+    def test_escapes_uid_and_bare_carriage_returns(self, tmp_path):
+        path = tmp_path / "e.ics"
+        sessions = [
+            {"start": START, "end": START + 60, "title": "a\rb", "uid": "x\r\nEND:VEVENT"}
+        ]
+        export_ics(sessions, path)
+        text = read(path)
+        assert r"SUMMARY:a\nb" in text
+        assert r"UID:x\nEND:VEVENT" in text
+        assert text.split("\r\n").count("END:VEVENT") == 1
+
+    def test_skips_out_of_range_sessions(self, tmp_path):
+        path = tmp_path / "r.ics"
+        sessions = [{"start": 1e300, "end": 1e300}, {"start": START, "end": START + 60}]
+        assert export_ics(sessions, path) == 1
+        assert "DTSTART:\r\n" not in path.read_bytes().decode()
+
+    def test_privacy_filter(self, tmp_path):
+        path = tmp_path / "p.ics"
+        sessions = [
+            {"start": START, "end": START + 60, "project_path": "/Users/me/secret"},
+            {"start": START + 60, "end": START + 120, "title": "call hunter2", "kind": "meeting"},
+        ]
+        set_privacy_filter(
+            PrivacyFilter(deny_projects=["/Users/me/secret"], redact_content=[r"hunter\d"])
+        )
+        try:
+            assert export_ics(sessions, path) == 1
+        finally:
+            set_privacy_filter(None)
+        text = read(path)
+        assert "secret" not in text and "hunter2" not in text
+        assert "SUMMARY:call [REDACTED]" in text
+
+    def test_unwritable_path(self, tmp_path):
+        with pytest.raises(IOError):
+            export_ics([], tmp_path / "missing" / "x.ics")
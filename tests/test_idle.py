@@ -0,0 +1,62 @@
+"""Tests for idle_seconds and the idle Collector source (Rust native via PyO3)."""
+
+import os
+import sys
+import time
+
+import pytest
+
+from snoopy._native import Collector, idle_seconds
+
+pytestmark = pytest.mark.skipif(sys.platform == "darwin", reason="uses the xprintidle path")
+
+
+def _fake_xprintidle(tmp_path, monkeypatch, idle_ms, exit_code=0):
+    bin_dir = tmp_path / "bin"
+    bin_dir.mkdir()
+    state = tmp_path / "idle_ms"
+    state.write_text(str(idle_ms))
+    script = bin_dir / "xprintidle"
+    script.write_text(f'#!/bin/sh\ncat "{state}"\nexit {exit_code}\n')
+    script.chmod(0o755)
+    monkeypatch.setenv("PATH", f"{bin_dir}{os.pathsep}{os.environ['PATH']}")
+    return state
+
+
+def _wait_for(collector, n, timeout=5.0):
+    events = []
+    deadline = time.time() + timeout
+    while len(events) < n and time.time() < deadline:
+        events.extend(collector.drain())
+        time.sleep(0.02)
+    return events
+
+
+class TestIdleSeconds:
+    def test_reads_xprintidle(self, tmp_path, monkeypatch):
+        _fake_xprintidle(tmp_path, monkeypatch, 12500)
+        assert idle_seconds() == pytest.approx(12.5)
+
+    def test_transitions(self, tmp_path, monkeypatch):
+        state = _fake_xprintidle(tmp_path, monkeypatch, 1000)
+        collector = Collector(["idle"], interval=0.05, idle_threshold=60)
+        collector.start()
+        try:
+            time.sleep(0.2)
+            assert collector.drain() == []
+            state.write_text("90000")
+            went_idle = _wait_for(collector, 1)
+            state.write_text("500")
+            came_back = _wait_for(collector, 1)
+        finally:
+            collector.stop()
+        assert [e["state"] for e in went_idle] == ["idle"]
+        assert went_idle[0]["source"] == "idle"
+        assert went_idle[0]["idle_seconds"] == pytest.approx(90.0)
+        assert went_idle[0]["timestamp"] == pytest.approx(time.time() - 90, abs=5)
+        assert [e["state"] for e in came_back] == ["active"]
+        assert came_back[0]["duration"] == pytest.approx(90.0, abs=5)
+
+    def test_invalid_threshold(self):
+        with pytest.raises(ValueError):
+            Collector(["idle"], idle_threshold=0)
@@ -0,0 +1,68 @@
+"""Tests for input_counts and the input Collector source (Rust native via PyO3)."""
+
+import os
+import sys
+import time
+
+import pytest
+
+from snoopy._native import Collector, input_counts
+
+
+def _listener_available():
+    if sys.platform == "darwin":
+        return None
+    try:
+        return any(os.access(f"/dev/input/{name}", os.R_OK)
+                   for name in os.listdir("/dev/input") if name.startswith("event"))
+    except OSError:
+        return False
+
+
+def _listener_threads():
+    """Threads of this process named for the Linux input listener."""
+    tasks = "/proc/self/task"
+    return sum(open(f"{tasks}/{tid}/comm").read().strip() == "snoopy-input"
+               for tid in os.listdir(tasks))
+
+
+class TestInputCounts:
+    def test_counts_or_permission_error(self):
+        try:
+            counts = input_counts()
+        except RuntimeError:
+            assert _listener_available() in (None, False)
+            return
+        assert set(counts) == {"keystrokes", "clicks", "scrolls"}
+        assert all(isinstance(n, int) and n >= 0 for n in counts.values())
+
+    @pytest.mark.skipif(_listener_available() is not False,
+                        reason="needs a machine without readable input devices")
+    def test_collector_reports_missing_access(self):
+        collector = Collector(["input"], interval=0.05)
+        collector.start()
+        try:
+            time.sleep(0.3)
+            status = collector.status()["sources"]["input"]
+        finally:
+            collector.stop()
+        assert status["errors"] >= 1
+        assert "input" in status["last_error"]
+        assert collector.drain() == []
+
+    @pytest.mark.skipif(not _listener_available(),
+                        reason="needs readable Linux input devices")
+    def test_listener_stops_with_collector(self):
+        # input_counts() keeps its listener running, so compare with what was there.
+        before = _listener_threads()
+        collector = Collector(["input"], interval=0.05)
+        collector.start()
+        try:
+            time.sleep(0.3)
+            assert _listener_threads() > 0
+        finally:
+            collector.stop()
+        deadline = time.time() + 2
+        while _listener_threads() > before and time.time() < deadline:
+            time.sleep(0.05)
+        assert _listener_threads() == before
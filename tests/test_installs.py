@@ -0,0 +1,69 @@
+"""Tests for install.log and Homebrew install parsing (Rust native via PyO3)."""
+
+import json
+
+import pytest
+
+from snoopy._native import homebrew_installs, install_log_events, parse_install_log
+
+LOG = """\
+2024-03-01 09:15:00-08 mac installd[612]: PackageKit: ----- Begin install -----
+2024-03-01 09:15:02-08 mac installd[612]: PackageKit: Installed "Zoom" (5.17.11)
+2024-03-02 18:40:10+05:30 mac softwareupdated[401]: Installed "macOS Sonoma 14.4" (14.4)
+2024-03-03 07:00:00-08 mac Installer[99]: Installed "Widget" ()
+not a log line Installed "Nope" (1)
+"""
+
+
+class TestInstallLog:
+    def test_parses_installed_lines(self):
+        events = parse_install_log(LOG)
+        assert [(e["installer"], e["package"], e["version"]) for e in events] == [
+            ("installd", "Zoom", "5.17.11"),
+            ("softwareupdated", "macOS Sonoma 14.4", "14.4"),
+            ("Installer", "Widget", None),
+        ]
+        assert events[0]["source"] == "install"
+        assert events[0]["kind"] == "system"
+        assert events[0]["timestamp"] == 1709313302.0
+        assert events[1]["timestamp"] == 1709385010.0
+        assert events[0]["summary"] == "installed Zoom 5.17.11"
+        assert events[2]["summary"] == "installed Widget"
+
+    def test_since_and_file(self, tmp_path):
+        log = tmp_path / "install.log"
+        log.write_text(LOG)
+        events = install_log_events(log, since=1709313302.0)
+        assert [e["package"] for e in events] == ["macOS Sonoma 14.4", "Widget"]
+        with pytest.raises(IOError):
+            install_log_events(tmp_path / "missing.log")
+
+
+class TestHomebrewInstalls:
+    def test_formulae_and_casks(self, tmp_path):
+        receipt = tmp_path / "Cellar/jq/1.7.1/INSTALL_RECEIPT.json"
+        receipt.parent.mkdir(parents=True)
+        receipt.write_text(json.dumps({
+            "time": 1700000000,
+            "installed_on_request": True,
+            "source": {"tap": "homebrew/core"},
+        }))
+        dep = tmp_path / "Cellar/oniguruma/6.9.9/INSTALL_RECEIPT.json"
+        dep.parent.mkdir(parents=True)
+        dep.write_text(json.dumps({"time": 1699999990, "installed_on_request": False,
+                                   "source": {"tap": "homebrew/core"}}))
+        (tmp_path / "Caskroom/firefox/.metadata/125.0/20240101120000.500/Casks").mkdir(
+            parents=True)
+        (tmp_path / "Cellar/broken/1.0").mkdir(parents=True)
+
+        events = homebrew_installs(tmp_path)
+        assert [(e["kind"], e["package"], e["version"], e["on_request"]) for e in events] == [
+            ("formula", "oniguruma", "6.9.9", False),
+            ("formula", "jq", "1.7.1", True),
+            ("cask", "firefox", "125.0", True),
+        ]
+        assert events[1]["tap"] == "homebrew/core"
+        assert events[2]["timestamp"] == 1704110400.5
+        assert all(e["installer"] == "homebrew" for e in events)
+        assert [e["package"] for e in homebrew_installs(tmp_path, since=1699999995)] == [
+            "jq", "firefox"]
@@ -0,0 +1,76 @@
+"""Tests for interruption analysis (Rust native via PyO3)."""
+
+from datetime import datetime, timezone
+
+import pytest
+
+from snoopy._native import interruption_stats, merge_timelines
+
+
+def _claude(ts, message_type="assistant_text"):
+    return {"source": "claude", "timestamp": ts, "session_id": "s1",
+            "message_type": message_type, "content_preview": "", "project_path": "/p"}
+
+
+def _imessage(ts, contact="+15551234567", is_from_me=0):
+    return {"source": "messages", "timestamp": ts, "contact": contact,
+            "is_from_me": is_from_me, "content_preview": "lunch?"}
+
+
+CODING = [
+    _claude(1000, "user"), _claude(1010), _claude(1100, "user"), _claude(1130),
+    _claude(1200, "user"), _claude(1210),
+    # A second session after a long break.
+    _claude(5000, "user"), _claude(5060),
+]
+
+
+class TestInterruptionStats:
+    def test_counts_and_refocus(self):
+        messages = [
+            _imessage(1020),                 # back at 1100: 80s
+            _imessage(1150, is_from_me=1),   # a reply, not an interruption
+            _imessage(1160, contact="Alex"),  # back at 1200: 40s
+            _imessage(3000),                 # between sessions
+            _imessage(5030),                 # never refocused
+            {"source": "slack", "timestamp": 1105, "sender": "bob", "text": "ping"},
+        ]
+        stats = interruption_stats(list(merge_timelines([CODING, messages])))
+        assert stats["interruptions"] == 4
+        assert stats["refocused"] == 3
+        assert stats["lost_seconds"] == 80 + 40 + 95
+        assert stats["median_refocus_seconds"] == 80
+        assert stats["mean_refocus_seconds"] == pytest.approx(215 / 3)
+        assert stats["by_source"]["slack"] == {"interruptions": 1, "mean_refocus_seconds": 95}
+        assert stats["by_source"]["messages"]["interruptions"] == 3
+        first, second = stats["sessions"]
+        assert (first["start"], first["end"], first["interruptions"]) == (1000, 1210, 3)
+        assert first["lost_seconds"] == 215
+        assert (second["interruptions"], second["lost_seconds"]) == (1, 0)
+        events = stats["events"]
+        assert [e["from"] for e in events] == ["+15551234567", "bob", "Alex", "+15551234567"]
+        assert events[-1]["refocus_seconds"] is None
+        assert events[-1]["session_start"] == 5000
+
+    def test_transcript_events_and_datetimes(self):
+        def when(ts):
+            return datetime.fromtimestamp(ts, tz=timezone.utc)
+
+        transcript = [{"timestamp": when(ts), "message_type": t, "session_id": "s"}
+                      for ts, t in [(0, "user"), (30, "assistant_text"), (60, "user")]]
+        mail = [{"source": "mail", "kind": "received", "timestamp": 10, "sender": "a@b.c"},
+                {"source": "mail", "kind": "sent", "timestamp": 20, "sender": "me@b.c"}]
+        stats = interruption_stats(transcript + mail)
+        assert stats["interruptions"] == 1
+        assert stats["events"][0]["refocus_seconds"] == 50
+
+    def test_options(self):
+        messages = [_imessage(1020)]
+        assert interruption_stats(CODING + messages, sources=["slack"])["interruptions"] == 0
+        stats = interruption_stats(CODING + messages, refocus_timeout=60)
+        assert stats["refocused"] == 0
+        # With a short gap, 1020 falls between sessions.
+        assert interruption_stats(CODING + messages, gap_seconds=5)["interruptions"] == 0
+        empty = interruption_stats([])
+        assert empty["interruptions"] == 0
+        assert empty["mean_refocus_seconds"] is None
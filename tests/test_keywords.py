@@ -0,0 +1,82 @@
+"""Tests for transcript keyword extraction (Rust native via PyO3)."""
+
+from datetime import datetime, timezone
+
+import pytest
+
+from snoopy._native import transcript_keywords
+
+
+def _msg(kind, content, ts="2026-02-25T10:00:00Z", cwd=None, **extra):
+    entry = {"type": kind, "timestamp": ts, "message": {"role": kind, "content": content},
+             **extra}
+    if cwd:
+        entry["cwd"] = cwd
+    return entry
+
+
+def _monday(day):
+    return datetime(2026, 2, day, tzinfo=timezone.utc).timestamp()
+
+
+@pytest.fixture
+def projects(tmp_path, write_transcript):
+    write_transcript(tmp_path / "-Users-me-app" / "s1.jsonl", [
+        _msg("user", "Please fix the parser crash on malformed JSON input", cwd="/Users/me/app"),
+        _msg("assistant", [{"type": "text", "text": "The parser panics on malformed input."},
+                           {"type": "tool_use", "id": "t1", "name": "Bash",
+                            "input": {"command": "cargo test toolonly"}}]),
+        _msg("user", [{"type": "tool_result", "tool_use_id": "t1", "content": "resultonly"}]),
+        _msg("user", "<command-name>/clear</command-name>"),
+        _msg("user", "metaonly caveat", isMeta=True),
+        _msg("assistant", "Fixed the parser:\n```rust\nfn fencedonly() {}\n```\nDon't worry."),
+        # The following week.
+        _msg("user", "Now add parser benchmarks", ts="2026-03-03T09:00:00Z"),
+    ])
+    write_transcript(tmp_path / "-Users-me-site" / "s2.jsonl", [
+        _msg("user", "Restyle the landing page input form", cwd="/Users/me/site"),
+        _msg("assistant", "Updated the landing page styles for the input form."),
+    ])
+    return tmp_path
+
+
+class TestTranscriptKeywords:
+    def test_by_project_and_week(self, projects):
+        result = transcript_keywords(str(projects))
+        assert set(result) == {"/Users/me/app", "/Users/me/site"}
+        first, second = result["/Users/me/app"]
+        assert first["period_start"] == _monday(23)
+        assert second["period_start"] == datetime(2026, 3, 2, tzinfo=timezone.utc).timestamp()
+        assert first["messages"] == 3
+        terms = {t["term"]: t for t in first["terms"]}
+        assert first["terms"][0]["term"] == "parser"
+        assert terms["parser"]["count"] == 3
+        assert terms["malformed"]["count"] == 2
+        for skipped in ["toolonly", "resultonly", "clear", "metaonly", "fencedonly", "don",
+                        "the", "please"]:
+            assert skipped not in terms
+        # "input" appears in both projects, so it ranks below terms particular to one.
+        assert terms["input"]["score"] < terms["malformed"]["score"]
+        assert [t["term"] for t in second["terms"]] == ["add", "benchmarks", "parser"]
+        scores = [t["score"] for t in first["terms"]]
+        assert scores == sorted(scores, reverse=True)
+
+    def test_options(self, projects):
+        result = transcript_keywords(str(projects), group_by="session", period="all", top_n=2)
+        assert set(result) == {"s1", "s2"}
+        (only,) = result["s1"]
+        assert only["period_start"] is None
+        assert only["messages"] == 4
+        assert len(only["terms"]) == 2
+        day = transcript_keywords(str(projects / "-Users-me-site" / "s2.jsonl"), period="day",
+                                  utc_offset=-12 * 3600)
+        (entry,) = day["/Users/me/site"]
+        assert entry["period_start"] == datetime(2026, 2, 24, 12, tzinfo=timezone.utc).timestamp()
+        since = datetime(2026, 3, 1, tzinfo=timezone.utc).timestamp()
+        assert list(transcript_keywords(str(projects), since=since)) == ["/Users/me/app"]
+
+    def test_invalid_arguments(self, projects):
+        with pytest.raises(ValueError):
+            transcript_keywords(str(projects), group_by="day")
+        with pytest.raises(ValueError):
+            transcript_keywords(str(projects), period="month")
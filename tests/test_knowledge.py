@@ -0,0 +1,72 @@
+"""Tests for screen_time_usage (Rust native via PyO3)."""
+
+import sqlite3
+
+import pytest
+
+from snoopy._native import PrivacyFilter, screen_time_usage, set_privacy_filter
+
+COCOA_EPOCH = 978307200
+
+
+def _knowledge_db(path, rows):
+    conn = sqlite3.connect(path)
+    conn.execute(
+        "CREATE TABLE ZOBJECT (Z_PK INTEGER PRIMARY KEY, ZSTREAMNAME TEXT, ZVALUESTRING TEXT,"
+        " ZSTARTDATE REAL, ZENDDATE REAL)"
+    )
+    for stream, bundle, start, end in rows:
+        conn.execute(
+            "INSERT INTO ZOBJECT (ZSTREAMNAME, ZVALUESTRING, ZSTARTDATE, ZENDDATE)"
+            " VALUES (?, ?, ?, ?)",
+            (stream, bundle, start - COCOA_EPOCH, end - COCOA_EPOCH),
+        )
+    conn.commit()
+    conn.close()
+
+
+class TestScreenTimeUsage:
+    def test_app_usage_intervals(self, tmp_path):
+        db = tmp_path / "knowledgeC.db"
+        _knowledge_db(db, [
+            ("/app/inFocus", "com.apple.Safari", 1_700_000_100.0, 1_700_000_160.0),
+            ("/app/usage", "com.microsoft.VSCode", 1_700_000_000.0, 1_700_000_300.0),
+            ("/display/isBacklit", None, 1_700_000_000.0, 1_700_000_900.0),
+        ])
+        events = screen_time_usage(str(db))
+        assert [(e["stream"], e["bundle_id"]) for e in events] == [
+            ("/app/usage", "com.microsoft.VSCode"),
+            ("/app/inFocus", "com.apple.Safari"),
+        ]
+        assert events[0]["source"] == "screentime"
+        assert events[0]["timestamp"] == pytest.approx(1_700_000_000.0)
+        assert events[0]["end"] == pytest.approx(1_700_000_300.0)
+        assert events[0]["duration"] == pytest.approx(300.0)
+
+    def test_streams_and_since(self, tmp_path):
+        db = tmp_path / "knowledgeC.db"
+        _knowledge_db(db, [
+            ("/display/isBacklit", None, 1_600_000_000.0, 1_600_000_100.0),
+            ("/display/isBacklit", None, 1_700_000_000.0, 1_700_000_100.0),
+        ])
+        events = screen_time_usage(
+            str(db), streams=["/display/isBacklit"], since=1_650_000_000.0
+        )
+        assert [e["timestamp"] for e in events] == [1_700_000_000.0]
+
+    def test_privacy_filter_drops_denied_apps(self, tmp_path):
+        db = tmp_path / "knowledgeC.db"
+        _knowledge_db(db, [
+            ("/app/usage", "com.secret.App", 1_700_000_000.0, 1_700_000_010.0),
+            ("/app/usage", "com.apple.Terminal", 1_700_000_020.0, 1_700_000_030.0),
+        ])
+        set_privacy_filter(PrivacyFilter(deny_apps=["com.secret.app"]))
+        try:
+            events = screen_time_usage(str(db))
+        finally:
+            set_privacy_filter(None)
+        assert [e["bundle_id"] for e in events] == ["com.apple.Terminal"]
+
+    def test_missing_database_raises(self, tmp_path):
+        with pytest.raises(IOError):
+            screen_time_usage(str(tmp_path / "knowledgeC.db"))
@@ -0,0 +1,61 @@
+"""Tests for resume lineage across transcripts (Rust native via PyO3)."""
+
+import pytest
+
+from snoopy._native import session_lineage
+
+
+def _msg(uuid, parent, minute, text="hi", cwd="/Users/me/app"):
+    return {"type": "user", "uuid": uuid, "parentUuid": parent, "cwd": cwd,
+            "timestamp": f"2026-02-25T10:{minute:02d}:00Z",
+            "message": {"role": "user", "content": text}}
+
+
+@pytest.fixture
+def projects(tmp_path, write_transcript):
+    project = tmp_path / "-Users-me-app"
+    first = [_msg("u1", None, 0), _msg("u2", "u1", 1), _msg("u3", "u2", 2)]
+    write_transcript(project / "a.jsonl", first)
+    # Resumed: history copied over, then continued.
+    second = first + [_msg("u4", "u3", 10), _msg("u5", "u4", 11)]
+    write_transcript(project / "b.jsonl", second)
+    # Resumed again, from b, replying to its last message without copying.
+    write_transcript(project / "c.jsonl", [_msg("u6", "u5", 20), _msg("u7", "u6", 21)])
+    # A summary pointing at c's leaf.
+    write_transcript(project / "d.jsonl", [
+        {"type": "summary", "summary": "Fix tests", "leafUuid": "u7"},
+        _msg("u8", None, 30),
+    ])
+    write_transcript(project / "other.jsonl", [_msg("x1", None, 5, cwd="/Users/me/other")])
+    write_transcript(project / "agent-1.jsonl", [_msg("u2", "u1", 1)])
+    return tmp_path
+
+
+class TestSessionLineage:
+    def test_chains(self, projects):
+        chains = session_lineage(str(projects))
+        assert [c["session_ids"] for c in chains] == [["a", "b", "c", "d"], ["other"]]
+        chain = chains[0]
+        assert chain["parents"] == [None, "a", "b", "c"]
+        assert chain["files"][0] == str(projects / "-Users-me-app" / "a.jsonl")
+        assert chain["project_path"] == "/Users/me/app"
+        assert chain["end"] - chain["start"] == 30 * 60
+        assert chains[1]["project_path"] == "/Users/me/other"
+
+    def test_exclude_single(self, projects):
+        chains = session_lineage(str(projects), include_single=False)
+        assert [c["session_ids"] for c in chains] == [["a", "b", "c", "d"]]
+
+    def test_fork(self, tmp_path, write_transcript):
+        base = [_msg("u1", None, 0), _msg("u2", "u1", 1)]
+        write_transcript(tmp_path / "a.jsonl", base)
+        write_transcript(tmp_path / "b.jsonl", base + [_msg("b1", "u2", 5)])
+        write_transcript(tmp_path / "c.jsonl", base + [_msg("c1", "u2", 6)])
+        (chain,) = session_lineage(str(tmp_path))
+        assert chain["session_ids"] == ["a", "b", "c"]
+        assert chain["parents"][:2] == [None, "a"]
+
+    def test_single_file(self, projects):
+        (chain,) = session_lineage(str(projects / "-Users-me-app" / "b.jsonl"))
+        assert chain["session_ids"] == ["b"]
+        assert session_lineage(str(projects / "missing")) == []
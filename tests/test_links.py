@@ -0,0 +1,70 @@
+"""Tests for URL extraction and link events (Rust native via PyO3)."""
+
+from datetime import datetime, timezone
+
+import pytest
+
+from snoopy._native import extract_urls, link_events
+
+
+class TestExtractUrls:
+    @pytest.mark.parametrize("text,url,domain", [
+        ("see https://docs.rs/regex/latest.", "https://docs.rs/regex/latest", "docs.rs"),
+        ("(docs at https://example.com/a?b=1&c=2)", "https://example.com/a?b=1&c=2",
+         "example.com"),
+        ("https://en.wikipedia.org/wiki/Rust_(language)!",
+         "https://en.wikipedia.org/wiki/Rust_(language)", "en.wikipedia.org"),
+        ("try www.Example.org/page, ok?", "https://www.Example.org/page", "example.org"),
+        ("<http://localhost:8080/health>", "http://localhost:8080/health", "localhost"),
+    ])
+    def test_urls(self, text, url, domain):
+        (found,) = extract_urls(text)
+        assert found["url"] == url
+        assert found["domain"] == domain
+        assert found["url"].endswith(text[found["start"]:found["end"]])
+
+    def test_several_and_offsets(self):
+        text = "café → https://a.io and http://b.io/x"
+        found = extract_urls(text)
+        assert [f["domain"] for f in found] == ["a.io", "b.io"]
+        assert text[found[0]["start"]:found[0]["end"]] == "https://a.io"
+
+    @pytest.mark.parametrize("text", ["no links here", "https://", "file:///tmp/x", "www.x"])
+    def test_none(self, text):
+        assert extract_urls(text) == []
+
+
+class TestLinkEvents:
+    def test_messages_and_transcripts(self):
+        events = [
+            {"source": "messages", "timestamp": 100.0, "contact": "+15550001111",
+             "is_from_me": 0, "content_preview": "look https://a.io/x and https://a.io/x"},
+            {"source": "slack", "timestamp": 101.0, "sender": "me", "kind": "sent",
+             "text": "PR: https://github.com/o/r/pull/1", "chat_name": "#dev"},
+            {"timestamp": datetime.fromtimestamp(102, tz=timezone.utc), "session_id": "s1",
+             "message_type": "assistant_text", "project_path": "/p",
+             "content_preview": "Docs: https://docs.rs/serde."},
+            {"timestamp": 103.0, "session_id": "s1", "message_type": "user",
+             "content_preview": "fetch www.python.org please"},
+            {"source": "browser", "timestamp": 104.0, "url": "https://skip.me"},
+            {"source": "mail", "timestamp": 105.0, "subject": "hello", "sender": "a@b.c"},
+        ]
+        found = link_events(events)
+        assert [e["url"] for e in found] == [
+            "https://a.io/x", "https://github.com/o/r/pull/1", "https://docs.rs/serde",
+            "https://www.python.org",
+        ]
+        received, shared, agent, prompt = found
+        assert received["source"] == "link"
+        assert received["link_source"] == "messages"
+        assert received["from"] == "+15550001111"
+        assert received["outgoing"] is False
+        assert shared["outgoing"] is True
+        assert shared["chat_name"] == "#dev"
+        assert agent["link_source"] == "claude"
+        assert agent["from"] == "assistant_text"
+        assert agent["timestamp"] == 102.0
+        assert (agent["session_id"], agent["project_path"]) == ("s1", "/p")
+        assert agent["outgoing"] is False
+        assert prompt["outgoing"] is True
+        assert prompt["domain"] == "python.org"
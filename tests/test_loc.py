@@ -0,0 +1,69 @@
+"""Tests for lines-of-code metrics (Rust native via PyO3)."""
+
+import pytest
+
+from snoopy._native import loc_stats
+
+
+def _call(tool_id, name, input, is_error=False, ts="2026-02-25T10:00:00Z"):
+    return [
+        {"type": "assistant", "timestamp": ts, "cwd": "/Users/me/app",
+         "message": {"content": [{"type": "tool_use", "id": tool_id, "name": name,
+                                  "input": input}]}},
+        {"type": "user", "timestamp": ts,
+         "message": {"content": [{"type": "tool_result", "tool_use_id": tool_id,
+                                  "content": "failed" if is_error else "ok",
+                                  "is_error": is_error}]}},
+    ]
+
+
+@pytest.fixture
+def projects(tmp_path, write_transcript):
+    write_transcript(tmp_path / "-Users-me-app" / "s1.jsonl", [
+        *_call("t1", "Write", {"file_path": "/app/src/main.rs",
+                               "content": "fn main() {\n    run();\n}\n"}),
+        # One line changed, one inserted.
+        *_call("t2", "Edit", {"file_path": "/app/src/main.rs",
+                              "old_string": "fn main() {\n    run();\n}",
+                              "new_string": "fn main() {\n    setup();\n    go();\n}"}),
+        *_call("t3", "MultiEdit", {"file_path": "/app/README.MD", "edits": [
+            {"old_string": "a", "new_string": "a\nb"},
+            {"old_string": "c\nd", "new_string": ""},
+        ]}),
+        *_call("t4", "Edit", {"file_path": "/app/src/lib.rs",
+                              "old_string": "x", "new_string": "y"}, is_error=True),
+        *_call("t5", "Read", {"file_path": "/app/Makefile"}),
+    ])
+    write_transcript(tmp_path / "-Users-me-app" / "s2.jsonl", [
+        *_call("t6", "Write", {"file_path": "/app/Makefile", "content": "all:\n\tcargo build"},
+               ts="2026-02-26T10:00:00Z"),
+    ])
+    write_transcript(tmp_path / "-Users-me-app" / "s3.jsonl", [
+        *_call("t7", "Read", {"file_path": "/app/src/main.rs"}),
+    ])
+    return tmp_path
+
+
+class TestLocStats:
+    def test_per_session(self, projects):
+        stats = loc_stats(str(projects))
+        assert sorted(stats) == ["s1", "s2"]
+        s1 = stats["s1"]
+        assert (s1["added"], s1["removed"], s1["files"], s1["edits"]) == (3 + 2 + 1, 1 + 2, 2, 3)
+        assert s1["by_extension"]["rs"] == {"added": 5, "removed": 1, "files": 1}
+        assert s1["by_extension"]["md"] == {"added": 1, "removed": 2, "files": 1}
+        assert stats["s2"]["by_extension"] == {"": {"added": 2, "removed": 0, "files": 1}}
+
+    def test_per_project_and_since(self, projects):
+        stats = loc_stats(str(projects), group_by="project")
+        (app,) = stats.values()
+        assert list(stats) == ["/Users/me/app"]
+        assert (app["added"], app["files"], app["edits"]) == (8, 3, 4)
+        stats = loc_stats(str(projects), since=1772100000.0)
+        assert list(stats) == ["s2"]
+
+    def test_single_file_and_errors(self, projects):
+        stats = loc_stats(str(projects / "-Users-me-app" / "s2.jsonl"))
+        assert stats["s2"]["added"] == 2
+        with pytest.raises(ValueError):
+            loc_stats(str(projects), group_by="file")
@@ -0,0 +1,67 @@
+"""Tests for forwarding Rust-side log records to Python logging (Rust native via PyO3)."""
+
+import json
+import logging
+
+import pytest
+
+from snoopy._native import parse_transcript, read_ndjson, set_log_level
+
+
+class _Records(logging.Handler):
+    def __init__(self):
+        super().__init__(logging.NOTSET)
+        self.records = []
+
+    def emit(self, record):
+        self.records.append(record)
+
+
+@pytest.fixture
+def records():
+    handler = _Records()
+    root = logging.getLogger("snoopy_native")
+    previous = root.level
+    root.addHandler(handler)
+    root.setLevel(logging.DEBUG)
+    yield handler.records
+    root.removeHandler(handler)
+    root.setLevel(previous)
+    set_log_level("warning")
+
+
+def _transcript(tmp_path):
+    path = tmp_path / "session.jsonl"
+    entry = {"type": "user", "timestamp": "2024-03-01T12:00:00Z",
+             "message": {"role": "user", "content": "hi"}}
+    path.write_text("{not json\n" + json.dumps(entry) + "\n")
+    return path
+
+
+class TestLogForwarding:
+    def test_skipped_line_logged_at_debug(self, tmp_path, records):
+        path = _transcript(tmp_path)
+        set_log_level(logging.DEBUG)
+        parse_transcript(str(path))
+        (record,) = records
+        assert record.name == "snoopy_native"
+        assert record.levelno == logging.DEBUG
+        assert "skipping unparseable line" in record.getMessage()
+        assert "offset=0" in record.getMessage()
+        assert str(path) in record.getMessage()
+
+    def test_module_scoped_logger(self, tmp_path, records):
+        path = tmp_path / "out.ndjson"
+        path.write_text('{"a": 1}\ngarbage\n\n')
+        set_log_level("debug")
+        assert read_ndjson(str(path)) == [{"a": 1}]
+        assert [r.name for r in records] == ["snoopy_native.compress"]
+
+    def test_default_level_is_quiet(self, tmp_path, records):
+        set_log_level("WARNING")
+        parse_transcript(str(_transcript(tmp_path)))
+        assert records == []
+
+    def test_invalid_level(self):
+        with pytest.raises(ValueError):
+            set_log_level("loud")
@@ -0,0 +1,70 @@
+"""Tests for `last` login record parsing (Rust native via PyO3)."""
+
+import time
+from datetime import datetime, timedelta
+
+import pytest
+
+from snoopy._native import parse_last
+
+LINUX = """\
+alice    pts/1        10.0.0.5         Fri Mar  1 09:15:02 2024 - Fri Mar  1 09:20:11 2024  (00:05)
+me       tty1                          Fri Mar  1 09:00:00 2024   still logged in
+me       pts/0        :0               Fri Mar  1 08:59:00 2024 - crash                    (00:01)
+reboot   system boot  6.1.0-18-amd64   Fri Mar  1 08:55:10 2024   still running
+
+wtmp begins Thu Feb  1 00:00:00 2024
+"""
+
+
+def ts(*parts):
+    return time.mktime((*parts, 0, 0, -1))
+
+
+class TestParseLast:
+    def test_linux_full_dates(self):
+        events = parse_last(LINUX)
+        assert [(e["kind"], e["user"]) for e in events] == [
+            ("reboot", "reboot"),
+            ("terminal", "me"),
+            ("console", "me"),
+            ("ssh", "alice"),
+        ]
+        reboot, crashed, console, ssh = events
+        assert reboot["kernel"] == "6.1.0-18-amd64"
+        assert reboot["remote_address"] is None
+        assert reboot["ongoing"] is True
+        assert reboot["duration"] is None
+        assert crashed["ending"] == "crash"
+        assert crashed["duration"] == 60
+        assert console["tty"] == "tty1"
+        assert console["end"] is None
+        assert ssh["source"] == "login"
+        assert ssh["timestamp"] == ts(2024, 3, 1, 9, 15, 2)
+        assert ssh["duration"] == 309
+        assert ssh["remote_address"] == "10.0.0.5"
+        assert ssh["summary"] == "alice ssh login from 10.0.0.5"
+
+    def test_macos_infers_year(self):
+        start = (datetime.now() - timedelta(days=40)).replace(second=0, microsecond=0)
+        stamp = start.strftime("%a %b ") + f"{start.day:2d} " + start.strftime("%H:%M")
+        text = (
+            f"me        ttys001                   {stamp} - 11:00  (1+02:03)\n"
+            f"me        console                   {stamp}   still logged in\n"
+            f"shutdown  ~                         {stamp}\n"
+        )
+        shutdown, console, terminal = parse_last(text)
+        assert terminal["timestamp"] == start.timestamp()
+        assert terminal["duration"] == 86400 + 2 * 3600 + 3 * 60
+        assert terminal["kind"] == "terminal"
+        assert console["kind"] == "console"
+        assert shutdown["kind"] == "shutdown"
+        assert shutdown["tty"] == "~"
+
+    def test_since(self):
+        events = parse_last(LINUX, since=ts(2024, 3, 1, 9, 0, 0))
+        assert [e["user"] for e in events] == ["alice"]
+
+    @pytest.mark.parametrize("line", ["", "garbage", "wtmp begins Fri Mar  1 00:00:00 2024"])
+    def test_ignores_other_lines(self, line):
+        assert parse_last(line) == []
@@ -0,0 +1,92 @@
+"""Tests for mail_events (Rust native via PyO3)."""
+
+import sqlite3
+
+import pytest
+
+from snoopy._native import mail_events
+
+T0 = 1_700_000_000.0
+INBOX = "imap://6F1C-4E2A/INBOX"
+SENT = "imap://6F1C-4E2A/%5BGmail%5D/Sent%20Mail"
+
+
+def _envelope_index(path, messages):
+    conn = sqlite3.connect(path)
+    conn.executescript(f"""
+        CREATE TABLE messages (ROWID INTEGER PRIMARY KEY, message_id INTEGER,
+            sender INTEGER, subject_prefix TEXT, subject INTEGER, summary INTEGER,
+            date_sent INTEGER, date_received INTEGER, mailbox INTEGER, read INTEGER,
+            flagged INTEGER, deleted INTEGER);
+        CREATE TABLE addresses (ROWID INTEGER PRIMARY KEY, address TEXT, comment TEXT);
+        CREATE TABLE subjects (ROWID INTEGER PRIMARY KEY, subject TEXT);
+        CREATE TABLE summaries (ROWID INTEGER PRIMARY KEY, summary TEXT);
+        CREATE TABLE mailboxes (ROWID INTEGER PRIMARY KEY, url TEXT);
+        CREATE TABLE recipients (ROWID INTEGER PRIMARY KEY, message INTEGER,
+            address INTEGER, type INTEGER, position INTEGER);
+        INSERT INTO addresses VALUES (1, 'alice@example.com', 'Alice Smith');
+        INSERT INTO addresses VALUES (2, 'me@example.com', '');
+        INSERT INTO addresses VALUES (3, 'bob@example.com', 'Bob');
+        INSERT INTO mailboxes VALUES (1, '{INBOX}');
+        INSERT INTO mailboxes VALUES (2, '{SENT}');
+    """)
+    for rowid, sender, prefix, subject, sent, received, mailbox, deleted, to in messages:
+        conn.execute("INSERT INTO subjects VALUES (?, ?)", (rowid, subject))
+        conn.execute("INSERT INTO summaries VALUES (?, ?)", (rowid, f"body of {subject}"))
+        conn.execute(
+            "INSERT INTO messages VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, 1, 0, ?)",
+            (rowid, 1000 + rowid, sender, prefix, rowid, rowid, sent, received, mailbox,
+             deleted),
+        )
+        for position, (address, kind) in enumerate(to):
+            conn.execute(
+                "INSERT INTO recipients (message, address, type, position) VALUES (?, ?, ?, ?)",
+                (rowid, address, kind, position),
+            )
+    conn.commit()
+    conn.close()
+
+
+@pytest.fixture
+def index(tmp_path):
+    path = tmp_path / "Envelope Index"
+    _envelope_index(path, [
+        (1, 1, None, "Lunch?", T0 - 30, T0, 1, 0, [(2, 0)]),
+        (2, 2, "Re: ", "Lunch?", T0 + 60, T0 + 65, 2, 0, [(3, 1), (1, 0)]),
+        (3, 1, None, "Spam", T0 + 90, T0 + 100, 1, 1, []),
+    ])
+    return path
+
+
+class TestMailEvents:
+    def test_sent_and_received(self, index):
+        received, sent = mail_events(index)
+        assert received["source"] == "mail"
+        assert received["kind"] == "received"
+        assert received["timestamp"] == T0
+        assert received["sender"] == "alice@example.com"
+        assert received["sender_name"] == "Alice Smith"
+        assert received["subject"] == "Lunch?"
+        assert received["mailbox"] == "INBOX"
+        assert received["recipients"] == ["me@example.com"]
+        assert received["read"] is True
+        assert received["message_id"] == 1001
+        assert sent["kind"] == "sent"
+        assert sent["timestamp"] == T0 + 60
+        assert sent["subject"] == "Re: Lunch?"
+        assert sent["mailbox"] == "[Gmail]/Sent Mail"
+        assert sent["sender_name"] is None
+        assert sent["recipient"] == "alice@example.com"
+        assert sent["recipients"] == ["alice@example.com", "bob@example.com"]
+
+    def test_bodies_only_when_enabled(self, index):
+        assert all(e["content_preview"] is None for e in mail_events(index))
+        previews = [e["content_preview"] for e in mail_events(index, include_body=True)]
+        assert previews == ["body of Lunch?", "body of Lunch?"]
+
+    def test_since(self, index):
+        assert [e["kind"] for e in mail_events(index, since=T0)] == ["sent"]
+
+    def test_missing_index(self, tmp_path):
+        with pytest.raises(IOError):
+            mail_events(tmp_path / "Envelope Index")
@@ -0,0 +1,77 @@
+"""Tests for meeting detection (Rust native via PyO3)."""
+
+import pytest
+
+from snoopy._native import Collector, MeetingDetector, media_in_use
+
+T0 = 1_700_000_000.0
+
+
+class TestMeetingDetector:
+    def test_zoom_meeting(self):
+        detector = MeetingDetector(grace=30)
+        assert detector.update(mic=True, app="Code", timestamp=T0) == []
+        (started,) = detector.update(mic=True, app="zoom.us", timestamp=T0 + 5)
+        assert started["source"] == "meetings"
+        assert started["kind"] == "meeting_started"
+        assert started["platform"] == "zoom"
+        assert started["app"] == "zoom.us"
+        assert detector.platform == "zoom"
+        # Switching apps and a short mute don't end it.
+        assert detector.update(mic=True, camera=True, app="Code", timestamp=T0 + 600) == []
+        assert detector.update(app="Code", timestamp=T0 + 610) == []
+        assert detector.update(mic=True, app="Code", timestamp=T0 + 620) == []
+        (ended,) = detector.update(app="Code", timestamp=T0 + 700)
+        assert ended["kind"] == "meeting_ended"
+        assert ended["timestamp"] == T0 + 620
+        assert ended["started"] == T0 + 5
+        assert ended["duration"] == 615
+        assert ended["camera"] is True
+        assert detector.platform is None
+
+    def test_browser_meeting_by_title(self):
+        detector = MeetingDetector()
+        events = detector.update(camera=True, app="Google Chrome",
+                                 title="Meet - abc-defg-hij", timestamp=T0)
+        assert [(e["platform"], e["app"]) for e in events] == [("meet", "Google Chrome")]
+
+    def test_connections(self):
+        detector = MeetingDetector()
+        zoom = [{"process_name": "CptHost", "remote_address": "170.114.0.1",
+                 "remote_port": 443}]
+        (started,) = detector.update(mic=True, app="Notes", connections=zoom, timestamp=T0)
+        assert (started["platform"], started["app"]) == ("zoom", "CptHost")
+        detector.finish()
+        stun = [{"process_name": "Arc", "remote_port": 19305}]
+        (started,) = detector.update(mic=True, app="Notes", connections=stun, timestamp=T0)
+        assert (started["platform"], started["app"]) == ("meet", "Arc")
+
+    def test_needs_media(self):
+        detector = MeetingDetector()
+        assert detector.update(app="zoom.us", timestamp=T0) == []
+        assert detector.update(mic=True, app="Voice Memos", timestamp=T0 + 1) == []
+
+    def test_finish(self):
+        detector = MeetingDetector()
+        assert detector.finish() == []
+        detector.update(mic=True, app="FaceTime", timestamp=T0)
+        detector.update(mic=True, app="FaceTime", timestamp=T0 + 90)
+        (ended,) = detector.finish()
+        assert ended["platform"] == "facetime"
+        assert ended["duration"] == 90
+
+    def test_invalid_grace(self):
+        with pytest.raises(ValueError):
+            MeetingDetector(grace=-1)
+
+
+def test_media_in_use():
+    state = media_in_use()
+    assert set(state) == {"mic", "camera"}
+    assert all(v in (True, False, None) for v in state.values())
+
+
+def test_collector_accepts_meetings():
+    collector = Collector(["meetings"], interval=60)
+    collector.start()
+    collector.stop()
@@ -45,3 +45,11 @@ class TestExtractAttributedBodyText:
         blob = b"\x00NSString\x01+" + bytes([20]) + text
         result = extract_attributed_body_text(blob)
         assert "short" in result
+
+    def test_buffer_inputs(self):
+        blob = _make_blob("Hello world")
+        assert extract_attributed_body_text(bytearray(blob)) == "Hello world"
+        assert extract_attributed_body_text(memoryview(blob)[2:]) == "Hello world"
+        # Non-contiguous views are copied rather than rejected.
+        spaced = bytes(b for pair in zip(blob, b"\x00" * len(blob)) for b in pair)
+        assert extract_attributed_body_text(memoryview(spaced)[::2]) == "Hello world"
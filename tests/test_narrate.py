@@ -0,0 +1,44 @@
+"""Tests for session narrative generation (Rust native via PyO3)."""
+
+from snoopy._native import narrate_session
+
+
+def _tool(ts, name, **inp):
+    return {"type": "assistant", "timestamp": ts,
+            "message": {"content": [{"type": "tool_use", "name": name, "input": inp}]}}
+
+
+class TestNarrateSession:
+    def test_collapses_repeated_actions(self, tmp_path, write_transcript):
+        transcript = tmp_path / "s.jsonl"
+        write_transcript(transcript, [
+            {"type": "user", "timestamp": "2026-02-25T09:12:00Z",
+             "message": {"content": "fix the flaky test"}},
+            _tool("2026-02-25T09:12:05Z", "Read", file_path="/src/a.py"),
+            _tool("2026-02-25T09:12:06Z", "Read", file_path="/src/b.py"),
+            _tool("2026-02-25T09:12:07Z", "Bash", command="pytest -x"),
+            _tool("2026-02-25T09:12:30Z", "Bash", command="pytest -x"),
+            _tool("2026-02-25T09:13:00Z", "Edit", file_path="/tests/test_foo.py"),
+            {"type": "user", "timestamp": "2026-02-25T09:20:00Z",
+             "message": {"content": "thanks"}},
+        ])
+
+        assert narrate_session(str(transcript)).splitlines() == [
+            "09:12 user asked: fix the flaky test → agent read 2 files → ran pytest 2× "
+            "→ edited test_foo.py",
+            "09:20 user asked: thanks",
+        ]
+
+    def test_utc_offset_and_long_prompt(self, tmp_path, write_transcript):
+        transcript = tmp_path / "s.jsonl"
+        write_transcript(transcript, [
+            {"type": "user", "timestamp": "2026-02-25T23:30:00Z",
+             "message": {"content": "x" * 100}},
+        ])
+        line = narrate_session(str(transcript), utc_offset=3600)
+        assert line == "00:30 user asked: " + "x" * 60 + "…"
+
+    def test_empty_transcript(self, tmp_path):
+        transcript = tmp_path / "s.jsonl"
+        transcript.write_text("")
+        assert narrate_session(str(transcript)) == ""
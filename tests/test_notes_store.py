@@ -0,0 +1,99 @@
+"""Tests for notes_events (Rust native via PyO3)."""
+
+import gzip
+import sqlite3
+
+import pytest
+
+from snoopy._native import notes_events
+
+COCOA_EPOCH = 978307200
+T0 = 1_700_000_000.0
+
+
+def _varint(value):
+    out = bytearray()
+    while True:
+        byte = value & 0x7F
+        value >>= 7
+        if value:
+            out.append(byte | 0x80)
+        else:
+            out.append(byte)
+            return bytes(out)
+
+
+def _field(number, payload):
+    return _varint(number << 3 | 2) + _varint(len(payload)) + payload
+
+
+def _note_store_proto(text):
+    # NoteStoreProto.document(2) -> Document{version(2)=1, note(3)} -> Note.note_text(2)
+    note = _field(2, text.encode()) + _varint(5 << 3) + _varint(7)
+    document = _varint(2 << 3) + _varint(1) + _field(3, note)
+    return gzip.compress(_field(2, document))
+
+
+def _notes_db(path, notes):
+    conn = sqlite3.connect(path)
+    conn.executescript("""
+        CREATE TABLE ZICCLOUDSYNCINGOBJECT (Z_PK INTEGER PRIMARY KEY, ZIDENTIFIER TEXT,
+            ZTITLE1 TEXT, ZTITLE2 TEXT, ZMODIFICATIONDATE1 REAL, ZCREATIONDATE3 REAL,
+            ZFOLDER INTEGER, ZACCOUNT2 INTEGER, ZNAME TEXT, ZNOTEDATA INTEGER);
+        CREATE TABLE ZICNOTEDATA (Z_PK INTEGER PRIMARY KEY, ZNOTE INTEGER, ZDATA BLOB);
+        INSERT INTO ZICCLOUDSYNCINGOBJECT (Z_PK, ZTITLE2) VALUES (100, 'Ideas');
+        INSERT INTO ZICCLOUDSYNCINGOBJECT (Z_PK, ZNAME) VALUES (200, 'iCloud');
+    """)
+    for pk, title, created, modified, zdata in notes:
+        conn.execute(
+            "INSERT INTO ZICCLOUDSYNCINGOBJECT (Z_PK, ZIDENTIFIER, ZTITLE1, ZCREATIONDATE3,"
+            " ZMODIFICATIONDATE1, ZFOLDER, ZACCOUNT2, ZNOTEDATA)"
+            " VALUES (?, ?, ?, ?, ?, 100, 200, ?)",
+            (pk, f"NOTE-{pk}", title, created - COCOA_EPOCH, modified - COCOA_EPOCH, pk),
+        )
+        conn.execute("INSERT INTO ZICNOTEDATA VALUES (?, ?, ?)", (pk, pk, zdata))
+    conn.commit()
+    conn.close()
+
+
+class TestNotesEvents:
+    def test_create_and_modify_events(self, tmp_path):
+        db = tmp_path / "NoteStore.sqlite"
+        _notes_db(db, [
+            (1, "Groceries", T0, T0 + 600, _note_store_proto("Groceries\nmilk\neggs")),
+            (2, "Untouched", T0 + 60, T0 + 60, _note_store_proto("Untouched")),
+        ])
+        events = notes_events(str(db))
+        assert [(e["title"], e["kind"]) for e in events] == [
+            ("Groceries", "created"),
+            ("Untouched", "created"),
+            ("Groceries", "modified"),
+        ]
+        modified = events[2]
+        assert modified["source"] == "notes"
+        assert modified["timestamp"] == pytest.approx(T0 + 600)
+        assert modified["created"] == pytest.approx(T0)
+        assert modified["note_id"] == "NOTE-1"
+        assert modified["folder"] == "Ideas"
+        assert modified["account"] == "iCloud"
+        assert modified["content"] == "Groceries\nmilk\neggs"
+
+    def test_since_and_unknown_layout(self, tmp_path):
+        db = tmp_path / "NoteStore.sqlite"
+        odd = gzip.compress(_field(7, _field(1, b"Loose text body")))
+        _notes_db(db, [
+            (1, "Old", T0 - 9000, T0 - 9000, _note_store_proto("old")),
+            (2, "Odd", T0 - 9000, T0 + 10, odd),
+            (3, "Broken", T0 + 20, T0 + 20, b"not gzip"),
+        ])
+        events = notes_events(str(db), since=T0)
+        assert [(e["title"], e["kind"]) for e in events] == [
+            ("Odd", "modified"),
+            ("Broken", "created"),
+        ]
+        assert events[0]["content"] == "Loose text body"
+        assert events[1]["content"] == ""
+
+    def test_missing_database_raises(self, tmp_path):
+        with pytest.raises(IOError):
+            notes_events(str(tmp_path / "NoteStore.sqlite"))
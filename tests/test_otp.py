@@ -0,0 +1,72 @@
+"""Tests for verification code detection (Rust native via PyO3)."""
+
+import pytest
+
+from snoopy._native import find_verification_code, verification_events
+
+
+class TestFindVerificationCode:
+    @pytest.mark.parametrize("text,code,service", [
+        ("Your code is 123456", "123456", None),
+        ("G-482913 is your Google verification code.", "482913", "Google"),
+        ("Apple: Your Apple ID code is: 774201. Don't share it.", "774201", "Apple"),
+        ("[Acme] Use 918 273 to sign in. Valid for 10 minutes.", "918273", "Acme"),
+        ("Your Chase security code is 5821", "5821", "Chase"),
+        ("<#> 6042 is your login PIN for Example", "6042", "Example"),
+    ])
+    def test_codes(self, text, code, service):
+        found = find_verification_code(text)
+        assert found["code"] == code
+        assert found["service"] == service
+        assert text[found["start"]:found["end"]].replace(" ", "").replace("-", "") == code
+
+    @pytest.mark.parametrize("text", [
+        "See you at 1830 tomorrow",  # no keyword
+        "Your order total is $1234 with code SAVE",
+        "Call me at 555-123-4567 about the login",
+        "Security update 2026.01.15 released",
+        "Meeting code moved to 12/05/2026",
+        "Your account 123456789 was verified",
+        "Your code is \u0661\u0662\u0663\u0664\u0665\u0666",  # not ASCII digits
+    ])
+    def test_not_codes(self, text):
+        assert find_verification_code(text) is None
+
+
+class TestVerificationEvents:
+    def test_events(self):
+        events = [
+            {"source": "messages", "timestamp": 100.0, "contact": "+15550001111",
+             "is_from_me": 0, "content_preview": "Your Acme verification code is 246810"},
+            {"source": "messages", "timestamp": 101.0, "contact": "+15550001111",
+             "is_from_me": 1, "content_preview": "my code is 135790"},
+            {"source": "mail", "kind": "received", "timestamp": 102.0, "sender": "no-reply@x.io",
+             "subject": "Your login code: 8642", "content_preview": None},
+            {"source": "slack", "timestamp": 103.0, "sender": "bob", "text": "lunch at noon?"},
+        ]
+        found = verification_events(events)
+        assert [e["timestamp"] for e in found] == [100.0, 102.0]
+        first = found[0]
+        assert first["source"] == "verification"
+        assert first["message_source"] == "messages"
+        assert first["from"] == "+15550001111"
+        assert first["service"] == "Acme"
+        assert first["code"] == "[REDACTED]"
+        assert first["content_preview"] == "Your Acme verification code is [REDACTED]"
+        assert found[1]["from"] == "no-reply@x.io"
+
+    def test_redacts_every_copy(self):
+        text = ("Your Apple Account code is: 482 913. Don't share it.\n\n"
+                "@apple.com #482913 %4829130")
+        (event,) = verification_events([{"source": "messages", "timestamp": 1.0,
+                                         "content_preview": text}])
+        assert event["content_preview"] == (
+            "Your Apple Account code is: [REDACTED]. Don't share it.\n\n"
+            "@apple.com #[REDACTED] %4829130")
+
+    def test_unredacted(self):
+        events = [{"source": "messages", "timestamp": 1.0, "contact": "x",
+                   "content_preview": "Código 1234 is your login code"}]
+        (event,) = verification_events(events, redact=False)
+        assert event["code"] == "1234"
+        assert event["content_preview"] == "Código 1234 is your login code"
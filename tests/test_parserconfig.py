@@ -0,0 +1,134 @@
+"""Tests for ParserConfig (Rust native via PyO3)."""
+
+import json
+import pickle
+from datetime import datetime, timezone
+
+import pytest
+
+from snoopy._native import (
+    ParserConfig,
+    PrivacyFilter,
+    parse_transcript,
+    replay_transcript,
+    truncate_preview,
+)
+
+LONG = "a" * 40 + "MIDDLE" + "z" * 40
+
+
+ENTRIES = [
+    {"type": "user", "timestamp": "2026-02-25T10:00:00.250Z",
+     "message": {"role": "user", "content": LONG}},
+    {"type": "assistant", "timestamp": "2026-02-25T10:00:01Z",
+     "message": {"role": "assistant", "content": [
+         {"type": "text", "text": "my api key is sk-123"},
+         {"type": "tool_use", "name": "Bash", "input": {"command": "ls /tmp"}},
+     ]}},
+    {"type": "system", "timestamp": "2026-02-25T10:00:02Z", "subtype": "api_error",
+     "level": "error", "content": "Overloaded"},
+]
+
+
+class TestParserConfig:
+    def test_defaults(self):
+        config = ParserConfig()
+        assert config.preview_len == 500
+        assert config.truncation == "end"
+        assert config.include_raw is False
+        assert config.message_types is None
+        assert config.privacy is None
+        assert config.time_format == "epoch"
+        assert config.timezone == "UTC"
+        assert config.content_hash is False
+
+    def test_truncation_modes(self, tmp_path, write_transcript):
+        path = str(write_transcript(tmp_path / "s.jsonl", ENTRIES))
+        events, _ = parse_transcript(path, config=ParserConfig(preview_len=20))
+        assert events[0]["content_preview"] == "a" * 20
+        config = ParserConfig(preview_len=21, truncation="middle")
+        events, _ = parse_transcript(path, config=config)
+        assert events[0]["content_preview"] == "a" * 9 + "…" + "z" * 9
+        events, _ = parse_transcript(path, config=ParserConfig(preview_len=20, truncation="none"))
+        assert events[0]["content_preview"] == LONG
+
+    def test_message_types_and_privacy(self, tmp_path, write_transcript):
+        path = str(write_transcript(tmp_path / "s.jsonl", ENTRIES))
+        config = ParserConfig(message_types=["assistant_text", "system"],
+                              privacy=PrivacyFilter(redact_content=[r"sk-\d+"]))
+        events, _ = parse_transcript(path, config=config)
+        assert [e["message_type"] for e in events] == ["assistant_text", "system:api_error"]
+        assert events[0]["content_preview"] == "my api key is [REDACTED]"
+
+    def test_time_formats(self, tmp_path, write_transcript):
+        path = str(write_transcript(tmp_path / "s.jsonl", ENTRIES))
+        events, _ = parse_transcript(path, config=ParserConfig(time_format="iso"))
+        assert events[0]["timestamp"] == "2026-02-25T10:00:00.250Z"
+        events, _ = parse_transcript(path, config=ParserConfig(time_format="datetime"))
+        assert events[1]["timestamp"] == datetime(2026, 2, 25, 10, 0, 1, tzinfo=timezone.utc)
+
+    def test_timezone(self, tmp_path, write_transcript):
+        path = str(write_transcript(tmp_path / "s.jsonl", ENTRIES))
+        config = ParserConfig(time_format="iso", timezone="America/Los_Angeles")
+        events, _ = parse_transcript(path, config=config)
+        assert events[0]["timestamp"] == "2026-02-25T02:00:00.250-08:00"
+        config = ParserConfig(time_format="datetime", timezone="Europe/Paris")
+        events, _ = parse_transcript(path, config=config)
+        assert events[1]["timestamp"].tzinfo.key == "Europe/Paris"
+        assert events[1]["timestamp"].hour == 11
+        # datetimes=True renders in the config's zone too.
+        events, _ = parse_transcript(path, datetimes=True, config=config)
+        assert events[1]["timestamp"].tzinfo.key == "Europe/Paris"
+        config = ParserConfig(time_format="iso", timezone="local")
+        events, _ = parse_transcript(path, config=config)
+        assert datetime.fromisoformat(events[1]["timestamp"]).timestamp() == 1772013601.0
+        copy = pickle.loads(pickle.dumps(config))
+        assert copy.timezone == "local"
+        assert ParserConfig.from_json(config.to_json()).timezone == "local"
+
+    def test_keywords_override_config(self, tmp_path, write_transcript):
+        path = str(write_transcript(tmp_path / "s.jsonl", ENTRIES))
+        config = ParserConfig(preview_len=5, include_raw=["type"], time_format="iso")
+        events, _ = parse_transcript(path, preview_len=8, datetimes=False, config=config)
+        assert events[0]["content_preview"] == "a" * 8
+        assert isinstance(events[0]["timestamp"], float)
+        assert json.loads(events[0]["raw"]) == {"type": "user"}
+
+    def test_replay_accepts_config(self, tmp_path, write_transcript):
+        path = str(write_transcript(tmp_path / "s.jsonl", ENTRIES))
+        config = ParserConfig(message_types=["tool_use"], time_format="iso")
+        (event,) = list(replay_transcript(path, speed=1000, config=config))
+        assert event["message_type"] == "tool_use:Bash"
+        assert event["timestamp"] == "2026-02-25T10:00:01.000Z"
+
+    def test_invalid_options(self):
+        with pytest.raises(ValueError):
+            ParserConfig(truncation="start")
+        with pytest.raises(ValueError):
+            ParserConfig(time_format="unix")
+        with pytest.raises(ValueError):
+            ParserConfig(timezone="Mars/Olympus")
+
+    def test_pickle_and_json_round_trip(self, tmp_path, write_transcript):
+        path = str(write_transcript(tmp_path / "s.jsonl", ENTRIES))
+        config = ParserConfig(preview_len=21, truncation="middle", include_raw=["type"],
+                              message_types=["user", "assistant_text"], time_format="iso",
+                              privacy=PrivacyFilter(redact_content=[r"sk-\d+"]))
+        expected, _ = parse_transcript(path, config=config)
+        for copy in (pickle.loads(pickle.dumps(config)), ParserConfig.from_json(config.to_json())):
+            assert copy.truncation == "middle"
+            assert copy.include_raw == ["type"]
+            assert parse_transcript(path, config=copy)[0] == expected
+        assert ParserConfig.from_json("{}").preview_len == 500
+        with pytest.raises(ValueError):
+            ParserConfig.from_json('{"truncation": "start"}')
+
+    def test_truncate_preview(self):
+        assert truncate_preview(LONG, 20) == "a" * 20
+        assert truncate_preview(LONG, 21, truncation="middle") == "a" * 9 + "…" + "z" * 9
+        assert truncate_preview("héllo", 2) == "h"
+        assert truncate_preview("short") == "short"
+        for max_len in range(3):
+            assert truncate_preview(LONG, max_len, truncation="middle") == "a" * max_len
+        with pytest.raises(ValueError):
+            truncate_preview("x", 1, truncation="start")
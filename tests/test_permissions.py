@@ -0,0 +1,73 @@
+"""Tests for permission request tracking (Rust native via PyO3)."""
+
+import pytest
+
+from snoopy._native import permission_requests
+
+REJECTED = ("The user doesn't want to proceed with this tool use. The tool use was rejected "
+            "(eg. if it was a file edit, the new_string was NOT written to the file). To tell "
+            "you how to proceed, the user said:\nuse cargo instead")
+BLOCKED = "Permission to use Bash with command rm -rf / has been denied."
+
+
+def _call(tool_id, name, input, result="ok", second=0, result_second=None, mode=None):
+    use = {"type": "assistant", "timestamp": f"2026-02-25T10:00:{second:02d}Z",
+           "message": {"content": [{"type": "tool_use", "id": tool_id, "name": name,
+                                    "input": input}]}}
+    entries = [use]
+    if mode:
+        entries.insert(0, {"type": "user", "permissionMode": mode,
+                           "timestamp": f"2026-02-25T10:00:{second:02d}Z",
+                           "message": {"role": "user", "content": "go"}})
+    if result is not None:
+        done = second if result_second is None else result_second
+        entries.append({"type": "user", "timestamp": f"2026-02-25T10:00:{done:02d}Z",
+                        "message": {"content": [{"type": "tool_result", "tool_use_id": tool_id,
+                                                 "content": result}]}})
+    return entries
+
+
+@pytest.fixture
+def transcript(tmp_path, write_transcript):
+    path = tmp_path / "-Users-me-app" / "s1.jsonl"
+    write_transcript(path, [
+        *_call("t1", "Read", {"file_path": "/app/main.rs"}, second=0),
+        *_call("t2", "Bash", {"command": "make test"}, second=1, result_second=9),
+        *_call("t3", "Bash", {"command": "make build"}, result=REJECTED, second=10,
+               result_second=15),
+        *_call("t4", "Bash", {"command": "rm -rf /"}, result=BLOCKED, second=20),
+        *_call("t5", "Edit", {"file_path": "/app/main.rs"}, second=30, mode="acceptEdits"),
+        *_call("t6", "mcp__github__create_issue", {"title": "x"}, second=40),
+        *_call("t7", "WebFetch", {"url": "https://example.com"}, result=None, second=50),
+    ])
+    return path
+
+
+class TestPermissionRequests:
+    def test_decisions(self, transcript):
+        requests = permission_requests(str(transcript))
+        assert [r["tool_use_id"] for r in requests] == ["t2", "t3", "t4", "t5", "t6", "t7"]
+        by_id = {r["tool_use_id"]: r for r in requests}
+        assert by_id["t2"]["decision"] == "allowed"
+        assert by_id["t2"]["command"] == "make test"
+        assert by_id["t2"]["response_seconds"] == 8
+        assert by_id["t2"]["permission_mode"] == "default"
+        assert by_id["t2"]["auto_approved"] is False
+        assert by_id["t3"]["decision"] == "denied"
+        assert by_id["t3"]["feedback"] == "use cargo instead"
+        assert by_id["t4"]["decision"] == "blocked"
+        assert by_id["t4"]["feedback"] is None
+        assert by_id["t5"]["permission_mode"] == "acceptEdits"
+        assert by_id["t5"]["auto_approved"] is True
+        assert by_id["t6"]["decision"] == "allowed"
+        assert by_id["t7"]["decision"] == "pending"
+        assert by_id["t7"]["response_seconds"] is None
+        assert requests[0]["session_id"] == "s1"
+        assert requests[0]["project_path"] == str(transcript.parent)
+
+    def test_custom_tools(self, transcript):
+        requests = permission_requests(str(transcript.parent.parent), tools=["Read"])
+        # Refusals are always reported; of the rest only the named tools.
+        assert [r["tool_use_id"] for r in requests] == ["t1", "t3", "t4"]
+        refused = permission_requests(str(transcript), tools=[])
+        assert [r["decision"] for r in refused] == ["denied", "blocked"]
@@ -0,0 +1,91 @@
+"""Tests for persistence_snapshot and the persistence Collector source (Rust native via PyO3)."""
+
+import sys
+import time
+
+import pytest
+
+from snoopy._native import Collector, persistence_snapshot
+
+pytestmark = pytest.mark.skipif(sys.platform == "darwin", reason="uses the XDG/systemd paths")
+
+AUTOSTART = """[Desktop Entry]
+Type=Application
+Name=Sync Helper
+Exec=/opt/sync/helper --quiet
+X-GNOME-Autostart-enabled=false
+"""
+
+UNIT = """[Unit]
+Description=Backup agent
+
+[Service]
+ExecStart=-/usr/bin/backup-agent --daemon
+Restart=always
+
+[Install]
+WantedBy=default.target
+"""
+
+
+def _user_items(home):
+    return [i for i in persistence_snapshot()["items"] if i["path"].startswith(str(home))]
+
+
+class TestPersistenceSnapshot:
+    def test_reads_autostart_and_units(self, tmp_path, monkeypatch):
+        monkeypatch.setenv("HOME", str(tmp_path))
+        (tmp_path / ".config/autostart").mkdir(parents=True)
+        (tmp_path / ".config/autostart/sync.desktop").write_text(AUTOSTART)
+        (tmp_path / ".config/systemd/user").mkdir(parents=True)
+        (tmp_path / ".config/systemd/user/backup.service").write_text(UNIT)
+        (tmp_path / ".config/systemd/user/notes.txt").write_text("ignored")
+
+        autostart, unit = _user_items(tmp_path)
+        assert autostart["source"] == "persistence"
+        assert (autostart["type"], autostart["scope"]) == ("autostart", "user")
+        assert autostart["label"] == "Sync Helper"
+        assert autostart["program"] == "/opt/sync/helper"
+        assert autostart["arguments"] == ["/opt/sync/helper", "--quiet"]
+        assert (autostart["run_at_load"], autostart["disabled"]) == (True, True)
+        assert len(autostart["sha256"]) == 64
+        assert (unit["type"], unit["label"]) == ("systemd_unit", "Backup agent")
+        assert unit["program"] == "/usr/bin/backup-agent"
+        assert (unit["run_at_load"], unit["keep_alive"], unit["disabled"]) == (True, True, False)
+
+    def test_digest_tracks_content(self, tmp_path, monkeypatch):
+        monkeypatch.setenv("HOME", str(tmp_path))
+        (tmp_path / ".config/autostart").mkdir(parents=True)
+        entry = tmp_path / ".config/autostart/sync.desktop"
+        entry.write_text(AUTOSTART)
+        first = persistence_snapshot()["digest"]
+        assert persistence_snapshot()["digest"] == first
+        entry.write_text(AUTOSTART.replace("--quiet", "--loud"))
+        assert persistence_snapshot()["digest"] != first
+
+    def test_collector_reports_changes(self, tmp_path, monkeypatch):
+        monkeypatch.setenv("HOME", str(tmp_path))
+        autostart = tmp_path / ".config/autostart"
+        autostart.mkdir(parents=True)
+        (autostart / "old.desktop").write_text(AUTOSTART)
+        (autostart / "edit.desktop").write_text(AUTOSTART)
+        collector = Collector(["persistence"], interval=0.05)
+        collector.start()
+        try:
+            time.sleep(0.3)
+            assert collector.drain() == []
+            (autostart / "old.desktop").unlink()
+            (autostart / "edit.desktop").write_text(AUTOSTART.replace("helper", "miner"))
+            (autostart / "new.desktop").write_text(AUTOSTART)
+            time.sleep(0.3)
+            events = collector.drain()
+        finally:
+            collector.stop()
+        assert [(e["kind"], e["path"].rsplit("/", 1)[1]) for e in events] == [
+            ("changed", "edit.desktop"),
+            ("added", "new.desktop"),
+            ("removed", "old.desktop"),
+        ]
+        assert events[0]["previous_sha256"] != events[0]["sha256"]
+        assert events[0]["program"] == "/opt/sync/miner"
+        assert events[1]["summary"] == "added autostart Sync Helper"
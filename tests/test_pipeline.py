@@ -0,0 +1,169 @@
+"""Tests for TranscriptPipeline (Rust native via PyO3)."""
+
+import json
+import threading
+import time
+
+import pytest
+
+from snoopy._native import ParserConfig, TranscriptPipeline
+
+
+def _entry(text, ts="2026-02-25T10:00:00Z"):
+    return json.dumps({"type": "user", "timestamp": ts,
+                       "message": {"role": "user", "content": text}}) + "\n"
+
+
+def _append(path, *texts):
+    with open(path, "a") as f:
+        for text in texts:
+            f.write(_entry(text))
+
+
+def _poll_until(pipeline, n, timeout=5.0):
+    events = []
+    deadline = time.time() + timeout
+    while len(events) < n and time.time() < deadline:
+        events.extend(pipeline.poll(timeout=0.1))
+    return events
+
+
+class TestTranscriptPipeline:
+    def test_existing_then_new_files(self, tmp_path):
+        project = tmp_path / "-Users-me-app"
+        project.mkdir()
+        _append(project / "s1.jsonl", "first", "second")
+        with TranscriptPipeline(tmp_path, debounce=0.05) as pipeline:
+            events = pipeline.poll()
+            assert [e["content_preview"] for e in events] == ["first", "second"]
+            assert events[0]["session_id"] == "s1"
+            assert events[0]["project_path"] == str(project)
+            other = tmp_path / "-Users-me-other"
+            other.mkdir()
+            time.sleep(0.2)
+            _append(other / "s2.jsonl", "hello")
+            (event,) = _poll_until(pipeline, 1)
+            assert event["session_id"] == "s2"
+            assert event["project_path"] == str(other)
+
+    def test_appends_are_incremental(self, tmp_path):
+        path = tmp_path / "s.jsonl"
+        _append(path, "one")
+        with TranscriptPipeline(tmp_path, debounce=0.05) as pipeline:
+            assert len(pipeline.poll()) == 1
+            _append(path, "two", "three")
+            events = _poll_until(pipeline, 2)
+            assert [e["content_preview"] for e in events] == ["two", "three"]
+            assert pipeline.offsets == {str(path): path.stat().st_size}
+            assert pipeline.poll(timeout=0.2) == []
+
+    def test_half_written_line_waits(self, tmp_path):
+        path = tmp_path / "s.jsonl"
+        _append(path, "one")
+        line = _entry("two")
+        with TranscriptPipeline(tmp_path, debounce=0.05) as pipeline:
+            assert len(pipeline.poll()) == 1
+            with open(path, "a") as f:
+                f.write(line[:20])
+            assert pipeline.poll(timeout=0.3) == []
+            assert pipeline.offsets == {str(path): len(_entry("one"))}
+            with open(path, "a") as f:
+                f.write(line[20:])
+            (event,) = _poll_until(pipeline, 1)
+            assert event["content_preview"] == "two"
+            assert pipeline.offsets == {str(path): path.stat().st_size}
+
+    def test_include_existing_false(self, tmp_path):
+        path = tmp_path / "s.jsonl"
+        _append(path, "old")
+        with TranscriptPipeline(tmp_path, include_existing=False, debounce=0.05) as pipeline:
+            assert pipeline.poll(timeout=0.2) == []
+            _append(path, "new")
+            (event,) = _poll_until(pipeline, 1)
+            assert event["content_preview"] == "new"
+
+    def test_resume_from_offsets(self, tmp_path):
+        path = tmp_path / "s.jsonl"
+        _append(path, "one")
+        with TranscriptPipeline(tmp_path, debounce=0.05) as pipeline:
+            pipeline.poll()
+            offsets = pipeline.offsets
+        _append(path, "two")
+        with TranscriptPipeline(tmp_path, offsets=offsets, include_existing=False,
+                                debounce=0.05) as pipeline:
+            (event,) = pipeline.poll()
+            assert event["content_preview"] == "two"
+
+    def test_truncated_file_starts_over(self, tmp_path):
+        path = tmp_path / "s.jsonl"
+        _append(path, "one", "two")
+        with TranscriptPipeline(tmp_path, debounce=0.05) as pipeline:
+            assert len(pipeline.poll()) == 2
+            path.write_text(_entry("fresh"))
+            (event,) = _poll_until(pipeline, 1)
+            assert event["content_preview"] == "fresh"
+
+    def test_tool_use_remembered_between_polls(self, tmp_path):
+        path = tmp_path / "s.jsonl"
+        use = {"type": "assistant", "timestamp": "2026-02-25T10:00:00Z",
+               "message": {"content": [{"type": "tool_use", "id": "toolu_1", "name": "Bash",
+                                        "input": {"command": "rm -rf build"}}]}}
+        denial = {"type": "user", "timestamp": "2026-02-25T10:00:05Z",
+                  "message": {"content": [{"type": "tool_result", "tool_use_id": "toolu_1",
+                                           "content": "The user doesn't want to proceed "
+                                                      "with this tool use."}]}}
+        path.write_text(json.dumps(use) + "\n")
+        with TranscriptPipeline(tmp_path, debounce=0.05) as pipeline:
+            assert [e["message_type"] for e in pipeline.poll()] == ["tool_use:Bash"]
+            with open(path, "a") as f:
+                f.write(json.dumps(denial) + "\n")
+            (event,) = _poll_until(pipeline, 1)
+            assert event["message_type"] == "permission_denied:Bash"
+            assert event["content_preview"] == "rm -rf build"
+
+    def test_offsets_while_polling(self, tmp_path):
+        path = tmp_path / "s.jsonl"
+        with open(path, "wb") as f:
+            for i in range(2000):
+                f.write(_entry(f"line {i}").encode())
+                f.write(b"\xff\xfe not utf-8\n")
+        pipeline = TranscriptPipeline(tmp_path)
+        done = threading.Event()
+        seen = []
+
+        def read_offsets():
+            while not done.is_set():
+                seen.append(pipeline.offsets)
+
+        reader = threading.Thread(target=read_offsets)
+        reader.start()
+        try:
+            pipeline.start()
+            events = pipeline.poll()
+        finally:
+            done.set()
+            reader.join(timeout=5)
+            pipeline.close()
+        assert not reader.is_alive()
+        assert len(events) == 2000
+        assert seen
+
+    def test_config_and_other_files(self, tmp_path):
+        _append(tmp_path / "s.jsonl", "x" * 50)
+        (tmp_path / "notes.txt").write_text("not a transcript\n")
+        config = ParserConfig(preview_len=10, time_format="iso")
+        with TranscriptPipeline(tmp_path, config, debounce=0.05) as pipeline:
+            (event,) = pipeline.poll()
+            assert event["content_preview"] == "x" * 10
+            assert event["timestamp"] == "2026-02-25T10:00:00.000Z"
+
+    def test_lifecycle(self, tmp_path):
+        pipeline = TranscriptPipeline(tmp_path)
+        assert not pipeline.running
+        assert pipeline.poll() == []
+        pipeline.start()
+        assert pipeline.running
+        assert pipeline.close()
+        assert not pipeline.running
+        with pytest.raises(ValueError):
+            TranscriptPipeline(tmp_path, debounce=float("inf"))
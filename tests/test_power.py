@@ -0,0 +1,65 @@
+"""Tests for parse_pmset_log and power_events (Rust native via PyO3)."""
+
+import os
+
+import pytest
+
+from snoopy._native import parse_pmset_log, power_events
+
+LOG = (
+    "Time stamp                Domain              \tMessage         \tDuration\n"
+    "==========                ======              \t=======         \t========\n"
+    "2023-11-14 08:00:00 -0800 Assertions          \tPID 88(coreaudiod) Created ...    \t\n"
+    "2023-11-14 08:30:07 -0800 Sleep               \tEntering Sleep state due to "
+    "'Clamshell Sleep':TCPKeepAlive=active Using Batt (Charge:78%)        \t3600 secs\n"
+    "2023-11-14 09:00:00 -0800 DarkWake            \tDarkWake from Deep Idle [CDN] : due to "
+    "EC.RTC/Maintenance Using Batt (Charge:77%)        \t45 secs\n"
+    "2023-11-14 09:30:07 -0800 Wake                \tWake from Deep Idle [CDNVA] : due to "
+    "EC.LidOpen/Lid Open Using AC (Charge:77%)        \t\n"
+)
+T_SLEEP = 1699979407.0
+
+
+class TestParsePmsetLog:
+    def test_sleep_wake_lid_and_charge(self):
+        events = parse_pmset_log(LOG)
+        assert [e["kind"] for e in events] == [
+            "sleep", "lid", "charge", "darkwake", "charge", "wake", "lid", "charge",
+        ]
+        sleep, lid_closed, charge = events[:3]
+        assert sleep["source"] == "power"
+        assert sleep["timestamp"] == T_SLEEP
+        assert sleep["reason"] == "Clamshell Sleep"
+        assert sleep["power_source"] == "Battery"
+        assert sleep["charge"] == 78
+        assert sleep["duration"] == 3600
+        assert lid_closed["state"] == "closed"
+        assert charge["previous_source"] is None
+        wake, lid_open, plugged = events[5:]
+        assert wake["reason"] == "EC.LidOpen/Lid Open"
+        assert wake["duration"] is None
+        assert lid_open["state"] == "open"
+        assert (plugged["power_source"], plugged["previous_source"]) == ("AC", "Battery")
+        assert events[3]["reason"] == "EC.RTC/Maintenance"
+
+    def test_since(self):
+        events = parse_pmset_log(LOG, since=T_SLEEP + 3000)
+        assert [e["kind"] for e in events] == ["wake", "lid", "charge"]
+        assert events[2]["previous_charge"] == 77
+
+
+class TestPowerEvents:
+    def test_runs_pmset(self, tmp_path, monkeypatch):
+        bin_dir = tmp_path / "bin"
+        bin_dir.mkdir()
+        (tmp_path / "log.txt").write_text(LOG)
+        script = bin_dir / "pmset"
+        script.write_text(
+            f'#!/bin/sh\n[ "$1 $2" = "-g log" ] || exit 2\ncat "{tmp_path}/log.txt"\n'
+        )
+        script.chmod(0o755)
+        monkeypatch.setenv("PATH", f"{bin_dir}{os.pathsep}{os.environ['PATH']}")
+        assert len(power_events()) == 8
+        script.write_text("#!/bin/sh\necho 'no log' >&2\nexit 1\n")
+        with pytest.raises(RuntimeError, match="no log"):
+            power_events()
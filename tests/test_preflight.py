@@ -0,0 +1,75 @@
+"""Tests for the permission preflight check (Rust native via PyO3)."""
+
+import os
+
+import pytest
+
+from snoopy._native import PermissionDenied, check_permissions
+
+
+@pytest.fixture
+def paths(tmp_path):
+    chat = tmp_path / "chat.db"
+    chat.write_bytes(b"SQLite format 3\x00")
+    return {
+        "messages": str(chat),
+        "safari": str(tmp_path / "missing.db"),
+        "knowledge": str(tmp_path),
+        "tcc": str(tmp_path / "also-missing.db"),
+    }
+
+
+def _checks(report):
+    return {c["name"]: c for c in report["checks"]}
+
+
+class TestCheckPermissions:
+    def test_report(self, paths):
+        report = check_permissions(paths=paths)
+        assert report["ok"]
+        assert report["full_disk_access"] is True
+        assert report["executable"]
+        checks = _checks(report)
+        assert list(checks) == ["messages", "safari", "knowledge", "tcc"]
+        assert checks["messages"]["status"] == "granted"
+        assert checks["messages"]["path"] == paths["messages"]
+        assert checks["messages"]["hint"] is None
+        assert checks["messages"]["error"] is None
+        assert "safari_history" in checks["safari"]["needed_by"]
+        assert checks["safari"]["status"] == "missing"
+        assert "Safari" in checks["safari"]["hint"]
+        # A directory opens but can't be read.
+        assert checks["knowledge"]["status"] == "error"
+        assert checks["knowledge"]["error"]
+
+    def test_nothing_to_tell_by(self, paths):
+        paths["messages"] = paths["safari"]
+        report = check_permissions(paths=paths)
+        assert report["ok"]
+        assert report["full_disk_access"] is None
+        # Missing files aren't refusals, so require doesn't raise for them.
+        check_permissions(require=["messages", "safari"], paths=paths)
+
+    def test_denied(self, paths):
+        if os.geteuid() == 0:
+            pytest.skip("root bypasses file permissions")
+        os.chmod(paths["messages"], 0)
+        try:
+            report = check_permissions(paths=paths)
+            with pytest.raises(PermissionDenied) as info:
+                check_permissions(require=["messages"], paths=paths)
+        finally:
+            os.chmod(paths["messages"], 0o600)
+        assert not report["ok"]
+        assert report["full_disk_access"] is False
+        messages = _checks(report)["messages"]
+        assert messages["status"] == "denied"
+        assert "Full Disk Access" in messages["hint"]
+        assert info.value.path == paths["messages"]
+        assert "Full Disk Access" in str(info.value)
+
+    def test_unknown_check(self, paths):
+        with pytest.raises(ValueError):
+            check_permissions(require=["photos"])
+        with pytest.raises(ValueError):
+            check_permissions(paths={"mail": paths["safari"]})
@@ -0,0 +1,192 @@
+"""Tests for the privacy filter engine (Rust native via PyO3)."""
+
+import json
+import pickle
+import time
+
+import pytest
+
+from snoopy._native import (
+    Collector,
+    FuzzyIndex,
+    ParserConfig,
+    PrivacyFilter,
+    content_hash,
+    export_training_data,
+    infer_git_activity,
+    infer_title,
+    narrate_session,
+    parse_transcript,
+    permission_requests,
+    set_privacy_filter,
+    tool_failure_stats,
+    transcript_keywords,
+)
+
+
+def _user(text, cwd):
+    return {"type": "user", "timestamp": "2026-02-25T10:00:00Z", "cwd": cwd,
+            "message": {"content": text}}
+
+
+class TestPrivacyFilter:
+    def test_deny_wins_and_allow_restricts(self):
+        f = PrivacyFilter(allow_apps=["Safari", "Slack"], deny_apps=["slack"])
+        assert f.apply({"app_name": "safari"}) is not None
+        assert f.apply({"app_name": "Slack"}) is None
+        assert f.apply({"app_name": "Zoom"}) is None
+        assert f.apply({"timestamp": 1.0}) == {"timestamp": 1.0}
+
+    def test_domains_and_projects(self):
+        f = PrivacyFilter(deny_domains=["bank.com"], deny_projects=["/Users/me/secret"])
+        assert f.apply({"url": "https://www.bank.com/login"}) is None
+        assert f.apply({"url": "https://notbank.com/"}) is not None
+        assert f.apply({"project_path": "/Users/me/secret/api"}) is None
+        assert f.apply({"project_path": "/Users/me/secrets"}) is not None
+
+    def test_content_deny_and_redact(self):
+        f = PrivacyFilter(deny_content=[r"(?i)password"], redact_content=[r"\d{3}-\d{2}-\d{4}"])
+        events = [
+            {"contact": "+1555", "content_preview": "my password is hunter2"},
+            {"contact": "+1555", "content_preview": "ssn 123-45-6789 ok"},
+        ]
+        assert f.filter(events) == [{"contact": "+1555", "content_preview": "ssn [REDACTED] ok"}]
+        assert events[1]["content_preview"] == "ssn 123-45-6789 ok"
+
+    def test_invalid_regex(self):
+        with pytest.raises(ValueError):
+            PrivacyFilter(deny_content=["("])
+
+
+    def test_pickle_and_json_round_trip(self):
+        f = PrivacyFilter(deny_apps=["Slack"], allow_projects=["/src/"],
+                          deny_content=["secret"], redact_content=[r"sk-\w+"])
+        event = {"app": "Terminal", "project_path": "/src", "text": "key sk-9 here"}
+        for copy in (pickle.loads(pickle.dumps(f)), PrivacyFilter.from_json(f.to_json())):
+            assert copy.apply({"app": "slack"}) is None
+            assert copy.apply({"text": "top secret"}) is None
+            assert copy.apply(dict(event))["text"] == "key [REDACTED] here"
+        assert json.loads(f.to_json())["deny_apps"] == ["slack"]
+        with pytest.raises(ValueError):
+            PrivacyFilter.from_json('{"deny_everything": true}')
+
+
+class TestGlobalFilter:
+    def test_parse_transcript_applies_global_filter(self, tmp_path, write_transcript):
+        secret = tmp_path / "secret" / "s.jsonl"
+        public = tmp_path / "app" / "s.jsonl"
+        write_transcript(secret, [_user("work on api", "/a")])
+        write_transcript(public, [_user("token sk-abc123 here", "/a")])
+        set_privacy_filter(PrivacyFilter(deny_projects=[str(tmp_path / "secret")],
+                                         redact_content=[r"sk-\w+"]))
+        try:
+            assert parse_transcript(str(secret))[0] == []
+            events, _ = parse_transcript(str(public))
+        finally:
+            set_privacy_filter(None)
+        assert [e["content_preview"] for e in events] == ["token [REDACTED] here"]
+        assert len(parse_transcript(str(secret))[0]) == 1
+
+    def test_raw_and_hash_are_filtered(self, tmp_path, write_transcript):
+        path = tmp_path / "app" / "s.jsonl"
+        long = "x" * 40 + " token sk-abc123"
+        write_transcript(path, [_user(long, "/a"), _user("y" * 40 + " a secret", "/a")])
+        config = ParserConfig(preview_len=20, content_hash=True, include_raw=True)
+        set_privacy_filter(PrivacyFilter(deny_content=["secret"], redact_content=[r"sk-\w+"]))
+        try:
+            (event,), _ = parse_transcript(str(path), config=config)
+        finally:
+            set_privacy_filter(None)
+        assert "sk-abc123" not in event["raw"]
+        assert "[REDACTED]" in event["raw"]
+        assert event["content_hash"] == content_hash("x" * 40 + " token [REDACTED]")
+
+    def test_filters_see_text_past_preview_len(self, tmp_path, write_transcript):
+        path = tmp_path / "app" / "s.jsonl"
+        key = "sk-" + "A" * 40
+        write_transcript(path, [_user("my key is " + key, "/a"),
+                                 _user("z" * 40 + " a secret", "/a")])
+        config = ParserConfig(preview_len=30, privacy=PrivacyFilter(
+            deny_content=["secret"], redact_content=[r"sk-[A-Za-z0-9]{40}"]))
+        (event,), _ = parse_transcript(str(path), config=config)
+        assert event["content_preview"] == "my key is [REDACTED]"
+        set_privacy_filter(PrivacyFilter(redact_content=[r"sk-[A-Za-z0-9]{40}"]))
+        try:
+            events, _ = parse_transcript(str(path), preview_len=30)
+        finally:
+            set_privacy_filter(None)
+        assert events[0]["content_preview"] == "my key is [REDACTED]"
+
+    def test_collector_applies_global_filter(self, tmp_path, write_transcript):
+        path = tmp_path / "-Users-me-app" / "s.jsonl"
+        write_transcript(path, [])
+        collector = Collector(["claude"], interval=0.05, projects_dir=str(tmp_path))
+        set_privacy_filter(PrivacyFilter(deny_content=["secret"]))
+        collector.start()
+        try:
+            time.sleep(0.2)
+            with open(path, "a") as f:
+                f.write(json.dumps(_user("a secret plan", "/a")) + "\n")
+                f.write(json.dumps(_user("a public plan", "/a")) + "\n")
+            events = []
+            deadline = time.time() + 5
+            while not events and time.time() < deadline:
+                events = collector.drain()
+                time.sleep(0.02)
+        finally:
+            collector.stop()
+            set_privacy_filter(None)
+        assert [e["content_preview"] for e in events] == ["a public plan"]
+
+
+def _session(cwd, token):
+    ts = "2026-02-25T10:00:00Z"
+    command = f"git commit -m 'add {token} support'"
+    return [
+        _user(f"wire up the {token} billing parser please", cwd),
+        {"type": "assistant", "timestamp": ts, "cwd": cwd, "message": {"content": [
+            {"type": "text", "text": f"Committing {token} now."},
+            {"type": "tool_use", "id": "t1", "name": "Bash", "input": {"command": command}}]}},
+        {"type": "user", "timestamp": ts, "cwd": cwd, "message": {"content": [
+            {"type": "tool_result", "tool_use_id": "t1", "is_error": True,
+             "content": "[main abc1234] add support"}]}},
+    ]
+
+
+class TestDerivedText:
+    """Functions that return text derived from transcripts apply the global filter."""
+
+    def test_filter_applies(self, tmp_path, write_transcript):
+        public = tmp_path / "-app" / "s1.jsonl"
+        secret = tmp_path / "-secret" / "s2.jsonl"
+        write_transcript(public, _session("/app", "sk-abc123"))
+        write_transcript(secret, _session("/secret", "hidden"))
+        set_privacy_filter(PrivacyFilter(deny_projects=["/secret"], redact_content=[r"sk-\w+"]))
+        try:
+            assert "sk-abc123" not in narrate_session(str(public))
+            assert "[REDACTED]" in infer_title(str(public))
+            assert infer_title(str(secret)) is None
+            stats = tool_failure_stats(str(tmp_path), group_by="project")
+            assert list(stats) == ["/app"]
+            assert "sk-abc123" not in stats["/app"]["top_failing"][0]["command"]
+            (commit,) = infer_git_activity(str(public))
+            assert commit["message"] == "add [REDACTED] support"
+            requests = permission_requests(str(tmp_path))
+            assert [r["command"] for r in requests] == ["git commit -m 'add [REDACTED] support'"]
+            keywords = transcript_keywords(str(tmp_path), period="all")
+            assert list(keywords) == ["/app"]
+            terms = {t["term"] for t in keywords["/app"][0]["terms"]}
+            assert "billing" in terms and not terms & {"abc123", "redacted", "hidden"}
+            index = FuzzyIndex([{"content_preview": "key sk-abc123", "project_path": "/app"},
+                                {"content_preview": "key sk-def456", "project_path": "/secret"}])
+            assert len(index) == 1
+            (hit,) = index.search("key")
+            assert hit["event"]["content_preview"] == "key [REDACTED]"
+            out = tmp_path / "out.jsonl"
+            counts = export_training_data(str(tmp_path), str(out))
+        finally:
+            set_privacy_filter(None)
+        assert counts["sessions"] == 1 and counts["skipped"] == 1
+        text = out.read_text()
+        assert "sk-abc123" not in text and "hidden" not in text
+        assert "[REDACTED]" in text
@@ -0,0 +1,62 @@
+"""Tests for snapshot_processes and diff_processes (Rust native via PyO3)."""
+
+import os
+import subprocess
+import time
+
+import pytest
+
+from snoopy._native import diff_processes, snapshot_processes
+
+
+class TestSnapshotProcesses:
+    def test_includes_current_process(self):
+        processes = snapshot_processes()
+        pids = [p["pid"] for p in processes]
+        assert pids == sorted(pids)
+        me = next(p for p in processes if p["pid"] == os.getpid())
+        assert me["source"] == "process"
+        assert me["parent"] == os.getppid()
+        assert me["argv"]
+        assert me["exe"]
+        assert me["start_time"] == pytest.approx(time.time(), abs=3600)
+
+    def test_diff_sees_launch_and_exit(self):
+        before = snapshot_processes()
+        child = subprocess.Popen(["sleep", "30"])
+        try:
+            during = snapshot_processes()
+        finally:
+            child.kill()
+            child.wait()
+        after = snapshot_processes()
+
+        launched = [e for e in diff_processes(before, during) if e["pid"] == child.pid]
+        assert [(e["kind"], e["name"]) for e in launched] == [("launch", "sleep")]
+        assert launched[0]["argv"] == ["sleep", "30"]
+        assert launched[0]["parent"] == os.getpid()
+        assert launched[0]["timestamp"] == launched[0]["start_time"]
+
+        exited = [e for e in diff_processes(during, after) if e["pid"] == child.pid]
+        assert [e["kind"] for e in exited] == ["exit"]
+        assert exited[0]["timestamp"] == pytest.approx(time.time(), abs=5)
+
+
+class TestDiffProcesses:
+    def test_pid_reuse_is_exit_plus_launch(self):
+        before = [{"pid": 10, "name": "a", "start_time": 100.0},
+                  {"pid": 11, "name": "b", "start_time": 100.0}]
+        after = [{"pid": 10, "name": "c", "start_time": 200.0},
+                 {"pid": 11, "name": "b", "start_time": 100.0},
+                 {"pid": 5, "name": "d", "start_time": 150.0}]
+        events = diff_processes(before, after)
+        assert [(e["kind"], e["name"], e["timestamp"]) for e in events[1:]] == [
+            ("launch", "d", 150.0),
+            ("launch", "c", 200.0),
+        ]
+        assert (events[0]["kind"], events[0]["name"]) == ("exit", "a")
+        assert before[0].get("kind") is None
+
+    def test_requires_pid(self):
+        with pytest.raises(ValueError):
+            diff_processes([{"name": "x"}], [])
@@ -0,0 +1,81 @@
+"""Tests for reminders_events (Rust native via PyO3)."""
+
+import sqlite3
+
+import pytest
+
+from snoopy._native import reminders_events
+
+COCOA_EPOCH = 978307200
+T0 = 1_700_000_000.0
+
+
+def _reminders_db(path, reminders, lists=True):
+    conn = sqlite3.connect(path)
+    conn.executescript("""
+        CREATE TABLE ZREMCDREMINDER (Z_PK INTEGER PRIMARY KEY, ZCKIDENTIFIER TEXT,
+            ZTITLE TEXT, ZNOTES TEXT, ZLIST INTEGER, ZCREATIONDATE REAL, ZCOMPLETED INTEGER,
+            ZCOMPLETIONDATE REAL, ZDUEDATE REAL, ZPRIORITY INTEGER, ZFLAGGED INTEGER,
+            ZMARKEDFORDELETION INTEGER);
+    """)
+    if lists:
+        conn.executescript("""
+            CREATE TABLE ZREMCDBASELIST (Z_PK INTEGER PRIMARY KEY, ZNAME TEXT);
+            INSERT INTO ZREMCDBASELIST VALUES (1, 'Groceries');
+        """)
+    for uid, title, created, completed, deleted in reminders:
+        conn.execute(
+            "INSERT INTO ZREMCDREMINDER (ZCKIDENTIFIER, ZTITLE, ZNOTES, ZLIST, ZCREATIONDATE,"
+            " ZCOMPLETED, ZCOMPLETIONDATE, ZDUEDATE, ZPRIORITY, ZFLAGGED, ZMARKEDFORDELETION)"
+            " VALUES (?, ?, 'oat', 1, ?, ?, ?, ?, 1, 1, ?)",
+            (uid, title, created - COCOA_EPOCH, completed is not None,
+             None if completed is None else completed - COCOA_EPOCH,
+             created + 86400 - COCOA_EPOCH, deleted),
+        )
+    conn.commit()
+    conn.close()
+
+
+class TestRemindersEvents:
+    def test_created_and_completed(self, tmp_path):
+        db = tmp_path / "Data-1.sqlite"
+        _reminders_db(db, [
+            ("R1", "Buy milk", T0, T0 + 3600, 0),
+            ("R2", "Call dentist", T0 + 60, None, 0),
+            ("R3", "Deleted", T0, None, 1),
+        ])
+        events = reminders_events(db)
+        assert [(e["kind"], e["title"]) for e in events] == [
+            ("created", "Buy milk"), ("created", "Call dentist"), ("completed", "Buy milk"),
+        ]
+        done = events[2]
+        assert done["source"] == "reminders"
+        assert done["reminder_id"] == "R1"
+        assert done["timestamp"] == pytest.approx(T0 + 3600)
+        assert done["created"] == pytest.approx(T0)
+        assert done["due"] == pytest.approx(T0 + 86400)
+        assert done["list"] == "Groceries"
+        assert done["notes"] == "oat"
+        assert done["priority"] == 1
+        assert done["flagged"] is True
+        assert done["completed"] is True
+        assert events[1]["completed"] is False
+
+    def test_since(self, tmp_path):
+        db = tmp_path / "Data-1.sqlite"
+        _reminders_db(db, [("R1", "Buy milk", T0, T0 + 3600, 0)])
+        events = reminders_events(db, since=T0 + 1)
+        assert [e["kind"] for e in events] == ["completed"]
+
+    def test_stores_directory(self, tmp_path):
+        _reminders_db(tmp_path / "Data-a.sqlite", [("R1", "Work task", T0 + 10, None, 0)])
+        _reminders_db(tmp_path / "Data-b.sqlite", [("R2", "Home task", T0, None, 0)],
+                      lists=False)
+        (tmp_path / "Other.sqlite").write_bytes(b"")
+        events = reminders_events(tmp_path)
+        assert [e["title"] for e in events] == ["Home task", "Work task"]
+        assert events[0]["list"] is None
+
+    def test_missing_store(self, tmp_path):
+        with pytest.raises(IOError):
+            reminders_events(tmp_path / "Data-missing.sqlite")
@@ -0,0 +1,76 @@
+"""Tests for timed transcript replay (Rust native via PyO3)."""
+
+import signal
+import time
+
+import pytest
+
+from snoopy._native import replay_transcript
+
+
+def _user(ts, text):
+    return {"type": "user", "timestamp": ts, "message": {"content": text}}
+
+
+class TestReplayTranscript:
+    def test_yields_events_with_scaled_timing(self, tmp_path, write_transcript):
+        transcript = tmp_path / "s.jsonl"
+        write_transcript(transcript, [
+            _user("2026-02-25T10:00:00Z", "one"),
+            _user("2026-02-25T10:00:02Z", "two"),
+        ])
+
+        replay = replay_transcript(str(transcript), speed=10.0)
+        assert len(replay) == 2
+        start = time.monotonic()
+        events = list(replay)
+        elapsed = time.monotonic() - start
+
+        assert [e["content_preview"] for e in events] == ["one", "two"]
+        assert 0.15 <= elapsed < 1.0
+
+    def test_max_delay_caps_gaps(self, tmp_path, write_transcript):
+        transcript = tmp_path / "s.jsonl"
+        write_transcript(transcript, [
+            _user("2026-02-25T10:00:00Z", "one"),
+            _user("2026-02-25T12:00:00Z", "two"),
+        ])
+
+        start = time.monotonic()
+        list(replay_transcript(str(transcript), max_delay=0.05))
+        assert time.monotonic() - start < 1.0
+
+    def test_tiny_speed_waits_until_interrupted(self, tmp_path, write_transcript):
+        transcript = tmp_path / "s.jsonl"
+        write_transcript(transcript, [
+            _user("2026-02-25T10:00:00Z", "one"),
+            _user("2026-02-25T10:00:02Z", "two"),
+        ])
+
+        def interrupt(signum, frame):
+            raise TimeoutError
+
+        replay = replay_transcript(str(transcript), speed=1e-300)
+        assert next(replay)["content_preview"] == "one"
+        previous = signal.signal(signal.SIGALRM, interrupt)
+        signal.setitimer(signal.ITIMER_REAL, 0.2)
+        try:
+            with pytest.raises(TimeoutError):
+                next(replay)
+        finally:
+            signal.setitimer(signal.ITIMER_REAL, 0)
+            signal.signal(signal.SIGALRM, previous)
+
+    def test_rejects_non_positive_speed(self, tmp_path):
+        transcript = tmp_path / "s.jsonl"
+        transcript.write_text("")
+        with pytest.raises(ValueError):
+            replay_transcript(str(transcript), speed=0)
+
+    def test_rejects_negative_max_delay(self, tmp_path):
+        transcript = tmp_path / "s.jsonl"
+        transcript.write_text("")
+        with pytest.raises(ValueError):
+            replay_transcript(str(transcript), max_delay=-1)
+        with pytest.raises(ValueError):
+            replay_transcript(str(transcript), max_delay=float("nan"))
@@ -0,0 +1,123 @@
+"""Tests for the daily Markdown report (Rust native via PyO3)."""
+
+import datetime
+import json
+
+import pytest
+
+from snoopy._native import generate_daily_report
+from snoopy.db import Database
+
+DAY = datetime.date(2024, 3, 1)
+MIDNIGHT = 1709251200.0  # 2024-03-01T00:00:00Z
+
+
+def _entry(kind, at, session="s1", **extra):
+    return {"type": kind, "timestamp": f"2024-03-01T{at}Z", "sessionId": session,
+            "cwd": "/Users/me/src/app", **extra}
+
+
+def _assistant(at, msg_id, tools=0, **extra):
+    content = [{"type": "text", "text": "ok"}]
+    content += [{"type": "tool_use", "id": f"t{i}", "name": "Bash", "input": {}}
+                for i in range(tools)]
+    message = {"id": msg_id, "role": "assistant", "model": "claude-sonnet-4-5",
+               "content": content, "usage": {"input_tokens": 1000, "output_tokens": 1000}}
+    return _entry("assistant", at, message=message, **extra)
+
+
+@pytest.fixture
+def claude_root(tmp_path):
+    project = tmp_path / "projects" / "-Users-me-src-app"
+    project.mkdir(parents=True)
+    entries = [
+        _entry("user", "09:00:00", message={"role": "user", "content": "fix the login test"}),
+        _assistant("09:01:00", "m1", tools=2),
+        _assistant("09:01:00", "m1", tools=1),
+        _assistant("09:10:00", "m2"),
+        # The previous day isn't counted.
+        {**_assistant("09:00:00", "m0"), "timestamp": "2024-02-29T09:00:00Z"},
+    ]
+    (project / "s1.jsonl").write_text("\n".join(json.dumps(e) for e in entries) + "\n")
+    agent = [_assistant("09:05:00", "m3", tools=1, isSidechain=True)]
+    (project / "agent-abc.jsonl").write_text(json.dumps(agent[0]) + "\n")
+    return tmp_path / "projects"
+
+
+@pytest.fixture
+def db_path(tmp_path):
+    path = tmp_path / "snoopy.db"
+    with Database(path=path) as db:
+        db.batch_insert(
+            "message_events",
+            ["timestamp", "contact", "is_from_me", "content_preview", "chat_name"],
+            [
+                (MIDNIGHT + 3600 * 10, "Alice", 0, "lunch?", None),
+                (MIDNIGHT + 3600 * 10 + 60, "Alice", 1, "sure", None),
+                (MIDNIGHT + 3600 * 18, "Alice", 0, "running late", None),
+                (MIDNIGHT + 3600 * 12, "Bob", 0, "hi", "Team"),
+                (MIDNIGHT - 3600, "Carol", 0, "yesterday", None),
+            ],
+        )
+        db.batch_insert(
+            "network_events",
+            ["timestamp", "process_name", "protocol", "remote_address", "remote_port"],
+            [
+                (MIDNIGHT - 86400, "curl", "TCP", "1.1.1.1", 443),
+                (MIDNIGHT + 3600, "curl", "TCP", "1.1.1.1", 443),
+                (MIDNIGHT + 7200, "ssh", "TCP", "203.0.113.7", 22),
+                (MIDNIGHT + 7300, "ssh", "TCP", "203.0.113.7", 22),
+            ],
+        )
+        db.batch_insert(
+            "browser_events",
+            ["timestamp", "url", "title", "browser", "visit_duration_s"],
+            [
+                (MIDNIGHT + 100, "https://www.example.com/a", "A", "safari", 60.0),
+                (MIDNIGHT + 200, "https://example.com/b", "B", "safari", 120.0),
+                (MIDNIGHT + 300, "https://docs.rs/x", "X", "safari", None),
+            ],
+        )
+    return path
+
+
+class TestGenerateDailyReport:
+    def test_full_report(self, claude_root, db_path):
+        report = generate_daily_report(DAY, db_path=db_path, claude_root=claude_root,
+                                       utc_offset=0)
+        lines = report.splitlines()
+        assert lines[0] == "# Daily report: Friday, March 1, 2024"
+        assert "across 1 project" in lines[2]
+        assert "4 messages in 2 conversations" in lines[2]
+        assert "1 new network destination" in lines[2]
+        assert "3 page visits" in lines[2]
+        assert [l for l in lines if l.startswith("## ")] == [
+            "## Top projects", "## Agent sessions", "## Notable messages",
+            "## New network destinations", "## Top sites",
+        ]
+        # Split messages are costed once; the subagent folds into its session.
+        assert "| app | 10m | 1 | 4 | $0.05 |" in report
+        assert "| 09:00–09:10 | Fix the login test | app | 10m |" in report
+        assert '- **Alice**: 3 messages (1 sent), last at 18:00: "running late"' in report
+        assert "- **Team**: 1 message (0 sent)" in report
+        assert "Carol" not in report
+        assert "- 02:00 `ssh` → 203.0.113.7:22" in report
+        assert "1.1.1.1" not in report
+        assert "| example.com | 2 | 3m |" in report
+        assert "| docs.rs | 1 | 0s |" in report
+
+    def test_sources_select_sections(self, claude_root, db_path):
+        report = generate_daily_report(DAY, ["browser", "messages"], db_path=db_path,
+                                       claude_root=claude_root, utc_offset=0)
+        headings = [l for l in report.splitlines() if l.startswith("## ")]
+        assert headings == ["## Top sites", "## Notable messages"]
+
+    def test_empty_day(self, tmp_path):
+        report = generate_daily_report(DAY, db_path=tmp_path / "missing.db",
+                                       claude_root=tmp_path, utc_offset=0)
+        assert "No activity recorded." in report
+        assert report.count("_Nothing recorded._") == 5
+
+    def test_unknown_source(self, tmp_path):
+        with pytest.raises(ValueError, match="unknown report source"):
+            generate_daily_report(DAY, ["email"], db_path=tmp_path / "x.db")
@@ -0,0 +1,74 @@
+"""Tests for ResourceLimits and the resources Collector source (Rust native via PyO3)."""
+
+import os
+import subprocess
+import sys
+import time
+
+import pytest
+
+from snoopy._native import Collector, ResourceLimits
+
+# Allocates ~64 MiB and holds it until killed.
+HOG = """
+import time
+block = bytearray(64 * 1024 * 1024)
+for i in range(0, len(block), 4096):
+    block[i] = 1
+while True:
+    time.sleep(0.02)
+"""
+
+
+def _wait_for(collector, predicate, timeout=10.0):
+    events = []
+    deadline = time.time() + timeout
+    while not predicate(events) and time.time() < deadline:
+        events.extend(collector.drain())
+        time.sleep(0.02)
+    return events
+
+
+class TestResourceLimits:
+    def test_requires_a_threshold(self):
+        with pytest.raises(ValueError):
+            ResourceLimits(["node"])
+        with pytest.raises(ValueError):
+            ResourceLimits(rss_mb=1, sustain=-1)
+
+    def test_exceeded_then_recovered(self):
+        # Linux reports the 15-character comm name.
+        name = os.path.basename(sys.executable)[:15]
+        limits = ResourceLimits([name], rss_mb=48, sustain=0.2)
+        collector = Collector(["resources"], interval=0.05, resource_limits=limits)
+        hog = subprocess.Popen([sys.executable, "-c", HOG])
+        collector.start()
+        try:
+            exceeded = _wait_for(
+                collector, lambda evs: any(e["pid"] == hog.pid for e in evs))
+            # Killed while still over the limit, so it recovers by exiting.
+            hog.kill()
+            hog.wait()
+            recovered = _wait_for(
+                collector, lambda evs: any(e["pid"] == hog.pid for e in evs))
+        finally:
+            collector.stop()
+            hog.kill()
+        exceeded = [e for e in exceeded if e["pid"] == hog.pid]
+        assert [(e["kind"], e["metric"]) for e in exceeded] == [("exceeded", "rss")]
+        assert exceeded[0]["source"] == "resources"
+        assert exceeded[0]["value"] > 48 * 1024 * 1024
+        assert exceeded[0]["threshold"] == 48 * 1024 * 1024
+        assert exceeded[0]["duration"] >= 0.2
+        recovered = [e for e in recovered if e["pid"] == hog.pid]
+        assert [e["kind"] for e in recovered] == ["recovered"]
+        assert recovered[0]["value"] is None
+        assert recovered[0]["peak"] >= exceeded[0]["value"]
+
+    def test_unwatched_processes_ignored(self):
+        limits = ResourceLimits(["no-such-process"], cpu_percent=0, rss_mb=0, sustain=0)
+        collector = Collector(["resources"], interval=0.05, resource_limits=limits)
+        collector.start()
+        time.sleep(0.3)
+        collector.stop()
+        assert collector.drain() == []
@@ -0,0 +1,53 @@
+"""Tests for per-project transcript rollups (Rust native via PyO3)."""
+
+from snoopy._native import project_rollup
+
+
+def _assistant(ts, msg_id, blocks, usage=None):
+    msg = {"id": msg_id, "model": "claude-sonnet-4", "role": "assistant", "content": blocks}
+    if usage:
+        msg["usage"] = usage
+    return {"type": "assistant", "timestamp": ts, "message": msg}
+
+
+class TestProjectRollup:
+    def test_aggregates_sessions_by_project(self, tmp_path, write_transcript):
+        usage = {"input_tokens": 1000, "output_tokens": 500}
+        write_transcript(tmp_path / "-Users-me-app" / "s1.jsonl", [
+            {"type": "user", "timestamp": "2026-02-25T10:00:00Z", "cwd": "/Users/me/app",
+             "message": {"content": "fix it"}},
+            _assistant("2026-02-25T10:01:00Z", "m1", [
+                {"type": "tool_use", "name": "Read", "input": {"file_path": "/Users/me/app/a.py"}},
+            ], usage),
+            # Same message id repeated for the next block — usage must not double count.
+            _assistant("2026-02-25T10:01:00Z", "m1", [
+                {"type": "tool_use", "name": "Edit", "input": {"file_path": "/Users/me/app/a.py"}},
+            ], usage),
+        ])
+        write_transcript(tmp_path / "-Users-me-app" / "s2.jsonl", [
+            {"type": "user", "timestamp": "2026-02-26T09:00:00Z", "message": {"content": "hi"}},
+            _assistant("2026-02-26T09:00:30Z", "m2", [
+                {"type": "tool_use", "name": "Bash", "input": {"command": "ls"}},
+            ], usage),
+        ])
+
+        rollup = project_rollup(str(tmp_path))
+
+        assert list(rollup) == ["/Users/me/app"]
+        stats = rollup["/Users/me/app"]
+        assert stats["sessions"] == 2
+        assert stats["active_seconds"] == 90.0
+        assert stats["input_tokens"] == 2000
+        assert stats["output_tokens"] == 1000
+        assert stats["tools"] == {"Read": 1, "Edit": 1, "Bash": 1}
+        assert stats["files_touched"] == ["/Users/me/app/a.py"]
+        assert stats["cost_usd"] > 0
+
+    def test_decodes_project_dir_without_cwd(self, tmp_path, write_transcript):
+        write_transcript(tmp_path / "-tmp-proj" / "s.jsonl", [
+            {"type": "user", "timestamp": "2026-02-25T10:00:00Z", "message": {"content": "x"}},
+        ])
+        assert list(project_rollup(str(tmp_path))) == ["/tmp/proj"]
+
+    def test_missing_root(self, tmp_path):
+        assert project_rollup(str(tmp_path / "nope")) == {}
@@ -0,0 +1,141 @@
+"""Tests for perceptual_hash and the screenshot Collector source (Rust native via PyO3)."""
+
+import os
+import struct
+import sys
+import time
+import zlib
+
+import pytest
+
+from snoopy._native import (
+    Collector,
+    PrivacyFilter,
+    ScreenshotOptions,
+    perceptual_hash,
+    set_privacy_filter,
+)
+
+
+def _write_png(path, width, height, pixel):
+    """An 8-bit RGB PNG where pixel(x, y) gives each (r, g, b)."""
+    def chunk(kind, data):
+        body = kind + data
+        return struct.pack(">I", len(data)) + body + struct.pack(">I", zlib.crc32(body))
+
+    rows = b"".join(
+        b"\x00" + b"".join(bytes(pixel(x, y)) for x in range(width)) for y in range(height)
+    )
+    header = struct.pack(">IIBBBBB", width, height, 8, 2, 0, 0, 0)
+    path.write_bytes(b"\x89PNG\r\n\x1a\n" + chunk(b"IHDR", header)
+                     + chunk(b"IDAT", zlib.compress(rows)) + chunk(b"IEND", b""))
+
+
+def _gradient(x, y):
+    return (x * 4 % 256, y * 4 % 256, 128)
+
+
+def _stripes(x, y):
+    return (255, 255, 255) if (x // 8) % 2 else (0, 0, 0)
+
+
+def _bits(a, b):
+    return bin(int(a, 16) ^ int(b, 16)).count("1")
+
+
+class TestPerceptualHash:
+    def test_similar_images_hash_close(self, tmp_path):
+        _write_png(tmp_path / "a.png", 64, 48, _gradient)
+        _write_png(tmp_path / "b.png", 64, 48, lambda x, y: (255, 0, 0) if (x, y) == (3, 3)
+                   else _gradient(x, y))
+        _write_png(tmp_path / "c.png", 64, 48, _stripes)
+        a, b, c = (perceptual_hash(tmp_path / n) for n in ("a.png", "b.png", "c.png"))
+        assert len(a) == 16
+        assert _bits(a, b) <= 2
+        assert _bits(a, c) > 10
+
+    def test_errors(self, tmp_path):
+        with pytest.raises(IOError):
+            perceptual_hash(tmp_path / "missing.png")
+        (tmp_path / "bad.png").write_bytes(b"not a png")
+        with pytest.raises(ValueError):
+            perceptual_hash(tmp_path / "bad.png")
+
+
+class TestScreenshotOptions:
+    def test_validation(self, tmp_path):
+        with pytest.raises(ValueError):
+            ScreenshotOptions(tmp_path, threshold=64)
+        with pytest.raises(ValueError):
+            ScreenshotOptions(tmp_path, max_width=0)
+        with pytest.raises(ValueError):
+            Collector(["screenshot"])
+
+
+def _fake_screen(tmp_path, monkeypatch, app="Terminal"):
+    """Fake import and xprop commands showing screen.png with app in front."""
+    screen = tmp_path / "screen.png"
+    _write_png(screen, 64, 48, _gradient)
+    bin_dir = tmp_path / "bin"
+    bin_dir.mkdir()
+    script = bin_dir / "import"
+    script.write_text(f'#!/bin/sh\ncat "{screen}"\n')
+    script.chmod(0o755)
+    script = bin_dir / "xprop"
+    script.write_text(
+        "#!/bin/sh\n"
+        'if [ "$1" = "-root" ]; then\n'
+        '  echo "_NET_ACTIVE_WINDOW(WINDOW): window id # 0x3a00007"\n'
+        "else\n"
+        f"  echo 'WM_CLASS(STRING) = \"term\", \"{app}\"'\n"
+        "fi\n"
+    )
+    script.chmod(0o755)
+    monkeypatch.setenv("PATH", f"{bin_dir}{os.pathsep}{os.environ['PATH']}")
+    monkeypatch.delenv("WAYLAND_DISPLAY", raising=False)
+    return screen
+
+
+@pytest.mark.skipif(sys.platform == "darwin", reason="uses the ImageMagick import path")
+class TestScreenshotCollector:
+    def test_saves_only_changed_frames(self, tmp_path, monkeypatch):
+        screen = _fake_screen(tmp_path, monkeypatch)
+
+        frames = tmp_path / "frames"
+        options = ScreenshotOptions(frames, max_width=32)
+        collector = Collector(["screenshot"], interval=0.05, screenshots=options)
+        collector.start()
+        try:
+            time.sleep(0.4)
+            first = collector.drain()
+            _write_png(screen, 64, 48, _stripes)
+            time.sleep(0.4)
+            second = collector.drain()
+        finally:
+            collector.stop()
+
+        assert len(first) == 1
+        assert (first[0]["width"], first[0]["height"]) == (32, 24)
+        assert first[0]["distance"] is None
+        assert first[0]["app"] == "Terminal"
+        assert len(second) == 1
+        assert second[0]["distance"] > 6
+        assert sorted(os.listdir(frames)) == sorted(
+            os.path.basename(e["path"]) for e in first + second
+        )
+        assert perceptual_hash(second[0]["path"]) == second[0]["phash"]
+
+    def test_denied_app_is_not_captured(self, tmp_path, monkeypatch):
+        _fake_screen(tmp_path, monkeypatch, app="Signal")
+        frames = tmp_path / "frames"
+        set_privacy_filter(PrivacyFilter(deny_apps=["Signal"]))
+        collector = Collector(["screenshot"], interval=0.05,
+                              screenshots=ScreenshotOptions(frames))
+        collector.start()
+        try:
+            time.sleep(0.3)
+        finally:
+            collector.stop()
+            set_privacy_filter(None)
+        assert collector.drain() == []
+        assert not frames.exists()
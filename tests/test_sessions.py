@@ -0,0 +1,31 @@
+"""Tests for gap-based sessionization (Rust native via PyO3)."""
+
+from snoopy._native import sessionize
+
+
+class TestSessionize:
+    def test_splits_on_gap(self):
+        events = [{"timestamp": t} for t in (0.0, 60.0, 120.0, 1000.0, 1030.0)]
+        sessions = sessionize(events, gap_seconds=300)
+
+        assert len(sessions) == 2
+        assert sessions[0]["start"] == 0.0
+        assert sessions[0]["end"] == 120.0
+        assert sessions[0]["duration"] == 120.0
+        assert sessions[0]["event_count"] == 3
+        assert sessions[1]["event_count"] == 2
+        assert sessions[1]["duration"] == 30.0
+
+    def test_accepts_bare_timestamps(self):
+        sessions = sessionize([10.0, 20.0, 30.0], gap_seconds=5)
+        assert len(sessions) == 3
+        assert all(s["duration"] == 0.0 for s in sessions)
+
+    def test_unsorted_input(self):
+        sessions = sessionize([100.0, 0.0, 50.0], gap_seconds=60)
+        assert len(sessions) == 1
+        assert sessions[0]["start"] == 0.0
+        assert sessions[0]["end"] == 100.0
+
+    def test_empty(self):
+        assert sessionize([]) == []
@@ -0,0 +1,126 @@
+"""Tests for parse_shell_history (Rust native via PyO3)."""
+
+import pytest
+
+from snoopy._native import PrivacyFilter, parse_shell_history, set_privacy_filter
+
+
+class TestZshHistory:
+    def test_extended_history(self, tmp_path):
+        path = tmp_path / ".zsh_history"
+        path.write_text(": 1700000000:3;git status\n: 1700000010:0;ls -la\n")
+        events, offset = parse_shell_history(str(path))
+        assert [e["command"] for e in events] == ["git status", "ls -la"]
+        assert events[0]["timestamp"] == 1700000000
+        assert events[0]["duration"] == 3
+        assert events[0]["shell"] == "zsh"
+        assert events[0]["source"] == "shell"
+        assert offset == path.stat().st_size
+
+    def test_multiline_and_metafied(self, tmp_path):
+        path = tmp_path / ".zsh_history"
+        # "ă" is c4 83 in UTF-8; zsh stores the 0x83 byte as 83 a3.
+        path.write_bytes(
+            b": 1700000000:0;for f in *; do\\\n  echo $f\\\ndone\n"
+            b": 1700000005:0;echo \xc4\x83\xa3\n"
+        )
+        events, _ = parse_shell_history(str(path))
+        assert events[0]["command"] == "for f in *; do\n  echo $f\ndone"
+        assert events[1]["command"] == "echo ă"
+
+    def test_incremental_leaves_unfinished_continuation(self, tmp_path):
+        path = tmp_path / ".zsh_history"
+        path.write_bytes(b": 1700000000:0;one\n: 1700000001:0;two \\\n")
+        events, offset = parse_shell_history(str(path))
+        assert [e["command"] for e in events] == ["one"]
+        with open(path, "ab") as f:
+            f.write(b"  more\n: 1700000002:0;three\n")
+        events, offset = parse_shell_history(str(path), since_offset=offset)
+        assert [e["command"] for e in events] == ["two \n  more", "three"]
+        assert offset == path.stat().st_size
+
+
+class TestBashHistory:
+    def test_histtimeformat_entries(self, tmp_path):
+        path = tmp_path / ".bash_history"
+        path.write_text("#1700000000\nmake\n#1700000020\ncat <<EOF\nhi\nEOF\n")
+        events, _ = parse_shell_history(str(path))
+        assert [(e["timestamp"], e["command"]) for e in events] == [
+            (1700000000, "make"),
+            (1700000020, "cat <<EOF\nhi\nEOF"),
+        ]
+        assert events[0]["duration"] is None
+
+    def test_incremental_leaves_trailing_timestamp(self, tmp_path):
+        path = tmp_path / ".bash_history"
+        path.write_text("#1700000000\nmake\n#1700000020\n")
+        events, offset = parse_shell_history(str(path))
+        assert [e["command"] for e in events] == ["make"]
+        with open(path, "a") as f:
+            f.write("make test\n")
+        events, offset = parse_shell_history(str(path), since_offset=offset)
+        assert [(e["timestamp"], e["command"]) for e in events] == [(1700000020, "make test")]
+        assert offset == path.stat().st_size
+
+    def test_untimed_lines(self, tmp_path):
+        path = tmp_path / "history.txt"
+        path.write_text("ls\n\ncd /tmp\n")
+        events, _ = parse_shell_history(str(path), shell="bash")
+        assert [(e["timestamp"], e["command"]) for e in events] == [(None, "ls"), (None, "cd /tmp")]
+
+
+class TestFishHistory:
+    def test_yaml_entries(self, tmp_path):
+        path = tmp_path / "fish_history"
+        path.write_text(
+            "- cmd: echo a\\\\b\n  when: 1700000000\n"
+            "- cmd: printf 'x\\ny'\n  when: 1700000009\n  paths:\n    - x\n"
+        )
+        events, _ = parse_shell_history(str(path))
+        assert [(e["timestamp"], e["command"]) for e in events] == [
+            (1700000000, "echo a\\b"),
+            (1700000009, "printf 'x\ny'"),
+        ]
+        assert events[0]["shell"] == "fish"
+
+    def test_incremental_waits_for_when(self, tmp_path):
+        path = tmp_path / "fish_history"
+        path.write_text("- cmd: ls\n  when: 1700000000\n- cmd: pwd\n")
+        events, offset = parse_shell_history(str(path))
+        assert [e["command"] for e in events] == ["ls"]
+        with open(path, "a") as f:
+            f.write("  when: 1700000005\n")
+        events, offset = parse_shell_history(str(path), since_offset=offset)
+        assert [(e["timestamp"], e["command"]) for e in events] == [(1700000005, "pwd")]
+        assert offset == path.stat().st_size
+
+
+class TestShellHistoryOptions:
+    def test_detects_format_from_contents(self, tmp_path):
+        path = tmp_path / "history"
+        path.write_text(": 1700000000:0;pwd\n")
+        events, _ = parse_shell_history(str(path))
+        assert events[0]["shell"] == "zsh"
+
+    def test_truncated_file_is_reread(self, tmp_path):
+        path = tmp_path / ".zsh_history"
+        path.write_text(": 1700000000:0;pwd\n")
+        events, offset = parse_shell_history(str(path), since_offset=10_000)
+        assert [e["command"] for e in events] == ["pwd"]
+        assert offset == path.stat().st_size
+
+    def test_privacy_filter_redacts_commands(self, tmp_path):
+        path = tmp_path / ".zsh_history"
+        path.write_text(": 1700000000:0;export TOKEN=abc123\n")
+        set_privacy_filter(PrivacyFilter(redact_content=[r"TOKEN=\S+"]))
+        try:
+            events, _ = parse_shell_history(str(path))
+        finally:
+            set_privacy_filter(None)
+        assert events[0]["command"] == "export [REDACTED]"
+
+    def test_errors(self, tmp_path):
+        with pytest.raises(IOError):
+            parse_shell_history(str(tmp_path / "missing"))
+        with pytest.raises(ValueError):
+            parse_shell_history(str(tmp_path / "missing"), shell="tcsh")
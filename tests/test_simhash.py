@@ -0,0 +1,38 @@
+"""Tests for near-duplicate prompt detection (Rust native via PyO3)."""
+
+from snoopy._native import find_duplicate_prompts, prompt_fingerprint
+
+
+def _user(text, session_id="s1", ts=0.0):
+    return {"timestamp": ts, "session_id": session_id, "message_type": "user",
+            "content_preview": text}
+
+
+class TestPromptFingerprint:
+    def test_normalizes_case_and_punctuation(self):
+        assert prompt_fingerprint("Run the tests!") == prompt_fingerprint("run the   tests")
+
+    def test_empty(self):
+        assert prompt_fingerprint("") == 0
+
+
+class TestFindDuplicatePrompts:
+    def test_clusters_repeated_prompts_across_sessions(self):
+        prompt = "run the full test suite and fix any failing tests in the parser module"
+        events = [
+            _user(prompt, "s1", 1.0),
+            _user(prompt + " please", "s2", 2.0),
+            _user("write a haiku about rust lifetimes", "s3", 3.0),
+            {"timestamp": 4.0, "session_id": "s1", "message_type": "assistant_text",
+             "content_preview": prompt},
+        ]
+        clusters = find_duplicate_prompts(events, max_distance=10)
+
+        assert len(clusters) == 1
+        assert clusters[0]["count"] == 2
+        assert clusters[0]["sessions"] == ["s1", "s2"]
+        assert clusters[0]["timestamps"] == [1.0, 2.0]
+
+    def test_min_size(self):
+        events = [_user("same"), _user("same")]
+        assert find_duplicate_prompts(events, min_size=3) == []
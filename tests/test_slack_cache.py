@@ -0,0 +1,57 @@
+"""Tests for slack_messages (Rust native via PyO3)."""
+
+import json
+
+import pytest
+
+from snoopy._native import slack_messages
+
+
+def _message(ts, text, channel="C024BE91L", **extra):
+    return json.dumps({"type": "message", "user": "U12345", "ts": ts, "text": text,
+                       "channel": channel, **extra})
+
+
+def _cache_file(root, relative, chunks):
+    path = root / relative
+    path.parent.mkdir(parents=True, exist_ok=True)
+    # LevelDB framing: binary record headers and keys around the JSON values.
+    path.write_bytes(b"".join(b"\x00\x8a\x13\x01_https://app.slack.com\x00" + c.encode()
+                              for c in chunks))
+
+
+class TestSlackMessages:
+    def test_extracts_messages_from_leveldb_files(self, tmp_path):
+        _cache_file(tmp_path, "IndexedDB/https_app.slack.com_0.indexeddb.leveldb/000003.log", [
+            _message("1700000100.000200", "deploy is green",
+                     user_profile={"real_name": "Sam Lee", "display_name": "sam"}),
+            _message("1700000000.000100", "morning all", thread_ts="1699999000.000001"),
+            '{"ts":"broken',
+        ])
+        _cache_file(tmp_path, "Local Storage/leveldb/000005.ldb", [
+            _message("1700000000.000100", "morning all"),
+            json.dumps({"ts": "1700000050.000000", "type": "presence_change"}),
+        ])
+        _cache_file(tmp_path, "Cache/ignored.log", [_message("1700000200.0", "not leveldb")])
+
+        events = slack_messages(str(tmp_path))
+        assert [e["text"] for e in events] == ["morning all", "deploy is green"]
+        first, second = events
+        assert first["source"] == "slack"
+        assert first["timestamp"] == pytest.approx(1700000000.0001)
+        assert first["channel"] == "C024BE91L"
+        assert first["sender"] == "U12345"
+        assert first["thread_ts"] == "1699999000.000001"
+        assert second["sender"] == "sam"
+        assert second["user_id"] == "U12345"
+
+    def test_since(self, tmp_path):
+        _cache_file(tmp_path, "Local Storage/leveldb/000005.ldb", [
+            _message("1600000000.000000", "old"),
+            _message("1700000000.000000", "new"),
+        ])
+        assert [e["text"] for e in slack_messages(str(tmp_path), since=1650000000)] == ["new"]
+
+    def test_missing_directory_raises(self, tmp_path):
+        with pytest.raises(IOError):
+            slack_messages(str(tmp_path / "Slack"))
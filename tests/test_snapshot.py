@@ -0,0 +1,100 @@
+"""Tests for SQLite snapshots (Rust native via PyO3)."""
+
+import os
+import sqlite3
+import stat
+import subprocess
+import sys
+
+import pytest
+
+from snoopy._native import safe_snapshot
+
+_HOLD_LOCK = """
+import sqlite3, sys
+conn = sqlite3.connect(sys.argv[1], isolation_level=None)
+conn.execute("BEGIN EXCLUSIVE")
+conn.execute("INSERT INTO message (text) VALUES ('uncommitted')")
+print("locked", flush=True)
+sys.stdin.read()
+conn.rollback()
+"""
+
+
+@pytest.fixture
+def live_db(tmp_path):
+    """A WAL database with writes still in the WAL, held open by its writer."""
+    path = tmp_path / "chat.db"
+    conn = sqlite3.connect(path)
+    conn.execute("PRAGMA journal_mode=WAL")
+    conn.execute("PRAGMA wal_autocheckpoint=0")
+    conn.execute("CREATE TABLE message (id INTEGER PRIMARY KEY, text TEXT)")
+    conn.executemany("INSERT INTO message (text) VALUES (?)", [("hi",), ("there",)])
+    conn.commit()
+    yield path, conn
+    conn.close()
+
+
+def _rows(path):
+    conn = sqlite3.connect(path)
+    try:
+        return [r[0] for r in conn.execute("SELECT text FROM message ORDER BY id")]
+    finally:
+        conn.close()
+
+
+class TestSafeSnapshot:
+    def test_backup_sees_wal(self, live_db):
+        path, _ = live_db
+        assert os.path.getsize(str(path) + "-wal") > 0
+        snapshot = safe_snapshot(str(path))
+        assert snapshot.method == "backup"
+        assert snapshot.source == str(path)
+        assert _rows(snapshot.path) == ["hi", "there"]
+        assert stat.S_IMODE(os.stat(os.path.dirname(snapshot.path)).st_mode) == 0o700
+        copy = snapshot.path
+        snapshot.close()
+        assert snapshot.closed
+        assert not os.path.exists(os.path.dirname(copy))
+        snapshot.close()
+
+    def test_context_manager_and_independence(self, live_db):
+        path, conn = live_db
+        with safe_snapshot(str(path)) as snapshot:
+            conn.execute("INSERT INTO message (text) VALUES ('later')")
+            conn.commit()
+            assert _rows(snapshot.path) == ["hi", "there"]
+            copy = snapshot.path
+        assert not os.path.exists(copy)
+        assert _rows(path) == ["hi", "there", "later"]
+
+    def test_lock_falls_back_to_copy(self, tmp_path):
+        path = tmp_path / "History.db"
+        conn = sqlite3.connect(path)
+        conn.execute("CREATE TABLE message (id INTEGER PRIMARY KEY, text TEXT)")
+        conn.execute("INSERT INTO message (text) VALUES ('kept')")
+        conn.commit()
+        conn.close()
+        # POSIX locks don't block their own process, so another one holds the lock.
+        holder = subprocess.Popen([sys.executable, "-c", _HOLD_LOCK, str(path)],
+                                  stdin=subprocess.PIPE, stdout=subprocess.PIPE, text=True)
+        try:
+            assert holder.stdout.readline().strip() == "locked"
+            with safe_snapshot(str(path), timeout=0.1) as snapshot:
+                assert snapshot.method == "copy"
+                assert _rows(snapshot.path) == ["kept"]
+        finally:
+            holder.communicate("")
+
+    def test_errors(self, tmp_path):
+        with pytest.raises(OSError):
+            safe_snapshot(str(tmp_path / "missing.db"))
+        with pytest.raises(ValueError):
+            safe_snapshot(str(tmp_path / "missing.db"), timeout=-1)
+        with pytest.raises(ValueError):
+            safe_snapshot(str(tmp_path / "missing.db"), timeout=1e20)
+
+    def test_long_timeout(self, live_db):
+        path, _ = live_db
+        with safe_snapshot(str(path), timeout=1e7) as snapshot:
+            assert _rows(snapshot.path) == ["hi", "there"]
@@ -0,0 +1,69 @@
+"""Tests for recent_documents (Rust native via PyO3)."""
+
+import os
+import time
+
+import pytest
+
+from snoopy._native import recent_documents
+
+
+def _fake_mdfind(tmp_path, monkeypatch, paths, exit_code=0):
+    bin_dir = tmp_path / "bin"
+    bin_dir.mkdir()
+    out = tmp_path / "matches"
+    out.write_bytes(b"".join(str(p).encode() + b"\0" for p in paths))
+    script = bin_dir / "mdfind"
+    script.write_text(
+        "#!/bin/sh\n"
+        f'printf "%s\\n" "$@" > "{tmp_path}/args"\n'
+        f'cat "{out}"\n'
+        "echo 'index unavailable' >&2\n"
+        f"exit {exit_code}\n"
+    )
+    script.chmod(0o755)
+    monkeypatch.setenv("PATH", f"{bin_dir}{os.pathsep}{os.environ['PATH']}")
+    return tmp_path / "args"
+
+
+class TestRecentDocuments:
+    def test_stats_matches(self, tmp_path, monkeypatch):
+        docs = tmp_path / "docs"
+        docs.mkdir()
+        old = docs / "notes.txt"
+        old.write_text("old")
+        hour_ago = time.time() - 3600
+        os.utime(old, (hour_ago, hour_ago))
+        slides = docs / "Slides Final.KEY"
+        slides.write_bytes(b"x" * 42)
+        args = _fake_mdfind(tmp_path, monkeypatch, [slides, old, docs / "vanished.pdf"])
+
+        events = recent_documents(name="slides", since=time.time() - 7200, onlyin=str(docs))
+        passed = args.read_text().split("\n")
+        assert passed[:3] == ["-0", "-onlyin", str(docs)]
+        assert 'kMDItemFSName == "*slides*"cd' in passed[3]
+        assert "kMDItemFSContentChangeDate >= $time.iso(" in passed[3]
+
+        assert [e["name"] for e in events] == ["notes.txt", "Slides Final.KEY"]
+        newest = events[1]
+        assert newest["source"] == "spotlight"
+        assert newest["path"] == str(slides)
+        assert newest["extension"] == "key"
+        assert newest["size"] == 42
+        assert newest["is_dir"] is False
+        assert events[0]["timestamp"] == pytest.approx(hour_ago, abs=1)
+
+    def test_limit_keeps_newest(self, tmp_path, monkeypatch):
+        paths = []
+        for i in range(3):
+            p = tmp_path / f"doc{i}.md"
+            p.write_text(str(i))
+            os.utime(p, (time.time() - 100 + i, time.time() - 100 + i))
+            paths.append(p)
+        _fake_mdfind(tmp_path, monkeypatch, paths)
+        assert [e["name"] for e in recent_documents(limit=2)] == ["doc1.md", "doc2.md"]
+
+    def test_failure_raises(self, tmp_path, monkeypatch):
+        _fake_mdfind(tmp_path, monkeypatch, [], exit_code=1)
+        with pytest.raises(RuntimeError, match="index unavailable"):
+            recent_documents()
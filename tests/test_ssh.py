@@ -0,0 +1,117 @@
+"""Tests for SSH log parsing (Rust native via PyO3)."""
+
+import json
+
+import pytest
+
+from snoopy._native import parse_ssh_log, ssh_events
+
+AUTH_LOG = """\
+2024-03-01T09:15:00.000000+00:00 box sshd[811]: Failed none for alice from 10.0.0.5 port 52314 ssh2
+2024-03-01T09:15:02.000000+00:00 box sshd[811]: Accepted publickey for alice from 10.0.0.5 \
+port 52314 ssh2: ED25519 SHA256:Zm9vYmFy
+2024-03-01T09:16:00.000000+00:00 box sshd[900]: Invalid user admin from 203.0.113.9 port 4022
+2024-03-01T09:16:01.000000+00:00 box sshd[900]: Failed password for invalid user admin \
+from 203.0.113.9 port 4022 ssh2
+2024-03-01T09:20:02.000000+00:00 box sshd[811]: Disconnected from user alice 10.0.0.5 port 52314
+2024-03-01T09:20:03.000000+00:00 box CRON[950]: pam_unix(cron:session): session opened
+"""
+
+
+def client(ts, message, pid=4321):
+    return json.dumps(
+        {
+            "timestamp": f"2024-03-01 10:{ts:02d}:00.000000+0000",
+            "processImagePath": "/usr/bin/ssh",
+            "processID": pid,
+            "eventMessage": message,
+        }
+    )
+
+
+CLIENT_LOG = "\n".join(
+    [
+        client(0, "Authenticating to box.example.com:22 as 'alice'"),
+        client(0, "Server host key: ssh-ed25519 SHA256:aG9zdGtleQ"),
+        client(1, 'Authenticated to box.example.com ([10.0.0.7]:22) using "publickey".'),
+        client(5, "Transferred: sent 3024, received 2920 bytes, in 240.0 seconds"),
+    ]
+)
+
+
+class TestInbound:
+    def test_login_failures_and_logout(self):
+        events = parse_ssh_log(AUTH_LOG)
+        assert [e["kind"] for e in events] == ["login", "failed", "failed", "logout"]
+        login, invalid, failed, logout = events
+        assert login["direction"] == "inbound"
+        assert login["user"] == "alice"
+        assert login["host"] == "10.0.0.5"
+        assert login["remote_address"] == "10.0.0.5"
+        assert login["port"] == 52314
+        assert login["method"] == "publickey"
+        assert login["key_type"] == "ed25519"
+        assert login["fingerprint"] == "SHA256:Zm9vYmFy"
+        assert login["pid"] == 811
+        assert login["summary"] == "ssh login by alice from 10.0.0.5"
+        assert invalid["user"] == "admin"
+        assert invalid["method"] is None
+        assert failed["method"] == "password"
+        assert logout["duration"] == pytest.approx(300.0)
+        assert logout["fingerprint"] == "SHA256:Zm9vYmFy"
+
+    def test_since(self):
+        events = parse_ssh_log(AUTH_LOG, since=1709284700.0)
+        assert [e["kind"] for e in events] == ["logout"]
+        assert events[0]["duration"] == pytest.approx(300.0)
+
+    def test_unpaired_logout(self):
+        line = "2024-03-01T09:20:02+00:00 box sshd[1]: Disconnected from user bob 10.0.0.6 port 7"
+        (event,) = parse_ssh_log(line)
+        assert event["user"] == "bob"
+        assert event["duration"] is None
+
+    def test_traditional_syslog_and_log_show(self):
+        text = (
+            "Mar  1 09:15:02 box sshd[811]: Accepted password for alice from 10.0.0.5 port 1 ssh2\n"
+            "2024-03-01 09:15:02.123456+0000  0x1a2b  Default  0x0  612  0  "
+            "sshd-session: Accepted password for bob from 10.0.0.6 port 2 ssh2\n"
+        )
+        events = parse_ssh_log(text)
+        assert {e["user"] for e in events} == {"alice", "bob"}
+        assert all(e["timestamp"] for e in events)
+        bob = next(e for e in events if e["user"] == "bob")
+        assert bob["pid"] == 612
+        assert bob["timestamp"] == pytest.approx(1709284502.123456)
+
+
+class TestOutbound:
+    def test_client_session(self):
+        events = parse_ssh_log(CLIENT_LOG)
+        assert [e["kind"] for e in events] == ["login", "logout"]
+        login, logout = events
+        assert login["direction"] == "outbound"
+        assert login["user"] == "alice"
+        assert login["host"] == "box.example.com"
+        assert login["remote_address"] == "10.0.0.7"
+        assert login["port"] == 22
+        assert login["key_type"] == "ed25519"
+        assert login["fingerprint"] == "SHA256:aG9zdGtleQ"
+        assert login["summary"] == "ssh to alice@box.example.com"
+        assert logout["duration"] == pytest.approx(240.0)
+        assert logout["pid"] == 4321
+
+    def test_unauthenticated_client_is_ignored(self):
+        line = client(0, "Transferred: sent 1, received 2 bytes, in 0.1 seconds")
+        assert parse_ssh_log(line) == []
+
+
+class TestSshEvents:
+    def test_reads_path(self, tmp_path):
+        log = tmp_path / "auth.log"
+        log.write_text(AUTH_LOG)
+        assert len(ssh_events(path=log)) == 4
+
+    def test_missing_path(self, tmp_path):
+        with pytest.raises(IOError):
+            ssh_events(path=tmp_path / "missing.log")
@@ -0,0 +1,96 @@
+"""Tests for tcc_permissions and the tcc Collector source (Rust native via PyO3)."""
+
+import sqlite3
+import time
+
+import pytest
+
+from snoopy._native import Collector, tcc_permissions
+
+
+def _make_db(path, rows):
+    conn = sqlite3.connect(path)
+    conn.execute(
+        "CREATE TABLE access (service TEXT NOT NULL, client TEXT NOT NULL,"
+        " client_type INTEGER NOT NULL, auth_value INTEGER NOT NULL,"
+        " auth_reason INTEGER NOT NULL, auth_version INTEGER NOT NULL,"
+        " last_modified INTEGER NOT NULL DEFAULT (CAST(strftime('%s','now') AS INTEGER)),"
+        " PRIMARY KEY (service, client, client_type))"
+    )
+    conn.executemany(
+        "INSERT INTO access (service, client, client_type, auth_value, auth_reason,"
+        " auth_version, last_modified) VALUES (?, ?, ?, ?, 2, 1, 1700000000)",
+        rows,
+    )
+    conn.commit()
+    conn.close()
+
+
+ROWS = [
+    ("kTCCServiceScreenCapture", "com.example.recorder", 0, 2),
+    ("kTCCServiceMicrophone", "/usr/local/bin/listener", 1, 0),
+    ("kTCCServicePhotos", "com.example.gallery", 0, 3),
+]
+
+
+class TestTccPermissions:
+    def test_reads_entries(self, tmp_path):
+        db = tmp_path / "TCC.db"
+        _make_db(db, ROWS)
+        entries = {e["client"]: e for e in tcc_permissions(db)}
+        recorder = entries["com.example.recorder"]
+        assert recorder["source"] == "tcc"
+        assert recorder["service"] == "screen_recording"
+        assert recorder["service_id"] == "kTCCServiceScreenCapture"
+        assert (recorder["auth"], recorder["allowed"]) == ("allowed", True)
+        assert recorder["client_type"] == "bundle"
+        assert recorder["last_modified"] == 1700000000
+        listener = entries["/usr/local/bin/listener"]
+        assert (listener["service"], listener["client_type"]) == ("microphone", "path")
+        assert (listener["auth"], listener["allowed"]) == ("denied", False)
+        assert entries["com.example.gallery"]["auth"] == "limited"
+
+    def test_legacy_allowed_column(self, tmp_path):
+        db = tmp_path / "TCC.db"
+        conn = sqlite3.connect(db)
+        conn.execute("CREATE TABLE access (service TEXT, client TEXT, client_type INTEGER,"
+                     " allowed INTEGER, prompt_count INTEGER)")
+        conn.execute("INSERT INTO access VALUES ('kTCCServiceCamera', 'com.example.cam', 0, 1, 1)")
+        conn.commit()
+        conn.close()
+        [entry] = tcc_permissions(db)
+        assert (entry["service"], entry["auth"]) == ("camera", "allowed")
+        assert entry["last_modified"] is None
+
+    def test_missing_db(self, tmp_path):
+        with pytest.raises(IOError):
+            tcc_permissions(tmp_path / "missing.db")
+
+    def test_collector_reports_changes(self, tmp_path):
+        db = tmp_path / "TCC.db"
+        _make_db(db, ROWS)
+        collector = Collector(["tcc"], interval=0.05, tcc_db=db)
+        collector.start()
+        try:
+            time.sleep(0.3)
+            assert collector.drain() == []
+            conn = sqlite3.connect(db)
+            conn.execute("UPDATE access SET auth_value = 2"
+                         " WHERE client = '/usr/local/bin/listener'")
+            conn.execute("DELETE FROM access WHERE client = 'com.example.gallery'")
+            conn.execute("INSERT INTO access VALUES ('kTCCServiceCamera', 'com.example.cam',"
+                         " 0, 0, 2, 1, 1700000100)")
+            conn.commit()
+            conn.close()
+            time.sleep(0.3)
+            events = collector.drain()
+        finally:
+            collector.stop()
+        kinds = {(e["client"], e["kind"], e["previous_auth"]) for e in events}
+        assert kinds == {
+            ("/usr/local/bin/listener", "granted", "denied"),
+            ("com.example.gallery", "removed", "limited"),
+            ("com.example.cam", "denied", None),
+        }
+        assert len(events) == 3
+        assert all(e["summary"] for e in events)
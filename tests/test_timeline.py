@@ -0,0 +1,62 @@
+"""Tests for multi-source timeline merging (Rust native via PyO3)."""
+
+from datetime import datetime, timezone
+
+import pytest
+
+from snoopy._native import merge_timelines
+
+
+def _events(source, *timestamps):
+    return [{"timestamp": ts, "source": source} for ts in timestamps]
+
+
+def _order(merged):
+    return [(e["timestamp"], e["source"]) for e in merged]
+
+
+class TestMergeTimelines:
+    def test_merges_sorted_sources(self):
+        merged = merge_timelines([
+            _events("claude", 1.0, 4.0, 9.0),
+            _events("imessage", 2.0, 3.0),
+            _events("network", 5.0),
+        ])
+        assert [e["timestamp"] for e in merged] == [1.0, 2.0, 3.0, 4.0, 5.0, 9.0]
+
+    def test_ties_keep_source_order(self):
+        merged = merge_timelines([_events("a", 1.0, 2.0), _events("b", 1.0, 2.0)])
+        assert _order(merged) == [(1.0, "a"), (1.0, "b"), (2.0, "a"), (2.0, "b")]
+
+    def test_lazy_and_accepts_generators(self):
+        pulled = []
+
+        def gen(source, timestamps):
+            for ts in timestamps:
+                pulled.append((source, ts))
+                yield {"timestamp": ts, "source": source}
+
+        merged = merge_timelines([gen("a", [1.0, 5.0]), gen("b", [2.0, 6.0])])
+        assert next(merged)["timestamp"] == 1.0
+        assert pulled == [("a", 1.0), ("b", 2.0)]
+
+    def test_window_reorders_roughly_sorted_source(self):
+        merged = merge_timelines([_events("a", 1.0, 3.0, 2.0, 4.0), _events("b", 2.5)],
+                                 window=1.5)
+        assert [e["timestamp"] for e in merged] == [1.0, 2.0, 2.5, 3.0, 4.0]
+
+    def test_offsets_correct_skew(self):
+        merged = merge_timelines([_events("phone", 100.0), _events("mac", 50.0, 150.0)],
+                                 offsets=[-60.0, 0.0])
+        assert _order(merged) == [(100.0, "phone"), (50.0, "mac"), (150.0, "mac")]
+
+    def test_datetimes_and_bare_timestamps(self):
+        dt = datetime(2026, 2, 25, 10, 0, tzinfo=timezone.utc)
+        merged = list(merge_timelines([[dt.timestamp() + 1], [{"timestamp": dt}]]))
+        assert merged[0] == {"timestamp": dt}
+
+    def test_invalid_arguments(self):
+        with pytest.raises(ValueError):
+            merge_timelines([[1.0]], window=-1.0)
+        with pytest.raises(ValueError):
+            merge_timelines([[1.0], [2.0]], offsets=[0.0])
@@ -0,0 +1,140 @@
+"""Tests for Time Machine backup parsing (Rust native via PyO3)."""
+
+import json
+import os
+import time
+
+import pytest
+
+from snoopy._native import Collector, parse_backupd_log, time_machine_status
+
+
+def entry(ts, message):
+    return json.dumps(
+        {
+            "timestamp": f"2024-03-01 10:{ts:02d}:00.000000+0000",
+            "processImagePath": "/System/Library/CoreServices/backupd.bundle/Contents/backupd",
+            "eventMessage": message,
+        }
+    )
+
+
+NDJSON = "\n".join(
+    [
+        entry(0, "Starting automatic backup"),
+        entry(1, "Copied 12.5 MB of 40 MB, 120 of 400 items"),
+        entry(3, "Copied 1.2 GB of 1.2 GB, 1,357 of 1,357 items"),
+        entry(4, "Backup completed successfully."),
+        entry(30, "Starting manual backup"),
+        entry(31, "Backup failed (19: BACKUP_FAILED_DESTINATION_UNAVAILABLE)"),
+    ]
+)
+
+TEXT = """\
+2024-03-01 11:00:00.000000+0000  0x1a2b  Default  0x0  612  0  backupd: Starting automatic backup
+2024-03-01 11:00:05.000000+0000  0x1a2b  Default  0x0  612  0  backupd: Copied 10 items (2 MB)
+2024-03-01 11:00:09.000000+0000  0x1a2b  Default  0x0  612  0  backupd: Copied 5 items (1.5 MB)
+2024-03-01 11:00:10.000000+0000  0x1a2b  Default  0x0  612  0  backupd: Backup canceled.
+"""
+
+STATUS = """\
+Backup session status:
+{
+    BackupPhase = Copying;
+    ClientID = "com.apple.backupd";
+    Percent = "0.25";
+    Progress =     {
+        TimeRemaining = 120;
+        "_raw_totalBytes" = 4000000;
+        bytes = 1000000;
+        files = 10;
+        totalBytes = 4000000;
+        totalFiles = 40;
+    };
+    Running = 1;
+}
+"""
+
+
+class TestParseBackupdLog:
+    def test_ndjson(self):
+        events = parse_backupd_log(NDJSON)
+        assert [(e["kind"], e.get("trigger")) for e in events] == [
+            ("started", "automatic"),
+            ("completed", None),
+            ("started", "manual"),
+            ("failed", None),
+        ]
+        started, completed, _, failed = events
+        assert started["source"] == "backup"
+        assert started["summary"] == "Time Machine backup started (automatic)"
+        assert completed["bytes"] == 1_200_000_000
+        assert completed["items"] == 1357
+        assert completed["duration"] == 240
+        assert completed["summary"] == "Time Machine backup completed: 1.1 GB"
+        assert failed["error"] == "19: BACKUP_FAILED_DESTINATION_UNAVAILABLE"
+        assert failed["bytes"] is None
+        assert failed["duration"] == 60
+
+    def test_text_style_sums_volumes(self):
+        _, canceled = parse_backupd_log(TEXT)
+        assert canceled["kind"] == "canceled"
+        assert canceled["bytes"] == 3_500_000
+        assert canceled["items"] == 15
+        assert canceled["duration"] == 10
+        assert canceled["error"] is None
+
+    def test_since(self):
+        since = parse_backupd_log(NDJSON)[2]["timestamp"] - 1
+        assert [e["kind"] for e in parse_backupd_log(NDJSON, since=since)] == [
+            "started",
+            "failed",
+        ]
+
+
+@pytest.fixture
+def fake_tmutil(tmp_path, monkeypatch):
+    state = tmp_path / "status.txt"
+    state.write_text("Backup session status:\n{\n    Running = 0;\n}\n")
+    bin_dir = tmp_path / "bin"
+    bin_dir.mkdir()
+    script = bin_dir / "tmutil"
+    script.write_text(f'#!/bin/sh\ncat "{state}"\n')
+    script.chmod(0o755)
+    monkeypatch.setenv("PATH", f"{bin_dir}{os.pathsep}{os.environ['PATH']}")
+    return state
+
+
+class TestTimeMachineStatus:
+    def test_status(self, fake_tmutil):
+        fake_tmutil.write_text(STATUS)
+        status = time_machine_status()
+        assert status == {
+            "running": True,
+            "phase": "Copying",
+            "percent": 25.0,
+            "bytes": 1_000_000,
+            "total_bytes": 4_000_000,
+            "files": 10,
+            "total_files": 40,
+            "time_remaining": 120.0,
+        }
+
+    def test_collector_reports_backups(self, fake_tmutil):
+        idle = fake_tmutil.read_text()
+        collector = Collector(["backup"], interval=0.05)
+        collector.start()
+        try:
+            time.sleep(0.3)
+            fake_tmutil.write_text(STATUS)
+            time.sleep(0.3)
+            fake_tmutil.write_text(idle)
+            time.sleep(0.3)
+            events = collector.drain()
+        finally:
+            collector.stop()
+        assert [e["kind"] for e in events] == ["started", "completed"]
+        assert events[0]["phase"] == "Copying"
+        assert events[1]["bytes"] == 1_000_000
+        assert events[1]["items"] == 10
+        assert events[1]["duration"] == pytest.approx(0.3, abs=0.2)
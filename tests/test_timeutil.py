@@ -0,0 +1,82 @@
+"""Tests for ISO 8601 timestamp parsing (Rust native via PyO3)."""
+
+import pytest
+
+from snoopy._native import parse_iso_timestamp, parse_iso_timestamps
+
+BASE = 1772013600.0  # 2026-02-25T10:00:00Z
+
+
+class TestParseIsoTimestamp:
+    @pytest.mark.parametrize("text", [
+        "2026-02-25T10:00:00Z",
+        "2026-02-25T10:00:00.000Z",
+        "2026-02-25t10:00:00z",
+        "2026-02-25 10:00:00+00:00",
+        "2026-02-25T11:00:00+01:00",
+        "2026-02-25T11:00:00+0100",
+        "2026-02-25T11:00:00+01",
+        "2026-02-25T05:30:00-04:30",
+        "20260225T100000Z",
+        "20260225T110000+0100",
+        "2026-02-25T10:00",
+        "2026-02-25T10:00:00",
+    ])
+    def test_equivalent_forms(self, text):
+        assert parse_iso_timestamp(text) == BASE
+
+    def test_fractional_seconds(self):
+        assert parse_iso_timestamp("2026-02-25T10:00:00.250Z") == pytest.approx(BASE + 0.25)
+        assert parse_iso_timestamp("2026-02-25T10:00:00.123456789Z") == pytest.approx(
+            BASE + 0.123456789
+        )
+
+    def test_leap_second(self):
+        assert parse_iso_timestamp("2016-12-31T23:59:60Z") == pytest.approx(1483228800.0)
+
+    def test_date_only(self):
+        assert parse_iso_timestamp("2026-02-25") == BASE - 10 * 3600
+
+    def test_negative_and_extended_years(self):
+        assert parse_iso_timestamp("-0001-01-01T00:00:00Z") < parse_iso_timestamp(
+            "0001-01-01T00:00:00Z"
+        )
+        assert parse_iso_timestamp("+10000-01-01T00:00:00Z") > BASE
+
+    @pytest.mark.parametrize("text", ["", "yesterday", "2026-13-01T00:00:00Z", "10:00:00"])
+    def test_invalid(self, text):
+        assert parse_iso_timestamp(text) is None
+
+    def test_named_zones(self):
+        assert parse_iso_timestamp("2026-02-25 05:00:00", tz="America/New_York") == BASE
+        assert parse_iso_timestamp("2026-02-25 11:00:00 Europe/Paris") == BASE
+        assert parse_iso_timestamp("2026-02-25 11:00:00 Europe/Paris", tz="Asia/Tokyo") == BASE
+        # Explicit offsets win over tz.
+        assert parse_iso_timestamp("2026-02-25T10:00:00Z", tz="Asia/Tokyo") == BASE
+        assert parse_iso_timestamps(["2026-02-25 19:00:00"], tz="Asia/Tokyo") == [BASE]
+
+    def test_dst_transitions(self):
+        # 02:30 doesn't exist on spring-forward day; 01:30 happens twice in the fall.
+        gap = parse_iso_timestamp("2026-03-08 02:30:00", tz="America/New_York")
+        assert gap == parse_iso_timestamp("2026-03-08T02:30:00-05:00")
+        fold = parse_iso_timestamp("2026-11-01 01:30:00", tz="America/New_York")
+        assert fold == parse_iso_timestamp("2026-11-01T01:30:00-04:00")
+
+    def test_invalid_zone(self):
+        with pytest.raises(ValueError):
+            parse_iso_timestamp("2026-02-25 10:00:00", tz="Mars/Olympus")
+
+
+class TestParseIsoTimestamps:
+    def test_preserves_order_and_marks_invalid(self):
+        result = parse_iso_timestamps(["2026-02-25T10:00:00Z", "garbage", "20260225T100001Z"])
+        assert result == [BASE, None, BASE + 1]
+
+    def test_large_batch(self):
+        stamps = [f"2026-02-25T10:00:{i % 60:02d}Z" for i in range(10_000)]
+        result = parse_iso_timestamps(stamps)
+        assert len(result) == 10_000
+        assert result[59] == BASE + 59
+
+    def test_empty(self):
+        assert parse_iso_timestamps([]) == []
@@ -0,0 +1,51 @@
+"""Tests for session title inference (Rust native via PyO3)."""
+
+import pytest
+
+from snoopy._native import infer_title
+
+
+def _user(text, **extra):
+    return {"type": "user", "timestamp": "2026-02-25T10:00:00Z",
+            "message": {"role": "user", "content": text}, **extra}
+
+
+class TestInferTitle:
+    def test_first_prompt(self, tmp_path, write_transcript):
+        path = tmp_path / "s.jsonl"
+        write_transcript(path, [_user("fix the flaky login test"), _user("thanks")])
+        assert infer_title(str(path)) == "Fix the flaky login test"
+
+    def test_summary_preferred(self, tmp_path, write_transcript):
+        path = tmp_path / "s.jsonl"
+        write_transcript(path, [
+            _user("fix the flaky login test"),
+            {"type": "summary", "summary": "Login test flakiness fix", "leafUuid": "u1"},
+        ])
+        assert infer_title(str(path)) == "Login test flakiness fix"
+
+    def test_skips_commands_and_logs(self, tmp_path, write_transcript):
+        path = tmp_path / "s.jsonl"
+        write_transcript(path, [
+            _user("Caveat: messages below were generated by local commands", isMeta=True),
+            _user("<command-name>/clear</command-name>"),
+            _user("/model opus"),
+            _user("Traceback (most recent call last):\n  File \"a.py\", line 1\nKeyError: 'x'"),
+            _user("2026-02-25 10:00:01 ERROR db timeout\nwhy does the worker keep timing out?"),
+        ])
+        assert infer_title(str(path)) == "Why does the worker keep timing out?"
+
+    def test_truncates_on_word_boundary(self, tmp_path, write_transcript):
+        path = tmp_path / "s.jsonl"
+        write_transcript(path, [_user("refactor the collector registry so plugins load lazily")])
+        title = infer_title(str(path), max_len=30)
+        assert title == "Refactor the collector…"
+
+    def test_none_when_nothing_usable(self, tmp_path, write_transcript):
+        path = tmp_path / "s.jsonl"
+        write_transcript(path, [_user("/clear")])
+        assert infer_title(str(path)) is None
+
+    def test_missing_file(self, tmp_path):
+        with pytest.raises(IOError):
+            infer_title(str(tmp_path / "nope.jsonl"))
@@ -0,0 +1,119 @@
+"""Tests for tmux session and pane tracking (Rust native via PyO3)."""
+
+import os
+import time
+
+import pytest
+
+from snoopy._native import (
+    TMUX_PANE_FORMAT,
+    Collector,
+    TmuxOptions,
+    parse_tmux_panes,
+    parse_tmux_sessions,
+    tmux_panes,
+    tmux_sessions,
+)
+
+
+def pane_line(pane_id, command, index=0, active=1):
+    return "\t".join(
+        ["work", "1", "editor", str(index), pane_id, "4242", command, "/Users/me/app", str(active)]
+    )
+
+
+class TestParse:
+    def test_panes(self):
+        text = pane_line("%3", "vim") + "\n" + pane_line("%4", "zsh", index=1, active=0) + "\n"
+        panes = parse_tmux_panes(text)
+        assert len(panes) == 2
+        assert panes[0] == {
+            "source": "tmux",
+            "session": "work",
+            "window": 1,
+            "window_name": "editor",
+            "pane": 0,
+            "pane_id": "%3",
+            "pid": 4242,
+            "command": "vim",
+            "cwd": "/Users/me/app",
+            "active": True,
+        }
+        assert panes[1]["active"] is False
+
+    def test_sessions(self):
+        sessions = parse_tmux_sessions("work\t$0\t1700000000\t1\t3\t1700000500\n")
+        assert sessions == [
+            {
+                "source": "tmux",
+                "timestamp": 1700000000.0,
+                "session": "work",
+                "session_id": "$0",
+                "attached": 1,
+                "windows": 3,
+                "last_activity": 1700000500.0,
+            }
+        ]
+
+    def test_skips_malformed_lines(self):
+        assert parse_tmux_panes("garbage\nwork\tx\tname\n") == []
+        assert parse_tmux_sessions("only\ttwo\n") == []
+
+    def test_format_matches_parser(self):
+        assert TMUX_PANE_FORMAT.count("\t") == 8
+
+
+class TestOptions:
+    def test_rejects_negative_duration(self):
+        with pytest.raises(ValueError):
+            TmuxOptions(min_duration=-1.0)
+
+    def test_rejects_empty_buffer(self):
+        with pytest.raises(ValueError):
+            TmuxOptions(buffer_size=0)
+
+
+@pytest.fixture
+def fake_tmux(tmp_path, monkeypatch):
+    panes = tmp_path / "panes.txt"
+    panes.write_text(pane_line("%1", "zsh") + "\n")
+    bin_dir = tmp_path / "bin"
+    bin_dir.mkdir()
+    script = bin_dir / "tmux"
+    script.write_text(
+        "#!/bin/sh\n"
+        'case "$1" in\n'
+        f'  list-panes) cat "{panes}" ;;\n'
+        "  list-sessions) printf 'work\\t$0\\t1700000000\\t1\\t1\\t1700000500\\n' ;;\n"
+        "esac\n"
+    )
+    script.chmod(0o755)
+    monkeypatch.setenv("PATH", f"{bin_dir}{os.pathsep}{os.environ['PATH']}")
+    return panes
+
+
+class TestLive:
+    def test_panes_and_sessions(self, fake_tmux):
+        assert [p["pane_id"] for p in tmux_panes()] == ["%1"]
+        assert [s["session"] for s in tmux_sessions()] == ["work"]
+
+    def test_collector_reports_panes_and_commands(self, fake_tmux):
+        collector = Collector(["tmux"], interval=0.05, tmux=TmuxOptions(min_duration=0.1))
+        collector.start()
+        try:
+            time.sleep(0.3)
+            fake_tmux.write_text(pane_line("%1", "cargo") + "\n" + pane_line("%2", "zsh", 1) + "\n")
+            time.sleep(0.4)
+            fake_tmux.write_text(pane_line("%1", "zsh") + "\n")
+            time.sleep(0.3)
+            events = collector.drain()
+        finally:
+            collector.stop()
+        kinds = [e["kind"] for e in events]
+        assert kinds == ["pane_opened", "command", "pane_closed"]
+        command = events[1]
+        assert command["command"] == "cargo"
+        assert command["duration"] == pytest.approx(0.4, abs=0.2)
+        assert command["output"] is None
+        assert command["summary"].startswith("cargo ran for ")
+        assert events[2]["pane_id"] == "%2"
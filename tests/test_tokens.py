@@ -0,0 +1,27 @@
+"""Tests for local token estimation (Rust native via PyO3)."""
+
+import json
+
+from snoopy._native import estimate_tokens, estimate_tokens_batch, parse_transcript
+
+
+class TestEstimateTokens:
+    def test_empty(self):
+        assert estimate_tokens("") == 0
+
+    def test_short_text(self):
+        assert estimate_tokens("hello world") == 2
+
+    def test_batch_matches_single(self):
+        texts = ["hello world", "", "fn main() { println!(\"hi\"); }"]
+        assert estimate_tokens_batch(texts) == [estimate_tokens(t) for t in texts]
+
+    def test_transcript_without_usage_is_estimated(self, tmp_path):
+        transcript = tmp_path / "s.jsonl"
+        entry = {"type": "assistant", "timestamp": "2026-02-25T10:00:00Z",
+                 "message": {"content": [{"type": "text", "text": "hello world"}]}}
+        transcript.write_text(json.dumps(entry) + "\n")
+
+        events, _ = parse_transcript(str(transcript))
+        assert events[0]["tokens"] == 2
+        assert events[0]["cost_usd"] > 0
@@ -0,0 +1,115 @@
+"""Tests for training data export (Rust native via PyO3)."""
+
+import json
+
+import pytest
+
+from snoopy._native import RewriteRules, export_training_data
+
+
+def _entry(kind, content, ts="2026-02-25T10:00:00Z", **extra):
+    return {"type": kind, "timestamp": ts, "cwd": "/Users/me/app",
+            "message": {"role": kind, "content": content}, **extra}
+
+
+def _read(path):
+    return [json.loads(line) for line in path.read_text().splitlines()]
+
+
+SESSION = [
+    {"type": "summary", "summary": "Parser fix", "leafUuid": "x"},
+    _entry("user", "<command-name>/clear</command-name>"),
+    _entry("user", "Fix the parser. My key is sk-ant-REDACTED"),
+    _entry("assistant", [{"type": "thinking", "thinking": "hmm"},
+                         {"type": "text", "text": "Running the tests."},
+                         {"type": "tool_use", "id": "t1", "name": "Bash",
+                          "input": {"command": "cargo test"}}]),
+    _entry("user", [{"type": "tool_result", "tool_use_id": "t1", "content": "1 failed",
+                     "is_error": True}]),
+    _entry("assistant", [{"type": "tool_use", "id": "t2", "name": "Edit",
+                          "input": {"file_path": "/Users/me/app/src/parse.rs"}}]),
+    _entry("user", [{"type": "tool_result", "tool_use_id": "t2",
+                     "content": [{"type": "text", "text": "ok"}]}]),
+    _entry("assistant", "Fixed."),
+    _entry("user", "[Request interrupted by user]"),
+    _entry("user", "meta", isMeta=True),
+    _entry("assistant", [{"type": "text", "text": "side"}], isSidechain=True),
+    {"type": "assistant", "timestamp": "2026-02-25T10:00:00Z",
+     "message": {"role": "assistant", "model": "<synthetic>", "content": "API Error"}},
+    # A dangling call with no result, and a trailing prompt with no reply.
+    _entry("user", "thanks, now the docs"),
+    _entry("assistant", [{"type": "tool_use", "id": "t3", "name": "Read", "input": {}}]),
+    _entry("user", "never mind"),
+]
+
+
+@pytest.fixture
+def projects(tmp_path, write_transcript):
+    write_transcript(tmp_path / "-Users-me-app" / "s1.jsonl", SESSION)
+    write_transcript(tmp_path / "-Users-me-app" / "agent-a1.jsonl", [
+        _entry("user", "subagent task"), _entry("assistant", "subagent reply")])
+    write_transcript(tmp_path / "-Users-me-app" / "s2.jsonl", [_entry("user", "unanswered")])
+    return tmp_path
+
+
+class TestExportTrainingData:
+    def test_openai(self, projects, tmp_path):
+        out = tmp_path / "out.jsonl"
+        counts = export_training_data(str(projects), str(out), system="You are helpful.")
+        assert counts == {"sessions": 1, "messages": 7, "tool_calls": 2, "redactions": 1,
+                          "skipped": 1}
+        (record,) = _read(out)
+        messages = record["messages"]
+        # The unanswered Read call is dropped and the prompts after "Fixed." go
+        # unanswered, so the conversation ends there.
+        assert [m["role"] for m in messages] == [
+            "system", "user", "assistant", "tool", "assistant", "tool", "assistant"]
+        assert messages[0] == {"role": "system", "content": "You are helpful."}
+        assert messages[1] == {"role": "user", "content": "Fix the parser. My key is [REDACTED]"}
+        call = messages[2]
+        assert call["content"] == "Running the tests."
+        assert call["tool_calls"] == [{"id": "t1", "type": "function", "function": {
+            "name": "Bash", "arguments": '{"command":"cargo test"}'}}]
+        assert messages[3] == {"role": "tool", "tool_call_id": "t1", "content": "1 failed"}
+        assert messages[4]["content"] is None
+        assert messages[4]["tool_calls"][0]["function"]["name"] == "Edit"
+        assert messages[5]["content"] == "ok"
+        assert messages[6] == {"role": "assistant", "content": "Fixed."}
+
+    def test_anthropic(self, projects, tmp_path):
+        out = tmp_path / "out.jsonl"
+        export_training_data(str(projects / "-Users-me-app" / "s1.jsonl"), str(out),
+                             "anthropic", system="sys")
+        (record,) = _read(out)
+        assert record["system"] == "sys"
+        messages = record["messages"]
+        assert [m["role"] for m in messages] == ["user", "assistant", "user", "assistant",
+                                                 "user", "assistant"]
+        assert messages[1]["content"] == [
+            {"type": "text", "text": "Running the tests."},
+            {"type": "tool_use", "id": "t1", "name": "Bash", "input": {"command": "cargo test"}},
+        ]
+        assert messages[2]["content"] == [{"type": "tool_result", "tool_use_id": "t1",
+                                           "content": "1 failed", "is_error": True}]
+        assert messages[5]["content"] == [{"type": "text", "text": "Fixed."}]
+
+    def test_without_tools_and_options(self, projects, tmp_path):
+        out = tmp_path / "out.jsonl"
+        counts = export_training_data(str(projects), str(out), include_tools=False,
+                                      include_subagents=True,
+                                      rules=RewriteRules(redact_secrets=False))
+        assert counts["sessions"] == 2
+        assert counts["tool_calls"] == 0
+        subagent, session = _read(out)
+        assert [m["content"] for m in subagent["messages"]] == ["subagent task",
+                                                                "subagent reply"]
+        assert [m["content"] for m in session["messages"]] == [
+            "Fix the parser. My key is sk-ant-REDACTED",
+            "Running the tests.\n\nFixed.",
+        ]
+        assert export_training_data(str(projects), str(out), since=2e9)["sessions"] == 0
+        assert out.read_text() == ""
+
+    def test_invalid_format(self, projects, tmp_path):
+        with pytest.raises(ValueError):
+            export_training_data(str(projects), str(tmp_path / "out.jsonl"), "gemini")
@@ -0,0 +1,54 @@
+"""Tests for merging many transcripts into one ordered stream (Rust native via PyO3)."""
+
+import pytest
+
+from snoopy._native import ParserConfig, iter_transcripts
+
+
+def _user(ts, text):
+    return {"type": "user", "timestamp": ts, "message": {"content": text}}
+
+
+class TestIterTranscripts:
+    def test_merges_in_timestamp_order(self, tmp_path, write_transcript):
+        a = tmp_path / "-Users-me-api" / "a.jsonl"
+        b = tmp_path / "-Users-me-web" / "b.jsonl"
+        write_transcript(a, [_user("2026-02-25T10:00:00Z", "a1"),
+                              _user("2026-02-25T10:00:03Z", "a2")])
+        write_transcript(b, [_user("2026-02-25T10:00:01Z", "b1"),
+                              _user("2026-02-25T10:00:03Z", "b2"),
+                              _user("2026-02-25T10:00:04Z", "b3")])
+
+        events = list(iter_transcripts([str(b), str(a)]))
+        assert [e["content_preview"] for e in events] == ["a1", "b1", "b2", "a2", "b3"]
+        assert [e["session_id"] for e in events[:2]] == ["a", "b"]
+
+    def test_directories_and_config(self, tmp_path, write_transcript):
+        write_transcript(tmp_path / "p1" / "s1.jsonl", [
+            _user("2026-02-25T10:00:02Z", "late"),
+            {"type": "system", "timestamp": "2026-02-25T10:00:05Z",
+             "subtype": "compact_boundary", "compactMetadata": {"trigger": "auto"}},
+            {"type": "user", "timestamp": "2026-02-25T10:00:05Z", "isCompactSummary": True,
+             "message": {"content": "summary of work"}},
+        ])
+        write_transcript(tmp_path / "p2" / "s2.jsonl", [_user("2026-02-25T10:00:01Z", "early")])
+
+        events = list(iter_transcripts([str(tmp_path)], config=ParserConfig(time_format="iso")))
+        assert [e["content_preview"] for e in events] == ["early", "late", "summary of work"]
+        assert events[2]["message_type"] == "compaction"
+        assert events[0]["timestamp"] == "2026-02-25T10:00:01.000Z"
+
+    def test_is_lazy(self, tmp_path, write_transcript):
+        path = tmp_path / "s.jsonl"
+        write_transcript(path, [_user("2026-02-25T10:00:00Z", "first")])
+        merge = iter_transcripts([str(path)])
+        with open(path, "ab") as f:
+            f.write(b"\xff\xfe\n")
+        assert next(merge)["content_preview"] == "first"
+        with pytest.raises(ValueError):
+            next(merge)
+
+    def test_missing_file(self, tmp_path):
+        with pytest.raises(IOError):
+            iter_transcripts([str(tmp_path / "missing.jsonl")])
+        assert list(iter_transcripts([])) == []
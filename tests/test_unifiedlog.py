@@ -0,0 +1,88 @@
+"""Tests for query_unified_log (Rust native via PyO3)."""
+
+import json
+import os
+
+import pytest
+
+from snoopy._native import query_unified_log
+
+ENTRIES = [
+    {
+        "timestamp": "2023-11-14 22:13:20.500000+0000",
+        "processImagePath": "/usr/libexec/bluetoothd",
+        "processID": 88,
+        "senderImagePath": "/System/Library/PrivateFrameworks/Core.framework/Core",
+        "subsystem": "com.apple.bluetooth",
+        "category": "Server",
+        "messageType": "Default",
+        "eventMessage": "Device connected: AirPods",
+    },
+    {
+        "timestamp": "2023-11-14 22:13:21.000000+0000",
+        "processImagePath": "/usr/libexec/bluetoothd",
+        "processID": 88,
+        "subsystem": "com.apple.bluetooth",
+        "category": "Server",
+        "messageType": "Info",
+        "eventMessage": "Scanning",
+    },
+]
+
+
+def _fake_log(tmp_path, monkeypatch, lines, exit_code=0):
+    bin_dir = tmp_path / "bin"
+    bin_dir.mkdir()
+    out = tmp_path / "out.ndjson"
+    out.write_text("".join(line + "\n" for line in lines))
+    script = bin_dir / "log"
+    script.write_text(
+        "#!/bin/sh\n"
+        f'printf "%s\\n" "$@" > "{tmp_path}/args"\n'
+        "echo 'Filtering the log data using \"x\"'\n"
+        f'cat "{out}"\n'
+        "echo 'failure detail' >&2\n"
+        f"exit {exit_code}\n"
+    )
+    script.chmod(0o755)
+    monkeypatch.setenv("PATH", f"{bin_dir}{os.pathsep}{os.environ['PATH']}")
+    return tmp_path / "args"
+
+
+class TestQueryUnifiedLog:
+    def test_parses_ndjson_entries(self, tmp_path, monkeypatch):
+        lines = [json.dumps(e) for e in ENTRIES] + ['{"count":2,"finished":1}']
+        args = _fake_log(tmp_path, monkeypatch, lines)
+        events = query_unified_log('subsystem == "com.apple.bluetooth"', last="1h", info=True)
+        assert args.read_text().split("\n")[:8] == [
+            "show", "--style", "ndjson", "--predicate", 'subsystem == "com.apple.bluetooth"',
+            "--last", "1h", "--info",
+        ]
+        assert len(events) == 2
+        first = events[0]
+        assert first["source"] == "unifiedlog"
+        assert first["timestamp"] == pytest.approx(1700000000.5)
+        assert first["process_name"] == "bluetoothd"
+        assert first["pid"] == 88
+        assert first["sender"] == "Core"
+        assert first["level"] == "Default"
+        assert first["message"] == "Device connected: AirPods"
+
+    def test_message_pattern_and_limit(self, tmp_path, monkeypatch):
+        _fake_log(tmp_path, monkeypatch, [json.dumps(e) for e in ENTRIES * 3])
+        events = query_unified_log(message_pattern=r"^Scan")
+        assert [e["message"] for e in events] == ["Scanning"] * 3
+        assert len(query_unified_log(limit=2)) == 2
+        assert query_unified_log(limit=0) == []
+
+    def test_start_end_window(self, tmp_path, monkeypatch):
+        args = _fake_log(tmp_path, monkeypatch, [])
+        query_unified_log(start=1_700_000_000, end=1_700_000_600)
+        passed = args.read_text().split("\n")
+        assert "--start" in passed and "--end" in passed
+        assert "--last" not in passed
+
+    def test_failure_raises(self, tmp_path, monkeypatch):
+        _fake_log(tmp_path, monkeypatch, [], exit_code=64)
+        with pytest.raises(RuntimeError, match="failure detail"):
+            query_unified_log()
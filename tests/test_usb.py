@@ -0,0 +1,28 @@
+"""Tests for usb_devices and the usb Collector source (Rust native via PyO3)."""
+
+import time
+
+from snoopy._native import Collector, usb_devices
+
+FIELDS = {"name", "vendor", "vendor_id", "product_id", "serial", "location"}
+
+
+class TestUsbDevices:
+    def test_device_shape(self):
+        devices = usb_devices()
+        assert isinstance(devices, list)
+        for device in devices:
+            assert set(device) == FIELDS
+            assert device["vendor_id"] == device["vendor_id"].lower()
+
+    def test_collector_baseline_emits_nothing(self):
+        collector = Collector(["usb"], interval=0.05)
+        collector.start()
+        try:
+            time.sleep(0.3)
+            status = collector.status()["sources"]["usb"]
+        finally:
+            collector.stop()
+        assert status["errors"] == 0
+        assert status["last_run"] is not None
+        assert collector.drain() == []
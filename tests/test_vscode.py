@@ -0,0 +1,101 @@
+"""Tests for the VS Code activity reader (Rust native via PyO3)."""
+
+import json
+import os
+import sqlite3
+
+import pytest
+
+from snoopy._native import vscode_activity
+
+
+def make_state(path, items):
+    path.parent.mkdir(parents=True, exist_ok=True)
+    conn = sqlite3.connect(path)
+    conn.execute("CREATE TABLE ItemTable (key TEXT UNIQUE ON CONFLICT REPLACE, value BLOB)")
+    for key, value in items.items():
+        conn.execute("INSERT INTO ItemTable VALUES (?, ?)", (key, json.dumps(value)))
+    conn.commit()
+    conn.close()
+
+
+def make_workspace(user_dir, name, meta, mtime):
+    folder = user_dir / "workspaceStorage" / name
+    folder.mkdir(parents=True)
+    (folder / "workspace.json").write_text(json.dumps(meta))
+    state = folder / "state.vscdb"
+    state.write_bytes(b"")
+    os.utime(state, (mtime, mtime))
+
+
+@pytest.fixture
+def user_dir(tmp_path):
+    user_dir = tmp_path / "User"
+    recent = {
+        "entries": [
+            {"folderUri": "file:///Users/me/My%20Project"},
+            {"fileUri": "file:///Users/me/My%20Project/src/main.rs"},
+            {"workspace": {"id": "abc", "configPath": "file:///Users/me/all.code-workspace"}},
+            {"folderUri": "vscode-remote://ssh-remote%2Bbox/home/me/api"},
+            {"fileUri": "file:///etc/hosts"},
+        ]
+    }
+    commands = {
+        "entries": [
+            {"key": "cargo build", "value": {"shellType": "zsh"}},
+            {"key": "cargo test", "value": {"shellType": "zsh"}},
+        ]
+    }
+    make_state(
+        user_dir / "globalStorage" / "state.vscdb",
+        {
+            "history.recentlyOpenedPathsList": recent,
+            "terminal.history.entries.commands": commands,
+        },
+    )
+    make_workspace(user_dir, "a1", {"folder": "file:///Users/me/My%20Project"}, 1_700_000_300)
+    make_workspace(user_dir, "b2", {"folder": "file:///Users/me/old"}, 1_600_000_000)
+    multi = {"workspace": "file:///Users/me/all.code-workspace"}
+    make_workspace(user_dir, "c3", multi, 1_700_000_100)
+    return user_dir
+
+
+class TestVscodeActivity:
+    def test_recent_workspaces_files_and_commands(self, user_dir):
+        events = vscode_activity(user_dir)
+        assert [(e["kind"], e["rank"]) for e in events] == [
+            ("workspace", 0),
+            ("file", 1),
+            ("workspace", 2),
+            ("workspace", 3),
+            ("file", 4),
+            ("workspace", None),
+            ("terminal_command", 0),
+            ("terminal_command", 1),
+        ]
+        project, main_rs, multi, remote, hosts, old, latest, first = events
+        assert project["source"] == "vscode"
+        assert project["project_path"] == "/Users/me/My Project"
+        assert project["timestamp"] == 1_700_000_300
+        assert project["workspace_file"] is None
+        assert main_rs["path"] == "/Users/me/My Project/src/main.rs"
+        assert main_rs["project_path"] == "/Users/me/My Project"
+        assert main_rs["timestamp"] is None
+        assert multi["workspace_file"] == "/Users/me/all.code-workspace"
+        assert multi["timestamp"] == 1_700_000_100
+        assert remote["remote"] == "ssh-remote+box"
+        assert remote["project_path"] == "/home/me/api"
+        assert remote["timestamp"] is None
+        assert hosts["project_path"] is None
+        assert old["project_path"] == "/Users/me/old"
+        assert latest["command"] == "cargo test"
+        assert latest["shell"] == "zsh"
+        assert first["command"] == "cargo build"
+
+    def test_since_keeps_timestamped_events(self, user_dir):
+        events = vscode_activity(user_dir, since=1_700_000_200)
+        assert [e["project_path"] for e in events] == ["/Users/me/My Project"]
+
+    def test_missing_state(self, tmp_path):
+        with pytest.raises(IOError):
+            vscode_activity(tmp_path)
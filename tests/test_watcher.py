@@ -0,0 +1,170 @@
+"""Tests for the native file Watcher (Rust native via PyO3)."""
+
+import asyncio
+import threading
+import time
+
+import pytest
+
+from snoopy._native import Watcher
+
+
+def _poll_until(watcher, n, timeout=5.0):
+    changes = []
+    deadline = time.time() + timeout
+    while len(changes) < n and time.time() < deadline:
+        changes.extend(watcher.poll(timeout=0.1))
+    return changes
+
+
+class TestWatcher:
+    def test_poll_reports_matching_changes(self, tmp_path):
+        watcher = Watcher([str(tmp_path)], patterns=["*.jsonl"], debounce=0.1)
+        watcher.start()
+        try:
+            (tmp_path / "s.jsonl").write_text("{}\n")
+            (tmp_path / "notes.txt").write_text("ignored")
+            changes = _poll_until(watcher, 1)
+            time.sleep(0.3)
+            changes.extend(watcher.poll())
+        finally:
+            watcher.stop()
+        assert [c["path"] for c in changes] == [str(tmp_path / "s.jsonl")]
+        assert changes[0]["kind"] == "created"
+
+    def test_debounce_merges_bursts(self, tmp_path):
+        path = tmp_path / "chat.db"
+        path.write_text("")
+        watcher = Watcher([str(tmp_path)], debounce=0.3)
+        watcher.start()
+        try:
+            for i in range(10):
+                with open(path, "a") as f:
+                    f.write(f"{i}\n")
+                time.sleep(0.01)
+            changes = _poll_until(watcher, 1)
+            time.sleep(0.5)
+            changes.extend(watcher.poll())
+        finally:
+            watcher.stop()
+        assert [(c["path"], c["kind"]) for c in changes] == [(str(path), "modified")]
+
+    def test_callback_and_ignore(self, tmp_path):
+        received = []
+        done = threading.Event()
+
+        def on_change(batch):
+            received.extend(batch)
+            done.set()
+
+        watcher = Watcher([str(tmp_path)], ignore=["*.tmp"], debounce=0.05, callback=on_change)
+        watcher.start()
+        try:
+            (tmp_path / "a.tmp").write_text("x")
+            (tmp_path / "b.jsonl").write_text("x")
+            done.wait(5)
+        finally:
+            watcher.stop()
+        assert [c["path"] for c in received] == [str(tmp_path / "b.jsonl")]
+        assert watcher.poll() == []
+
+    def test_async_iteration(self, tmp_path):
+        async def main():
+            watcher = Watcher([str(tmp_path)], patterns=["*.jsonl"], debounce=0.05)
+            assert await watcher.next_event(timeout=0.1) is None
+            watcher.start()
+            try:
+                assert await watcher.next_event(timeout=0.1) is None
+                (tmp_path / "a.jsonl").write_text("x")
+                first = await asyncio.wait_for(watcher.next_event(), 5)
+                (tmp_path / "b.jsonl").write_text("x")
+                async for change in watcher:
+                    second = change
+                    break
+            finally:
+                watcher.stop()
+            remaining = [change async for change in watcher]
+            return first, second, remaining
+
+        first, second, remaining = asyncio.run(main())
+        assert (first["path"], first["kind"]) == (str(tmp_path / "a.jsonl"), "created")
+        assert second["path"] == str(tmp_path / "b.jsonl")
+        assert remaining == []
+
+    def test_async_needs_queue(self, tmp_path):
+        watcher = Watcher([str(tmp_path)], callback=lambda batch: None)
+
+        async def main():
+            await watcher.next_event()
+
+        with pytest.raises(RuntimeError):
+            asyncio.run(main())
+
+    def test_stop_while_polling(self, tmp_path):
+        watcher = Watcher([str(tmp_path)])
+        watcher.start()
+        errors = []
+
+        def poll():
+            try:
+                watcher.poll(timeout=0.5)
+            except Exception as e:
+                errors.append(e)
+
+        poller = threading.Thread(target=poll)
+        poller.start()
+        time.sleep(0.1)
+        watcher.stop()
+        poller.join()
+        assert errors == []
+        assert watcher.running is False
+
+    def test_context_manager_closes_on_error(self, tmp_path):
+        with pytest.raises(KeyError):
+            with Watcher([str(tmp_path)]) as watcher:
+                assert watcher.running is True
+                raise KeyError("boom")
+        assert watcher.running is False
+
+    def test_close_timeout(self, tmp_path):
+        entered = threading.Event()
+
+        def slow(changes):
+            entered.set()
+            time.sleep(0.5)
+
+        watcher = Watcher([str(tmp_path)], debounce=0.05, callback=slow)
+        watcher.start()
+        (tmp_path / "a.txt").write_text("x")
+        assert entered.wait(5)
+        assert watcher.close(timeout=0.05) is False
+        assert watcher.running is True
+        assert watcher.close() is True
+        assert watcher.running is False
+
+    def test_unusual_timeouts(self, tmp_path):
+        watcher = Watcher([str(tmp_path)])
+        watcher.start()
+        with pytest.raises(ValueError):
+            watcher.poll(timeout=float("nan"))
+        with pytest.raises(ValueError):
+            watcher.close(timeout=float("nan"))
+        assert watcher.poll(timeout=-1) == []
+        assert watcher.close(timeout=float("inf")) is True
+        assert watcher.running is False
+
+    def test_lifecycle_and_errors(self, tmp_path):
+        watcher = Watcher([str(tmp_path)])
+        assert watcher.running is False
+        watcher.start()
+        with pytest.raises(RuntimeError):
+            watcher.start()
+        assert watcher.running is True
+        watcher.stop()
+        assert watcher.running is False
+        with pytest.raises(IOError):
+            Watcher([str(tmp_path / "missing")]).start()
+        with pytest.raises(ValueError):
+            Watcher([str(tmp_path)], patterns=["[unclosed"])
+        with pytest.raises(ValueError):
+            Watcher([str(tmp_path)], debounce=float("inf"))
@@ -0,0 +1,73 @@
+"""Tests for whatsapp_messages (Rust native via PyO3)."""
+
+import sqlite3
+
+import pytest
+
+from snoopy._native import whatsapp_messages
+
+COCOA_EPOCH = 978307200
+T0 = 1_700_000_000.0
+
+
+def _chat_storage(path, messages):
+    conn = sqlite3.connect(path)
+    conn.executescript("""
+        CREATE TABLE ZWACHATSESSION (Z_PK INTEGER PRIMARY KEY, ZCONTACTJID TEXT,
+            ZPARTNERNAME TEXT);
+        CREATE TABLE ZWAGROUPMEMBER (Z_PK INTEGER PRIMARY KEY, ZMEMBERJID TEXT,
+            ZCONTACTNAME TEXT);
+        CREATE TABLE ZWAMESSAGE (Z_PK INTEGER PRIMARY KEY, ZCHATSESSION INTEGER,
+            ZGROUPMEMBER INTEGER, ZISFROMME INTEGER, ZMESSAGEDATE REAL, ZTEXT TEXT,
+            ZFROMJID TEXT, ZMESSAGETYPE INTEGER, ZMEDIAITEM INTEGER);
+        INSERT INTO ZWACHATSESSION VALUES (1, '15551234@s.whatsapp.net', 'Alice');
+        INSERT INTO ZWACHATSESSION VALUES (2, '1203630@g.us', 'Climbing crew');
+        INSERT INTO ZWAGROUPMEMBER VALUES (1, '15559876@s.whatsapp.net', 'Bob');
+    """)
+    for chat, member, from_me, ts, text, from_jid, mtype, media in messages:
+        conn.execute(
+            "INSERT INTO ZWAMESSAGE (ZCHATSESSION, ZGROUPMEMBER, ZISFROMME, ZMESSAGEDATE,"
+            " ZTEXT, ZFROMJID, ZMESSAGETYPE, ZMEDIAITEM) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            (chat, member, from_me, ts - COCOA_EPOCH, text, from_jid, mtype, media),
+        )
+    conn.commit()
+    conn.close()
+
+
+class TestWhatsAppMessages:
+    def test_maps_onto_message_schema(self, tmp_path):
+        db = tmp_path / "ChatStorage.sqlite"
+        _chat_storage(db, [
+            (1, None, 0, T0, "hey!", "15551234@s.whatsapp.net", 0, None),
+            (1, None, 1, T0 + 5, "hi Alice", None, 0, None),
+            (2, 1, 0, T0 + 10, None, "1203630@g.us", 1, 7),
+        ])
+        events = whatsapp_messages(str(db))
+        assert [
+            (e["contact"], e["is_from_me"], e["content_preview"], e["chat_name"])
+            for e in events
+        ] == [
+            ("15551234@s.whatsapp.net", 0, "hey!", "Alice"),
+            ("15551234@s.whatsapp.net", 1, "hi Alice", "Alice"),
+            ("Bob", 0, "[attachment]", "Climbing crew"),
+        ]
+        assert events[0]["source"] == "messages"
+        assert events[0]["service"] == "WhatsApp"
+        assert events[0]["timestamp"] == pytest.approx(T0)
+        assert [e["has_attachment"] for e in events] == [0, 0, 1]
+        assert set(events[0]) == {
+            "source", "timestamp", "contact", "is_from_me", "content_preview",
+            "has_attachment", "service", "chat_name",
+        }
+
+    def test_since(self, tmp_path):
+        db = tmp_path / "ChatStorage.sqlite"
+        _chat_storage(db, [
+            (1, None, 0, T0 - 100, "old", "a", 0, None),
+            (1, None, 0, T0 + 100, "new", "a", 0, None),
+        ])
+        assert [e["content_preview"] for e in whatsapp_messages(str(db), since=T0)] == ["new"]
+
+    def test_missing_database_raises(self, tmp_path):
+        with pytest.raises(IOError):
+            whatsapp_messages(str(tmp_path / "ChatStorage.sqlite"))
@@ -0,0 +1,78 @@
+"""Tests for current_wifi and the wifi Collector source (Rust native via PyO3)."""
+
+import os
+import sys
+import time
+
+import pytest
+
+from snoopy._native import Collector, current_wifi
+
+pytestmark = pytest.mark.skipif(sys.platform == "darwin", reason="uses the nmcli path")
+
+HOME = (
+    "no:Neighbors:11\\:22\\:33\\:44\\:55\\:66:wlan0\n"
+    "yes:Home\\: 5G:AA\\:BB\\:CC\\:DD\\:EE\\:01:wlan0\n"
+)
+
+
+def _fake_nmcli(tmp_path, monkeypatch, output):
+    bin_dir = tmp_path / "bin"
+    bin_dir.mkdir()
+    state = tmp_path / "nmcli.out"
+    state.write_text(output)
+    script = bin_dir / "nmcli"
+    script.write_text(f'#!/bin/sh\ncat "{state}"\n')
+    script.chmod(0o755)
+    monkeypatch.setenv("PATH", f"{bin_dir}{os.pathsep}{os.environ['PATH']}")
+    return state
+
+
+def _wait_for(collector, n, timeout=5.0):
+    events = []
+    deadline = time.time() + timeout
+    while len(events) < n and time.time() < deadline:
+        events.extend(collector.drain())
+        time.sleep(0.02)
+    return events
+
+
+class TestCurrentWifi:
+    def test_active_network(self, tmp_path, monkeypatch):
+        _fake_nmcli(tmp_path, monkeypatch, HOME)
+        assert current_wifi() == {
+            "interface": "wlan0",
+            "ssid": "Home: 5G",
+            "bssid": "aa:bb:cc:dd:ee:01",
+        }
+
+    def test_not_connected(self, tmp_path, monkeypatch):
+        _fake_nmcli(tmp_path, monkeypatch, "no:Neighbors:11\\:22\\:33\\:44\\:55\\:66:wlan0\n")
+        assert current_wifi() is None
+
+    def test_collector_reports_changes(self, tmp_path, monkeypatch):
+        state = _fake_nmcli(tmp_path, monkeypatch, HOME)
+        collector = Collector(["wifi"], interval=0.05)
+        collector.start()
+        try:
+            events = _wait_for(collector, 1)
+            state.write_text(HOME.replace("EE\\:01", "EE\\:02"))
+            events += _wait_for(collector, 1)
+            state.write_text("yes:Blue Bottle:00\\:11\\:22\\:33\\:44\\:55:wlan0\n")
+            events += _wait_for(collector, 1)
+            state.write_text("")
+            events += _wait_for(collector, 1)
+        finally:
+            collector.stop()
+        assert [(e["kind"], e["ssid"]) for e in events] == [
+            ("joined", "Home: 5G"),
+            ("roamed", "Home: 5G"),
+            ("joined", "Blue Bottle"),
+            ("disconnected", None),
+        ]
+        assert events[0]["source"] == "wifi"
+        assert events[0]["summary"] == "joined network Home: 5G"
+        assert events[0]["previous_ssid"] is None
+        assert events[2]["previous_ssid"] == "Home: 5G"
+        assert events[2]["previous_bssid"] == "aa:bb:cc:dd:ee:02"
+        assert events[3]["previous_duration"] > 0
@@ -0,0 +1,82 @@
+"""Tests for the Xcode DerivedData readers (Rust native via PyO3)."""
+
+import datetime
+import plistlib
+
+from snoopy._native import xcode_builds, xcode_recent_projects
+
+COCOA_EPOCH = 978_307_200
+
+
+def make_project(derived, name, workspace, accessed, logs=None):
+    folder = derived / name
+    folder.mkdir(parents=True)
+    info = {
+        "WorkspacePath": workspace,
+        "LastAccessedDate": datetime.datetime.fromtimestamp(accessed, datetime.timezone.utc),
+    }
+    (folder / "info.plist").write_bytes(plistlib.dumps(info))
+    if logs:
+        build = folder / "Logs" / "Build"
+        build.mkdir(parents=True)
+        manifest = {"logFormatVersion": 11, "logs": logs}
+        (build / "LogStoreManifest.plist").write_bytes(plistlib.dumps(manifest))
+    return folder
+
+
+def log(uid, title, start, stop, status, errors=0, warnings=0, scheme=None):
+    entry = {
+        "fileName": f"{uid}.xcactivitylog",
+        "title": title,
+        "timeStartedRecording": float(start - COCOA_EPOCH),
+        "timeStoppedRecording": float(stop - COCOA_EPOCH),
+        "primaryObservable": {
+            "highLevelStatus": status,
+            "totalNumberOfErrors": errors,
+            "totalNumberOfWarnings": warnings,
+            "totalNumberOfAnalyzerIssues": 0,
+            "totalNumberOfTestFailures": 0,
+        },
+    }
+    if scheme:
+        entry["schemeIdentifier-schemeName"] = scheme
+    return entry
+
+
+def test_recent_projects(tmp_path):
+    make_project(tmp_path, "Old-abc", "/Users/me/Old/Old.xcodeproj", 1_600_000_000)
+    make_project(tmp_path, "App-def", "/Users/me/App/App.xcworkspace", 1_700_000_000)
+    (tmp_path / "ModuleCache.noindex").mkdir()
+    projects = xcode_recent_projects(tmp_path)
+    assert [(p["name"], p["timestamp"]) for p in projects] == [
+        ("App", 1_700_000_000),
+        ("Old", 1_600_000_000),
+    ]
+    assert projects[0]["project_path"] == "/Users/me/App/App.xcworkspace"
+    assert projects[0]["derived_data"] == str(tmp_path / "App-def")
+
+
+def test_builds(tmp_path):
+    logs = {
+        "B": log("B", "Build App", 1_700_000_500, 1_700_000_542, "W", warnings=42),
+        "A": log("A", "Build App", 1_700_000_000, 1_700_000_010, "E", errors=1, scheme="App"),
+        "C": log("C", "Clean App", 1_700_000_600, 1_700_000_601, "S"),
+    }
+    folder = make_project(tmp_path, "App-def", "/Users/me/App/App.xcodeproj", 1_700_000_000, logs)
+    builds = xcode_builds(tmp_path)
+    assert [b["summary"] for b in builds] == [
+        "failed to build App, 1 error",
+        "built App, 42 warnings",
+        "cleaned App",
+    ]
+    failed, warned, clean = builds
+    assert failed["source"] == "xcode"
+    assert failed["status"] == "failed"
+    assert failed["scheme"] == "App"
+    assert warned["kind"] == "build"
+    assert warned["status"] == "warnings"
+    assert warned["duration"] == 42
+    assert warned["project_path"] == "/Users/me/App/App.xcodeproj"
+    assert warned["log_path"] == str(folder / "Logs" / "Build" / "B.xcactivitylog")
+    assert clean["kind"] == "clean"
+    assert [b["title"] for b in xcode_builds(tmp_path, since=1_700_000_550)] == ["Clean App"]